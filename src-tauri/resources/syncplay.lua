@@ -5,7 +5,7 @@
  Principal author: Etoh
  Other contributors: DerGenaue, jb, Pilotat
  Project: https://syncplay.pl/
- Version: 0.3.7
+ Version: 0.3.8
 
  Note:
  * This interface module is intended to be used in conjunction with Syncplay.
@@ -25,10 +25,19 @@ Syncplay should install this automatically to your user folder.
  .
     ? >> inputstate-change: [<input/no-input>]
     ? >> filepath-change-notification
+    ? >> cache: [<buffered fraction from 0 to 1>]
 
     * >> playstate: [<playing/paused/no-input>]
     * >> position: [<decimal seconds/no-input>]
 
+ set-push-events: [<on/off>]
+    * >> push-events: [<on/off>]
+
+    While on, play state changes and seeks are sent as soon as they happen:
+    ? >> playstate: [<playing/paused>]
+    ? >> position: [<decimal seconds>]
+    ? >> state-change-notification
+
  get-interface-version
     * >> interface-version: [syncplay connector version]
 
@@ -59,6 +68,12 @@ Syncplay should install this automatically to your user folder.
  set-rate: [decimal rate]
     ? >> set-rate-error: no-input
 
+ set-audio-delay: [decimal seconds]
+    ? >> set-audio-delay-error: no-input
+
+ set-subtitle-delay: [decimal seconds]
+    ? >> set-subtitle-delay-error: no-input
+
  set-title
     ? >> set-title-error: no-input
 
@@ -78,7 +93,7 @@ Syncplay should install this automatically to your user folder.
 
 --]==========================================================================]
 
-local connectorversion = "0.3.7"
+local connectorversion = "0.3.8"
 local vlcversion = vlc.misc.version()
 local vlcmajorversion = tonumber(vlcversion:sub(1,1)) -- get the major version of VLC
 
@@ -89,6 +104,8 @@ end
 local durationdelay = 500000 -- Pause for get_duration command etc for increased reliability (uses microseconds)
 local loopsleepduration = 2500 -- Pause for every event loop (uses microseconds)
 local quitcheckfrequency = 20 -- Check whether VLC has closed every X loops
+local pushcheckfrequency = 4 -- Check for play state changes and seeks to push every X loops
+local seekpushthreshold = 1 -- Position jumps beyond this are pushed as seeks (uses seconds)
 
 local host = "127.0.0.1"
 local port
@@ -116,6 +133,8 @@ local oldtitle = 0
 local newtitle = 0
 local oldduration = 0
 local newduration = 0
+local oldcache
+local newcache
 
 local channel1
 local channel2
@@ -123,6 +142,11 @@ local l
 
 local running = true
 
+local pushevents = false
+local pushedplaystate
+local pushedtime
+local pushedat
+
 
 function radixsafe_tonumber(str)
     -- Version of tonumber that works with any radix character (but not thousand seperators)
@@ -186,6 +210,11 @@ function detectchanges()
                 oldduration = newduration
                 notificationbuffer = notificationbuffer .. "duration-change"..msgseperator..tostring(newduration)..msgterminator
             end
+            newcache = get_var("cache", 1)
+            if newcache ~= oldcache then
+                oldcache = newcache
+                notificationbuffer = notificationbuffer .. "cache"..msgseperator..tostring(newcache)..msgterminator
+            end
         else
             notificationbuffer = notificationbuffer .. "playstate"..msgseperator..noinput..msgterminator
             notificationbuffer = notificationbuffer .. "position"..msgseperator..noinput..msgterminator
@@ -199,6 +228,58 @@ function detectchanges()
     return notificationbuffer
 end
 
+function detectpushevents()
+    -- Detects play state changes and seeks to push to Syncplay ahead of the next poll.
+    -- [Used by the main loop once set-push-events is on]
+
+    local notificationbuffer = ""
+
+    if not vlc.object.input() then
+        pushedplaystate = nil
+        pushedtime = nil
+        return notificationbuffer
+    end
+
+    local playstate = get_play_state()
+    local time = get_time()
+    local now = vlc.misc.mdate()
+    if type(time) ~= "number" then
+        return notificationbuffer
+    end
+
+    local seeked = false
+    if pushedtime ~= nil then
+        local expectedtime = pushedtime
+        if pushedplaystate == "playing" then
+            expectedtime = expectedtime + (now - pushedat) / 1000000
+        end
+        seeked = math.abs(time - expectedtime) > seekpushthreshold
+    end
+    local changed = pushedplaystate ~= nil and playstate ~= pushedplaystate
+
+    pushedplaystate = playstate
+    pushedtime = time
+    pushedat = now
+
+    if changed or seeked then
+        notificationbuffer = notificationbuffer .. "playstate"..msgseperator..tostring(playstate)..msgterminator
+        notificationbuffer = notificationbuffer .. "position"..msgseperator..tostring(time)..msgterminator
+        notificationbuffer = notificationbuffer .. "state-change"..notificationmarker..msgterminator
+    end
+    return notificationbuffer
+end
+
+function set_push_events ( argument )
+    -- [Used by the set-push-events command]
+    pushevents = argument == "on"
+    pushedplaystate = nil
+    pushedtime = nil
+    if pushevents then
+        return "on"
+    end
+    return "off"
+end
+
 function get_args (argument, argcount)
     -- Converts comma-space-seperated values into array of a given size, with last item absorbing all remaining data if needed.
     -- [Used by the display-osd command]
@@ -257,7 +338,7 @@ end
 
 
 function set_var(vartoset, varvalue)
-    -- [Used by the set-time and set-rate commands]
+    -- [Used by the set-time, set-rate and delay commands]
 
     local errormsg
     local input = vlc.object.input()
@@ -266,6 +347,11 @@ function set_var(vartoset, varvalue)
         varvalue = varvalue * 1000000
     end
 
+    -- Delays are always kept in microseconds
+    if vartoset == "audio-delay" or vartoset == "spu-delay" then
+        varvalue = math.floor(varvalue * 1000000)
+    end
+
     if input then
         vlc.var.set(input,tostring(vartoset),varvalue)
     else
@@ -495,6 +581,7 @@ function do_command ( command, argument)
 
     if     command == "get-interface-version" then response           = "interface-version"..msgseperator..connectorversion..msgterminator
     elseif command == "get-vlc-version"       then response           = "vlc-version"..msgseperator..vlcversion..msgterminator
+    elseif command == "set-push-events"       then response           = "push-events"..msgseperator..set_push_events(argument)..msgterminator
     elseif command == "get-duration"          then
         newduration = errormerge(get_duration())
         response           = "duration"..msgseperator..newduration..msgterminator
@@ -506,6 +593,8 @@ function do_command ( command, argument)
     elseif command == "seek-within-title"     then           errormsg = set_var("time", radixsafe_tonumber(argument))
     elseif command == "set-playstate"         then           errormsg = set_playstate(argument)
     elseif command == "set-rate"              then           errormsg = set_var("rate", radixsafe_tonumber(argument))
+    elseif command == "set-audio-delay"       then           errormsg = set_var("audio-delay", radixsafe_tonumber(argument))
+    elseif command == "set-subtitle-delay"    then           errormsg = set_var("spu-delay", radixsafe_tonumber(argument))
     elseif command == "set-title"             then           errormsg = set_var("title", radixsafe_tonumber(argument))
     elseif command == "display-osd"           then           errormsg = display_osd(argument)
     elseif command == "display-secondary-osd" then           errormsg = display_secondary_osd(argument)
@@ -561,6 +650,7 @@ end
 while running == true do
     --accept new connections and select active clients
     local quitcheckcounter = 0
+    local pushcheckcounter = 0
     local fd = l:accept()
     local buffer, inputbuffer, responsebuffer = "", "", ""
     while fd >= 0 and running == true do
@@ -605,6 +695,18 @@ while running == true do
             net.close(fd)
         end
 
+        -- push play state changes and seeks without waiting for a poll
+
+        pushcheckcounter = pushcheckcounter + 1
+
+        if pushevents and pushcheckcounter >= pushcheckfrequency and running == true then
+            local pushbuffer = detectpushevents()
+            if pushbuffer ~= "" then
+                responsebuffer = (responsebuffer or "") .. pushbuffer
+            end
+            pushcheckcounter = 0
+        end
+
         -- handle write mode
 
         if (responsebuffer and running == true) then
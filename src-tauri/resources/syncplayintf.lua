@@ -47,6 +47,7 @@ local GOOD_ALERT_TEXT_COLOUR = "00FF00" -- RBG
 local NOTIFICATION_TEXT_COLOUR = "FFFF00" -- RBG
 
 local FONT_SIZE_MULTIPLIER = 2
+local NOTIFICATION_MARGIN = 25
 
 local chat_log = {}
 
@@ -157,12 +158,6 @@ function chat_update()
     if to_add ~= nil and to_add ~= "" then
         chat_ass = to_add
     end
-    incrementRow,to_add = process_notification_osd(rowsAdded)
-    rowsAdded = rowsAdded + incrementRow
-    if to_add ~= nil and to_add ~= "" then
-        chat_ass = chat_ass .. to_add
-    end
-
     if #chat_log > 0 then
         for i = 1, #chat_log do
             local to_add = process_chat_item(i,rowsAdded)
@@ -174,7 +169,7 @@ function chat_update()
 
     local xpos = opts['chatLeftMargin']
     local ypos = opts['chatTopMargin']
-    chat_ass = "\n".."{\\pos("..xpos..","..ypos..")}".. chat_ass
+    chat_ass = "\n".."{\\pos("..xpos..","..ypos..")}".. chat_ass .. notification_ass()
 
     if use_alpha_rows_for_chat == false and opts['chatDirectInput'] == true then
         local alphawarning_ass = assdraw.ass_new()
@@ -225,21 +220,27 @@ function process_alert_osd()
     return rowsCreated, stringToAdd
 end
 
-function process_notification_osd(startRow)
-    local rowsCreated = 0
-    local startRow = startRow
-    local stringToAdd = ""
-    if notification_osd ~= "" and mp.get_time() - last_notification_osd_time < opts['alertTimeout'] and last_notification_osd_time ~= nil then
-        local messageColour
-        messageColour = "{\\1c&H"..NOTIFICATION_TEXT_COLOUR.."}"
-        local messageString
-        messageString = wordwrapify_string(notification_osd)
-        messageString = messageColour..messageString
-        messageString = format_chatroom(messageString)
-        stringToAdd = messageString
-        rowsCreated = 1
+-- Notifications are drawn as their own event, anchored by notificationPosition (ASS numpad alignment)
+function notification_ass()
+    if notification_osd == "" or last_notification_osd_time == nil or mp.get_time() - last_notification_osd_time >= opts['alertTimeout'] then
+        return ""
     end
-    return rowsCreated, stringToAdd
+    local alignment = opts['notificationPosition']
+    local column = (alignment - 1) % 3
+    local row = math.floor((alignment - 1) / 3)
+    local xpos = NOTIFICATION_MARGIN + column * (CANVAS_WIDTH / 2 - NOTIFICATION_MARGIN)
+    local ypos = CANVAS_HEIGHT - NOTIFICATION_MARGIN - row * (CANVAS_HEIGHT / 2 - NOTIFICATION_MARGIN)
+    local messageColour = NOTIFICATION_TEXT_COLOUR
+    local color = opts['notificationColor']
+    if color ~= "" then
+        messageColour = string.sub(color,6,7) .. string.sub(color,4,5) .. string.sub(color,2,3)
+    end
+    local scale = opts['notificationFontScale']
+    local style = '{\\r\\an' .. alignment .. '\\pos(' .. xpos .. ',' .. ypos .. ')' ..
+                  '\\1c&H' .. messageColour .. '&\\3c&H111111&\\bord2' ..
+                  '\\fn' .. opts['chatOutputFontFamily'] .. '\\fs' .. (opts['chatOutputRelativeFontSize']*FONT_SIZE_MULTIPLIER) ..
+                  '\\fscx' .. scale .. '\\fscy' .. scale .. '}'
+    return "\n" .. style .. wordwrapify_string(notification_osd)
 end
 
 
@@ -404,6 +405,9 @@ opts = {
     ['chatDirectInput'] = true,
     --
     ['notificationTimeout'] = 3,
+    ['notificationPosition'] = 9,
+    ['notificationColor'] = "",
+    ['notificationFontScale'] = 100,
     ['alertTimeout'] = 5,
     ['chatTimeout'] = 7,
     --
@@ -1,33 +1,56 @@
 use anyhow::Result;
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use tempfile::TempDir;
+use tokio::sync::{broadcast, Notify};
 
 use crate::client::{
-    chat::ChatManager, local_state::LocalPlaybackState, media_index::MediaIndex,
-    playlist::Playlist, state::ClientState, sync::SyncEngine,
+    bookmarks::BookmarkStore,
+    chat::ChatManager,
+    delays::DelayStore,
+    endgame::EndgameTracker,
+    file_diff::FileDifference,
+    file_update::FileUpdateThrottle,
+    local_state::LocalPlaybackState,
+    media_index::MediaIndex,
+    media_resolver::MediaResolver,
+    motd::ServerMotdEvent,
+    operator::OperatorHandoff,
+    persistent_rooms::PersistentRoomStore,
+    playlist::Playlist,
+    ready::ReadyCheck,
+    room_control::OperatorsOnlyRule,
+    room_events::RoomTimeline,
+    segments::SegmentStore,
+    session_actor::{SessionActor, SessionCommand},
+    session_stats::SessionStats,
+    state::ClientState,
+    sync::SyncEngine,
+    votes::VoteBox,
 };
 use crate::config::{SyncplayConfig, UnpauseAction};
+use crate::integrations::metrics::ClientMetrics;
+use crate::integrations::remote::{RemoteEvent, REMOTE_EVENT_CAPACITY};
+use crate::integrations::scripts::ScriptHost;
 use crate::network::connection::Connection;
 use crate::network::messages::HelloMessage;
 use crate::network::ping::PingService;
-use crate::player::backend::{PlayerBackend, PlayerKind};
+use crate::player::backend::PlayerBackend;
+use crate::player::registry::PlayerRegistry;
 
 /// Global application state
 pub struct AppState {
-    /// Network connection to Syncplay server
-    pub connection: Arc<Mutex<Option<Arc<Connection>>>>,
+    /// State scoped to the current server session
+    pub session: SessionState,
     /// Player backend instance
     pub player: Arc<Mutex<Option<Arc<dyn PlayerBackend>>>>,
     /// Player process handle
     pub player_process: Arc<Mutex<Option<tokio::process::Child>>>,
-    /// Client state (users, room, etc.)
-    pub client_state: Arc<ClientState>,
-    /// Playlist manager
-    pub playlist: Arc<Playlist>,
+    /// Factories that launch each supported player
+    pub player_registry: Arc<Mutex<PlayerRegistry>>,
     /// Chat manager
     pub chat: Arc<ChatManager>,
     /// Synchronization engine
@@ -36,40 +59,30 @@ pub struct AppState {
     pub config: Arc<Mutex<SyncplayConfig>>,
     /// Suppress next file update for server-driven loads
     pub suppress_next_file_update: Arc<Mutex<bool>>,
-    /// Last hello payload (for TLS re-handshake)
-    pub last_hello: Arc<Mutex<Option<HelloMessage>>>,
-    /// Whether hello has been sent for the current connection
-    pub hello_sent: Arc<Mutex<bool>>,
+    /// File updates held back so rapid changes reach the server as one
+    pub file_update_throttle: Arc<Mutex<FileUpdateThrottle>>,
     /// Tauri app handle for event emission
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
     /// Autoplay countdown state
     pub autoplay: Arc<Mutex<AutoPlayState>>,
-    /// Ping RTT tracking
-    pub ping_service: Arc<Mutex<PingService>>,
-    /// Last latency calculation timestamp from server
-    pub last_latency_calculation: Arc<Mutex<Option<f64>>>,
-    /// Last time a global playstate was received
-    pub last_global_update: Arc<Mutex<Option<Instant>>>,
+    /// Automatic readiness tracking for the loaded file
+    pub auto_ready: Arc<Mutex<AutoReadyState>>,
     /// Last time we established a connection
     pub last_connect_time: Arc<Mutex<Option<Instant>>>,
     /// Latest local playback state
     pub local_playback_state: Arc<Mutex<LocalPlaybackState>>,
-    /// Ignoring-on-the-fly counters
-    pub ignoring_on_the_fly: Arc<Mutex<IgnoringOnTheFlyState>>,
-    /// Server feature support
-    pub server_features: Arc<Mutex<ServerFeatures>>,
-    /// Last rewind timestamp
-    pub last_rewind_time: Arc<Mutex<Option<Instant>>>,
-    /// Last local seek origin position for notifications
-    pub last_seek_from_position: Arc<Mutex<Option<f64>>>,
-    /// Last playlist advance timestamp
-    pub last_advance_time: Arc<Mutex<Option<Instant>>>,
-    /// Last time a file update was sent/received
-    pub last_updated_file_time: Arc<Mutex<Option<Instant>>>,
-    /// Last time we paused due to a user leaving
-    pub last_paused_on_leave_time: Arc<Mutex<Option<Instant>>>,
+    /// Local player runs on its own until resynced, e.g. while frame stepping
+    pub sync_detached: Arc<Mutex<bool>>,
+    /// Do-not-disturb: only errors reach the OSD, without chat or mention alerts
+    pub do_not_disturb: Arc<Mutex<bool>>,
+    /// Whether the app window currently has focus
+    pub app_window_focused: Arc<Mutex<bool>>,
     /// Whether we should restore playlist on reconnect
     pub playlist_may_need_restoring: Arc<Mutex<bool>>,
+    /// Room of a playlist restored from disk at startup
+    pub restored_playlist_room: Arc<Mutex<Option<String>>>,
+    /// Command line and environment overrides merged over the loaded config
+    pub config_overrides: Arc<Mutex<Option<crate::config::AppliedOverrides>>>,
     /// Whether client TLS is supported
     pub client_supports_tls: Arc<Mutex<bool>>,
     /// Whether server TLS is supported
@@ -78,18 +91,26 @@ pub struct AppState {
     pub reconnect_state: Arc<Mutex<ReconnectState>>,
     /// Last connection snapshot for reconnect
     pub reconnect_snapshot: Arc<Mutex<Option<ConnectionSnapshot>>>,
+    /// Room we asked to join that the server has not listed us in yet
+    pub pending_room_join: Arc<Mutex<Option<String>>>,
+    /// Room whose password the server rejected, kept for `retry_room_join`
+    pub rejected_room_join: Arc<Mutex<Option<String>>>,
+    /// URL load held back until the user trusts its domain, kept for `accept_untrusted_url`
+    pub pending_untrusted_url: Arc<Mutex<Option<PendingUrlLoad>>>,
+    /// Large local seek taken back until the user confirms it with `confirm_seek`
+    pub pending_seek: Arc<Mutex<Option<SeekConfirmationRequest>>>,
+    /// Target of a confirmed seek, let through to the room once
+    pub confirmed_seek: Arc<Mutex<Option<f64>>>,
     /// Whether disconnect was initiated by user
     pub manual_disconnect: Arc<Mutex<bool>>,
-    /// Warning timers for OSD warnings
-    pub warning_timers: Arc<Mutex<WarningTimers>>,
-    /// Whether the first playlist index has been received
-    pub had_first_playlist_index: Arc<Mutex<bool>>,
-    /// Last time a player process was spawned
-    pub last_player_spawn: Arc<Mutex<Option<Instant>>>,
-    /// Kind of the last spawned player
-    pub last_player_kind: Arc<Mutex<Option<PlayerKind>>>,
+    /// Whether the user cancelled the connect-on-launch countdown
+    pub launch_connect_cancelled: Arc<Mutex<bool>>,
     /// Whether a player connection is in progress
     pub player_connecting: Arc<Mutex<bool>>,
+    /// Set by the health watchdog while pushed player events are not trusted
+    pub player_poll_fallback: Arc<Mutex<bool>>,
+    /// Our file while it is still being downloaded, so the warning is shown once
+    pub downloading_file: Arc<Mutex<Option<String>>>,
     /// Runtime directory for MPV IPC socket
     pub mpv_runtime_dir: Arc<Mutex<Option<TempDir>>>,
     /// Cached MPV IPC socket path
@@ -102,18 +123,142 @@ pub struct AppState {
     pub controlled_room_passwords: Arc<Mutex<HashMap<String, String>>>,
     /// Last controller password attempt
     pub last_control_password_attempt: Arc<Mutex<Option<String>>>,
+    /// Users whose file differences are not reported until the app closes
+    pub session_trusted_users: Arc<Mutex<HashSet<String>>>,
+    /// Media index cache
+    pub media_index: Arc<MediaIndex>,
+    /// Playback bookmarks, kept across sessions
+    pub bookmarks: Arc<BookmarkStore>,
+    /// Intro and outro segments per file
+    pub segments: Arc<SegmentStore>,
+    /// Audio and subtitle offsets per file
+    pub delays: Arc<DelayStore>,
+    /// Rooms marked persistent and their remembered playlists
+    pub persistent_rooms: Arc<PersistentRoomStore>,
+    /// Rate limiting for high-frequency frontend events
+    pub event_coalescer: Arc<Mutex<EventCoalescer>>,
+    /// Frontend events, forwarded to remote control clients
+    pub remote_events: broadcast::Sender<RemoteEvent>,
+    /// Remote control server, while it runs
+    pub remote_server: Arc<Mutex<Option<tauri::async_runtime::JoinHandle<()>>>>,
+    /// User scripts thread, while scripts are enabled
+    pub scripts: Arc<Mutex<Option<ScriptHost>>>,
+    /// Counters for the metrics endpoint
+    pub metrics: Arc<ClientMetrics>,
+    /// Metrics endpoint, while it runs
+    pub metrics_server: Arc<Mutex<Option<tauri::async_runtime::JoinHandle<()>>>>,
+    /// Statistics of the watch session, kept across reconnects
+    pub session_stats: Arc<Mutex<Option<SessionStats>>>,
+}
+
+/// Connection-scoped state that is replaced or reset per server session
+pub struct SessionState {
+    /// Network connection to Syncplay server
+    pub connection: Arc<Mutex<Option<Arc<Connection>>>>,
+    /// Client state (users, room, etc.)
+    pub client_state: Arc<ClientState>,
+    /// Playlist manager
+    pub playlist: Arc<Playlist>,
+    /// Ping RTT tracking
+    pub ping_service: Arc<Mutex<PingService>>,
+    /// Warning timers for OSD warnings
+    pub warning_timers: Arc<Mutex<WarningTimers>>,
+    /// Last hello payload (for TLS re-handshake)
+    pub last_hello: Arc<Mutex<Option<HelloMessage>>>,
+    /// Whether hello has been sent for the current connection
+    pub hello_sent: Arc<Mutex<bool>>,
+    /// Last latency calculation timestamp from server
+    pub last_latency_calculation: Arc<Mutex<Option<f64>>>,
+    /// Whether clock skew was already reported for this connection
+    pub clock_skew_warned: Arc<Mutex<bool>>,
+    /// Last time a global playstate was received
+    pub last_global_update: Arc<Mutex<Option<Instant>>>,
+    /// Playback bookkeeping owned by the session actor
+    pub actor: SessionActor,
+    /// Server feature support
+    pub server_features: Arc<Mutex<ServerFeatures>>,
+    /// How `server_features` was settled during the Hello
+    pub feature_negotiation: Arc<Mutex<Option<FeatureNegotiation>>>,
     /// Room warning state
     pub room_warning_state: Arc<Mutex<RoomWarningState>>,
     /// Whether the room warning task is running
     pub room_warning_task_running: Arc<Mutex<bool>>,
-    /// Media index cache
-    pub media_index: Arc<MediaIndex>,
+    /// Notified whenever a user list from the server has been applied
+    pub list_updated: Arc<Notify>,
+    /// Last reported TLS status
+    pub tls_status: Arc<Mutex<String>>,
+    /// MOTD received with the server Hello
+    pub motd: Arc<Mutex<Option<ServerMotdEvent>>>,
+    /// Join, leave, file and playback events for the activity feed
+    pub room_timeline: Arc<RoomTimeline>,
+    /// Ready check currently collecting answers
+    pub ready_check: Arc<Mutex<Option<ReadyCheck>>>,
+    /// Operator handoffs waiting for their next chat message
+    pub operator_handoff: Arc<Mutex<OperatorHandoff>>,
+    /// Operators-only rule announced in room chat
+    pub operators_only: Arc<Mutex<Option<OperatorsOnlyRule>>>,
+    /// Open "!vote" votes of the current room
+    pub votes: Arc<Mutex<VoteBox>>,
+    /// Whether the room was already announced as finished with the current file
+    pub endgame: Arc<Mutex<EndgameTracker>>,
 }
 
-#[derive(Debug, Default)]
-pub struct IgnoringOnTheFlyState {
-    pub server: u32,
-    pub client: u32,
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            connection: Arc::new(Mutex::new(None)),
+            client_state: ClientState::new(),
+            playlist: Playlist::new(),
+            ping_service: Arc::new(Mutex::new(PingService::default())),
+            warning_timers: Arc::new(Mutex::new(WarningTimers::default())),
+            last_hello: Arc::new(Mutex::new(None)),
+            hello_sent: Arc::new(Mutex::new(false)),
+            last_latency_calculation: Arc::new(Mutex::new(None)),
+            clock_skew_warned: Arc::new(Mutex::new(false)),
+            last_global_update: Arc::new(Mutex::new(None)),
+            actor: SessionActor::spawn(),
+            server_features: Arc::new(Mutex::new(ServerFeatures::default())),
+            feature_negotiation: Arc::new(Mutex::new(None)),
+            room_warning_state: Arc::new(Mutex::new(RoomWarningState::default())),
+            room_warning_task_running: Arc::new(Mutex::new(false)),
+            list_updated: Arc::new(Notify::new()),
+            tls_status: Arc::new(Mutex::new("unknown".to_string())),
+            motd: Arc::new(Mutex::new(None)),
+            room_timeline: RoomTimeline::new(),
+            ready_check: Arc::new(Mutex::new(None)),
+            operator_handoff: Arc::new(Mutex::new(OperatorHandoff::default())),
+            operators_only: Arc::new(Mutex::new(None)),
+            votes: Arc::new(Mutex::new(VoteBox::default())),
+            endgame: Arc::new(Mutex::new(EndgameTracker::default())),
+        }
+    }
+
+    /// Reset per-connection tracking before a new session starts
+    pub fn reset_for_new_connection(&self) {
+        self.actor.send(SessionCommand::Reset);
+        *self.last_global_update.lock() = None;
+        *self.clock_skew_warned.lock() = false;
+        *self.motd.lock() = None;
+        self.room_timeline.clear();
+        *self.ready_check.lock() = None;
+        *self.operator_handoff.lock() = OperatorHandoff::default();
+        *self.operators_only.lock() = None;
+        self.votes.lock().clear();
+        self.endgame.lock().reset();
+    }
+
+    /// Reset room warnings after the connection is lost
+    pub fn reset_warnings(&self) {
+        *self.room_warning_state.lock() = RoomWarningState::default();
+        *self.warning_timers.lock() = WarningTimers::default();
+        *self.room_warning_task_running.lock() = false;
+    }
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -123,6 +268,10 @@ pub struct ConnectionSnapshot {
     pub username: String,
     pub room: String,
     pub password: Option<String>,
+    /// What Hello carries: the MD5 of `password`, or a hash saved for this server
+    pub password_hash: Option<String>,
+    /// Password of `room`, given for this join or saved with the room list
+    pub room_password: Option<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -148,51 +297,59 @@ pub struct WarningTimers {
 impl AppState {
     pub fn new() -> Arc<Self> {
         Arc::new(Self {
-            connection: Arc::new(Mutex::new(None)),
+            session: SessionState::new(),
             player: Arc::new(Mutex::new(None)),
             player_process: Arc::new(Mutex::new(None)),
-            client_state: ClientState::new(),
-            playlist: Playlist::new(),
+            player_registry: Arc::new(Mutex::new(PlayerRegistry::with_builtin())),
             chat: ChatManager::new(),
             sync_engine: Arc::new(Mutex::new(SyncEngine::new())),
             config: Arc::new(Mutex::new(SyncplayConfig::default())),
             suppress_next_file_update: Arc::new(Mutex::new(false)),
-            last_hello: Arc::new(Mutex::new(None)),
-            hello_sent: Arc::new(Mutex::new(false)),
+            file_update_throttle: Arc::new(Mutex::new(FileUpdateThrottle::new())),
             app_handle: Arc::new(Mutex::new(None)),
             autoplay: Arc::new(Mutex::new(AutoPlayState::default())),
-            ping_service: Arc::new(Mutex::new(PingService::default())),
-            last_latency_calculation: Arc::new(Mutex::new(None)),
-            last_global_update: Arc::new(Mutex::new(None)),
+            auto_ready: Arc::new(Mutex::new(AutoReadyState::default())),
             last_connect_time: Arc::new(Mutex::new(None)),
             local_playback_state: Arc::new(Mutex::new(LocalPlaybackState::new())),
-            ignoring_on_the_fly: Arc::new(Mutex::new(IgnoringOnTheFlyState::default())),
-            server_features: Arc::new(Mutex::new(ServerFeatures::default())),
-            last_rewind_time: Arc::new(Mutex::new(None)),
-            last_seek_from_position: Arc::new(Mutex::new(None)),
-            last_advance_time: Arc::new(Mutex::new(None)),
-            last_updated_file_time: Arc::new(Mutex::new(None)),
-            last_paused_on_leave_time: Arc::new(Mutex::new(None)),
+            sync_detached: Arc::new(Mutex::new(false)),
+            do_not_disturb: Arc::new(Mutex::new(false)),
+            app_window_focused: Arc::new(Mutex::new(true)),
             playlist_may_need_restoring: Arc::new(Mutex::new(false)),
+            restored_playlist_room: Arc::new(Mutex::new(None)),
+            config_overrides: Arc::new(Mutex::new(None)),
             client_supports_tls: Arc::new(Mutex::new(true)),
             server_supports_tls: Arc::new(Mutex::new(true)),
             reconnect_state: Arc::new(Mutex::new(ReconnectState::default())),
             reconnect_snapshot: Arc::new(Mutex::new(None)),
+            pending_room_join: Arc::new(Mutex::new(None)),
+            rejected_room_join: Arc::new(Mutex::new(None)),
+            pending_untrusted_url: Arc::new(Mutex::new(None)),
+            pending_seek: Arc::new(Mutex::new(None)),
+            confirmed_seek: Arc::new(Mutex::new(None)),
             manual_disconnect: Arc::new(Mutex::new(false)),
-            warning_timers: Arc::new(Mutex::new(WarningTimers::default())),
-            had_first_playlist_index: Arc::new(Mutex::new(false)),
-            last_player_spawn: Arc::new(Mutex::new(None)),
-            last_player_kind: Arc::new(Mutex::new(None)),
+            launch_connect_cancelled: Arc::new(Mutex::new(false)),
             mpv_runtime_dir: Arc::new(Mutex::new(None)),
             mpv_socket_path: Arc::new(Mutex::new(None)),
             player_connecting: Arc::new(Mutex::new(false)),
+            player_poll_fallback: Arc::new(Mutex::new(false)),
+            downloading_file: Arc::new(Mutex::new(None)),
             detected_players: Arc::new(Mutex::new(Vec::new())),
             detected_players_updated_at: Arc::new(Mutex::new(None)),
             controlled_room_passwords: Arc::new(Mutex::new(HashMap::new())),
             last_control_password_attempt: Arc::new(Mutex::new(None)),
-            room_warning_state: Arc::new(Mutex::new(RoomWarningState::default())),
-            room_warning_task_running: Arc::new(Mutex::new(false)),
+            session_trusted_users: Arc::new(Mutex::new(HashSet::new())),
             media_index: MediaIndex::new(),
+            bookmarks: BookmarkStore::new(),
+            segments: SegmentStore::new(),
+            delays: DelayStore::new(),
+            persistent_rooms: PersistentRoomStore::new(),
+            event_coalescer: Arc::new(Mutex::new(EventCoalescer::default())),
+            remote_events: broadcast::channel(REMOTE_EVENT_CAPACITY).0,
+            remote_server: Arc::new(Mutex::new(None)),
+            scripts: Arc::new(Mutex::new(None)),
+            metrics: Arc::new(ClientMetrics::default()),
+            metrics_server: Arc::new(Mutex::new(None)),
+            session_stats: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -202,7 +359,18 @@ impl AppState {
     }
 
     /// Emit an event to the frontend
+    /// Async media lookups over the index and the configured directories
+    pub fn media_resolver(&self) -> MediaResolver {
+        let directories = self.config.lock().player.media_directories.clone();
+        MediaResolver::new(self.media_index.clone(), directories)
+    }
+
     pub fn emit_event(&self, event: &str, payload: impl serde::Serialize + Clone) {
+        if COALESCED_EVENTS.contains(&event) {
+            self.emit_coalesced(event, payload);
+            return;
+        }
+        forward_to_remote(&self.remote_events, event, &payload);
         if let Some(handle) = self.app_handle.lock().as_ref() {
             if let Err(e) = handle.emit(event, payload) {
                 tracing::error!("Failed to emit event {}: {}", event, e);
@@ -210,9 +378,69 @@ impl AppState {
         }
     }
 
+    /// Emit a high-frequency event, dropping repeats and deferring bursts so
+    /// the frontend sees at most one update per configured interval
+    fn emit_coalesced(&self, event: &str, payload: impl serde::Serialize) {
+        let payload = match serde_json::to_value(payload) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::error!("Failed to serialize event {}: {}", event, e);
+                return;
+            }
+        };
+        let interval = Duration::from_millis(self.config.lock().user.ui_event_interval_ms);
+        let Some(handle) = self.app_handle.lock().clone() else {
+            return;
+        };
+        let decision = self
+            .event_coalescer
+            .lock()
+            .offer(event, payload, Instant::now(), interval);
+        match decision {
+            CoalesceDecision::Emit(payload) => {
+                forward_to_remote(&self.remote_events, event, &payload);
+                if let Err(e) = handle.emit(event, payload) {
+                    tracing::error!("Failed to emit event {}: {}", event, e);
+                }
+            }
+            CoalesceDecision::Defer(wait) => {
+                let coalescer = self.event_coalescer.clone();
+                let remote_events = self.remote_events.clone();
+                let event = event.to_string();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(wait).await;
+                    let pending = coalescer.lock().take_pending(&event, Instant::now());
+                    if let Some(payload) = pending {
+                        forward_to_remote(&remote_events, &event, &payload);
+                        if let Err(e) = handle.emit(&event, payload) {
+                            tracing::error!("Failed to emit event {}: {}", event, e);
+                        }
+                    }
+                });
+            }
+            CoalesceDecision::Drop => {}
+        }
+    }
+
+    /// Translate a client message into the configured language
+    pub fn tr(&self, key: &str, args: &[&dyn std::fmt::Display]) -> String {
+        let language = self.config.lock().user.language;
+        crate::i18n::tr(language, key, args)
+    }
+
+    /// Record the TLS status and notify the frontend
+    pub fn set_tls_status(&self, status: &str) {
+        *self.session.tls_status.lock() = status.to_string();
+        self.emit_event(
+            "tls-status-changed",
+            serde_json::json!({ "status": status }),
+        );
+    }
+
     /// Check if connected to server
     pub fn is_connected(&self) -> bool {
-        self.connection
+        self.session
+            .connection
             .lock()
             .as_ref()
             .map(|c| c.is_connected())
@@ -228,52 +456,152 @@ impl AppState {
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            connection: Arc::new(Mutex::new(None)),
+            session: SessionState::new(),
             player: Arc::new(Mutex::new(None)),
             player_process: Arc::new(Mutex::new(None)),
-            client_state: ClientState::new(),
-            playlist: Playlist::new(),
+            player_registry: Arc::new(Mutex::new(PlayerRegistry::with_builtin())),
             chat: ChatManager::new(),
             sync_engine: Arc::new(Mutex::new(SyncEngine::new())),
             config: Arc::new(Mutex::new(SyncplayConfig::default())),
             suppress_next_file_update: Arc::new(Mutex::new(false)),
-            last_hello: Arc::new(Mutex::new(None)),
-            hello_sent: Arc::new(Mutex::new(false)),
+            file_update_throttle: Arc::new(Mutex::new(FileUpdateThrottle::new())),
             app_handle: Arc::new(Mutex::new(None)),
             autoplay: Arc::new(Mutex::new(AutoPlayState::default())),
-            ping_service: Arc::new(Mutex::new(PingService::default())),
-            last_latency_calculation: Arc::new(Mutex::new(None)),
-            last_global_update: Arc::new(Mutex::new(None)),
+            auto_ready: Arc::new(Mutex::new(AutoReadyState::default())),
             last_connect_time: Arc::new(Mutex::new(None)),
             local_playback_state: Arc::new(Mutex::new(LocalPlaybackState::new())),
-            ignoring_on_the_fly: Arc::new(Mutex::new(IgnoringOnTheFlyState::default())),
-            server_features: Arc::new(Mutex::new(ServerFeatures::default())),
-            last_rewind_time: Arc::new(Mutex::new(None)),
-            last_seek_from_position: Arc::new(Mutex::new(None)),
-            last_advance_time: Arc::new(Mutex::new(None)),
-            last_updated_file_time: Arc::new(Mutex::new(None)),
-            last_paused_on_leave_time: Arc::new(Mutex::new(None)),
+            sync_detached: Arc::new(Mutex::new(false)),
+            do_not_disturb: Arc::new(Mutex::new(false)),
+            app_window_focused: Arc::new(Mutex::new(true)),
             playlist_may_need_restoring: Arc::new(Mutex::new(false)),
+            restored_playlist_room: Arc::new(Mutex::new(None)),
+            config_overrides: Arc::new(Mutex::new(None)),
             client_supports_tls: Arc::new(Mutex::new(true)),
             server_supports_tls: Arc::new(Mutex::new(true)),
             reconnect_state: Arc::new(Mutex::new(ReconnectState::default())),
             reconnect_snapshot: Arc::new(Mutex::new(None)),
+            pending_room_join: Arc::new(Mutex::new(None)),
+            rejected_room_join: Arc::new(Mutex::new(None)),
+            pending_untrusted_url: Arc::new(Mutex::new(None)),
+            pending_seek: Arc::new(Mutex::new(None)),
+            confirmed_seek: Arc::new(Mutex::new(None)),
             manual_disconnect: Arc::new(Mutex::new(false)),
-            warning_timers: Arc::new(Mutex::new(WarningTimers::default())),
-            had_first_playlist_index: Arc::new(Mutex::new(false)),
-            last_player_spawn: Arc::new(Mutex::new(None)),
-            last_player_kind: Arc::new(Mutex::new(None)),
+            launch_connect_cancelled: Arc::new(Mutex::new(false)),
             mpv_runtime_dir: Arc::new(Mutex::new(None)),
             mpv_socket_path: Arc::new(Mutex::new(None)),
             player_connecting: Arc::new(Mutex::new(false)),
+            player_poll_fallback: Arc::new(Mutex::new(false)),
+            downloading_file: Arc::new(Mutex::new(None)),
             detected_players: Arc::new(Mutex::new(Vec::new())),
             detected_players_updated_at: Arc::new(Mutex::new(None)),
             controlled_room_passwords: Arc::new(Mutex::new(HashMap::new())),
             last_control_password_attempt: Arc::new(Mutex::new(None)),
-            room_warning_state: Arc::new(Mutex::new(RoomWarningState::default())),
-            room_warning_task_running: Arc::new(Mutex::new(false)),
+            session_trusted_users: Arc::new(Mutex::new(HashSet::new())),
             media_index: MediaIndex::new(),
+            bookmarks: BookmarkStore::new(),
+            segments: SegmentStore::new(),
+            delays: DelayStore::new(),
+            persistent_rooms: PersistentRoomStore::new(),
+            event_coalescer: Arc::new(Mutex::new(EventCoalescer::default())),
+            remote_events: broadcast::channel(REMOTE_EVENT_CAPACITY).0,
+            remote_server: Arc::new(Mutex::new(None)),
+            scripts: Arc::new(Mutex::new(None)),
+            metrics: Arc::new(ClientMetrics::default()),
+            metrics_server: Arc::new(Mutex::new(None)),
+            session_stats: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Events that fire often enough to need rate limiting before reaching the webview
+const COALESCED_EVENTS: &[&str] = &["player-state-changed", "ping-updated"];
+
+/// Send an event on to remote control clients, if any are connected
+fn forward_to_remote(
+    remote_events: &broadcast::Sender<RemoteEvent>,
+    event: &str,
+    payload: &impl serde::Serialize,
+) {
+    if remote_events.receiver_count() == 0 {
+        return;
+    }
+    match serde_json::to_value(payload) {
+        Ok(payload) => {
+            let _ = remote_events.send(RemoteEvent {
+                event: event.to_string(),
+                payload,
+            });
+        }
+        Err(e) => tracing::error!(
+            "Failed to serialize event {} for remote clients: {}",
+            event,
+            e
+        ),
+    }
+}
+
+#[derive(Debug, Default)]
+struct CoalescedEvent {
+    last_emit: Option<Instant>,
+    last_payload: Option<serde_json::Value>,
+    pending: Option<serde_json::Value>,
+    flush_scheduled: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CoalesceDecision {
+    /// Send the payload now
+    Emit(serde_json::Value),
+    /// Schedule a flush of the pending payload after the given delay
+    Defer(Duration),
+    /// Nothing to send: a duplicate, or a flush is already scheduled
+    Drop,
+}
+
+/// Tracks the last emitted payload per event so repeats can be merged
+#[derive(Debug, Default)]
+pub struct EventCoalescer {
+    events: HashMap<String, CoalescedEvent>,
+}
+
+impl EventCoalescer {
+    pub fn offer(
+        &mut self,
+        event: &str,
+        payload: serde_json::Value,
+        now: Instant,
+        interval: Duration,
+    ) -> CoalesceDecision {
+        let entry = self.events.entry(event.to_string()).or_default();
+        if entry.last_payload.as_ref() == Some(&payload) {
+            entry.pending = None;
+            return CoalesceDecision::Drop;
         }
+        if let Some(last_emit) = entry.last_emit {
+            let elapsed = now.saturating_duration_since(last_emit);
+            if elapsed < interval {
+                entry.pending = Some(payload);
+                if entry.flush_scheduled {
+                    return CoalesceDecision::Drop;
+                }
+                entry.flush_scheduled = true;
+                return CoalesceDecision::Defer(interval - elapsed);
+            }
+        }
+        entry.last_emit = Some(now);
+        entry.last_payload = Some(payload.clone());
+        entry.pending = None;
+        CoalesceDecision::Emit(payload)
+    }
+
+    /// Take the payload deferred by `offer`, marking it as emitted
+    pub fn take_pending(&mut self, event: &str, now: Instant) -> Option<serde_json::Value> {
+        let entry = self.events.get_mut(event)?;
+        entry.flush_scheduled = false;
+        let payload = entry.pending.take()?;
+        entry.last_emit = Some(now);
+        entry.last_payload = Some(payload.clone());
+        Some(payload)
     }
 }
 
@@ -285,9 +613,28 @@ pub struct AutoPlayState {
     pub unpause_action: UnpauseAction,
     pub countdown_active: bool,
     pub countdown_remaining: i32,
+    /// Whether a room-wide unpause countdown is running
+    pub unpause_countdown_active: bool,
+    /// Let the next local unpause through without starting a countdown
+    pub unpause_countdown_bypass: bool,
+    /// Start time of a scheduled session, in server unix milliseconds
+    pub scheduled_start: Option<i64>,
+    /// Let the next local unpause through because the scheduled start was reached
+    pub scheduled_start_due: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+pub struct AutoReadyState {
+    /// File the tracking below refers to
+    pub filename: Option<String>,
+    pub last_size: Option<u64>,
+    pub stable_since: Option<Instant>,
+    pub last_check: Option<Instant>,
+    /// Whether ready was already set automatically for this file
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ServerFeatures {
     pub feature_list: bool,
     pub shared_playlists: bool,
@@ -302,10 +649,49 @@ pub struct ServerFeatures {
     pub max_filename_length: Option<usize>,
 }
 
+/// Why the client treats a server feature as unavailable
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DowngradeReason {
+    /// The server version predates the feature and did not announce it
+    ServerTooOld,
+    /// The server feature list turned it off
+    DisabledByServer,
+    /// Available on the server but turned off in the settings
+    DisabledInSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FeatureDowngrade {
+    /// Feature list key, e.g. `chat`
+    pub feature: String,
+    pub reason: DowngradeReason,
+    /// Oldest server version with the feature, for `ServerTooOld`
+    pub min_version: Option<String>,
+}
+
+/// Inputs and decisions behind the resolved `ServerFeatures`
+#[derive(Debug, Clone, Default)]
+pub struct FeatureNegotiation {
+    /// Feature list exactly as the server sent it
+    pub raw_features: Option<serde_json::Value>,
+    pub downgrades: Vec<FeatureDowngrade>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServerFeatureReport {
+    pub server_version: Option<String>,
+    pub features: ServerFeatures,
+    pub raw_features: Option<serde_json::Value>,
+    pub downgrades: Vec<FeatureDowngrade>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct RoomWarningState {
     pub alone: bool,
     pub file_differences: Option<String>,
+    /// What differs, per user, behind `file_differences`
+    pub file_difference_details: Vec<FileDifference>,
     pub not_ready: Option<String>,
 }
 
@@ -318,6 +704,10 @@ impl Default for AutoPlayState {
             unpause_action: UnpauseAction::IfOthersReady,
             countdown_active: false,
             countdown_remaining: 0,
+            unpause_countdown_active: false,
+            unpause_countdown_bypass: false,
+            scheduled_start: None,
+            scheduled_start_due: false,
         }
     }
 }
@@ -370,12 +760,58 @@ pub struct ChatMessageEvent {
     pub message_type: String,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RoomPasswordRequest {
+    pub room: String,
+    pub message: String,
+}
+
+/// Payload of the `untrusted-url` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UntrustedUrlRequest {
+    pub url: String,
+    pub domain: String,
+}
+
+/// Payload of the `seek-confirmation` event
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct SeekConfirmationRequest {
+    /// Room position the seek was taken back to
+    pub from: f64,
+    pub to: f64,
+}
+
+/// How `load_media_by_name` was asked to load a URL it turned down
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingUrlLoad {
+    pub url: String,
+    pub domain: String,
+    pub reset_position: bool,
+    pub suppress_update: bool,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PlaylistEvent {
     pub items: Vec<String>,
     pub current_index: Option<usize>,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RoomStateSnapshot {
+    pub connected: bool,
+    pub server: Option<String>,
+    pub server_version: Option<String>,
+    pub features: ServerFeatures,
+    pub username: String,
+    pub room: String,
+    pub users: Vec<UserInfo>,
+    pub global_position: f64,
+    pub global_paused: bool,
+    pub global_set_by: Option<String>,
+    pub playlist: PlaylistEvent,
+    pub tls_status: String,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PlayerStateEvent {
     pub filename: Option<String>,
@@ -384,3 +820,39 @@ pub struct PlayerStateEvent {
     pub paused: Option<bool>,
     pub speed: Option<f64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_coalescer_merges_and_defers() {
+        let mut coalescer = EventCoalescer::default();
+        let interval = Duration::from_millis(250);
+        let start = Instant::now();
+
+        let first = coalescer.offer("ping-updated", json!({ "rttMs": 10 }), start, interval);
+        assert_eq!(first, CoalesceDecision::Emit(json!({ "rttMs": 10 })));
+
+        let repeat = coalescer.offer("ping-updated", json!({ "rttMs": 10 }), start, interval);
+        assert_eq!(repeat, CoalesceDecision::Drop);
+
+        let soon = start + Duration::from_millis(100);
+        let burst = coalescer.offer("ping-updated", json!({ "rttMs": 20 }), soon, interval);
+        assert_eq!(burst, CoalesceDecision::Defer(Duration::from_millis(150)));
+        let burst = coalescer.offer("ping-updated", json!({ "rttMs": 30 }), soon, interval);
+        assert_eq!(burst, CoalesceDecision::Drop);
+
+        let flushed = coalescer.take_pending("ping-updated", start + interval);
+        assert_eq!(flushed, Some(json!({ "rttMs": 30 })));
+        assert_eq!(
+            coalescer.take_pending("ping-updated", start + interval),
+            None
+        );
+
+        let later = start + Duration::from_secs(1);
+        let next = coalescer.offer("ping-updated", json!({ "rttMs": 40 }), later, interval);
+        assert_eq!(next, CoalesceDecision::Emit(json!({ "rttMs": 40 })));
+    }
+}
@@ -0,0 +1,142 @@
+// Bookmarks module
+// Labelled playback positions per file, kept locally and optionally shared with the room
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::utils::same_filename;
+
+/// Longest label kept for a bookmark, in characters
+pub const MAX_BOOKMARK_LABEL_LENGTH: usize = 64;
+/// Bookmarks two positions are considered the same within, in seconds
+const SAME_POSITION_SECONDS: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub id: u32,
+    pub file: String,
+    pub position: f64,
+    pub label: String,
+    /// User who shared the bookmark, when it did not come from us
+    #[serde(default)]
+    pub shared_by: Option<String>,
+}
+
+/// Bookmark list, oldest first
+pub struct BookmarkStore {
+    bookmarks: RwLock<Vec<Bookmark>>,
+}
+
+impl BookmarkStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            bookmarks: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// Add a bookmark, replacing one with the same label at the same spot
+    pub fn add(
+        &self,
+        file: impl Into<String>,
+        position: f64,
+        label: &str,
+        shared_by: Option<String>,
+    ) -> Bookmark {
+        let file = file.into();
+        let label: String = label
+            .trim()
+            .chars()
+            .take(MAX_BOOKMARK_LABEL_LENGTH)
+            .collect();
+        let mut bookmarks = self.bookmarks.write();
+        bookmarks.retain(|bookmark| {
+            !(bookmark.label == label
+                && (bookmark.position - position).abs() < SAME_POSITION_SECONDS
+                && same_filename(Some(&bookmark.file), Some(&file)))
+        });
+        let id = bookmarks
+            .iter()
+            .map(|bookmark| bookmark.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let bookmark = Bookmark {
+            id,
+            file,
+            position: position.max(0.0),
+            label,
+            shared_by,
+        };
+        bookmarks.push(bookmark.clone());
+        bookmark
+    }
+
+    pub fn get(&self, id: u32) -> Option<Bookmark> {
+        self.bookmarks
+            .read()
+            .iter()
+            .find(|bookmark| bookmark.id == id)
+            .cloned()
+    }
+
+    pub fn remove(&self, id: u32) -> bool {
+        let mut bookmarks = self.bookmarks.write();
+        let before = bookmarks.len();
+        bookmarks.retain(|bookmark| bookmark.id != id);
+        bookmarks.len() != before
+    }
+
+    /// All bookmarks, optionally only those for one file, sorted by position
+    pub fn entries(&self, file: Option<&str>) -> Vec<Bookmark> {
+        let mut entries: Vec<Bookmark> = self
+            .bookmarks
+            .read()
+            .iter()
+            .filter(|bookmark| {
+                file.is_none_or(|file| same_filename(Some(&bookmark.file), Some(file)))
+            })
+            .cloned()
+            .collect();
+        if file.is_some() {
+            entries.sort_by(|a, b| a.position.total_cmp(&b.position));
+        }
+        entries
+    }
+
+    /// Replace everything with bookmarks loaded from disk
+    pub fn restore(&self, bookmarks: Vec<Bookmark>) {
+        *self.bookmarks.write() = bookmarks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmarks_per_file() {
+        let store = BookmarkStore::new();
+        let op = store.add("Episode 01.mkv", 90.0, "  OP end ", None);
+        store.add("Episode 02.mkv", 85.0, "OP end", Some("alice".to_string()));
+        store.add("Episode 01.mkv", 12.0, "cold open", None);
+
+        assert_eq!(op.label, "OP end");
+        let entries = store.entries(Some("episode 01.mkv"));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].label, "cold open");
+        assert_eq!(store.get(op.id), Some(op.clone()));
+        assert!(store.remove(op.id));
+        assert!(store.get(op.id).is_none());
+    }
+
+    #[test]
+    fn test_bookmark_duplicates_are_replaced() {
+        let store = BookmarkStore::new();
+        store.add("a.mkv", 100.0, "resume", None);
+        let newer = store.add("a.mkv", 100.4, "resume", Some("bob".to_string()));
+        let entries = store.entries(None);
+        assert_eq!(entries, vec![newer]);
+    }
+}
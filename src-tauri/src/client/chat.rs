@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
+use unicode_normalization::UnicodeNormalization;
 
 /// Chat message type
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +18,17 @@ pub enum ChatMessageType {
     Error,
 }
 
+impl ChatMessageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatMessageType::User => "user",
+            ChatMessageType::System => "system",
+            ChatMessageType::Server => "server",
+            ChatMessageType::Error => "error",
+        }
+    }
+}
+
 /// Chat message
 #[derive(Debug, Clone)]
 pub struct ChatMessage {
@@ -141,32 +154,202 @@ impl ChatCommand {
 /unready - Mark yourself as not ready
 /setready <name> or /sr <name> - Set user as ready
 /setnotready <name> or /sn <name> - Set user as not ready
+!vote pause, !vote seek <time> or !vote skip - Vote with the room
 /help or /h or /? - Show this help message"#
             .to_string()
     }
 }
 
+const EMOJI_SHORTCODES: &[(&str, &str)] = &[
+    ("+1", "\u{1f44d}"),
+    ("-1", "\u{1f44e}"),
+    ("100", "\u{1f4af}"),
+    ("clap", "\u{1f44f}"),
+    ("cry", "\u{1f622}"),
+    ("eyes", "\u{1f440}"),
+    ("fire", "\u{1f525}"),
+    ("grin", "\u{1f601}"),
+    ("heart", "\u{2764}\u{fe0f}"),
+    ("joy", "\u{1f602}"),
+    ("laughing", "\u{1f606}"),
+    ("ok_hand", "\u{1f44c}"),
+    ("popcorn", "\u{1f37f}"),
+    ("pray", "\u{1f64f}"),
+    ("rofl", "\u{1f923}"),
+    ("scream", "\u{1f631}"),
+    ("skull", "\u{1f480}"),
+    ("slightly_smiling_face", "\u{1f642}"),
+    ("smile", "\u{1f604}"),
+    ("sob", "\u{1f62d}"),
+    ("sunglasses", "\u{1f60e}"),
+    ("tada", "\u{1f389}"),
+    ("thinking", "\u{1f914}"),
+    ("thumbsdown", "\u{1f44e}"),
+    ("thumbsup", "\u{1f44d}"),
+    ("wave", "\u{1f44b}"),
+    ("wink", "\u{1f609}"),
+];
+
+/// Replace known `:shortcode:` sequences with their emoji, leaving unknown ones as typed
+pub fn expand_emoji_shortcodes(message: &str) -> String {
+    let mut output = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find(':') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let emoji = after.find(':').and_then(|end| {
+            EMOJI_SHORTCODES
+                .iter()
+                .find(|(code, _)| *code == &after[..end])
+                .map(|(_, emoji)| (end, *emoji))
+        });
+        match emoji {
+            Some((end, emoji)) => {
+                output.push_str(emoji);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push(':');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Compose outgoing chat text so other clients see the same characters
+pub fn normalize_chat_text(message: &str) -> String {
+    message.nfc().collect()
+}
+
+/// Messages that may be sent in a burst, also the most parts a message may be split into
+pub const CHAT_RATE_BURST: u32 = 5;
+/// Sustained chat messages per second
+pub const CHAT_RATE_PER_SECOND: f64 = 1.0;
+
+/// Split a chat message into parts of at most `max_len` characters
+///
+/// Parts are numbered like "(1/3) " and broken at whitespace where possible.
+pub fn split_chat_message(message: &str, max_len: usize) -> Vec<String> {
+    let message = message.trim();
+    if max_len == 0 || message.chars().count() <= max_len {
+        return vec![message.to_string()];
+    }
+    let mut total = 2;
+    loop {
+        let prefix_len = format!("({}/{}) ", total, total).len();
+        if prefix_len >= max_len {
+            return chunk_at_whitespace(message, max_len);
+        }
+        let chunks = chunk_at_whitespace(message, max_len - prefix_len);
+        if chunks.len() <= total {
+            let count = chunks.len();
+            return chunks
+                .into_iter()
+                .enumerate()
+                .map(|(index, chunk)| format!("({}/{}) {}", index + 1, count, chunk))
+                .collect();
+        }
+        total = chunks.len();
+    }
+}
+
+fn chunk_at_whitespace(message: &str, max_len: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest: Vec<char> = message.chars().collect();
+    while rest.len() > max_len {
+        // Only break at a space that keeps the chunk at least half full
+        let cut = rest[..=max_len]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .filter(|&index| index > max_len / 2);
+        let (chunk, next) = match cut {
+            Some(index) => (&rest[..index], &rest[index + 1..]),
+            None => (&rest[..max_len], &rest[max_len..]),
+        };
+        chunks.push(chunk.iter().collect::<String>().trim_end().to_string());
+        rest = next
+            .iter()
+            .collect::<String>()
+            .trim_start()
+            .chars()
+            .collect();
+    }
+    if !rest.is_empty() {
+        chunks.push(rest.into_iter().collect());
+    }
+    chunks
+}
+
+/// Token bucket limiting how fast chat messages are sent
+#[derive(Debug)]
+pub struct ChatRateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl ChatRateLimiter {
+    pub fn new(capacity: u32, refill_per_second: f64) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            refill_per_second,
+            tokens: f64::from(capacity),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take `count` tokens, or return how long until that many are available
+    pub fn try_acquire(&mut self, count: u32, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+        let count = f64::from(count);
+        if self.tokens >= count {
+            self.tokens -= count;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (count - self.tokens) / self.refill_per_second,
+            ))
+        }
+    }
+}
+
+impl Default for ChatRateLimiter {
+    fn default() -> Self {
+        Self::new(CHAT_RATE_BURST, CHAT_RATE_PER_SECOND)
+    }
+}
+
 /// Chat manager
 pub struct ChatManager {
     messages: RwLock<Vec<ChatMessage>>,
     max_messages: usize,
+    rate_limiter: Mutex<ChatRateLimiter>,
 }
 
 impl ChatManager {
     pub fn new() -> Arc<Self> {
-        Arc::new(Self {
-            messages: RwLock::new(Vec::new()),
-            max_messages: 1000,
-        })
+        Arc::new(Self::default())
     }
 
     pub fn with_max_messages(max_messages: usize) -> Arc<Self> {
         Arc::new(Self {
-            messages: RwLock::new(Vec::new()),
             max_messages,
+            ..Self::default()
         })
     }
 
+    /// Reserve send capacity for `count` outgoing messages
+    pub fn try_acquire_send(&self, count: u32) -> Result<(), Duration> {
+        self.rate_limiter.lock().try_acquire(count, Instant::now())
+    }
+
     /// Add a message to chat history
     pub fn add_message(&self, message: ChatMessage) {
         let mut messages = self.messages.write();
@@ -237,6 +420,7 @@ impl Default for ChatManager {
         Self {
             messages: RwLock::new(Vec::new()),
             max_messages: 1000,
+            rate_limiter: Mutex::new(ChatRateLimiter::default()),
         }
     }
 }
@@ -340,4 +524,58 @@ mod tests {
         manager.clear();
         assert!(manager.is_empty());
     }
+
+    #[test]
+    fn test_split_chat_message_short() {
+        assert_eq!(split_chat_message("  hello  ", 10), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_split_chat_message_numbers_parts() {
+        let parts = split_chat_message("the quick brown fox jumps over the lazy dog", 20);
+        assert_eq!(
+            parts,
+            vec![
+                "(1/4) the quick",
+                "(2/4) brown fox",
+                "(3/4) jumps over the",
+                "(4/4) lazy dog",
+            ]
+        );
+        assert!(parts.iter().all(|part| part.chars().count() <= 20));
+    }
+
+    #[test]
+    fn test_split_chat_message_without_spaces() {
+        let parts = split_chat_message(&"é".repeat(25), 16);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], format!("(1/3) {}", "é".repeat(10)));
+        assert_eq!(parts[2], format!("(3/3) {}", "é".repeat(5)));
+    }
+
+    #[test]
+    fn test_expand_emoji_shortcodes() {
+        assert_eq!(
+            expand_emoji_shortcodes("nice :fire: :notanemoji: at 12:30 :+1:"),
+            "nice \u{1f525} :notanemoji: at 12:30 \u{1f44d}"
+        );
+        assert_eq!(expand_emoji_shortcodes("::smile::"), ":\u{1f604}:");
+    }
+
+    #[test]
+    fn test_normalize_chat_text() {
+        assert_eq!(normalize_chat_text("cafe\u{301}"), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_chat_rate_limiter() {
+        let mut limiter = ChatRateLimiter::new(3, 1.0);
+        let start = Instant::now();
+        assert!(limiter.try_acquire(3, start).is_ok());
+        let wait = limiter.try_acquire(2, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(2));
+        assert!(limiter
+            .try_acquire(2, start + Duration::from_secs(2))
+            .is_ok());
+    }
 }
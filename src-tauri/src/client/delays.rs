@@ -0,0 +1,105 @@
+// Delays module
+// Audio and subtitle offsets remembered per file
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::utils::same_filename;
+
+/// Largest audio or subtitle offset accepted, in milliseconds
+pub const MAX_DELAY_MS: i64 = 60_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaDelays {
+    pub file: String,
+    #[serde(default)]
+    pub audio_ms: i64,
+    #[serde(default)]
+    pub sub_ms: i64,
+}
+
+impl MediaDelays {
+    pub fn is_zero(&self) -> bool {
+        self.audio_ms == 0 && self.sub_ms == 0
+    }
+}
+
+/// Offsets per file; files without an entry play with no offset
+pub struct DelayStore {
+    entries: RwLock<Vec<MediaDelays>>,
+}
+
+impl DelayStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            entries: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// Offsets for `file`, zero when none were set
+    pub fn get(&self, file: &str) -> MediaDelays {
+        self.entries
+            .read()
+            .iter()
+            .find(|entry| same_filename(Some(&entry.file), Some(file)))
+            .cloned()
+            .unwrap_or_else(|| MediaDelays {
+                file: file.to_string(),
+                audio_ms: 0,
+                sub_ms: 0,
+            })
+    }
+
+    /// Change the offsets of `file`, dropping the entry once both are zero
+    pub fn update(&self, file: &str, audio_ms: Option<i64>, sub_ms: Option<i64>) -> MediaDelays {
+        let mut delays = self.get(file);
+        if let Some(audio_ms) = audio_ms {
+            delays.audio_ms = audio_ms.clamp(-MAX_DELAY_MS, MAX_DELAY_MS);
+        }
+        if let Some(sub_ms) = sub_ms {
+            delays.sub_ms = sub_ms.clamp(-MAX_DELAY_MS, MAX_DELAY_MS);
+        }
+        let mut entries = self.entries.write();
+        entries.retain(|entry| !same_filename(Some(&entry.file), Some(file)));
+        if !delays.is_zero() {
+            entries.push(delays.clone());
+        }
+        delays
+    }
+
+    pub fn entries(&self) -> Vec<MediaDelays> {
+        self.entries.read().clone()
+    }
+
+    /// Replace everything with offsets loaded from disk
+    pub fn restore(&self, entries: Vec<MediaDelays>) {
+        *self.entries.write() = entries.into_iter().filter(|d| !d.is_zero()).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_per_file() {
+        let store = DelayStore::new();
+        store.update("Episode 01.mkv", Some(-250), None);
+        let delays = store.update("episode 01.mkv", None, Some(1200));
+        assert_eq!(delays.audio_ms, -250);
+        assert_eq!(delays.sub_ms, 1200);
+        assert_eq!(store.get("Episode 02.mkv").audio_ms, 0);
+        assert_eq!(store.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_delays_clamped_and_cleared() {
+        let store = DelayStore::new();
+        let delays = store.update("a.mkv", Some(120_000), None);
+        assert_eq!(delays.audio_ms, MAX_DELAY_MS);
+        store.update("a.mkv", Some(0), Some(0));
+        assert!(store.entries().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+// Endgame module
+// Notices when everyone in the room has played the current file to its end
+
+use serde::Serialize;
+
+/// Seconds before the end at which a user counts as finished
+pub const ROOM_FINISHED_THRESHOLD: f64 = 5.0;
+/// Files shorter than this never count as finished, like the playlist advance
+pub const ROOM_FINISHED_MINIMUM_LENGTH: f64 = 10.0;
+
+/// Where one room member is in their file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberProgress {
+    pub username: String,
+    pub position: Option<f64>,
+    pub duration: Option<f64>,
+}
+
+impl MemberProgress {
+    pub fn is_finished(&self) -> bool {
+        match (self.position, self.duration) {
+            (Some(position), Some(duration)) if duration > ROOM_FINISHED_MINIMUM_LENGTH => {
+                position >= duration - ROOM_FINISHED_THRESHOLD
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Payload of the `room-finished` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomFinishedEvent {
+    pub room: String,
+    pub file: String,
+    pub users: Vec<String>,
+}
+
+/// Remembers which file the room was last announced as finished with
+#[derive(Debug, Default)]
+pub struct EndgameTracker {
+    announced: Option<String>,
+    /// File whose playlist advance waits for the rest of the room
+    waiting_advance: Option<String>,
+}
+
+impl EndgameTracker {
+    /// Feed the room's progress on `file`; true the first time everyone is at the end
+    ///
+    /// Someone seeking back arms the announcement again.
+    pub fn update(&mut self, file: &str, members: &[MemberProgress]) -> bool {
+        let finished = !members.is_empty() && members.iter().all(MemberProgress::is_finished);
+        if !finished {
+            self.announced = None;
+            return false;
+        }
+        if self.announced.as_deref() == Some(file) {
+            return false;
+        }
+        self.announced = Some(file.to_string());
+        true
+    }
+
+    /// Hold the playlist advance after `file` until the room catches up
+    pub fn wait_to_advance(&mut self, file: &str) -> bool {
+        if self.waiting_advance.as_deref() == Some(file) {
+            return false;
+        }
+        self.waiting_advance = Some(file.to_string());
+        true
+    }
+
+    /// Whether an advance waits after `file`; one left over from another file is dropped
+    pub fn is_waiting_to_advance(&mut self, file: Option<&str>) -> bool {
+        if self.waiting_advance.is_some() && self.waiting_advance.as_deref() != file {
+            self.waiting_advance = None;
+        }
+        self.waiting_advance.is_some()
+    }
+
+    pub fn finish_waiting(&mut self) {
+        self.waiting_advance = None;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(username: &str, position: Option<f64>, duration: Option<f64>) -> MemberProgress {
+        MemberProgress {
+            username: username.to_string(),
+            position,
+            duration,
+        }
+    }
+
+    #[test]
+    fn test_announces_once_when_everyone_reaches_end() {
+        let mut tracker = EndgameTracker::default();
+        let watching = [
+            member("me", Some(1418.0), Some(1420.0)),
+            member("bob", Some(1300.0), Some(1420.0)),
+        ];
+        assert!(!tracker.update("a.mkv", &watching));
+        let done = [
+            member("me", Some(1418.0), Some(1420.0)),
+            member("bob", Some(1416.0), Some(1420.0)),
+        ];
+        assert!(tracker.update("a.mkv", &done));
+        assert!(!tracker.update("a.mkv", &done));
+        assert!(!tracker.update("a.mkv", &watching));
+        assert!(tracker.update("a.mkv", &done));
+    }
+
+    #[test]
+    fn test_advance_wait_is_dropped_when_the_file_changes() {
+        let mut tracker = EndgameTracker::default();
+        assert!(tracker.wait_to_advance("a.mkv"));
+        assert!(!tracker.wait_to_advance("a.mkv"));
+        assert!(tracker.is_waiting_to_advance(Some("a.mkv")));
+        assert!(!tracker.is_waiting_to_advance(Some("b.mkv")));
+        assert!(!tracker.is_waiting_to_advance(Some("a.mkv")));
+    }
+
+    #[test]
+    fn test_unknown_progress_is_not_finished() {
+        let mut tracker = EndgameTracker::default();
+        assert!(!tracker.update("a.mkv", &[]));
+        assert!(!tracker.update("a.mkv", &[member("me", Some(1418.0), None)]));
+        assert!(!tracker.update("clip.mkv", &[member("me", Some(8.0), Some(9.0))]));
+    }
+}
@@ -0,0 +1,203 @@
+// File difference module
+// Compares our file with another user's and keeps the values that differ
+
+use serde::Serialize;
+
+use crate::network::messages::FileSizeInfo;
+use crate::utils::{
+    hash_filename, is_filename_hash, is_url, same_filename, same_filesize, strip_filename,
+};
+
+/// Durations further apart than this, in seconds, count as different files
+pub const DIFFERENT_DURATION_THRESHOLD: f64 = 2.5;
+
+/// The file attributes a difference is judged on
+#[derive(Debug, Clone, Copy)]
+pub struct FileSide<'a> {
+    pub name: &'a str,
+    pub size: Option<&'a FileSizeInfo>,
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameDifference {
+    pub mine: String,
+    pub theirs: String,
+    /// The names as compared, without punctuation and URL parts
+    pub mine_normalized: String,
+    pub theirs_normalized: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeDifference {
+    pub mine: Option<FileSizeInfo>,
+    pub theirs: Option<FileSizeInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationDifference {
+    pub mine: Option<f64>,
+    pub theirs: Option<f64>,
+    /// Their duration minus ours, when both are known
+    pub delta_seconds: Option<f64>,
+}
+
+/// How another user's file differs from ours
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDifference {
+    pub username: String,
+    pub file: String,
+    pub name: Option<NameDifference>,
+    pub size: Option<SizeDifference>,
+    pub duration: Option<DurationDifference>,
+}
+
+/// Payload of the `file-differences` event, sent whenever the set of differences changes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDifferencesEvent {
+    pub room: String,
+    pub differences: Vec<FileDifference>,
+}
+
+pub fn same_duration(a: Option<f64>, b: Option<f64>, allow: bool) -> bool {
+    if !allow {
+        return true;
+    }
+    let (Some(a), Some(b)) = (a, b) else {
+        return false;
+    };
+    (a.round() - b.round()).abs() < DIFFERENT_DURATION_THRESHOLD
+}
+
+/// Compare `theirs` with `mine`; None when nothing differs
+///
+/// Durations are only compared with `check_duration`, matching the duration notification setting.
+pub fn compare_files(
+    username: &str,
+    mine: FileSide<'_>,
+    theirs: FileSide<'_>,
+    check_duration: bool,
+) -> Option<FileDifference> {
+    let name = (!same_filename(Some(mine.name), Some(theirs.name))).then(|| {
+        let strip_url = is_url(mine.name) ^ is_url(theirs.name);
+        // Against a hashed name, only our hash says anything about the difference
+        let mine_normalized = if is_filename_hash(theirs.name) && !is_filename_hash(mine.name) {
+            hash_filename(mine.name, is_url(mine.name))
+        } else {
+            strip_filename(mine.name, strip_url)
+        };
+        NameDifference {
+            mine: mine.name.to_string(),
+            theirs: theirs.name.to_string(),
+            mine_normalized,
+            theirs_normalized: strip_filename(theirs.name, strip_url),
+        }
+    });
+    let size = (!same_filesize(mine.size, theirs.size)).then(|| SizeDifference {
+        mine: mine.size.cloned(),
+        theirs: theirs.size.cloned(),
+    });
+    let duration = (!same_duration(mine.duration, theirs.duration, check_duration)).then(|| {
+        DurationDifference {
+            mine: mine.duration,
+            theirs: theirs.duration,
+            delta_seconds: mine
+                .duration
+                .zip(theirs.duration)
+                .map(|(mine, theirs)| theirs - mine),
+        }
+    });
+    if name.is_none() && size.is_none() && duration.is_none() {
+        return None;
+    }
+    Some(FileDifference {
+        username: username.to_string(),
+        file: theirs.name.to_string(),
+        name,
+        size,
+        duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difference_keeps_both_values() {
+        let mine_size = FileSizeInfo::Number(1_000);
+        let theirs_size = FileSizeInfo::Number(2_000);
+        let mine = FileSide {
+            name: "Show.S01E01.mkv",
+            size: Some(&mine_size),
+            duration: Some(1420.0),
+        };
+        let theirs = FileSide {
+            name: "Show S01E02.mkv",
+            size: Some(&theirs_size),
+            duration: Some(1435.0),
+        };
+        let diff = compare_files("bob", mine, theirs, true).unwrap();
+        assert_eq!(diff.username, "bob");
+        let name = diff.name.unwrap();
+        assert_eq!(name.mine_normalized, "ShowS01E01mkv");
+        assert_eq!(name.theirs_normalized, "ShowS01E02mkv");
+        assert_eq!(diff.size.unwrap().theirs, Some(theirs_size));
+        assert_eq!(diff.duration.unwrap().delta_seconds, Some(15.0));
+    }
+
+    #[test]
+    fn test_matching_files_have_no_difference() {
+        let size = FileSizeInfo::Number(1_000);
+        let mine = FileSide {
+            name: "Show.S01E01.mkv",
+            size: Some(&size),
+            duration: Some(1420.0),
+        };
+        let theirs = FileSide {
+            name: "Show S01E01.mkv",
+            duration: Some(1421.0),
+            ..mine
+        };
+        assert!(compare_files("bob", mine, theirs, true).is_none());
+        let longer = FileSide {
+            duration: Some(1500.0),
+            ..mine
+        };
+        assert!(compare_files("bob", mine, longer, false).is_none());
+    }
+
+    #[test]
+    fn test_hashed_name_is_compared_with_our_hash() {
+        let hashed = hash_filename("Show S01E01.mkv", false);
+        let size = FileSizeInfo::Number(1_000);
+        let mine = FileSide {
+            name: "Show.S01E01.mkv",
+            size: Some(&size),
+            duration: None,
+        };
+        let theirs = FileSide {
+            name: &hashed,
+            ..mine
+        };
+        assert!(compare_files("bob", mine, theirs, false).is_none());
+        let other = FileSide {
+            name: "Show.S01E02.mkv",
+            ..mine
+        };
+        let name = compare_files("bob", other, theirs, false)
+            .unwrap()
+            .name
+            .unwrap();
+        assert_eq!(
+            name.mine_normalized,
+            hash_filename("Show.S01E02.mkv", false)
+        );
+        assert_eq!(name.theirs_normalized, hashed);
+    }
+}
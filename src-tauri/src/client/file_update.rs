@@ -0,0 +1,156 @@
+// File update module
+// Coalesces rapid file metadata changes into as few Set/List round-trips as possible
+
+use std::time::{Duration, Instant};
+
+use crate::network::messages::FileInfo;
+
+/// Shortest gap between two file updates sent to the server
+pub const FILE_UPDATE_MIN_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileUpdateAction {
+    /// Send the update right away
+    Send,
+    /// Too soon after the last one; flush the pending update after the delay
+    Defer(Duration),
+    /// Folded into an update that is already waiting for its flush
+    Merged,
+    /// The server already has this file information
+    Unchanged,
+}
+
+/// Tracks what the server last heard about our file and what is still waiting to go out
+#[derive(Debug, Default)]
+pub struct FileUpdateThrottle {
+    last_sent: Option<FileInfo>,
+    last_sent_at: Option<Instant>,
+    pending: Option<FileInfo>,
+    flush_scheduled: bool,
+    ticket: u64,
+}
+
+impl FileUpdateThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start preparing an update; only the latest ticket may still submit
+    pub fn begin(&mut self) -> u64 {
+        self.ticket = self.ticket.wrapping_add(1);
+        self.ticket
+    }
+
+    pub fn is_current(&self, ticket: u64) -> bool {
+        self.ticket == ticket
+    }
+
+    /// Decide what to do with fresh file information
+    pub fn submit(&mut self, info: FileInfo, now: Instant) -> FileUpdateAction {
+        if self.last_sent.as_ref() == Some(&info) {
+            // A change that was reverted before its flush has nothing left to say
+            self.pending = None;
+            return FileUpdateAction::Unchanged;
+        }
+        let elapsed = self
+            .last_sent_at
+            .map(|at| now.saturating_duration_since(at));
+        match elapsed {
+            Some(elapsed) if elapsed < FILE_UPDATE_MIN_INTERVAL => {
+                self.pending = Some(info);
+                if self.flush_scheduled {
+                    FileUpdateAction::Merged
+                } else {
+                    self.flush_scheduled = true;
+                    FileUpdateAction::Defer(FILE_UPDATE_MIN_INTERVAL - elapsed)
+                }
+            }
+            _ => {
+                self.pending = None;
+                self.mark_sent(info, now);
+                FileUpdateAction::Send
+            }
+        }
+    }
+
+    /// The dirty update to send once its deferral ends, if one is still waiting
+    pub fn take_pending(&mut self, now: Instant) -> Option<FileInfo> {
+        self.flush_scheduled = false;
+        let info = self.pending.take()?;
+        self.mark_sent(info.clone(), now);
+        Some(info)
+    }
+
+    /// Forget what was sent, e.g. after reconnecting to a server that never saw it
+    pub fn reset(&mut self) {
+        let ticket = self.ticket;
+        *self = Self {
+            ticket,
+            ..Self::default()
+        };
+    }
+
+    fn mark_sent(&mut self, info: FileInfo, now: Instant) {
+        self.last_sent = Some(info);
+        self.last_sent_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, duration: Option<f64>) -> FileInfo {
+        FileInfo {
+            name: Some(name.to_string()),
+            size: None,
+            duration,
+            downloading: None,
+        }
+    }
+
+    #[test]
+    fn test_rapid_updates_are_coalesced() {
+        let mut throttle = FileUpdateThrottle::new();
+        let start = Instant::now();
+        assert_eq!(
+            throttle.submit(info("a.mkv", None), start),
+            FileUpdateAction::Send
+        );
+        let later = start + Duration::from_millis(200);
+        assert_eq!(
+            throttle.submit(info("b.mkv", None), later),
+            FileUpdateAction::Defer(Duration::from_millis(800))
+        );
+        assert_eq!(
+            throttle.submit(info("b.mkv", Some(1400.0)), later),
+            FileUpdateAction::Merged
+        );
+        let flushed = throttle.take_pending(start + FILE_UPDATE_MIN_INTERVAL);
+        assert_eq!(flushed, Some(info("b.mkv", Some(1400.0))));
+        assert!(throttle
+            .take_pending(start + FILE_UPDATE_MIN_INTERVAL)
+            .is_none());
+    }
+
+    #[test]
+    fn test_unchanged_update_is_skipped_until_reset() {
+        let mut throttle = FileUpdateThrottle::new();
+        let start = Instant::now();
+        throttle.submit(info("a.mkv", Some(60.0)), start);
+        let later = start + Duration::from_secs(5);
+        assert_eq!(
+            throttle.submit(info("a.mkv", Some(60.0)), later),
+            FileUpdateAction::Unchanged
+        );
+        let ticket = throttle.begin();
+        throttle.reset();
+        assert!(throttle.is_current(ticket));
+        throttle.begin();
+        assert!(!throttle.is_current(ticket));
+        assert_eq!(
+            throttle.submit(info("a.mkv", Some(60.0)), later),
+            FileUpdateAction::Send
+        );
+    }
+}
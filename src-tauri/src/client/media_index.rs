@@ -1,7 +1,10 @@
 use crate::app_state::AppState;
 use crate::commands::connection::emit_error_message;
 use crate::player::controller::load_media_by_name;
-use crate::utils::{hash_filename, same_filename, strip_filename, PRIVACY_HIDDEN_FILENAME};
+use crate::utils::{
+    hash_filename, is_filename_hash, is_media_file, same_filename, strip_filename,
+    PRIVACY_HIDDEN_FILENAME,
+};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -65,6 +68,12 @@ impl MediaIndexCache {
     }
 
     fn resolve_by_name(&self, filename: &str) -> Option<PathBuf> {
+        // Peers in hashed privacy mode send the hash we index under
+        if is_filename_hash(filename) {
+            if let Some(path) = self.find_existing(self.by_hash.get(filename)) {
+                return Some(path);
+            }
+        }
         let lower = filename.to_ascii_lowercase();
         if let Some(path) = self.find_existing(self.by_lower.get(&lower)) {
             return Some(path);
@@ -96,6 +105,8 @@ pub struct MediaIndex {
     directories: RwLock<Vec<String>>,
     updating: AtomicBool,
     disabled: AtomicBool,
+    /// Set once the first scan has finished, whatever its outcome
+    primed: AtomicBool,
 }
 
 impl MediaIndex {
@@ -105,6 +116,7 @@ impl MediaIndex {
             directories: RwLock::new(Vec::new()),
             updating: AtomicBool::new(false),
             disabled: AtomicBool::new(false),
+            primed: AtomicBool::new(false),
         })
     }
 
@@ -138,6 +150,29 @@ impl MediaIndex {
         self.cache.write().insert_override(filename, path);
     }
 
+    /// Index the media files of a single directory, returning them by filename
+    pub fn index_directory(&self, directory: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(directory)?.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(filename) = entry.file_name().to_str().map(|name| name.to_string()) else {
+                continue;
+            };
+            if !is_media_file(&filename) {
+                continue;
+            }
+            files.push((filename, path));
+        }
+        let mut cache = self.cache.write();
+        for (filename, path) in &files {
+            cache.insert_override(filename, path.clone());
+        }
+        Ok(files)
+    }
+
     pub fn is_available(&self, filename: &str) -> bool {
         self.resolve_path(filename).is_some()
     }
@@ -146,6 +181,10 @@ impl MediaIndex {
         self.updating.load(Ordering::SeqCst)
     }
 
+    pub fn is_primed(&self) -> bool {
+        self.primed.load(Ordering::SeqCst)
+    }
+
     pub fn spawn_indexer(self: Arc<Self>, state: Arc<AppState>) {
         tauri::async_runtime::spawn(async move {
             self.refresh(&state).await;
@@ -165,6 +204,7 @@ impl MediaIndex {
 
     async fn refresh(&self, state: &Arc<AppState>) {
         if self.disabled.load(Ordering::SeqCst) {
+            self.primed.store(true, Ordering::SeqCst);
             return;
         }
         if self.updating.swap(true, Ordering::SeqCst) {
@@ -177,6 +217,7 @@ impl MediaIndex {
         let directories = self.directories.read().clone();
         if directories.is_empty() {
             self.updating.store(false, Ordering::SeqCst);
+            self.primed.store(true, Ordering::SeqCst);
             state.emit_event(
                 "media-index-refreshing",
                 serde_json::json!({ "refreshing": false }),
@@ -191,11 +232,11 @@ impl MediaIndex {
                     "media-index-updated",
                     serde_json::json!({ "timestamp": chrono::Utc::now().to_rfc3339() }),
                 );
-                let queued = state.playlist.get_queued_index_filename();
+                let queued = state.session.playlist.get_queued_index_filename();
                 if let Some(filename) = queued {
-                    let current = state.client_state.get_file();
+                    let current = state.session.client_state.get_file();
                     let already_loaded = same_filename(current.as_deref(), Some(&filename));
-                    if !already_loaded && self.is_available(&filename) {
+                    if !already_loaded && state.media_resolver().is_available(&filename).await {
                         if let Err(e) = load_media_by_name(state, &filename, true, false).await {
                             tracing::warn!("Failed to load queued playlist item after scan: {}", e);
                         }
@@ -222,6 +263,7 @@ impl MediaIndex {
             }
         }
         self.updating.store(false, Ordering::SeqCst);
+        self.primed.store(true, Ordering::SeqCst);
         state.emit_event(
             "media-index-refreshing",
             serde_json::json!({ "refreshing": false }),
@@ -0,0 +1,132 @@
+// Media resolver module
+// Async front for media lookups; filesystem access runs on the blocking pool so slow
+// or network directories cannot stall the sync loop
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::client::media_index::MediaIndex;
+use crate::utils::{same_filename, PRIVACY_HIDDEN_FILENAME};
+
+pub struct MediaResolver {
+    index: Arc<MediaIndex>,
+    directories: Vec<String>,
+}
+
+impl MediaResolver {
+    pub fn new(index: Arc<MediaIndex>, directories: Vec<String>) -> Self {
+        Self { index, directories }
+    }
+
+    /// Find `filename` in the index, falling back to a direct look in the media directories
+    pub async fn resolve(&self, filename: &str) -> Option<PathBuf> {
+        let index = self.index.clone();
+        let directories = self.directories.clone();
+        let filename = filename.to_string();
+        tokio::task::spawn_blocking(move || {
+            index
+                .resolve_path(&filename)
+                .or_else(|| resolve_media_path(&directories, &filename))
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    pub async fn is_available(&self, filename: &str) -> bool {
+        self.resolve(filename).await.is_some()
+    }
+
+    /// Index one directory without blocking the runtime
+    pub async fn index_directory(
+        &self,
+        directory: PathBuf,
+    ) -> Result<Vec<(String, PathBuf)>, String> {
+        let index = self.index.clone();
+        tokio::task::spawn_blocking(move || index.index_directory(&directory))
+            .await
+            .map_err(|e| format!("Failed to scan directory: {}", e))?
+            .map_err(|e| format!("Failed to read directory: {}", e))
+    }
+}
+
+/// Size of a local file, or `None` when it cannot be read
+pub async fn file_size(path: &Path) -> Option<u64> {
+    tokio::fs::metadata(path)
+        .await
+        .ok()
+        .map(|metadata| metadata.len())
+}
+
+pub fn resolve_media_path(media_directories: &[String], filename: &str) -> Option<PathBuf> {
+    if filename == PRIVACY_HIDDEN_FILENAME {
+        return None;
+    }
+    let target = Path::new(filename)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(filename);
+    for directory in media_directories {
+        let directory = directory.trim();
+        if directory.is_empty() {
+            continue;
+        }
+        let candidate = Path::new(directory).join(target);
+        if candidate.exists() {
+            return Some(candidate);
+        }
+    }
+
+    for directory in media_directories {
+        let directory = directory.trim();
+        if directory.is_empty() {
+            continue;
+        }
+        let dir_path = Path::new(directory);
+        let entries = match std::fs::read_dir(dir_path) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let candidate_name = path.file_name()?.to_string_lossy();
+            if same_filename(Some(target), Some(candidate_name.as_ref())) {
+                return Some(path);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_media_path;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_media_path_multiple_directories() {
+        let dir1 = TempDir::new().unwrap();
+        let dir2 = TempDir::new().unwrap();
+        let filename = "movie.mp4";
+        let file_path = dir2.path().join(filename);
+        fs::write(&file_path, b"test").unwrap();
+
+        let directories = vec![
+            dir1.path().to_string_lossy().to_string(),
+            dir2.path().to_string_lossy().to_string(),
+        ];
+        let resolved = resolve_media_path(&directories, filename).unwrap();
+        assert_eq!(resolved, file_path);
+    }
+
+    #[test]
+    fn test_resolve_media_path_empty() {
+        let directories: Vec<String> = Vec::new();
+        assert!(resolve_media_path(&directories, "file.mp4").is_none());
+    }
+}
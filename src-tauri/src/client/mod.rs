@@ -1,8 +1,25 @@
+pub mod bookmarks;
 pub mod chat;
+pub mod delays;
+pub mod endgame;
+pub mod file_diff;
+pub mod file_update;
 pub mod local_state;
 pub mod media_index;
+pub mod media_resolver;
+pub mod motd;
+pub mod operator;
+pub mod persistent_rooms;
 pub mod playlist;
+pub mod protocol;
 pub mod ready;
+pub mod room_control;
+pub mod room_events;
+pub mod segments;
+pub mod session_actor;
+pub mod session_log;
+pub mod session_stats;
 pub mod state;
 pub mod sync;
 pub mod userlist;
+pub mod votes;
@@ -0,0 +1,120 @@
+// Server message module
+// Prepares server MOTDs and announcements for display outside the chat stream
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerMessageKind {
+    /// Message of the day sent with the server Hello
+    Motd,
+    /// Chat line sent by the server itself rather than a user
+    Announcement,
+}
+
+/// Payload of the `server-motd` event
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMotdEvent {
+    pub server: String,
+    pub kind: ServerMessageKind,
+    pub message: String,
+    pub links: Vec<String>,
+    pub markdown: bool,
+    /// Stable hash of the message, used to remember dismissals
+    pub fingerprint: String,
+    pub timestamp: String,
+}
+
+impl ServerMotdEvent {
+    pub fn new(server: impl Into<String>, kind: ServerMessageKind, message: &str) -> Self {
+        let message = message.trim().to_string();
+        Self {
+            server: server.into(),
+            kind,
+            links: detect_links(&message),
+            markdown: looks_like_markdown(&message),
+            fingerprint: fingerprint(&message),
+            message,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Collect http(s) URLs in order of appearance, without duplicates
+pub fn detect_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| rest.find(scheme))
+        .min()
+    {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | ']' | '>' | '"' | '\''))
+            .unwrap_or(candidate.len());
+        let link = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if link.len() > "https://".len() && !links.iter().any(|known| known == link) {
+            links.push(link.to_string());
+        }
+        rest = &candidate[end..];
+    }
+    links
+}
+
+/// Cheap check for markdown syntax the frontend should render
+pub fn looks_like_markdown(text: &str) -> bool {
+    if text.contains("**") || text.contains("](") || text.contains('`') {
+        return true;
+    }
+    text.lines().any(|line| {
+        let line = line.trim_start();
+        ["# ", "## ", "- ", "* ", "> "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+    })
+}
+
+/// FNV-1a hash, stable across builds so persisted dismissals keep matching
+pub fn fingerprint(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.trim().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_links() {
+        let text = "Rules: https://example.com/rules. Discord (https://discord.gg/abc) \
+                    and [docs](http://docs.example.org) https://example.com/rules";
+        assert_eq!(
+            detect_links(text),
+            vec![
+                "https://example.com/rules".to_string(),
+                "https://discord.gg/abc".to_string(),
+                "http://docs.example.org".to_string(),
+            ]
+        );
+        assert!(detect_links("no links, just https://").is_empty());
+    }
+
+    #[test]
+    fn test_looks_like_markdown() {
+        assert!(looks_like_markdown("Welcome to **our** server"));
+        assert!(looks_like_markdown("Rules:\n- be nice\n- no spam"));
+        assert!(!looks_like_markdown("Welcome to the server - have fun"));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_surrounding_whitespace() {
+        assert_eq!(fingerprint("hello"), fingerprint("  hello\n"));
+        assert_ne!(fingerprint("hello"), fingerprint("hello!"));
+    }
+}
@@ -0,0 +1,212 @@
+// Operator handoff module
+// Hands a managed room's password to another user without showing it in room chat
+//
+// The operator offers control to one user, the user answers with a one-time X25519
+// key, and the operator replies with the password sealed for that key. Everyone
+// else in the room only ever sees public keys and ciphertext.
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+use x25519_dalek::{EphemeralSecret, PublicKey, ReusableSecret};
+
+/// How long either side waits for the next step of a handoff
+pub const OPERATOR_HANDOFF_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Operator side: control offered to `grantee`, waiting for their key
+#[derive(Debug, Clone)]
+pub struct OperatorGrant {
+    pub grantee: String,
+    pub room: String,
+    pub started: Instant,
+}
+
+/// Receiving side: control offered by `operator`
+pub struct OperatorOffer {
+    pub operator: String,
+    pub room: String,
+    pub started: Instant,
+    /// Set once the offer was accepted and our key went out
+    secret: Option<ReusableSecret>,
+}
+
+/// Payload of the `operator-offer` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorOfferEvent {
+    pub operator: String,
+    pub room: String,
+}
+
+/// Handoffs in flight for the current connection
+#[derive(Default)]
+pub struct OperatorHandoff {
+    pub grant: Option<OperatorGrant>,
+    pub offer: Option<OperatorOffer>,
+}
+
+impl OperatorGrant {
+    pub fn new(grantee: impl Into<String>, room: impl Into<String>) -> Self {
+        Self {
+            grantee: grantee.into(),
+            room: room.into(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) > OPERATOR_HANDOFF_TIMEOUT
+    }
+}
+
+impl OperatorOffer {
+    pub fn new(operator: impl Into<String>, room: impl Into<String>) -> Self {
+        Self {
+            operator: operator.into(),
+            room: room.into(),
+            started: Instant::now(),
+            secret: None,
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) > OPERATOR_HANDOFF_TIMEOUT
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.secret.is_some()
+    }
+
+    /// Create the one-time key for this offer; returns the public half as hex
+    pub fn accept(&mut self) -> String {
+        let secret = ReusableSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret);
+        self.secret = Some(secret);
+        self.started = Instant::now();
+        encode_hex(public.as_bytes())
+    }
+
+    /// Open a sealed password
+    ///
+    /// Fails for keys sealed for someone else, so the offer stays usable until one opens.
+    pub fn open(&self, operator_key: &str, sealed: &str) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let operator_key = PublicKey::from(decode_key(operator_key)?);
+        let shared = secret.diffie_hellman(&operator_key);
+        let cipher = cipher_for(shared.as_bytes(), &self.room);
+        let plaintext = cipher
+            .decrypt(&Nonce::default(), decode_hex(sealed)?.as_slice())
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+}
+
+impl OperatorHandoff {
+    /// Forget handoffs whose next step never came
+    pub fn expire(&mut self, now: Instant) {
+        if self
+            .grant
+            .as_ref()
+            .is_some_and(|grant| grant.is_expired(now))
+        {
+            self.grant = None;
+        }
+        if self
+            .offer
+            .as_ref()
+            .is_some_and(|offer| offer.is_expired(now))
+        {
+            self.offer = None;
+        }
+    }
+}
+
+/// Seal `password` for the grantee's key; returns our public key and the ciphertext as hex
+pub fn seal_password(grantee_key: &str, room: &str, password: &str) -> Option<(String, String)> {
+    let grantee_key = PublicKey::from(decode_key(grantee_key)?);
+    let secret = EphemeralSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    let shared = secret.diffie_hellman(&grantee_key);
+    // Both keys are single use, so a fixed nonce never repeats under one key
+    let sealed = cipher_for(shared.as_bytes(), room)
+        .encrypt(&Nonce::default(), password.as_bytes())
+        .ok()?;
+    Some((encode_hex(public.as_bytes()), encode_hex(&sealed)))
+}
+
+fn cipher_for(shared: &[u8; 32], room: &str) -> ChaCha20Poly1305 {
+    let mut hasher = Sha256::new();
+    hasher.update(b"syncplay-operator-handoff");
+    hasher.update(shared);
+    hasher.update(room.as_bytes());
+    let key = hasher.finalize();
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn decode_key(value: &str) -> Option<[u8; 32]> {
+    decode_hex(value)?.try_into().ok()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim();
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&value[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_password_opens_only_for_the_accepting_user() {
+        let mut offer = OperatorOffer::new("op", "+movies:ABCDEF");
+        let key = offer.accept();
+        assert!(offer.is_accepted());
+        let (operator_key, sealed) = seal_password(&key, "+movies:ABCDEF", "AB-123-456").unwrap();
+        assert!(!sealed.contains("AB-123-456"));
+
+        let mut eavesdropper = OperatorOffer::new("op", "+movies:ABCDEF");
+        eavesdropper.accept();
+        assert!(eavesdropper.open(&operator_key, &sealed).is_none());
+
+        let (other_key, other_sealed) =
+            seal_password(&eavesdropper.accept(), "+movies:ABCDEF", "x").unwrap();
+        assert!(offer.open(&other_key, &other_sealed).is_none());
+        assert_eq!(
+            offer.open(&operator_key, &sealed).as_deref(),
+            Some("AB-123-456")
+        );
+    }
+
+    #[test]
+    fn test_malformed_keys_are_rejected() {
+        assert!(seal_password("not hex", "+room:ABC", "pw").is_none());
+        assert!(seal_password("abcd", "+room:ABC", "pw").is_none());
+        let offer = OperatorOffer::new("op", "+room:ABC");
+        assert!(offer.open(&"00".repeat(32), "00").is_none());
+    }
+
+    #[test]
+    fn test_stale_handoffs_expire() {
+        let mut handoff = OperatorHandoff {
+            grant: Some(OperatorGrant::new("bob", "+room:ABC")),
+            offer: Some(OperatorOffer::new("op", "+room:ABC")),
+        };
+        handoff.expire(Instant::now());
+        assert!(handoff.grant.is_some() && handoff.offer.is_some());
+        handoff.expire(Instant::now() + OPERATOR_HANDOFF_TIMEOUT + Duration::from_secs(1));
+        assert!(handoff.grant.is_none() && handoff.offer.is_none());
+    }
+}
@@ -0,0 +1,160 @@
+// Persistent rooms module
+// Rooms marked to keep their playlist between visits, remembered per server
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentRoom {
+    /// `host:port` of the server the room lives on
+    pub server: String,
+    pub room: String,
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub current_index: Option<usize>,
+}
+
+/// A persistent room as shown in the room browser
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentRoomInfo {
+    pub name: String,
+    pub user_count: usize,
+    /// Listed by the server while empty, so the server keeps it around
+    pub on_server: bool,
+    /// Marked persistent on this client
+    pub marked: bool,
+    pub playlist_length: usize,
+    pub is_current: bool,
+}
+
+/// Locally marked persistent rooms with the playlist last seen in each
+pub struct PersistentRoomStore {
+    rooms: RwLock<Vec<PersistentRoom>>,
+}
+
+impl PersistentRoomStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            rooms: RwLock::new(Vec::new()),
+        })
+    }
+
+    pub fn is_marked(&self, server: &str, room: &str) -> bool {
+        self.rooms
+            .read()
+            .iter()
+            .any(|entry| entry.server == server && entry.room == room)
+    }
+
+    /// Mark or unmark a room; returns whether anything changed
+    pub fn set_marked(&self, server: &str, room: &str, marked: bool) -> bool {
+        let mut rooms = self.rooms.write();
+        let position = rooms
+            .iter()
+            .position(|entry| entry.server == server && entry.room == room);
+        match (position, marked) {
+            (Some(index), false) => {
+                rooms.remove(index);
+                true
+            }
+            (None, true) => {
+                rooms.push(PersistentRoom {
+                    server: server.to_string(),
+                    room: room.to_string(),
+                    items: Vec::new(),
+                    current_index: None,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remember the playlist of a marked room; returns whether it was stored
+    pub fn remember_playlist(
+        &self,
+        server: &str,
+        room: &str,
+        items: Vec<String>,
+        current_index: Option<usize>,
+    ) -> bool {
+        let mut rooms = self.rooms.write();
+        let Some(entry) = rooms
+            .iter_mut()
+            .find(|entry| entry.server == server && entry.room == room)
+        else {
+            return false;
+        };
+        if entry.items == items && entry.current_index == current_index {
+            return false;
+        }
+        entry.current_index = current_index.filter(|index| *index < items.len());
+        entry.items = items;
+        true
+    }
+
+    /// The remembered playlist of a marked room, if it had any items
+    pub fn playlist_for(&self, server: &str, room: &str) -> Option<(Vec<String>, Option<usize>)> {
+        self.rooms
+            .read()
+            .iter()
+            .find(|entry| entry.server == server && entry.room == room)
+            .filter(|entry| !entry.items.is_empty())
+            .map(|entry| (entry.items.clone(), entry.current_index))
+    }
+
+    pub fn rooms_for(&self, server: &str) -> Vec<PersistentRoom> {
+        self.rooms
+            .read()
+            .iter()
+            .filter(|entry| entry.server == server)
+            .cloned()
+            .collect()
+    }
+
+    pub fn entries(&self) -> Vec<PersistentRoom> {
+        self.rooms.read().clone()
+    }
+
+    /// Replace everything with rooms loaded from disk
+    pub fn restore(&self, rooms: Vec<PersistentRoom>) {
+        *self.rooms.write() = rooms;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER: &str = "syncplay.pl:8999";
+
+    #[test]
+    fn test_playlist_only_remembered_for_marked_rooms() {
+        let store = PersistentRoomStore::new();
+        let items = vec!["a.mkv".to_string(), "b.mkv".to_string()];
+        assert!(!store.remember_playlist(SERVER, "movies", items.clone(), Some(1)));
+        assert!(store.set_marked(SERVER, "movies", true));
+        assert!(!store.set_marked(SERVER, "movies", true));
+        assert!(store.remember_playlist(SERVER, "movies", items.clone(), Some(1)));
+        assert_eq!(store.playlist_for(SERVER, "movies"), Some((items, Some(1))));
+        assert!(store.playlist_for("other:8999", "movies").is_none());
+    }
+
+    #[test]
+    fn test_unmarking_forgets_playlist() {
+        let store = PersistentRoomStore::new();
+        store.set_marked(SERVER, "movies", true);
+        store.remember_playlist(SERVER, "movies", vec!["a.mkv".to_string()], Some(4));
+        assert_eq!(
+            store.playlist_for(SERVER, "movies"),
+            Some((vec!["a.mkv".to_string()], None))
+        );
+        assert!(store.set_marked(SERVER, "movies", false));
+        assert!(store.playlist_for(SERVER, "movies").is_none());
+        assert!(store.rooms_for(SERVER).is_empty());
+    }
+}
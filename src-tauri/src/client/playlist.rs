@@ -1,4 +1,5 @@
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
@@ -26,6 +27,14 @@ impl PlaylistItem {
     }
 }
 
+/// Playlist contents persisted between sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistSnapshot {
+    pub room: Option<String>,
+    pub items: Vec<String>,
+    pub current_index: Option<usize>,
+}
+
 /// Shared playlist manager
 pub struct Playlist {
     items: RwLock<Vec<PlaylistItem>>,
@@ -34,6 +43,8 @@ pub struct Playlist {
     previous_playlist: RwLock<Option<Vec<String>>>,
     previous_playlist_room: RwLock<Option<String>>,
     switch_to_new_item: RwLock<bool>,
+    queued_next_filename: RwLock<Option<String>>,
+    stop_after_current: RwLock<bool>,
     last_index_change: RwLock<Option<Instant>>,
 }
 
@@ -46,6 +57,8 @@ impl Playlist {
             previous_playlist: RwLock::new(None),
             previous_playlist_room: RwLock::new(None),
             switch_to_new_item: RwLock::new(false),
+            queued_next_filename: RwLock::new(None),
+            stop_after_current: RwLock::new(false),
             last_index_change: RwLock::new(None),
         })
     }
@@ -91,6 +104,47 @@ impl Playlist {
         *self.switch_to_new_item.write() = true;
     }
 
+    pub fn get_queued_next_filename(&self) -> Option<String> {
+        self.queued_next_filename.read().clone()
+    }
+
+    /// Remember an item that should be played once the current file ends
+    pub fn set_queued_next_filename(&self, filename: Option<String>) {
+        *self.queued_next_filename.write() = filename;
+    }
+
+    /// Take the queued item and resolve it against the current items
+    pub fn take_queued_next_index(&self) -> Option<usize> {
+        let filename = self.queued_next_filename.write().take()?;
+        let index = self.index_of_filename(&filename)?;
+        if Some(index) == self.get_current_index() {
+            return None;
+        }
+        Some(index)
+    }
+
+    pub fn is_stop_after_current(&self) -> bool {
+        *self.stop_after_current.read()
+    }
+
+    pub fn set_stop_after_current(&self, stop: bool) {
+        *self.stop_after_current.write() = stop;
+    }
+
+    /// Consume the one-shot "stop after current item" flag
+    pub fn take_stop_after_current(&self) -> bool {
+        std::mem::take(&mut *self.stop_after_current.write())
+    }
+
+    /// Position at which a "play next" item should be inserted
+    pub fn next_insert_position(&self) -> usize {
+        let len = self.items.read().len();
+        match *self.current_index.read() {
+            Some(index) if index < len => index + 1,
+            _ => len,
+        }
+    }
+
     pub fn opened_file(&self) {
         *self.last_index_change.write() = Some(Instant::now());
     }
@@ -323,6 +377,7 @@ impl Playlist {
         *self.current_index.write() = None;
         *self.last_index_change.write() = Some(Instant::now());
         *self.queued_index_filename.write() = None;
+        *self.queued_next_filename.write() = None;
     }
 
     /// Get playlist size
@@ -402,6 +457,26 @@ impl Playlist {
         previous.as_ref() != Some(&current_items) && current_items != new_playlist
     }
 
+    pub fn snapshot(&self) -> PlaylistSnapshot {
+        PlaylistSnapshot {
+            room: self.previous_playlist_room.read().clone(),
+            items: self.get_item_filenames(),
+            current_index: self.get_current_index(),
+        }
+    }
+
+    /// Restore a playlist persisted by a previous session
+    pub fn restore_snapshot(&self, snapshot: PlaylistSnapshot) {
+        info!(
+            "Restoring playlist with {} items for room {:?}",
+            snapshot.items.len(),
+            snapshot.room
+        );
+        self.set_items_with_index(snapshot.items, snapshot.current_index);
+        *self.previous_playlist.write() = None;
+        *self.previous_playlist_room.write() = snapshot.room;
+    }
+
     pub fn can_undo(&self) -> bool {
         let current_items = self.get_item_filenames();
         let previous = self.previous_playlist.read();
@@ -418,6 +493,8 @@ impl Default for Playlist {
             previous_playlist: RwLock::new(None),
             previous_playlist_room: RwLock::new(None),
             switch_to_new_item: RwLock::new(false),
+            queued_next_filename: RwLock::new(None),
+            stop_after_current: RwLock::new(false),
             last_index_change: RwLock::new(None),
         }
     }
@@ -532,4 +609,63 @@ mod tests {
         assert!(playlist.is_empty());
         assert_eq!(playlist.get_current_index(), None);
     }
+
+    #[test]
+    fn test_playlist_next_insert_position() {
+        let playlist = Playlist::new();
+        assert_eq!(playlist.next_insert_position(), 0);
+
+        playlist.set_items(vec![
+            "file1.mp4".to_string(),
+            "file2.mp4".to_string(),
+            "file3.mp4".to_string(),
+        ]);
+        playlist.set_current_index(1);
+        assert_eq!(playlist.next_insert_position(), 2);
+    }
+
+    #[test]
+    fn test_playlist_take_queued_next_index() {
+        let playlist = Playlist::new();
+        playlist.set_items(vec![
+            "file1.mp4".to_string(),
+            "file2.mp4".to_string(),
+            "file3.mp4".to_string(),
+        ]);
+        playlist.set_queued_next_filename(Some("file3.mp4".to_string()));
+
+        assert_eq!(playlist.take_queued_next_index(), Some(2));
+        assert_eq!(playlist.take_queued_next_index(), None);
+
+        playlist.set_queued_next_filename(Some("missing.mp4".to_string()));
+        assert_eq!(playlist.take_queued_next_index(), None);
+        assert_eq!(playlist.get_queued_next_filename(), None);
+    }
+
+    #[test]
+    fn test_playlist_snapshot_roundtrip() {
+        let playlist = Playlist::new();
+        playlist.update_previous_playlist(&["file1.mp4".to_string()], "room");
+        playlist.set_items(vec!["file1.mp4".to_string(), "file2.mp4".to_string()]);
+        playlist.set_current_index(1);
+
+        let snapshot = playlist.snapshot();
+        assert_eq!(snapshot.room.as_deref(), Some("room"));
+
+        let restored = Playlist::new();
+        restored.restore_snapshot(snapshot.clone());
+        assert_eq!(restored.snapshot(), snapshot);
+        assert!(!restored.playlist_buffer_is_from_old_room("room"));
+    }
+
+    #[test]
+    fn test_playlist_stop_after_current_is_one_shot() {
+        let playlist = Playlist::new();
+        assert!(!playlist.take_stop_after_current());
+
+        playlist.set_stop_after_current(true);
+        assert!(playlist.is_stop_after_current());
+        assert!(playlist.take_stop_after_current());
+        assert!(!playlist.is_stop_after_current());
+    }
 }
@@ -0,0 +1,1735 @@
+// Protocol handling module
+// Applies server messages through a context trait so it can run without Tauri
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::app_state::{DowngradeReason, FeatureDowngrade, ServerFeatures};
+use crate::client::chat::ChatManager;
+use crate::client::motd::{ServerMessageKind, ServerMotdEvent};
+use crate::client::playlist::Playlist;
+use crate::client::ready::READY_CHECK_SECONDS;
+use crate::client::room_control::Suggestion;
+use crate::client::state::{ClientState, User};
+use crate::client::votes::VoteSubject;
+use crate::config::SyncplayConfig;
+use crate::network::messages::{
+    ChatMessage, ControllerAuth, HelloMessage, IgnoringInfo, ListResponse, NewControlledRoom,
+    PingInfo, PlayState, PlaylistChange, PlaylistIndexUpdate, ProtocolMessage, ReadyState,
+    SetMessage, StateMessage, TLSMessage, UserUpdate,
+};
+use crate::player::backend::{OsdStyle, PlayerBackend};
+use crate::utils::{is_controlled_room, is_placeholder_username, same_filename, version_meets_min};
+
+pub(crate) const UNPAUSE_COUNTDOWN_CHAT_PREFIX: &str = "[unpause-countdown] ";
+pub(crate) const READY_CHECK_CHAT_PREFIX: &str = "[ready-check] ";
+/// Followed by the start time in server unix milliseconds
+pub(crate) const SCHEDULED_START_CHAT_PREFIX: &str = "[scheduled-start] ";
+/// How far ahead a session start can be scheduled
+pub(crate) const MAX_SCHEDULE_AHEAD_HOURS: i64 = 24;
+/// Followed by the position in seconds and the bookmark label
+pub(crate) const BOOKMARK_CHAT_PREFIX: &str = "[bookmark] ";
+/// Followed by the user offered operator control
+pub(crate) const OPERATOR_OFFER_CHAT_PREFIX: &str = "[operator-offer] ";
+/// Followed by the one-time public key of the user accepting an offer
+pub(crate) const OPERATOR_ACCEPT_CHAT_PREFIX: &str = "[operator-accept] ";
+/// Followed by the operator's one-time public key and the sealed room password
+pub(crate) const OPERATOR_KEY_CHAT_PREFIX: &str = "[operator-key] ";
+/// Followed by the users allowed to pause and seek; none lifts the rule
+pub(crate) const OPERATORS_ONLY_CHAT_PREFIX: &str = "[operators-only]";
+/// Followed by what a non-operator suggests the operators do
+pub(crate) const SUGGESTION_CHAT_PREFIX: &str = "[suggest] ";
+/// Typed by users: `!vote pause`, `!vote seek <time>` or `!vote skip`
+pub(crate) const VOTE_CHAT_PREFIX: &str = "!vote ";
+
+pub(crate) const CONTROLLED_ROOMS_MIN_VERSION: &str = "1.3.0";
+pub(crate) const USER_READY_MIN_VERSION: &str = "1.3.0";
+pub(crate) const SHARED_PLAYLIST_MIN_VERSION: &str = "1.4.0";
+pub(crate) const CHAT_MIN_VERSION: &str = "1.5.0";
+pub(crate) const FEATURE_LIST_MIN_VERSION: &str = "1.5.0";
+pub(crate) const SET_OTHERS_READINESS_MIN_VERSION: &str = "1.7.2";
+pub(crate) const FALLBACK_MAX_CHAT_MESSAGE_LENGTH: usize = 50;
+pub(crate) const FALLBACK_MAX_USERNAME_LENGTH: usize = 16;
+pub(crate) const FALLBACK_MAX_ROOM_NAME_LENGTH: usize = 35;
+pub(crate) const FALLBACK_MAX_FILENAME_LENGTH: usize = 250;
+
+/// Everything the protocol handlers need from the running client
+#[async_trait]
+pub trait ClientContext: Send + Sync {
+    fn client_state(&self) -> &ClientState;
+    fn chat_history(&self) -> &ChatManager;
+    fn playlist(&self) -> &Playlist;
+    fn config(&self) -> SyncplayConfig;
+    fn chat_supported(&self) -> bool;
+    fn shared_playlists_supported(&self) -> bool;
+    /// host:port of the current server
+    fn server_address(&self) -> Option<String>;
+    fn player(&self) -> Option<Arc<dyn PlayerBackend>>;
+    /// Whether chat should stay off the OSD for now
+    fn do_not_disturb(&self) -> bool;
+    fn emit(&self, event: &str, payload: serde_json::Value);
+    fn send(&self, message: ProtocolMessage) -> anyhow::Result<()>;
+
+    /// The server answered our Hello; settle features and mark the session as started
+    fn hello_accepted(&self, hello: &HelloMessage);
+    fn server_motd(&self, motd: &str);
+    fn server_error(&self, message: &str);
+    async fn tls_answer(&self, tls: TLSMessage);
+    /// Send the control password of the current room again, if we know it
+    fn reidentify_as_controller(&self);
+    /// Tell a fresh session which file we have open
+    fn announce_file(&self);
+    /// The known users were replaced by a List response
+    fn user_list_applied(&self);
+    /// Act on what `handle_chat` decided; `entry` is the sender and text of the message
+    fn chat_handled(&self, outcome: ChatOutcome, entry: Option<(String, String)>);
+
+    fn update_ignoring(&self, ignoring: IgnoringInfo);
+    async fn client_ignore_active(&self) -> bool;
+    /// Track the RTT of a server ping, returning how old its message is in seconds
+    fn ping_received(&self, ping: &PingInfo) -> f64;
+    async fn apply_playstate(&self, playstate: PlayState, message_age: f64);
+    /// Answer a server State with ours
+    async fn reply_to_state(&self, latency_calculation: Option<f64>);
+
+    /// The server moved us to another room
+    fn room_changed(&self);
+    async fn load_file(&self, name: &str) -> Result<(), String>;
+    /// Announce a user update that was already applied to the client state
+    fn user_changed(&self, username: &str, change: &UserChange);
+    fn ready_changed(&self, username: &str, room: String, ready: bool, set_by: Option<String>);
+    fn controller_auth(&self, auth: ControllerAuth);
+    async fn new_controlled_room(&self, room: NewControlledRoom);
+    fn users_changed(&self);
+    /// Someone left the room and the user wants playback paused for that
+    async fn pause_for_leave(&self);
+    /// Whether the playlist of the previous room may still have to be restored, clearing it
+    fn take_playlist_restore(&self) -> bool;
+    /// Playlist remembered for `room` if the user keeps it persistent
+    fn remembered_playlist(&self, room: &str) -> Option<(Vec<String>, Option<usize>)>;
+    fn playlist_changed(&self);
+    /// Mark the playlist index as seen, returning whether playback should start over
+    async fn playlist_index_seen(&self) -> bool;
+    async fn apply_playlist_index(&self, index: usize, reset_position: bool) -> Result<(), String>;
+    /// Show a message about the current room on the OSD, if the user wants those
+    fn show_osd(&self, message: &str);
+    fn evaluate_autoplay(&self);
+}
+
+/// What the caller still has to do after a chat message was handled
+#[derive(Debug, PartialEq)]
+pub enum ChatOutcome {
+    /// Nothing was shown
+    Dropped,
+    /// Another user announced an unpause countdown
+    UnpauseCountdown { actor: String, seconds: i32 },
+    /// Another user asked the room for a ready check
+    ReadyCheck { actor: String, seconds: u64 },
+    /// Another user scheduled the session start
+    ScheduledStart { actor: String, start_ms: i64 },
+    /// Another user shared a bookmark for the file they are playing
+    Bookmark {
+        actor: String,
+        position: f64,
+        label: String,
+    },
+    /// An operator offered us control of their room
+    OperatorOffer { actor: String },
+    /// A user accepted an operator offer with their one-time key
+    OperatorAccept { actor: String, key: String },
+    /// An operator sent the room password sealed for our one-time key
+    OperatorKey {
+        actor: String,
+        key: String,
+        sealed: String,
+    },
+    /// Another user set who may pause and seek in this normal room
+    OperatorsOnly {
+        actor: String,
+        operators: Vec<String>,
+    },
+    /// A non-operator suggested a pause or seek instead of doing it
+    Suggestion {
+        actor: String,
+        suggestion: Suggestion,
+    },
+    /// A user, possibly us, voted on playback
+    Vote { actor: String, subject: VoteSubject },
+    /// A server announcement was sent as a `server-motd` event
+    Announcement,
+    /// The message was shown on the player and sent to the frontend
+    Delivered { mentioned: bool },
+}
+
+pub(crate) fn parse_unpause_countdown(message: &str) -> Option<i32> {
+    let seconds = message
+        .strip_prefix(UNPAUSE_COUNTDOWN_CHAT_PREFIX)?
+        .trim()
+        .parse::<i32>()
+        .ok()?;
+    (1..=10).contains(&seconds).then_some(seconds)
+}
+
+pub(crate) fn parse_ready_check(message: &str) -> Option<u64> {
+    let seconds = message
+        .strip_prefix(READY_CHECK_CHAT_PREFIX)?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    READY_CHECK_SECONDS.contains(&seconds).then_some(seconds)
+}
+
+pub(crate) fn parse_scheduled_start(message: &str) -> Option<i64> {
+    let start_ms = message
+        .strip_prefix(SCHEDULED_START_CHAT_PREFIX)?
+        .trim()
+        .parse::<i64>()
+        .ok()?;
+    (start_ms > 0).then_some(start_ms)
+}
+
+/// Whether `start_ms` lies in the window a sender may schedule, both in server milliseconds
+pub(crate) fn scheduled_start_in_window(start_ms: i64, server_now_ms: i64) -> bool {
+    let ahead_ms = start_ms.saturating_sub(server_now_ms);
+    ahead_ms > 0 && ahead_ms <= MAX_SCHEDULE_AHEAD_HOURS * 3_600_000
+}
+
+pub(crate) fn parse_bookmark(message: &str) -> Option<(f64, String)> {
+    let (position, label) = message
+        .strip_prefix(BOOKMARK_CHAT_PREFIX)?
+        .trim()
+        .split_once(' ')?;
+    let position = position.parse::<f64>().ok()?;
+    let label = label.trim();
+    (position.is_finite() && position >= 0.0 && !label.is_empty())
+        .then(|| (position, label.to_string()))
+}
+
+pub(crate) fn parse_operator_offer(message: &str) -> Option<String> {
+    let grantee = message.strip_prefix(OPERATOR_OFFER_CHAT_PREFIX)?.trim();
+    (!grantee.is_empty()).then(|| grantee.to_string())
+}
+
+pub(crate) fn parse_operator_accept(message: &str) -> Option<String> {
+    let key = message.strip_prefix(OPERATOR_ACCEPT_CHAT_PREFIX)?.trim();
+    (!key.is_empty() && !key.contains(' ')).then(|| key.to_string())
+}
+
+pub(crate) fn parse_operator_key(message: &str) -> Option<(String, String)> {
+    let (key, sealed) = message
+        .strip_prefix(OPERATOR_KEY_CHAT_PREFIX)?
+        .trim()
+        .split_once(' ')?;
+    let sealed = sealed.trim();
+    (!key.is_empty() && !sealed.is_empty()).then(|| (key.to_string(), sealed.to_string()))
+}
+
+pub(crate) fn parse_operators_only(message: &str) -> Option<Vec<String>> {
+    let operators = message.strip_prefix(OPERATORS_ONLY_CHAT_PREFIX)?;
+    if !operators.is_empty() && !operators.starts_with(' ') {
+        return None;
+    }
+    Some(operators.split_whitespace().map(str::to_string).collect())
+}
+
+pub(crate) fn parse_suggestion(message: &str) -> Option<Suggestion> {
+    Suggestion::parse(message.strip_prefix(SUGGESTION_CHAT_PREFIX)?)
+}
+
+pub(crate) fn parse_vote(message: &str) -> Option<VoteSubject> {
+    VoteSubject::parse(message.strip_prefix(VOTE_CHAT_PREFIX)?)
+}
+
+/// Whether a chat message addresses `username`, as "@name" or the bare name as a word
+pub fn mentions_user(message: &str, username: &str) -> bool {
+    let username = username.trim().to_lowercase();
+    if username.is_empty() {
+        return false;
+    }
+    let message = message.to_lowercase();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    message.match_indices(&username).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + username.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Settle the features of a server from its version and the feature list in its Hello
+///
+/// Version checks are only the fallback: an announced feature list wins either way.
+pub fn resolve_server_features(
+    server_version: &str,
+    feature_list: Option<&serde_json::Value>,
+) -> (ServerFeatures, Vec<FeatureDowngrade>) {
+    let map = feature_list.and_then(|value| value.as_object());
+    let announced = |key: &str| map.and_then(|map| map.get(key)).and_then(|v| v.as_bool());
+    let length = |key: &str, fallback: usize| {
+        map.and_then(|map| map.get(key))
+            .and_then(|v| v.as_u64())
+            .map_or(fallback, |value| value as usize)
+    };
+
+    let mut downgrades = Vec::new();
+    let mut resolve = |key: &str, min_version: Option<&str>| {
+        let by_version = min_version.is_some_and(|min| version_meets_min(server_version, min));
+        let enabled = announced(key).unwrap_or(by_version);
+        if !enabled {
+            let (reason, min_version) = match (announced(key), min_version) {
+                (Some(false), _) => (DowngradeReason::DisabledByServer, None),
+                (_, Some(min)) => (DowngradeReason::ServerTooOld, Some(min.to_string())),
+                // Nothing to explain for optional features the server never mentioned
+                (_, None) => return enabled,
+            };
+            downgrades.push(FeatureDowngrade {
+                feature: key.to_string(),
+                reason,
+                min_version,
+            });
+        }
+        enabled
+    };
+
+    let features = ServerFeatures {
+        feature_list: resolve("featureList", Some(FEATURE_LIST_MIN_VERSION)),
+        shared_playlists: resolve("sharedPlaylists", Some(SHARED_PLAYLIST_MIN_VERSION)),
+        chat: resolve("chat", Some(CHAT_MIN_VERSION)),
+        readiness: resolve("readiness", Some(USER_READY_MIN_VERSION)),
+        managed_rooms: resolve("managedRooms", Some(CONTROLLED_ROOMS_MIN_VERSION)),
+        persistent_rooms: resolve("persistentRooms", None),
+        set_others_readiness: resolve("setOthersReadiness", Some(SET_OTHERS_READINESS_MIN_VERSION)),
+        max_chat_message_length: Some(length(
+            "maxChatMessageLength",
+            FALLBACK_MAX_CHAT_MESSAGE_LENGTH,
+        )),
+        max_username_length: Some(length("maxUsernameLength", FALLBACK_MAX_USERNAME_LENGTH)),
+        max_room_name_length: Some(length("maxRoomNameLength", FALLBACK_MAX_ROOM_NAME_LENGTH)),
+        max_filename_length: Some(length("maxFilenameLength", FALLBACK_MAX_FILENAME_LENGTH)),
+    };
+    (features, downgrades)
+}
+
+/// Replace the known users with a List response from the server
+pub fn apply_user_list(ctx: &impl ClientContext, users_by_room: ListResponse) {
+    let client_state = ctx.client_state();
+    client_state.clear_users();
+    let mut empty_rooms = Vec::new();
+    for (room_name, room_users) in users_by_room {
+        if room_users
+            .keys()
+            .all(|username| is_placeholder_username(username))
+        {
+            empty_rooms.push(room_name.clone());
+        }
+        for (username, user_info) in room_users {
+            if is_placeholder_username(&username) {
+                tracing::debug!(
+                    "Ignoring placeholder user entry from List in room '{}'",
+                    room_name
+                );
+                continue;
+            }
+            let file = user_info.file.as_ref().and_then(|f| f.name.clone());
+            let file_size = user_info.file.as_ref().and_then(|f| f.size.clone());
+            let file_duration = user_info.file.as_ref().and_then(|f| f.duration);
+            client_state.add_user(User {
+                username,
+                room: room_name.clone(),
+                file,
+                file_size,
+                file_duration,
+                is_ready: user_info.is_ready,
+                is_controller: user_info.controller.unwrap_or(false),
+                utc_offset: utc_offset_from_features(user_info.features.as_ref()),
+            });
+        }
+    }
+    empty_rooms.sort();
+    client_state.set_empty_rooms(empty_rooms);
+}
+
+/// The UTC offset a user shares in their features, if it is a real one
+///
+/// Clients that do not share one, including other Syncplay clients, simply have none.
+pub fn utc_offset_from_features(features: Option<&serde_json::Value>) -> Option<i32> {
+    let minutes = features?.get("utcOffset")?.as_i64()?;
+    // From UTC-12:00 to UTC+14:00
+    (-720..=840).contains(&minutes).then_some(minutes as i32)
+}
+
+/// Whether `username` may start things for the whole current room
+///
+/// Anyone may in rooms without operators; in managed rooms only operators may.
+fn may_control_room(ctx: &impl ClientContext, username: &str) -> bool {
+    let client_state = ctx.client_state();
+    let room = client_state.get_room();
+    !is_controlled_room(&room)
+        || client_state
+            .get_user(username)
+            .is_some_and(|user| user.room == room && user.is_controller)
+}
+
+/// Show an incoming chat message, filtering countdowns and ignored users
+pub fn handle_chat(ctx: &impl ClientContext, chat: ChatMessage) -> ChatOutcome {
+    let config = ctx.config();
+    if !ctx.chat_supported() || !config.user.chat_output_enabled {
+        return ChatOutcome::Dropped;
+    }
+    let (username, message) = match chat {
+        ChatMessage::Entry { username, message } => (username, message),
+        ChatMessage::Text(message) => {
+            // Lines without a sender come from the server itself
+            let server = ctx.server_address().unwrap_or_default();
+            ctx.chat_history().add_server_message(message.clone());
+            let event = ServerMotdEvent::new(server, ServerMessageKind::Announcement, &message);
+            ctx.emit("server-motd", serde_json::json!(event));
+            return ChatOutcome::Announcement;
+        }
+    };
+    if let Some(seconds) = parse_unpause_countdown(&message) {
+        let current_username = ctx.client_state().get_username();
+        if username == current_username
+            || config.user.is_user_ignored(&username)
+            || !may_control_room(ctx, &username)
+        {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::UnpauseCountdown {
+            actor: username,
+            seconds,
+        };
+    }
+    if let Some(seconds) = parse_ready_check(&message) {
+        if username == ctx.client_state().get_username()
+            || config.user.is_user_ignored(&username)
+            || !may_control_room(ctx, &username)
+        {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::ReadyCheck {
+            actor: username,
+            seconds,
+        };
+    }
+    if let Some(start_ms) = parse_scheduled_start(&message) {
+        if username == ctx.client_state().get_username()
+            || config.user.is_user_ignored(&username)
+            || !may_control_room(ctx, &username)
+        {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::ScheduledStart {
+            actor: username,
+            start_ms,
+        };
+    }
+    if let Some((position, label)) = parse_bookmark(&message) {
+        if username == ctx.client_state().get_username() || config.user.is_user_ignored(&username) {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::Bookmark {
+            actor: username,
+            position,
+            label,
+        };
+    }
+    let current_username = ctx.client_state().get_username();
+    if let Some(grantee) = parse_operator_offer(&message) {
+        if username == current_username
+            || grantee != current_username
+            || config.user.is_user_ignored(&username)
+        {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::OperatorOffer { actor: username };
+    }
+    if let Some(key) = parse_operator_accept(&message) {
+        if username == current_username {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::OperatorAccept {
+            actor: username,
+            key,
+        };
+    }
+    if let Some((key, sealed)) = parse_operator_key(&message) {
+        if username == current_username {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::OperatorKey {
+            actor: username,
+            key,
+            sealed,
+        };
+    }
+    if let Some(operators) = parse_operators_only(&message) {
+        if username == current_username || config.user.is_user_ignored(&username) {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::OperatorsOnly {
+            actor: username,
+            operators,
+        };
+    }
+    if let Some(suggestion) = parse_suggestion(&message) {
+        if username == current_username || config.user.is_user_ignored(&username) {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::Suggestion {
+            actor: username,
+            suggestion,
+        };
+    }
+    // Our own votes come back from the server and count like everyone else's
+    if let Some(subject) = parse_vote(&message).filter(|_| config.user.room_votes) {
+        if config.user.is_user_ignored(&username) {
+            return ChatOutcome::Dropped;
+        }
+        return ChatOutcome::Vote {
+            actor: username,
+            subject,
+        };
+    }
+    if config.user.is_user_ignored(&username) {
+        tracing::debug!("Dropping chat message from ignored user");
+        return ChatOutcome::Dropped;
+    }
+    ctx.chat_history()
+        .add_user_message(username.clone(), message.clone());
+    let mentioned = username != ctx.client_state().get_username()
+        && mentions_user(&message, &ctx.client_state().get_username());
+    let player = ctx
+        .player()
+        .filter(|player| player.capabilities().osd && !ctx.do_not_disturb());
+    if let Some(player) = player {
+        let style = OsdStyle::from_config(&config.user)
+            .with_duration(u64::from(config.user.chat_timeout) * 1000);
+        let _ = player.show_chat_message(Some(&username), &message, &style);
+    }
+    ctx.emit(
+        "chat-message-received",
+        serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "username": username,
+            "message": message,
+            "messageType": "normal",
+            "mentioned": mentioned,
+        }),
+    );
+    ChatOutcome::Delivered { mentioned }
+}
+
+/// How a Set user update changed the known users
+#[derive(Debug, Clone)]
+pub enum UserChange {
+    /// The user left the server; None if they were never listed
+    Left { user: Option<User> },
+    /// The user was added or updated; `file_updated` is set when the update named a file
+    Updated {
+        old: Option<User>,
+        user: User,
+        file_updated: bool,
+    },
+}
+
+fn tr(ctx: &impl ClientContext, key: &str, args: &[&dyn std::fmt::Display]) -> String {
+    crate::i18n::tr(ctx.config().user.language, key, args)
+}
+
+/// Add a system line to the chat
+pub fn system_message(ctx: &impl ClientContext, message: &str) {
+    ctx.chat_history().add_system_message(message.to_string());
+    ctx.emit(
+        "chat-message-received",
+        serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "username": null,
+            "message": message,
+            "messageType": "system",
+        }),
+    );
+}
+
+/// Apply one message from the server
+pub async fn handle_server_message(ctx: &impl ClientContext, message: ProtocolMessage) {
+    match message {
+        ProtocolMessage::Hello { Hello } => {
+            tracing::info!("Received hello message: {:?}", Hello);
+            ctx.client_state()
+                .set_server_version(Hello.realversion.clone());
+            ctx.hello_accepted(&Hello);
+            system_message(ctx, &tr(ctx, "hello", &[&Hello.username]));
+            if let Some(motd) = Hello.motd.filter(|motd| !motd.trim().is_empty()) {
+                ctx.server_motd(&motd);
+            }
+            system_message(ctx, &tr(ctx, "connected", &[]));
+            if let Err(e) = ctx.send(ProtocolMessage::List { List: None }) {
+                tracing::warn!("Failed to request user list: {}", e);
+            }
+            ctx.reidentify_as_controller();
+            ctx.announce_file();
+        }
+        ProtocolMessage::List { List } => {
+            tracing::info!("Received user list: {:?}", List);
+            if let Some(users_by_room) = List {
+                apply_user_list(ctx, users_by_room);
+                ctx.user_list_applied();
+            }
+        }
+        ProtocolMessage::Chat { Chat } => {
+            tracing::info!("Received chat message: {:?}", Chat);
+            let entry = match &Chat {
+                ChatMessage::Entry { username, message } => {
+                    Some((username.clone(), message.clone()))
+                }
+                ChatMessage::Text(_) => None,
+            };
+            let outcome = handle_chat(ctx, Chat);
+            ctx.chat_handled(outcome, entry);
+        }
+        ProtocolMessage::State { State } => handle_state_message(ctx, State).await,
+        ProtocolMessage::Error { Error } => {
+            tracing::error!("Received error from server: {:?}", Error);
+            ctx.server_error(&Error.message);
+        }
+        ProtocolMessage::Set { Set } => {
+            tracing::info!("Received set message: {:?}", Set);
+            handle_set_message(ctx, *Set).await;
+        }
+        ProtocolMessage::TLS { TLS } => {
+            tracing::info!("Received TLS message: {:?}", TLS);
+            ctx.tls_answer(TLS).await;
+        }
+    }
+}
+
+/// Follow the room playstate of a State message and answer it with ours
+pub async fn handle_state_message(ctx: &impl ClientContext, state_msg: StateMessage) {
+    if state_msg.playstate.is_some() || state_msg.ignoring_on_the_fly.is_some() {
+        tracing::info!(
+            "Received state update: playstate={:?}, ignoring_on_the_fly={:?}",
+            state_msg.playstate.as_ref(),
+            state_msg.ignoring_on_the_fly.as_ref()
+        );
+    }
+    if let Some(ignoring) = state_msg.ignoring_on_the_fly.clone() {
+        ctx.update_ignoring(ignoring);
+    }
+    let client_ignore_active = ctx.client_ignore_active().await;
+    let message_age = state_msg
+        .ping
+        .as_ref()
+        .map_or(0.0, |ping| ctx.ping_received(ping));
+    if let Some(playstate) = state_msg.playstate {
+        if !client_ignore_active {
+            ctx.apply_playstate(playstate, message_age).await;
+        }
+    }
+    let latency_calculation = state_msg
+        .ping
+        .as_ref()
+        .and_then(|ping| ping.latency_calculation);
+    ctx.reply_to_state(latency_calculation).await;
+}
+
+/// Apply a Set message: room, file, users, readiness, room control and playlist
+pub async fn handle_set_message(ctx: &impl ClientContext, set_msg: SetMessage) {
+    let client_state = ctx.client_state();
+    let has_index_update = set_msg.playlist_index.is_some();
+    if let Some(room) = set_msg.room {
+        client_state.set_room(room.name);
+        ctx.room_changed();
+        ctx.reidentify_as_controller();
+    }
+
+    if let Some(file) = set_msg.file {
+        if let Some(name) = file.name {
+            client_state.set_file(Some(name.clone()));
+            client_state.set_file_size(file.size.clone());
+            client_state.set_file_duration(file.duration);
+            if let Err(e) = ctx.load_file(&name).await {
+                tracing::warn!("Failed to load file from set: {}", e);
+            }
+        }
+    }
+
+    let mut users_changed = false;
+    let mut left_in_room = false;
+    let mut controller_left = false;
+    if let Some(user_updates) = set_msg.user {
+        let room = client_state.get_room();
+        for (username, update) in user_updates {
+            let Some(change) = apply_user_update(ctx, &username, update) else {
+                continue;
+            };
+            if let UserChange::Left { user: Some(user) } = &change {
+                if user.room == room {
+                    left_in_room = true;
+                    controller_left |= user.is_controller;
+                }
+            }
+            ctx.user_changed(&username, &change);
+            users_changed = true;
+        }
+    }
+
+    if let Some(ready) = set_msg.ready {
+        match ready.username.clone() {
+            Some(username) if is_placeholder_username(&username) => {
+                tracing::debug!("Ready update contains placeholder username, ignoring");
+            }
+            Some(username) => {
+                apply_ready_state(ctx, username, ready);
+                users_changed = true;
+            }
+            None => tracing::debug!("Ready state missing username, ignoring"),
+        }
+    }
+
+    if let Some(controller_auth) = set_msg.controller_auth {
+        ctx.controller_auth(controller_auth);
+    }
+
+    if let Some(new_room) = set_msg.new_controlled_room {
+        ctx.new_controlled_room(new_room).await;
+    }
+
+    if users_changed {
+        ctx.users_changed();
+    }
+
+    let config = ctx.config();
+    if left_in_room {
+        let remaining = client_state
+            .get_users_in_room(&client_state.get_room())
+            .iter()
+            .filter(|user| !is_placeholder_username(&user.username))
+            .count();
+        if config.user.pauses_on_leave(controller_left, remaining) {
+            ctx.pause_for_leave().await;
+        }
+    }
+
+    if config.user.shared_playlist_enabled && ctx.shared_playlists_supported() {
+        let mut emit_playlist = false;
+        if let Some(change) = set_msg.playlist_change {
+            emit_playlist = apply_playlist_change(ctx, change, has_index_update).await;
+        }
+        if let Some(index_update) = set_msg.playlist_index {
+            if index_update.index.is_some() {
+                apply_playlist_index_update(ctx, index_update).await;
+                emit_playlist = false;
+            }
+        }
+        if emit_playlist {
+            ctx.playlist_changed();
+        }
+    }
+
+    ctx.evaluate_autoplay();
+}
+
+/// Merge a Set user update into the known users
+///
+/// Returns None when the update is for a placeholder entry and was ignored.
+pub fn apply_user_update(
+    ctx: &impl ClientContext,
+    username: &str,
+    update: UserUpdate,
+) -> Option<UserChange> {
+    if is_placeholder_username(username) {
+        tracing::debug!("User update contains placeholder username, ignoring");
+        return None;
+    }
+    let client_state = ctx.client_state();
+    let old = client_state.get_user(username);
+    if update
+        .event
+        .as_ref()
+        .and_then(|event| event.left)
+        .unwrap_or(false)
+    {
+        client_state.remove_user(username);
+        return Some(UserChange::Left { user: old });
+    }
+
+    let mut user = old.clone().unwrap_or_else(|| User {
+        username: username.to_string(),
+        room: client_state.get_room(),
+        file: None,
+        file_size: None,
+        file_duration: None,
+        is_ready: None,
+        is_controller: false,
+        utc_offset: None,
+    });
+    if let Some(room) = update.room {
+        user.room = room.name;
+    }
+    let file_updated = update.file.is_some();
+    if let Some(file) = update.file {
+        user.file = file.name;
+        user.file_size = file.size;
+        user.file_duration = file.duration;
+    }
+    if let Some(is_ready) = update.is_ready {
+        user.is_ready = Some(is_ready);
+    }
+    if let Some(controller) = update.controller {
+        user.is_controller = controller;
+    }
+    if let Some(features) = update.features.as_ref() {
+        user.utc_offset = utc_offset_from_features(Some(features));
+    }
+    client_state.add_user(user.clone());
+    Some(UserChange::Updated {
+        old,
+        user,
+        file_updated,
+    })
+}
+
+fn apply_ready_state(ctx: &impl ClientContext, username: String, ready: ReadyState) {
+    let client_state = ctx.client_state();
+    let known = client_state.get_user(&username);
+    let is_ready = ready
+        .is_ready
+        .or_else(|| known.as_ref().and_then(|user| user.is_ready));
+    let mut user = known.unwrap_or_else(|| User {
+        username: username.clone(),
+        room: client_state.get_room(),
+        file: None,
+        file_size: None,
+        file_duration: None,
+        is_ready: None,
+        is_controller: false,
+        utc_offset: None,
+    });
+    user.is_ready = is_ready;
+    let room = user.room.clone();
+    client_state.add_user(user);
+
+    if let Some(value) = ready.is_ready {
+        if username == client_state.get_username() {
+            client_state.set_ready(value);
+        }
+        ctx.ready_changed(&username, room, value, ready.set_by.clone());
+    }
+
+    if let Some(set_by) = ready.set_by {
+        let message = if ready.is_ready.unwrap_or(false) {
+            tr(ctx, "user-set-ready-by", &[&username, &set_by])
+        } else {
+            tr(ctx, "user-set-not-ready-by", &[&username, &set_by])
+        };
+        system_message(ctx, &message);
+    }
+}
+
+/// Take over a playlist change, returning whether the playlist still has to be announced
+async fn apply_playlist_change(
+    ctx: &impl ClientContext,
+    change: PlaylistChange,
+    has_index_update: bool,
+) -> bool {
+    let playlist = ctx.playlist();
+    let room = ctx.client_state().get_room();
+    let cleared = change.files.is_empty() && change.user.is_none();
+    let mut should_restore = ctx.take_playlist_restore()
+        && cleared
+        && !playlist.get_item_filenames().is_empty()
+        && !playlist.playlist_buffer_is_from_old_room(&room);
+    let mut emit_playlist = false;
+    if !should_restore && cleared {
+        // The server forgot the playlist of a room we marked persistent
+        if let Some((items, current_index)) = ctx.remembered_playlist(&room) {
+            tracing::info!("Restoring remembered playlist of persistent room {}", room);
+            playlist.set_items_with_index(items, current_index);
+            emit_playlist = true;
+            should_restore = true;
+        }
+    }
+
+    if should_restore {
+        let restore_message = set_only(SetMessage {
+            playlist_change: Some(PlaylistChange {
+                user: None,
+                files: playlist.get_item_filenames(),
+            }),
+            ..empty_set()
+        });
+        if let Err(e) = ctx.send(restore_message) {
+            tracing::warn!("Failed to restore playlist: {}", e);
+        }
+        if let Some(index) = playlist.get_current_index() {
+            let index_message = set_only(SetMessage {
+                playlist_index: Some(PlaylistIndexUpdate {
+                    user: None,
+                    index: Some(index),
+                }),
+                ..empty_set()
+            });
+            if let Err(e) = ctx.send(index_message) {
+                tracing::warn!("Failed to restore playlist index: {}", e);
+            }
+        }
+        return emit_playlist;
+    }
+
+    playlist.update_previous_playlist(&change.files, &room);
+    let next_index = match playlist.get_current_index() {
+        Some(index) if index < change.files.len() => Some(index),
+        _ if change.files.is_empty() => None,
+        _ => Some(0),
+    };
+    playlist.set_items_with_index(change.files, next_index);
+    if let Some(user) = change.user {
+        let message = tr(ctx, "playlist-updated", &[&user]);
+        system_message(ctx, &message);
+        ctx.show_osd(&message);
+    }
+    if !has_index_update && ctx.client_state().get_file().is_none() {
+        if let Some(index) = playlist.get_current_index() {
+            if let Err(e) = ctx.apply_playlist_index(index, false).await {
+                tracing::warn!("Failed to load playlist after sync: {}", e);
+            }
+        }
+    }
+    true
+}
+
+async fn apply_playlist_index_update(ctx: &impl ClientContext, update: PlaylistIndexUpdate) {
+    let Some(index) = update.index else {
+        return;
+    };
+    let reset_position = ctx.playlist_index_seen().await;
+    let playlist = ctx.playlist();
+    let client_state = ctx.client_state();
+    // Our own selection of the file we already play needs no reload
+    let playing = update
+        .user
+        .as_ref()
+        .filter(|user| **user == client_state.get_username())
+        .and_then(|_| playlist.get_item_filenames().get(index).cloned())
+        .filter(|filename| same_filename(client_state.get_file().as_deref(), Some(filename)));
+    if let Some(filename) = playing {
+        playlist.set_current_index(index);
+        playlist.set_queued_index_filename(Some(filename));
+        ctx.playlist_changed();
+    } else if let Err(e) = ctx.apply_playlist_index(index, reset_position).await {
+        tracing::warn!("Failed to apply playlist index: {}", e);
+    }
+    if let Some(user) = update.user {
+        let message = tr(ctx, "playlist-selection-changed", &[&user]);
+        system_message(ctx, &message);
+        ctx.show_osd(&message);
+    }
+}
+
+fn set_only(set: SetMessage) -> ProtocolMessage {
+    ProtocolMessage::Set { Set: Box::new(set) }
+}
+
+fn empty_set() -> SetMessage {
+    SetMessage {
+        room: None,
+        file: None,
+        user: None,
+        ready: None,
+        playlist_index: None,
+        playlist_change: None,
+        controller_auth: None,
+        new_controlled_room: None,
+        features: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PauseOnLeave;
+    use crate::network::messages::UserInfo;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    struct FakeContext {
+        client_state: Arc<ClientState>,
+        chat: Arc<ChatManager>,
+        playlist: Arc<Playlist>,
+        config: SyncplayConfig,
+        events: Mutex<Vec<(String, serde_json::Value)>>,
+        /// Messages sent to the server
+        sent: Mutex<Vec<ProtocolMessage>>,
+        /// Client effects the handlers asked for, in order
+        calls: Mutex<Vec<String>>,
+        client_ignore: Mutex<u32>,
+    }
+
+    impl FakeContext {
+        fn new() -> Self {
+            let client_state = ClientState::new();
+            client_state.set_username("me".to_string());
+            Self {
+                client_state,
+                chat: ChatManager::new(),
+                playlist: Playlist::new(),
+                config: SyncplayConfig::default(),
+                events: Mutex::new(Vec::new()),
+                sent: Mutex::new(Vec::new()),
+                calls: Mutex::new(Vec::new()),
+                client_ignore: Mutex::new(0),
+            }
+        }
+
+        fn call(&self, call: String) {
+            self.calls.lock().push(call);
+        }
+
+        fn take_calls(&self) -> Vec<String> {
+            std::mem::take(&mut *self.calls.lock())
+        }
+    }
+
+    #[async_trait]
+    impl ClientContext for FakeContext {
+        fn client_state(&self) -> &ClientState {
+            &self.client_state
+        }
+
+        fn chat_history(&self) -> &ChatManager {
+            &self.chat
+        }
+
+        fn playlist(&self) -> &Playlist {
+            &self.playlist
+        }
+
+        fn config(&self) -> SyncplayConfig {
+            self.config.clone()
+        }
+
+        fn chat_supported(&self) -> bool {
+            true
+        }
+
+        fn shared_playlists_supported(&self) -> bool {
+            true
+        }
+
+        fn server_address(&self) -> Option<String> {
+            Some("localhost:8999".to_string())
+        }
+
+        fn player(&self) -> Option<Arc<dyn PlayerBackend>> {
+            None
+        }
+
+        fn do_not_disturb(&self) -> bool {
+            false
+        }
+
+        fn emit(&self, event: &str, payload: serde_json::Value) {
+            self.events.lock().push((event.to_string(), payload));
+        }
+
+        fn send(&self, message: ProtocolMessage) -> anyhow::Result<()> {
+            self.sent.lock().push(message);
+            Ok(())
+        }
+
+        fn hello_accepted(&self, hello: &HelloMessage) {
+            self.call(format!("hello_accepted {}", hello.realversion));
+        }
+
+        fn server_motd(&self, motd: &str) {
+            self.call(format!("server_motd {}", motd));
+        }
+
+        fn server_error(&self, message: &str) {
+            self.call(format!("server_error {}", message));
+        }
+
+        async fn tls_answer(&self, _tls: TLSMessage) {
+            self.call("tls_answer".to_string());
+        }
+
+        fn reidentify_as_controller(&self) {
+            self.call("reidentify_as_controller".to_string());
+        }
+
+        fn announce_file(&self) {
+            self.call("announce_file".to_string());
+        }
+
+        fn user_list_applied(&self) {
+            self.call("user_list_applied".to_string());
+        }
+
+        fn chat_handled(&self, outcome: ChatOutcome, _entry: Option<(String, String)>) {
+            self.call(format!("chat_handled {:?}", outcome));
+        }
+
+        fn update_ignoring(&self, ignoring: IgnoringInfo) {
+            if let Some(client) = ignoring.client {
+                *self.client_ignore.lock() = client;
+            }
+        }
+
+        async fn client_ignore_active(&self) -> bool {
+            *self.client_ignore.lock() != 0
+        }
+
+        fn ping_received(&self, _ping: &PingInfo) -> f64 {
+            0.25
+        }
+
+        async fn apply_playstate(&self, playstate: PlayState, message_age: f64) {
+            self.call(format!(
+                "apply_playstate {} {} {}",
+                playstate.position, playstate.paused, message_age
+            ));
+        }
+
+        async fn reply_to_state(&self, latency_calculation: Option<f64>) {
+            let ping = PingInfo {
+                latency_calculation,
+                client_latency_calculation: None,
+                client_rtt: None,
+                server_rtt: None,
+            };
+            self.sent.lock().push(ProtocolMessage::State {
+                State: StateMessage {
+                    playstate: None,
+                    ping: Some(ping),
+                    ignoring_on_the_fly: None,
+                },
+            });
+        }
+
+        fn room_changed(&self) {
+            self.call("room_changed".to_string());
+        }
+
+        async fn load_file(&self, name: &str) -> Result<(), String> {
+            self.call(format!("load_file {}", name));
+            Ok(())
+        }
+
+        fn user_changed(&self, username: &str, change: &UserChange) {
+            let kind = match change {
+                UserChange::Left { .. } => "left",
+                UserChange::Updated { old: None, .. } => "joined",
+                UserChange::Updated { .. } => "updated",
+            };
+            self.call(format!("user_changed {} {}", username, kind));
+        }
+
+        fn ready_changed(&self, username: &str, room: String, ready: bool, set_by: Option<String>) {
+            self.call(format!(
+                "ready_changed {} {} {} {:?}",
+                username, room, ready, set_by
+            ));
+        }
+
+        fn controller_auth(&self, _auth: ControllerAuth) {
+            self.call("controller_auth".to_string());
+        }
+
+        async fn new_controlled_room(&self, _room: NewControlledRoom) {
+            self.call("new_controlled_room".to_string());
+        }
+
+        fn users_changed(&self) {
+            self.call("users_changed".to_string());
+        }
+
+        async fn pause_for_leave(&self) {
+            self.call("pause_for_leave".to_string());
+        }
+
+        fn take_playlist_restore(&self) -> bool {
+            false
+        }
+
+        fn remembered_playlist(&self, _room: &str) -> Option<(Vec<String>, Option<usize>)> {
+            None
+        }
+
+        fn playlist_changed(&self) {
+            self.call("playlist_changed".to_string());
+        }
+
+        async fn playlist_index_seen(&self) -> bool {
+            false
+        }
+
+        async fn apply_playlist_index(
+            &self,
+            index: usize,
+            reset_position: bool,
+        ) -> Result<(), String> {
+            self.call(format!("apply_playlist_index {} {}", index, reset_position));
+            self.playlist.set_current_index(index);
+            Ok(())
+        }
+
+        fn show_osd(&self, message: &str) {
+            self.call(format!("show_osd {}", message));
+        }
+
+        fn evaluate_autoplay(&self) {
+            self.call("evaluate_autoplay".to_string());
+        }
+    }
+
+    fn entry(username: &str, message: &str) -> ChatMessage {
+        ChatMessage::Entry {
+            username: username.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_handle_chat_emits_message() {
+        let ctx = FakeContext::new();
+        assert_eq!(
+            handle_chat(&ctx, entry("alice", "hi")),
+            ChatOutcome::Delivered { mentioned: false }
+        );
+        let events = ctx.events.lock();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "chat-message-received");
+        assert_eq!(events[0].1["username"], "alice");
+        assert_eq!(events[0].1["mentioned"], false);
+        assert_eq!(ctx.chat.get_messages()[0].message, "hi");
+    }
+
+    #[test]
+    fn test_handle_chat_flags_mentions() {
+        let ctx = FakeContext::new();
+        assert_eq!(
+            handle_chat(&ctx, entry("alice", "@Me ready?")),
+            ChatOutcome::Delivered { mentioned: true }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("me", "me again")),
+            ChatOutcome::Delivered { mentioned: false }
+        );
+    }
+
+    #[test]
+    fn test_mentions_user() {
+        assert!(mentions_user("hey bob, pause", "Bob"));
+        assert!(mentions_user("@bob", "bob"));
+        assert!(!mentions_user("bobby is here", "bob"));
+        assert!(!mentions_user("anything", " "));
+    }
+
+    #[test]
+    fn test_handle_chat_countdown_and_ignore() {
+        let mut ctx = FakeContext::new();
+        ctx.config.user.ignored_users = vec!["bob".to_string()];
+
+        let countdown = format!("{}3", UNPAUSE_COUNTDOWN_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &countdown)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &countdown)),
+            ChatOutcome::UnpauseCountdown {
+                actor: "op".to_string(),
+                seconds: 3,
+            }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("me", &countdown)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(handle_chat(&ctx, entry("bob", "hi")), ChatOutcome::Dropped);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &format!("{}60", READY_CHECK_CHAT_PREFIX))),
+            ChatOutcome::ReadyCheck {
+                actor: "op".to_string(),
+                seconds: 60,
+            }
+        );
+        assert_eq!(
+            handle_chat(
+                &ctx,
+                entry("bob", &format!("{}60", READY_CHECK_CHAT_PREFIX))
+            ),
+            ChatOutcome::Dropped
+        );
+        assert!(parse_ready_check(&format!("{}5", READY_CHECK_CHAT_PREFIX)).is_none());
+        let schedule = format!("{}1760000000000", SCHEDULED_START_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &schedule)),
+            ChatOutcome::ScheduledStart {
+                actor: "op".to_string(),
+                start_ms: 1_760_000_000_000,
+            }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("me", &schedule)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &schedule)),
+            ChatOutcome::Dropped
+        );
+        let now_ms = 1_760_000_000_000;
+        assert!(scheduled_start_in_window(now_ms + 60_000, now_ms));
+        assert!(scheduled_start_in_window(now_ms + 24 * 3_600_000, now_ms));
+        assert!(!scheduled_start_in_window(now_ms, now_ms));
+        assert!(!scheduled_start_in_window(1, now_ms));
+        assert!(!scheduled_start_in_window(
+            now_ms + 24 * 3_600_000 + 1,
+            now_ms
+        ));
+        let bookmark = format!("{}90.5 OP end", BOOKMARK_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &bookmark)),
+            ChatOutcome::Bookmark {
+                actor: "op".to_string(),
+                position: 90.5,
+                label: "OP end".to_string(),
+            }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &bookmark)),
+            ChatOutcome::Dropped
+        );
+        assert!(parse_bookmark(&format!("{}-3 x", BOOKMARK_CHAT_PREFIX)).is_none());
+        assert!(ctx.events.lock().is_empty());
+    }
+
+    #[test]
+    fn test_handle_chat_room_commands_need_an_operator() {
+        let ctx = FakeContext::new();
+        let room = "+movies:0123456789ab";
+        ctx.client_state.set_room(room.to_string());
+        let user = |username: &str, is_controller| User {
+            username: username.to_string(),
+            room: room.to_string(),
+            file: None,
+            file_size: None,
+            file_duration: None,
+            is_ready: None,
+            is_controller,
+            utc_offset: None,
+        };
+        ctx.client_state.add_user(user("op", true));
+        ctx.client_state.add_user(user("bob", false));
+
+        let countdown = format!("{}3", UNPAUSE_COUNTDOWN_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &countdown)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &countdown)),
+            ChatOutcome::UnpauseCountdown {
+                actor: "op".to_string(),
+                seconds: 3,
+            }
+        );
+
+        let ready_check = format!("{}60", READY_CHECK_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &ready_check)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &ready_check)),
+            ChatOutcome::ReadyCheck {
+                actor: "op".to_string(),
+                seconds: 60,
+            }
+        );
+
+        let schedule = format!("{}1760000000000", SCHEDULED_START_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &schedule)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("stranger", &schedule)),
+            ChatOutcome::Dropped
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &schedule)),
+            ChatOutcome::ScheduledStart {
+                actor: "op".to_string(),
+                start_ms: 1_760_000_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_handle_chat_operator_handoff() {
+        let ctx = FakeContext::new();
+        let offer_to_me = format!("{}me", OPERATOR_OFFER_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &offer_to_me)),
+            ChatOutcome::OperatorOffer {
+                actor: "op".to_string(),
+            }
+        );
+        let offer_to_bob = format!("{}bob", OPERATOR_OFFER_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &offer_to_bob)),
+            ChatOutcome::Dropped
+        );
+        let accept = format!("{}abcd", OPERATOR_ACCEPT_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &accept)),
+            ChatOutcome::OperatorAccept {
+                actor: "bob".to_string(),
+                key: "abcd".to_string(),
+            }
+        );
+        let key = format!("{}abcd 0123", OPERATOR_KEY_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &key)),
+            ChatOutcome::OperatorKey {
+                actor: "op".to_string(),
+                key: "abcd".to_string(),
+                sealed: "0123".to_string(),
+            }
+        );
+        assert_eq!(handle_chat(&ctx, entry("me", &key)), ChatOutcome::Dropped);
+        assert!(parse_operator_key(&format!("{}abcd", OPERATOR_KEY_CHAT_PREFIX)).is_none());
+        assert!(ctx.chat.get_messages().is_empty());
+    }
+
+    #[test]
+    fn test_handle_chat_operators_only_and_suggestions() {
+        let ctx = FakeContext::new();
+        let rule = format!("{} op bob", OPERATORS_ONLY_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("op", &rule)),
+            ChatOutcome::OperatorsOnly {
+                actor: "op".to_string(),
+                operators: vec!["op".to_string(), "bob".to_string()],
+            }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("op", OPERATORS_ONLY_CHAT_PREFIX)),
+            ChatOutcome::OperatorsOnly {
+                actor: "op".to_string(),
+                operators: Vec::new(),
+            }
+        );
+        assert_eq!(handle_chat(&ctx, entry("me", &rule)), ChatOutcome::Dropped);
+        assert!(parse_operators_only("[operators-only]x").is_none());
+        let suggestion = format!("{}seek 90.0", SUGGESTION_CHAT_PREFIX);
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", &suggestion)),
+            ChatOutcome::Suggestion {
+                actor: "bob".to_string(),
+                suggestion: Suggestion::Seek(90.0),
+            }
+        );
+        assert!(ctx.chat.get_messages().is_empty());
+    }
+
+    #[test]
+    fn test_handle_chat_votes() {
+        let mut ctx = FakeContext::new();
+        assert_eq!(
+            handle_chat(&ctx, entry("me", "!vote seek 10:00")),
+            ChatOutcome::Vote {
+                actor: "me".to_string(),
+                subject: VoteSubject::Seek(600.0),
+            }
+        );
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", "!vote maybe")),
+            ChatOutcome::Delivered { mentioned: false }
+        );
+        ctx.config.user.room_votes = false;
+        assert_eq!(
+            handle_chat(&ctx, entry("bob", "!vote pause")),
+            ChatOutcome::Delivered { mentioned: false }
+        );
+    }
+
+    #[test]
+    fn test_handle_chat_routes_server_text_to_motd() {
+        let ctx = FakeContext::new();
+        let text = ChatMessage::Text("Maintenance at https://status.example.com".to_string());
+        assert_eq!(handle_chat(&ctx, text), ChatOutcome::Announcement);
+        let events = ctx.events.lock();
+        assert_eq!(events[0].0, "server-motd");
+        assert_eq!(events[0].1["kind"], "announcement");
+        assert_eq!(events[0].1["links"][0], "https://status.example.com");
+    }
+
+    #[test]
+    fn test_apply_user_list_skips_placeholders() {
+        let ctx = FakeContext::new();
+        let user = |ready| UserInfo {
+            file: None,
+            is_ready: ready,
+            controller: None,
+            features: None,
+        };
+        let room = HashMap::from([
+            ("alice".to_string(), user(Some(true))),
+            (" ".to_string(), user(None)),
+        ]);
+        apply_user_list(&ctx, HashMap::from([("lobby".to_string(), room)]));
+        let users = ctx.client_state.get_users();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "alice");
+        assert_eq!(users[0].room, "lobby");
+    }
+
+    #[test]
+    fn test_apply_user_list_records_empty_rooms() {
+        let ctx = FakeContext::new();
+        let alice = UserInfo {
+            file: None,
+            is_ready: None,
+            controller: None,
+            features: None,
+        };
+        apply_user_list(
+            &ctx,
+            HashMap::from([
+                (
+                    "lobby".to_string(),
+                    HashMap::from([("alice".to_string(), alice)]),
+                ),
+                ("movies".to_string(), HashMap::new()),
+                ("anime".to_string(), HashMap::new()),
+            ]),
+        );
+        assert_eq!(ctx.client_state.get_empty_rooms(), vec!["anime", "movies"]);
+    }
+
+    #[test]
+    fn test_apply_user_list_reads_shared_utc_offsets() {
+        let ctx = FakeContext::new();
+        let user = |features| UserInfo {
+            file: None,
+            is_ready: None,
+            controller: None,
+            features,
+        };
+        let room = HashMap::from([
+            (
+                "alice".to_string(),
+                user(Some(serde_json::json!({"chat": true, "utcOffset": 330}))),
+            ),
+            (
+                "bob".to_string(),
+                user(Some(serde_json::json!({"chat": true}))),
+            ),
+            ("carol".to_string(), user(None)),
+            (
+                "dave".to_string(),
+                user(Some(serde_json::json!({"utcOffset": 5000}))),
+            ),
+        ]);
+        apply_user_list(&ctx, HashMap::from([("lobby".to_string(), room)]));
+        let offset = |name: &str| ctx.client_state.get_user(name).unwrap().utc_offset;
+        assert_eq!(offset("alice"), Some(330));
+        assert_eq!(offset("bob"), None);
+        assert_eq!(offset("carol"), None);
+        assert_eq!(offset("dave"), None);
+    }
+
+    #[test]
+    fn test_resolve_server_features_explains_downgrades() {
+        let (features, downgrades) = resolve_server_features("1.4.1", None);
+        assert!(features.shared_playlists);
+        assert!(!features.chat);
+        assert!(!features.persistent_rooms);
+        let chat = downgrades.iter().find(|d| d.feature == "chat").unwrap();
+        assert_eq!(chat.reason, DowngradeReason::ServerTooOld);
+        assert_eq!(chat.min_version.as_deref(), Some(CHAT_MIN_VERSION));
+        assert!(downgrades.iter().all(|d| d.feature != "persistentRooms"));
+
+        let announced = serde_json::json!({
+            "chat": false,
+            "persistentRooms": true,
+            "maxChatMessageLength": 200,
+        });
+        let (features, downgrades) = resolve_server_features("1.7.3", Some(&announced));
+        assert!(!features.chat);
+        assert!(features.persistent_rooms);
+        assert_eq!(features.max_chat_message_length, Some(200));
+        assert_eq!(
+            downgrades,
+            vec![FeatureDowngrade {
+                feature: "chat".to_string(),
+                reason: DowngradeReason::DisabledByServer,
+                min_version: None,
+            }]
+        );
+    }
+
+    /// Play the server side of one wire message, returning the effects it asked for
+    async fn serve(ctx: &FakeContext, line: &str) -> Vec<String> {
+        let message: ProtocolMessage = serde_json::from_str(line).unwrap();
+        handle_server_message(ctx, message).await;
+        ctx.take_calls()
+    }
+
+    #[tokio::test]
+    async fn test_scripted_server_session() {
+        let mut ctx = FakeContext::new();
+        ctx.config.user.pause_on_leave_policy = PauseOnLeave::Anyone;
+        ctx.client_state.set_room("movies".to_string());
+        let tr = |key: &str, args: &[&dyn std::fmt::Display]| {
+            crate::i18n::tr(ctx.config.user.language, key, args)
+        };
+
+        let calls = serve(
+            &ctx,
+            r#"{"Hello":{"username":"me","room":{"name":"movies"},"version":"1.2.255","realversion":"1.7.3","motd":"Welcome"}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                "hello_accepted 1.7.3",
+                "server_motd Welcome",
+                "reidentify_as_controller",
+                "announce_file",
+            ]
+        );
+        assert_eq!(
+            ctx.client_state.get_server_version().as_deref(),
+            Some("1.7.3")
+        );
+        assert!(matches!(
+            ctx.sent.lock().as_slice(),
+            [ProtocolMessage::List { List: None }]
+        ));
+
+        let calls = serve(
+            &ctx,
+            r#"{"List":{"movies":{"me":{"position":0,"file":{},"controller":false,"isReady":false},"bob":{"position":0,"file":{"name":"film.mkv","duration":5400.0},"controller":false,"isReady":true}}}}"#,
+        )
+        .await;
+        assert_eq!(calls, ["user_list_applied"]);
+        assert_eq!(ctx.client_state.get_users_in_room("movies").len(), 2);
+
+        let calls = serve(
+            &ctx,
+            r#"{"Set":{"user":{"carol":{"room":{"name":"movies"},"event":{"joined":true}}}}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                "user_changed carol joined",
+                "users_changed",
+                "evaluate_autoplay"
+            ]
+        );
+
+        let calls = serve(
+            &ctx,
+            r#"{"Set":{"ready":{"username":"carol","isReady":true,"manuallyInitiated":true,"setBy":"bob"}}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                "ready_changed carol movies true Some(\"bob\")",
+                "users_changed",
+                "evaluate_autoplay",
+            ]
+        );
+        assert_eq!(
+            ctx.client_state.get_user("carol").unwrap().is_ready,
+            Some(true)
+        );
+        let announced = tr("user-set-ready-by", &[&"carol", &"bob"]);
+        assert!(ctx
+            .chat
+            .get_messages()
+            .iter()
+            .any(|message| message.message == announced));
+
+        let calls = serve(
+            &ctx,
+            r#"{"State":{"playstate":{"position":42.5,"paused":false,"setBy":"bob"},"ping":{"latencyCalculation":1700000000.5,"clientLatencyCalculation":1700000000.25,"serverRtt":0.1}}}"#,
+        )
+        .await;
+        assert_eq!(calls, ["apply_playstate 42.5 false 0.25"]);
+        match ctx.sent.lock().last() {
+            Some(ProtocolMessage::State { State }) => assert_eq!(
+                State
+                    .ping
+                    .as_ref()
+                    .and_then(|ping| ping.latency_calculation),
+                Some(1700000000.5)
+            ),
+            other => panic!("expected a State reply, got {:?}", other),
+        }
+
+        // The server still ignores our own change, so its playstate is stale
+        let calls = serve(
+            &ctx,
+            r#"{"State":{"playstate":{"position":10.0,"paused":true},"ignoringOnTheFly":{"client":1}}}"#,
+        )
+        .await;
+        assert!(calls.is_empty());
+        assert_eq!(ctx.sent.lock().len(), 3);
+
+        let calls = serve(
+            &ctx,
+            r#"{"Set":{"playlistChange":{"user":"bob","files":["a.mkv","b.mkv"]}}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                format!("show_osd {}", tr("playlist-updated", &[&"bob"])),
+                "apply_playlist_index 0 false".to_string(),
+                "playlist_changed".to_string(),
+                "evaluate_autoplay".to_string(),
+            ]
+        );
+        assert_eq!(ctx.playlist.get_item_filenames(), ["a.mkv", "b.mkv"]);
+
+        let calls = serve(
+            &ctx,
+            r#"{"Set":{"playlistIndex":{"user":"bob","index":1}}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                "apply_playlist_index 1 false".to_string(),
+                format!("show_osd {}", tr("playlist-selection-changed", &[&"bob"])),
+                "evaluate_autoplay".to_string(),
+            ]
+        );
+        assert_eq!(ctx.playlist.get_current_index(), Some(1));
+
+        let calls = serve(&ctx, r#"{"Chat":{"username":"bob","message":"ready?"}}"#).await;
+        assert_eq!(calls, ["chat_handled Delivered { mentioned: false }"]);
+
+        let calls = serve(
+            &ctx,
+            r#"{"Set":{"user":{"bob":{"room":{"name":"movies"},"event":{"left":true}}}}}"#,
+        )
+        .await;
+        assert_eq!(
+            calls,
+            [
+                "user_changed bob left",
+                "users_changed",
+                "pause_for_leave",
+                "evaluate_autoplay",
+            ]
+        );
+        assert!(ctx.client_state.get_user("bob").is_none());
+    }
+}
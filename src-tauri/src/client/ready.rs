@@ -1,2 +1,97 @@
 // Ready state management module
 // Handles user ready states for synchronized playback start
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Allowed answer window for a ready check
+pub const READY_CHECK_SECONDS: std::ops::RangeInclusive<u64> = 10..=300;
+pub const DEFAULT_READY_CHECK_SECONDS: u64 = 60;
+
+/// Ready check asked for by an operator, answered through readiness changes
+#[derive(Debug, Clone)]
+pub struct ReadyCheck {
+    pub initiator: String,
+    pub room: String,
+    pub started: Instant,
+    pub timeout: Duration,
+    responses: BTreeMap<String, bool>,
+}
+
+/// Payload of the `ready-check-summary` event
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyCheckSummary {
+    pub initiator: String,
+    pub room: String,
+    pub ready: Vec<String>,
+    pub not_ready: Vec<String>,
+    pub no_response: Vec<String>,
+}
+
+impl ReadyCheck {
+    pub fn new(initiator: impl Into<String>, room: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            initiator: initiator.into(),
+            room: room.into(),
+            started: Instant::now(),
+            timeout,
+            responses: BTreeMap::new(),
+        }
+    }
+
+    /// Remember the latest readiness a user reported during the check
+    pub fn record(&mut self, username: &str, ready: bool) {
+        self.responses.insert(username.to_string(), ready);
+    }
+
+    /// Sort room members by their answer
+    ///
+    /// Members who did not answer but were already ready count as ready.
+    pub fn summary<I>(&self, members: I) -> ReadyCheckSummary
+    where
+        I: IntoIterator<Item = (String, Option<bool>)>,
+    {
+        let mut summary = ReadyCheckSummary {
+            initiator: self.initiator.clone(),
+            room: self.room.clone(),
+            ready: Vec::new(),
+            not_ready: Vec::new(),
+            no_response: Vec::new(),
+        };
+        for (username, current) in members {
+            match self.responses.get(&username).copied().or(current) {
+                Some(true) => summary.ready.push(username),
+                _ if self.responses.contains_key(&username) => summary.not_ready.push(username),
+                _ => summary.no_response.push(username),
+            }
+        }
+        summary.ready.sort();
+        summary.not_ready.sort();
+        summary.no_response.sort();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready_check_summary() {
+        let mut check = ReadyCheck::new("op", "movies", Duration::from_secs(60));
+        check.record("bob", true);
+        check.record("carol", true);
+        check.record("carol", false);
+        let summary = check.summary(vec![
+            ("bob".to_string(), Some(true)),
+            ("carol".to_string(), Some(false)),
+            ("dave".to_string(), Some(true)),
+            ("erin".to_string(), None),
+        ]);
+        assert_eq!(summary.ready, vec!["bob", "dave"]);
+        assert_eq!(summary.not_ready, vec!["carol"]);
+        assert_eq!(summary.no_response, vec!["erin"]);
+    }
+}
@@ -0,0 +1,125 @@
+// Room control module
+// Operators-only pause and seek for rooms the server does not manage
+//
+// The server lets anyone in a normal room pause and seek, so the rule is a convention
+// between clients: it is announced in room chat, and every client that knows it turns
+// a local pause or seek of a non-operator back into a suggestion to the operators.
+
+/// Users allowed to pause and seek in `room`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorsOnlyRule {
+    pub room: String,
+    pub operators: Vec<String>,
+}
+
+impl OperatorsOnlyRule {
+    pub fn new(room: impl Into<String>, operators: Vec<String>) -> Self {
+        Self {
+            room: room.into(),
+            operators,
+        }
+    }
+
+    /// Whether `username` may pause and seek; names compare case-insensitively
+    pub fn allows(&self, username: &str) -> bool {
+        self.operators
+            .iter()
+            .any(|operator| operator.eq_ignore_ascii_case(username))
+    }
+}
+
+/// Trimmed, deduplicated operator names, with `username` added when any are given
+pub fn normalize_operators(operators: &[String], username: &str) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for operator in operators {
+        let operator = operator.trim();
+        if !operator.is_empty()
+            && !operator.contains(' ')
+            && !normalized
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(operator))
+        {
+            normalized.push(operator.to_string());
+        }
+    }
+    if !normalized.is_empty()
+        && !normalized
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(username))
+    {
+        normalized.insert(0, username.to_string());
+    }
+    normalized
+}
+
+/// What a non-operator asked the operators to do instead of doing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suggestion {
+    Pause,
+    Unpause,
+    /// Seek to the position, in seconds
+    Seek(f64),
+}
+
+impl Suggestion {
+    /// Chat form following the suggestion prefix
+    pub fn to_chat(self) -> String {
+        match self {
+            Self::Pause => "pause".to_string(),
+            Self::Unpause => "unpause".to_string(),
+            Self::Seek(position) => format!("seek {:.1}", position),
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().split_once(' ') {
+            None if text.trim() == "pause" => Some(Self::Pause),
+            None if text.trim() == "unpause" => Some(Self::Unpause),
+            Some(("seek", position)) => {
+                let position = position.trim().parse::<f64>().ok()?;
+                (position.is_finite() && position >= 0.0).then_some(Self::Seek(position))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_allows_operators_case_insensitively() {
+        let rule = OperatorsOnlyRule::new("movies", vec!["Alice".to_string()]);
+        assert!(rule.allows("alice"));
+        assert!(!rule.allows("bob"));
+    }
+
+    #[test]
+    fn test_normalize_operators_adds_the_announcer() {
+        let operators = vec![
+            " bob ".to_string(),
+            "Bob".to_string(),
+            String::new(),
+            "two words".to_string(),
+        ];
+        assert_eq!(
+            normalize_operators(&operators, "alice"),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+        assert!(normalize_operators(&[String::new()], "alice").is_empty());
+    }
+
+    #[test]
+    fn test_suggestion_round_trip() {
+        for suggestion in [
+            Suggestion::Pause,
+            Suggestion::Unpause,
+            Suggestion::Seek(754.5),
+        ] {
+            assert_eq!(Suggestion::parse(&suggestion.to_chat()), Some(suggestion));
+        }
+        assert_eq!(Suggestion::parse("seek -3"), None);
+        assert_eq!(Suggestion::parse("rewind"), None);
+    }
+}
@@ -0,0 +1,143 @@
+// Room events module
+// Keeps a typed timeline of room activity, separate from the chat history
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+const DEFAULT_MAX_EVENTS: usize = 500;
+
+/// What happened in the room
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoomEventKind {
+    Joined,
+    Left,
+    FileChanged {
+        file: String,
+        duration: Option<f64>,
+    },
+    Seeked {
+        from: f64,
+        to: f64,
+    },
+    Paused {
+        position: f64,
+    },
+    Unpaused,
+    Ready {
+        ready: bool,
+        /// User who changed the ready state, when it was not the user themself
+        #[serde(rename = "setBy")]
+        set_by: Option<String>,
+    },
+}
+
+/// Single timeline entry, also the payload of the `room-event` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomEvent {
+    pub timestamp: String,
+    pub username: String,
+    pub room: String,
+    #[serde(flatten)]
+    pub kind: RoomEventKind,
+}
+
+impl RoomEvent {
+    pub fn new(username: impl Into<String>, room: impl Into<String>, kind: RoomEventKind) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            username: username.into(),
+            room: room.into(),
+            kind,
+        }
+    }
+}
+
+/// Bounded log of room events, oldest first
+pub struct RoomTimeline {
+    events: RwLock<VecDeque<RoomEvent>>,
+    max_events: usize,
+}
+
+impl RoomTimeline {
+    pub fn new() -> Arc<Self> {
+        Self::with_max_events(DEFAULT_MAX_EVENTS)
+    }
+
+    pub fn with_max_events(max_events: usize) -> Arc<Self> {
+        Arc::new(Self {
+            events: RwLock::new(VecDeque::new()),
+            max_events,
+        })
+    }
+
+    /// Append an event, dropping the oldest ones past the limit
+    pub fn record(&self, event: RoomEvent) {
+        let mut events = self.events.write();
+        events.push_back(event);
+        while events.len() > self.max_events {
+            events.pop_front();
+        }
+    }
+
+    /// Events in the order they happened, optionally limited to one room
+    pub fn entries(&self, room: Option<&str>) -> Vec<RoomEvent> {
+        self.events
+            .read()
+            .iter()
+            .filter(|event| room.is_none_or(|room| event.room == room))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.events.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_drops_oldest_events() {
+        let timeline = RoomTimeline::with_max_events(2);
+        timeline.record(RoomEvent::new("alice", "lobby", RoomEventKind::Joined));
+        timeline.record(RoomEvent::new("bob", "lobby", RoomEventKind::Joined));
+        timeline.record(RoomEvent::new("alice", "lobby", RoomEventKind::Unpaused));
+
+        let entries = timeline.entries(None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].username, "bob");
+        assert_eq!(entries[1].kind, RoomEventKind::Unpaused);
+    }
+
+    #[test]
+    fn test_timeline_filters_by_room() {
+        let timeline = RoomTimeline::new();
+        timeline.record(RoomEvent::new("alice", "lobby", RoomEventKind::Joined));
+        timeline.record(RoomEvent::new("bob", "movies", RoomEventKind::Left));
+        let entries = timeline.entries(Some("movies"));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].username, "bob");
+    }
+
+    #[test]
+    fn test_room_event_serialization() {
+        let event = RoomEvent::new(
+            "alice",
+            "lobby",
+            RoomEventKind::Seeked {
+                from: 10.0,
+                to: 2480.0,
+            },
+        );
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "seeked");
+        assert_eq!(value["to"], 2480.0);
+        assert_eq!(value["username"], "alice");
+    }
+}
@@ -0,0 +1,409 @@
+// Segments module
+// Per-file intro and outro ranges that the room can skip together
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::player::backend::Chapter;
+use crate::utils::same_filename;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentKind {
+    Intro,
+    Outro,
+    Preview,
+}
+
+impl SegmentKind {
+    pub fn i18n_key(&self) -> &'static str {
+        match self {
+            SegmentKind::Intro => "segment-intro",
+            SegmentKind::Outro => "segment-outro",
+            SegmentKind::Preview => "segment-preview",
+        }
+    }
+
+    /// Kind of a chapter titled e.g. "OP", "Ending" or "Next Episode Preview"
+    pub fn from_chapter_title(title: &str) -> Option<Self> {
+        let title = title.to_lowercase();
+        let words: Vec<&str> = title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.trim_end_matches(|c: char| c.is_ascii_digit()))
+            .collect();
+        if words.contains(&"preview") {
+            return Some(SegmentKind::Preview);
+        }
+        match words.first().copied()? {
+            "op" | "opening" | "intro" => Some(SegmentKind::Intro),
+            "ed" | "ending" | "outro" | "credits" => Some(SegmentKind::Outro),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub kind: SegmentKind,
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Segment {
+    pub fn contains(&self, position: f64) -> bool {
+        position >= self.start && position < self.end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSegments {
+    pub file: String,
+    pub segments: Vec<Segment>,
+}
+
+/// Segments read from an import file, for the named file or the open one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSegments {
+    pub file: Option<String>,
+    pub segments: Vec<Segment>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SegmentImport {
+    Segments(Vec<Segment>),
+    Files(Vec<FileSegments>),
+    File(FileSegments),
+}
+
+/// Check and sort segments, rejecting empty or negative ranges
+pub fn validate_segments(mut segments: Vec<Segment>) -> Result<Vec<Segment>, String> {
+    for segment in &segments {
+        if !segment.start.is_finite() || !segment.end.is_finite() || segment.start < 0.0 {
+            return Err("Segment times must be positive numbers".to_string());
+        }
+        if segment.end <= segment.start {
+            return Err(format!(
+                "Segment end ({:.1}) must be after its start ({:.1})",
+                segment.end, segment.start
+            ));
+        }
+    }
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(segments)
+}
+
+/// Parse a JSON segment list or an MPlayer EDL file
+///
+/// EDL lines carry no kind, so ranges in the first half of `duration` become intros
+/// and the rest outros; without a duration only the first range is an intro.
+pub fn parse_segment_file(
+    text: &str,
+    duration: Option<f64>,
+) -> Result<Vec<ImportedSegments>, String> {
+    if text.trim_start().starts_with(['[', '{']) {
+        let import: SegmentImport =
+            serde_json::from_str(text).map_err(|e| format!("Invalid segment JSON: {}", e))?;
+        return match import {
+            SegmentImport::Segments(segments) => Ok(vec![ImportedSegments {
+                file: None,
+                segments: validate_segments(segments)?,
+            }]),
+            SegmentImport::Files(files) => files
+                .into_iter()
+                .map(|entry| {
+                    Ok(ImportedSegments {
+                        file: Some(entry.file),
+                        segments: validate_segments(entry.segments)?,
+                    })
+                })
+                .collect(),
+            SegmentImport::File(entry) => Ok(vec![ImportedSegments {
+                file: Some(entry.file),
+                segments: validate_segments(entry.segments)?,
+            }]),
+        };
+    }
+
+    let mut ranges = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let parse = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid EDL line {}: {}", index + 1, line))
+        };
+        if fields.len() < 2 {
+            return Err(format!("Invalid EDL line {}: {}", index + 1, line));
+        }
+        // Action 0 skips the range; anything else (e.g. 1 for mute) is not a segment
+        if fields.get(2).is_some_and(|action| *action != "0") {
+            continue;
+        }
+        ranges.push((parse(fields[0])?, parse(fields[1])?));
+    }
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let segments = ranges
+        .iter()
+        .enumerate()
+        .map(|(index, &(start, end))| {
+            let is_intro = match duration {
+                Some(duration) if duration > 0.0 => end <= duration / 2.0,
+                _ => index == 0,
+            };
+            Segment {
+                kind: if is_intro {
+                    SegmentKind::Intro
+                } else {
+                    SegmentKind::Outro
+                },
+                start,
+                end,
+            }
+        })
+        .collect();
+    Ok(vec![ImportedSegments {
+        file: None,
+        segments: validate_segments(segments)?,
+    }])
+}
+
+/// Segments for chapters with a recognised title, each lasting until the next chapter
+pub fn segments_from_chapters(chapters: &[Chapter], duration: Option<f64>) -> Vec<Segment> {
+    let mut chapters = chapters.to_vec();
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters
+        .iter()
+        .enumerate()
+        .filter_map(|(index, chapter)| {
+            let kind = SegmentKind::from_chapter_title(&chapter.title)?;
+            let end = chapters
+                .get(index + 1)
+                .map(|next| next.start)
+                .or(duration.filter(|duration| *duration > 0.0))?;
+            (end > chapter.start).then_some(Segment {
+                kind,
+                start: chapter.start.max(0.0),
+                end,
+            })
+        })
+        .collect()
+}
+
+/// Known segments for every file
+pub struct SegmentStore {
+    files: RwLock<Vec<FileSegments>>,
+    /// Segments derived from the chapters of the open file
+    chapters: RwLock<Option<FileSegments>>,
+    /// File and start of the segment playback was last seen in
+    prompted: Mutex<Option<(String, f64)>>,
+}
+
+impl SegmentStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            files: RwLock::new(Vec::new()),
+            chapters: RwLock::new(None),
+            prompted: Mutex::new(None),
+        })
+    }
+
+    /// Replace the segments of a file; an empty list forgets the file
+    pub fn set(&self, file: &str, segments: Vec<Segment>) {
+        let mut files = self.files.write();
+        files.retain(|entry| !same_filename(Some(&entry.file), Some(file)));
+        if !segments.is_empty() {
+            files.push(FileSegments {
+                file: file.to_string(),
+                segments,
+            });
+        }
+    }
+
+    pub fn get(&self, file: &str) -> Vec<Segment> {
+        self.files
+            .read()
+            .iter()
+            .find(|entry| same_filename(Some(&entry.file), Some(file)))
+            .map(|entry| entry.segments.clone())
+            .unwrap_or_default()
+    }
+
+    /// Segment of `file` playing at `position`, optionally of one kind only
+    ///
+    /// Segments entered for the file take precedence over chapter segments.
+    pub fn segment_at(
+        &self,
+        file: &str,
+        position: f64,
+        kind: Option<SegmentKind>,
+    ) -> Option<Segment> {
+        let matches = |segment: &Segment| {
+            segment.contains(position) && kind.is_none_or(|kind| segment.kind == kind)
+        };
+        self.get(file)
+            .into_iter()
+            .find(matches)
+            .or_else(|| self.chapter_segment_at(file, position).filter(matches))
+    }
+
+    /// Chapter segment of the open file playing at `position`
+    pub fn chapter_segment_at(&self, file: &str, position: f64) -> Option<Segment> {
+        let chapters = self.chapters.read();
+        let entry = chapters.as_ref()?;
+        if !same_filename(Some(&entry.file), Some(file)) {
+            return None;
+        }
+        entry
+            .segments
+            .iter()
+            .find(|segment| segment.contains(position))
+            .copied()
+    }
+
+    /// Remember the chapter segments of the file that was just opened
+    pub fn set_chapter_segments(&self, file: &str, segments: Vec<Segment>) {
+        *self.chapters.write() = (!segments.is_empty()).then(|| FileSegments {
+            file: file.to_string(),
+            segments,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.read().is_empty() && self.chapters.read().is_none()
+    }
+
+    pub fn entries(&self) -> Vec<FileSegments> {
+        self.files.read().clone()
+    }
+
+    pub fn restore(&self, files: Vec<FileSegments>) {
+        *self.files.write() = files;
+    }
+
+    /// Track the segment playback is in, returning what changed since the last call
+    ///
+    /// `Some(Some(_))` means a segment was entered and `Some(None)` that it was left.
+    pub fn prompt_change(&self, file: &str, segment: Option<Segment>) -> Option<Option<Segment>> {
+        let key = segment.map(|segment| (file.to_string(), segment.start));
+        let mut prompted = self.prompted.lock();
+        if *prompted == key {
+            return None;
+        }
+        *prompted = key;
+        Some(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intro(start: f64, end: f64) -> Segment {
+        Segment {
+            kind: SegmentKind::Intro,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_segment_store_lookup_and_prompt() {
+        let store = SegmentStore::new();
+        store.set("Episode 01.mkv", vec![intro(5.0, 95.0)]);
+        assert_eq!(
+            store.segment_at("episode 01.mkv", 30.0, None),
+            Some(intro(5.0, 95.0))
+        );
+        assert!(store
+            .segment_at("Episode 01.mkv", 30.0, Some(SegmentKind::Outro))
+            .is_none());
+        assert!(store.segment_at("Episode 01.mkv", 95.0, None).is_none());
+
+        let inside = store.segment_at("Episode 01.mkv", 30.0, None);
+        assert_eq!(store.prompt_change("Episode 01.mkv", None), None);
+        assert_eq!(store.prompt_change("Episode 01.mkv", inside), Some(inside));
+        assert_eq!(store.prompt_change("Episode 01.mkv", inside), None);
+        assert_eq!(store.prompt_change("Episode 01.mkv", None), Some(None));
+        assert_eq!(store.prompt_change("Episode 01.mkv", inside), Some(inside));
+
+        store.set("Episode 01.mkv", Vec::new());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_chapter_segments() {
+        let chapter = |title: &str, start: f64| Chapter {
+            title: title.to_string(),
+            start,
+        };
+        let chapters = vec![
+            chapter("Prologue", 0.0),
+            chapter("OP1", 60.0),
+            chapter("Part A", 150.0),
+            chapter("Ending", 1300.0),
+            chapter("Next Episode Preview", 1390.0),
+        ];
+        let segments = segments_from_chapters(&chapters, Some(1420.0));
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0], intro(60.0, 150.0));
+        assert_eq!(segments[1].kind, SegmentKind::Outro);
+        assert_eq!(segments[2].kind, SegmentKind::Preview);
+        assert_eq!(segments[2].end, 1420.0);
+        assert_eq!(SegmentKind::from_chapter_title("Edit"), None);
+
+        let store = SegmentStore::new();
+        store.set_chapter_segments("a.mkv", segments);
+        assert_eq!(
+            store.segment_at("a.mkv", 100.0, None),
+            Some(intro(60.0, 150.0))
+        );
+        store.set("a.mkv", vec![intro(50.0, 140.0)]);
+        assert_eq!(
+            store.segment_at("a.mkv", 100.0, None),
+            Some(intro(50.0, 140.0))
+        );
+        assert!(store.segment_at("b.mkv", 100.0, None).is_none());
+    }
+
+    #[test]
+    fn test_validate_segments() {
+        assert!(validate_segments(vec![intro(10.0, 10.0)]).is_err());
+        assert!(validate_segments(vec![intro(-1.0, 10.0)]).is_err());
+        let sorted = validate_segments(vec![intro(50.0, 60.0), intro(0.0, 20.0)]).unwrap();
+        assert_eq!(sorted[0].start, 0.0);
+    }
+
+    #[test]
+    fn test_parse_json_segments() {
+        let imported = parse_segment_file(
+            r#"[{"file": "a.mkv", "segments": [{"kind": "outro", "start": 1300, "end": 1420}]}]"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(imported[0].file.as_deref(), Some("a.mkv"));
+        assert_eq!(imported[0].segments[0].kind, SegmentKind::Outro);
+
+        let imported =
+            parse_segment_file(r#"[{"kind": "intro", "start": 0, "end": 90}]"#, None).unwrap();
+        assert_eq!(imported[0].file, None);
+        assert!(parse_segment_file("[{\"kind\": \"recap\"}]", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_edl_segments() {
+        let edl = "# chapters\n5.0 92.5 0\n600 610 1\n1320.0 1410.0 0\n";
+        let imported = parse_segment_file(edl, Some(1440.0)).unwrap();
+        let segments = &imported[0].segments;
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], intro(5.0, 92.5));
+        assert_eq!(segments[1].kind, SegmentKind::Outro);
+        assert!(parse_segment_file("12 abc", None).is_err());
+    }
+}
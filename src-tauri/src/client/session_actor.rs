@@ -0,0 +1,275 @@
+// Session actor module
+// Owns connection-scoped playback bookkeeping behind a command channel
+
+use std::time::Instant;
+
+use tokio::sync::{mpsc, oneshot, watch};
+
+use crate::network::messages::IgnoringInfo;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoringOnTheFlyState {
+    pub server: u32,
+    pub client: u32,
+}
+
+/// Ignoring-on-the-fly data to attach to an outgoing State message
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingIgnoring {
+    /// Whether the local playstate may be sent with this message
+    pub include_playstate: bool,
+    pub info: Option<IgnoringInfo>,
+}
+
+/// Mutable per-session data, only ever modified by the actor task
+#[derive(Debug, Clone, Default)]
+pub struct SessionData {
+    /// Last rewind timestamp
+    pub last_rewind_time: Option<Instant>,
+    /// Last playlist advance timestamp
+    pub last_advance_time: Option<Instant>,
+    /// Last time a file update was sent/received
+    pub last_updated_file_time: Option<Instant>,
+    /// Last time we paused due to a user leaving
+    pub last_paused_on_leave_time: Option<Instant>,
+    /// Last local seek origin position for notifications
+    pub last_seek_from_position: Option<f64>,
+    /// Ignoring-on-the-fly counters
+    pub ignoring_on_the_fly: IgnoringOnTheFlyState,
+    /// Whether the first playlist index has been received
+    pub had_first_playlist_index: bool,
+}
+
+pub enum SessionCommand {
+    MarkRewind(Instant),
+    MarkAdvance(Instant),
+    MarkFileUpdated(Instant),
+    MarkPausedOnLeave(Instant),
+    SetSeekFromPosition(Option<f64>),
+    UpdateIgnoring(IgnoringInfo),
+    ResetPlaylistIndex,
+    Reset,
+    PrepareOutgoingState {
+        state_change: bool,
+        reply: oneshot::Sender<OutgoingIgnoring>,
+    },
+    MarkPlaylistIndexSeen {
+        reply: oneshot::Sender<bool>,
+    },
+    TakePausedOnLeave {
+        reply: oneshot::Sender<Option<Instant>>,
+    },
+    TakeSeekFromPosition {
+        reply: oneshot::Sender<Option<f64>>,
+    },
+    Current {
+        reply: oneshot::Sender<SessionData>,
+    },
+}
+
+impl SessionData {
+    pub fn apply(&mut self, command: SessionCommand) {
+        match command {
+            SessionCommand::MarkRewind(at) => self.last_rewind_time = Some(at),
+            SessionCommand::MarkAdvance(at) => self.last_advance_time = Some(at),
+            SessionCommand::MarkFileUpdated(at) => self.last_updated_file_time = Some(at),
+            SessionCommand::MarkPausedOnLeave(at) => self.last_paused_on_leave_time = Some(at),
+            SessionCommand::SetSeekFromPosition(position) => {
+                self.last_seek_from_position = position;
+            }
+            SessionCommand::UpdateIgnoring(ignoring) => self.update_ignoring(&ignoring),
+            SessionCommand::ResetPlaylistIndex => self.had_first_playlist_index = false,
+            SessionCommand::Reset => self.reset(),
+            SessionCommand::PrepareOutgoingState {
+                state_change,
+                reply,
+            } => {
+                let _ = reply.send(self.prepare_outgoing_state(state_change));
+            }
+            SessionCommand::MarkPlaylistIndexSeen { reply } => {
+                let _ = reply.send(self.mark_playlist_index_seen());
+            }
+            SessionCommand::TakePausedOnLeave { reply } => {
+                let _ = reply.send(self.last_paused_on_leave_time.take());
+            }
+            SessionCommand::TakeSeekFromPosition { reply } => {
+                let _ = reply.send(self.last_seek_from_position.take());
+            }
+            SessionCommand::Current { reply } => {
+                let _ = reply.send(self.clone());
+            }
+        }
+    }
+
+    /// Reset per-connection tracking before a new session starts
+    pub fn reset(&mut self) {
+        *self = Self {
+            ignoring_on_the_fly: self.ignoring_on_the_fly.clone(),
+            last_seek_from_position: self.last_seek_from_position,
+            ..Self::default()
+        };
+    }
+
+    /// Apply ignoring-on-the-fly counters received from the server
+    pub fn update_ignoring(&mut self, ignoring: &IgnoringInfo) {
+        let local = &mut self.ignoring_on_the_fly;
+        if let Some(server) = ignoring.server {
+            local.server = server;
+            local.client = 0;
+        } else if let Some(client) = ignoring.client {
+            if client == local.client {
+                local.client = 0;
+            }
+        }
+    }
+
+    /// Work out the ignoring-on-the-fly fields for an outgoing State message
+    pub fn prepare_outgoing_state(&mut self, state_change: bool) -> OutgoingIgnoring {
+        let ignoring = &mut self.ignoring_on_the_fly;
+        let include_playstate = ignoring.client == 0 || ignoring.server != 0;
+        if state_change {
+            ignoring.client = ignoring.client.saturating_add(1);
+        }
+        let info = if ignoring.server != 0 || ignoring.client != 0 {
+            Some(IgnoringInfo {
+                server: (ignoring.server != 0).then_some(ignoring.server),
+                client: (ignoring.client != 0).then_some(ignoring.client),
+            })
+        } else {
+            None
+        };
+        ignoring.server = 0;
+        OutgoingIgnoring {
+            include_playstate,
+            info,
+        }
+    }
+
+    /// Record a playlist index update, returning whether one was seen before
+    pub fn mark_playlist_index_seen(&mut self) -> bool {
+        std::mem::replace(&mut self.had_first_playlist_index, true)
+    }
+}
+
+/// Handle to the session actor task
+///
+/// Writes are queued as commands and applied in order. Reads come from the
+/// snapshot the actor publishes after every command, so a read issued right
+/// after a fire-and-forget write may briefly see the previous value; use the
+/// async request methods when the result of a change is needed.
+#[derive(Clone)]
+pub struct SessionActor {
+    tx: mpsc::UnboundedSender<SessionCommand>,
+    snapshot: watch::Receiver<SessionData>,
+}
+
+impl SessionActor {
+    pub fn spawn() -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<SessionCommand>();
+        let (snapshot_tx, snapshot) = watch::channel(SessionData::default());
+        tauri::async_runtime::spawn(async move {
+            let mut data = SessionData::default();
+            while let Some(command) = rx.recv().await {
+                data.apply(command);
+                snapshot_tx.send_replace(data.clone());
+            }
+            tracing::debug!("Session actor terminated");
+        });
+        Self { tx, snapshot }
+    }
+
+    pub fn send(&self, command: SessionCommand) {
+        if self.tx.send(command).is_err() {
+            tracing::warn!("Session actor is not running");
+        }
+    }
+
+    /// Latest published session data
+    pub fn snapshot(&self) -> SessionData {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Session data after every previously queued command has been applied
+    pub async fn current(&self) -> SessionData {
+        self.request(|reply| SessionCommand::Current { reply })
+            .await
+    }
+
+    async fn request<T: Default>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> SessionCommand,
+    ) -> T {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.send(command(reply_tx));
+        reply_rx.await.unwrap_or_default()
+    }
+
+    pub async fn prepare_outgoing_state(&self, state_change: bool) -> OutgoingIgnoring {
+        self.request(|reply| SessionCommand::PrepareOutgoingState {
+            state_change,
+            reply,
+        })
+        .await
+    }
+
+    pub async fn mark_playlist_index_seen(&self) -> bool {
+        self.request(|reply| SessionCommand::MarkPlaylistIndexSeen { reply })
+            .await
+    }
+
+    pub async fn take_paused_on_leave(&self) -> Option<Instant> {
+        self.request(|reply| SessionCommand::TakePausedOnLeave { reply })
+            .await
+    }
+
+    pub async fn take_seek_from_position(&self) -> Option<f64> {
+        self.request(|reply| SessionCommand::TakeSeekFromPosition { reply })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_outgoing_state_tracks_client_ignores() {
+        let mut data = SessionData::default();
+        let first = data.prepare_outgoing_state(true);
+        assert!(first.include_playstate);
+        assert_eq!(first.info.and_then(|info| info.client), Some(1));
+
+        // Until the server acknowledges, further playstates are withheld
+        let second = data.prepare_outgoing_state(false);
+        assert!(!second.include_playstate);
+
+        data.update_ignoring(&IgnoringInfo {
+            server: None,
+            client: Some(1),
+        });
+        let third = data.prepare_outgoing_state(false);
+        assert!(third.include_playstate);
+        assert!(third.info.is_none());
+    }
+
+    #[test]
+    fn test_server_ignore_is_echoed_once() {
+        let mut data = SessionData::default();
+        data.update_ignoring(&IgnoringInfo {
+            server: Some(3),
+            client: None,
+        });
+        let echoed = data.prepare_outgoing_state(false);
+        assert_eq!(echoed.info.and_then(|info| info.server), Some(3));
+        assert_eq!(data.ignoring_on_the_fly, IgnoringOnTheFlyState::default());
+    }
+
+    #[test]
+    fn test_mark_playlist_index_seen() {
+        let mut data = SessionData::default();
+        assert!(!data.mark_playlist_index_seen());
+        assert!(data.mark_playlist_index_seen());
+        data.apply(SessionCommand::Reset);
+        assert!(!data.mark_playlist_index_seen());
+    }
+}
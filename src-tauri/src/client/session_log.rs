@@ -0,0 +1,171 @@
+// Session log module
+// Renders chat history and room activity as an exportable transcript
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::client::chat::{ChatMessage, ChatMessageType};
+use crate::client::room_events::{RoomEvent, RoomEventKind};
+use crate::utils::format_time;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionLogFormat {
+    Text,
+    Json,
+}
+
+/// Everything that goes into an exported session log
+pub struct SessionLog<'a> {
+    pub server: Option<String>,
+    pub room: String,
+    pub exported_at: DateTime<Utc>,
+    pub messages: &'a [ChatMessage],
+    pub events: &'a [RoomEvent],
+}
+
+impl SessionLog<'_> {
+    pub fn render(&self, format: SessionLogFormat) -> String {
+        match format {
+            SessionLogFormat::Text => self.render_text(),
+            SessionLogFormat::Json => self.render_json(),
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "Syncplay session log - {} room '{}'",
+                self.server.as_deref().unwrap_or("offline"),
+                self.room
+            ),
+            format!(
+                "Exported {}",
+                self.exported_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            String::new(),
+            "== Chat ==".to_string(),
+        ];
+        for message in self.messages {
+            let time = message.timestamp.format("%H:%M:%S");
+            lines.push(match (&message.message_type, &message.username) {
+                (ChatMessageType::User, Some(username)) => {
+                    format!("[{}] <{}> {}", time, username, message.message)
+                }
+                (ChatMessageType::Error, _) => format!("[{}] ! {}", time, message.message),
+                _ => format!("[{}] * {}", time, message.message),
+            });
+        }
+        lines.push(String::new());
+        lines.push("== Room activity ==".to_string());
+        for event in self.events {
+            let time = DateTime::parse_from_rfc3339(&event.timestamp)
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .unwrap_or_else(|_| event.timestamp.clone());
+            lines.push(format!(
+                "[{}] ({}) {}",
+                time,
+                event.room,
+                describe_event(event)
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn render_json(&self) -> String {
+        let messages: Vec<_> = self
+            .messages
+            .iter()
+            .map(|message| {
+                serde_json::json!({
+                    "timestamp": message.timestamp.to_rfc3339(),
+                    "username": message.username,
+                    "message": message.message,
+                    "messageType": message.message_type.as_str(),
+                })
+            })
+            .collect();
+        let log = serde_json::json!({
+            "server": self.server,
+            "room": self.room,
+            "exportedAt": self.exported_at.to_rfc3339(),
+            "messages": messages,
+            "roomEvents": self.events,
+        });
+        serde_json::to_string_pretty(&log).unwrap_or_default()
+    }
+}
+
+fn describe_event(event: &RoomEvent) -> String {
+    let user = &event.username;
+    match &event.kind {
+        RoomEventKind::Joined => format!("{} joined", user),
+        RoomEventKind::Left => format!("{} left", user),
+        RoomEventKind::FileChanged { file, .. } => format!("{} is playing '{}'", user, file),
+        RoomEventKind::Seeked { from, to } => format!(
+            "{} seeked from {} to {}",
+            user,
+            format_time(*from, false),
+            format_time(*to, false)
+        ),
+        RoomEventKind::Paused { position } => {
+            format!("{} paused at {}", user, format_time(*position, false))
+        }
+        RoomEventKind::Unpaused => format!("{} unpaused", user),
+        RoomEventKind::Ready { ready, set_by } => {
+            let state = if *ready { "ready" } else { "not ready" };
+            match set_by {
+                Some(set_by) => format!("{} was set {} by {}", user, state, set_by),
+                None => format!("{} is {}", user, state),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_log<'a>(messages: &'a [ChatMessage], events: &'a [RoomEvent]) -> SessionLog<'a> {
+        SessionLog {
+            server: Some("syncplay.pl:8999".to_string()),
+            room: "movies".to_string(),
+            exported_at: Utc::now(),
+            messages,
+            events,
+        }
+    }
+
+    #[test]
+    fn test_render_text() {
+        let messages = vec![
+            ChatMessage::user("alice".to_string(), "ready?".to_string()),
+            ChatMessage::system("bob joined the room".to_string()),
+        ];
+        let events = vec![RoomEvent::new(
+            "bob",
+            "movies",
+            RoomEventKind::Seeked {
+                from: 5.0,
+                to: 2480.0,
+            },
+        )];
+        let text = sample_log(&messages, &events).render(SessionLogFormat::Text);
+        assert!(text.starts_with("Syncplay session log - syncplay.pl:8999 room 'movies'"));
+        assert!(text.contains("<alice> ready?"));
+        assert!(text.contains("* bob joined the room"));
+        assert!(text.contains("(movies) bob seeked from 00:05 to 41:20"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let messages = vec![ChatMessage::user("alice".to_string(), "hi".to_string())];
+        let events = vec![RoomEvent::new("alice", "movies", RoomEventKind::Joined)];
+        let json = sample_log(&messages, &events).render(SessionLogFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["room"], "movies");
+        assert_eq!(value["messages"][0]["messageType"], "user");
+        assert_eq!(value["roomEvents"][0]["type"], "joined");
+    }
+}
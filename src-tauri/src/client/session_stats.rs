@@ -0,0 +1,168 @@
+// Session statistics module
+// Tallies one watch session, from connecting until leaving the server, for a recap
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::sync::CorrectionTotals;
+
+/// Payload of the `session-summary` event and one line of the history file
+///
+/// Holds counts only, no names, so it can be shared for diagnostics.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub started_at: DateTime<Utc>,
+    pub connected_secs: u64,
+    /// Time the player was playing a file
+    pub watched_secs: u64,
+    pub files_played: usize,
+    pub rewinds: u64,
+    pub fastforwards: u64,
+    pub average_rtt_ms: Option<f64>,
+    pub chat_messages: u32,
+    pub reconnects: u32,
+}
+
+#[derive(Debug)]
+pub struct SessionStats {
+    started_at: DateTime<Utc>,
+    started: Instant,
+    /// Corrections made before the session, subtracted at the end
+    corrections_at_start: CorrectionTotals,
+    playing_since: Option<Instant>,
+    watched: Duration,
+    files: Vec<String>,
+    rtt_total: f64,
+    rtt_samples: u32,
+    chat_messages: u32,
+    reconnects: u32,
+}
+
+impl SessionStats {
+    pub fn new(corrections: CorrectionTotals, now: Instant) -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: now,
+            corrections_at_start: corrections,
+            playing_since: None,
+            watched: Duration::ZERO,
+            files: Vec::new(),
+            rtt_total: 0.0,
+            rtt_samples: 0,
+            chat_messages: 0,
+            reconnects: 0,
+        }
+    }
+
+    /// Feed whether the player is playing a file
+    pub fn set_playing(&mut self, playing: bool, now: Instant) {
+        match (playing, self.playing_since) {
+            (true, None) => self.playing_since = Some(now),
+            (false, Some(since)) => {
+                self.watched += now.saturating_duration_since(since);
+                self.playing_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Count a file once, however often it is reloaded
+    pub fn file_loaded(&mut self, filename: &str) {
+        if !self.files.iter().any(|file| file == filename) {
+            self.files.push(filename.to_string());
+        }
+    }
+
+    /// Record a round trip time, in seconds
+    pub fn record_rtt(&mut self, rtt: f64) {
+        if rtt.is_finite() && rtt >= 0.0 {
+            self.rtt_total += rtt;
+            self.rtt_samples += 1;
+        }
+    }
+
+    pub fn chat_message(&mut self) {
+        self.chat_messages += 1;
+    }
+
+    pub fn reconnected(&mut self) {
+        self.reconnects += 1;
+    }
+
+    pub fn summary(&self, corrections: CorrectionTotals, now: Instant) -> SessionSummary {
+        let watched = self.watched
+            + self
+                .playing_since
+                .map(|since| now.saturating_duration_since(since))
+                .unwrap_or_default();
+        let start = self.corrections_at_start;
+        SessionSummary {
+            started_at: self.started_at,
+            connected_secs: now.saturating_duration_since(self.started).as_secs(),
+            watched_secs: watched.as_secs(),
+            files_played: self.files.len(),
+            rewinds: corrections.rewinds.saturating_sub(start.rewinds),
+            fastforwards: corrections.fastforwards.saturating_sub(start.fastforwards),
+            average_rtt_ms: (self.rtt_samples > 0)
+                .then(|| self.rtt_total / f64::from(self.rtt_samples) * 1000.0),
+            chat_messages: self.chat_messages,
+            reconnects: self.reconnects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_counts_the_session() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut stats = SessionStats::new(
+            CorrectionTotals {
+                rewinds: 4,
+                ..CorrectionTotals::default()
+            },
+            start,
+        );
+        stats.set_playing(true, at(10));
+        stats.set_playing(true, at(20));
+        stats.set_playing(false, at(70));
+        stats.set_playing(true, at(100));
+        stats.file_loaded("a.mkv");
+        stats.file_loaded("b.mkv");
+        stats.file_loaded("a.mkv");
+        stats.record_rtt(0.04);
+        stats.record_rtt(0.06);
+        stats.record_rtt(f64::NAN);
+        stats.chat_message();
+
+        let summary = stats.summary(
+            CorrectionTotals {
+                rewinds: 6,
+                fastforwards: 1,
+                ..CorrectionTotals::default()
+            },
+            at(130),
+        );
+        assert_eq!(summary.connected_secs, 130);
+        assert_eq!(summary.watched_secs, 90);
+        assert_eq!(summary.files_played, 2);
+        assert_eq!(summary.rewinds, 2);
+        assert_eq!(summary.fastforwards, 1);
+        assert!((summary.average_rtt_ms.unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!(summary.chat_messages, 1);
+    }
+
+    #[test]
+    fn test_summary_without_rtt_samples() {
+        let start = Instant::now();
+        let stats = SessionStats::new(CorrectionTotals::default(), start);
+        let summary = stats.summary(CorrectionTotals::default(), start);
+        assert_eq!(summary.average_rtt_ms, None);
+        assert_eq!(summary.watched_secs, 0);
+    }
+}
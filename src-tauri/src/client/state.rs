@@ -1,6 +1,8 @@
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::network::messages::FileSizeInfo;
 
@@ -14,6 +16,8 @@ pub struct User {
     pub file_duration: Option<f64>,
     pub is_ready: Option<bool>,
     pub is_controller: bool,
+    /// Minutes east of UTC, for users who share their timezone
+    pub utc_offset: Option<i32>,
 }
 
 impl User {
@@ -23,12 +27,78 @@ impl User {
     }
 }
 
+/// Occupancy summary for a room on the server
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomSummary {
+    pub name: String,
+    pub user_count: usize,
+    pub files: Vec<String>,
+    pub is_current: bool,
+}
+
+/// What a room is watching, as far as a peek from outside can tell
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomOverview {
+    pub name: String,
+    pub is_current: bool,
+    pub users: Vec<RoomOverviewUser>,
+    /// Most watched file first
+    pub files: Vec<String>,
+    /// Freshest position heard from the room; servers only send these for our own room
+    pub position: Option<f64>,
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomOverviewUser {
+    pub username: String,
+    pub file: Option<String>,
+    pub is_ready: bool,
+    pub is_controller: bool,
+}
+
+/// Playback state last reported by a user
+#[derive(Debug, Clone)]
+pub struct UserPlayState {
+    pub position: f64,
+    pub paused: bool,
+    pub reported_at: Instant,
+}
+
+impl UserPlayState {
+    /// Position extrapolated to `now`
+    pub fn position_at(&self, now: Instant) -> f64 {
+        extrapolate_position(self.position, self.paused, self.reported_at, now)
+    }
+}
+
 /// Global playback state
 #[derive(Debug, Clone)]
 pub struct GlobalPlayState {
     pub position: f64,
     pub paused: bool,
     pub set_by: Option<String>,
+    pub updated_at: Option<Instant>,
+}
+
+impl GlobalPlayState {
+    /// Position extrapolated to `now`
+    pub fn position_at(&self, now: Instant) -> f64 {
+        match self.updated_at {
+            Some(updated_at) => extrapolate_position(self.position, self.paused, updated_at, now),
+            None => self.position,
+        }
+    }
+}
+
+fn extrapolate_position(position: f64, paused: bool, since: Instant, now: Instant) -> f64 {
+    if paused {
+        position
+    } else {
+        position + now.saturating_duration_since(since).as_secs_f64()
+    }
 }
 
 /// Client global state
@@ -43,6 +113,10 @@ pub struct ClientState {
     file_duration: RwLock<Option<f64>>,
     /// User list (username -> User)
     users: RwLock<HashMap<String, User>>,
+    /// Rooms the last List reported without anybody in them
+    empty_rooms: RwLock<Vec<String>>,
+    /// Last reported playback state per user
+    user_playstates: RwLock<HashMap<String, UserPlayState>>,
     /// Global playback state
     global_state: RwLock<GlobalPlayState>,
     /// Local ready state
@@ -60,10 +134,13 @@ impl ClientState {
             file_size: RwLock::new(None),
             file_duration: RwLock::new(None),
             users: RwLock::new(HashMap::new()),
+            empty_rooms: RwLock::new(Vec::new()),
+            user_playstates: RwLock::new(HashMap::new()),
             global_state: RwLock::new(GlobalPlayState {
                 position: 0.0,
                 paused: true,
                 set_by: None,
+                updated_at: None,
             }),
             is_ready: RwLock::new(false),
             server_version: RwLock::new(None),
@@ -120,6 +197,7 @@ impl ClientState {
 
     pub fn remove_user(&self, username: &str) {
         self.users.write().remove(username);
+        self.user_playstates.write().remove(username);
     }
 
     pub fn get_user(&self, username: &str) -> Option<User> {
@@ -143,6 +221,103 @@ impl ClientState {
         self.users.write().clear();
     }
 
+    /// Servers with persistent rooms keep listing them once everybody left
+    pub fn get_empty_rooms(&self) -> Vec<String> {
+        self.empty_rooms.read().clone()
+    }
+
+    pub fn set_empty_rooms(&self, rooms: Vec<String>) {
+        *self.empty_rooms.write() = rooms;
+    }
+
+    // User playback state methods
+    pub fn record_user_playstate(&self, username: &str, position: f64, paused: bool) {
+        self.user_playstates.write().insert(
+            username.to_string(),
+            UserPlayState {
+                position,
+                paused,
+                reported_at: Instant::now(),
+            },
+        );
+    }
+
+    pub fn get_user_playstate(&self, username: &str) -> Option<UserPlayState> {
+        self.user_playstates.read().get(username).cloned()
+    }
+
+    pub fn clear_user_playstates(&self) {
+        self.user_playstates.write().clear();
+    }
+
+    /// Seconds a user is ahead (positive) or behind (negative) the global state
+    pub fn user_sync_delta(&self, playstate: &UserPlayState) -> Option<f64> {
+        let global = self.get_global_state();
+        global.updated_at?;
+        let now = Instant::now();
+        Some(playstate.position_at(now) - global.position_at(now))
+    }
+
+    /// Summarize known rooms with their users and playing files
+    pub fn get_room_summaries(&self) -> Vec<RoomSummary> {
+        let current_room = self.get_room();
+        let mut rooms: BTreeMap<String, RoomSummary> = BTreeMap::new();
+        for user in self.users.read().values() {
+            let summary = rooms
+                .entry(user.room.clone())
+                .or_insert_with(|| RoomSummary {
+                    name: user.room.clone(),
+                    user_count: 0,
+                    files: Vec::new(),
+                    is_current: user.room == current_room,
+                });
+            summary.user_count += 1;
+            if let Some(file) = user.file.as_ref() {
+                if !summary.files.contains(file) {
+                    summary.files.push(file.clone());
+                }
+            }
+        }
+        rooms.into_values().collect()
+    }
+
+    /// Peek at `room` from the user list, without joining it
+    pub fn get_room_overview(&self, room: &str) -> RoomOverview {
+        let mut members = self.get_users_in_room(room);
+        members.sort_by_key(|user| user.username.to_lowercase());
+        let mut file_counts: Vec<(String, usize)> = Vec::new();
+        for file in members.iter().filter_map(|user| user.file.as_ref()) {
+            match file_counts.iter_mut().find(|(name, _)| name == file) {
+                Some((_, count)) => *count += 1,
+                None => file_counts.push((file.clone(), 1)),
+            }
+        }
+        file_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let playstates = self.user_playstates.read();
+        let freshest = members
+            .iter()
+            .filter_map(|user| playstates.get(&user.username))
+            .max_by_key(|playstate| playstate.reported_at);
+        let now = Instant::now();
+        RoomOverview {
+            name: room.to_string(),
+            is_current: room == self.get_room(),
+            position: freshest.map(|playstate| playstate.position_at(now)),
+            paused: freshest.map(|playstate| playstate.paused),
+            files: file_counts.into_iter().map(|(file, _)| file).collect(),
+            users: members
+                .into_iter()
+                .map(|user| RoomOverviewUser {
+                    is_ready: user.is_ready.unwrap_or(false),
+                    username: user.username,
+                    file: user.file,
+                    is_controller: user.is_controller,
+                })
+                .collect(),
+        }
+    }
+
     // Global state methods
     pub fn get_global_state(&self) -> GlobalPlayState {
         self.global_state.read().clone()
@@ -153,6 +328,7 @@ impl ClientState {
         state.position = position;
         state.paused = paused;
         state.set_by = set_by;
+        state.updated_at = Some(Instant::now());
     }
 
     // Ready state methods
@@ -183,13 +359,67 @@ impl Default for ClientState {
             file_size: RwLock::new(None),
             file_duration: RwLock::new(None),
             users: RwLock::new(HashMap::new()),
+            empty_rooms: RwLock::new(Vec::new()),
+            user_playstates: RwLock::new(HashMap::new()),
             global_state: RwLock::new(GlobalPlayState {
                 position: 0.0,
                 paused: true,
                 set_by: None,
+                updated_at: None,
             }),
             is_ready: RwLock::new(false),
             server_version: RwLock::new(None),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_user_sync_delta_extrapolates_playing_state() {
+        let state = ClientState::new();
+        state.set_global_state(100.0, false, Some("alice".to_string()));
+        let reported_at = Instant::now() - Duration::from_secs(2);
+        let lagging = UserPlayState {
+            position: 95.0,
+            paused: false,
+            reported_at,
+        };
+        let delta = state.user_sync_delta(&lagging).unwrap();
+        assert!((delta + 3.0).abs() < 0.1);
+
+        let paused = UserPlayState {
+            position: 95.0,
+            paused: true,
+            reported_at,
+        };
+        assert!((paused.position_at(Instant::now()) - 95.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_room_overview_orders_files_by_viewers() {
+        let state = ClientState::new();
+        let user = |username: &str, room: &str, file: Option<&str>| User {
+            username: username.to_string(),
+            room: room.to_string(),
+            file: file.map(str::to_string),
+            file_size: None,
+            file_duration: None,
+            is_ready: Some(true),
+            is_controller: false,
+            utc_offset: None,
+        };
+        state.add_user(user("bob", "movies", Some("b.mkv")));
+        state.add_user(user("amy", "movies", Some("a.mkv")));
+        state.add_user(user("cat", "movies", Some("a.mkv")));
+        state.add_user(user("dan", "lobby", None));
+        let overview = state.get_room_overview("movies");
+        assert_eq!(overview.files, vec!["a.mkv", "b.mkv"]);
+        let names: Vec<_> = overview.users.iter().map(|u| u.username.as_str()).collect();
+        assert_eq!(names, vec!["amy", "bob", "cat"]);
+        assert!(overview.position.is_none());
+    }
+}
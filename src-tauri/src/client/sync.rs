@@ -1,9 +1,9 @@
-use crate::config::UserPreferences;
+use crate::config::{SyncMode, UserPreferences};
+use serde::Serialize;
+use std::collections::VecDeque;
 use tracing::{debug, info};
 
-pub(crate) const FASTFORWARD_EXTRA_TIME: f64 = 0.25;
-pub(crate) const FASTFORWARD_RESET_THRESHOLD: f64 = 3.0;
-pub(crate) const FASTFORWARD_BEHIND_THRESHOLD: f64 = 1.75;
+const SYNC_HISTORY_LIMIT: usize = 300;
 
 /// Synchronization action to take
 #[derive(Debug, Clone, PartialEq)]
@@ -20,6 +20,50 @@ pub enum SyncAction {
     ResetSpeed,
 }
 
+/// Correction applied to the local player to follow the room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncCorrection {
+    Rewind,
+    Fastforward,
+    Slowdown,
+    Speedup,
+    ResetSpeed,
+}
+
+/// Local-vs-global difference observed on a state update
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncSample {
+    pub timestamp: i64,
+    pub diff: f64,
+    pub correction: Option<SyncCorrection>,
+}
+
+/// Aggregated sync statistics for the current session
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub sample_count: usize,
+    pub average_diff: f64,
+    pub max_abs_diff: f64,
+    pub last_diff: Option<f64>,
+    pub rewinds: u32,
+    pub fastforwards: u32,
+    pub slowdowns: u32,
+    pub speedups: u32,
+    pub speed_resets: u32,
+    pub samples: Vec<SyncSample>,
+}
+
+/// Corrections applied since the client started; unlike the report, never reset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CorrectionTotals {
+    pub rewinds: u64,
+    pub fastforwards: u64,
+    pub slowdowns: u64,
+    pub speedups: u64,
+    pub speed_resets: u64,
+}
+
 pub struct SyncInputs {
     pub local_position: f64,
     pub local_paused: bool,
@@ -40,9 +84,20 @@ pub struct SyncEngine {
     slowdown_threshold: f64,
     slowdown_reset_threshold: f64,
     slowdown_rate: f64,
+    fastforward_behind_threshold: f64,
+    fastforward_extra_time: f64,
+    fastforward_reset_threshold: f64,
     slow_on_desync: bool,
     rewind_on_desync: bool,
     fastforward_on_desync: bool,
+    sync_mode: SyncMode,
+    smooth_sync_threshold: f64,
+    smooth_sync_rate: f64,
+    /// Speed currently applied by smooth sync
+    smooth_speed: f64,
+    history: VecDeque<SyncSample>,
+    report: SyncReport,
+    totals: CorrectionTotals,
 }
 
 impl SyncEngine {
@@ -55,9 +110,19 @@ impl SyncEngine {
             slowdown_threshold: 1.5,
             slowdown_reset_threshold: 0.1,
             slowdown_rate: 0.95,
+            fastforward_behind_threshold: 1.75,
+            fastforward_extra_time: 0.25,
+            fastforward_reset_threshold: 3.0,
             slow_on_desync: true,
             rewind_on_desync: true,
             fastforward_on_desync: true,
+            sync_mode: SyncMode::Seek,
+            smooth_sync_threshold: 1.5,
+            smooth_sync_rate: 0.03,
+            smooth_speed: 1.0,
+            history: VecDeque::new(),
+            report: SyncReport::default(),
+            totals: CorrectionTotals::default(),
         }
     }
 
@@ -67,9 +132,45 @@ impl SyncEngine {
         self.slowdown_threshold = prefs.slowdown_threshold;
         self.slowdown_reset_threshold = prefs.slowdown_reset_threshold;
         self.slowdown_rate = prefs.slowdown_rate;
+        self.fastforward_behind_threshold = prefs.fastforward_behind_threshold;
+        self.fastforward_extra_time = prefs.fastforward_extra_time;
+        self.fastforward_reset_threshold = prefs.fastforward_reset_threshold;
         self.slow_on_desync = prefs.slow_on_desync;
         self.rewind_on_desync = prefs.rewind_on_desync;
         self.fastforward_on_desync = prefs.fastforward_on_desync;
+        self.sync_mode = prefs.sync_mode;
+        self.smooth_sync_threshold = prefs.smooth_sync_threshold;
+        self.smooth_sync_rate = prefs.smooth_sync_rate;
+    }
+
+    pub fn is_smooth_sync(&self) -> bool {
+        self.sync_mode == SyncMode::Smooth
+    }
+
+    pub fn smooth_speed(&self) -> f64 {
+        self.smooth_speed
+    }
+
+    pub fn set_smooth_speed(&mut self, speed: f64) {
+        self.smooth_speed = speed;
+    }
+
+    /// Speed that smooth sync wants for the given drift
+    ///
+    /// Returns `None` when the drift is too large and a hard correction should handle it.
+    pub fn smooth_sync_target(&self, diff: f64) -> Option<f64> {
+        let magnitude = diff.abs();
+        if magnitude < self.slowdown_reset_threshold {
+            Some(1.0)
+        } else if magnitude < self.smooth_sync_threshold {
+            if diff > 0.0 {
+                Some(1.0 - self.smooth_sync_rate)
+            } else {
+                Some(1.0 + self.smooth_sync_rate)
+            }
+        } else {
+            None
+        }
     }
 
     pub fn behind_first_detected(&self) -> Option<std::time::Instant> {
@@ -88,6 +189,18 @@ impl SyncEngine {
         self.slowdown_rate
     }
 
+    pub fn fastforward_behind_threshold(&self) -> f64 {
+        self.fastforward_behind_threshold
+    }
+
+    pub fn fastforward_extra_time(&self) -> f64 {
+        self.fastforward_extra_time
+    }
+
+    pub fn fastforward_reset_threshold(&self) -> f64 {
+        self.fastforward_reset_threshold
+    }
+
     /// Calculate synchronization actions needed
     pub fn calculate_sync_actions(&mut self, inputs: SyncInputs) -> Vec<SyncAction> {
         let mut actions = Vec::new();
@@ -149,7 +262,7 @@ impl SyncEngine {
                 self.behind_first_detected = None;
             }
             if inputs.allow_fastforward && self.fastforward_on_desync {
-                if diff < -FASTFORWARD_BEHIND_THRESHOLD {
+                if diff < -self.fastforward_behind_threshold {
                     let now = std::time::Instant::now();
                     match self.behind_first_detected {
                         None => {
@@ -161,7 +274,8 @@ impl SyncEngine {
                                 .unwrap_or_default()
                                 .as_secs_f64();
                             if duration_behind
-                                > (self.seek_threshold_fastforward - FASTFORWARD_BEHIND_THRESHOLD)
+                                > (self.seek_threshold_fastforward
+                                    - self.fastforward_behind_threshold)
                                 && diff < -self.seek_threshold_fastforward
                             {
                                 info!(
@@ -170,12 +284,12 @@ impl SyncEngine {
                                     self.seek_threshold_fastforward
                                 );
                                 actions.push(SyncAction::Seek(
-                                    adjusted_global_position + FASTFORWARD_EXTRA_TIME,
+                                    adjusted_global_position + self.fastforward_extra_time,
                                 ));
                                 self.slowdown_active = false;
                                 self.behind_first_detected = Some(
                                     now + std::time::Duration::from_secs_f64(
-                                        FASTFORWARD_RESET_THRESHOLD,
+                                        self.fastforward_reset_threshold,
                                     ),
                                 );
                             }
@@ -217,9 +331,78 @@ impl SyncEngine {
         actions
     }
 
+    /// Record the difference observed on a state update
+    pub fn record_sample(&mut self, diff: f64) {
+        if self.history.len() >= SYNC_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(SyncSample {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            diff,
+            correction: None,
+        });
+        self.report.sample_count += 1;
+        self.report.last_diff = Some(diff);
+        if diff.abs() > self.report.max_abs_diff {
+            self.report.max_abs_diff = diff.abs();
+        }
+    }
+
+    /// Record a correction applied for the most recent sample
+    pub fn record_correction(&mut self, correction: SyncCorrection) {
+        if let Some(sample) = self.history.back_mut() {
+            sample.correction = Some(correction);
+        }
+        match correction {
+            SyncCorrection::Rewind => {
+                self.report.rewinds += 1;
+                self.totals.rewinds += 1;
+            }
+            SyncCorrection::Fastforward => {
+                self.report.fastforwards += 1;
+                self.totals.fastforwards += 1;
+            }
+            SyncCorrection::Slowdown => {
+                self.report.slowdowns += 1;
+                self.totals.slowdowns += 1;
+            }
+            SyncCorrection::Speedup => {
+                self.report.speedups += 1;
+                self.totals.speedups += 1;
+            }
+            SyncCorrection::ResetSpeed => {
+                self.report.speed_resets += 1;
+                self.totals.speed_resets += 1;
+            }
+        }
+    }
+
+    pub fn correction_totals(&self) -> CorrectionTotals {
+        self.totals
+    }
+
+    /// Build a report of the recorded statistics
+    pub fn sync_report(&self, include_samples: bool) -> SyncReport {
+        let mut report = self.report.clone();
+        if !self.history.is_empty() {
+            let total: f64 = self.history.iter().map(|sample| sample.diff).sum();
+            report.average_diff = total / self.history.len() as f64;
+        }
+        if include_samples {
+            report.samples = self.history.iter().cloned().collect();
+        }
+        report
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.history.clear();
+        self.report = SyncReport::default();
+    }
+
     /// Reset slowdown state
     pub fn reset_slowdown(&mut self) {
         self.slowdown_active = false;
+        self.smooth_speed = 1.0;
     }
 
     /// Check if slowdown is active
@@ -228,6 +411,13 @@ impl SyncEngine {
     }
 }
 
+/// Position a seek emulating slowdown moves to when `diff` seconds ahead of the room
+///
+/// Steps back by at most `step`, so the player eases towards the room instead of jumping.
+pub fn emulated_slowdown_position(local_position: f64, diff: f64, step: f64) -> f64 {
+    (local_position - diff.clamp(0.0, step)).max(0.0)
+}
+
 impl Default for SyncEngine {
     fn default() -> Self {
         Self::new()
@@ -239,6 +429,25 @@ mod tests {
     use super::*;
     use std::time::{Duration, Instant};
 
+    #[test]
+    fn test_smooth_sync_target_follows_drift() {
+        let mut engine = SyncEngine::new();
+        engine.sync_mode = SyncMode::Smooth;
+        assert!(engine.is_smooth_sync());
+        assert_eq!(engine.smooth_sync_target(0.05), Some(1.0));
+        assert_eq!(engine.smooth_sync_target(0.8), Some(0.97));
+        assert_eq!(engine.smooth_sync_target(-0.8), Some(1.03));
+        assert_eq!(engine.smooth_sync_target(2.0), None);
+    }
+
+    #[test]
+    fn test_emulated_slowdown_steps_back_towards_room() {
+        assert_eq!(emulated_slowdown_position(100.0, 1.6, 0.5), 99.5);
+        assert_eq!(emulated_slowdown_position(100.0, 0.3, 0.5), 99.7);
+        assert_eq!(emulated_slowdown_position(100.0, -1.0, 0.5), 100.0);
+        assert_eq!(emulated_slowdown_position(0.2, 1.6, 0.5), 0.0);
+    }
+
     #[test]
     fn test_sync_no_action_when_in_sync() {
         let mut engine = SyncEngine::new();
@@ -271,6 +480,55 @@ mod tests {
         assert!(matches!(actions[0], SyncAction::Seek(_)));
     }
 
+    #[test]
+    fn test_sync_uses_configured_fastforward_extra_time() {
+        let mut prefs = UserPreferences::default();
+        prefs.seek_threshold_fastforward = 2.0;
+        prefs.fastforward_behind_threshold = 1.0;
+        prefs.fastforward_extra_time = 1.5;
+        let mut engine = SyncEngine::new();
+        engine.update_from_config(&prefs);
+        engine.behind_first_detected = Some(Instant::now() - Duration::from_secs(2));
+        let actions = engine.calculate_sync_actions(SyncInputs {
+            local_position: 7.0,
+            local_paused: false,
+            global_position: 10.0,
+            global_paused: false,
+            message_age: 0.0,
+            do_seek: false,
+            allow_fastforward: true,
+        });
+        assert_eq!(actions[0], SyncAction::Seek(11.5));
+    }
+
+    #[test]
+    fn test_sync_report_counts_corrections() {
+        let mut engine = SyncEngine::new();
+        engine.record_sample(0.5);
+        engine.record_sample(-4.5);
+        engine.record_correction(SyncCorrection::Fastforward);
+        engine.record_sample(2.0);
+        engine.record_correction(SyncCorrection::Slowdown);
+
+        let report = engine.sync_report(true);
+        assert_eq!(report.sample_count, 3);
+        assert_eq!(report.fastforwards, 1);
+        assert_eq!(report.slowdowns, 1);
+        assert_eq!(report.max_abs_diff, 4.5);
+        assert_eq!(report.last_diff, Some(2.0));
+        assert_eq!(report.samples.len(), 3);
+        assert_eq!(
+            report.samples[1].correction,
+            Some(SyncCorrection::Fastforward)
+        );
+
+        engine.reset_stats();
+        assert_eq!(engine.sync_report(false).sample_count, 0);
+        assert_eq!(engine.sync_report(false).fastforwards, 0);
+        assert_eq!(engine.correction_totals().fastforwards, 1);
+        assert_eq!(engine.correction_totals().slowdowns, 1);
+    }
+
     #[test]
     fn test_sync_seek_when_ahead() {
         let mut engine = SyncEngine::new();
@@ -1,2 +1,122 @@
 // User list management module
 // Handles adding, removing, and updating users in the room
+
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::client::state::User;
+use crate::utils::same_filename;
+
+/// Users of one room with the aggregates shown above them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomGroup {
+    pub name: String,
+    pub is_current: bool,
+    /// Usernames in display order
+    pub users: Vec<String>,
+    pub ready_count: usize,
+    /// Users playing the same file as the local user
+    pub same_file_count: usize,
+    pub controllers: Vec<String>,
+}
+
+/// Group users by room: the current room first, then by name
+///
+/// Within a room the local user comes first, then controllers, then everyone else by name.
+pub fn group_users_by_room(
+    users: &[User],
+    current_username: &str,
+    current_room: &str,
+    current_file: Option<&str>,
+) -> Vec<RoomGroup> {
+    let mut by_room: BTreeMap<&str, Vec<&User>> = BTreeMap::new();
+    for user in users {
+        by_room.entry(user.room.as_str()).or_default().push(user);
+    }
+    let mut groups: Vec<RoomGroup> = by_room
+        .into_iter()
+        .map(|(room, mut members)| {
+            members.sort_by(|a, b| compare_users(a, b, current_username));
+            RoomGroup {
+                name: room.to_string(),
+                is_current: room == current_room,
+                users: members.iter().map(|user| user.username.clone()).collect(),
+                ready_count: members
+                    .iter()
+                    .filter(|user| user.is_ready.unwrap_or(false))
+                    .count(),
+                same_file_count: current_file.map_or(0, |file| {
+                    members
+                        .iter()
+                        .filter(|user| same_filename(user.file.as_deref(), Some(file)))
+                        .count()
+                }),
+                controllers: members
+                    .iter()
+                    .filter(|user| user.is_controller)
+                    .map(|user| user.username.clone())
+                    .collect(),
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.is_current
+            .cmp(&a.is_current)
+            .then_with(|| compare_names(&a.name, &b.name))
+    });
+    groups
+}
+
+fn compare_users(a: &User, b: &User, current_username: &str) -> Ordering {
+    (b.username == current_username)
+        .cmp(&(a.username == current_username))
+        .then_with(|| b.is_controller.cmp(&a.is_controller))
+        .then_with(|| compare_names(&a.username, &b.username))
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(username: &str, room: &str, file: Option<&str>, ready: bool, controller: bool) -> User {
+        User {
+            username: username.to_string(),
+            room: room.to_string(),
+            file: file.map(str::to_string),
+            file_size: None,
+            file_duration: None,
+            is_ready: Some(ready),
+            is_controller: controller,
+            utc_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_current_room_first_with_aggregates() {
+        let users = vec![
+            user("zoe", "anime", None, false, false),
+            user("bob", "movies", Some("a.mkv"), true, false),
+            user("me", "movies", Some("a.mkv"), false, false),
+            user("Carol", "movies", Some("b.mkv"), true, true),
+            user("amy", "movies", None, false, false),
+        ];
+        let groups = group_users_by_room(&users, "me", "movies", Some("a.mkv"));
+        assert_eq!(groups.len(), 2);
+        let movies = &groups[0];
+        assert!(movies.is_current);
+        assert_eq!(movies.users, vec!["me", "Carol", "amy", "bob"]);
+        assert_eq!(movies.ready_count, 2);
+        assert_eq!(movies.same_file_count, 2);
+        assert_eq!(movies.controllers, vec!["Carol"]);
+        assert_eq!(groups[1].name, "anime");
+        assert_eq!(groups[1].same_file_count, 0);
+    }
+}
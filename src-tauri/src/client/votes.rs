@@ -0,0 +1,208 @@
+// Votes module
+// Room votes on pausing, seeking and skipping, cast as "!vote" chat messages
+//
+// Every client tallies the votes it sees on its own; when one passes, a single client
+// (an operator when the room has them) carries it out for everyone.
+
+use std::time::{Duration, Instant};
+
+/// Seek votes this close together count as votes for the same seek
+pub const VOTE_SEEK_TOLERANCE: f64 = 5.0;
+
+/// What the room votes on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoteSubject {
+    Pause,
+    /// Seek to the position, in seconds
+    Seek(f64),
+    /// Move on to the next playlist item
+    Skip,
+}
+
+impl VoteSubject {
+    /// Parse what follows "!vote", taking seek positions as seconds, m:ss or h:mm:ss
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split_whitespace();
+        let subject = match parts.next()?.to_lowercase().as_str() {
+            "pause" => Self::Pause,
+            "skip" | "next" => Self::Skip,
+            "seek" => Self::Seek(parse_position(parts.next()?)?),
+            _ => return None,
+        };
+        parts.next().is_none().then_some(subject)
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Seek(a), Self::Seek(b)) => (a - b).abs() < VOTE_SEEK_TOLERANCE,
+            _ => self == other,
+        }
+    }
+}
+
+fn parse_position(text: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        let value = part.parse::<f64>().ok()?;
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(seconds)
+}
+
+/// Votes needed out of `room_size` users for more than `majority_percent` of the room
+pub fn votes_needed(room_size: usize, majority_percent: u8) -> usize {
+    (room_size * usize::from(majority_percent) / 100 + 1).clamp(1, room_size.max(1))
+}
+
+/// Result of counting one vote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoteCast {
+    /// Counted, and the vote is still open
+    Counted { votes: usize, needed: usize },
+    /// This vote completed the majority; the vote is closed
+    Passed { subject: VoteSubject, votes: usize },
+    /// The voter already voted for this
+    Repeated,
+}
+
+#[derive(Debug, Clone)]
+struct OpenVote {
+    subject: VoteSubject,
+    started: Instant,
+    voters: Vec<String>,
+}
+
+/// Open votes of the current room
+#[derive(Debug, Default)]
+pub struct VoteBox {
+    votes: Vec<OpenVote>,
+}
+
+impl VoteBox {
+    /// Count a vote; votes older than `timeout` are dropped first
+    pub fn cast(
+        &mut self,
+        voter: &str,
+        subject: VoteSubject,
+        needed: usize,
+        timeout: Duration,
+        now: Instant,
+    ) -> VoteCast {
+        self.votes
+            .retain(|vote| now.saturating_duration_since(vote.started) < timeout);
+        let index = match self
+            .votes
+            .iter()
+            .position(|vote| vote.subject.same_as(&subject))
+        {
+            Some(index) => index,
+            None => {
+                self.votes.push(OpenVote {
+                    subject,
+                    started: now,
+                    voters: Vec::new(),
+                });
+                self.votes.len() - 1
+            }
+        };
+        let vote = &mut self.votes[index];
+        if vote
+            .voters
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(voter))
+        {
+            return VoteCast::Repeated;
+        }
+        vote.voters.push(voter.to_string());
+        let votes = vote.voters.len();
+        if votes < needed {
+            return VoteCast::Counted { votes, needed };
+        }
+        // The first seek voted for is the one carried out
+        let subject = self.votes.remove(index).subject;
+        VoteCast::Passed { subject, votes }
+    }
+
+    pub fn clear(&mut self) {
+        self.votes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_parse_vote_subject() {
+        assert_eq!(VoteSubject::parse("pause"), Some(VoteSubject::Pause));
+        assert_eq!(VoteSubject::parse("Skip"), Some(VoteSubject::Skip));
+        assert_eq!(
+            VoteSubject::parse("seek 1:02:03"),
+            Some(VoteSubject::Seek(3723.0))
+        );
+        assert_eq!(VoteSubject::parse("seek 90"), Some(VoteSubject::Seek(90.0)));
+        assert_eq!(VoteSubject::parse("seek"), None);
+        assert_eq!(VoteSubject::parse("seek -5"), None);
+        assert_eq!(VoteSubject::parse("pause now"), None);
+    }
+
+    #[test]
+    fn test_votes_needed_is_more_than_the_majority() {
+        assert_eq!(votes_needed(1, 50), 1);
+        assert_eq!(votes_needed(3, 50), 2);
+        assert_eq!(votes_needed(4, 50), 3);
+        assert_eq!(votes_needed(4, 100), 4);
+        assert_eq!(votes_needed(0, 50), 1);
+    }
+
+    #[test]
+    fn test_vote_passes_once_and_repeats_do_not_count() {
+        let mut votes = VoteBox::default();
+        let now = Instant::now();
+        assert_eq!(
+            votes.cast("alice", VoteSubject::Seek(600.0), 3, TIMEOUT, now),
+            VoteCast::Counted {
+                votes: 1,
+                needed: 3
+            }
+        );
+        assert_eq!(
+            votes.cast("Alice", VoteSubject::Seek(600.0), 3, TIMEOUT, now),
+            VoteCast::Repeated
+        );
+        votes.cast("bob", VoteSubject::Seek(603.0), 3, TIMEOUT, now);
+        assert_eq!(
+            votes.cast("carol", VoteSubject::Seek(598.0), 3, TIMEOUT, now),
+            VoteCast::Passed {
+                subject: VoteSubject::Seek(600.0),
+                votes: 3
+            }
+        );
+        assert_eq!(
+            votes.cast("dave", VoteSubject::Seek(600.0), 3, TIMEOUT, now),
+            VoteCast::Counted {
+                votes: 1,
+                needed: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_votes_expire() {
+        let mut votes = VoteBox::default();
+        let now = Instant::now();
+        votes.cast("alice", VoteSubject::Pause, 2, TIMEOUT, now);
+        assert_eq!(
+            votes.cast("bob", VoteSubject::Pause, 2, TIMEOUT, now + TIMEOUT),
+            VoteCast::Counted {
+                votes: 1,
+                needed: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,118 @@
+// Connect-on-launch command handlers
+
+use crate::app_state::AppState;
+use crate::commands::connection::{connect_to_server, emit_error_message};
+use crate::config::SyncplayConfig;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{Manager, State};
+use tokio::time::{sleep, Duration};
+
+/// Seconds the user has to cancel before the launch connect starts
+pub const LAUNCH_CONNECT_COUNTDOWN_SECONDS: u64 = 5;
+/// Longest wait for the first media index scan before connecting anyway
+const MEDIA_INDEX_PRIME_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Payload of the `launch-connect-countdown` event; `remaining` is 0 once the connect starts
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchConnectCountdown {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub room: String,
+    pub remaining: u64,
+}
+
+/// The saved server, room and username, when all of them are filled in
+fn launch_target(config: &SyncplayConfig) -> Option<LaunchConnectCountdown> {
+    let host = config.server.host.trim();
+    let username = config.user.username.trim();
+    let room = config.user.default_room.trim();
+    if host.is_empty() || config.server.port == 0 || username.is_empty() || room.is_empty() {
+        return None;
+    }
+    Some(LaunchConnectCountdown {
+        host: host.to_string(),
+        port: config.server.port,
+        username: username.to_string(),
+        room: room.to_string(),
+        remaining: LAUNCH_CONNECT_COUNTDOWN_SECONDS,
+    })
+}
+
+fn launch_connect_cancelled(state: &Arc<AppState>) -> bool {
+    *state.launch_connect_cancelled.lock() || state.is_connected()
+}
+
+/// Start the connect-on-launch countdown if the config asks for it
+pub fn schedule_launch_connect(state: Arc<AppState>) {
+    let config = state.config.lock().clone();
+    if !config.user.connects_on_launch() {
+        return;
+    }
+    let Some(target) = launch_target(&config) else {
+        tracing::info!("Skipping connect on launch: saved server, room or username is missing");
+        return;
+    };
+    *state.launch_connect_cancelled.lock() = false;
+    let password = config.server.password.clone().filter(|p| !p.is_empty());
+    tauri::async_runtime::spawn(async move {
+        run_launch_connect(state, target, password).await;
+    });
+}
+
+async fn run_launch_connect(
+    state: Arc<AppState>,
+    mut target: LaunchConnectCountdown,
+    password: Option<String>,
+) {
+    // Connecting before the index is primed would miss files the room is already playing
+    let started = Instant::now();
+    while !state.media_index.is_primed() && started.elapsed() < MEDIA_INDEX_PRIME_TIMEOUT {
+        if launch_connect_cancelled(&state) {
+            return;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    for remaining in (1..=LAUNCH_CONNECT_COUNTDOWN_SECONDS).rev() {
+        if launch_connect_cancelled(&state) {
+            return;
+        }
+        target.remaining = remaining;
+        state.emit_event("launch-connect-countdown", target.clone());
+        sleep(Duration::from_secs(1)).await;
+    }
+    if launch_connect_cancelled(&state) {
+        return;
+    }
+    target.remaining = 0;
+    state.emit_event("launch-connect-countdown", target.clone());
+    let Some(app) = state.app_handle.lock().clone() else {
+        return;
+    };
+    let app_state = app.state::<Arc<AppState>>();
+    if let Err(e) = connect_to_server(
+        target.host,
+        target.port,
+        target.username,
+        target.room,
+        password,
+        None,
+        app.clone(),
+        app_state,
+    )
+    .await
+    {
+        tracing::warn!("Connect on launch failed: {}", e);
+        emit_error_message(&state, &state.tr("launch-connect-failed", &[&e]));
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_launch_connect(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    *state.launch_connect_cancelled.lock() = true;
+    state.emit_event("launch-connect-cancelled", serde_json::json!({}));
+    Ok(())
+}
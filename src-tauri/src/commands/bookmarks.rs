@@ -0,0 +1,144 @@
+// Bookmark command handlers
+
+use crate::app_state::AppState;
+use crate::client::bookmarks::Bookmark;
+use crate::client::protocol::BOOKMARK_CHAT_PREFIX;
+use crate::commands::connection::emit_system_message;
+use crate::network::messages::{ChatMessage, ProtocolMessage};
+use crate::player::controller::ensure_player_connected;
+use crate::utils::{format_time, same_filename};
+use std::sync::Arc;
+use tauri::State;
+
+/// Bookmark the current position of the open file, optionally sharing it with the room
+#[tauri::command]
+pub async fn add_bookmark(
+    label: String,
+    share: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Bookmark, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Bookmark label cannot be empty".to_string());
+    }
+    let Some(file) = state.session.client_state.get_file() else {
+        return Err("No file is open".to_string());
+    };
+    let player = state.player.lock().clone();
+    let Some(position) = player.and_then(|player| player.get_state().position) else {
+        return Err("Player position is unknown".to_string());
+    };
+    let bookmark = state.bookmarks.add(file, position, label, None);
+    persist_bookmarks(state.inner());
+    if share.unwrap_or(false) {
+        share_bookmark(state.inner(), &bookmark)?;
+    }
+    Ok(bookmark)
+}
+
+#[tauri::command]
+pub async fn get_bookmarks(
+    file: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Bookmark>, String> {
+    Ok(state.bookmarks.entries(file.as_deref()))
+}
+
+#[tauri::command]
+pub async fn remove_bookmark(id: u32, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    if !state.bookmarks.remove(id) {
+        return Err(format!("Bookmark {} not found", id));
+    }
+    persist_bookmarks(state.inner());
+    Ok(())
+}
+
+/// Seek to a bookmark; the seek reaches the room like any other local seek
+#[tauri::command]
+pub async fn jump_to_bookmark(id: u32, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    let Some(bookmark) = state.bookmarks.get(id) else {
+        return Err(format!("Bookmark {} not found", id));
+    };
+    let current_file = state.session.client_state.get_file();
+    if !same_filename(current_file.as_deref(), Some(&bookmark.file)) {
+        return Err(format!("Bookmark belongs to {}", bookmark.file));
+    }
+    ensure_player_connected(state.inner()).await?;
+    let player = state.player.lock().clone();
+    let Some(player) = player else {
+        return Err("Player not connected".to_string());
+    };
+    player
+        .set_position(bookmark.position)
+        .await
+        .map_err(|e| format!("Failed to seek: {}", e))
+}
+
+/// Store a bookmark announced by another user for the file they are playing
+pub(crate) fn receive_shared_bookmark(
+    state: &Arc<AppState>,
+    actor: &str,
+    position: f64,
+    label: &str,
+) {
+    let file = state
+        .session
+        .client_state
+        .get_user(actor)
+        .and_then(|user| user.file)
+        .or_else(|| state.session.client_state.get_file());
+    let Some(file) = file else {
+        tracing::debug!("Ignoring shared bookmark without a known file");
+        return;
+    };
+    state
+        .bookmarks
+        .add(file, position, label, Some(actor.to_string()));
+    persist_bookmarks(state);
+    emit_system_message(
+        state,
+        &state.tr(
+            "bookmark-shared",
+            &[&actor, &label, &format_time(position, false)],
+        ),
+    );
+}
+
+fn share_bookmark(state: &Arc<AppState>, bookmark: &Bookmark) -> Result<(), String> {
+    if !state.session.server_features.lock().chat {
+        return Err("Sharing bookmarks needs chat support on the server".to_string());
+    }
+    let max_length = state
+        .session
+        .server_features
+        .lock()
+        .max_chat_message_length
+        .unwrap_or(150);
+    let prefix = format!("{}{:.1} ", BOOKMARK_CHAT_PREFIX, bookmark.position);
+    let label: String = bookmark
+        .label
+        .chars()
+        .take(max_length.saturating_sub(prefix.chars().count()))
+        .collect();
+    let message = ProtocolMessage::Chat {
+        Chat: ChatMessage::Text(format!("{}{}", prefix, label)),
+    };
+    let connection = state.session.connection.lock().clone();
+    let Some(connection) = connection else {
+        return Err("Not connected to server".to_string());
+    };
+    connection
+        .send(message)
+        .map_err(|e| format!("Failed to share bookmark: {}", e))
+}
+
+fn persist_bookmarks(state: &Arc<AppState>) {
+    let bookmarks = state.bookmarks.entries(None);
+    let app = state.app_handle.lock().clone();
+    if let Some(app) = app {
+        if let Err(e) = crate::config::save_bookmarks(&app, &bookmarks) {
+            tracing::warn!("Failed to persist bookmarks: {}", e);
+        }
+    }
+    state.emit_event("bookmarks-updated", bookmarks);
+}
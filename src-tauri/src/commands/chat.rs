@@ -1,15 +1,20 @@
 // Chat command handlers
 
 use crate::app_state::AppState;
-use crate::client::chat::ChatCommand;
+use crate::client::chat::{
+    expand_emoji_shortcodes, normalize_chat_text, split_chat_message, ChatCommand, CHAT_RATE_BURST,
+};
+use crate::client::protocol::ClientContext;
+use crate::client::session_log::{SessionLog, SessionLogFormat};
 use crate::commands::connection::{reidentify_as_controller, store_control_password};
+use crate::config::save_config;
 use crate::network::messages::ProtocolMessage;
 use crate::network::messages::{
     ChatMessage as ProtocolChatMessage, ReadyState, RoomInfo, SetMessage,
 };
 use crate::utils::{parse_controlled_room_input, truncate_text};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Runtime, State};
 
 #[tauri::command]
 pub async fn send_chat_message(
@@ -19,6 +24,94 @@ pub async fn send_chat_message(
     send_chat_message_inner(state.inner(), &message).await
 }
 
+#[tauri::command]
+pub async fn ignore_user<R: Runtime>(
+    app: AppHandle<R>,
+    username: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let username = username.trim().to_string();
+    if username.is_empty() {
+        return Err("Username cannot be empty".to_string());
+    }
+    tracing::info!("Ignoring user: {}", username);
+    update_ignored_users(&app, state.inner(), |ignored| {
+        if !ignored.contains(&username) {
+            ignored.push(username);
+        }
+    })
+}
+
+#[tauri::command]
+pub async fn unignore_user<R: Runtime>(
+    app: AppHandle<R>,
+    username: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    tracing::info!("Unignoring user: {}", username.trim());
+    update_ignored_users(&app, state.inner(), |ignored| {
+        ignored.retain(|name| name != username.trim());
+    })
+}
+
+/// Stop showing the current server MOTD until it changes
+#[tauri::command]
+pub async fn dismiss_motd<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let Some(motd) = state.session.motd.lock().clone() else {
+        return Err("No server MOTD to dismiss".to_string());
+    };
+    let config = {
+        let mut config = state.config.lock();
+        config
+            .user
+            .dismissed_motds
+            .insert(motd.server, motd.fingerprint);
+        config.clone()
+    };
+    save_config(&app, &config).map_err(|e| format!("Failed to save configuration: {}", e))?;
+    state.emit_event("config-updated", config);
+    Ok(())
+}
+
+/// Write the chat history and room activity of this session to a file
+#[tauri::command]
+pub async fn export_session_log(
+    path: String,
+    format: SessionLogFormat,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let messages = state.chat.get_messages();
+    let events = state.session.room_timeline.entries(None);
+    let log = SessionLog {
+        server: state.server_address(),
+        room: state.session.client_state.get_room(),
+        exported_at: chrono::Utc::now(),
+        messages: &messages,
+        events: &events,
+    };
+    tokio::fs::write(&path, log.render(format))
+        .await
+        .map_err(|e| format!("Failed to write session log: {}", e))
+}
+
+fn update_ignored_users<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &Arc<AppState>,
+    update: impl FnOnce(&mut Vec<String>),
+) -> Result<(), String> {
+    let config = {
+        let mut config = state.config.lock();
+        update(&mut config.user.ignored_users);
+        config.clone()
+    };
+    save_config(app, &config).map_err(|e| format!("Failed to save configuration: {}", e))?;
+    state.emit_event("config-updated", config);
+    Ok(())
+}
+
 pub async fn send_chat_message_from_player(
     state: &Arc<AppState>,
     message: &str,
@@ -27,7 +120,8 @@ pub async fn send_chat_message_from_player(
 }
 
 async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result<(), String> {
-    let trimmed = message.trim();
+    let normalized = normalize_chat_text(message);
+    let trimmed = normalized.trim();
     if trimmed.is_empty() {
         return Ok(());
     }
@@ -36,27 +130,28 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
     if !config.user.chat_input_enabled {
         return Err("Chat input is disabled".to_string());
     }
-    if !state.server_features.lock().chat {
+    if !state.session.server_features.lock().chat {
         return Err("Chat is disabled by the server".to_string());
     }
 
     let max_length = state
+        .session
         .server_features
         .lock()
         .max_chat_message_length
         .unwrap_or(150);
-    let message = truncate_text(trimmed, max_length);
-    tracing::info!("Sending chat message: {}", message);
+    tracing::info!("Sending chat message: {}", trimmed);
 
     if !state.is_connected() {
         return Err("Not connected to server".to_string());
     }
 
-    if let Some(command) = ChatCommand::parse(&message) {
+    if let Some(command) = ChatCommand::parse(&truncate_text(trimmed, max_length)) {
         match command {
             ChatCommand::Room(room) => {
                 tracing::info!("Command: Change room to {}", room);
                 let max_len = state
+                    .session
                     .server_features
                     .lock()
                     .max_room_name_length
@@ -68,11 +163,11 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
                 if let Some(password) = control_password {
                     store_control_password(state, &room, &password, true);
                 }
-                state.client_state.set_room(room);
+                state.session.client_state.set_room(room);
                 let set_msg = ProtocolMessage::Set {
                     Set: Box::new(SetMessage {
                         room: Some(RoomInfo {
-                            name: state.client_state.get_room(),
+                            name: state.session.client_state.get_room(),
                             password: None,
                         }),
                         file: None,
@@ -91,7 +186,7 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
             }
             ChatCommand::List => {
                 tracing::info!("Command: List users");
-                let users = state.client_state.get_users();
+                let users = state.session.client_state.get_users();
                 let user_list: Vec<String> = users
                     .iter()
                     .map(|u| format!("{} ({})", u.username, u.room))
@@ -124,11 +219,11 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
             }
             ChatCommand::Ready => {
                 tracing::info!("Command: Set ready");
-                if !state.server_features.lock().readiness {
+                if !state.session.server_features.lock().readiness {
                     return Err("Ready state is not supported by the server".to_string());
                 }
-                state.client_state.set_ready(true);
-                let username = state.client_state.get_username();
+                state.session.client_state.set_ready(true);
+                let username = state.session.client_state.get_username();
                 let set_msg = ProtocolMessage::Set {
                     Set: Box::new(SetMessage {
                         room: None,
@@ -151,11 +246,11 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
             }
             ChatCommand::Unready => {
                 tracing::info!("Command: Set unready");
-                if !state.server_features.lock().readiness {
+                if !state.session.server_features.lock().readiness {
                     return Err("Ready state is not supported by the server".to_string());
                 }
-                state.client_state.set_ready(false);
-                let username = state.client_state.get_username();
+                state.session.client_state.set_ready(false);
+                let username = state.session.client_state.get_username();
                 let set_msg = ProtocolMessage::Set {
                     Set: Box::new(SetMessage {
                         room: None,
@@ -178,7 +273,7 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
             }
             ChatCommand::SetReady(username) => {
                 tracing::info!("Command: Set other user ready");
-                if !state.server_features.lock().set_others_readiness {
+                if !state.session.server_features.lock().set_others_readiness {
                     return Err("Readiness override is not supported by the server".to_string());
                 }
                 let set_msg = ProtocolMessage::Set {
@@ -203,7 +298,7 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
             }
             ChatCommand::SetNotReady(username) => {
                 tracing::info!("Command: Set other user not ready");
-                if !state.server_features.lock().set_others_readiness {
+                if !state.session.server_features.lock().set_others_readiness {
                     return Err("Readiness override is not supported by the server".to_string());
                 }
                 let set_msg = ProtocolMessage::Set {
@@ -243,10 +338,26 @@ async fn send_chat_message_inner(state: &Arc<AppState>, message: &str) -> Result
         }
         Ok(())
     } else {
-        let chat_msg = ProtocolMessage::Chat {
-            Chat: ProtocolChatMessage::Text(message.clone()),
-        };
-        send_to_server_arc(state, chat_msg)?;
+        let parts = split_chat_message(&expand_emoji_shortcodes(trimmed), max_length);
+        if parts.len() > CHAT_RATE_BURST as usize {
+            return Err(format!(
+                "Message is too long ({} parts, at most {} allowed)",
+                parts.len(),
+                CHAT_RATE_BURST
+            ));
+        }
+        if let Err(wait) = state.chat.try_acquire_send(parts.len() as u32) {
+            return Err(format!(
+                "Sending messages too quickly, try again in {}s",
+                wait.as_secs_f64().ceil()
+            ));
+        }
+        for part in parts {
+            let chat_msg = ProtocolMessage::Chat {
+                Chat: ProtocolChatMessage::Text(part),
+            };
+            send_to_server_arc(state, chat_msg)?;
+        }
         Ok(())
     }
 }
@@ -255,7 +366,7 @@ fn send_to_server(
     state: &State<'_, Arc<AppState>>,
     message: ProtocolMessage,
 ) -> Result<(), String> {
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
@@ -265,7 +376,7 @@ fn send_to_server(
 }
 
 fn send_to_server_arc(state: &Arc<AppState>, message: ProtocolMessage) -> Result<(), String> {
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
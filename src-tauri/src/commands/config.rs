@@ -1,7 +1,9 @@
 // Configuration command handlers
 
 use crate::app_state::AppState;
-use crate::config::{save_config, SyncplayConfig};
+use crate::config::{
+    changed_sections, save_config, ConfigSection, ConfigSectionEvent, FieldError, SyncplayConfig,
+};
 use std::sync::Arc;
 use tauri::{AppHandle, Runtime, State};
 
@@ -12,6 +14,12 @@ pub async fn get_config(state: State<'_, Arc<AppState>>) -> Result<SyncplayConfi
     Ok(state.config.lock().clone())
 }
 
+/// Check a candidate config without applying it
+#[tauri::command]
+pub async fn validate_config(candidate: SyncplayConfig) -> Result<Vec<FieldError>, String> {
+    Ok(crate::config::validate_config(&candidate))
+}
+
 #[tauri::command]
 pub async fn update_config<R: Runtime>(
     app: AppHandle<R>,
@@ -32,18 +40,22 @@ pub async fn update_config<R: Runtime>(
         format!("Failed to save configuration: {}", e)
     })?;
 
-    *state.config.lock() = config.clone();
-    state.sync_engine.lock().update_from_config(&config.user);
-    if state
-        .media_index
-        .update_directories(config.player.media_directories.clone())
+    let previous = std::mem::replace(&mut *state.config.lock(), config.clone());
+    let sections = changed_sections(&previous, &config);
+    if sections.contains(&ConfigSection::Sync) {
+        state.sync_engine.lock().update_from_config(&config.user);
+    }
+    if sections.contains(&ConfigSection::MediaIndex)
+        && state
+            .media_index
+            .update_directories(config.player.media_directories.clone())
     {
         state
             .media_index
             .clone()
             .request_refresh(state.inner().clone());
     }
-    {
+    if sections.contains(&ConfigSection::Autoplay) {
         let mut autoplay = state.autoplay.lock();
         autoplay.enabled = config.user.autoplay_enabled;
         autoplay.min_users = config.user.autoplay_min_users;
@@ -54,7 +66,32 @@ pub async fn update_config<R: Runtime>(
             autoplay.countdown_remaining = 0;
         }
     }
+    if sections.contains(&ConfigSection::Remote) {
+        crate::integrations::remote::apply_config(state.inner(), &config.remote);
+    }
+    if sections.contains(&ConfigSection::Scripts) {
+        crate::integrations::scripts::start(state.inner(), &config.scripts);
+    }
+    if sections.contains(&ConfigSection::Metrics) {
+        crate::integrations::metrics::apply_config(state.inner(), &config.metrics);
+    }
     state.emit_event("config-updated", config.clone());
+    for section in &sections {
+        state.emit_event(
+            "config-section-updated",
+            ConfigSectionEvent { section: *section },
+        );
+    }
+    if [
+        ConfigSection::Osd,
+        ConfigSection::Chat,
+        ConfigSection::Player,
+    ]
+    .iter()
+    .any(|section| sections.contains(section))
+    {
+        crate::player::controller::reload_player_settings(state.inner(), &previous, &config).await;
+    }
 
     Ok(())
 }
@@ -1,146 +1,150 @@
 // Connection command handlers
 
 use crate::app_state::{
-    AppState, ConnectionSnapshot, ConnectionStatusEvent, ServerFeatures, WarningTimerState,
-    WarningTimers,
+    AppState, ConnectionSnapshot, ConnectionStatusEvent, DowngradeReason, FeatureDowngrade,
+    FeatureNegotiation, RoomPasswordRequest, ServerFeatureReport, ServerFeatures,
+    WarningTimerState,
 };
-use crate::client::sync::{
-    FASTFORWARD_BEHIND_THRESHOLD, FASTFORWARD_EXTRA_TIME, FASTFORWARD_RESET_THRESHOLD,
+use crate::client::chat::ChatManager;
+use crate::client::endgame::{MemberProgress, RoomFinishedEvent};
+use crate::client::file_diff::{
+    compare_files, same_duration, FileDifference, FileDifferencesEvent, FileSide,
 };
+use crate::client::motd::{ServerMessageKind, ServerMotdEvent};
+use crate::client::playlist::Playlist;
+use crate::client::protocol::{
+    self, ChatOutcome, ClientContext, UserChange, FALLBACK_MAX_ROOM_NAME_LENGTH,
+    FALLBACK_MAX_USERNAME_LENGTH,
+};
+use crate::client::ready::ReadyCheck;
+use crate::client::room_events::{RoomEvent, RoomEventKind};
+use crate::client::session_actor::SessionCommand;
+use crate::client::session_stats::{SessionStats, SessionSummary};
+use crate::client::state::ClientState;
+use crate::client::sync::{emulated_slowdown_position, SyncCorrection};
+use crate::client::userlist::group_users_by_room;
 use crate::commands::playlist::apply_playlist_index_from_server;
-use crate::config::{save_config, ServerConfig};
-use crate::network::connection::Connection;
+use crate::config::{
+    save_config, OsdCategory, PauseOnLeave, ReconnectGiveUpAction, ReconnectPolicy, ServerConfig,
+    SyncplayConfig,
+};
+use crate::integrations::metrics::MetricsSnapshot;
+use crate::integrations::scripts::{self, ScriptEvent};
+use crate::network::compat::ServerCompat;
+use crate::network::connection::{CloseReason, Connection, ConnectionMetrics};
 use crate::network::messages::{
     ClientFeatures, ControllerAuth, HelloMessage, IgnoringInfo, NewControlledRoom, PingInfo,
-    PlayState, ProtocolMessage, RoomInfo, SetMessage, StateMessage, TLSMessage, UserUpdate,
+    PlayState, ProtocolMessage, RoomInfo, SetMessage, StateMessage, TLSMessage,
 };
 use crate::network::tls::create_tls_connector;
-use crate::player::backend::PlayerBackend;
+use crate::notifications;
+use crate::player::backend::{OsdStyle, PlayerBackend};
 use crate::player::controller::{
     ensure_player_connected, load_media_by_name, load_placeholder_if_empty, stop_player,
 };
 use crate::player::properties::PlayerState;
 use crate::utils::{
-    is_controlled_room, parse_controlled_room_input, same_filename, strip_control_password,
-    truncate_text, version_meets_min,
+    format_time, hash_server_password, is_controlled_room, is_placeholder_username,
+    parse_controlled_room_input, parse_join_link, same_filename, strip_control_password,
+    truncate_text,
 };
+use async_trait::async_trait;
+use rand::Rng;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tauri::{AppHandle, Runtime, State};
+use tauri::{AppHandle, Manager, Runtime, State};
 use tokio::sync::mpsc;
 use tokio::time::{interval, sleep, Duration};
 
 const AUTOPLAY_DELAY_SECONDS: i32 = 3;
-const DIFFERENT_DURATION_THRESHOLD: f64 = 2.5;
+/// Seconds before a scheduled start during which the OSD counts down every second
+const SCHEDULED_START_COUNTDOWN_SECONDS: u64 = 10;
 const WARNING_OSD_INTERVAL_SECONDS: u64 = 1;
+const SYNC_STATS_INTERVAL_TICKS: u32 = 5;
 const OSD_WARNING_MESSAGE_DURATION_SECONDS: u32 = 5;
 const OSD_MESSAGE_SEPARATOR: &str = "; ";
 const LAST_PAUSED_DIFF_THRESHOLD_SECONDS: f64 = 2.0;
-const RECONNECT_RETRIES: u32 = 999;
-const RECONNECT_BASE_DELAY_SECONDS: f64 = 0.1;
-const RECONNECT_MAX_EXPONENT: u32 = 5;
-const CONTROLLED_ROOMS_MIN_VERSION: &str = "1.3.0";
-const USER_READY_MIN_VERSION: &str = "1.3.0";
-const SHARED_PLAYLIST_MIN_VERSION: &str = "1.4.0";
-const CHAT_MIN_VERSION: &str = "1.5.0";
-const FEATURE_LIST_MIN_VERSION: &str = "1.5.0";
-const SET_OTHERS_READINESS_MIN_VERSION: &str = "1.7.2";
-const FALLBACK_MAX_CHAT_MESSAGE_LENGTH: usize = 50;
-const FALLBACK_MAX_USERNAME_LENGTH: usize = 16;
-const FALLBACK_MAX_ROOM_NAME_LENGTH: usize = 35;
-const FALLBACK_MAX_FILENAME_LENGTH: usize = 250;
+const CLOCK_SKEW_WARNING_SECONDS: f64 = 2.0;
 const IGNORE_SEEK_AFTER_REWIND_SECONDS: f64 = 1.0;
 const IGNORE_SEEK_AFTER_REWIND_POSITION_THRESHOLD: f64 = 5.0;
+/// Time given to the connection task to close the socket before the app exits
+const SHUTDOWN_DISCONNECT_GRACE: Duration = Duration::from_millis(200);
+/// Longest wait for the player to quit before the app exits anyway
+const SHUTDOWN_PLAYER_TIMEOUT: Duration = Duration::from_secs(3);
+/// Longest wait for the room state before adding an opened file to the playlist
+const OPEN_FILE_ROOM_STATE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest wait for the server to accept or reject a password check
+const PASSWORD_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Session summaries, one JSON object per line, in the app data directory
+const SESSION_HISTORY_FILE: &str = "session-history.jsonl";
 
 fn update_server_features(
     state: &Arc<AppState>,
     server_version: &str,
     feature_list: Option<Value>,
 ) {
-    let mut features = ServerFeatures {
-        feature_list: version_meets_min(server_version, FEATURE_LIST_MIN_VERSION),
-        shared_playlists: version_meets_min(server_version, SHARED_PLAYLIST_MIN_VERSION),
-        chat: version_meets_min(server_version, CHAT_MIN_VERSION),
-        readiness: version_meets_min(server_version, USER_READY_MIN_VERSION),
-        managed_rooms: version_meets_min(server_version, CONTROLLED_ROOMS_MIN_VERSION),
-        persistent_rooms: false,
-        set_others_readiness: version_meets_min(server_version, SET_OTHERS_READINESS_MIN_VERSION),
-        max_chat_message_length: Some(FALLBACK_MAX_CHAT_MESSAGE_LENGTH),
-        max_username_length: Some(FALLBACK_MAX_USERNAME_LENGTH),
-        max_room_name_length: Some(FALLBACK_MAX_ROOM_NAME_LENGTH),
-        max_filename_length: Some(FALLBACK_MAX_FILENAME_LENGTH),
-    };
-
-    if let Some(Value::Object(map)) = feature_list {
-        if let Some(value) = map.get("featureList").and_then(|v| v.as_bool()) {
-            features.feature_list = value;
-        }
-        if let Some(value) = map.get("sharedPlaylists").and_then(|v| v.as_bool()) {
-            features.shared_playlists = value;
-        }
-        if let Some(value) = map.get("chat").and_then(|v| v.as_bool()) {
-            features.chat = value;
-        }
-        if let Some(value) = map.get("readiness").and_then(|v| v.as_bool()) {
-            features.readiness = value;
-        }
-        if let Some(value) = map.get("managedRooms").and_then(|v| v.as_bool()) {
-            features.managed_rooms = value;
-        }
-        if let Some(value) = map.get("persistentRooms").and_then(|v| v.as_bool()) {
-            features.persistent_rooms = value;
-        }
-        if let Some(value) = map.get("setOthersReadiness").and_then(|v| v.as_bool()) {
-            features.set_others_readiness = value;
-        }
-        if let Some(value) = map.get("maxChatMessageLength").and_then(|v| v.as_u64()) {
-            features.max_chat_message_length = Some(value as usize);
-        }
-        if let Some(value) = map.get("maxUsernameLength").and_then(|v| v.as_u64()) {
-            features.max_username_length = Some(value as usize);
-        }
-        if let Some(value) = map.get("maxRoomNameLength").and_then(|v| v.as_u64()) {
-            features.max_room_name_length = Some(value as usize);
-        }
-        if let Some(value) = map.get("maxFilenameLength").and_then(|v| v.as_u64()) {
-            features.max_filename_length = Some(value as usize);
-        }
+    let (features, downgrades) =
+        protocol::resolve_server_features(server_version, feature_list.as_ref());
+    for downgrade in &downgrades {
+        tracing::info!(
+            "Server feature {} unavailable: {:?}",
+            downgrade.feature,
+            downgrade.reason
+        );
     }
+    if let Some(connection) = state.session.connection.lock().clone() {
+        connection.set_compat(ServerCompat {
+            shared_playlists: features.shared_playlists,
+            readiness: features.readiness,
+            chat: features.chat,
+            managed_rooms: features.managed_rooms,
+        });
+    }
+    *state.session.server_features.lock() = features;
 
-    *state.server_features.lock() = features.clone();
-
-    if !version_meets_min(server_version, SHARED_PLAYLIST_MIN_VERSION) {
+    // One notice for everything an old server lacks instead of a failure per feature
+    let too_old: Vec<String> = downgrades
+        .iter()
+        .filter(|downgrade| downgrade.reason == DowngradeReason::ServerTooOld)
+        .filter_map(|downgrade| feature_label_key(&downgrade.feature))
+        .map(|key| state.tr(key, &[]))
+        .collect();
+    if !too_old.is_empty() {
         emit_error_message(
             state,
-            &format!(
-                "Shared playlists require server version {} or later",
-                SHARED_PLAYLIST_MIN_VERSION
-            ),
+            &state.tr("server-too-old", &[&server_version, &too_old.join(", ")]),
         );
-    } else if !features.shared_playlists {
-        emit_error_message(state, "Shared playlists are disabled by the server");
     }
+    if downgrades.iter().any(|downgrade| {
+        downgrade.feature == "sharedPlaylists"
+            && downgrade.reason == DowngradeReason::DisabledByServer
+    }) {
+        emit_error_message(state, &state.tr("shared-playlists-disabled", &[]));
+    }
+    *state.session.feature_negotiation.lock() = Some(FeatureNegotiation {
+        raw_features: feature_list,
+        downgrades,
+    });
 }
 
-struct EstablishedConnection {
-    connection: Arc<Connection>,
-    receiver: mpsc::UnboundedReceiver<ProtocolMessage>,
+/// Catalog key naming a server feature in notices; `None` for protocol internals
+fn feature_label_key(feature: &str) -> Option<&'static str> {
+    match feature {
+        "sharedPlaylists" => Some("feature-shared-playlists"),
+        "chat" => Some("feature-chat"),
+        "readiness" => Some("feature-readiness"),
+        "managedRooms" => Some("feature-managed-rooms"),
+        "setOthersReadiness" => Some("feature-set-others-readiness"),
+        _ => None,
+    }
 }
 
-async fn establish_connection(
-    state: &Arc<AppState>,
+fn build_hello(
+    config: &crate::config::SyncplayConfig,
     snapshot: &ConnectionSnapshot,
-    emit_reachout: bool,
-) -> Result<EstablishedConnection, String> {
-    let connection = Arc::new(Connection::new());
-    let (receiver, peer_address) = connection
-        .connect(snapshot.host.clone(), snapshot.port)
-        .await
-        .map_err(|e| format!("Connection failed: {}", e))?;
-
-    tracing::info!("Successfully connected to server");
-
-    let config = state.config.lock().clone();
+) -> HelloMessage {
     let client_features = ClientFeatures {
         shared_playlists: Some(config.user.shared_playlist_enabled),
         chat: Some(true),
@@ -150,24 +154,47 @@ async fn establish_connection(
         feature_list: Some(true),
         set_others_readiness: Some(true),
         ui_mode: Some("GUI".to_string()),
+        utc_offset: config
+            .user
+            .share_utc_offset
+            .then(|| chrono::Local::now().offset().local_minus_utc() / 60),
     };
-    let features_value = serde_json::to_value(client_features).ok();
-
-    let hello_payload = HelloMessage {
+    HelloMessage {
         username: snapshot.username.clone(),
-        password: snapshot.password.clone(),
+        password: snapshot.password_hash.clone(),
         room: Some(RoomInfo {
             name: snapshot.room.clone(),
-            password: None,
+            password: snapshot.room_password.clone(),
         }),
         version: "1.2.255".to_string(),
         realversion: "1.7.5".to_string(),
-        features: features_value,
+        features: serde_json::to_value(client_features).ok(),
         motd: None,
-    };
+    }
+}
+
+struct EstablishedConnection {
+    connection: Arc<Connection>,
+    receiver: mpsc::UnboundedReceiver<ProtocolMessage>,
+}
+
+async fn establish_connection(
+    state: &Arc<AppState>,
+    snapshot: &ConnectionSnapshot,
+    emit_reachout: bool,
+) -> Result<EstablishedConnection, String> {
+    let bind_address = state.config.lock().server.bind_ip();
+    let connection = Arc::new(Connection::with_traffic(state.metrics.traffic.clone()));
+    let (receiver, peer_address) = connection
+        .connect(snapshot.host.clone(), snapshot.port, bind_address)
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    tracing::info!("Successfully connected to server");
 
-    *state.last_hello.lock() = Some(hello_payload);
-    *state.hello_sent.lock() = false;
+    let config = state.config.lock().clone();
+    *state.session.last_hello.lock() = Some(build_hello(&config, snapshot));
+    *state.session.hello_sent.lock() = false;
 
     let client_supports_tls = create_tls_connector().is_ok();
     *state.client_supports_tls.lock() = client_supports_tls;
@@ -175,17 +202,25 @@ async fn establish_connection(
 
     if emit_reachout {
         if let Some(peer_address) = peer_address {
+            let family = if peer_address.is_ipv6() {
+                "IPv6"
+            } else {
+                "IPv4"
+            };
             emit_system_message(
                 state,
-                &format!("Successfully reached {} ({})", snapshot.host, peer_address),
+                &state.tr(
+                    "reached-server-with-address",
+                    &[&snapshot.host, &peer_address.ip(), &family],
+                ),
             );
         } else {
-            emit_system_message(state, &format!("Successfully reached {}", snapshot.host));
+            emit_system_message(state, &state.tr("reached-server", &[&snapshot.host]));
         }
     }
 
     if client_supports_tls && server_supports_tls {
-        emit_system_message(state, "Attempting secure connection");
+        emit_system_message(state, &state.tr("attempting-tls", &[]));
         let tls_request = ProtocolMessage::TLS {
             TLS: TLSMessage {
                 start_tls: Some("send".to_string()),
@@ -193,32 +228,23 @@ async fn establish_connection(
         };
         if let Err(e) = connection.send(tls_request) {
             tracing::error!("Failed to send TLS request: {}", e);
-            state.emit_event(
-                "tls-status-changed",
-                serde_json::json!({ "status": "unsupported" }),
-            );
+            state.set_tls_status("unsupported");
             send_hello(state);
         } else {
             tracing::info!("Sent TLS request");
-            state.emit_event(
-                "tls-status-changed",
-                serde_json::json!({ "status": "pending" }),
-            );
+            state.set_tls_status("pending");
         }
     } else {
         if !client_supports_tls {
-            emit_system_message(state, "This client does not support TLS");
+            emit_system_message(state, &state.tr("client-no-tls", &[]));
         } else if !server_supports_tls {
-            emit_error_message(state, "This server does not support TLS");
+            emit_error_message(state, &state.tr("server-no-tls", &[]));
         }
-        state.emit_event(
-            "tls-status-changed",
-            serde_json::json!({ "status": "unsupported" }),
-        );
+        state.set_tls_status("unsupported");
         send_hello(state);
     }
 
-    *state.connection.lock() = Some(connection.clone());
+    *state.session.connection.lock() = Some(connection.clone());
 
     Ok(EstablishedConnection {
         connection,
@@ -233,16 +259,29 @@ async fn finalize_connection_setup(
     server_label: String,
 ) {
     let config = state.config.lock().clone();
-    state.client_state.set_username(snapshot.username.clone());
-    state.client_state.set_room(snapshot.room.clone());
-    *state.had_first_playlist_index.lock() = false;
-    *state.playlist_may_need_restoring.lock() = false;
-    *state.last_advance_time.lock() = None;
-    *state.last_rewind_time.lock() = None;
-    *state.last_updated_file_time.lock() = None;
-    *state.last_paused_on_leave_time.lock() = None;
-    *state.last_global_update.lock() = None;
-    state.sync_engine.lock().update_from_config(&config.user);
+    state
+        .session
+        .client_state
+        .set_username(snapshot.username.clone());
+    state.session.client_state.set_room(snapshot.room.clone());
+    state.session.reset_for_new_connection();
+    {
+        let restored_room = state.restored_playlist_room.lock().take();
+        let mut may_restore = state.playlist_may_need_restoring.lock();
+        let restore = *may_restore || restored_room.as_deref() == Some(snapshot.room.as_str());
+        *may_restore = restore && !state.session.playlist.is_empty();
+    }
+    let corrections = {
+        let mut engine = state.sync_engine.lock();
+        engine.update_from_config(&config.user);
+        engine.reset_stats();
+        engine.correction_totals()
+    };
+    // A reconnect carries on the session it interrupted
+    state
+        .session_stats
+        .lock()
+        .get_or_insert_with(|| SessionStats::new(corrections, std::time::Instant::now()));
     update_autoplay_state(state, &config);
 
     if let Err(e) = ensure_player_connected(state).await {
@@ -264,7 +303,7 @@ async fn finalize_connection_setup(
     tokio::spawn(async move {
         while let Some(message) = receiver.recv().await {
             tracing::debug!("Received message: {:?}", message);
-            handle_server_message(message, &state_clone).await;
+            protocol::handle_server_message(&state_clone, message).await;
         }
         tracing::info!("Message processing loop ended");
         handle_connection_closed(&state_clone).await;
@@ -277,10 +316,34 @@ fn reset_reconnect_state(state: &Arc<AppState>) {
     reconnect.attempts = 0;
 }
 
-fn reconnect_delay(attempt: u32) -> Duration {
-    let exponent = attempt.min(RECONNECT_MAX_EXPONENT);
-    let delay = RECONNECT_BASE_DELAY_SECONDS * 2_f64.powi(exponent as i32);
-    Duration::from_secs_f64(delay)
+fn reconnect_delay(policy: &ReconnectPolicy, attempt: u32) -> Duration {
+    let delay = policy.delay_for_attempt(attempt);
+    let jitter = if policy.jitter > 0.0 {
+        delay * rand::thread_rng().gen_range(0.0..=policy.jitter)
+    } else {
+        0.0
+    };
+    Duration::from_secs_f64(delay + jitter)
+}
+
+async fn give_up_reconnect(state: &Arc<AppState>, policy: &ReconnectPolicy) {
+    emit_error_message(state, &state.tr("connection-failed", &[]));
+    finish_session_stats(state);
+    {
+        let mut reconnect = state.reconnect_state.lock();
+        reconnect.enabled = false;
+        reconnect.running = false;
+        reconnect.attempts = 0;
+    }
+    match policy.give_up_action {
+        ReconnectGiveUpAction::Notify => {}
+        ReconnectGiveUpAction::PausePlayer => pause_local_player(state).await,
+        ReconnectGiveUpAction::StopPlayer => {
+            if let Err(e) = stop_player(state).await {
+                tracing::warn!("Failed to stop player: {}", e);
+            }
+        }
+    }
 }
 
 fn start_reconnect_loop(state: Arc<AppState>) {
@@ -313,40 +376,54 @@ fn start_reconnect_loop(state: Arc<AppState>) {
             };
 
             if attempt == 1 {
-                *state.last_global_update.lock() = None;
+                *state.session.last_global_update.lock() = None;
                 *state.playlist_may_need_restoring.lock() = true;
-                state.emit_event(
-                    "tls-status-changed",
-                    serde_json::json!({ "status": "unknown" }),
-                );
-                emit_system_message(
-                    &state,
-                    "Connection with server lost, attempting to reconnect",
-                );
+                state.set_tls_status("unknown");
+                emit_system_message(&state, &state.tr("connection-lost-reconnecting", &[]));
                 let config = state.config.lock().clone();
-                if config.user.pause_on_leave {
+                if config.user.effective_pause_on_leave() != PauseOnLeave::Never {
                     pause_local_player(&state).await;
                 }
             }
 
-            if attempt > RECONNECT_RETRIES {
-                emit_error_message(&state, "Connection with server failed");
-                let mut reconnect = state.reconnect_state.lock();
-                reconnect.enabled = false;
-                reconnect.running = false;
-                reconnect.attempts = 0;
+            let policy = state.config.lock().reconnect.clone();
+            if attempt > policy.max_retries {
+                give_up_reconnect(&state, &policy).await;
                 break;
             }
 
-            sleep(reconnect_delay(attempt.saturating_sub(1))).await;
+            sleep(reconnect_delay(&policy, attempt.saturating_sub(1))).await;
 
             if !state.reconnect_state.lock().enabled {
                 reset_reconnect_state(&state);
                 break;
             }
 
+            state
+                .metrics
+                .reconnect_attempts
+                .fetch_add(1, Ordering::Relaxed);
             match establish_connection(&state, &snapshot, false).await {
+                Ok(established) if !state.reconnect_state.lock().enabled => {
+                    // Cancelled while this attempt was in flight
+                    tracing::info!("Dropping reconnect that finished after it was cancelled");
+                    established.connection.disconnect();
+                    let mut connection = state.session.connection.lock();
+                    if connection
+                        .as_ref()
+                        .is_some_and(|current| Arc::ptr_eq(current, &established.connection))
+                    {
+                        *connection = None;
+                    }
+                    drop(connection);
+                    reset_reconnect_state(&state);
+                    break;
+                }
                 Ok(established) => {
+                    state.metrics.reconnects.fetch_add(1, Ordering::Relaxed);
+                    if let Some(stats) = state.session_stats.lock().as_mut() {
+                        stats.reconnected();
+                    }
                     finalize_connection_setup(
                         &state,
                         &snapshot,
@@ -367,12 +444,14 @@ fn start_reconnect_loop(state: Arc<AppState>) {
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn connect_to_server<R: Runtime>(
     host: String,
     port: u16,
     username: String,
     room: String,
     password: Option<String>,
+    room_password: Option<String>,
     app: AppHandle<R>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<(), String> {
@@ -383,10 +462,7 @@ pub async fn connect_to_server<R: Runtime>(
         username,
         room
     );
-    emit_system_message(
-        state.inner(),
-        &format!("Attempting to connect to {}:{}", host, port),
-    );
+    emit_system_message(state.inner(), &state.tr("connecting", &[&host, &port]));
 
     // Check if already connected
     if state.is_connected() {
@@ -399,14 +475,15 @@ pub async fn connect_to_server<R: Runtime>(
         store_control_password(state.inner(), &room, &password, true);
     }
     let username = truncate_text(&username, FALLBACK_MAX_USERNAME_LENGTH);
-
-    let snapshot = ConnectionSnapshot {
-        host: host.clone(),
+    let snapshot = connection_snapshot(
+        state.inner(),
+        host.clone(),
         port,
-        username: username.clone(),
-        room: room.clone(),
-        password: password.clone(),
-    };
+        username,
+        room,
+        password,
+        room_password,
+    );
 
     {
         let mut reconnect = state.reconnect_state.lock();
@@ -417,6 +494,7 @@ pub async fn connect_to_server<R: Runtime>(
     *state.manual_disconnect.lock() = false;
     *state.server_supports_tls.lock() = true;
     *state.reconnect_snapshot.lock() = Some(snapshot.clone());
+    *state.pending_room_join.lock() = Some(snapshot.room.clone());
 
     let config = state.config.lock().clone();
 
@@ -439,202 +517,646 @@ pub async fn connect_to_server<R: Runtime>(
     }
 }
 
-async fn handle_server_message(message: ProtocolMessage, state: &Arc<AppState>) {
-    match message {
-        ProtocolMessage::Hello { Hello } => {
-            tracing::info!("Received hello message: {:?}", Hello);
-            if let Some(connection) = state.connection.lock().clone() {
-                connection.set_authenticated();
-            }
-            state
-                .client_state
-                .set_server_version(Hello.realversion.clone());
-            update_server_features(state, &Hello.realversion, Hello.features.clone());
-            *state.last_connect_time.lock() = Some(std::time::Instant::now());
-            emit_system_message(state, &format!("Hello {},", Hello.username));
-            if let Some(motd) = Hello.motd {
-                state.emit_event(
-                    "chat-message-received",
-                    serde_json::json!({
-                        "timestamp": chrono::Utc::now().to_rfc3339(),
-                        "username": null,
-                        "message": motd,
-                        "messageType": "system",
-                    }),
-                );
+/// Snapshot for joining `host:port`, falling back to the password hash saved for it
+fn connection_snapshot(
+    state: &Arc<AppState>,
+    host: String,
+    port: u16,
+    username: String,
+    room: String,
+    password: Option<String>,
+    room_password: Option<String>,
+) -> ConnectionSnapshot {
+    let config = state.config.lock().clone();
+    let password = password.filter(|password| !password.is_empty());
+    let password_hash = match password.as_deref() {
+        Some(password) => Some(hash_server_password(password)),
+        None => config.saved_hello_password(&host, port),
+    };
+    let room_password = room_password
+        .filter(|password| !password.is_empty())
+        .or_else(|| config.user.room_password(&room));
+    ConnectionSnapshot {
+        host,
+        port,
+        username,
+        room,
+        password,
+        password_hash,
+        room_password,
+    }
+}
+
+/// Outcome of a server password check
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PasswordCheck {
+    Accepted,
+    /// The server turned the Hello down, typically over a wrong or missing password
+    AuthFailed {
+        message: String,
+    },
+    /// The server could not be reached or dropped the connection
+    NetworkError {
+        message: String,
+    },
+}
+
+/// Try a Hello on a throwaway connection to check the password before joining
+///
+/// A successful check joins the room for a moment, as upstream offers no other way.
+#[tauri::command]
+pub async fn verify_server_password(
+    host: String,
+    port: u16,
+    username: String,
+    room: String,
+    password: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<PasswordCheck, String> {
+    let username = truncate_text(&username, FALLBACK_MAX_USERNAME_LENGTH);
+    let (room, _) = parse_controlled_room_input(&room);
+    let room = truncate_text(&room, FALLBACK_MAX_ROOM_NAME_LENGTH);
+    let snapshot = connection_snapshot(state.inner(), host, port, username, room, password, None);
+    let config = state.config.lock().clone();
+    let connection = Connection::new();
+    let mut receiver = match connection
+        .connect(
+            snapshot.host.clone(),
+            snapshot.port,
+            config.server.bind_ip(),
+        )
+        .await
+    {
+        Ok((receiver, _)) => receiver,
+        Err(e) => {
+            return Ok(PasswordCheck::NetworkError {
+                message: e.to_string(),
+            })
+        }
+    };
+    let hello = ProtocolMessage::Hello {
+        Hello: build_hello(&config, &snapshot),
+    };
+    let exchange = async {
+        // Go through TLS first when possible so the hash is not sent in the clear
+        let first = if create_tls_connector().is_ok() {
+            ProtocolMessage::TLS {
+                TLS: TLSMessage {
+                    start_tls: Some("send".to_string()),
+                },
             }
-            emit_system_message(state, "Successfully connected to server");
-            if let Some(connection) = state.connection.lock().clone() {
-                if let Err(e) = connection.send(ProtocolMessage::List { List: None }) {
-                    tracing::warn!("Failed to request user list: {}", e);
+        } else {
+            hello.clone()
+        };
+        connection.send(first).map_err(|e| e.to_string())?;
+        while let Some(message) = receiver.recv().await {
+            match message {
+                ProtocolMessage::Hello { .. } => return Ok(PasswordCheck::Accepted),
+                ProtocolMessage::TLS { TLS } if TLS.start_tls.is_some() => {
+                    if TLS.start_tls.as_deref() == Some("true") {
+                        connection.upgrade_tls().await.map_err(|e| e.to_string())?;
+                    }
+                    connection.send(hello.clone()).map_err(|e| e.to_string())?;
                 }
-            }
-            reidentify_as_controller(state);
-            if let Some(player) = state.player.lock().clone() {
-                let player_state = player.get_state();
-                if (player_state.filename.is_some() || player_state.path.is_some())
-                    && !crate::player::controller::is_placeholder_file(state, &player_state)
-                {
-                    crate::player::controller::send_file_update(state, &player_state);
+                ProtocolMessage::Error { Error } if Error.message.contains("startTLS") => {
+                    connection.send(hello.clone()).map_err(|e| e.to_string())?;
+                }
+                ProtocolMessage::Error { Error } => {
+                    return Ok(PasswordCheck::AuthFailed {
+                        message: Error.message,
+                    })
                 }
+                _ => {}
             }
         }
-        ProtocolMessage::List { List } => {
-            tracing::info!("Received user list: {:?}", List);
-            if let Some(users_by_room) = List {
-                state.client_state.clear_users();
-                for (room_name, room_users) in users_by_room {
-                    for (username, user_info) in room_users {
-                        if is_placeholder_username(&username) {
-                            tracing::debug!(
-                                "Ignoring placeholder user entry from List in room '{}'",
-                                room_name
-                            );
-                            continue;
-                        }
-                        let file = user_info.file.as_ref().and_then(|f| f.name.clone());
-                        let file_size = user_info.file.as_ref().and_then(|f| f.size.clone());
-                        let file_duration = user_info.file.as_ref().and_then(|f| f.duration);
-                        state.client_state.add_user(crate::client::state::User {
-                            username,
-                            room: room_name.clone(),
-                            file,
-                            file_size,
-                            file_duration,
-                            is_ready: user_info.is_ready,
-                            is_controller: user_info.controller.unwrap_or(false),
-                        });
-                    }
-                }
-                emit_user_list(state);
-                evaluate_autoplay(state);
-                update_room_warnings(state, false);
+        Err("Server closed the connection".to_string())
+    };
+    let result = match tokio::time::timeout(PASSWORD_CHECK_TIMEOUT, exchange).await {
+        Ok(Ok(check)) => check,
+        Ok(Err(message)) => PasswordCheck::NetworkError { message },
+        Err(_) => PasswordCheck::NetworkError {
+            message: "Server did not answer in time".to_string(),
+        },
+    };
+    connection.disconnect();
+    Ok(result)
+}
+
+#[async_trait]
+impl ClientContext for Arc<AppState> {
+    fn client_state(&self) -> &ClientState {
+        &self.session.client_state
+    }
+
+    fn chat_history(&self) -> &ChatManager {
+        &self.chat
+    }
+
+    fn playlist(&self) -> &Playlist {
+        &self.session.playlist
+    }
+
+    fn config(&self) -> SyncplayConfig {
+        self.config.lock().clone()
+    }
+
+    fn chat_supported(&self) -> bool {
+        self.session.server_features.lock().chat
+    }
+
+    fn shared_playlists_supported(&self) -> bool {
+        self.session.server_features.lock().shared_playlists
+    }
+
+    fn server_address(&self) -> Option<String> {
+        self.reconnect_snapshot
+            .lock()
+            .as_ref()
+            .map(|snapshot| format!("{}:{}", snapshot.host, snapshot.port))
+    }
+
+    fn player(&self) -> Option<Arc<dyn PlayerBackend>> {
+        self.player.lock().clone()
+    }
+
+    fn do_not_disturb(&self) -> bool {
+        *self.do_not_disturb.lock()
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        self.emit_event(event, payload);
+    }
+
+    fn send(&self, message: ProtocolMessage) -> anyhow::Result<()> {
+        let Some(connection) = self.session.connection.lock().clone() else {
+            anyhow::bail!("Not connected");
+        };
+        connection.send(message)
+    }
+
+    fn hello_accepted(&self, hello: &HelloMessage) {
+        if let Some(connection) = self.session.connection.lock().clone() {
+            connection.set_authenticated();
+        }
+        update_server_features(self, &hello.realversion, hello.features.clone());
+        *self.last_connect_time.lock() = Some(std::time::Instant::now());
+        // A fresh session has not heard about our file yet
+        self.file_update_throttle.lock().reset();
+    }
+
+    fn server_motd(&self, motd: &str) {
+        handle_server_motd(self, motd);
+    }
+
+    fn server_error(&self, message: &str) {
+        let authenticated = self
+            .session
+            .connection
+            .lock()
+            .as_ref()
+            .map(|conn| conn.state() == crate::network::connection::ConnectionState::Authenticated)
+            .unwrap_or(false);
+        if message.contains("startTLS") && !authenticated {
+            *self.server_supports_tls.lock() = false;
+            self.set_tls_status("unsupported");
+            send_hello(self);
+            return;
+        }
+        emit_error_message(self, message);
+        if is_room_password_error(message) {
+            if let Some(room) = self.pending_room_join.lock().take() {
+                handle_room_password_rejected(self, room, message.to_string());
             }
         }
-        ProtocolMessage::Chat { Chat } => {
-            tracing::info!("Received chat message: {:?}", Chat);
-            let config = state.config.lock().clone();
-            if !state.server_features.lock().chat {
-                return;
+        let mut reconnect = self.reconnect_state.lock();
+        reconnect.enabled = false;
+        reconnect.running = false;
+        let connection = self.session.connection.lock().clone();
+        drop(reconnect);
+        if let Some(connection) = connection {
+            connection.disconnect();
+        }
+    }
+
+    async fn tls_answer(&self, tls: TLSMessage) {
+        handle_tls_message(self, tls).await;
+    }
+
+    fn reidentify_as_controller(&self) {
+        reidentify_as_controller(self);
+    }
+
+    fn announce_file(&self) {
+        if let Some(player) = self.player.lock().clone() {
+            let player_state = player.get_state();
+            if (player_state.filename.is_some() || player_state.path.is_some())
+                && !crate::player::controller::is_placeholder_file(self, &player_state)
+            {
+                crate::player::controller::send_file_update(self, &player_state);
             }
-            if !config.user.chat_output_enabled {
-                return;
+        }
+    }
+
+    fn user_list_applied(&self) {
+        confirm_room_join(self);
+        emit_user_list(self);
+        evaluate_autoplay(self);
+        update_room_warnings(self, false);
+        self.session.list_updated.notify_waiters();
+    }
+
+    fn chat_handled(&self, outcome: ChatOutcome, entry: Option<(String, String)>) {
+        match outcome {
+            ChatOutcome::UnpauseCountdown { actor, seconds } => {
+                emit_system_message(self, &self.tr("user-unpausing-in", &[&actor, &seconds]));
+                start_unpause_countdown(self.clone(), seconds, false);
             }
-            // Transform chat message to match frontend format
-            let (username, message) = match Chat {
-                crate::network::messages::ChatMessage::Entry { username, message } => {
-                    (Some(username), message)
+            ChatOutcome::ReadyCheck { actor, seconds } => {
+                // Another operator's check keeps running until it times out
+                let running = self
+                    .session
+                    .ready_check
+                    .lock()
+                    .as_ref()
+                    .is_some_and(|check| check.initiator != actor);
+                if running {
+                    tracing::debug!("Ignoring ready check from {} during another", actor);
+                } else {
+                    start_ready_check(self.clone(), actor, seconds, false);
                 }
-                crate::network::messages::ChatMessage::Text(message) => (None, message),
-            };
-            if let Some(player) = state.player.lock().clone() {
-                let _ = player.show_chat_message(username.as_deref(), &message);
-            }
-            let chat_msg = serde_json::json!({
-                "timestamp": chrono::Utc::now().to_rfc3339(),
-                "username": username,
-                "message": message,
-                "messageType": "normal",
-            });
-            state.emit_event("chat-message-received", chat_msg);
-        }
-        ProtocolMessage::State { State: state_msg } => {
-            if state_msg.playstate.is_some() || state_msg.ignoring_on_the_fly.is_some() {
-                tracing::info!(
-                    "Received state update: playstate={:?}, ignoring_on_the_fly={:?}",
-                    state_msg.playstate.as_ref(),
-                    state_msg.ignoring_on_the_fly.as_ref()
-                );
             }
-            let mut message_age = 0.0;
-            if let Some(ignore) = state_msg.ignoring_on_the_fly.as_ref() {
-                update_ignoring_on_the_fly(state, ignore);
+            ChatOutcome::ScheduledStart { actor, start_ms } => {
+                start_scheduled_session(self.clone(), actor, start_ms, false);
             }
-            let client_ignore_active = state.ignoring_on_the_fly.lock().client != 0;
-            if let Some(ping) = state_msg.ping.as_ref() {
-                if let (Some(client_latency), Some(server_rtt)) =
-                    (ping.client_latency_calculation, ping.server_rtt)
-                {
-                    state
-                        .ping_service
-                        .lock()
-                        .receive_message(client_latency, server_rtt);
-                    message_age = state.ping_service.lock().get_last_forward_delay();
-                    let rtt_ms = state.ping_service.lock().get_rtt() * 1000.0;
-                    state.emit_event("ping-updated", serde_json::json!({ "rttMs": rtt_ms }));
-                }
-                *state.last_latency_calculation.lock() = ping.latency_calculation;
+            ChatOutcome::Bookmark {
+                actor,
+                position,
+                label,
+            } => {
+                crate::commands::bookmarks::receive_shared_bookmark(self, &actor, position, &label);
             }
-            if let Some(playstate) = state_msg.playstate {
-                if !client_ignore_active {
-                    handle_state_update(state, playstate, message_age).await;
-                }
+            ChatOutcome::OperatorOffer { actor } => {
+                crate::commands::room::receive_operator_offer(self, &actor);
             }
-            let latency_calculation = state_msg
-                .ping
-                .as_ref()
-                .and_then(|ping| ping.latency_calculation);
-            if let Err(e) = send_state_message(
-                state,
-                build_local_playstate(state),
-                latency_calculation,
-                false,
-            ) {
-                tracing::warn!("Failed to send state response: {}", e);
+            ChatOutcome::OperatorAccept { actor, key } => {
+                crate::commands::room::receive_operator_accept(self, &actor, &key);
             }
-        }
-        ProtocolMessage::Error { Error } => {
-            tracing::error!("Received error from server: {:?}", Error);
-            let authenticated = state
-                .connection
-                .lock()
-                .as_ref()
-                .map(|conn| {
-                    conn.state() == crate::network::connection::ConnectionState::Authenticated
-                })
-                .unwrap_or(false);
-            if Error.message.contains("startTLS") && !authenticated {
-                *state.server_supports_tls.lock() = false;
-                state.emit_event(
-                    "tls-status-changed",
-                    serde_json::json!({ "status": "unsupported" }),
-                );
-                send_hello(state);
-            } else {
-                emit_error_message(state, &Error.message);
-                let mut reconnect = state.reconnect_state.lock();
-                reconnect.enabled = false;
-                reconnect.running = false;
-                let connection = state.connection.lock().clone();
-                drop(reconnect);
-                if let Some(connection) = connection {
-                    connection.disconnect();
+            ChatOutcome::OperatorKey { actor, key, sealed } => {
+                crate::commands::room::receive_operator_key(self, &actor, &key, &sealed);
+            }
+            ChatOutcome::OperatorsOnly { actor, operators } => {
+                crate::commands::room::receive_operators_only(self, &actor, operators);
+            }
+            ChatOutcome::Suggestion { actor, suggestion } => {
+                crate::commands::room::receive_suggestion(self, &actor, suggestion);
+            }
+            ChatOutcome::Vote { actor, subject } => {
+                crate::commands::room::receive_vote(self, &actor, subject);
+            }
+            ChatOutcome::Delivered { mentioned } => {
+                if mentioned {
+                    notifications::notify_mention(self);
+                }
+                if let Some(stats) = self.session_stats.lock().as_mut() {
+                    stats.chat_message();
+                }
+                if let Some((username, message)) = entry {
+                    let own = username == self.session.client_state.get_username();
+                    scripts::dispatch(
+                        self,
+                        ScriptEvent::ChatMessage {
+                            username,
+                            message,
+                            own,
+                        },
+                    );
                 }
             }
+            _ => {}
         }
-        ProtocolMessage::Set { Set } => {
-            tracing::info!("Received set message: {:?}", Set);
-            handle_set_message(state, *Set).await;
+    }
+
+    fn update_ignoring(&self, ignoring: IgnoringInfo) {
+        self.session
+            .actor
+            .send(SessionCommand::UpdateIgnoring(ignoring));
+    }
+
+    async fn client_ignore_active(&self) -> bool {
+        self.session
+            .actor
+            .current()
+            .await
+            .ignoring_on_the_fly
+            .client
+            != 0
+    }
+
+    fn ping_received(&self, ping: &PingInfo) -> f64 {
+        let mut message_age = 0.0;
+        if let (Some(client_latency), Some(server_rtt)) =
+            (ping.client_latency_calculation, ping.server_rtt)
+        {
+            self.session
+                .ping_service
+                .lock()
+                .receive_message(client_latency, server_rtt);
+            let ping_service = self.session.ping_service.lock().clone();
+            if let Some(stats) = self.session_stats.lock().as_mut() {
+                stats.record_rtt(ping_service.get_rtt());
+            }
+            message_age = ping_service.get_last_forward_delay();
+            self.emit_event(
+                "ping-updated",
+                serde_json::json!({
+                    "rttMs": ping_service.get_rtt() * 1000.0,
+                    "filteredRttMs": ping_service.get_filtered_rtt() * 1000.0,
+                    "forwardDelayMs": message_age * 1000.0,
+                    "rawForwardDelayMs": ping_service.get_raw_forward_delay() * 1000.0,
+                }),
+            );
         }
-        ProtocolMessage::TLS { TLS } => {
-            tracing::info!("Received TLS message: {:?}", TLS);
-            handle_tls_message(state, TLS).await;
+        *self.session.last_latency_calculation.lock() = ping.latency_calculation;
+        if let Some(server_timestamp) = ping.latency_calculation {
+            let skew = self
+                .session
+                .ping_service
+                .lock()
+                .update_clock_skew(server_timestamp);
+            if let Some(skew) = skew {
+                check_clock_skew(self, skew);
+            }
         }
+        message_age
     }
-}
 
-fn should_ignore_seek_after_rewind(state: &Arc<AppState>, position: f64) -> bool {
-    let guard = state.last_rewind_time.lock();
-    let Some(last_rewind) = guard.as_ref() else {
-        return false;
-    };
-    last_rewind.elapsed().as_secs_f64() < IGNORE_SEEK_AFTER_REWIND_SECONDS
-        && position > IGNORE_SEEK_AFTER_REWIND_POSITION_THRESHOLD
-}
+    async fn apply_playstate(&self, playstate: PlayState, message_age: f64) {
+        handle_state_update(self, playstate, message_age).await;
+    }
 
-async fn try_set_position(
-    state: &Arc<AppState>,
-    player: &Arc<dyn PlayerBackend>,
+    async fn reply_to_state(&self, latency_calculation: Option<f64>) {
+        if let Err(e) = send_state_message(
+            self,
+            build_local_playstate(self),
+            latency_calculation,
+            false,
+        )
+        .await
+        {
+            tracing::warn!("Failed to send state response: {}", e);
+        }
+    }
+
+    fn room_changed(&self) {
+        self.session.actor.send(SessionCommand::ResetPlaylistIndex);
+        *self.playlist_may_need_restoring.lock() = false;
+    }
+
+    async fn load_file(&self, name: &str) -> Result<(), String> {
+        load_media_by_name(self, name, false, true).await
+    }
+
+    fn user_changed(&self, username: &str, change: &UserChange) {
+        announce_user_change(self, username, change);
+    }
+
+    fn ready_changed(&self, username: &str, room: String, ready: bool, set_by: Option<String>) {
+        if let Some(check) = self.session.ready_check.lock().as_mut() {
+            check.record(username, ready);
+        }
+        record_room_event(
+            self,
+            RoomEvent::new(username, room, RoomEventKind::Ready { ready, set_by }),
+        );
+    }
+
+    fn controller_auth(&self, auth: ControllerAuth) {
+        handle_controller_auth(self, auth);
+    }
+
+    async fn new_controlled_room(&self, room: NewControlledRoom) {
+        handle_new_controlled_room(self, room).await;
+    }
+
+    fn users_changed(&self) {
+        emit_user_list(self);
+    }
+
+    async fn pause_for_leave(&self) {
+        pause_local_player(self).await;
+    }
+
+    fn take_playlist_restore(&self) -> bool {
+        std::mem::take(&mut *self.playlist_may_need_restoring.lock())
+    }
+
+    fn remembered_playlist(&self, room: &str) -> Option<(Vec<String>, Option<usize>)> {
+        crate::commands::room::persistent_rooms_server(self)
+            .and_then(|server| self.persistent_rooms.playlist_for(&server, room))
+    }
+
+    fn playlist_changed(&self) {
+        emit_playlist_update(self);
+    }
+
+    async fn playlist_index_seen(&self) -> bool {
+        self.session.actor.mark_playlist_index_seen().await
+    }
+
+    async fn apply_playlist_index(&self, index: usize, reset_position: bool) -> Result<(), String> {
+        apply_playlist_index_from_server(self, index, reset_position).await
+    }
+
+    fn show_osd(&self, message: &str) {
+        let config = self.config.lock().clone();
+        maybe_show_osd(self, &config, message, OsdCategory::SameRoom);
+    }
+
+    fn evaluate_autoplay(&self) {
+        evaluate_autoplay(self);
+    }
+}
+
+/// Servers word it differently, but always name both the room and its password
+fn is_room_password_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("room") && message.contains("password")
+}
+
+/// The join went through once the server lists us in the room
+fn confirm_room_join(state: &Arc<AppState>) {
+    let mut pending = state.pending_room_join.lock();
+    let Some(room) = pending.as_ref() else {
+        return;
+    };
+    let username = state.session.client_state.get_username();
+    let joined = state
+        .session
+        .client_state
+        .get_users()
+        .iter()
+        .any(|user| user.username == username && &user.room == room);
+    if joined {
+        *pending = None;
+    }
+}
+
+/// Drop the saved password that failed and ask the user for another one
+fn handle_room_password_rejected(state: &Arc<AppState>, room: String, message: String) {
+    tracing::warn!("Server rejected the password for room {}", room);
+    let mut config = state.config.lock().clone();
+    if config.user.room_passwords.remove(&room).is_some() {
+        if let Some(app) = state.app_handle.lock().clone() {
+            if let Err(e) = save_config(&app, &config) {
+                tracing::warn!("Failed to forget rejected room password: {}", e);
+            }
+        }
+        *state.config.lock() = config.clone();
+        state.emit_event("config-updated", config);
+    }
+    emit_error_message(state, &state.tr("room-password-rejected", &[&room]));
+    *state.rejected_room_join.lock() = Some(room.clone());
+    state.emit_event(
+        "room-password-required",
+        RoomPasswordRequest { room, message },
+    );
+}
+
+/// Join the room whose password was rejected again, with `password`
+#[tauri::command]
+pub async fn retry_room_join<R: Runtime>(
+    password: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let Some(room) = state.rejected_room_join.lock().take() else {
+        return Err("No room join to retry".to_string());
+    };
+    if state.is_connected() {
+        return crate::commands::room::change_room(room, Some(password), app, state).await;
+    }
+    let Some(snapshot) = state.reconnect_snapshot.lock().clone() else {
+        return Err("No server to reconnect to".to_string());
+    };
+    connect_to_server(
+        snapshot.host,
+        snapshot.port,
+        snapshot.username,
+        room,
+        snapshot.password,
+        Some(password),
+        app,
+        state,
+    )
+    .await
+}
+
+/// Go by `new_name` from now on
+///
+/// Servers file users under the name from their Hello and have no rename message, so once
+/// the Hello is out the session reconnects with the new name; before that it just swaps it.
+#[tauri::command]
+pub async fn change_username<R: Runtime>(
+    new_name: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let max_len = if state.is_connected() {
+        state
+            .session
+            .server_features
+            .lock()
+            .max_username_length
+            .unwrap_or(FALLBACK_MAX_USERNAME_LENGTH)
+    } else {
+        FALLBACK_MAX_USERNAME_LENGTH
+    };
+    let username = truncate_text(new_name.trim(), max_len);
+    if username.is_empty() {
+        return Err("Username cannot be empty".to_string());
+    }
+    let previous = state.session.client_state.get_username();
+    if username == previous {
+        return Ok(());
+    }
+
+    let mut updated = state.config.lock().clone();
+    updated.user.username = username.clone();
+    if let Err(e) = save_config(&app, &updated) {
+        tracing::warn!("Failed to save config after username change: {}", e);
+    }
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated.clone());
+    state.session.client_state.set_username(username.clone());
+
+    if !state.is_connected() {
+        return Ok(());
+    }
+    {
+        let mut snapshot = state.reconnect_snapshot.lock();
+        if let Some(snapshot) = snapshot.as_mut() {
+            let room = state.session.client_state.get_room();
+            if snapshot.room != room {
+                snapshot.room_password = updated.user.room_password(&room);
+                snapshot.room = room;
+            }
+            snapshot.username = username.clone();
+        }
+    }
+    if !*state.session.hello_sent.lock() {
+        if let Some(hello) = state.session.last_hello.lock().as_mut() {
+            hello.username = username;
+        }
+        return Ok(());
+    }
+
+    tracing::info!("Reconnecting to rename {} to {}", previous, username);
+    emit_system_message(
+        state.inner(),
+        &state.tr("username-changing", &[&previous, &username]),
+    );
+    {
+        let mut reconnect = state.reconnect_state.lock();
+        reconnect.enabled = true;
+        reconnect.attempts = 0;
+    }
+    *state.manual_disconnect.lock() = false;
+    let connection = state.session.connection.lock().clone();
+    if let Some(connection) = connection {
+        // The reconnect loop picks the new name up from the snapshot
+        connection.disconnect();
+    }
+    Ok(())
+}
+
+fn handle_server_motd(state: &Arc<AppState>, motd: &str) {
+    let server = state.server_address().unwrap_or_default();
+    let event = ServerMotdEvent::new(server, ServerMessageKind::Motd, motd);
+    *state.session.motd.lock() = Some(event.clone());
+    let dismissed = state
+        .config
+        .lock()
+        .user
+        .is_motd_dismissed(&event.server, &event.fingerprint);
+    if dismissed {
+        tracing::debug!("Server MOTD was dismissed for {}", event.server);
+        return;
+    }
+    state.emit_event("server-motd", event);
+}
+
+fn should_ignore_seek_after_rewind(state: &Arc<AppState>, position: f64) -> bool {
+    let Some(last_rewind) = state.session.actor.snapshot().last_rewind_time else {
+        return false;
+    };
+    last_rewind.elapsed().as_secs_f64() < IGNORE_SEEK_AFTER_REWIND_SECONDS
+        && position > IGNORE_SEEK_AFTER_REWIND_POSITION_THRESHOLD
+}
+
+async fn try_set_position(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
     position: f64,
     context: &str,
 ) -> bool {
@@ -650,23 +1172,34 @@ async fn try_set_position(
 }
 
 async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, message_age: f64) {
-    let had_last_global = state.last_global_update.lock().is_some();
-    *state.last_global_update.lock() = Some(std::time::Instant::now());
+    let had_last_global = state.session.last_global_update.lock().is_some();
+    *state.session.last_global_update.lock() = Some(std::time::Instant::now());
     let adjusted_global_position = if !playstate.paused {
         playstate.position + message_age
     } else {
         playstate.position
     };
-    let previous_global = state.client_state.get_global_state();
-    state.client_state.set_global_state(
+    let previous_global = state.session.client_state.get_global_state();
+    state.session.client_state.set_global_state(
         adjusted_global_position,
         playstate.paused,
         playstate.set_by.clone(),
     );
+    if let Some(set_by) = playstate.set_by.as_deref() {
+        state.session.client_state.record_user_playstate(
+            set_by,
+            adjusted_global_position,
+            playstate.paused,
+        );
+    }
+    if *state.sync_detached.lock() {
+        tracing::debug!("Local player is detached; not applying room state");
+        return;
+    }
 
     let player = state.player.lock().clone();
     let Some(player) = player else { return };
-    let player_kind = player.kind();
+    let speed_control = player.capabilities().speed_control;
     let mut player_state: PlayerState = player.get_state();
     let (local_position, local_paused) = match (player_state.position, player_state.paused) {
         (Some(pos), Some(paused)) => (pos, paused),
@@ -684,18 +1217,19 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
     };
 
     let config = state.config.lock().clone();
-    let current_username = state.client_state.get_username();
+    let current_username = state.session.client_state.get_username();
     let actor_name = playstate
         .set_by
         .clone()
-        .unwrap_or_else(|| "Unknown".to_string());
+        .unwrap_or_else(|| state.tr("unknown-user", &[]));
     let do_seek = playstate.do_seek.unwrap_or(false);
     let pause_changed =
         playstate.paused != previous_global.paused || playstate.paused != local_paused;
     let diff = local_position - adjusted_global_position;
     let mut made_change_on_player = false;
+    state.sync_engine.lock().record_sample(diff);
 
-    if !had_last_global && state.client_state.get_file().is_some() {
+    if !had_last_global && state.session.client_state.get_file().is_some() {
         if try_set_position(state, &player, adjusted_global_position, "init").await {
             made_change_on_player = true;
         }
@@ -709,25 +1243,42 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
     if do_seek {
         let from_position = if actor_name == current_username {
             state
-                .last_seek_from_position
-                .lock()
-                .take()
+                .session
+                .actor
+                .take_seek_from_position()
+                .await
                 .unwrap_or(local_position)
         } else {
-            *state.last_seek_from_position.lock() = None;
+            state
+                .session
+                .actor
+                .send(SessionCommand::SetSeekFromPosition(None));
             if try_set_position(state, &player, adjusted_global_position, "seek").await {
                 made_change_on_player = true;
             }
             local_position
         };
-        let message = format!(
-            "{} jumped from {} to {}",
-            actor_name,
-            format_time(from_position),
-            format_time(adjusted_global_position)
+        let message = state.tr(
+            "user-seeked",
+            &[
+                &actor_name,
+                &format_time(from_position, config.user.always_show_hours),
+                &format_time(adjusted_global_position, config.user.always_show_hours),
+            ],
         );
         emit_system_message(state, &message);
-        maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+        maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
+        record_room_event(
+            state,
+            RoomEvent::new(
+                actor_name.as_str(),
+                state.session.client_state.get_room(),
+                RoomEventKind::Seeked {
+                    from: from_position,
+                    to: adjusted_global_position,
+                },
+            ),
+        );
     }
 
     if diff > config.user.seek_threshold_rewind
@@ -737,16 +1288,20 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
     {
         if try_set_position(state, &player, adjusted_global_position, "rewind").await {
             made_change_on_player = true;
+            state
+                .sync_engine
+                .lock()
+                .record_correction(SyncCorrection::Rewind);
         }
-        let message = format!("Rewinded due to time difference with {}", actor_name);
+        let message = state.tr("rewinded", &[&actor_name]);
         emit_system_message(state, &message);
-        maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+        maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
     }
 
     if config.user.fastforward_on_desync && should_allow_fastforward(state, &config) {
         let mut next_behind_marker = None;
         let mut fastforward_target = None;
-        if diff < -FASTFORWARD_BEHIND_THRESHOLD && !do_seek {
+        if diff < -config.user.fastforward_behind_threshold && !do_seek {
             let now = std::time::Instant::now();
             let start = state.sync_engine.lock().behind_first_detected();
             match start {
@@ -759,13 +1314,14 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
                         .unwrap_or_default()
                         .as_secs_f64();
                     if duration_behind
-                        > (config.user.seek_threshold_fastforward - FASTFORWARD_BEHIND_THRESHOLD)
+                        > (config.user.seek_threshold_fastforward
+                            - config.user.fastforward_behind_threshold)
                         && diff < -config.user.seek_threshold_fastforward
                     {
                         fastforward_target =
-                            Some(adjusted_global_position + FASTFORWARD_EXTRA_TIME);
+                            Some(adjusted_global_position + config.user.fastforward_extra_time);
                         next_behind_marker = Some(Some(
-                            now + Duration::from_secs_f64(FASTFORWARD_RESET_THRESHOLD),
+                            now + Duration::from_secs_f64(config.user.fastforward_reset_threshold),
                         ));
                     }
                 }
@@ -778,10 +1334,14 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
             if actor_name != current_username {
                 if try_set_position(state, &player, position, "fastforward").await {
                     made_change_on_player = true;
+                    state
+                        .sync_engine
+                        .lock()
+                        .record_correction(SyncCorrection::Fastforward);
                 }
-                let message = format!("Fast-forwarded due to time difference with {}", actor_name);
+                let message = state.tr("fastforwarded", &[&actor_name]);
                 emit_system_message(state, &message);
-                maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+                maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
             }
         }
 
@@ -790,7 +1350,49 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
         }
     }
 
-    if player_supports_speed(player_kind)
+    let smooth_sync = state.sync_engine.lock().is_smooth_sync();
+    if smooth_sync && speed_control && !do_seek && !playstate.paused {
+        let (target, current) = {
+            let engine = state.sync_engine.lock();
+            (engine.smooth_sync_target(diff), engine.smooth_speed())
+        };
+        if let Some(target) = target {
+            let resetting = target == 1.0;
+            if target != current && (resetting || actor_name != current_username) {
+                if let Err(e) = player.set_speed(target).await {
+                    tracing::warn!("Failed to set smooth sync speed: {}", e);
+                } else {
+                    made_change_on_player = true;
+                    let correction = if resetting {
+                        SyncCorrection::ResetSpeed
+                    } else if target < 1.0 {
+                        SyncCorrection::Slowdown
+                    } else {
+                        SyncCorrection::Speedup
+                    };
+                    let mut engine = state.sync_engine.lock();
+                    engine.set_smooth_speed(target);
+                    engine.record_correction(correction);
+                }
+                let message = if resetting {
+                    state.tr("speed-reset", &[])
+                } else {
+                    state.tr(
+                        "smooth-sync-adjusting",
+                        &[&format!("{:.0}", target * 100.0), &actor_name],
+                    )
+                };
+                emit_system_message(state, &message);
+                maybe_show_osd(state, &config, &message, OsdCategory::Slowdown);
+            }
+        }
+    }
+
+    // Players without rate control follow slowdowns with small seeks back, also in smooth mode
+    let emulate_slowdown = !speed_control && config.user.slowdown_emulation;
+    let micro_seek_position =
+        emulated_slowdown_position(local_position, diff, config.user.slowdown_micro_seek_step);
+    if (emulate_slowdown || (speed_control && !smooth_sync))
         && !do_seek
         && !playstate.paused
         && config.user.slow_on_desync
@@ -798,26 +1400,47 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
         let slowdown_active = state.sync_engine.lock().is_slowdown_active();
         if diff > config.user.slowdown_threshold && !slowdown_active {
             if actor_name != current_username {
-                if let Err(e) = player.set_speed(config.user.slowdown_rate).await {
+                if emulate_slowdown {
+                    if try_set_position(state, &player, micro_seek_position, "slowdown").await {
+                        made_change_on_player = true;
+                    }
+                } else if let Err(e) = player.set_speed(config.user.slowdown_rate).await {
                     tracing::warn!("Failed to set slowdown: {}", e);
                 } else {
                     made_change_on_player = true;
                 }
-                state.sync_engine.lock().set_slowdown_active(true);
-                let message = format!("Slowing down due to time difference with {}", actor_name);
+                {
+                    let mut engine = state.sync_engine.lock();
+                    engine.set_slowdown_active(true);
+                    engine.record_correction(SyncCorrection::Slowdown);
+                }
+                let message = state.tr("slowdown", &[&actor_name]);
                 emit_system_message(state, &message);
-                maybe_show_osd(state, &config, &message, config.user.show_slowdown_osd);
+                maybe_show_osd(state, &config, &message, OsdCategory::Slowdown);
             }
         } else if slowdown_active && diff < config.user.slowdown_reset_threshold {
-            if let Err(e) = player.set_speed(1.0).await {
-                tracing::warn!("Failed to reset speed: {}", e);
-            } else {
-                made_change_on_player = true;
+            // Emulated slowdowns only seeked, so there is no speed to restore
+            if !emulate_slowdown {
+                if let Err(e) = player.set_speed(1.0).await {
+                    tracing::warn!("Failed to reset speed: {}", e);
+                } else {
+                    made_change_on_player = true;
+                }
+            }
+            {
+                let mut engine = state.sync_engine.lock();
+                engine.set_slowdown_active(false);
+                engine.record_correction(SyncCorrection::ResetSpeed);
             }
-            state.sync_engine.lock().set_slowdown_active(false);
-            let message = "Reverting speed back to normal".to_string();
+            let message = state.tr("speed-reset", &[]);
             emit_system_message(state, &message);
-            maybe_show_osd(state, &config, &message, config.user.show_slowdown_osd);
+            maybe_show_osd(state, &config, &message, OsdCategory::Slowdown);
+        } else if slowdown_active
+            && emulate_slowdown
+            && try_set_position(state, &player, micro_seek_position, "slowdown").await
+        {
+            // Keep stepping back until the reset threshold is reached
+            made_change_on_player = true;
         }
     }
 
@@ -833,22 +1456,42 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
             } else {
                 made_change_on_player = true;
             }
-            let message = format!(
-                "{} paused at {}",
-                actor_name,
-                format_time(adjusted_global_position)
+            let message = state.tr(
+                "user-paused",
+                &[
+                    &actor_name,
+                    &format_time(adjusted_global_position, config.user.always_show_hours),
+                ],
             );
             emit_system_message(state, &message);
-            maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+            maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
+            record_room_event(
+                state,
+                RoomEvent::new(
+                    actor_name.as_str(),
+                    state.session.client_state.get_room(),
+                    RoomEventKind::Paused {
+                        position: adjusted_global_position,
+                    },
+                ),
+            );
         } else {
             if let Err(e) = player.set_paused(false).await {
                 tracing::warn!("Failed to set paused: {}", e);
             } else {
                 made_change_on_player = true;
             }
-            let message = format!("{} unpaused", actor_name);
+            let message = state.tr("user-unpaused", &[&actor_name]);
             emit_system_message(state, &message);
-            maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+            maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
+            record_room_event(
+                state,
+                RoomEvent::new(
+                    actor_name.as_str(),
+                    state.session.client_state.get_room(),
+                    RoomEventKind::Unpaused,
+                ),
+            );
         }
     }
 
@@ -858,7 +1501,7 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
         }
         let refreshed_state = player.get_state();
         if let (Some(position), Some(paused)) = (refreshed_state.position, refreshed_state.paused) {
-            let global = state.client_state.get_global_state();
+            let global = state.session.client_state.get_global_state();
             state.local_playback_state.lock().update_from_player(
                 position,
                 paused,
@@ -871,23 +1514,20 @@ async fn handle_state_update(state: &Arc<AppState>, playstate: PlayState, messag
     update_room_warnings(state, false);
 }
 
-fn update_ignoring_on_the_fly(state: &Arc<AppState>, ignoring: &IgnoringInfo) {
-    let mut local = state.ignoring_on_the_fly.lock();
-    if let Some(server) = ignoring.server {
-        local.server = server;
-        local.client = 0;
-    } else if let Some(client) = ignoring.client {
-        if client == local.client {
-            local.client = 0;
-        }
-    }
-}
-
 fn build_local_playstate(state: &Arc<AppState>) -> Option<PlayState> {
-    if state.last_global_update.lock().is_none() {
+    if state.session.last_global_update.lock().is_none() {
         return None;
     }
-    let global = state.client_state.get_global_state();
+    let global = state.session.client_state.get_global_state();
+    if *state.sync_detached.lock() {
+        // Keep reporting the room position so a detached player drags nobody along
+        return Some(PlayState {
+            position: global.position_at(std::time::Instant::now()),
+            paused: global.paused,
+            do_seek: None,
+            set_by: None,
+        });
+    }
     let local_state = state.local_playback_state.lock();
     let (local_position, local_paused) = local_state.current()?;
     let config = state.config.lock().clone();
@@ -909,73 +1549,69 @@ fn build_local_playstate(state: &Arc<AppState>) -> Option<PlayState> {
     })
 }
 
-pub(crate) fn send_state_message(
+pub(crate) async fn send_state_message(
     state: &Arc<AppState>,
     playstate: Option<PlayState>,
     latency_calculation: Option<f64>,
     state_change: bool,
 ) -> Result<(), String> {
-    let mut ignoring = state.ignoring_on_the_fly.lock();
-    let client_ignore_is_not_set = ignoring.client == 0 || ignoring.server != 0;
-    let playstate = if client_ignore_is_not_set {
+    let ignoring = state
+        .session
+        .actor
+        .prepare_outgoing_state(state_change)
+        .await;
+    let playstate = if ignoring.include_playstate {
         playstate
     } else {
         None
     };
-    if state_change {
-        ignoring.client = ignoring.client.saturating_add(1);
-    }
-    let ignoring_info = if ignoring.server != 0 || ignoring.client != 0 {
-        Some(IgnoringInfo {
-            server: if ignoring.server != 0 {
-                Some(ignoring.server)
-            } else {
-                None
-            },
-            client: if ignoring.client != 0 {
-                Some(ignoring.client)
-            } else {
-                None
-            },
-        })
-    } else {
-        None
-    };
-    if ignoring.server != 0 {
-        ignoring.server = 0;
-    }
-    drop(ignoring);
 
     let ping = PingInfo {
         latency_calculation,
         client_latency_calculation: Some(crate::network::ping::PingService::new_timestamp()),
-        client_rtt: Some(state.ping_service.lock().get_rtt()),
+        client_rtt: Some(state.session.ping_service.lock().get_rtt()),
         server_rtt: None,
     };
     let message = ProtocolMessage::State {
         State: StateMessage {
             playstate,
             ping: Some(ping),
-            ignoring_on_the_fly: ignoring_info,
+            ignoring_on_the_fly: ignoring.info,
         },
     };
-    let Some(connection) = state.connection.lock().clone() else {
+    let Some(connection) = state.session.connection.lock().clone() else {
         return Err("Not connected".to_string());
     };
     connection.send(message).map_err(|e| e.to_string())
 }
 
 pub(crate) fn emit_system_message(state: &Arc<AppState>, message: &str) {
-    state.chat.add_system_message(message.to_string());
+    protocol::system_message(state, message);
+}
+
+pub(crate) fn record_room_event(state: &Arc<AppState>, event: RoomEvent) {
+    state.emit_event("room-event", serde_json::json!(event));
+    state.session.room_timeline.record(event);
+}
+
+fn check_clock_skew(state: &Arc<AppState>, skew: f64) {
+    if skew.abs() <= CLOCK_SKEW_WARNING_SECONDS {
+        return;
+    }
+    {
+        let mut warned = state.session.clock_skew_warned.lock();
+        if *warned {
+            return;
+        }
+        *warned = true;
+    }
+    tracing::warn!("Detected system clock skew of {:.1}s against server", skew);
     state.emit_event(
-        "chat-message-received",
-        serde_json::json!({
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "username": null,
-            "message": message,
-            "messageType": "system",
-        }),
+        "clock-skew-warning",
+        serde_json::json!({ "skewSeconds": skew }),
     );
+    let message = state.tr("clock-skew-warning", &[&format!("{:.1}", skew.abs())]);
+    emit_error_message(state, &message);
 }
 
 fn should_allow_fastforward(state: &Arc<AppState>, config: &crate::config::SyncplayConfig) -> bool {
@@ -986,13 +1622,6 @@ fn should_allow_fastforward(state: &Arc<AppState>, config: &crate::config::Syncp
     !can_control
 }
 
-fn player_supports_speed(kind: crate::player::backend::PlayerKind) -> bool {
-    !matches!(
-        kind,
-        crate::player::backend::PlayerKind::MpcHc | crate::player::backend::PlayerKind::MpcBe
-    )
-}
-
 pub(crate) fn emit_error_message(state: &Arc<AppState>, message: &str) {
     state.chat.add_error_message(message.to_string());
     state.emit_event(
@@ -1004,26 +1633,33 @@ pub(crate) fn emit_error_message(state: &Arc<AppState>, message: &str) {
             "messageType": "error",
         }),
     );
+    let config = state.config.lock().clone();
+    maybe_show_osd(state, &config, message, OsdCategory::Error);
 }
 
 pub(crate) fn maybe_show_osd(
     state: &Arc<AppState>,
     config: &crate::config::SyncplayConfig,
     message: &str,
-    allow: bool,
+    category: OsdCategory,
 ) {
-    if !allow || !config.user.show_osd {
+    if !config.user.osd_allows(category) {
+        return;
+    }
+    // Errors still matter while the user does not want to be disturbed
+    if category != OsdCategory::Error && *state.do_not_disturb.lock() {
         return;
     }
     let player = state.player.lock().clone();
     let Some(player) = player else { return };
-    if let Err(e) = player.show_osd(message, Some(config.user.osd_duration)) {
+    let style = OsdStyle::from_config(&config.user);
+    if let Err(e) = player.show_osd(message, &style) {
         tracing::warn!("Failed to show OSD: {}", e);
     }
 }
 
 fn start_room_warning_loop(state: Arc<AppState>) {
-    let mut running = state.room_warning_task_running.lock();
+    let mut running = state.session.room_warning_task_running.lock();
     if *running {
         return;
     }
@@ -1032,32 +1668,121 @@ fn start_room_warning_loop(state: Arc<AppState>) {
 
     tokio::spawn(async move {
         let mut ticker = interval(Duration::from_secs(WARNING_OSD_INTERVAL_SECONDS));
+        let mut ticks: u32 = 0;
         loop {
             ticker.tick().await;
             if !state.is_connected() {
-                *state.room_warning_task_running.lock() = false;
+                *state.session.room_warning_task_running.lock() = false;
                 break;
             }
             update_room_warnings(&state, true);
+            check_room_finished(&state);
+            let file = state.session.client_state.get_file();
+            if state
+                .session
+                .endgame
+                .lock()
+                .is_waiting_to_advance(file.as_deref())
+            {
+                crate::player::controller::resume_playlist_advance(&state).await;
+            }
+            ticks = ticks.wrapping_add(1);
+            if ticks.is_multiple_of(SYNC_STATS_INTERVAL_TICKS) {
+                let report = state.sync_engine.lock().sync_report(false);
+                state.emit_event("sync-stats", report);
+                emit_user_list(&state);
+            }
         }
     });
 }
 
-fn update_room_warnings(state: &Arc<AppState>, osd_only: bool) {
+/// Progress of the room members with a file open that `include` selects
+///
+/// Users who never changed the playstate are assumed to follow the room position.
+pub(crate) fn room_member_progress(
+    state: &Arc<AppState>,
+    include: impl Fn(&crate::client::state::User) -> bool,
+) -> Vec<MemberProgress> {
+    let client_state = &state.session.client_state;
+    let room = client_state.get_room();
+    let current_username = client_state.get_username();
+    let global = client_state.get_global_state();
+    let now = std::time::Instant::now();
+    client_state
+        .get_users_in_room(&room)
+        .into_iter()
+        .filter(|user| {
+            !is_placeholder_username(&user.username) && user.file.is_some() && include(user)
+        })
+        .map(|user| {
+            if user.username == current_username {
+                return MemberProgress {
+                    position: local_user_playstate(state).map(|playstate| playstate.position),
+                    duration: client_state.get_file_duration(),
+                    username: user.username,
+                };
+            }
+            let position = client_state
+                .get_user_playstate(&user.username)
+                .map(|playstate| playstate.position_at(now))
+                .or_else(|| global.updated_at.map(|_| global.position_at(now)));
+            MemberProgress {
+                position,
+                duration: user.file_duration,
+                username: user.username,
+            }
+        })
+        .collect()
+}
+
+/// Announce once when everyone watching in the room reached the end of their file
+fn check_room_finished(state: &Arc<AppState>) {
+    let Some(file) = state.session.client_state.get_file() else {
+        return;
+    };
+    let room = state.session.client_state.get_room();
+    let members = room_member_progress(state, |_| true);
+    if !state.session.endgame.lock().update(&file, &members) {
+        return;
+    }
+    let config = state.config.lock().clone();
+    let message = state.tr("room-finished-file", &[&file]);
+    emit_system_message(state, &message);
+    maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
+    state.emit_event(
+        "room-finished",
+        RoomFinishedEvent {
+            room,
+            file,
+            users: members.into_iter().map(|member| member.username).collect(),
+        },
+    );
+}
+
+pub(crate) fn update_room_warnings(state: &Arc<AppState>, osd_only: bool) {
     let config = state.config.lock().clone();
     if autoplay_conditions_met(state) {
         return;
     }
     let warnings = compute_room_warning_state(state, &config);
-    let show_osd = config.user.show_osd && config.user.show_osd_warnings;
-    let mut last = state.room_warning_state.lock();
-    let mut timers = state.warning_timers.lock();
+    let show_osd = config.user.osd_allows(OsdCategory::Warning);
+    let mut last = state.session.room_warning_state.lock();
+    let mut timers = state.session.warning_timers.lock();
 
     if !osd_only && warnings.alone && !last.alone {
-        emit_system_message(state, "You are currently by yourself in the room");
+        emit_system_message(state, &state.tr("alone-in-room", &[]));
     }
 
     let was_not_ready = last.not_ready.is_some();
+    if warnings.file_difference_details != last.file_difference_details {
+        state.emit_event(
+            "file-differences",
+            FileDifferencesEvent {
+                room: state.session.client_state.get_room(),
+                differences: warnings.file_difference_details.clone(),
+            },
+        );
+    }
 
     update_warning_timer_state(&mut timers.alone, warnings.alone);
     update_warning_timer_state(
@@ -1101,7 +1826,7 @@ fn show_room_warning_osd(
     let Some(message) = build_room_warning_message(state, config, warnings) else {
         return;
     };
-    maybe_show_osd(state, config, &message, true);
+    maybe_show_osd(state, config, &message, OsdCategory::Warning);
 }
 
 fn update_warning_timer_state(timer: &mut WarningTimerState, active: bool) {
@@ -1154,32 +1879,32 @@ fn build_room_warning_message(
     config: &crate::config::SyncplayConfig,
     warnings: &crate::app_state::RoomWarningState,
 ) -> Option<String> {
-    if !config.user.show_osd {
+    if !config.user.osd_allows(OsdCategory::Warning) {
         return None;
     }
     if state.player.lock().is_none() {
         return None;
     }
-    if state.autoplay.lock().countdown_active {
-        return None;
+    {
+        let autoplay = state.autoplay.lock();
+        if autoplay.countdown_active || autoplay.unpause_countdown_active {
+            return None;
+        }
     }
 
     if warnings.alone {
-        return Some("You are currently by yourself in the room".to_string());
+        return Some(state.tr("alone-in-room", &[]));
     }
 
     let file_diff_message = warnings
         .file_differences
         .as_ref()
-        .map(|file_diff| format!("File differences: {}", file_diff));
+        .map(|file_diff| state.tr("file-differences", &[&file_diff]));
 
     let readiness_supported = is_readiness_supported(state, true);
     let ready_message = if readiness_supported {
         if are_all_users_in_room_ready(state, false) {
-            Some(format!(
-                "Everyone is ready ({} users)",
-                ready_user_count(state)
-            ))
+            Some(state.tr("all-users-ready", &[&ready_user_count(state)]))
         } else {
             warnings.not_ready.clone()
         }
@@ -1206,9 +1931,9 @@ fn compute_room_warning_state(
     state: &Arc<AppState>,
     config: &crate::config::SyncplayConfig,
 ) -> crate::app_state::RoomWarningState {
-    let current_room = state.client_state.get_room();
-    let current_username = state.client_state.get_username();
-    let users = state.client_state.get_users();
+    let current_room = state.session.client_state.get_room();
+    let current_username = state.session.client_state.get_username();
+    let users = state.session.client_state.get_users();
     let users_in_room: Vec<crate::client::state::User> = users
         .into_iter()
         .filter(|user| user.room == current_room)
@@ -1221,51 +1946,39 @@ fn compute_room_warning_state(
         .collect();
     let alone = others_in_room.is_empty() && !recently_connected(state);
 
-    let current_file = state.client_state.get_file();
-    let current_size = state.client_state.get_file_size();
-    let current_duration = state.client_state.get_file_duration();
-    let mut diff_name = false;
-    let mut diff_size = false;
-    let mut diff_duration = false;
+    let current_file = state.session.client_state.get_file();
+    let current_size = state.session.client_state.get_file_size();
+    let current_duration = state.session.client_state.get_file_duration();
+    let mut file_difference_details = Vec::new();
     if let Some(current_file) = current_file.as_ref() {
-        for user in others_in_room
-            .iter()
-            .filter(|user| user_can_control_in_room(state, user))
-        {
+        let mine = FileSide {
+            name: current_file,
+            size: current_size.as_ref(),
+            duration: current_duration,
+        };
+        for user in others_in_room.iter().filter(|user| {
+            user_can_control_in_room(state, user)
+                && !is_file_difference_trusted(state, config, user)
+        }) {
             let Some(other_file) = user.file.as_ref() else {
                 continue;
             };
-            if !same_filename(Some(current_file), Some(other_file)) {
-                diff_name = true;
-            }
-            if !crate::utils::same_filesize(current_size.as_ref(), user.file_size.as_ref()) {
-                diff_size = true;
-            }
-            if !same_duration(
-                current_duration,
-                user.file_duration,
+            let theirs = FileSide {
+                name: other_file,
+                size: user.file_size.as_ref(),
+                duration: user.file_duration,
+            };
+            if let Some(difference) = compare_files(
+                &user.username,
+                mine,
+                theirs,
                 config.user.show_duration_notification,
             ) {
-                diff_duration = true;
+                file_difference_details.push(difference);
             }
         }
     }
-
-    let mut diff_parts = Vec::new();
-    if diff_name {
-        diff_parts.push("name");
-    }
-    if diff_size {
-        diff_parts.push("size");
-    }
-    if diff_duration {
-        diff_parts.push("duration");
-    }
-    let file_differences = if diff_parts.is_empty() {
-        None
-    } else {
-        Some(diff_parts.join(", "))
-    };
+    let file_differences = describe_file_differences(state, &file_difference_details);
 
     let not_ready = if alone
         || !is_readiness_supported(state, true)
@@ -1288,46 +2001,18 @@ fn compute_room_warning_state(
         if not_ready_users.is_empty() {
             None
         } else {
-            Some(format!("Not ready: {}", not_ready_users.join(", ")))
+            Some(state.tr("not-ready-users", &[&not_ready_users.join(", ")]))
         }
     };
 
     crate::app_state::RoomWarningState {
         alone,
         file_differences,
+        file_difference_details,
         not_ready,
     }
 }
 
-fn format_time(time_seconds: f64) -> String {
-    let mut seconds = time_seconds.round() as i64;
-    let sign = if seconds < 0 {
-        seconds = -seconds;
-        "-"
-    } else {
-        ""
-    };
-
-    let weeks = seconds / 604_800;
-    let days = (seconds % 604_800) / 86_400;
-    let hours = (seconds % 86_400) / 3_600;
-    let minutes = (seconds % 3_600) / 60;
-    let secs = seconds % 60;
-
-    if weeks > 0 {
-        format!(
-            "{}{}w, {}d, {:02}:{:02}:{:02}",
-            sign, weeks, days, hours, minutes, secs
-        )
-    } else if days > 0 {
-        format!("{}{}d, {:02}:{:02}:{:02}", sign, days, hours, minutes, secs)
-    } else if hours > 0 {
-        format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, secs)
-    } else {
-        format!("{}{:02}:{:02}", sign, minutes, secs)
-    }
-}
-
 pub(crate) fn store_control_password(
     state: &Arc<AppState>,
     room: &str,
@@ -1368,7 +2053,7 @@ pub(crate) fn store_control_password(
 }
 
 pub fn reidentify_as_controller(state: &Arc<AppState>) {
-    let room = state.client_state.get_room();
+    let room = state.session.client_state.get_room();
     if !is_controlled_room(&room) {
         return;
     }
@@ -1376,10 +2061,7 @@ pub fn reidentify_as_controller(state: &Arc<AppState>) {
     let Some(password) = password else {
         return;
     };
-    let message = format!(
-        "Identifying as room operator with password '{}'...",
-        password
-    );
+    let message = state.tr("identifying-as-operator", &[&password]);
     emit_system_message(state, &message);
     *state.last_control_password_attempt.lock() = Some(password.clone());
     if let Err(e) = send_controller_auth(state, &room, &password) {
@@ -1388,7 +2070,7 @@ pub fn reidentify_as_controller(state: &Arc<AppState>) {
 }
 
 fn send_controller_auth(state: &Arc<AppState>, room: &str, password: &str) -> Result<(), String> {
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
@@ -1416,7 +2098,7 @@ fn send_controller_auth(state: &Arc<AppState>, room: &str, password: &str) -> Re
 }
 
 pub(crate) async fn handle_connection_closed(state: &Arc<AppState>) {
-    let connection = state.connection.lock().take();
+    let connection = state.session.connection.lock().take();
     if connection.is_none() {
         return;
     }
@@ -1426,9 +2108,11 @@ pub(crate) async fn handle_connection_closed(state: &Arc<AppState>) {
         return;
     }
 
-    *state.room_warning_state.lock() = crate::app_state::RoomWarningState::default();
-    *state.warning_timers.lock() = WarningTimers::default();
-    *state.room_warning_task_running.lock() = false;
+    state.session.reset_warnings();
+    if let Some(CloseReason::Stalled) = connection.and_then(|connection| connection.close_reason())
+    {
+        emit_error_message(state, &state.tr("connection-stalled", &[]));
+    }
 
     state.emit_event(
         "connection-status-changed",
@@ -1437,279 +2121,64 @@ pub(crate) async fn handle_connection_closed(state: &Arc<AppState>) {
             server: None,
         },
     );
-    state.emit_event(
-        "tls-status-changed",
-        serde_json::json!({ "status": "unknown" }),
-    );
+    state.set_tls_status("unknown");
+    crate::player::controller::refresh_idle_screen(state);
 
     if state.reconnect_state.lock().enabled {
         start_reconnect_loop(state.clone());
     } else {
-        emit_system_message(state, "Disconnected from server");
+        emit_system_message(state, &state.tr("disconnected", &[]));
+        finish_session_stats(state);
     }
 }
 
-async fn handle_set_message(state: &Arc<AppState>, set_msg: SetMessage) {
-    let has_index_update = set_msg.playlist_index.is_some();
-    if let Some(room) = set_msg.room {
-        state.client_state.set_room(room.name);
-        *state.had_first_playlist_index.lock() = false;
-        *state.playlist_may_need_restoring.lock() = false;
-        reidentify_as_controller(state);
-    }
-
-    if let Some(file) = set_msg.file {
-        if let Some(name) = file.name {
-            state.client_state.set_file(Some(name.clone()));
-            state.client_state.set_file_size(file.size.clone());
-            state.client_state.set_file_duration(file.duration);
-            if let Err(e) = load_media_by_name(state, &name, false, true).await {
-                tracing::warn!("Failed to load file from set: {}", e);
-            }
-        }
-    }
-
-    let mut users_changed = false;
-    let mut left_in_room = false;
-    if let Some(user_updates) = set_msg.user {
-        for (username, update) in user_updates {
-            if update
-                .event
-                .as_ref()
-                .and_then(|event| event.left)
-                .unwrap_or(false)
-            {
-                if let Some(user) = state.client_state.get_user(&username) {
-                    if user.room == state.client_state.get_room() {
-                        left_in_room = true;
-                    }
-                }
-            }
-            if apply_user_update(state, username, update) {
-                users_changed = true;
-            }
-        }
-    }
-
-    if let Some(ready) = set_msg.ready {
-        if let Some(username) = ready.username.clone() {
-            if is_placeholder_username(&username) {
-                tracing::debug!("Ready update contains placeholder username, ignoring");
-            } else {
-                let is_ready = match ready.is_ready {
-                    Some(value) => Some(value),
-                    None => state
-                        .client_state
-                        .get_user(&username)
-                        .and_then(|user| user.is_ready),
-                };
-
-                if let Some(mut user) = state.client_state.get_user(&username) {
-                    user.is_ready = is_ready;
-                    state.client_state.add_user(user);
-                    users_changed = true;
-                } else {
-                    state.client_state.add_user(crate::client::state::User {
-                        username: username.clone(),
-                        room: state.client_state.get_room(),
-                        file: None,
-                        file_size: None,
-                        file_duration: None,
-                        is_ready,
-                        is_controller: false,
-                    });
-                    users_changed = true;
-                }
-
-                if let Some(value) = ready.is_ready {
-                    if username == state.client_state.get_username() {
-                        state.client_state.set_ready(value);
-                    }
-                }
-
-                if let Some(set_by) = ready.set_by {
-                    let message = if ready.is_ready.unwrap_or(false) {
-                        format!("{} was set as ready by {}", username, set_by)
-                    } else {
-                        format!("{} was set as not ready by {}", username, set_by)
-                    };
-                    emit_system_message(state, &message);
-                }
-            }
-        } else {
-            tracing::debug!("Ready state missing username, ignoring");
-        }
-    }
-
-    if let Some(controller_auth) = set_msg.controller_auth {
-        handle_controller_auth(state, controller_auth);
-    }
-
-    if let Some(new_room) = set_msg.new_controlled_room {
-        handle_new_controlled_room(state, new_room).await;
-    }
-
-    if users_changed {
-        emit_user_list(state);
-    }
-
-    if left_in_room {
-        let config = state.config.lock().clone();
-        if config.user.pause_on_leave {
-            pause_local_player(state).await;
-        }
-    }
-
+/// End the watch session: announce its recap and add it to the history file when asked to
+fn finish_session_stats(state: &Arc<AppState>) {
+    let Some(stats) = state.session_stats.lock().take() else {
+        return;
+    };
+    let corrections = state.sync_engine.lock().correction_totals();
+    let summary = stats.summary(corrections, std::time::Instant::now());
     let config = state.config.lock().clone();
-    if shared_playlists_enabled(state, &config) {
-        let mut emit_playlist = false;
-        if let Some(change) = set_msg.playlist_change {
-            let room = state.client_state.get_room();
-            let mut should_restore = false;
-            {
-                let mut may_restore = state.playlist_may_need_restoring.lock();
-                if *may_restore {
-                    *may_restore = false;
-                    if change.files.is_empty()
-                        && change.user.is_none()
-                        && !state.playlist.get_item_filenames().is_empty()
-                        && !state.playlist.playlist_buffer_is_from_old_room(&room)
-                    {
-                        should_restore = true;
-                    }
-                }
-            }
-
-            if should_restore {
-                let items = state.playlist.get_item_filenames();
-                let restore_message = ProtocolMessage::Set {
-                    Set: Box::new(SetMessage {
-                        room: None,
-                        file: None,
-                        user: None,
-                        ready: None,
-                        playlist_index: None,
-                        playlist_change: Some(crate::network::messages::PlaylistChange {
-                            user: None,
-                            files: items.clone(),
-                        }),
-                        controller_auth: None,
-                        new_controlled_room: None,
-                        features: None,
-                    }),
-                };
-                if let Some(connection) = state.connection.lock().clone() {
-                    if let Err(e) = connection.send(restore_message) {
-                        tracing::warn!("Failed to restore playlist: {}", e);
-                    }
-                    if let Some(index) = state.playlist.get_current_index() {
-                        let index_message = ProtocolMessage::Set {
-                            Set: Box::new(SetMessage {
-                                room: None,
-                                file: None,
-                                user: None,
-                                ready: None,
-                                playlist_index: Some(
-                                    crate::network::messages::PlaylistIndexUpdate {
-                                        user: None,
-                                        index: Some(index),
-                                    },
-                                ),
-                                playlist_change: None,
-                                controller_auth: None,
-                                new_controlled_room: None,
-                                features: None,
-                            }),
-                        };
-                        if let Err(e) = connection.send(index_message) {
-                            tracing::warn!("Failed to restore playlist index: {}", e);
-                        }
-                    }
-                }
-            } else {
-                state
-                    .playlist
-                    .update_previous_playlist(&change.files, &room);
-                let current_index = state.playlist.get_current_index();
-                let next_index = match current_index {
-                    Some(index) if index < change.files.len() => Some(index),
-                    _ if change.files.is_empty() => None,
-                    _ => Some(0),
-                };
-                state
-                    .playlist
-                    .set_items_with_index(change.files, next_index);
-                emit_playlist = true;
-                if let Some(user) = change.user {
-                    let message = format!("{} updated the playlist", user);
-                    emit_system_message(state, &message);
-                    maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
-                }
-                if !has_index_update && state.client_state.get_file().is_none() {
-                    if let Some(index) = state.playlist.get_current_index() {
-                        if let Err(e) = apply_playlist_index_from_server(state, index, false).await
-                        {
-                            tracing::warn!("Failed to load playlist after sync: {}", e);
-                        }
-                    }
-                }
-            }
-        }
-
-        if let Some(index_update) = set_msg.playlist_index {
-            if let Some(index) = index_update.index {
-                let reset_position = {
-                    let mut had_first = state.had_first_playlist_index.lock();
-                    if !*had_first {
-                        *had_first = true;
-                        false
-                    } else {
-                        true
-                    }
-                };
-                let mut skipped_load = false;
-                let user = index_update.user.clone();
-                if let Some(user) = user.as_ref() {
-                    let current_user = state.client_state.get_username();
-                    if user == &current_user {
-                        let items = state.playlist.get_item_filenames();
-                        if let Some(filename) = items.get(index) {
-                            if same_filename(
-                                state.client_state.get_file().as_deref(),
-                                Some(filename),
-                            ) {
-                                state.playlist.set_current_index(index);
-                                state
-                                    .playlist
-                                    .set_queued_index_filename(Some(filename.clone()));
-                                emit_playlist_update(state);
-                                skipped_load = true;
-                            }
-                        }
-                    }
-                }
-                if !skipped_load {
-                    if let Err(e) =
-                        apply_playlist_index_from_server(state, index, reset_position).await
-                    {
-                        tracing::warn!("Failed to apply playlist index: {}", e);
-                    }
-                }
-                if let Some(user) = user {
-                    let message = format!("{} changed the playlist selection", user);
-                    emit_system_message(state, &message);
-                    maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
-                }
-                emit_playlist = false;
-            }
-        }
-
-        if emit_playlist {
-            emit_playlist_update(state);
+    let always_show_hours = config.user.always_show_hours;
+    emit_system_message(
+        state,
+        &state.tr(
+            "session-summary",
+            &[
+                &format_time(summary.watched_secs as f64, always_show_hours),
+                &format_time(summary.connected_secs as f64, always_show_hours),
+                &summary.files_played,
+                &summary.rewinds,
+                &summary.fastforwards,
+                &summary.chat_messages,
+            ],
+        ),
+    );
+    if config.user.session_history {
+        if let Err(e) = append_session_history(state, &summary) {
+            tracing::warn!("Failed to write session history: {}", e);
         }
     }
+    state.emit_event("session-summary", summary);
+}
+
+fn append_session_history(state: &Arc<AppState>, summary: &SessionSummary) -> anyhow::Result<()> {
+    use std::io::Write;
 
-    evaluate_autoplay(state);
+    let handle = state
+        .app_handle
+        .lock()
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("App is not running"))?;
+    let directory = handle.path().app_data_dir()?;
+    std::fs::create_dir_all(&directory)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(directory.join(SESSION_HISTORY_FILE))?;
+    writeln!(file, "{}", serde_json::to_string(summary)?)?;
+    Ok(())
 }
 
 fn handle_controller_auth(state: &Arc<AppState>, auth: ControllerAuth) {
@@ -1719,21 +2188,21 @@ fn handle_controller_auth(state: &Arc<AppState>, auth: ControllerAuth) {
     let username = auth
         .user
         .clone()
-        .unwrap_or_else(|| state.client_state.get_username());
+        .unwrap_or_else(|| state.session.client_state.get_username());
     let room = auth
         .room
         .clone()
-        .unwrap_or_else(|| state.client_state.get_room());
-    let current_room = state.client_state.get_room();
-    let current_username = state.client_state.get_username();
+        .unwrap_or_else(|| state.session.client_state.get_room());
+    let current_room = state.session.client_state.get_room();
+    let current_username = state.session.client_state.get_username();
     let config = state.config.lock().clone();
 
     if success {
         let changed = set_user_controller_status(state, &username, Some(&room), true);
         if room == current_room {
-            let message = format!("{} authenticated as a room operator", username);
+            let message = state.tr("operator-authenticated", &[&username]);
             emit_system_message(state, &message);
-            maybe_show_osd(state, &config, &message, config.user.show_same_room_osd);
+            maybe_show_osd(state, &config, &message, OsdCategory::SameRoom);
         }
         if username == current_username {
             if let Some(password) = state.last_control_password_attempt.lock().clone() {
@@ -1744,7 +2213,7 @@ fn handle_controller_auth(state: &Arc<AppState>, auth: ControllerAuth) {
             emit_user_list(state);
         }
     } else if username == current_username {
-        let message = format!("{} failed to identify as a room operator.", username);
+        let message = state.tr("operator-auth-failed", &[&username]);
         emit_error_message(state, &message);
     }
 }
@@ -1754,17 +2223,14 @@ async fn handle_new_controlled_room(state: &Arc<AppState>, room: NewControlledRo
         return;
     };
     let room_with_password = format!("{}:{}", room_name, password);
-    let message = format!(
-        "Created managed room '{}' with password '{}'. Please save this information for future reference!\n\nIn managed rooms everyone is kept in sync with the room operator(s) who are the only ones who can pause, unpause, seek, and change the playlist.\n\nYou should ask regular viewers to join the room '{}' but the room operators can join the room '{}' to automatically authenticate themselves.",
-        room_name,
-        password,
-        room_name,
-        room_with_password,
+    let message = state.tr(
+        "managed-room-created",
+        &[&room_name, &password, &room_name, &room_with_password],
     );
     emit_system_message(state, &message);
 
-    state.client_state.set_room(room_name.clone());
-    if let Some(connection) = state.connection.lock().clone() {
+    state.session.client_state.set_room(room_name.clone());
+    if let Some(connection) = state.session.connection.lock().clone() {
         let set_room = ProtocolMessage::Set {
             Set: Box::new(SetMessage {
                 room: Some(RoomInfo {
@@ -1807,26 +2273,29 @@ fn set_user_controller_status(
     room: Option<&str>,
     is_controller: bool,
 ) -> bool {
-    let mut user = state
-        .client_state
-        .get_user(username)
-        .unwrap_or(crate::client::state::User {
-            username: username.to_string(),
-            room: room
-                .map(|value| value.to_string())
-                .unwrap_or_else(|| state.client_state.get_room()),
-            file: None,
-            file_size: None,
-            file_duration: None,
-            is_ready: None,
-            is_controller: false,
-        });
+    let mut user =
+        state
+            .session
+            .client_state
+            .get_user(username)
+            .unwrap_or(crate::client::state::User {
+                username: username.to_string(),
+                room: room
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| state.session.client_state.get_room()),
+                file: None,
+                file_size: None,
+                file_duration: None,
+                is_ready: None,
+                is_controller: false,
+                utc_offset: None,
+            });
     if let Some(room) = room {
         user.room = room.to_string();
     }
     let changed = user.is_controller != is_controller;
     user.is_controller = is_controller;
-    state.client_state.add_user(user);
+    state.session.client_state.add_user(user);
     changed
 }
 
@@ -1835,7 +2304,7 @@ async fn handle_tls_message(state: &Arc<AppState>, tls: TLSMessage) {
         return;
     };
 
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else { return };
 
     if answer == "true" {
@@ -1844,31 +2313,19 @@ async fn handle_tls_message(state: &Arc<AppState>, tls: TLSMessage) {
             Ok(info) => info,
             Err(e) => {
                 tracing::error!("TLS upgrade failed: {}", e);
-                state.emit_event(
-                    "tls-status-changed",
-                    serde_json::json!({ "status": "unsupported" }),
-                );
+                state.set_tls_status("unsupported");
                 send_hello(state);
                 return;
             }
         };
-        state.emit_event(
-            "tls-status-changed",
-            serde_json::json!({ "status": "enabled" }),
-        );
+        state.set_tls_status("enabled");
         let protocol = tls_info.protocol.unwrap_or_else(|| "TLS".to_string());
-        emit_system_message(
-            state,
-            &format!("Secure connection established ({})", protocol),
-        );
+        emit_system_message(state, &state.tr("tls-established", &[&protocol]));
         send_hello(state);
     } else if answer == "false" {
         tracing::info!("Server does not support TLS, sending Hello");
         *state.server_supports_tls.lock() = false;
-        state.emit_event(
-            "tls-status-changed",
-            serde_json::json!({ "status": "unsupported" }),
-        );
+        state.set_tls_status("unsupported");
         send_hello(state);
     } else {
         tracing::debug!("Ignoring TLS message: {}", answer);
@@ -1876,15 +2333,15 @@ async fn handle_tls_message(state: &Arc<AppState>, tls: TLSMessage) {
 }
 
 fn send_hello(state: &Arc<AppState>) {
-    let mut hello_sent = state.hello_sent.lock();
+    let mut hello_sent = state.session.hello_sent.lock();
     if *hello_sent {
         return;
     }
 
-    let Some(hello) = state.last_hello.lock().clone() else {
+    let Some(hello) = state.session.last_hello.lock().clone() else {
         return;
     };
-    let Some(connection) = state.connection.lock().clone() else {
+    let Some(connection) = state.session.connection.lock().clone() else {
         return;
     };
 
@@ -1928,6 +2385,7 @@ fn maybe_autosave_connection<R: Runtime>(
     updated.server.host = snapshot.host.to_string();
     updated.server.port = snapshot.port;
     updated.server.password = snapshot.password.clone();
+    updated.server.password_hash = snapshot.password_hash.clone();
     updated.user.username = snapshot.username.to_string();
     updated.user.default_room = snapshot.room.to_string();
 
@@ -1935,6 +2393,11 @@ fn maybe_autosave_connection<R: Runtime>(
         host: snapshot.host.to_string(),
         port: snapshot.port,
         password: snapshot.password.clone(),
+        password_hash: snapshot.password_hash.clone(),
+        bind_address: updated.server.bind_address.clone(),
+        username: Some(snapshot.username.to_string()),
+        room: Some(snapshot.room.to_string()),
+        pinned: false,
     });
 
     if !updated
@@ -1945,6 +2408,12 @@ fn maybe_autosave_connection<R: Runtime>(
     {
         updated.user.room_list.insert(0, snapshot.room.to_string());
     }
+    if let Some(room_password) = snapshot.room_password.clone() {
+        updated
+            .user
+            .room_passwords
+            .insert(snapshot.room.clone(), room_password);
+    }
 
     if let Err(e) = save_config(app, &updated) {
         tracing::warn!("Failed to save config after connect: {}", e);
@@ -1955,13 +2424,14 @@ fn maybe_autosave_connection<R: Runtime>(
     state.emit_event("config-updated", updated);
 }
 
-fn current_user_can_control(state: &Arc<AppState>) -> bool {
-    let room = state.client_state.get_room();
+pub(crate) fn current_user_can_control(state: &Arc<AppState>) -> bool {
+    let room = state.session.client_state.get_room();
     if !is_controlled_room(&room) {
         return true;
     }
-    let username = state.client_state.get_username();
+    let username = state.session.client_state.get_username();
     state
+        .session
         .client_state
         .get_user(&username)
         .map(|user| user.is_controller)
@@ -1969,7 +2439,7 @@ fn current_user_can_control(state: &Arc<AppState>) -> bool {
 }
 
 fn user_can_control_in_room(state: &Arc<AppState>, user: &crate::client::state::User) -> bool {
-    let room = state.client_state.get_room();
+    let room = state.session.client_state.get_room();
     if !is_controlled_room(&room) {
         return true;
     }
@@ -1977,21 +2447,22 @@ fn user_can_control_in_room(state: &Arc<AppState>, user: &crate::client::state::
 }
 
 fn current_user_ready_with_file(state: &Arc<AppState>) -> Option<bool> {
-    state.client_state.get_file()?;
-    Some(state.client_state.is_ready())
+    state.session.client_state.get_file()?;
+    Some(state.session.client_state.is_ready())
 }
 
 fn is_readiness_supported(state: &Arc<AppState>, requires_other_users: bool) -> bool {
-    let features = state.server_features.lock();
+    let features = state.session.server_features.lock();
     if !features.readiness {
         return false;
     }
     if !requires_other_users {
         return true;
     }
-    let room = state.client_state.get_room();
-    let username = state.client_state.get_username();
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
     let others_support = state
+        .session
         .client_state
         .get_users_in_room(&room)
         .iter()
@@ -2007,13 +2478,13 @@ fn are_all_users_in_room_ready(state: &Arc<AppState>, require_same_filenames: bo
     if current_ready != Some(true) {
         return false;
     }
-    let current_file = state.client_state.get_file();
+    let current_file = state.session.client_state.get_file();
     if require_same_filenames && current_file.is_none() {
         return false;
     }
-    let room = state.client_state.get_room();
-    let username = state.client_state.get_username();
-    for user in state.client_state.get_users_in_room(&room) {
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
+    for user in state.session.client_state.get_users_in_room(&room) {
         if user.username == username {
             continue;
         }
@@ -2046,9 +2517,9 @@ fn are_all_relevant_users_in_room_ready(
     if current_user_can_control(state) {
         return are_all_users_in_room_ready(state, require_same_filenames);
     }
-    let room = state.client_state.get_room();
-    let current_file = state.client_state.get_file();
-    for user in state.client_state.get_users_in_room(&room) {
+    let room = state.session.client_state.get_room();
+    let current_file = state.session.client_state.get_file();
+    for user in state.session.client_state.get_users_in_room(&room) {
         if !user_can_control_in_room(state, &user) {
             continue;
         }
@@ -2071,9 +2542,9 @@ fn are_all_relevant_users_in_room_ready(
 }
 
 fn are_all_other_users_ready(state: &Arc<AppState>) -> bool {
-    let room = state.client_state.get_room();
-    let username = state.client_state.get_username();
-    for user in state.client_state.get_users_in_room(&room) {
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
+    for user in state.session.client_state.get_users_in_room(&room) {
         if user.username == username {
             continue;
         }
@@ -2085,10 +2556,10 @@ fn are_all_other_users_ready(state: &Arc<AppState>) -> bool {
 }
 
 fn users_in_room_count(state: &Arc<AppState>) -> usize {
-    let room = state.client_state.get_room();
-    let username = state.client_state.get_username();
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
     let mut count = 1;
-    for user in state.client_state.get_users_in_room(&room) {
+    for user in state.session.client_state.get_users_in_room(&room) {
         if user.username == username {
             continue;
         }
@@ -2100,7 +2571,7 @@ fn users_in_room_count(state: &Arc<AppState>) -> usize {
 }
 
 fn shared_playlists_enabled(state: &Arc<AppState>, config: &crate::config::SyncplayConfig) -> bool {
-    config.user.shared_playlist_enabled && state.server_features.lock().shared_playlists
+    config.user.shared_playlist_enabled && state.session.server_features.lock().shared_playlists
 }
 
 fn recently_connected(state: &Arc<AppState>) -> bool {
@@ -2112,8 +2583,7 @@ fn recently_connected(state: &Arc<AppState>) -> bool {
 }
 
 fn recently_advanced(state: &Arc<AppState>) -> bool {
-    let guard = state.last_advance_time.lock();
-    let Some(last_advance) = guard.as_ref() else {
+    let Some(last_advance) = state.session.actor.snapshot().last_advance_time else {
         return false;
     };
     last_advance.elapsed().as_secs_f64() < (AUTOPLAY_DELAY_SECONDS as f64 + 5.0)
@@ -2121,6 +2591,7 @@ fn recently_advanced(state: &Arc<AppState>) -> bool {
 
 fn is_playing_music(state: &Arc<AppState>) -> bool {
     state
+        .session
         .client_state
         .get_file()
         .as_deref()
@@ -2151,7 +2622,7 @@ fn maybe_unpause_for_music(state: &Arc<AppState>) {
     });
 }
 
-fn send_ready_state(
+pub(crate) fn send_ready_state(
     state: &Arc<AppState>,
     is_ready: bool,
     manually_initiated: bool,
@@ -2159,8 +2630,8 @@ fn send_ready_state(
     if !is_readiness_supported(state, false) {
         return Ok(());
     }
-    state.client_state.set_ready(is_ready);
-    let username = state.client_state.get_username();
+    state.session.client_state.set_ready(is_ready);
+    let username = state.session.client_state.get_username();
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
@@ -2179,7 +2650,7 @@ fn send_ready_state(
             features: None,
         }),
     };
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
@@ -2262,12 +2733,11 @@ fn start_autoplay_countdown(state: Arc<AppState>) {
             if !should_unpause {
                 let remaining = state.autoplay.lock().countdown_remaining;
                 let ready_count = ready_user_count(&state);
-                let message = format!(
-                    "All users ready ({}) - autoplaying in {}s",
-                    ready_count, remaining
-                );
-                if let Some(player) = state.player.lock().clone() {
-                    let _ = player.show_osd(&message, Some(1000));
+                let message = state.tr("autoplay-countdown", &[&ready_count, &remaining]);
+                let style = OsdStyle::from_config(&state.config.lock().user).with_duration(1000);
+                let player = state.player.lock().clone();
+                if let Some(player) = player {
+                    let _ = player.show_osd(&message, &style);
                 }
             }
 
@@ -2294,6 +2764,274 @@ fn start_autoplay_countdown(state: Arc<AppState>) {
     });
 }
 
+/// Collect readiness answers for `seconds`, then report who is ready
+///
+/// The initiator announces the check to the room through chat.
+pub(crate) fn start_ready_check(
+    state: Arc<AppState>,
+    initiator: String,
+    seconds: u64,
+    announce: bool,
+) {
+    let room = state.session.client_state.get_room();
+    let check = ReadyCheck::new(initiator.clone(), room, Duration::from_secs(seconds));
+    let (started, timeout) = (check.started, check.timeout);
+    *state.session.ready_check.lock() = Some(check);
+
+    if announce {
+        let message = ProtocolMessage::Chat {
+            Chat: crate::network::messages::ChatMessage::Text(format!(
+                "{}{}",
+                protocol::READY_CHECK_CHAT_PREFIX,
+                seconds
+            )),
+        };
+        if let Some(connection) = state.session.connection.lock().clone() {
+            if let Err(e) = connection.send(message) {
+                tracing::warn!("Failed to announce ready check: {}", e);
+            }
+        }
+    }
+
+    let config = state.config.lock().clone();
+    let message = state.tr("ready-check-started", &[&initiator, &seconds]);
+    emit_system_message(&state, &message);
+    maybe_show_osd(&state, &config, &message, OsdCategory::SameRoom);
+    state.emit_event(
+        "ready-check-started",
+        serde_json::json!({ "initiator": initiator, "seconds": seconds }),
+    );
+
+    tokio::spawn(async move {
+        sleep(timeout).await;
+        let check = {
+            let mut current = state.session.ready_check.lock();
+            if current.as_ref().map(|check| check.started) != Some(started) {
+                // Replaced by a newer check or cleared by a reconnect
+                return;
+            }
+            current.take()
+        };
+        let Some(check) = check else { return };
+        let members = state
+            .session
+            .client_state
+            .get_users_in_room(&check.room)
+            .into_iter()
+            .filter(|user| !is_placeholder_username(&user.username))
+            .map(|user| (user.username, user.is_ready));
+        let summary = check.summary(members);
+        let total = summary.ready.len() + summary.not_ready.len() + summary.no_response.len();
+        let mut message = state.tr("ready-check-result", &[&summary.ready.len(), &total]);
+        let missing: Vec<&str> = summary
+            .not_ready
+            .iter()
+            .chain(summary.no_response.iter())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            message.push_str(OSD_MESSAGE_SEPARATOR);
+            message.push_str(&state.tr("not-ready-users", &[&missing.join(", ")]));
+        }
+        emit_system_message(&state, &message);
+        let config = state.config.lock().clone();
+        maybe_show_osd(&state, &config, &message, OsdCategory::SameRoom);
+        state.emit_event("ready-check-summary", summary);
+    });
+}
+
+/// Count down to a scheduled session start and unpause once it is reached
+///
+/// `start_ms` is in server time, the ping clock offset maps it onto the local clock.
+/// Users who cannot control the room follow the operators' unpause instead.
+pub(crate) fn start_scheduled_session(
+    state: Arc<AppState>,
+    actor: String,
+    start_ms: i64,
+    announce: bool,
+) {
+    if !announce {
+        let server_now_ms = (state
+            .session
+            .ping_service
+            .lock()
+            .local_to_server_time(crate::network::ping::PingService::new_timestamp())
+            * 1000.0) as i64;
+        if !protocol::scheduled_start_in_window(start_ms, server_now_ms) {
+            tracing::debug!(
+                "Ignoring scheduled start from {} outside the scheduling window",
+                actor
+            );
+            return;
+        }
+    }
+    state.autoplay.lock().scheduled_start = Some(start_ms);
+
+    if announce {
+        let message = ProtocolMessage::Chat {
+            Chat: crate::network::messages::ChatMessage::Text(format!(
+                "{}{}",
+                protocol::SCHEDULED_START_CHAT_PREFIX,
+                start_ms
+            )),
+        };
+        if let Some(connection) = state.session.connection.lock().clone() {
+            if let Err(e) = connection.send(message) {
+                tracing::warn!("Failed to announce scheduled start: {}", e);
+            }
+        }
+    }
+
+    let local_start_ms = (state
+        .session
+        .ping_service
+        .lock()
+        .server_to_local_time(start_ms as f64 / 1000.0)
+        * 1000.0) as i64;
+    let local_start = chrono::DateTime::from_timestamp_millis(local_start_ms).unwrap_or_default();
+    let start_label = local_start
+        .with_timezone(&chrono::Local)
+        .format("%H:%M:%S")
+        .to_string();
+    let config = state.config.lock().clone();
+    let message = state.tr("session-scheduled", &[&actor, &start_label]);
+    emit_system_message(&state, &message);
+    maybe_show_osd(&state, &config, &message, OsdCategory::SameRoom);
+    state.emit_event(
+        "session-scheduled",
+        serde_json::json!({ "setBy": actor, "startTime": local_start.to_rfc3339() }),
+    );
+
+    tokio::spawn(async move {
+        loop {
+            if state.autoplay.lock().scheduled_start != Some(start_ms) {
+                return;
+            }
+            if !state.is_connected() {
+                state.autoplay.lock().scheduled_start = None;
+                return;
+            }
+            // Re-read the offset on every step, it keeps improving while we wait
+            let local_start = state
+                .session
+                .ping_service
+                .lock()
+                .server_to_local_time(start_ms as f64 / 1000.0);
+            let remaining = local_start - crate::network::ping::PingService::new_timestamp();
+            if remaining <= 0.0 {
+                break;
+            }
+            let whole_seconds = remaining.ceil() as u64;
+            if whole_seconds <= SCHEDULED_START_COUNTDOWN_SECONDS
+                || whole_seconds.is_multiple_of(60)
+            {
+                let message = state.tr(
+                    "session-starts-in",
+                    &[&format_time(whole_seconds as f64, false)],
+                );
+                let style = OsdStyle::from_config(&state.config.lock().user).with_duration(1000);
+                let player = state.player.lock().clone();
+                if let Some(player) = player {
+                    let _ = player.show_osd(&message, &style);
+                }
+            }
+            // Wake up on the whole seconds before the start so the last step lands on it
+            let step = remaining - (whole_seconds - 1) as f64;
+            sleep(Duration::from_secs_f64(step.clamp(0.001, 1.0))).await;
+        }
+
+        {
+            let mut autoplay = state.autoplay.lock();
+            if autoplay.scheduled_start != Some(start_ms) {
+                return;
+            }
+            autoplay.scheduled_start = None;
+        }
+        if !current_user_can_control(&state) {
+            return;
+        }
+        if let Err(e) = ensure_player_connected(&state).await {
+            tracing::warn!("Failed to connect to player for scheduled start: {}", e);
+            return;
+        }
+        let player = state.player.lock().clone();
+        if let Some(player) = player {
+            if player.get_state().paused == Some(false) {
+                return;
+            }
+            state.autoplay.lock().scheduled_start_due = true;
+            if let Err(e) = player.set_paused(false).await {
+                state.autoplay.lock().scheduled_start_due = false;
+                tracing::warn!("Failed to unpause at scheduled start: {}", e);
+            }
+        }
+    });
+}
+
+/// Count down on the OSD before an unpause
+///
+/// The initiating client announces the countdown in chat and unpauses when it ends;
+/// other clients only show it and follow the resulting state update.
+pub(crate) fn start_unpause_countdown(state: Arc<AppState>, seconds: i32, initiator: bool) {
+    {
+        let mut autoplay = state.autoplay.lock();
+        if autoplay.unpause_countdown_active {
+            return;
+        }
+        autoplay.unpause_countdown_active = true;
+    }
+
+    if initiator {
+        let message = ProtocolMessage::Chat {
+            Chat: crate::network::messages::ChatMessage::Text(format!(
+                "{}{}",
+                protocol::UNPAUSE_COUNTDOWN_CHAT_PREFIX,
+                seconds
+            )),
+        };
+        if let Some(connection) = state.session.connection.lock().clone() {
+            if let Err(e) = connection.send(message) {
+                tracing::warn!("Failed to announce unpause countdown: {}", e);
+            }
+        }
+    }
+
+    tokio::spawn(async move {
+        for remaining in (1..=seconds).rev() {
+            if !state.autoplay.lock().unpause_countdown_active || !state.is_connected() {
+                state.autoplay.lock().unpause_countdown_active = false;
+                return;
+            }
+            let message = state.tr("unpause-countdown", &[&remaining]);
+            let style = OsdStyle::from_config(&state.config.lock().user).with_duration(1000);
+            let player = state.player.lock().clone();
+            if let Some(player) = player {
+                let _ = player.show_osd(&message, &style);
+            }
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        {
+            let mut autoplay = state.autoplay.lock();
+            autoplay.unpause_countdown_active = false;
+            autoplay.unpause_countdown_bypass = initiator;
+        }
+        if !initiator {
+            return;
+        }
+        if let Err(e) = ensure_player_connected(&state).await {
+            tracing::warn!("Failed to connect to player for unpause: {}", e);
+            return;
+        }
+        let player = state.player.lock().clone();
+        if let Some(player) = player {
+            if let Err(e) = player.set_paused(false).await {
+                tracing::warn!("Failed to unpause after countdown: {}", e);
+            }
+        }
+    });
+}
+
 pub(crate) fn evaluate_autoplay(state: &Arc<AppState>) {
     if autoplay_conditions_met(state) {
         start_autoplay_countdown(state.clone());
@@ -2305,12 +3043,12 @@ pub(crate) fn evaluate_autoplay(state: &Arc<AppState>) {
 }
 
 fn ready_user_count(state: &Arc<AppState>) -> usize {
-    let room = state.client_state.get_room();
+    let room = state.session.client_state.get_room();
     let mut count = 0usize;
-    if state.client_state.get_file().is_some() && state.client_state.is_ready() {
+    if state.session.client_state.get_file().is_some() && state.session.client_state.is_ready() {
         count += 1;
     }
-    for user in state.client_state.get_users_in_room(&room) {
+    for user in state.session.client_state.get_users_in_room(&room) {
         if user.is_ready_with_file() == Some(true) {
             count += 1;
         }
@@ -2318,7 +3056,7 @@ fn ready_user_count(state: &Arc<AppState>) -> usize {
     count
 }
 
-async fn pause_local_player(state: &Arc<AppState>) {
+pub(crate) async fn pause_local_player(state: &Arc<AppState>) {
     if let Err(e) = ensure_player_connected(state).await {
         tracing::warn!("Failed to connect to player for pause: {}", e);
         return;
@@ -2328,137 +3066,135 @@ async fn pause_local_player(state: &Arc<AppState>) {
         if let Err(e) = player.set_paused(true).await {
             tracing::warn!("Failed to pause player: {}", e);
         }
-        *state.last_paused_on_leave_time.lock() = Some(std::time::Instant::now());
+        state
+            .session
+            .actor
+            .send(SessionCommand::MarkPausedOnLeave(std::time::Instant::now()));
     }
 }
 
-fn apply_user_update(state: &Arc<AppState>, username: String, update: UserUpdate) -> bool {
-    if is_placeholder_username(&username) {
-        tracing::debug!("User update contains placeholder username, ignoring");
-        return false;
-    }
-
+/// Tell the room about a user update the protocol handler already applied
+fn announce_user_change(state: &Arc<AppState>, username: &str, change: &UserChange) {
     let config = state.config.lock().clone();
-    let current_username = state.client_state.get_username();
-    let current_room = state.client_state.get_room();
-    let old_user = state.client_state.get_user(&username);
-
-    if let Some(event) = update.event.as_ref() {
-        if event.left.unwrap_or(false) {
-            if let Some(old_user) = old_user.as_ref() {
-                let allow_osd = if old_user.room == current_room {
-                    config.user.show_same_room_osd
-                } else {
-                    config.user.show_different_room_osd
-                };
-                let message = format!("{} has left", username);
-                emit_system_message(state, &message);
-                maybe_show_osd(state, &config, &message, allow_osd);
+    let current_username = state.session.client_state.get_username();
+    let current_room = state.session.client_state.get_room();
+
+    let (old_user, user, file_updated) = match change {
+        UserChange::Left { user: None } => return,
+        UserChange::Left {
+            user: Some(old_user),
+        } => {
+            let category = if old_user.room == current_room {
+                OsdCategory::SameRoom
+            } else {
+                OsdCategory::DifferentRoom
+            };
+            let message = state.tr("user-left", &[&username]);
+            emit_system_message(state, &message);
+            if !config.user.is_user_ignored(username) {
+                maybe_show_osd(state, &config, &message, category);
             }
-            state.client_state.remove_user(&username);
-            return true;
+            record_room_event(
+                state,
+                RoomEvent::new(username, old_user.room.as_str(), RoomEventKind::Left),
+            );
+            return;
         }
-    }
-
-    let mut user = state
-        .client_state
-        .get_user(&username)
-        .unwrap_or(crate::client::state::User {
-            username: username.clone(),
-            room: state.client_state.get_room(),
-            file: None,
-            file_size: None,
-            file_duration: None,
-            is_ready: None,
-            is_controller: false,
-        });
-
-    if let Some(room) = update.room {
-        user.room = room.name;
-    }
-
-    let mut updated_file = None;
-    if let Some(file) = update.file {
-        user.file = file.name;
-        user.file_size = file.size;
-        user.file_duration = file.duration;
-        updated_file = Some(());
-    }
-    if let Some(is_ready) = update.is_ready {
-        user.is_ready = Some(is_ready);
-    }
-    if let Some(controller) = update.controller {
-        user.is_controller = controller;
-    }
-
-    let room_changed = old_user
-        .as_ref()
-        .map(|old| old.room != user.room)
-        .unwrap_or(true);
-    let file_changed = if updated_file.is_some() {
-        !is_same_file(old_user.as_ref(), &user, &config)
-    } else {
-        false
+        UserChange::Updated {
+            old,
+            user,
+            file_updated,
+        } => (old.as_ref(), user, *file_updated),
     };
 
-    if updated_file.is_some() && file_changed {
+    let room_changed = old_user.map(|old| old.room != user.room).unwrap_or(true);
+    let file_changed = file_updated && !is_same_file(old_user, user, &config);
+
+    if file_changed {
         if let Some(file_name) = user.file.as_ref() {
             let duration = user.file_duration.unwrap_or(0.0);
             let duration_text = if duration > 0.0 {
-                format_time(duration)
+                format_time(duration, config.user.always_show_hours)
             } else {
                 "--:--".to_string()
             };
-            let mut message = format!(
-                "{} is playing '{}' ({})",
-                username, file_name, duration_text
-            );
+            let mut message = state.tr("user-playing", &[&username, file_name, &duration_text]);
             if current_room != user.room || username == current_username {
-                message.push_str(&format!(" in room: '{}'", user.room));
+                message.push_str(&state.tr("user-playing-in-room", &[&user.room]));
             }
             emit_system_message(state, &message);
-            let allow_osd = allow_osd_for_user(&config, &current_room, old_user.as_ref(), &user);
-            maybe_show_osd(state, &config, &message, allow_osd);
+            if let Some(category) = osd_category_for_user(&config, &current_room, old_user, user) {
+                maybe_show_osd(state, &config, &message, category);
+            }
+            record_room_event(
+                state,
+                RoomEvent::new(
+                    username,
+                    user.room.as_str(),
+                    RoomEventKind::FileChanged {
+                        file: file_name.clone(),
+                        duration: user.file_duration,
+                    },
+                ),
+            );
 
             if username != current_username {
-                if let Some(diff) = file_differences(state, &user, &config) {
-                    let message = format!("Your file differs in the following way(s): {}", diff);
-                    emit_system_message(state, &message);
+                if let Some(difference) = file_differences(state, user, &config) {
+                    if let Some(summary) =
+                        describe_file_differences(state, std::slice::from_ref(&difference))
+                    {
+                        emit_system_message(state, &state.tr("your-file-differs", &[&summary]));
+                    }
                 }
             }
         }
     } else if room_changed {
-        let message = format!("{} has joined the room: '{}'", username, user.room);
+        let message = state.tr("user-joined-room", &[&username, &user.room]);
         emit_system_message(state, &message);
-        let allow_osd = allow_osd_for_user(&config, &current_room, old_user.as_ref(), &user);
-        maybe_show_osd(state, &config, &message, allow_osd);
+        if let Some(category) = osd_category_for_user(&config, &current_room, old_user, user) {
+            maybe_show_osd(state, &config, &message, category);
+        }
+        record_room_event(
+            state,
+            RoomEvent::new(username, user.room.as_str(), RoomEventKind::Joined),
+        );
+        if user.room == current_room {
+            crate::commands::room::reannounce_operators_only(state);
+            if username != current_username {
+                scripts::dispatch(
+                    state,
+                    ScriptEvent::UserJoined {
+                        username: username.to_string(),
+                        room: user.room.clone(),
+                    },
+                );
+            }
+        }
     }
-
-    state.client_state.add_user(user);
-    true
 }
 
-fn allow_osd_for_user(
+/// OSD category for an event about `user`, or None when it should not be shown
+fn osd_category_for_user(
     config: &crate::config::SyncplayConfig,
     current_room: &str,
     old_user: Option<&crate::client::state::User>,
     user: &crate::client::state::User,
-) -> bool {
+) -> Option<OsdCategory> {
     let was_in_room = old_user
         .map(|old| old.room == current_room)
         .unwrap_or(false);
     let is_in_room = user.room == current_room;
-    let allow = if was_in_room || is_in_room {
-        config.user.show_same_room_osd
-    } else {
-        config.user.show_different_room_osd
-    };
-
-    if !config.user.show_non_controller_osd && !user.is_controller {
-        return false;
+    if config.user.is_user_ignored(&user.username) {
+        return None;
     }
-
-    allow
+    if !user.is_controller && !config.user.osd_allows(OsdCategory::NonController) {
+        return None;
+    }
+    Some(if was_in_room || is_in_room {
+        OsdCategory::SameRoom
+    } else {
+        OsdCategory::DifferentRoom
+    })
 }
 
 fn is_same_file(
@@ -2480,63 +3216,118 @@ fn is_same_file(
     same_name && same_size && same_duration
 }
 
-fn same_duration(a: Option<f64>, b: Option<f64>, allow: bool) -> bool {
-    if !allow {
-        return true;
-    }
-    let (Some(a), Some(b)) = (a, b) else {
-        return false;
-    };
-    (a.round() - b.round()).abs() < DIFFERENT_DURATION_THRESHOLD
-}
-
 fn file_differences(
     state: &Arc<AppState>,
     user: &crate::client::state::User,
     config: &crate::config::SyncplayConfig,
-) -> Option<String> {
-    if user.room != state.client_state.get_room() {
+) -> Option<FileDifference> {
+    if user.room != state.session.client_state.get_room() {
         return None;
     }
-    let current_file = state.client_state.get_file();
-    let current_size = state.client_state.get_file_size();
-    let current_duration = state.client_state.get_file_duration();
+    let current_file = state.session.client_state.get_file();
+    let current_size = state.session.client_state.get_file_size();
+    let current_duration = state.session.client_state.get_file_duration();
     let (Some(current_file), Some(other_file)) = (current_file.as_ref(), user.file.as_ref()) else {
         return None;
     };
-
-    let mut differences = Vec::new();
-    if !same_filename(Some(current_file), Some(other_file)) {
-        differences.push("name");
-    }
-    if !crate::utils::same_filesize(current_size.as_ref(), user.file_size.as_ref()) {
-        differences.push("size");
+    if is_file_difference_trusted(state, config, user) {
+        return None;
     }
-    if !same_duration(
-        current_duration,
-        user.file_duration,
+    compare_files(
+        &user.username,
+        FileSide {
+            name: current_file,
+            size: current_size.as_ref(),
+            duration: current_duration,
+        },
+        FileSide {
+            name: other_file,
+            size: user.file_size.as_ref(),
+            duration: user.file_duration,
+        },
         config.user.show_duration_notification,
-    ) {
-        differences.push("duration");
-    }
+    )
+}
 
-    if differences.is_empty() {
-        None
-    } else {
-        Some(differences.join(", "))
+/// "name, size" style summary of which attributes differ from any of `differences`
+fn describe_file_differences(
+    state: &Arc<AppState>,
+    differences: &[FileDifference],
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if differences
+        .iter()
+        .any(|difference| difference.name.is_some())
+    {
+        parts.push(state.tr("file-difference-name", &[]));
+    }
+    if differences
+        .iter()
+        .any(|difference| difference.size.is_some())
+    {
+        parts.push(state.tr("file-difference-size", &[]));
     }
+    if differences
+        .iter()
+        .any(|difference| difference.duration.is_some())
+    {
+        parts.push(state.tr("file-difference-duration", &[]));
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
 }
 
-fn is_placeholder_username(username: &str) -> bool {
-    username.trim().is_empty()
+/// Whether differences with this user's file were accepted, for their file or for the session
+pub(crate) fn is_file_difference_trusted(
+    state: &Arc<AppState>,
+    config: &crate::config::SyncplayConfig,
+    user: &crate::client::state::User,
+) -> bool {
+    if state.session_trusted_users.lock().contains(&user.username) {
+        return true;
+    }
+    user.file
+        .as_deref()
+        .is_some_and(|file| config.user.is_file_trusted(&user.username, file))
 }
 
-fn emit_user_list(state: &Arc<AppState>) {
-    let users = state.client_state.get_users();
-    let users_json: Vec<serde_json::Value> = users
+pub(crate) fn emit_user_list(state: &Arc<AppState>) {
+    let client_state = &state.session.client_state;
+    let mut users: Vec<_> = client_state
+        .get_users()
         .into_iter()
         .filter(|u| !is_placeholder_username(&u.username))
+        .collect();
+    let current_username = client_state.get_username();
+    let rooms = group_users_by_room(
+        &users,
+        &current_username,
+        &client_state.get_room(),
+        client_state.get_file().as_deref(),
+    );
+    // Same order as the groups, so the flat list needs no sorting either
+    let order: HashMap<&str, usize> = rooms
+        .iter()
+        .flat_map(|room| room.users.iter())
+        .enumerate()
+        .map(|(index, username)| (username.as_str(), index))
+        .collect();
+    users.sort_by_key(|u| order.get(u.username.as_str()).copied());
+    let local_playstate = local_user_playstate(state);
+    let config = state.config.lock().clone();
+    let users_json: Vec<serde_json::Value> = users
+        .into_iter()
         .map(|u| {
+            let playstate = if u.username == current_username {
+                local_playstate.clone()
+            } else {
+                client_state.get_user_playstate(&u.username)
+            };
+            let position = playstate
+                .as_ref()
+                .map(|p| p.position_at(std::time::Instant::now()));
+            let sync_delta = playstate
+                .as_ref()
+                .and_then(|p| client_state.user_sync_delta(p));
             serde_json::json!({
                 "username": u.username,
                 "room": u.room,
@@ -2545,17 +3336,34 @@ fn emit_user_list(state: &Arc<AppState>) {
                 "fileDuration": u.file_duration,
                 "isReady": u.is_ready.unwrap_or(false),
                 "isController": u.is_controller,
+                "position": position,
+                "paused": playstate.as_ref().map(|p| p.paused),
+                "syncDelta": sync_delta,
+                "fileTrusted": is_file_difference_trusted(state, &config, &u),
+                "utcOffset": u.utc_offset,
             })
         })
         .collect();
     state.emit_event(
         "user-list-updated",
-        serde_json::json!({ "users": users_json }),
+        serde_json::json!({ "users": users_json, "rooms": rooms }),
     );
 }
 
+/// The local player's state, which is fresher than anything the server echoes back
+fn local_user_playstate(state: &Arc<AppState>) -> Option<crate::client::state::UserPlayState> {
+    let player = state.player.lock().clone()?;
+    let player_state = player.get_state();
+    Some(crate::client::state::UserPlayState {
+        position: player_state.position?,
+        paused: player_state.paused?,
+        reported_at: std::time::Instant::now(),
+    })
+}
+
 fn emit_playlist_update(state: &Arc<AppState>) {
     let items: Vec<String> = state
+        .session
         .playlist
         .get_items()
         .iter()
@@ -2565,9 +3373,30 @@ fn emit_playlist_update(state: &Arc<AppState>) {
         "playlist-updated",
         crate::app_state::PlaylistEvent {
             items,
-            current_index: state.playlist.get_current_index(),
+            current_index: state.session.playlist.get_current_index(),
         },
     );
+    crate::commands::playlist::persist_playlist(state);
+}
+
+#[tauri::command]
+pub async fn cancel_reconnect(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    let was_reconnecting = {
+        let mut reconnect = state.reconnect_state.lock();
+        // While connected this must not turn auto-reconnect off for later drops
+        if reconnect.running {
+            reconnect.enabled = false;
+            reconnect.attempts = 0;
+        }
+        reconnect.running
+    };
+    if !was_reconnecting {
+        return Err("Not reconnecting".to_string());
+    }
+    tracing::info!("Reconnect cancelled by user");
+    emit_system_message(state.inner(), &state.tr("reconnect-cancelled", &[]));
+    finish_session_stats(state.inner());
+    Ok(())
 }
 
 #[tauri::command]
@@ -2581,9 +3410,10 @@ pub async fn disconnect_from_server(state: State<'_, Arc<AppState>>) -> Result<(
         reconnect.attempts = 0;
     }
     *state.manual_disconnect.lock() = true;
+    finish_session_stats(state.inner());
 
     // Disconnect
-    if let Some(connection) = state.connection.lock().take() {
+    if let Some(connection) = state.session.connection.lock().take() {
         connection.disconnect();
     }
 
@@ -2591,27 +3421,26 @@ pub async fn disconnect_from_server(state: State<'_, Arc<AppState>>) -> Result<(
         tracing::warn!("Failed to stop player: {}", e);
     }
 
-    state.client_state.clear_users();
-    state.playlist.clear();
-    state.client_state.set_file(None);
-    state.client_state.set_ready(false);
-    *state.server_features.lock() = ServerFeatures::default();
+    state.session.client_state.clear_users();
+    state.session.client_state.clear_user_playstates();
+    state.session.playlist.clear();
+    state.session.client_state.set_file(None);
+    state.session.client_state.set_ready(false);
+    *state.session.server_features.lock() = ServerFeatures::default();
+    *state.session.feature_negotiation.lock() = None;
     *state.playlist_may_need_restoring.lock() = false;
-    *state.had_first_playlist_index.lock() = false;
+    state.session.reset_for_new_connection();
     *state.last_connect_time.lock() = None;
-    *state.last_rewind_time.lock() = None;
-    *state.last_advance_time.lock() = None;
-    *state.last_updated_file_time.lock() = None;
-    *state.last_paused_on_leave_time.lock() = None;
     {
         let mut autoplay = state.autoplay.lock();
         autoplay.countdown_active = false;
         autoplay.countdown_remaining = 0;
     }
-    *state.room_warning_state.lock() = crate::app_state::RoomWarningState::default();
-    *state.warning_timers.lock() = WarningTimers::default();
-    *state.room_warning_task_running.lock() = false;
-    state.emit_event("user-list-updated", serde_json::json!({ "users": [] }));
+    state.session.reset_warnings();
+    state.emit_event(
+        "user-list-updated",
+        serde_json::json!({ "users": [], "rooms": [] }),
+    );
     state.emit_event(
         "playlist-updated",
         crate::app_state::PlaylistEvent {
@@ -2628,15 +3457,207 @@ pub async fn disconnect_from_server(state: State<'_, Arc<AppState>>) -> Result<(
             server: None,
         },
     );
-    state.emit_event(
-        "tls-status-changed",
-        serde_json::json!({ "status": "unknown" }),
-    );
+    state.set_tls_status("unknown");
 
     Ok(())
 }
 
+/// Follow a `syncplay://` join link: connect to its server and room, then open its file
+pub(crate) async fn open_join_link<R: Runtime>(app: AppHandle<R>, link: &str) {
+    let link = match parse_join_link(link) {
+        Ok(link) => link,
+        Err(e) => {
+            tracing::warn!("Ignoring join link {}: {}", link, e);
+            return;
+        }
+    };
+    let state = app.state::<Arc<AppState>>();
+    tracing::info!("Opening join link for {}:{}", link.host, link.port);
+    if state.is_connected() {
+        if let Err(e) = disconnect_from_server(app.state()).await {
+            tracing::warn!("Failed to disconnect before joining: {}", e);
+        }
+    }
+    let room = link
+        .room
+        .unwrap_or_else(|| state.config.lock().user.default_room.clone());
+    if !join_server(&app, link.host, link.port, room).await {
+        return;
+    }
+    if let Some(file) = link.file {
+        if let Err(e) = load_media_by_name(state.inner(), &file, true, false).await {
+            tracing::warn!("Failed to open {} from join link: {}", file, e);
+            emit_error_message(state.inner(), &e);
+        }
+    }
+}
+
+/// "Open with Syncplay": join the saved server and default room, then make `path` the
+/// current playlist item
+pub(crate) async fn open_media_file<R: Runtime>(app: AppHandle<R>, path: &str) {
+    let state = app.state::<Arc<AppState>>();
+    let local = match tokio::fs::canonicalize(path).await {
+        Ok(local) if tokio::fs::metadata(&local).await.is_ok_and(|m| m.is_file()) => Some(local),
+        _ => None,
+    };
+    let resolved = match local {
+        Some(local) => Some(local),
+        None => state.media_resolver().resolve(path).await,
+    }
+    .map(|path| path.to_string_lossy().to_string());
+    let Some(file) = resolved else {
+        emit_error_message(state.inner(), &state.tr("opened-file-not-found", &[&path]));
+        return;
+    };
+    tracing::info!("Opening {} with Syncplay", file);
+    if !state.is_connected() {
+        let (server, room) = {
+            let config = state.config.lock();
+            (config.server.clone(), config.user.default_room.clone())
+        };
+        if !join_server(&app, server.host, server.port, room).await {
+            return;
+        }
+    }
+    // The room playlist has to arrive first, or adding to it would replace it
+    let started = std::time::Instant::now();
+    while state.session.last_global_update.lock().is_none()
+        && started.elapsed() < OPEN_FILE_ROOM_STATE_TIMEOUT
+    {
+        sleep(Duration::from_millis(100)).await;
+    }
+    if let Err(e) = crate::commands::playlist::open_as_current_item(state.inner(), &file).await {
+        tracing::warn!("Failed to open {}: {}", file, e);
+        emit_error_message(state.inner(), &e);
+    }
+}
+
+/// Connect with the saved username and whatever password is saved for the server
+async fn join_server<R: Runtime>(
+    app: &AppHandle<R>,
+    host: String,
+    port: u16,
+    room: String,
+) -> bool {
+    let state = app.state::<Arc<AppState>>();
+    let config = state.config.lock().clone();
+    if config.user.username.trim().is_empty() {
+        emit_error_message(state.inner(), &state.tr("join-needs-username", &[]));
+        return false;
+    }
+    // The saved password, or its hash, is picked up by host and port
+    match connect_to_server(
+        host,
+        port,
+        config.user.username,
+        room,
+        None,
+        None,
+        app.clone(),
+        app.state(),
+    )
+    .await
+    {
+        Ok(()) => true,
+        Err(e) => {
+            emit_error_message(state.inner(), &e);
+            false
+        }
+    }
+}
+
+/// Leave the server and deal with the spawned player before the app exits
+pub(crate) async fn shutdown_session(state: &Arc<AppState>) {
+    tracing::info!("Shutting down session");
+    {
+        let mut reconnect = state.reconnect_state.lock();
+        reconnect.enabled = false;
+        reconnect.running = false;
+    }
+    *state.manual_disconnect.lock() = true;
+    crate::commands::playlist::persist_playlist(state);
+    finish_session_stats(state);
+
+    let connection = state.session.connection.lock().take();
+    if let Some(connection) = connection {
+        connection.disconnect();
+        sleep(SHUTDOWN_DISCONNECT_GRACE).await;
+    }
+
+    if state.config.lock().player.leave_player_running {
+        // Dropping the handles leaves the process alone
+        *state.player.lock() = None;
+        if state.player_process.lock().take().is_some() {
+            tracing::info!("Leaving player running");
+        }
+        return;
+    }
+    match tokio::time::timeout(SHUTDOWN_PLAYER_TIMEOUT, stop_player(state)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!("Failed to stop player on exit: {}", e),
+        Err(_) => {
+            tracing::warn!("Player did not quit in time, killing it");
+            let child = state.player_process.lock().take();
+            if let Some(mut child) = child {
+                let _ = child.start_kill();
+            }
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn get_connection_status(state: State<'_, Arc<AppState>>) -> Result<bool, String> {
     Ok(state.is_connected())
 }
+
+/// Outbound queue metrics of the current connection
+#[tauri::command]
+pub async fn get_connection_metrics(
+    state: State<'_, Arc<AppState>>,
+) -> Result<ConnectionMetrics, String> {
+    let connection = state.session.connection.lock().clone();
+    Ok(connection
+        .map(|connection| connection.metrics())
+        .unwrap_or_default())
+}
+
+/// Counters and gauges of the metrics endpoint, when metrics are turned on
+#[tauri::command]
+pub async fn get_metrics(state: State<'_, Arc<AppState>>) -> Result<MetricsSnapshot, String> {
+    if !state.config.lock().metrics.enabled {
+        return Err("Metrics are turned off".to_string());
+    }
+    Ok(MetricsSnapshot::collect(state.inner()))
+}
+
+/// What the server supports and why the client turned features off
+#[tauri::command]
+pub async fn get_server_features(
+    state: State<'_, Arc<AppState>>,
+) -> Result<ServerFeatureReport, String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    let features = state.session.server_features.lock().clone();
+    let negotiation = state
+        .session
+        .feature_negotiation
+        .lock()
+        .clone()
+        .unwrap_or_default();
+    let mut downgrades = negotiation.downgrades;
+    let config = state.config.lock().clone();
+    if features.shared_playlists && !config.user.shared_playlist_enabled {
+        downgrades.push(FeatureDowngrade {
+            feature: "sharedPlaylists".to_string(),
+            reason: DowngradeReason::DisabledInSettings,
+            min_version: None,
+        });
+    }
+    Ok(ServerFeatureReport {
+        server_version: state.session.client_state.get_server_version(),
+        features,
+        raw_features: negotiation.raw_features,
+        downgrades,
+    })
+}
@@ -0,0 +1,99 @@
+// Audio and subtitle delay command handlers
+
+use crate::app_state::AppState;
+use crate::client::delays::MediaDelays;
+use crate::player::backend::PlayerBackend;
+use crate::player::controller::ensure_player_connected;
+use std::sync::Arc;
+use tauri::State;
+
+/// Offsets saved for the open file
+#[tauri::command]
+pub async fn get_delays(state: State<'_, Arc<AppState>>) -> Result<MediaDelays, String> {
+    let Some(file) = state.session.client_state.get_file() else {
+        return Err("No file is open".to_string());
+    };
+    Ok(state.delays.get(&file))
+}
+
+/// Shift the audio of the open file and remember the offset for it
+#[tauri::command]
+pub async fn set_audio_delay(
+    ms: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<MediaDelays, String> {
+    update_delays(state.inner(), Some(ms), None).await
+}
+
+/// Shift the subtitles of the open file and remember the offset for it
+#[tauri::command]
+pub async fn set_sub_delay(
+    ms: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<MediaDelays, String> {
+    update_delays(state.inner(), None, Some(ms)).await
+}
+
+/// Restore the saved offsets, or clear leftovers, when a file opens
+pub(crate) fn apply_saved_delays(state: Arc<AppState>, player: Arc<dyn PlayerBackend>) {
+    tokio::spawn(async move {
+        let Some(file) = state.session.client_state.get_file() else {
+            return;
+        };
+        let delays = state.delays.get(&file);
+        if let Err(e) = apply_delays(player.as_ref(), &delays).await {
+            tracing::debug!("Failed to apply delays for {}: {}", file, e);
+        } else if !delays.is_zero() {
+            tracing::info!(
+                "Applied saved delays for {}: audio {}ms, subtitles {}ms",
+                file,
+                delays.audio_ms,
+                delays.sub_ms
+            );
+        }
+    });
+}
+
+async fn update_delays(
+    state: &Arc<AppState>,
+    audio_ms: Option<i64>,
+    sub_ms: Option<i64>,
+) -> Result<MediaDelays, String> {
+    let Some(file) = state.session.client_state.get_file() else {
+        return Err("No file is open".to_string());
+    };
+    ensure_player_connected(state).await?;
+    let player = state.player.lock().clone();
+    let Some(player) = player else {
+        return Err("Player not connected".to_string());
+    };
+    let previous = state.delays.get(&file);
+    let delays = state.delays.update(&file, audio_ms, sub_ms);
+    let result = if audio_ms.is_some() {
+        player.set_audio_delay(delays.audio_ms).await
+    } else {
+        player.set_sub_delay(delays.sub_ms).await
+    };
+    if let Err(e) = result {
+        state
+            .delays
+            .update(&file, Some(previous.audio_ms), Some(previous.sub_ms));
+        return Err(format!("Failed to set delay: {}", e));
+    }
+    persist_delays(state);
+    Ok(delays)
+}
+
+async fn apply_delays(player: &dyn PlayerBackend, delays: &MediaDelays) -> anyhow::Result<()> {
+    player.set_audio_delay(delays.audio_ms).await?;
+    player.set_sub_delay(delays.sub_ms).await
+}
+
+fn persist_delays(state: &Arc<AppState>) {
+    let app = state.app_handle.lock().clone();
+    if let Some(app) = app {
+        if let Err(e) = crate::config::save_delays(&app, &state.delays.entries()) {
+            tracing::warn!("Failed to persist delays: {}", e);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Do-not-disturb command handler
+//
+// While it is on, only errors reach the OSD; chat stays off the OSD and mentions neither
+// sound nor flash the taskbar. Sync corrections and countdowns to playback keep running.
+// The config is never touched, so turning it off brings back exactly what was set before.
+
+use crate::app_state::AppState;
+use crate::commands::connection::emit_system_message;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn set_dnd(enabled: bool, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    {
+        let mut dnd = state.do_not_disturb.lock();
+        if *dnd == enabled {
+            return Ok(());
+        }
+        *dnd = enabled;
+    }
+    tracing::info!(
+        "Do not disturb {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    state.emit_event("dnd-changed", enabled);
+    let key = if enabled {
+        "dnd-enabled"
+    } else {
+        "dnd-disabled"
+    };
+    emit_system_message(state.inner(), &state.tr(key, &[]));
+    Ok(())
+}
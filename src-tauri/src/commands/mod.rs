@@ -1,13 +1,39 @@
+pub mod autostart;
+pub mod bookmarks;
 pub mod chat;
 pub mod config;
 pub mod connection;
+pub mod delays;
+pub mod do_not_disturb;
 pub mod player;
 pub mod playlist;
+pub mod presets;
+pub mod privacy;
+pub mod recent_files;
 pub mod room;
+pub mod room_passwords;
+pub mod scripts;
+pub mod segments;
+pub mod servers;
+pub mod sync;
+pub mod untrusted_urls;
 
+pub use autostart::*;
+pub use bookmarks::*;
 pub use chat::*;
 pub use config::*;
 pub use connection::*;
+pub use delays::*;
+pub use do_not_disturb::*;
 pub use player::*;
 pub use playlist::*;
+pub use presets::*;
+pub use privacy::*;
+pub use recent_files::*;
 pub use room::*;
+pub use room_passwords::*;
+pub use scripts::*;
+pub use segments::*;
+pub use servers::*;
+pub use sync::*;
+pub use untrusted_urls::*;
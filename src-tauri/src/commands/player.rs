@@ -1,18 +1,15 @@
 use crate::app_state::AppState;
-use crate::player::detection::{detect_players, DetectedPlayer};
-use serde::Serialize;
+use crate::config::save_player_detection;
+use crate::player::detection::{detect_players, PlayerDetectionCache};
 use std::sync::Arc;
-use tauri::State;
-
-#[derive(Debug, Clone, Serialize)]
-pub struct PlayerDetectionCache {
-    pub players: Vec<DetectedPlayer>,
-    pub updated_at: Option<i64>,
-}
+use tauri::{AppHandle, Runtime, State};
 
 #[tauri::command]
-pub fn detect_available_players(state: State<'_, Arc<AppState>>) -> PlayerDetectionCache {
-    refresh_player_detection_inner(state.inner())
+pub fn detect_available_players<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> PlayerDetectionCache {
+    refresh_player_detection_inner(&app, state.inner())
 }
 
 #[tauri::command]
@@ -26,17 +23,39 @@ pub fn get_cached_players(state: State<'_, Arc<AppState>>) -> PlayerDetectionCac
 }
 
 #[tauri::command]
-pub fn refresh_player_detection(state: State<'_, Arc<AppState>>) -> PlayerDetectionCache {
-    refresh_player_detection_inner(state.inner())
+pub fn refresh_player_detection<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> PlayerDetectionCache {
+    refresh_player_detection_inner(&app, state.inner())
+}
+
+/// Forget the detected players so the next lookup detects them again
+#[tauri::command]
+pub fn clear_player_detection<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    state.detected_players.lock().clear();
+    *state.detected_players_updated_at.lock() = None;
+    save_player_detection(&app, &PlayerDetectionCache::default())
+        .map_err(|e| format!("Failed to clear player detection: {}", e))
 }
 
-fn refresh_player_detection_inner(state: &Arc<AppState>) -> PlayerDetectionCache {
+fn refresh_player_detection_inner<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &Arc<AppState>,
+) -> PlayerDetectionCache {
     let players = detect_players();
     let updated_at = Some(chrono::Utc::now().timestamp_millis());
     *state.detected_players.lock() = players.clone();
     *state.detected_players_updated_at.lock() = updated_at;
-    PlayerDetectionCache {
+    let cache = PlayerDetectionCache {
         players,
         updated_at,
+    };
+    if let Err(e) = save_player_detection(app, &cache) {
+        tracing::warn!("Failed to save player detection: {}", e);
     }
+    cache
 }
@@ -1,16 +1,17 @@
 // Playlist command handlers
 
 use crate::app_state::{AppState, PlaylistEvent};
-use crate::config::SyncplayConfig;
+use crate::client::session_actor::SessionCommand;
+use crate::config::{save_config, PlaylistMode, SyncplayConfig};
 use crate::network::messages::{PlayState, StateMessage};
 use crate::network::messages::{PlaylistChange, PlaylistIndexUpdate, ProtocolMessage, SetMessage};
-use crate::player::controller::{load_media_by_name, resolve_media_path};
-use crate::utils::{is_music_file, is_url};
+use crate::player::controller::load_media_by_name;
+use crate::utils::{is_music_file, is_url, natural_cmp};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Runtime, State};
 
 #[tauri::command]
 pub async fn update_playlist(
@@ -24,7 +25,7 @@ pub async fn update_playlist(
     if !shared_playlists_enabled(state.inner(), &config) {
         return Err("Shared playlists are disabled".to_string());
     }
-    let current_items = state.playlist.get_item_filenames();
+    let current_items = state.session.playlist.get_item_filenames();
     let mut new_items = current_items.clone();
 
     match action.as_str() {
@@ -68,11 +69,7 @@ pub async fn update_playlist(
             }
         }
         "next" => {
-            let index = next_index(state.inner(), &config)?;
-            send_playlist_index(state.inner(), index, true)?;
-            if let Err(e) = apply_playlist_index_from_server(state.inner(), index, true).await {
-                tracing::warn!("Failed to load next playlist item: {}", e);
-            }
+            play_next_item(state.inner(), &config).await?;
         }
         "previous" => {
             let index = previous_index(state.inner())?;
@@ -82,21 +79,21 @@ pub async fn update_playlist(
             }
         }
         "undo" => {
-            if let Some(previous) = state.playlist.previous_playlist() {
+            if let Some(previous) = state.session.playlist.previous_playlist() {
                 apply_playlist_change_local(state.inner(), previous, false)?;
             }
         }
         "shuffle" => {
             new_items.shuffle(&mut thread_rng());
             apply_playlist_change_local(state.inner(), new_items, true)?;
-            if !state.playlist.get_item_filenames().is_empty() {
+            if !state.session.playlist.get_item_filenames().is_empty() {
                 if let Err(e) = apply_playlist_index_from_server(state.inner(), 0, true).await {
                     tracing::warn!("Failed to load shuffled playlist start: {}", e);
                 }
             }
         }
         "shuffle_remaining" => {
-            let Some(current_index) = state.playlist.get_current_index() else {
+            let Some(current_index) = state.session.playlist.get_current_index() else {
                 return Ok(());
             };
             let split_point = current_index + 1;
@@ -139,21 +136,121 @@ pub async fn update_playlist(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn queue_next(
+    filename: String,
+    jump_on_end: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    tracing::info!("Queueing playlist item next: {}", filename);
+    let config = state.config.lock().clone();
+    if !shared_playlists_enabled(state.inner(), &config) {
+        return Err("Shared playlists are disabled".to_string());
+    }
+    let (normalized, override_path) = normalize_playlist_entry(&filename);
+    if let Some(path) = override_path {
+        state.media_index.add_override_path(&normalized, path);
+    }
+
+    let mut new_items = state.session.playlist.get_item_filenames();
+    if state.session.playlist.is_empty() {
+        state.session.playlist.mark_switch_to_new_item();
+    }
+    let position = state
+        .session
+        .playlist
+        .next_insert_position()
+        .min(new_items.len());
+    new_items.insert(position, normalized.clone());
+    apply_playlist_change_local(state.inner(), new_items, false)?;
+
+    if jump_on_end.unwrap_or(true) {
+        state
+            .session
+            .playlist
+            .set_queued_next_filename(Some(normalized));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn add_directory_to_playlist(
+    path: String,
+    sort: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<usize, String> {
+    tracing::info!("Adding directory to playlist: {}", path);
+    let config = state.config.lock().clone();
+    if !shared_playlists_enabled(state.inner(), &config) {
+        return Err("Shared playlists are disabled".to_string());
+    }
+    let directory = PathBuf::from(&path);
+    let is_dir = tokio::fs::metadata(&directory)
+        .await
+        .map(|metadata| metadata.is_dir())
+        .unwrap_or(false);
+    if !is_dir {
+        return Err(format!("Not a directory: {}", path));
+    }
+
+    let mut files = state.media_resolver().index_directory(directory).await?;
+    if sort.unwrap_or(true) {
+        files.sort_by(|(a, _), (b, _)| natural_cmp(a, b));
+    }
+
+    let mut new_items = state.session.playlist.get_item_filenames();
+    let mut added = 0;
+    for (filename, _) in files {
+        if new_items.contains(&filename) {
+            continue;
+        }
+        new_items.push(filename);
+        added += 1;
+    }
+    if added == 0 {
+        return Ok(0);
+    }
+    apply_playlist_change_local(state.inner(), new_items, false)?;
+    Ok(added)
+}
+
+#[tauri::command]
+pub async fn set_playlist_mode<R: Runtime>(
+    app: AppHandle<R>,
+    mode: PlaylistMode,
+    stop_after_current: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    tracing::info!("Setting playlist mode: {:?}", mode);
+    if let Some(stop) = stop_after_current {
+        state.session.playlist.set_stop_after_current(stop);
+    }
+
+    let config = {
+        let mut config = state.config.lock();
+        config.user.loop_at_end_of_playlist = mode == PlaylistMode::RepeatAll;
+        config.user.playlist_mode = mode;
+        config.clone()
+    };
+    save_config(&app, &config).map_err(|e| format!("Failed to save configuration: {}", e))?;
+    state.emit_event("config-updated", config);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn check_playlist_items(
     items: Vec<String>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<Vec<PlaylistItemInfo>, String> {
-    let config = state.config.lock().clone();
+    let resolver = state.media_resolver();
     let mut results = Vec::with_capacity(items.len());
     for item in items {
         let path = if is_url(&item) {
             Some(item.clone())
         } else {
-            state
-                .media_index
-                .resolve_path(&item)
-                .or_else(|| resolve_media_path(&config.player.media_directories, &item))
+            resolver
+                .resolve(&item)
+                .await
                 .map(|path| path.to_string_lossy().to_string())
         };
         let available = path.is_some();
@@ -174,7 +271,7 @@ pub struct PlaylistItemInfo {
 }
 
 pub(crate) fn shared_playlists_enabled(state: &Arc<AppState>, config: &SyncplayConfig) -> bool {
-    config.user.shared_playlist_enabled && state.server_features.lock().shared_playlists
+    config.user.shared_playlist_enabled && state.session.server_features.lock().shared_playlists
 }
 
 pub(crate) fn send_playlist_index(
@@ -182,10 +279,10 @@ pub(crate) fn send_playlist_index(
     index: usize,
     reset_position: bool,
 ) -> Result<(), String> {
-    state.playlist.set_current_index(index);
+    state.session.playlist.set_current_index(index);
     emit_playlist_update(state);
 
-    let username = state.client_state.get_username();
+    let username = state.session.client_state.get_username();
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
@@ -205,8 +302,9 @@ pub(crate) fn send_playlist_index(
     send_to_server(state, message)?;
 
     if reset_position {
-        *state.last_advance_time.lock() = Some(std::time::Instant::now());
-        *state.last_rewind_time.lock() = Some(std::time::Instant::now());
+        let now = std::time::Instant::now();
+        state.session.actor.send(SessionCommand::MarkAdvance(now));
+        state.session.actor.send(SessionCommand::MarkRewind(now));
         let state_message = ProtocolMessage::State {
             State: StateMessage {
                 playstate: Some(PlayState {
@@ -225,14 +323,30 @@ pub(crate) fn send_playlist_index(
     Ok(())
 }
 
+/// Move the room on to the next playlist item
+pub(crate) async fn play_next_item(
+    state: &Arc<AppState>,
+    config: &SyncplayConfig,
+) -> Result<(), String> {
+    let index = next_index(state, config)?;
+    send_playlist_index(state, index, true)?;
+    if let Err(e) = apply_playlist_index_from_server(state, index, true).await {
+        tracing::warn!("Failed to load next playlist item: {}", e);
+    }
+    Ok(())
+}
+
 pub(crate) async fn apply_playlist_index_from_server(
     state: &Arc<AppState>,
     index: usize,
     reset_position: bool,
 ) -> Result<(), String> {
-    state.playlist.set_current_index(index);
-    let filename = state.playlist.get_current_filename();
-    state.playlist.set_queued_index_filename(filename.clone());
+    state.session.playlist.set_current_index(index);
+    let filename = state.session.playlist.get_current_filename();
+    state
+        .session
+        .playlist
+        .set_queued_index_filename(filename.clone());
     emit_playlist_update(state);
 
     if let Some(filename) = filename {
@@ -246,6 +360,36 @@ pub(crate) async fn apply_playlist_index_from_server(
     Ok(())
 }
 
+/// Make `entry` the current item, adding it after the current one when it is missing
+///
+/// Without shared playlists the file is only opened locally.
+pub(crate) async fn open_as_current_item(state: &Arc<AppState>, entry: &str) -> Result<(), String> {
+    let config = state.config.lock().clone();
+    if !shared_playlists_enabled(state, &config) {
+        return load_media_by_name(state, entry, true, false).await;
+    }
+    let (normalized, override_path) = normalize_playlist_entry(entry);
+    if let Some(path) = override_path {
+        state.media_index.add_override_path(&normalized, path);
+    }
+    let index = match state.session.playlist.index_of_filename(&normalized) {
+        Some(index) => index,
+        None => {
+            let mut new_items = state.session.playlist.get_item_filenames();
+            let position = state
+                .session
+                .playlist
+                .next_insert_position()
+                .min(new_items.len());
+            new_items.insert(position, normalized);
+            apply_playlist_change_local(state, new_items, false)?;
+            position
+        }
+    };
+    send_playlist_index(state, index, true)?;
+    apply_playlist_index_from_server(state, index, true).await
+}
+
 pub(crate) async fn change_playlist_from_filename(
     state: &Arc<AppState>,
     filename: &str,
@@ -257,16 +401,22 @@ pub(crate) async fn change_playlist_from_filename(
 
     let normalized =
         crate::utils::playlist_filename_from_path(filename).unwrap_or_else(|| filename.to_string());
-    let Some(index) = state.playlist.index_of_filename(&normalized) else {
+    let Some(index) = state.session.playlist.index_of_filename(&normalized) else {
         return Ok(());
     };
 
-    if state.playlist.get_current_index() != Some(index) {
+    if state.session.playlist.get_current_index() != Some(index) {
         send_playlist_index(state, index, true)?;
         return Ok(());
     }
 
-    if state.playlist.get_queued_index_filename().as_deref() == Some(&normalized) {
+    if state
+        .session
+        .playlist
+        .get_queued_index_filename()
+        .as_deref()
+        == Some(&normalized)
+    {
         return Ok(());
     }
 
@@ -282,23 +432,27 @@ fn apply_playlist_change_local(
     new_items: Vec<String>,
     reset_index: bool,
 ) -> Result<(), String> {
-    let room = state.client_state.get_room();
-    state.playlist.set_queued_index_filename(None);
-    state.playlist.update_previous_playlist(&new_items, &room);
+    let room = state.session.client_state.get_room();
+    state.session.playlist.set_queued_index_filename(None);
+    state
+        .session
+        .playlist
+        .update_previous_playlist(&new_items, &room);
 
     let new_index = if new_items.is_empty() {
         None
     } else if reset_index {
         Some(0)
     } else {
-        Some(state.playlist.compute_valid_index(&new_items))
+        Some(state.session.playlist.compute_valid_index(&new_items))
     };
 
     state
+        .session
         .playlist
         .set_items_with_index(new_items.clone(), new_index);
 
-    let username = state.client_state.get_username();
+    let username = state.session.client_state.get_username();
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
@@ -326,6 +480,30 @@ fn apply_playlist_change_local(
     Ok(())
 }
 
+/// Switch the room to `entry`, adding it to the playlist unless it is already there
+pub(crate) async fn play_for_room(state: &Arc<AppState>, entry: &str) -> Result<(), String> {
+    let config = state.config.lock().clone();
+    if !shared_playlists_enabled(state, &config) {
+        return Err("Shared playlists are disabled".to_string());
+    }
+    let (normalized, override_path) = normalize_playlist_entry(entry);
+    if let Some(path) = override_path {
+        state.media_index.add_override_path(&normalized, path);
+    }
+    let mut items = state.session.playlist.get_item_filenames();
+    let index = match items.iter().position(|item| item == &normalized) {
+        Some(index) => index,
+        None => {
+            items.push(normalized);
+            let index = items.len() - 1;
+            apply_playlist_change_local(state, items, false)?;
+            index
+        }
+    };
+    send_playlist_index(state, index, true)?;
+    apply_playlist_index_from_server(state, index, true).await
+}
+
 fn normalize_playlist_entry(entry: &str) -> (String, Option<PathBuf>) {
     if is_url(entry) {
         return (entry.to_string(), None);
@@ -340,12 +518,13 @@ fn normalize_playlist_entry(entry: &str) -> (String, Option<PathBuf>) {
 }
 
 fn next_index(state: &Arc<AppState>, config: &SyncplayConfig) -> Result<usize, String> {
-    let items = state.playlist.get_item_filenames();
+    let items = state.session.playlist.get_item_filenames();
     if items.is_empty() {
         return Err("Playlist is empty".to_string());
     }
-    let current = state.playlist.get_current_index().unwrap_or(0);
-    let loop_at_end = config.user.loop_at_end_of_playlist || is_playing_music(state);
+    let current = state.session.playlist.get_current_index().unwrap_or(0);
+    let loop_at_end =
+        config.user.effective_playlist_mode() == PlaylistMode::RepeatAll || is_playing_music(state);
     if current + 1 < items.len() {
         return Ok(current + 1);
     }
@@ -356,11 +535,11 @@ fn next_index(state: &Arc<AppState>, config: &SyncplayConfig) -> Result<usize, S
 }
 
 fn previous_index(state: &Arc<AppState>) -> Result<usize, String> {
-    let items = state.playlist.get_item_filenames();
+    let items = state.session.playlist.get_item_filenames();
     if items.is_empty() {
         return Err("Playlist is empty".to_string());
     }
-    let current = state.playlist.get_current_index().unwrap_or(0);
+    let current = state.session.playlist.get_current_index().unwrap_or(0);
     if current == 0 {
         return Err("Already at start of playlist".to_string());
     }
@@ -369,6 +548,7 @@ fn previous_index(state: &Arc<AppState>) -> Result<usize, String> {
 
 fn is_playing_music(state: &Arc<AppState>) -> bool {
     state
+        .session
         .client_state
         .get_file()
         .as_deref()
@@ -377,18 +557,33 @@ fn is_playing_music(state: &Arc<AppState>) -> bool {
 }
 
 fn emit_playlist_update(state: &Arc<AppState>) {
-    let items = state.playlist.get_item_filenames();
+    let items = state.session.playlist.get_item_filenames();
     state.emit_event(
         "playlist-updated",
         PlaylistEvent {
             items,
-            current_index: state.playlist.get_current_index(),
+            current_index: state.session.playlist.get_current_index(),
         },
     );
+    persist_playlist(state);
+}
+
+pub(crate) fn persist_playlist(state: &Arc<AppState>) {
+    crate::commands::room::remember_persistent_playlist(state);
+    let Some(app) = state.app_handle.lock().clone() else {
+        return;
+    };
+    let snapshot = state.session.playlist.snapshot();
+    if snapshot.room.is_none() {
+        return;
+    }
+    if let Err(e) = crate::config::save_playlist_snapshot(&app, &snapshot) {
+        tracing::warn!("Failed to persist playlist: {}", e);
+    }
 }
 
 fn send_to_server(state: &Arc<AppState>, message: ProtocolMessage) -> Result<(), String> {
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
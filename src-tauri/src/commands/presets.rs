@@ -0,0 +1,80 @@
+// Connection preset command handlers
+
+use crate::app_state::AppState;
+use crate::config::{save_config, ConnectionPreset};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
+
+#[tauri::command]
+pub async fn save_preset<R: Runtime>(
+    preset: ConnectionPreset,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let name = preset.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+    if preset.host.trim().is_empty() || preset.port == 0 {
+        return Err("Preset needs a server address".to_string());
+    }
+    if preset.username.trim().is_empty() {
+        return Err("Preset needs a username".to_string());
+    }
+    let mut updated = state.config.lock().clone();
+    updated.save_preset(ConnectionPreset { name, ..preset });
+    save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_preset<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let mut updated = state.config.lock().clone();
+    if !updated.delete_preset(&name) {
+        return Err(format!("No preset named {}", name));
+    }
+    save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+    Ok(())
+}
+
+/// Switch to the preset's player, then connect like the connect dialog does
+#[tauri::command]
+pub async fn connect_with_preset<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let mut updated = state.config.lock().clone();
+    let preset = updated
+        .preset(&name)
+        .cloned()
+        .ok_or_else(|| format!("No preset named {}", name))?;
+    if let Some(player_path) = preset
+        .player_path
+        .filter(|path| !path.trim().is_empty() && *path != updated.player.player_path)
+    {
+        updated.player.player_path = player_path;
+        save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+        *state.config.lock() = updated.clone();
+        state.emit_event("config-updated", updated);
+    }
+    crate::commands::connection::connect_to_server(
+        preset.host,
+        preset.port,
+        preset.username,
+        preset.room,
+        None,
+        None,
+        app,
+        state,
+    )
+    .await
+}
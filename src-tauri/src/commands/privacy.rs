@@ -0,0 +1,47 @@
+// Privacy command handlers
+
+use crate::app_state::AppState;
+use crate::client::media_resolver::file_size;
+use crate::network::messages::FileSizeInfo;
+use crate::player::controller::{file_privacy, outbound_file_info};
+use crate::utils::{is_url, ResolvedPrivacy};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+/// What the server would receive for a file in the current room
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyPreview {
+    pub name: Option<String>,
+    pub size: Option<FileSizeInfo>,
+    pub privacy: ResolvedPrivacy,
+}
+
+/// Preview the name and size sent for `filename`, a local path or a bare name
+#[tauri::command]
+pub async fn preview_privacy(
+    filename: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<PrivacyPreview, String> {
+    let path = Path::new(&filename);
+    let (name, size) = if !is_url(&filename) && path.is_file() {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| filename.clone());
+        (name, file_size(path).await)
+    } else {
+        (filename.clone(), None)
+    };
+    let config = state.config.lock().clone();
+    let privacy = file_privacy(state.inner(), &config, &name);
+    let info = outbound_file_info(state.inner(), &config, Some(name), size, None, false);
+    Ok(PrivacyPreview {
+        name: info.name,
+        size: info.size,
+        privacy,
+    })
+}
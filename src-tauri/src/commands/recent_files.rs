@@ -0,0 +1,84 @@
+// Recently played file command handlers
+
+use crate::app_state::AppState;
+use crate::config::{save_config, RecentFile};
+use crate::utils::is_url;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime, State};
+
+#[tauri::command]
+pub async fn get_recent_files(state: State<'_, Arc<AppState>>) -> Result<Vec<RecentFile>, String> {
+    Ok(state.config.lock().recent_files.clone())
+}
+
+/// Put a recently played file into the room's playlist and switch everyone to it
+#[tauri::command]
+pub async fn replay_recent_file(
+    name: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let file = state
+        .config
+        .lock()
+        .recent_files
+        .iter()
+        .find(|entry| entry.name == name)
+        .cloned()
+        .ok_or_else(|| format!("{} is not in the recently played files", name))?;
+    // Prefer the original location so the playlist entry resolves without the media index
+    let entry = file
+        .path
+        .filter(|path| is_url(path) || Path::new(path).is_file())
+        .unwrap_or(file.name);
+    crate::commands::playlist::play_for_room(state.inner(), &entry).await
+}
+
+#[tauri::command]
+pub async fn remove_recent_file<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let mut updated = state.config.lock().clone();
+    let before = updated.recent_files.len();
+    updated.recent_files.retain(|entry| entry.name != name);
+    if updated.recent_files.len() == before {
+        return Ok(());
+    }
+    save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+    Ok(())
+}
+
+/// Remember a file opened while connected to a room
+pub(crate) fn remember_recent_file(state: &Arc<AppState>, name: &str, path: Option<&str>) {
+    let room = state.session.client_state.get_room();
+    let mut updated = state.config.lock().clone();
+    if updated
+        .recent_files
+        .first()
+        .is_some_and(|entry| entry.name == name && entry.room == room)
+    {
+        return;
+    }
+    let played_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    updated.add_recent_file(RecentFile {
+        name: name.to_string(),
+        path: path.map(|path| path.to_string()),
+        room,
+        played_at,
+    });
+    if let Some(app) = state.app_handle.lock().clone() {
+        if let Err(e) = save_config(&app, &updated) {
+            tracing::warn!("Failed to save recently played files: {}", e);
+        }
+    }
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+}
@@ -1,16 +1,41 @@
 // Room command handlers
 
-use crate::app_state::AppState;
-use crate::commands::connection::{reidentify_as_controller, store_control_password};
-use crate::config::save_config;
-use crate::network::messages::{ProtocolMessage, ReadyState, RoomInfo, SetMessage};
-use crate::utils::parse_controlled_room_input;
+use crate::app_state::{AppState, PlaylistEvent, RoomStateSnapshot, UserInfo};
+use crate::client::operator::{seal_password, OperatorGrant, OperatorOffer, OperatorOfferEvent};
+use crate::client::persistent_rooms::PersistentRoomInfo;
+use crate::client::protocol::{
+    MAX_SCHEDULE_AHEAD_HOURS, OPERATORS_ONLY_CHAT_PREFIX, OPERATOR_ACCEPT_CHAT_PREFIX,
+    OPERATOR_KEY_CHAT_PREFIX, OPERATOR_OFFER_CHAT_PREFIX,
+};
+use crate::client::ready::{DEFAULT_READY_CHECK_SECONDS, READY_CHECK_SECONDS};
+use crate::client::room_control::{normalize_operators, OperatorsOnlyRule, Suggestion};
+use crate::client::room_events::RoomEvent;
+use crate::client::session_actor::SessionCommand;
+use crate::client::state::{RoomOverview, RoomSummary};
+use crate::client::votes::{votes_needed, VoteCast, VoteSubject};
+use crate::commands::connection::{
+    current_user_can_control, emit_error_message, emit_system_message, emit_user_list,
+    maybe_show_osd, reidentify_as_controller, start_ready_check, start_scheduled_session,
+    store_control_password, update_room_warnings,
+};
+use crate::config::{save_config, save_persistent_rooms, OperatorsOnlyRoom, OsdCategory};
+use crate::network::messages::{ChatMessage, ProtocolMessage, ReadyState, RoomInfo, SetMessage};
+use crate::player::controller::load_media_by_name;
+use crate::utils::{
+    format_time, is_controlled_room, is_placeholder_username, parse_controlled_room_input,
+    same_filename,
+};
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::{AppHandle, Runtime, State};
+use tokio::time::{timeout, Duration};
+
+const LIST_ROOMS_TIMEOUT_SECONDS: u64 = 2;
 
 #[tauri::command]
 pub async fn change_room<R: Runtime>(
     room: String,
+    room_password: Option<String>,
     app: AppHandle<R>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<(), String> {
@@ -21,6 +46,7 @@ pub async fn change_room<R: Runtime>(
         return Err("Not connected to server".to_string());
     }
     let max_len = state
+        .session
         .server_features
         .lock()
         .max_room_name_length
@@ -32,16 +58,25 @@ pub async fn change_room<R: Runtime>(
         store_control_password(state.inner(), &room, &password, true);
     }
 
+    let config = state.config.lock().clone();
+    let room_password = room_password.filter(|password| !password.is_empty());
+    let sent_password = room_password
+        .clone()
+        .or_else(|| config.user.room_password(&room));
+
     // Update client state
-    state.client_state.set_room(room.clone());
-    *state.had_first_playlist_index.lock() = false;
+    state.session.client_state.set_room(room.clone());
+    state.session.actor.send(SessionCommand::ResetPlaylistIndex);
     *state.playlist_may_need_restoring.lock() = false;
+    *state.pending_room_join.lock() = Some(room.clone());
+    state.session.votes.lock().clear();
+    crate::player::controller::refresh_idle_screen(state.inner());
 
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: Some(RoomInfo {
                 name: room.clone(),
-                password: None,
+                password: sent_password,
             }),
             file: None,
             user: None,
@@ -56,14 +91,22 @@ pub async fn change_room<R: Runtime>(
     send_to_server(&state, message)?;
     send_to_server(&state, ProtocolMessage::List { List: None })?;
     reidentify_as_controller(state.inner());
+    crate::player::controller::refresh_file_privacy(state.inner());
 
-    let config = state.config.lock().clone();
-    if config.user.autosave_joins_to_list {
+    let autosave = config.user.autosave_joins_to_list;
+    // A password typed for a saved room is kept with it
+    let keep_password = room_password.is_some() && config.user.room_list.contains(&room);
+    if autosave || keep_password {
         let mut updated = config.clone();
-        if !updated.user.room_list.contains(&room) {
-            updated.user.room_list.push(room.clone());
+        if autosave {
+            if !updated.user.room_list.contains(&room) {
+                updated.user.room_list.push(room.clone());
+            }
+            updated.user.default_room = room.clone();
+        }
+        if let Some(password) = room_password {
+            updated.user.room_passwords.insert(room.clone(), password);
         }
-        updated.user.default_room = room.clone();
         if let Err(e) = save_config(&app, &updated) {
             tracing::warn!("Failed to save config after room change: {}", e);
         }
@@ -84,9 +127,9 @@ pub async fn set_ready(is_ready: bool, state: State<'_, Arc<AppState>>) -> Resul
     }
 
     // Update client state
-    state.client_state.set_ready(is_ready);
+    state.session.client_state.set_ready(is_ready);
 
-    let username = state.client_state.get_username();
+    let username = state.session.client_state.get_username();
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
@@ -110,11 +153,624 @@ pub async fn set_ready(is_ready: bool, state: State<'_, Arc<AppState>>) -> Resul
     Ok(())
 }
 
+/// Ask everyone in the room to mark themselves ready within `seconds`
+#[tauri::command]
+pub async fn request_ready_check(
+    seconds: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    {
+        let features = state.session.server_features.lock();
+        if !features.readiness || !features.chat {
+            return Err("Ready checks need readiness and chat support on the server".to_string());
+        }
+    }
+    if !current_user_can_control(state.inner()) {
+        return Err("Only room operators can start a ready check".to_string());
+    }
+    let seconds = seconds.unwrap_or(DEFAULT_READY_CHECK_SECONDS);
+    if !READY_CHECK_SECONDS.contains(&seconds) {
+        return Err(format!(
+            "Ready check must last between {} and {} seconds",
+            READY_CHECK_SECONDS.start(),
+            READY_CHECK_SECONDS.end()
+        ));
+    }
+    if state.session.ready_check.lock().is_some() {
+        return Err("A ready check is already running".to_string());
+    }
+    let username = state.session.client_state.get_username();
+    tracing::info!("Starting a {}s ready check", seconds);
+    start_ready_check(state.inner().clone(), username, seconds, true);
+    Ok(())
+}
+
+/// Schedule a room-wide start at `start_time` (RFC 3339, e.g. "2026-10-14T20:00:00Z")
+#[tauri::command]
+pub async fn schedule_session(
+    start_time: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    if !state.session.server_features.lock().chat {
+        return Err("Scheduling needs chat support on the server".to_string());
+    }
+    if !current_user_can_control(state.inner()) {
+        return Err("Only room operators can schedule the session start".to_string());
+    }
+    let start = chrono::DateTime::parse_from_rfc3339(start_time.trim())
+        .map_err(|e| format!("Invalid start time: {}", e))?;
+    let ahead = start.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    if ahead <= chrono::Duration::zero() {
+        return Err("Start time must be in the future".to_string());
+    }
+    if ahead > chrono::Duration::hours(MAX_SCHEDULE_AHEAD_HOURS) {
+        return Err(format!(
+            "Start time must be within {} hours",
+            MAX_SCHEDULE_AHEAD_HOURS
+        ));
+    }
+    let start_ms = (state
+        .session
+        .ping_service
+        .lock()
+        .local_to_server_time(start.timestamp_millis() as f64 / 1000.0)
+        * 1000.0)
+        .round() as i64;
+    let username = state.session.client_state.get_username();
+    tracing::info!("Scheduling session start at {}", start.to_rfc3339());
+    start_scheduled_session(state.inner().clone(), username, start_ms, true);
+    Ok(())
+}
+
+/// Stop reporting file differences with `username`
+///
+/// Without `for_session` only their current file is trusted, and that is remembered.
+#[tauri::command]
+pub async fn trust_user_file<R: Runtime>(
+    username: String,
+    for_session: bool,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let username = username.trim().to_string();
+    if for_session {
+        state.session_trusted_users.lock().insert(username);
+    } else {
+        let Some(file) = state
+            .session
+            .client_state
+            .get_user(&username)
+            .and_then(|user| user.file)
+        else {
+            return Err(format!("{} is not playing a file", username));
+        };
+        let config = {
+            let mut config = state.config.lock();
+            config.user.trust_file(&username, &file);
+            config.clone()
+        };
+        save_config(&app, &config).map_err(|e| format!("Failed to save configuration: {}", e))?;
+        state.emit_event("config-updated", config);
+    }
+    update_room_warnings(state.inner(), false);
+    emit_user_list(state.inner());
+    Ok(())
+}
+
+/// Report file differences with `username` again, forgetting every file trusted for them
+#[tauri::command]
+pub async fn untrust_user_file<R: Runtime>(
+    username: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let username = username.trim().to_string();
+    state.session_trusted_users.lock().remove(&username);
+    let config = {
+        let mut config = state.config.lock();
+        if config.user.trusted_files.remove(&username).is_none() {
+            None
+        } else {
+            Some(config.clone())
+        }
+    };
+    if let Some(config) = config {
+        save_config(&app, &config).map_err(|e| format!("Failed to save configuration: {}", e))?;
+        state.emit_event("config-updated", config);
+    }
+    update_room_warnings(state.inner(), false);
+    emit_user_list(state.inner());
+    Ok(())
+}
+
+/// Operators of the current room, by name
+#[tauri::command]
+pub async fn list_room_operators(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    let room = state.session.client_state.get_room();
+    let mut operators: Vec<String> = state
+        .session
+        .client_state
+        .get_users_in_room(&room)
+        .into_iter()
+        .filter(|user| user.is_controller)
+        .map(|user| user.username)
+        .collect();
+    operators.sort_by_key(|name| name.to_lowercase());
+    Ok(operators)
+}
+
+/// Offer operator control of the current managed room to `username`
+///
+/// The room password only leaves this client sealed for a one-time key of the grantee.
+#[tauri::command]
+pub async fn grant_operator(
+    username: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    let room = state.session.client_state.get_room();
+    if !is_controlled_room(&room) {
+        return Err("Only managed rooms have operators".to_string());
+    }
+    if !current_user_can_control(state.inner()) {
+        return Err("Only operators of this room can hand over control".to_string());
+    }
+    let Some(password) = state.controlled_room_passwords.lock().get(&room).cloned() else {
+        return Err("The password of this room is not known on this client".to_string());
+    };
+    let (chat, max_length) = {
+        let features = state.session.server_features.lock();
+        (
+            features.chat,
+            features.max_chat_message_length.unwrap_or(150),
+        )
+    };
+    if !chat {
+        return Err("Handing over control needs chat support on the server".to_string());
+    }
+    // Prefix, 32-byte key and the sealed password with its 16-byte tag, all as hex
+    let sealed_length = OPERATOR_KEY_CHAT_PREFIX.len() + 64 + 1 + (password.len() + 16) * 2;
+    if sealed_length > max_length {
+        return Err("The room password is too long to hand over on this server".to_string());
+    }
+    let username = username.trim().to_string();
+    if username == state.session.client_state.get_username() {
+        return Err("You are already an operator".to_string());
+    }
+    let Some(user) = state.session.client_state.get_user(&username) else {
+        return Err(format!("{} is not connected", username));
+    };
+    if user.room != room {
+        return Err(format!("{} is not in this room", username));
+    }
+    if user.is_controller {
+        return Err(format!("{} is already an operator", username));
+    }
+
+    send_handoff_chat(
+        state.inner(),
+        format!("{}{}", OPERATOR_OFFER_CHAT_PREFIX, username),
+    )?;
+    state.session.operator_handoff.lock().grant = Some(OperatorGrant::new(&username, &room));
+    emit_system_message(
+        state.inner(),
+        &state.tr("operator-offer-sent", &[&username]),
+    );
+    Ok(())
+}
+
+/// Accept or decline the operator control offered to us
+#[tauri::command]
+pub async fn respond_operator_offer(
+    accept: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let room = state.session.client_state.get_room();
+    let key = {
+        let mut handoff = state.session.operator_handoff.lock();
+        handoff.expire(Instant::now());
+        let Some(offer) = handoff.offer.as_mut() else {
+            return Err("No operator offer is waiting".to_string());
+        };
+        if !accept || offer.room != room {
+            handoff.offer = None;
+            return Ok(());
+        }
+        offer.accept()
+    };
+    send_handoff_chat(
+        state.inner(),
+        format!("{}{}", OPERATOR_ACCEPT_CHAT_PREFIX, key),
+    )
+}
+
+/// An operator of our room offered us control
+pub(crate) fn receive_operator_offer(state: &Arc<AppState>, operator: &str) {
+    let room = state.session.client_state.get_room();
+    let is_operator = state
+        .session
+        .client_state
+        .get_user(operator)
+        .is_some_and(|user| user.room == room && user.is_controller);
+    if !is_controlled_room(&room) || !is_operator || current_user_can_control(state) {
+        return;
+    }
+    state.session.operator_handoff.lock().offer = Some(OperatorOffer::new(operator, &room));
+    emit_system_message(state, &state.tr("operator-offer-received", &[&operator]));
+    state.emit_event(
+        "operator-offer",
+        OperatorOfferEvent {
+            operator: operator.to_string(),
+            room,
+        },
+    );
+}
+
+/// The user we offered control to sent their one-time key; answer with the sealed password
+pub(crate) fn receive_operator_accept(state: &Arc<AppState>, grantee: &str, key: &str) {
+    let room = state.session.client_state.get_room();
+    let grant = {
+        let mut handoff = state.session.operator_handoff.lock();
+        handoff.expire(Instant::now());
+        match handoff.grant.take() {
+            Some(grant) if grant.grantee == grantee && grant.room == room => grant,
+            other => {
+                handoff.grant = other;
+                return;
+            }
+        }
+    };
+    let password = state
+        .controlled_room_passwords
+        .lock()
+        .get(&grant.room)
+        .cloned();
+    let Some((operator_key, sealed)) =
+        password.and_then(|password| seal_password(key, &grant.room, &password))
+    else {
+        tracing::warn!("Could not seal the room password for {}", grantee);
+        return;
+    };
+    match send_handoff_chat(
+        state,
+        format!("{}{} {}", OPERATOR_KEY_CHAT_PREFIX, operator_key, sealed),
+    ) {
+        Ok(()) => emit_system_message(state, &state.tr("operator-handoff-sent", &[&grantee])),
+        Err(e) => emit_error_message(state, &e),
+    }
+}
+
+/// The operator sent the room password sealed for our key; identify with it
+pub(crate) fn receive_operator_key(state: &Arc<AppState>, operator: &str, key: &str, sealed: &str) {
+    let (room, password) = {
+        let mut handoff = state.session.operator_handoff.lock();
+        handoff.expire(Instant::now());
+        let Some(offer) = handoff.offer.as_ref() else {
+            return;
+        };
+        if offer.operator != operator || !offer.is_accepted() {
+            return;
+        }
+        // Keys sealed for other grantees do not open; keep waiting for ours
+        let Some(password) = offer.open(key, sealed) else {
+            return;
+        };
+        let room = offer.room.clone();
+        handoff.offer = None;
+        (room, password)
+    };
+    if room != state.session.client_state.get_room() {
+        return;
+    }
+    emit_system_message(state, &state.tr("operator-handoff-received", &[&operator]));
+    store_control_password(state, &room, &password, true);
+    reidentify_as_controller(state);
+}
+
+/// Let only `operators` pause and seek in the current normal room, or everyone when empty
+///
+/// The server does not enforce this; clients that saw the rule announced in room chat do.
+#[tauri::command]
+pub async fn set_operators_only<R: Runtime>(
+    operators: Vec<String>,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    let room = state.session.client_state.get_room();
+    if is_controlled_room(&room) {
+        return Err("Managed rooms already limit control to their operators".to_string());
+    }
+    let username = state.session.client_state.get_username();
+    if operators_only_rule(state.inner()).is_some_and(|rule| !rule.allows(&username)) {
+        return Err("Only operators of this room can change who controls it".to_string());
+    }
+    let operators = normalize_operators(&operators, &username);
+
+    let mut updated = state.config.lock().clone();
+    updated
+        .user
+        .operators_only_rooms
+        .retain(|entry| entry.room != room);
+    if !operators.is_empty() {
+        updated.user.operators_only_rooms.push(OperatorsOnlyRoom {
+            room: room.clone(),
+            operators: operators.clone(),
+        });
+    }
+    save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+
+    *state.session.operators_only.lock() =
+        Some(OperatorsOnlyRule::new(room.as_str(), operators.clone()));
+    if state.session.server_features.lock().chat {
+        if let Err(e) = send_room_control_chat(state.inner(), operators_only_chat(&operators)) {
+            emit_error_message(state.inner(), &e);
+        }
+    }
+    let message = if operators.is_empty() {
+        state.tr("operators-only-lifted", &[&username])
+    } else {
+        state.tr("operators-only-set", &[&operators.join(", ")])
+    };
+    emit_system_message(state.inner(), &message);
+    Ok(())
+}
+
+/// Operators-only rule of the current room, as last announced or as we set it up
+pub(crate) fn operators_only_rule(state: &Arc<AppState>) -> Option<OperatorsOnlyRule> {
+    let room = state.session.client_state.get_room();
+    if is_controlled_room(&room) {
+        return None;
+    }
+    let announced = state
+        .session
+        .operators_only
+        .lock()
+        .clone()
+        .filter(|rule| rule.room == room);
+    announced
+        .or_else(|| {
+            let config = state.config.lock();
+            config
+                .user
+                .operators_only_room(&room)
+                .map(|entry| OperatorsOnlyRule::new(room.as_str(), entry.operators.clone()))
+        })
+        .filter(|rule| !rule.operators.is_empty())
+}
+
+/// Repeat the rule we set up for this room, so users joining it follow it too
+pub(crate) fn reannounce_operators_only(state: &Arc<AppState>) {
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
+    let Some(rule) = operators_only_rule(state) else {
+        return;
+    };
+    let set_up_here = state
+        .config
+        .lock()
+        .user
+        .operators_only_room(&room)
+        .is_some();
+    if !set_up_here || !rule.allows(&username) || !state.session.server_features.lock().chat {
+        return;
+    }
+    if let Err(e) = send_room_control_chat(state, operators_only_chat(&rule.operators)) {
+        tracing::warn!("Failed to repeat the operators-only rule: {}", e);
+    }
+}
+
+/// Another user announced who may pause and seek in this normal room
+pub(crate) fn receive_operators_only(state: &Arc<AppState>, actor: &str, operators: Vec<String>) {
+    let room = state.session.client_state.get_room();
+    if is_controlled_room(&room) {
+        return;
+    }
+    // A rule this client set up only changes through set_operators_only
+    if state
+        .config
+        .lock()
+        .user
+        .operators_only_room(&room)
+        .is_some()
+    {
+        tracing::info!(
+            "Ignoring operators-only change from {} for a room set up here",
+            actor
+        );
+        return;
+    }
+    // Once a rule is in place, only its operators can change it
+    if operators_only_rule(state).is_some_and(|rule| !rule.allows(actor)) {
+        tracing::info!("Ignoring operators-only change from non-operator {}", actor);
+        return;
+    }
+    if !operators.is_empty()
+        && !operators
+            .iter()
+            .any(|operator| operator.eq_ignore_ascii_case(actor))
+    {
+        tracing::info!(
+            "Ignoring operators-only list that leaves out its sender {}",
+            actor
+        );
+        return;
+    }
+    let message = if operators.is_empty() {
+        state.tr("operators-only-lifted", &[&actor])
+    } else {
+        state.tr("operators-only-announced", &[&actor, &operators.join(", ")])
+    };
+    *state.session.operators_only.lock() = Some(OperatorsOnlyRule::new(room, operators));
+    emit_system_message(state, &message);
+}
+
+/// A non-operator suggested a pause or seek; operators see it on the player too
+pub(crate) fn receive_suggestion(state: &Arc<AppState>, actor: &str, suggestion: Suggestion) {
+    let Some(rule) = operators_only_rule(state) else {
+        return;
+    };
+    let message = match suggestion {
+        Suggestion::Pause => state.tr("suggestion-pause", &[&actor]),
+        Suggestion::Unpause => state.tr("suggestion-unpause", &[&actor]),
+        Suggestion::Seek(position) => {
+            state.tr("suggestion-seek", &[&actor, &format_time(position, false)])
+        }
+    };
+    emit_system_message(state, &message);
+    if rule.allows(&state.session.client_state.get_username()) {
+        let config = state.config.lock().clone();
+        maybe_show_osd(state, &config, &message, OsdCategory::Status);
+    }
+}
+
+/// Count a vote on playback; the one client chosen to carry it out does once it passes
+pub(crate) fn receive_vote(state: &Arc<AppState>, voter: &str, subject: VoteSubject) {
+    let config = state.config.lock().clone();
+    let room = state.session.client_state.get_room();
+    let room_size = state.session.client_state.get_users_in_room(&room).len();
+    let needed = votes_needed(room_size, config.user.vote_majority_percent);
+    let open_for = Duration::from_secs(config.user.vote_timeout_secs);
+    let cast = state
+        .session
+        .votes
+        .lock()
+        .cast(voter, subject, needed, open_for, Instant::now());
+    match cast {
+        VoteCast::Repeated => {}
+        VoteCast::Counted { votes, needed } => {
+            let message = match subject {
+                VoteSubject::Pause => state.tr("vote-counted-pause", &[&voter, &votes, &needed]),
+                VoteSubject::Seek(position) => state.tr(
+                    "vote-counted-seek",
+                    &[&voter, &format_time(position, false), &votes, &needed],
+                ),
+                VoteSubject::Skip => state.tr("vote-counted-skip", &[&voter, &votes, &needed]),
+            };
+            emit_system_message(state, &message);
+        }
+        VoteCast::Passed { subject, .. } => {
+            let message = match subject {
+                VoteSubject::Pause => state.tr("vote-passed-pause", &[]),
+                VoteSubject::Seek(position) => {
+                    state.tr("vote-passed-seek", &[&format_time(position, false)])
+                }
+                VoteSubject::Skip => state.tr("vote-passed-skip", &[]),
+            };
+            emit_system_message(state, &message);
+            maybe_show_osd(state, &config, &message, OsdCategory::Status);
+            if carries_out_votes(state, voter) {
+                tokio::spawn(carry_out_vote(state.clone(), subject));
+            }
+        }
+    }
+}
+
+/// Whether this client carries out a passed vote, so that exactly one in the room does
+///
+/// That is the first operator present when the room has operators, and otherwise the
+/// user whose vote decided it.
+fn carries_out_votes(state: &Arc<AppState>, deciding_voter: &str) -> bool {
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
+    let users = state.session.client_state.get_users_in_room(&room);
+    let mut operators: Vec<String> = if is_controlled_room(&room) {
+        users
+            .into_iter()
+            .filter(|user| user.is_controller)
+            .map(|user| user.username)
+            .collect()
+    } else if let Some(rule) = operators_only_rule(state) {
+        users
+            .into_iter()
+            .filter(|user| rule.allows(&user.username))
+            .map(|user| user.username)
+            .collect()
+    } else {
+        return deciding_voter == username;
+    };
+    operators.sort_by_key(|name| name.to_lowercase());
+    operators.first() == Some(&username)
+}
+
+async fn carry_out_vote(state: Arc<AppState>, subject: VoteSubject) {
+    let result = if subject == VoteSubject::Skip {
+        let config = state.config.lock().clone();
+        crate::commands::playlist::play_next_item(&state, &config).await
+    } else {
+        let player = state.player.lock().clone();
+        let Some(player) = player else {
+            tracing::warn!("No player to carry out the vote on");
+            return;
+        };
+        // The state loop sends these on like any pause or seek of ours
+        match subject {
+            VoteSubject::Seek(position) => {
+                *state.confirmed_seek.lock() = Some(position);
+                player.set_position(position).await
+            }
+            _ => player.set_paused(true).await,
+        }
+        .map_err(|e| e.to_string())
+    };
+    if let Err(e) = result {
+        emit_error_message(&state, &format!("Failed to carry out the vote: {}", e));
+    }
+}
+
+fn operators_only_chat(operators: &[String]) -> String {
+    if operators.is_empty() {
+        OPERATORS_ONLY_CHAT_PREFIX.to_string()
+    } else {
+        format!("{} {}", OPERATORS_ONLY_CHAT_PREFIX, operators.join(" "))
+    }
+}
+
+/// Send a room control message, such as a rule or a suggestion, as chat
+pub(crate) fn send_room_control_chat(state: &Arc<AppState>, text: String) -> Result<(), String> {
+    let connection = state.session.connection.lock().clone();
+    let Some(connection) = connection else {
+        return Err("Not connected to server".to_string());
+    };
+    connection
+        .send(ProtocolMessage::Chat {
+            Chat: ChatMessage::Text(text),
+        })
+        .map_err(|e| format!("Failed to send to the room: {}", e))
+}
+
+fn send_handoff_chat(state: &Arc<AppState>, text: String) -> Result<(), String> {
+    let connection = state.session.connection.lock().clone();
+    let Some(connection) = connection else {
+        return Err("Not connected to server".to_string());
+    };
+    connection
+        .send(ProtocolMessage::Chat {
+            Chat: ChatMessage::Text(text),
+        })
+        .map_err(|e| format!("Failed to hand over control: {}", e))
+}
+
 fn send_to_server(
     state: &State<'_, Arc<AppState>>,
     message: ProtocolMessage,
 ) -> Result<(), String> {
-    let connection = state.connection.lock().clone();
+    let connection = state.session.connection.lock().clone();
     let Some(connection) = connection else {
         return Err("Not connected to server".to_string());
     };
@@ -122,3 +778,275 @@ fn send_to_server(
         .send(message)
         .map_err(|e| format!("Failed to send message: {}", e))
 }
+
+#[tauri::command]
+pub async fn list_rooms(state: State<'_, Arc<AppState>>) -> Result<Vec<RoomSummary>, String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    refresh_user_list(&state).await?;
+    Ok(state.session.client_state.get_room_summaries())
+}
+
+/// Look into another room from the user list without leaving the current one
+#[tauri::command]
+pub async fn get_room_overview(
+    room: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<RoomOverview, String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    refresh_user_list(&state).await?;
+    Ok(state.session.client_state.get_room_overview(&room))
+}
+
+/// Join `room` with the file its users are watching; the room state then seeks us to them
+#[tauri::command]
+pub async fn join_room_at_position<R: Runtime>(
+    room: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if !state.is_connected() {
+        return Err("Not connected to server".to_string());
+    }
+    let app_state = state.inner().clone();
+    refresh_user_list(&state).await?;
+    let overview = app_state.session.client_state.get_room_overview(&room);
+    if !overview.is_current {
+        change_room(room, None, app, state).await?;
+    }
+    let Some(file) = overview.files.first() else {
+        return Ok(());
+    };
+    let current_file = app_state.session.client_state.get_file();
+    if !same_filename(current_file.as_deref(), Some(file)) {
+        load_media_by_name(&app_state, file, false, false).await?;
+    }
+    Ok(())
+}
+
+/// Ask for a fresh List, falling back to the cached users if it is slow to come
+async fn refresh_user_list(state: &State<'_, Arc<AppState>>) -> Result<(), String> {
+    let list_updated = state.session.list_updated.clone();
+    let notified = list_updated.notified();
+    send_to_server(state, ProtocolMessage::List { List: None })?;
+    if timeout(Duration::from_secs(LIST_ROOMS_TIMEOUT_SECONDS), notified)
+        .await
+        .is_err()
+    {
+        tracing::warn!("Timed out waiting for room list, using cached users");
+    }
+    Ok(())
+}
+
+/// Persistent rooms on the server plus the ones marked on this client
+#[tauri::command]
+pub async fn list_persistent_rooms(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PersistentRoomInfo>, String> {
+    let Some(server) = persistent_rooms_server(state.inner()) else {
+        return Err(persistent_rooms_unavailable(state.inner()));
+    };
+    refresh_user_list(&state).await?;
+
+    let current_room = state.session.client_state.get_room();
+    let summaries = state.session.client_state.get_room_summaries();
+    let marked = state.persistent_rooms.rooms_for(&server);
+    let mut rooms: Vec<PersistentRoomInfo> = state
+        .session
+        .client_state
+        .get_empty_rooms()
+        .into_iter()
+        .map(|name| PersistentRoomInfo {
+            is_current: name == current_room,
+            name,
+            user_count: 0,
+            on_server: true,
+            marked: false,
+            playlist_length: 0,
+        })
+        .collect();
+    for entry in marked {
+        if let Some(info) = rooms.iter_mut().find(|room| room.name == entry.room) {
+            info.marked = true;
+            info.playlist_length = entry.items.len();
+            continue;
+        }
+        let user_count = summaries
+            .iter()
+            .find(|summary| summary.name == entry.room)
+            .map_or(0, |summary| summary.user_count);
+        rooms.push(PersistentRoomInfo {
+            is_current: entry.room == current_room,
+            name: entry.room,
+            user_count,
+            on_server: false,
+            marked: true,
+            playlist_length: entry.items.len(),
+        });
+    }
+    rooms.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rooms)
+}
+
+/// Mark the current room persistent so its playlist comes back after a disconnect
+#[tauri::command]
+pub async fn set_room_persistent(
+    persistent: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let Some(server) = persistent_rooms_server(state.inner()) else {
+        return Err(persistent_rooms_unavailable(state.inner()));
+    };
+    let room = state.session.client_state.get_room();
+    if !state
+        .persistent_rooms
+        .set_marked(&server, &room, persistent)
+    {
+        return Ok(());
+    }
+    if persistent {
+        state.persistent_rooms.remember_playlist(
+            &server,
+            &room,
+            state.session.playlist.get_item_filenames(),
+            state.session.playlist.get_current_index(),
+        );
+    }
+    persist_persistent_rooms(state.inner());
+    let key = if persistent {
+        "room-marked-persistent"
+    } else {
+        "room-unmarked-persistent"
+    };
+    emit_system_message(state.inner(), &state.tr(key, &[&room]));
+    Ok(())
+}
+
+/// `host:port` of the current server when it supports persistent rooms
+pub(crate) fn persistent_rooms_server(state: &Arc<AppState>) -> Option<String> {
+    if !state.is_connected() || !state.session.server_features.lock().persistent_rooms {
+        return None;
+    }
+    state
+        .reconnect_snapshot
+        .lock()
+        .as_ref()
+        .map(|snapshot| format!("{}:{}", snapshot.host, snapshot.port))
+}
+
+/// Keep the playlist of the current room if it is marked persistent
+pub(crate) fn remember_persistent_playlist(state: &Arc<AppState>) {
+    let Some(server) = persistent_rooms_server(state) else {
+        return;
+    };
+    let room = state.session.client_state.get_room();
+    if state.persistent_rooms.remember_playlist(
+        &server,
+        &room,
+        state.session.playlist.get_item_filenames(),
+        state.session.playlist.get_current_index(),
+    ) {
+        persist_persistent_rooms(state);
+    }
+}
+
+fn persistent_rooms_unavailable(state: &Arc<AppState>) -> String {
+    if state.is_connected() {
+        "Server does not support persistent rooms".to_string()
+    } else {
+        "Not connected to server".to_string()
+    }
+}
+
+fn persist_persistent_rooms(state: &Arc<AppState>) {
+    let app = state.app_handle.lock().clone();
+    if let Some(app) = app {
+        if let Err(e) = save_persistent_rooms(&app, &state.persistent_rooms.entries()) {
+            tracing::warn!("Failed to persist persistent rooms: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn join_room<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Room name cannot be empty".to_string());
+    }
+    if state.session.client_state.get_room() == name {
+        return Ok(());
+    }
+    change_room(name, None, app, state).await
+}
+
+#[tauri::command]
+pub async fn get_room_state(state: State<'_, Arc<AppState>>) -> Result<RoomStateSnapshot, String> {
+    Ok(room_state_snapshot(state.inner()))
+}
+
+pub(crate) fn room_state_snapshot(state: &Arc<AppState>) -> RoomStateSnapshot {
+    let connected = state.is_connected();
+    let server = if connected {
+        state
+            .reconnect_snapshot
+            .lock()
+            .as_ref()
+            .map(|snapshot| format!("{}:{}", snapshot.host, snapshot.port))
+    } else {
+        None
+    };
+    let users = state
+        .session
+        .client_state
+        .get_users()
+        .into_iter()
+        .filter(|user| !is_placeholder_username(&user.username))
+        .map(|user| UserInfo {
+            username: user.username,
+            room: user.room,
+            file: user.file,
+            is_ready: user.is_ready.unwrap_or(false),
+            is_controller: user.is_controller,
+        })
+        .collect();
+    let global = state.session.client_state.get_global_state();
+    let playlist = PlaylistEvent {
+        items: state
+            .session
+            .playlist
+            .get_items()
+            .iter()
+            .map(|item| item.filename.clone())
+            .collect(),
+        current_index: state.session.playlist.get_current_index(),
+    };
+
+    RoomStateSnapshot {
+        connected,
+        server,
+        server_version: state.session.client_state.get_server_version(),
+        features: state.session.server_features.lock().clone(),
+        username: state.session.client_state.get_username(),
+        room: state.session.client_state.get_room(),
+        users,
+        global_position: global.position,
+        global_paused: global.paused,
+        global_set_by: global.set_by,
+        playlist,
+        tls_status: state.session.tls_status.lock().clone(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_room_timeline(
+    room: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<RoomEvent>, String> {
+    Ok(state.session.room_timeline.entries(room.as_deref()))
+}
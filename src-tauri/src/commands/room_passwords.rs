@@ -0,0 +1,132 @@
+// Saved room password command handlers
+
+use crate::app_state::AppState;
+use crate::config::save_config;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedPasswordKind {
+    /// Operator password of a managed room
+    Operator,
+    /// Password the server asks for when joining the room
+    Join,
+}
+
+/// A saved room credential as listed in settings; the password itself is only revealed on request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedRoomPassword {
+    pub room: String,
+    pub kind: SavedPasswordKind,
+    /// Stored in the config, not only remembered for this session
+    pub saved: bool,
+    /// Held for this session to identify as operator
+    pub active: bool,
+}
+
+#[tauri::command]
+pub async fn list_saved_room_passwords(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<SavedRoomPassword>, String> {
+    let config = state.config.lock().clone();
+    let active = state.controlled_room_passwords.lock().clone();
+    let mut entries: BTreeMap<(String, SavedPasswordKind), SavedRoomPassword> = BTreeMap::new();
+    for (room, _) in config.user.saved_operator_passwords() {
+        entries.insert(
+            (room.clone(), SavedPasswordKind::Operator),
+            SavedRoomPassword {
+                room: room.clone(),
+                kind: SavedPasswordKind::Operator,
+                saved: true,
+                active: active.contains_key(&room),
+            },
+        );
+    }
+    for room in active.keys() {
+        entries
+            .entry((room.clone(), SavedPasswordKind::Operator))
+            .or_insert_with(|| SavedRoomPassword {
+                room: room.clone(),
+                kind: SavedPasswordKind::Operator,
+                saved: false,
+                active: true,
+            });
+    }
+    for room in config.user.room_passwords.keys() {
+        entries.insert(
+            (room.clone(), SavedPasswordKind::Join),
+            SavedRoomPassword {
+                room: room.clone(),
+                kind: SavedPasswordKind::Join,
+                saved: true,
+                active: false,
+            },
+        );
+    }
+    Ok(entries.into_values().collect())
+}
+
+#[tauri::command]
+pub async fn reveal_saved_room_password(
+    room: String,
+    kind: SavedPasswordKind,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    let password = match kind {
+        SavedPasswordKind::Operator => state
+            .controlled_room_passwords
+            .lock()
+            .get(&room)
+            .cloned()
+            .or_else(|| {
+                state
+                    .config
+                    .lock()
+                    .user
+                    .saved_operator_passwords()
+                    .into_iter()
+                    .find(|(saved_room, _)| saved_room == &room)
+                    .map(|(_, password)| password)
+            }),
+        SavedPasswordKind::Join => state.config.lock().user.room_password(&room),
+    };
+    password.ok_or_else(|| format!("No password saved for {}", room))
+}
+
+/// Forget a saved password everywhere it is kept, leaving the room in the room list
+#[tauri::command]
+pub async fn remove_saved_room_password<R: Runtime>(
+    room: String,
+    kind: SavedPasswordKind,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let mut updated = state.config.lock().clone();
+    let changed = match kind {
+        SavedPasswordKind::Operator => {
+            let active = state
+                .controlled_room_passwords
+                .lock()
+                .remove(&room)
+                .is_some();
+            let saved = updated.user.forget_operator_password(&room);
+            if active && !saved {
+                // Only remembered for this session; nothing to write back
+                return Ok(());
+            }
+            saved
+        }
+        SavedPasswordKind::Join => updated.user.room_passwords.remove(&room).is_some(),
+    };
+    if !changed {
+        return Err(format!("No password saved for {}", room));
+    }
+    save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+    Ok(())
+}
@@ -0,0 +1,21 @@
+// User script command handlers
+
+use crate::app_state::AppState;
+use crate::integrations::scripts::{self, ScriptsStatus};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_scripts(state: State<'_, Arc<AppState>>) -> ScriptsStatus {
+    scripts::status(state.inner())
+}
+
+/// Load the scripts again, e.g. after editing them
+#[tauri::command]
+pub async fn reload_scripts(state: State<'_, Arc<AppState>>) -> Result<ScriptsStatus, String> {
+    let config = state.config.lock().scripts.clone();
+    if !config.enabled {
+        return Err("Scripts are turned off".to_string());
+    }
+    Ok(scripts::start(state.inner(), &config))
+}
@@ -0,0 +1,166 @@
+// Intro and outro segment command handlers
+
+use crate::app_state::AppState;
+use crate::client::segments::segments_from_chapters;
+use crate::client::segments::{parse_segment_file, validate_segments, Segment, SegmentKind};
+use crate::commands::connection::{current_user_can_control, maybe_show_osd};
+use crate::config::OsdCategory;
+use crate::player::backend::PlayerBackend;
+use crate::player::controller::ensure_player_connected;
+use crate::utils::format_time;
+use std::sync::Arc;
+use tauri::State;
+
+/// Segments of `file`, or of the open file
+#[tauri::command]
+pub async fn get_segments(
+    file: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Segment>, String> {
+    let file = resolve_file(state.inner(), file)?;
+    Ok(state.segments.get(&file))
+}
+
+/// Replace the segments of `file`, or of the open file
+#[tauri::command]
+pub async fn set_segments(
+    file: Option<String>,
+    segments: Vec<Segment>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Segment>, String> {
+    let file = resolve_file(state.inner(), file)?;
+    let segments = validate_segments(segments)?;
+    state.segments.set(&file, segments.clone());
+    persist_segments(state.inner());
+    Ok(segments)
+}
+
+/// Import segments from a JSON or EDL file, returning how many were stored
+///
+/// Entries that do not name a file apply to the open file.
+#[tauri::command]
+pub async fn import_segments(
+    path: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<usize, String> {
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read segment file: {}", e))?;
+    let duration = state.session.client_state.get_file_duration();
+    let imported = parse_segment_file(&text, duration)?;
+    let mut count = 0;
+    for entry in imported {
+        let file = resolve_file(state.inner(), entry.file)?;
+        count += entry.segments.len();
+        state.segments.set(&file, entry.segments);
+    }
+    persist_segments(state.inner());
+    tracing::info!("Imported {} segments from {}", count, path);
+    Ok(count)
+}
+
+/// Seek past the segment playing now; the seek reaches the room like any other local seek
+#[tauri::command]
+pub async fn skip_segment(
+    kind: Option<SegmentKind>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let file = resolve_file(state.inner(), None)?;
+    ensure_player_connected(state.inner()).await?;
+    let player = state.player.lock().clone();
+    let Some(player) = player else {
+        return Err("Player not connected".to_string());
+    };
+    let Some(position) = player.get_state().position else {
+        return Err("Player position is unknown".to_string());
+    };
+    let Some(segment) = state.segments.segment_at(&file, position, kind) else {
+        return Err("Not inside a known segment".to_string());
+    };
+    tracing::info!(
+        "Skipping {:?} from {:.1}s to {:.1}s",
+        segment.kind,
+        position,
+        segment.end
+    );
+    player
+        .set_position(segment.end)
+        .await
+        .map_err(|e| format!("Failed to seek: {}", e))
+}
+
+/// Offer to skip when playback of the open file enters a known segment
+pub(crate) fn maybe_prompt_segment(state: &Arc<AppState>, position: f64) {
+    if state.segments.is_empty() {
+        return;
+    }
+    let config = state.config.lock().clone();
+    if !config.user.segment_skip_prompt {
+        return;
+    }
+    let Some(file) = state.session.client_state.get_file() else {
+        return;
+    };
+    // Chapter segments skip for the whole room, so only offer them to operators
+    let manual = state
+        .segments
+        .get(&file)
+        .into_iter()
+        .find(|segment| segment.contains(position));
+    let current = manual.or_else(|| {
+        current_user_can_control(state)
+            .then(|| state.segments.chapter_segment_at(&file, position))
+            .flatten()
+    });
+    let Some(change) = state.segments.prompt_change(&file, current) else {
+        return;
+    };
+    if let Some(segment) = change {
+        let kind = state.tr(segment.kind.i18n_key(), &[]);
+        let message = state.tr(
+            "segment-skip-prompt",
+            &[&kind, &format_time(segment.end, false)],
+        );
+        maybe_show_osd(state, &config, &message, OsdCategory::Status);
+    }
+    state.emit_event("segment-changed", change);
+}
+
+/// Read the chapters of a newly opened file and keep the skippable ones
+pub(crate) fn refresh_chapter_segments(state: Arc<AppState>, player: Arc<dyn PlayerBackend>) {
+    tokio::spawn(async move {
+        let Some(file) = state.session.client_state.get_file() else {
+            return;
+        };
+        if !player.capabilities().chapters {
+            return;
+        }
+        let chapters = match player.get_chapters().await {
+            Ok(chapters) => chapters,
+            Err(e) => {
+                tracing::debug!("Failed to read chapters: {}", e);
+                Vec::new()
+            }
+        };
+        let duration = player.get_state().duration;
+        let segments = segments_from_chapters(&chapters, duration);
+        if !segments.is_empty() {
+            tracing::info!("Found {} skippable chapters in {}", segments.len(), file);
+        }
+        state.segments.set_chapter_segments(&file, segments);
+    });
+}
+
+fn resolve_file(state: &Arc<AppState>, file: Option<String>) -> Result<String, String> {
+    file.filter(|file| !file.trim().is_empty())
+        .or_else(|| state.session.client_state.get_file())
+        .ok_or_else(|| "No file is open".to_string())
+}
+
+fn persist_segments(state: &Arc<AppState>) {
+    let app = state.app_handle.lock().clone();
+    if let Some(app) = app {
+        if let Err(e) = crate::config::save_segments(&app, &state.segments.entries()) {
+            tracing::warn!("Failed to persist segments: {}", e);
+        }
+    }
+}
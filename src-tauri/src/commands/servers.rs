@@ -0,0 +1,53 @@
+// Recent server command handlers
+
+use crate::app_state::AppState;
+use crate::config::{save_config, ServerConfig, SyncplayConfig};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
+
+#[tauri::command]
+pub async fn get_recent_servers(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ServerConfig>, String> {
+    Ok(state.config.lock().recent_servers.clone())
+}
+
+#[tauri::command]
+pub async fn remove_recent_server<R: Runtime>(
+    host: String,
+    port: u16,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    update_recent_servers(&app, state.inner(), |config| {
+        config.remove_recent_server(&host, port)
+    })
+}
+
+#[tauri::command]
+pub async fn pin_server<R: Runtime>(
+    host: String,
+    port: u16,
+    pinned: bool,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    update_recent_servers(&app, state.inner(), |config| {
+        config.pin_server(&host, port, pinned)
+    })
+}
+
+fn update_recent_servers<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &Arc<AppState>,
+    change: impl FnOnce(&mut SyncplayConfig) -> bool,
+) -> Result<(), String> {
+    let mut updated = state.config.lock().clone();
+    if !change(&mut updated) {
+        return Err("Server is not in the recent servers list".to_string());
+    }
+    save_config(app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.lock() = updated.clone();
+    state.emit_event("config-updated", updated);
+    Ok(())
+}
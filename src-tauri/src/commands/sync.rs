@@ -0,0 +1,143 @@
+// Sync statistics and local override command handlers
+
+use crate::app_state::AppState;
+use crate::client::sync::SyncReport;
+use crate::commands::connection::{emit_system_message, maybe_show_osd};
+use crate::config::OsdCategory;
+use crate::player::backend::PlayerBackend;
+use crate::player::controller::ensure_player_connected;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::State;
+
+/// Slowest and fastest local slow-motion speeds
+const LOCAL_SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.05..=1.0;
+
+#[tauri::command]
+pub async fn get_sync_report(state: State<'_, Arc<AppState>>) -> Result<SyncReport, String> {
+    Ok(state.sync_engine.lock().sync_report(true))
+}
+
+/// Step one frame forward without moving the room
+#[tauri::command]
+pub async fn frame_step(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    step_frame(state.inner(), false).await
+}
+
+/// Step one frame back without moving the room
+#[tauri::command]
+pub async fn frame_back_step(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    step_frame(state.inner(), true).await
+}
+
+/// Play slower than the room without slowing anybody else down
+#[tauri::command]
+pub async fn set_local_speed(speed: f64, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    if !LOCAL_SPEED_RANGE.contains(&speed) {
+        return Err(format!(
+            "Local speed must be between {} and {}",
+            LOCAL_SPEED_RANGE.start(),
+            LOCAL_SPEED_RANGE.end()
+        ));
+    }
+    let player = connected_player(state.inner()).await?;
+    detach(state.inner());
+    player
+        .set_speed(speed)
+        .await
+        .map_err(|e| format!("Failed to set speed: {}", e))
+}
+
+/// Reattach a detached player and jump back to the room position
+#[tauri::command]
+pub async fn resync_playback(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    if !*state.sync_detached.lock() {
+        return Ok(());
+    }
+    let player = connected_player(state.inner()).await?;
+    let global = state.session.client_state.get_global_state();
+    if let Err(e) = player.set_speed(1.0).await {
+        tracing::warn!("Failed to reset speed on resync: {}", e);
+    }
+    let position = global.position_at(Instant::now());
+    player
+        .set_position(position)
+        .await
+        .map_err(|e| format!("Failed to seek: {}", e))?;
+    player
+        .set_paused(global.paused)
+        .await
+        .map_err(|e| format!("Failed to set paused: {}", e))?;
+    if let Err(e) = player.poll_state().await {
+        tracing::warn!("Failed to refresh player state after resync: {}", e);
+    }
+    // Record the jump as our own state so the controller does not report it as a seek
+    let refreshed = player.get_state();
+    if let (Some(position), Some(paused)) = (refreshed.position, refreshed.paused) {
+        let global = state.session.client_state.get_global_state();
+        state.local_playback_state.lock().update_from_player(
+            position,
+            paused,
+            global.position,
+            global.paused,
+        );
+    }
+    *state.sync_detached.lock() = false;
+    state.emit_event("sync-detached-changed", false);
+    announce(state.inner(), "sync-resynced");
+    Ok(())
+}
+
+/// Let a large seek that was taken back through to the room, or drop it
+#[tauri::command]
+pub async fn confirm_seek(confirm: bool, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    let request = state
+        .pending_seek
+        .lock()
+        .take()
+        .ok_or_else(|| "No seek is waiting for confirmation".to_string())?;
+    if !confirm {
+        return Ok(());
+    }
+    let player = connected_player(state.inner()).await?;
+    *state.confirmed_seek.lock() = Some(request.to);
+    player
+        .set_position(request.to)
+        .await
+        .map_err(|e| format!("Failed to seek: {}", e))
+}
+
+async fn step_frame(state: &Arc<AppState>, backwards: bool) -> Result<(), String> {
+    let player = connected_player(state).await?;
+    detach(state);
+    player
+        .frame_step(backwards)
+        .await
+        .map_err(|e| format!("Failed to step frame: {}", e))
+}
+
+async fn connected_player(state: &Arc<AppState>) -> Result<Arc<dyn PlayerBackend>, String> {
+    ensure_player_connected(state).await?;
+    let player = state.player.lock().clone();
+    player.ok_or_else(|| "Player not connected".to_string())
+}
+
+fn detach(state: &Arc<AppState>) {
+    {
+        let mut detached = state.sync_detached.lock();
+        if *detached {
+            return;
+        }
+        *detached = true;
+    }
+    tracing::info!("Detached local player from room sync");
+    state.emit_event("sync-detached-changed", true);
+    announce(state, "sync-detached");
+}
+
+fn announce(state: &Arc<AppState>, key: &str) {
+    let config = state.config.lock().clone();
+    let message = state.tr(key, &[]);
+    emit_system_message(state, &message);
+    maybe_show_osd(state, &config, &message, OsdCategory::Status);
+}
@@ -0,0 +1,58 @@
+// Untrusted URL command handlers
+
+use crate::app_state::AppState;
+use crate::config::save_config;
+use crate::player::controller::load_url;
+use serde::Deserialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UntrustedUrlAcceptance {
+    /// Load this URL without trusting its domain
+    Once,
+    /// Add the domain to the trusted domains, then load the URL
+    Always,
+}
+
+/// Resume the URL load that was held back over an untrusted domain
+#[tauri::command]
+pub async fn accept_untrusted_url<R: Runtime>(
+    url: String,
+    mode: UntrustedUrlAcceptance,
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let pending = {
+        let mut pending = state.pending_untrusted_url.lock();
+        match pending.as_ref() {
+            Some(load) if load.url == url => pending.take(),
+            _ => None,
+        }
+    }
+    .ok_or_else(|| "No pending load for this URL".to_string())?;
+    if mode == UntrustedUrlAcceptance::Always {
+        let mut updated = state.config.lock().clone();
+        if !updated.user.trusted_domains.contains(&pending.domain) {
+            updated.user.trusted_domains.push(pending.domain.clone());
+            save_config(&app, &updated).map_err(|e| format!("Failed to save config: {}", e))?;
+            *state.config.lock() = updated.clone();
+            state.emit_event("config-updated", updated);
+        }
+    }
+    load_url(
+        state.inner(),
+        &pending.url,
+        pending.reset_position,
+        pending.suppress_update,
+    )
+    .await
+}
+
+/// Drop the URL load that was held back over an untrusted domain
+#[tauri::command]
+pub async fn dismiss_untrusted_url(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.pending_untrusted_url.lock().take();
+    Ok(())
+}
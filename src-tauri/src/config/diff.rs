@@ -0,0 +1,192 @@
+// Config diff module
+// Works out which parts of the configuration an update touched
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+use super::SyncplayConfig;
+
+/// A part of the configuration that one subsystem depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSection {
+    /// Servers, presets and the connection target
+    Server,
+    /// Thresholds and corrections the sync engine uses
+    Sync,
+    /// Autoplay and unpause behaviour
+    Autoplay,
+    Osd,
+    Chat,
+    Playlist,
+    /// Player path, arguments and process handling
+    Player,
+    /// Directories the media index scans
+    MediaIndex,
+    Reconnect,
+    /// Recently played files
+    History,
+    /// The remote control API
+    Remote,
+    /// User scripts and where they are loaded from
+    Scripts,
+    /// The metrics endpoint
+    Metrics,
+    /// Any other user preference
+    Preferences,
+}
+
+/// Payload of the `config-section-updated` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSectionEvent {
+    pub section: ConfigSection,
+}
+
+/// The sections that differ between `previous` and `config`
+pub fn changed_sections(
+    previous: &SyncplayConfig,
+    config: &SyncplayConfig,
+) -> BTreeSet<ConfigSection> {
+    let (Ok(Value::Object(previous)), Ok(Value::Object(config))) =
+        (serde_json::to_value(previous), serde_json::to_value(config))
+    else {
+        return BTreeSet::new();
+    };
+    changed_fields(&previous, &config)
+        .flat_map(|key| match key {
+            "user" => nested_sections(&previous, &config, key, user_field_section),
+            "player" => nested_sections(&previous, &config, key, player_field_section),
+            _ => BTreeSet::from([top_level_section(key)]),
+        })
+        .collect()
+}
+
+fn changed_fields<'a>(
+    previous: &'a Map<String, Value>,
+    config: &'a Map<String, Value>,
+) -> impl Iterator<Item = &'a str> {
+    let keys: BTreeSet<&str> = previous
+        .keys()
+        .chain(config.keys())
+        .map(String::as_str)
+        .collect();
+    keys.into_iter()
+        .filter(|key| previous.get(*key) != config.get(*key))
+}
+
+fn nested_sections(
+    previous: &Map<String, Value>,
+    config: &Map<String, Value>,
+    key: &str,
+    section: fn(&str) -> ConfigSection,
+) -> BTreeSet<ConfigSection> {
+    let empty = Map::new();
+    let previous = previous
+        .get(key)
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let config = config.get(key).and_then(Value::as_object).unwrap_or(&empty);
+    changed_fields(previous, config).map(section).collect()
+}
+
+fn top_level_section(key: &str) -> ConfigSection {
+    match key {
+        "reconnect" => ConfigSection::Reconnect,
+        "recent_files" => ConfigSection::History,
+        "remote" => ConfigSection::Remote,
+        "scripts" => ConfigSection::Scripts,
+        "metrics" => ConfigSection::Metrics,
+        _ => ConfigSection::Server,
+    }
+}
+
+fn player_field_section(field: &str) -> ConfigSection {
+    match field {
+        "media_directories" => ConfigSection::MediaIndex,
+        _ => ConfigSection::Player,
+    }
+}
+
+fn user_field_section(field: &str) -> ConfigSection {
+    const SYNC_FIELDS: [&str; 5] = [
+        "sync_mode",
+        "slow_on_desync",
+        "rewind_on_desync",
+        "fastforward_on_desync",
+        "dont_slow_down_with_me",
+    ];
+    const SYNC_PREFIXES: [&str; 4] = [
+        "seek_threshold_",
+        "slowdown_",
+        "fastforward_",
+        "smooth_sync_",
+    ];
+    const OSD_FIELDS: [&str; 3] = [
+        "show_duration_notification",
+        "notification_timeout",
+        "alert_timeout",
+    ];
+
+    if SYNC_FIELDS.contains(&field) || SYNC_PREFIXES.iter().any(|prefix| field.starts_with(prefix))
+    {
+        ConfigSection::Sync
+    } else if field.starts_with("autoplay_") || field.starts_with("unpause_") {
+        ConfigSection::Autoplay
+    } else if field.starts_with("osd_") || OSD_FIELDS.contains(&field) {
+        ConfigSection::Osd
+    } else if field.starts_with("chat_") && field != "chat_timestamp_format" {
+        ConfigSection::Chat
+    } else if field.starts_with("playlist_")
+        || field.starts_with("loop_")
+        || field == "shared_playlist_enabled"
+    {
+        ConfigSection::Playlist
+    } else {
+        ConfigSection::Preferences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_map_to_their_sections() {
+        let previous = SyncplayConfig::default();
+        assert!(changed_sections(&previous, &previous).is_empty());
+
+        let mut config = previous.clone();
+        config.user.seek_threshold_rewind += 1.0;
+        config.user.chat_max_lines += 1;
+        config.player.media_directories.push("/media".to_string());
+        config.server.port = 8995;
+        assert_eq!(
+            changed_sections(&previous, &config),
+            BTreeSet::from([
+                ConfigSection::Server,
+                ConfigSection::Sync,
+                ConfigSection::Chat,
+                ConfigSection::MediaIndex,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_user_fields_fall_back_to_preferences() {
+        assert_eq!(
+            user_field_section("unpause_action"),
+            ConfigSection::Autoplay
+        );
+        assert_eq!(user_field_section("osd_color"), ConfigSection::Osd);
+        assert_eq!(
+            user_field_section("loop_single_files"),
+            ConfigSection::Playlist
+        );
+        assert_eq!(
+            user_field_section("chat_timestamp_format"),
+            ConfigSection::Preferences
+        );
+        assert_eq!(user_field_section("theme"), ConfigSection::Preferences);
+    }
+}
@@ -1,8 +1,23 @@
+pub mod diff;
+pub mod overrides;
 pub mod persistence;
 pub mod settings;
+pub mod validation;
 
-pub use persistence::{get_config_path, load_config, save_config};
+pub use diff::{changed_sections, ConfigSection, ConfigSectionEvent};
+pub use overrides::{AppliedOverrides, ConfigOverrides};
+pub use persistence::{
+    get_config_path, load_bookmarks, load_config, load_delays, load_persistent_rooms,
+    load_player_detection, load_playlist_snapshot, load_segments, save_bookmarks, save_config,
+    save_delays, save_persistent_rooms, save_player_detection, save_playlist_snapshot,
+    save_segments,
+};
 pub use settings::{
-    ChatInputPosition, ChatOutputMode, PrivacyMode, PublicServer, ServerConfig, SyncplayConfig,
-    UnpauseAction, UserPreferences,
+    ChatInputPosition, ChatOutputMode, ConnectionPreset, IdleScreen, Language, MetricsConfig,
+    MpvProfile, OperatorsOnlyRoom, OsdCategory, OsdPosition, OsdVerbosity, PauseOnLeave,
+    PlayerConfig, PlaylistAdvanceWait, PlaylistMode, PrivacyMode, PrivacyRule, PublicServer,
+    RecentFile, ReconnectGiveUpAction, ReconnectPolicy, RemoteControlConfig, ScriptsConfig,
+    ServerConfig, SyncMode, SyncplayConfig, TimestampFormat, UnpauseAction, UserPreferences,
+    WatchdogPolicy,
 };
+pub use validation::{validate_config, FieldError};
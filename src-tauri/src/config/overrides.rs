@@ -0,0 +1,225 @@
+// Config override module
+// Startup values from the environment and command line that win over the saved config
+//
+// Overrides only live in memory: when the config is saved, a field that still holds its
+// override keeps the value it had on disk.
+
+use super::SyncplayConfig;
+
+/// Values given on the command line or through `SYNCPLAY_*` variables
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub room: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub player_path: Option<String>,
+}
+
+/// Overrides applied at startup, with the config as loaded to restore on save
+#[derive(Debug, Clone)]
+pub struct AppliedOverrides {
+    overrides: ConfigOverrides,
+    saved: SyncplayConfig,
+}
+
+impl ConfigOverrides {
+    /// Read `SYNCPLAY_HOST`, `SYNCPLAY_ROOM`, ... then let the command line replace them
+    ///
+    /// Flags follow the upstream client: `-a/--host`, `-r/--room`, `-n/--name`,
+    /// `-p/--password` and `--player-path`, as `--flag value` or `--flag=value`.
+    pub fn from_env_and_args(
+        var: impl Fn(&str) -> Option<String>,
+        args: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let mut overrides = Self::default();
+        for (name, value) in [
+            ("SYNCPLAY_HOST", var("SYNCPLAY_HOST")),
+            ("SYNCPLAY_PORT", var("SYNCPLAY_PORT")),
+            ("SYNCPLAY_ROOM", var("SYNCPLAY_ROOM")),
+            ("SYNCPLAY_NAME", var("SYNCPLAY_NAME")),
+            ("SYNCPLAY_PASSWORD", var("SYNCPLAY_PASSWORD")),
+            ("SYNCPLAY_PLAYER_PATH", var("SYNCPLAY_PLAYER_PATH")),
+        ] {
+            if let Some(value) = value {
+                overrides.set(name, value);
+            }
+        }
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with('-') => (flag.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            let Some(name) = flag_variable(&flag) else {
+                continue;
+            };
+            let value = match inline {
+                Some(value) => value.to_string(),
+                None => match args.next() {
+                    Some(value) => value,
+                    None => break,
+                },
+            };
+            overrides.set(name, value);
+        }
+        overrides
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            return;
+        }
+        match name {
+            "SYNCPLAY_HOST" => match split_host_port(&value) {
+                Some((host, port)) => {
+                    self.host = Some(host);
+                    self.port = Some(port);
+                }
+                None => self.host = Some(value),
+            },
+            "SYNCPLAY_PORT" => self.port = value.parse().ok().or(self.port),
+            "SYNCPLAY_ROOM" => self.room = Some(value),
+            "SYNCPLAY_NAME" => self.username = Some(value),
+            "SYNCPLAY_PASSWORD" => self.password = Some(value),
+            "SYNCPLAY_PLAYER_PATH" => self.player_path = Some(value),
+            _ => {}
+        }
+    }
+
+    /// Merge the overrides over `config`, remembering what it held before
+    pub fn apply(self, config: &mut SyncplayConfig) -> AppliedOverrides {
+        let saved = config.clone();
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(password) = &self.password {
+            config.server.password = Some(password.clone());
+            config.server.password_hash = None;
+        }
+        if let Some(room) = &self.room {
+            config.user.default_room = room.clone();
+        }
+        if let Some(username) = &self.username {
+            config.user.username = username.clone();
+        }
+        if let Some(player_path) = &self.player_path {
+            config.player.player_path = player_path.clone();
+        }
+        AppliedOverrides {
+            overrides: self,
+            saved,
+        }
+    }
+}
+
+impl AppliedOverrides {
+    /// `config` as it should be written, with untouched overrides swapped for the saved values
+    pub fn persisted(&self, config: &SyncplayConfig) -> SyncplayConfig {
+        let overrides = &self.overrides;
+        let mut persisted = config.clone();
+        if overrides.host.as_ref() == Some(&config.server.host) {
+            persisted.server.host = self.saved.server.host.clone();
+        }
+        if overrides.port == Some(config.server.port) {
+            persisted.server.port = self.saved.server.port;
+        }
+        if overrides.password.is_some() && overrides.password == config.server.password {
+            persisted.server.password = self.saved.server.password.clone();
+            persisted.server.password_hash = self.saved.server.password_hash.clone();
+        }
+        if overrides.room.as_ref() == Some(&config.user.default_room) {
+            persisted.user.default_room = self.saved.user.default_room.clone();
+        }
+        if overrides.username.as_ref() == Some(&config.user.username) {
+            persisted.user.username = self.saved.user.username.clone();
+        }
+        if overrides.player_path.as_ref() == Some(&config.player.player_path) {
+            persisted.player.player_path = self.saved.player.player_path.clone();
+        }
+        persisted
+    }
+}
+
+/// The variable an override flag stands for
+fn flag_variable(flag: &str) -> Option<&'static str> {
+    match flag {
+        "-a" | "--host" => Some("SYNCPLAY_HOST"),
+        "-r" | "--room" => Some("SYNCPLAY_ROOM"),
+        "-n" | "--name" => Some("SYNCPLAY_NAME"),
+        "-p" | "--password" => Some("SYNCPLAY_PASSWORD"),
+        "--player-path" => Some("SYNCPLAY_PLAYER_PATH"),
+        _ => None,
+    }
+}
+
+/// Whether `arg` is an override flag whose value is the next argument
+pub fn takes_value(arg: &str) -> bool {
+    flag_variable(arg).is_some()
+}
+
+fn split_host_port(value: &str) -> Option<(String, u16)> {
+    let (host, port) = value.rsplit_once(':')?;
+    let port = port.parse().ok()?;
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_command_line_wins_over_environment() {
+        let env = |name: &str| match name {
+            "SYNCPLAY_HOST" => Some("env.example:8995".to_string()),
+            "SYNCPLAY_ROOM" => Some("env-room".to_string()),
+            _ => None,
+        };
+        let overrides = ConfigOverrides::from_env_and_args(
+            env,
+            args(&["syncplay", "--room", "movies", "-n=alice", "movie.mkv"]),
+        );
+        assert_eq!(overrides.host.as_deref(), Some("env.example"));
+        assert_eq!(overrides.port, Some(8995));
+        assert_eq!(overrides.room.as_deref(), Some("movies"));
+        assert_eq!(overrides.username.as_deref(), Some("alice"));
+        assert!(overrides.player_path.is_none());
+        assert!(ConfigOverrides::from_env_and_args(|_| None, args(&["--demo"])).is_empty());
+    }
+
+    #[test]
+    fn test_overrides_are_not_persisted() {
+        let mut config = SyncplayConfig::default();
+        config.user.default_room = "saved-room".to_string();
+        let applied = ConfigOverrides::from_env_and_args(
+            |_| None,
+            args(&["--host", "override.example", "--room", "movies"]),
+        )
+        .apply(&mut config);
+        assert_eq!(config.server.host, "override.example");
+        assert_eq!(config.user.default_room, "movies");
+
+        config.user.username = "bob".to_string();
+        let persisted = applied.persisted(&config);
+        assert_eq!(persisted.server.host, "syncplay.pl");
+        assert_eq!(persisted.user.default_room, "saved-room");
+        assert_eq!(persisted.user.username, "bob");
+
+        config.user.default_room = "anime".to_string();
+        assert_eq!(applied.persisted(&config).user.default_room, "anime");
+    }
+}
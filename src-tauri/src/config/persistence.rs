@@ -2,13 +2,27 @@
 // Configuration storage via tauri-plugin-store
 
 use super::settings::SyncplayConfig;
+use crate::app_state::AppState;
+use crate::client::bookmarks::Bookmark;
+use crate::client::delays::MediaDelays;
+use crate::client::persistent_rooms::PersistentRoom;
+use crate::client::playlist::PlaylistSnapshot;
+use crate::client::segments::FileSegments;
+use crate::player::detection::PlayerDetectionCache;
 use anyhow::{Context, Result};
 use std::path::PathBuf;
-use tauri::{AppHandle, Runtime};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_store::{resolve_store_path, StoreBuilder};
 
 const STORE_PATH: &str = "syncplay.store.json";
 const CONFIG_KEY: &str = "config";
+const PLAYLIST_KEY: &str = "playlist";
+const BOOKMARKS_KEY: &str = "bookmarks";
+const SEGMENTS_KEY: &str = "segments";
+const DELAYS_KEY: &str = "delays";
+const PERSISTENT_ROOMS_KEY: &str = "persistent_rooms";
+const PLAYER_DETECTION_KEY: &str = "player_detection";
 
 /// Get the configuration store path
 pub fn get_config_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
@@ -22,7 +36,13 @@ pub fn load_config<R: Runtime>(app: &AppHandle<R>) -> Result<SyncplayConfig> {
         .context("Failed to open config store")?;
 
     if let Some(value) = store.get(CONFIG_KEY) {
-        if let Ok(config) = serde_json::from_value::<SyncplayConfig>(value) {
+        if let Ok(mut config) = serde_json::from_value::<SyncplayConfig>(value) {
+            if config.user.migrate_legacy_osd_flags() {
+                tracing::info!("Migrated legacy OSD flags to verbosity levels");
+                let value = serde_json::to_value(&config).context("Failed to serialize config")?;
+                store.set(CONFIG_KEY.to_string(), value);
+                store.save().context("Failed to save config store")?;
+            }
             return Ok(config);
         }
         tracing::warn!("Failed to deserialize config, resetting to defaults");
@@ -41,12 +61,214 @@ pub fn save_config<R: Runtime>(app: &AppHandle<R>, config: &SyncplayConfig) -> R
         .build()
         .context("Failed to open config store")?;
 
-    let value = serde_json::to_value(config).context("Failed to serialize config")?;
+    // Startup overrides stay out of the saved config
+    let mut config = match app.try_state::<Arc<AppState>>() {
+        Some(state) => match state.config_overrides.lock().as_ref() {
+            Some(overrides) => overrides.persisted(config),
+            None => config.clone(),
+        },
+        None => config.clone(),
+    };
+    config.secure_passwords();
+    let value = serde_json::to_value(&config).context("Failed to serialize config")?;
     store.set(CONFIG_KEY.to_string(), value);
     store.save().context("Failed to save config store")?;
     Ok(())
 }
 
+/// Load the playlist persisted by the previous session
+pub fn load_playlist_snapshot<R: Runtime>(app: &AppHandle<R>) -> Result<Option<PlaylistSnapshot>> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(PLAYLIST_KEY) else {
+        return Ok(None);
+    };
+    match serde_json::from_value::<PlaylistSnapshot>(value) {
+        Ok(snapshot) => Ok(Some(snapshot)),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved playlist: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Save the playlist so it can be restored after a restart
+pub fn save_playlist_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    snapshot: &PlaylistSnapshot,
+) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let value = serde_json::to_value(snapshot).context("Failed to serialize playlist")?;
+    store.set(PLAYLIST_KEY.to_string(), value);
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
+/// Load the saved playback bookmarks
+pub fn load_bookmarks<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<Bookmark>> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(BOOKMARKS_KEY) else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_value::<Vec<Bookmark>>(value) {
+        Ok(bookmarks) => Ok(bookmarks),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved bookmarks: {}", e);
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Save the playback bookmarks
+pub fn save_bookmarks<R: Runtime>(app: &AppHandle<R>, bookmarks: &[Bookmark]) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let value = serde_json::to_value(bookmarks).context("Failed to serialize bookmarks")?;
+    store.set(BOOKMARKS_KEY.to_string(), value);
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
+/// Load the saved intro and outro segments
+pub fn load_segments<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<FileSegments>> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(SEGMENTS_KEY) else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_value::<Vec<FileSegments>>(value) {
+        Ok(segments) => Ok(segments),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved segments: {}", e);
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Save the intro and outro segments
+pub fn save_segments<R: Runtime>(app: &AppHandle<R>, segments: &[FileSegments]) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let value = serde_json::to_value(segments).context("Failed to serialize segments")?;
+    store.set(SEGMENTS_KEY.to_string(), value);
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
+/// Load the saved audio and subtitle offsets
+pub fn load_delays<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<MediaDelays>> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(DELAYS_KEY) else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_value::<Vec<MediaDelays>>(value) {
+        Ok(delays) => Ok(delays),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved delays: {}", e);
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Save the audio and subtitle offsets
+pub fn save_delays<R: Runtime>(app: &AppHandle<R>, delays: &[MediaDelays]) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let value = serde_json::to_value(delays).context("Failed to serialize delays")?;
+    store.set(DELAYS_KEY.to_string(), value);
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
+/// Load the rooms marked persistent and their remembered playlists
+pub fn load_persistent_rooms<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<PersistentRoom>> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(PERSISTENT_ROOMS_KEY) else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_value::<Vec<PersistentRoom>>(value) {
+        Ok(rooms) => Ok(rooms),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved persistent rooms: {}", e);
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Save the rooms marked persistent and their remembered playlists
+pub fn save_persistent_rooms<R: Runtime>(
+    app: &AppHandle<R>,
+    rooms: &[PersistentRoom],
+) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let value = serde_json::to_value(rooms).context("Failed to serialize persistent rooms")?;
+    store.set(PERSISTENT_ROOMS_KEY.to_string(), value);
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
+/// Load the players found by the last detection run
+pub fn load_player_detection<R: Runtime>(app: &AppHandle<R>) -> Result<PlayerDetectionCache> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    let Some(value) = store.get(PLAYER_DETECTION_KEY) else {
+        return Ok(PlayerDetectionCache::default());
+    };
+    match serde_json::from_value::<PlayerDetectionCache>(value) {
+        Ok(cache) => Ok(cache),
+        Err(e) => {
+            tracing::warn!("Failed to deserialize saved player detection: {}", e);
+            Ok(PlayerDetectionCache::default())
+        }
+    }
+}
+
+/// Save the detected players; an empty cache removes the saved entry
+pub fn save_player_detection<R: Runtime>(
+    app: &AppHandle<R>,
+    cache: &PlayerDetectionCache,
+) -> Result<()> {
+    let store = StoreBuilder::new(app, STORE_PATH)
+        .build()
+        .context("Failed to open config store")?;
+
+    if cache.updated_at.is_none() {
+        store.delete(PLAYER_DETECTION_KEY);
+    } else {
+        let value = serde_json::to_value(cache).context("Failed to serialize player detection")?;
+        store.set(PLAYER_DETECTION_KEY.to_string(), value);
+    }
+    store.save().context("Failed to save config store")?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,6 +304,23 @@ mod tests {
         assert_eq!(loaded.user.username, "testuser");
     }
 
+    #[test]
+    fn test_save_and_load_playlist_snapshot() {
+        let app = build_test_app();
+        clear_store(&app);
+        assert!(load_playlist_snapshot(app.handle()).unwrap().is_none());
+
+        let snapshot = PlaylistSnapshot {
+            room: Some("movies".to_string()),
+            items: vec!["a.mkv".to_string(), "b.mkv".to_string()],
+            current_index: Some(1),
+        };
+        save_playlist_snapshot(app.handle(), &snapshot).unwrap();
+
+        let loaded = load_playlist_snapshot(app.handle()).unwrap();
+        assert_eq!(loaded, Some(snapshot));
+    }
+
     #[test]
     fn test_config_path() {
         let app = build_test_app();
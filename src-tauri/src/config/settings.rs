@@ -3,6 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::IpAddr;
+
+use crate::config::validation::FieldError;
+use crate::utils::{hash_filename, hash_server_password, parse_controlled_room_input};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -12,6 +16,32 @@ pub enum PrivacyMode {
     DoNotSend,
 }
 
+/// Privacy override for matching rooms and files
+///
+/// Patterns match case-insensitively, `*` standing for any run of characters; an
+/// empty pattern matches everything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct PrivacyRule {
+    pub room: String,
+    pub file: String,
+    /// Only apply on the servers listed as public
+    pub public_servers_only: bool,
+    /// Mode replacing `filename_privacy_mode`; `None` leaves it to later rules
+    pub filename_mode: Option<PrivacyMode>,
+    pub filesize_mode: Option<PrivacyMode>,
+}
+
+/// Normal room where only the listed users may pause and seek
+///
+/// Everyone else's pauses and seeks are taken back and suggested to the operators.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct OperatorsOnlyRoom {
+    pub room: String,
+    pub operators: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum UnpauseAction {
@@ -46,12 +76,379 @@ pub enum TransparencyMode {
     High,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistMode {
+    #[default]
+    Normal,
+    RepeatOne,
+    RepeatAll,
+}
+
+/// Who has to reach the end of a file before the playlist moves on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistAdvanceWait {
+    /// Advance as soon as this client reaches the end
+    #[default]
+    Off,
+    ReadyUsers,
+    Controllers,
+}
+
+/// What the player shows while no file is open
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleScreen {
+    /// Room, server and a waiting notice over a generated background; mpv only
+    #[default]
+    Generated,
+    /// The bundled placeholder image
+    Image,
+}
+
+/// Which departures from the room pause playback
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseOnLeave {
+    #[default]
+    Never,
+    Anyone,
+    ControllersOnly,
+    /// Once fewer than `pause_on_leave_min_users` people are left
+    BelowMinUsers,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Correct drift by seeking and slowing down
+    #[default]
+    Seek,
+    /// Correct small drift by nudging playback speed
+    Smooth,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconnectGiveUpAction {
+    /// Only report the failure
+    #[default]
+    Notify,
+    /// Pause the local player
+    PausePlayer,
+    /// Stop the local player
+    StopPlayer,
+}
+
+/// Clock style for chat timestamps
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    #[default]
+    Hours24,
+    Hours12,
+}
+
+/// Language of client-generated messages
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "zh_CN")]
+    ChineseSimplified,
+}
+
+/// Screen anchor for OSD messages
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OsdPosition {
+    TopLeft,
+    Top,
+    #[default]
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// How much the player OSD shows, from nothing up to every room event
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OsdVerbosity {
+    Off,
+    Errors,
+    #[default]
+    Important,
+    Everything,
+}
+
+/// Kinds of OSD messages, each shown from a verbosity level upwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdCategory {
+    Error,
+    /// Room warnings such as being alone or having a different file
+    Warning,
+    /// Changes to your own state, like readiness
+    Status,
+    /// Events from users in your room
+    SameRoom,
+    /// Slowdown and speed reset notices
+    Slowdown,
+    /// Events from users in other rooms
+    DifferentRoom,
+    /// Events from users who are not operators
+    NonController,
+}
+
+impl OsdCategory {
+    pub fn min_verbosity(self) -> OsdVerbosity {
+        match self {
+            OsdCategory::Error => OsdVerbosity::Errors,
+            OsdCategory::Warning
+            | OsdCategory::Status
+            | OsdCategory::SameRoom
+            | OsdCategory::Slowdown => OsdVerbosity::Important,
+            OsdCategory::DifferentRoom | OsdCategory::NonController => OsdVerbosity::Everything,
+        }
+    }
+}
+
+/// Per-category exceptions to the OSD verbosity level
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct OsdOverrides {
+    pub errors: Option<bool>,
+    pub warnings: Option<bool>,
+    pub status: Option<bool>,
+    pub same_room: Option<bool>,
+    pub slowdown: Option<bool>,
+    pub different_room: Option<bool>,
+    pub non_controller: Option<bool>,
+}
+
+impl OsdOverrides {
+    pub fn get(&self, category: OsdCategory) -> Option<bool> {
+        match category {
+            OsdCategory::Error => self.errors,
+            OsdCategory::Warning => self.warnings,
+            OsdCategory::Status => self.status,
+            OsdCategory::SameRoom => self.same_room,
+            OsdCategory::Slowdown => self.slowdown,
+            OsdCategory::DifferentRoom => self.different_room,
+            OsdCategory::NonController => self.non_controller,
+        }
+    }
+
+    pub fn get_mut(&mut self, category: OsdCategory) -> &mut Option<bool> {
+        match category {
+            OsdCategory::Error => &mut self.errors,
+            OsdCategory::Warning => &mut self.warnings,
+            OsdCategory::Status => &mut self.status,
+            OsdCategory::SameRoom => &mut self.same_room,
+            OsdCategory::Slowdown => &mut self.slowdown,
+            OsdCategory::DifferentRoom => &mut self.different_room,
+            OsdCategory::NonController => &mut self.non_controller,
+        }
+    }
+}
+
+/// Reconnect behaviour after losing the server connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectPolicy {
+    pub max_retries: u32,
+    pub base_delay_seconds: f64,
+    pub max_delay_seconds: f64,
+    /// Random extra delay as a fraction of the computed delay
+    pub jitter: f64,
+    pub give_up_action: ReconnectGiveUpAction,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 999,
+            base_delay_seconds: 0.1,
+            max_delay_seconds: 3.2,
+            jitter: 0.0,
+            give_up_action: ReconnectGiveUpAction::Notify,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the given zero-based retry, without jitter
+    pub fn delay_for_attempt(&self, attempt: u32) -> f64 {
+        let exponent = attempt.min(16) as i32;
+        (self.base_delay_seconds * 2_f64.powi(exponent)).min(self.max_delay_seconds)
+    }
+}
+
+/// Sync-friendly mpv defaults, written to a profile the player includes at startup
+///
+/// Each toggle can be turned off when the user's own mpv.conf sets that option differently.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MpvProfile {
+    /// Pass the profile at all; off leaves these options to mpv.conf
+    pub enabled: bool,
+    /// Precise seeks, so a seek lands where the room is
+    pub hr_seek: bool,
+    /// Stay paused on the last frame instead of closing at the end of a file
+    pub keep_open: bool,
+    /// Read ahead of the playback position to ride out slow sources
+    pub cache: bool,
+    pub cache_secs: u32,
+    /// Seconds a stream buffers ahead before playback resumes after stalling
+    pub cache_pause_wait: f64,
+}
+
+impl Default for MpvProfile {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hr_seek: true,
+            keep_open: true,
+            cache: false,
+            cache_secs: 30,
+            cache_pause_wait: 1.0,
+        }
+    }
+}
+
+/// How the player health watchdog escalates when the player stops answering
+///
+/// Each step fires once the player has been unresponsive for its number of seconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WatchdogPolicy {
+    pub enabled: bool,
+    /// Tell the user the player is not responding
+    pub warn_after_secs: u64,
+    /// Stop waiting for pushed events and poll the player instead
+    pub poll_fallback_after_secs: u64,
+    /// Restart the player; off stops it and asks the user to restart
+    pub restart: bool,
+    pub restart_after_secs: u64,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            warn_after_secs: 5,
+            poll_fallback_after_secs: 15,
+            restart: true,
+            restart_after_secs: 60,
+        }
+    }
+}
+
+/// Localhost WebSocket API for controlling the session from other programs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RemoteControlConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Secret every remote client must present
+    pub token: String,
+}
+
+impl Default for RemoteControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8996,
+            token: String::new(),
+        }
+    }
+}
+
+/// Opt-in counters and gauges for graphing sync health
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    /// Port of the Prometheus endpoint on localhost
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9464,
+        }
+    }
+}
+
+/// Lua scripts that react to client events
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ScriptsConfig {
+    pub enabled: bool,
+    /// Directory holding the `*.lua` files; `scripts` in the config directory when unset
+    pub directory: Option<String>,
+}
+
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub password: Option<String>,
+    /// MD5 of the password, the form sent in Hello; kept when the plaintext is not
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+    /// Local address to connect from, picking the interface Syncplay traffic uses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    /// Username last used on this server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Room last joined on this server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    /// Pinned servers stay on top of the recent list and are never dropped from it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+impl ServerConfig {
+    /// The password as sent in Hello, from the plaintext when there is one
+    pub fn hello_password(&self) -> Option<String> {
+        self.password
+            .as_deref()
+            .filter(|password| !password.is_empty())
+            .map(hash_server_password)
+            .or_else(|| self.password_hash.clone())
+    }
+
+    /// Replace the plaintext password with its hash unless asked to keep it
+    pub fn secure_password(&mut self, keep_plaintext: bool) {
+        if self.password.as_deref().is_some_and(|p| !p.is_empty()) {
+            self.password_hash = self.hello_password();
+        }
+        if !keep_plaintext || self.password.as_deref() == Some("") {
+            self.password = None;
+        }
+    }
+
+    /// The parsed bind address, `None` when unset or blank
+    pub fn bind_ip(&self) -> Option<IpAddr> {
+        self.bind_address
+            .as_deref()
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .and_then(|address| address.parse().ok())
+    }
 }
 
 impl Default for ServerConfig {
@@ -60,6 +457,11 @@ impl Default for ServerConfig {
             host: "syncplay.pl".to_string(),
             port: 8999,
             password: None,
+            password_hash: None,
+            bind_address: None,
+            username: None,
+            room: None,
+            pinned: false,
         }
     }
 }
@@ -70,6 +472,9 @@ pub struct UserPreferences {
     pub username: String,
     pub default_room: String,
     pub room_list: Vec<String>,
+    /// Passwords for rooms that need one to join, keyed by room name
+    #[serde(default)]
+    pub room_passwords: HashMap<String, String>,
     pub theme: String,
     #[serde(default)]
     pub transparency_mode: TransparencyMode,
@@ -80,6 +485,41 @@ pub struct UserPreferences {
     pub slowdown_threshold: f64,
     pub slowdown_reset_threshold: f64,
     pub slowdown_rate: f64,
+    #[serde(default = "default_fastforward_behind_threshold")]
+    pub fastforward_behind_threshold: f64,
+    #[serde(default = "default_fastforward_extra_time")]
+    pub fastforward_extra_time: f64,
+    #[serde(default = "default_fastforward_reset_threshold")]
+    pub fastforward_reset_threshold: f64,
+    #[serde(default)]
+    pub sync_mode: SyncMode,
+    #[serde(default = "default_smooth_sync_threshold")]
+    pub smooth_sync_threshold: f64,
+    #[serde(default = "default_smooth_sync_rate")]
+    pub smooth_sync_rate: f64,
+    /// Follow slowdowns with small seeks back on players without rate control (MPC)
+    #[serde(default = "default_slowdown_emulation")]
+    pub slowdown_emulation: bool,
+    /// Largest step back, in seconds, a single emulated slowdown seek takes
+    #[serde(default = "default_slowdown_micro_seek_step")]
+    pub slowdown_micro_seek_step: f64,
+    /// Ask before a seek of ours further than `large_seek_threshold` seconds moves the room
+    #[serde(default)]
+    pub confirm_large_seeks: bool,
+    #[serde(default = "default_large_seek_threshold")]
+    pub large_seek_threshold: f64,
+    /// Normal rooms set up for operators-only pause and seek, announced on joining
+    #[serde(default)]
+    pub operators_only_rooms: Vec<OperatorsOnlyRoom>,
+    /// Count "!vote" chat messages and carry out the ones that pass
+    #[serde(default = "default_room_votes")]
+    pub room_votes: bool,
+    /// Share of the room, in percent, a vote must exceed to pass
+    #[serde(default = "default_vote_majority_percent")]
+    pub vote_majority_percent: u8,
+    /// Seconds a vote stays open
+    #[serde(default = "default_vote_timeout_secs")]
+    pub vote_timeout_secs: u64,
     pub slow_on_desync: bool,
     pub rewind_on_desync: bool,
     pub fastforward_on_desync: bool,
@@ -88,28 +528,84 @@ pub struct UserPreferences {
     // Ready & autoplay
     pub ready_at_start: bool,
     pub pause_on_leave: bool,
+    #[serde(default)]
+    pub pause_on_leave_policy: PauseOnLeave,
+    #[serde(default = "default_pause_on_leave_min_users")]
+    pub pause_on_leave_min_users: usize,
+    #[serde(default)]
+    pub pause_on_focus_loss: bool,
     pub unpause_action: UnpauseAction,
     pub autoplay_enabled: bool,
     pub autoplay_min_users: i32,
     pub autoplay_require_same_filenames: bool,
+    #[serde(default)]
+    pub unpause_countdown_enabled: bool,
+    #[serde(default = "default_unpause_countdown_seconds")]
+    pub unpause_countdown_seconds: i32,
+    #[serde(default)]
+    pub auto_ready_on_matching_file: bool,
+    #[serde(default)]
+    pub auto_ready_require_stable_size: bool,
+    #[serde(default = "default_auto_ready_stable_seconds")]
+    pub auto_ready_stable_seconds: u64,
+    /// Offer to skip when playback enters a known intro or outro
+    #[serde(default = "default_segment_skip_prompt")]
+    pub segment_skip_prompt: bool,
 
     // Privacy
     pub filename_privacy_mode: PrivacyMode,
     pub filesize_privacy_mode: PrivacyMode,
+    /// Per-room and per-file exceptions, checked in order
+    #[serde(default)]
+    pub privacy_rules: Vec<PrivacyRule>,
+    /// Append a summary of each session, counts only, to the history file
+    #[serde(default)]
+    pub session_history: bool,
+    /// Tell the server our UTC offset so others can see our local time
+    #[serde(default)]
+    pub share_utc_offset: bool,
 
     // Trusted domains
     pub only_switch_to_trusted_domains: bool,
     pub trusted_domains: Vec<String>,
 
+    // Users whose chat and OSD notifications are hidden
+    #[serde(default)]
+    pub ignored_users: Vec<String>,
+    /// Server MOTD fingerprints the user chose not to see again, keyed by host:port
+    #[serde(default)]
+    pub dismissed_motds: HashMap<String, String>,
+    /// Filename hashes per user whose file differences are not reported
+    #[serde(default)]
+    pub trusted_files: HashMap<String, Vec<String>>,
+
     // OSD settings
-    pub show_osd: bool,
+    #[serde(default)]
+    pub osd_verbosity: OsdVerbosity,
+    #[serde(default)]
+    pub osd_overrides: OsdOverrides,
     pub osd_duration: u64,
-    pub show_osd_warnings: bool,
-    pub show_slowdown_osd: bool,
-    pub show_different_room_osd: bool,
-    pub show_same_room_osd: bool,
-    pub show_non_controller_osd: bool,
+    #[serde(default)]
+    pub osd_position: OsdPosition,
+    /// OSD text color as `#RRGGBB`; the player default is used when unset
+    #[serde(default)]
+    pub osd_color: Option<String>,
+    #[serde(default = "default_osd_font_scale")]
+    pub osd_font_scale: f64,
     pub show_duration_notification: bool,
+    // Flags replaced by `osd_verbosity`, only read to migrate older configs
+    #[serde(default, skip_serializing)]
+    show_osd: Option<bool>,
+    #[serde(default, skip_serializing)]
+    show_osd_warnings: Option<bool>,
+    #[serde(default, skip_serializing)]
+    show_slowdown_osd: Option<bool>,
+    #[serde(default, skip_serializing)]
+    show_different_room_osd: Option<bool>,
+    #[serde(default, skip_serializing)]
+    show_same_room_osd: Option<bool>,
+    #[serde(default, skip_serializing)]
+    show_non_controller_osd: Option<bool>,
 
     // Chat settings
     pub chat_input_enabled: bool,
@@ -135,12 +631,35 @@ pub struct UserPreferences {
     pub notification_timeout: u32,
     pub alert_timeout: u32,
     pub chat_timeout: u32,
+    /// Flash the taskbar when someone mentions you in chat
+    #[serde(default = "default_mention_flash_taskbar")]
+    pub mention_flash_taskbar: bool,
+    /// Play a sound when someone mentions you in chat
+    #[serde(default)]
+    pub mention_sound: bool,
 
     // UI settings
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub chat_timestamp_format: TimestampFormat,
+    /// Show the hour field for positions under an hour
+    #[serde(default)]
+    pub always_show_hours: bool,
+    /// Minimum spacing between high-frequency UI updates (player state, ping)
+    #[serde(default = "default_ui_event_interval_ms")]
+    pub ui_event_interval_ms: u64,
     pub autosave_joins_to_list: bool,
+    /// Save server passwords in plain text instead of only their hash
+    #[serde(default)]
+    pub remember_plaintext_passwords: bool,
     pub shared_playlist_enabled: bool,
     pub loop_at_end_of_playlist: bool,
     pub loop_single_files: bool,
+    #[serde(default)]
+    pub playlist_mode: PlaylistMode,
+    #[serde(default)]
+    pub playlist_advance_wait: PlaylistAdvanceWait,
     pub show_playlist: bool,
     #[serde(default = "default_side_panel_layout")]
     pub side_panel_layout: String,
@@ -153,6 +672,9 @@ pub struct UserPreferences {
     #[serde(default)]
     pub window_height: Option<u32>,
     pub auto_connect: bool,
+    /// Connect to the saved server and room when the app starts
+    #[serde(default)]
+    pub auto_connect_on_launch: bool,
     pub force_gui_prompt: bool,
     pub check_for_updates_automatically: Option<bool>,
     pub debug: bool,
@@ -168,6 +690,33 @@ pub struct PlayerConfig {
     pub player_arguments: Vec<String>,
     #[serde(default)]
     pub per_player_arguments: HashMap<String, Vec<String>>,
+    /// Relaunch the player when it exits unexpectedly during a session
+    #[serde(default = "default_auto_restart")]
+    pub auto_restart: bool,
+    /// Seconds to wait before relaunching a crashed player
+    #[serde(default = "default_restart_grace_seconds")]
+    pub restart_grace_seconds: u64,
+    /// Keep a player we launched open after the app quits
+    #[serde(default)]
+    pub leave_player_running: bool,
+    #[serde(default)]
+    pub mpv_profile: MpvProfile,
+    #[serde(default)]
+    pub watchdog: WatchdogPolicy,
+    #[serde(default)]
+    pub idle_screen: IdleScreen,
+    /// Pause and mark not ready while a streamed file rebuffers, so the room waits
+    #[serde(default = "default_wait_while_buffering")]
+    pub wait_while_buffering: bool,
+    /// Mark not ready when playback is unpaused but stops moving
+    #[serde(default = "default_stall_detection")]
+    pub stall_detection: bool,
+    /// Seconds the position may stand still before playback counts as stalled
+    #[serde(default = "default_stall_timeout_secs")]
+    pub stall_timeout_secs: u64,
+    /// Let Flatpak players read the whole host filesystem, not just the media directories
+    #[serde(default)]
+    pub flatpak_host_filesystem: bool,
 }
 
 impl Default for PlayerConfig {
@@ -177,6 +726,16 @@ impl Default for PlayerConfig {
             media_directories: Vec::new(),
             player_arguments: Vec::new(),
             per_player_arguments: HashMap::new(),
+            auto_restart: default_auto_restart(),
+            restart_grace_seconds: default_restart_grace_seconds(),
+            leave_player_running: false,
+            mpv_profile: MpvProfile::default(),
+            watchdog: WatchdogPolicy::default(),
+            idle_screen: IdleScreen::default(),
+            wait_while_buffering: default_wait_while_buffering(),
+            stall_detection: default_stall_detection(),
+            stall_timeout_secs: default_stall_timeout_secs(),
+            flatpak_host_filesystem: false,
         }
     }
 }
@@ -187,6 +746,7 @@ impl Default for UserPreferences {
             username: String::new(),
             default_room: "default".to_string(),
             room_list: Vec::new(),
+            room_passwords: HashMap::new(),
             theme: "dark".to_string(),
             transparency_mode: TransparencyMode::Off,
 
@@ -196,6 +756,20 @@ impl Default for UserPreferences {
             slowdown_threshold: 1.5,
             slowdown_reset_threshold: 0.1,
             slowdown_rate: 0.95,
+            fastforward_behind_threshold: default_fastforward_behind_threshold(),
+            fastforward_extra_time: default_fastforward_extra_time(),
+            fastforward_reset_threshold: default_fastforward_reset_threshold(),
+            sync_mode: SyncMode::Seek,
+            smooth_sync_threshold: default_smooth_sync_threshold(),
+            smooth_sync_rate: default_smooth_sync_rate(),
+            slowdown_emulation: default_slowdown_emulation(),
+            slowdown_micro_seek_step: default_slowdown_micro_seek_step(),
+            confirm_large_seeks: false,
+            large_seek_threshold: default_large_seek_threshold(),
+            operators_only_rooms: Vec::new(),
+            room_votes: default_room_votes(),
+            vote_majority_percent: default_vote_majority_percent(),
+            vote_timeout_secs: default_vote_timeout_secs(),
             slow_on_desync: true,
             rewind_on_desync: true,
             fastforward_on_desync: true,
@@ -204,28 +778,48 @@ impl Default for UserPreferences {
             // Ready & autoplay defaults
             ready_at_start: false,
             pause_on_leave: false,
+            pause_on_leave_policy: PauseOnLeave::Never,
+            pause_on_leave_min_users: default_pause_on_leave_min_users(),
+            pause_on_focus_loss: false,
             unpause_action: UnpauseAction::IfOthersReady,
             autoplay_enabled: false,
             autoplay_min_users: -1,
             autoplay_require_same_filenames: true,
+            unpause_countdown_enabled: false,
+            unpause_countdown_seconds: default_unpause_countdown_seconds(),
+            auto_ready_on_matching_file: false,
+            auto_ready_require_stable_size: false,
+            auto_ready_stable_seconds: default_auto_ready_stable_seconds(),
+            segment_skip_prompt: true,
 
             // Privacy defaults
             filename_privacy_mode: PrivacyMode::SendRaw,
             filesize_privacy_mode: PrivacyMode::SendRaw,
+            privacy_rules: Vec::new(),
+            session_history: false,
+            share_utc_offset: false,
 
             // Trusted domains defaults
             only_switch_to_trusted_domains: true,
             trusted_domains: vec!["youtube.com".to_string(), "youtu.be".to_string()],
+            ignored_users: Vec::new(),
+            dismissed_motds: HashMap::new(),
+            trusted_files: HashMap::new(),
 
             // OSD defaults
-            show_osd: true,
+            osd_verbosity: OsdVerbosity::Important,
+            osd_overrides: OsdOverrides::default(),
             osd_duration: 3000,
-            show_osd_warnings: true,
-            show_slowdown_osd: true,
-            show_different_room_osd: false,
-            show_same_room_osd: true,
-            show_non_controller_osd: false,
+            osd_position: OsdPosition::TopRight,
+            osd_color: None,
+            osd_font_scale: default_osd_font_scale(),
             show_duration_notification: true,
+            show_osd: None,
+            show_osd_warnings: None,
+            show_slowdown_osd: None,
+            show_different_room_osd: None,
+            show_same_room_osd: None,
+            show_non_controller_osd: None,
 
             // Chat defaults
             chat_input_enabled: true,
@@ -251,12 +845,21 @@ impl Default for UserPreferences {
             notification_timeout: 3,
             alert_timeout: 5,
             chat_timeout: 7,
+            mention_flash_taskbar: true,
+            mention_sound: false,
 
             // UI defaults
+            language: Language::English,
+            chat_timestamp_format: TimestampFormat::Hours24,
+            always_show_hours: false,
+            ui_event_interval_ms: default_ui_event_interval_ms(),
             autosave_joins_to_list: true,
+            remember_plaintext_passwords: false,
             shared_playlist_enabled: true,
             loop_at_end_of_playlist: false,
             loop_single_files: false,
+            playlist_mode: PlaylistMode::Normal,
+            playlist_advance_wait: PlaylistAdvanceWait::Off,
             show_playlist: true,
             side_panel_layout: default_side_panel_layout(),
             side_column_width: None,
@@ -264,6 +867,7 @@ impl Default for UserPreferences {
             window_width: None,
             window_height: None,
             auto_connect: false,
+            auto_connect_on_launch: false,
             force_gui_prompt: true,
             check_for_updates_automatically: None,
             debug: false,
@@ -271,16 +875,290 @@ impl Default for UserPreferences {
     }
 }
 
+impl UserPreferences {
+    /// Playlist mode taking the legacy loop flag into account
+    pub fn effective_playlist_mode(&self) -> PlaylistMode {
+        match self.playlist_mode {
+            PlaylistMode::Normal if self.loop_at_end_of_playlist => PlaylistMode::RepeatAll,
+            ref mode => mode.clone(),
+        }
+    }
+
+    /// Whether startup connects by itself, taking the legacy auto-connect flag into account
+    ///
+    /// Asking for the connection dialog on startup wins over both.
+    pub fn connects_on_launch(&self) -> bool {
+        (self.auto_connect_on_launch || self.auto_connect) && !self.force_gui_prompt
+    }
+
+    /// Pause-on-leave policy taking the legacy on/off flag into account
+    pub fn effective_pause_on_leave(&self) -> PauseOnLeave {
+        match self.pause_on_leave_policy {
+            PauseOnLeave::Never if self.pause_on_leave => PauseOnLeave::Anyone,
+            policy => policy,
+        }
+    }
+
+    /// Whether someone leaving the room should pause playback
+    ///
+    /// `remaining_users` counts everyone still in the room, including us.
+    pub fn pauses_on_leave(&self, controller_left: bool, remaining_users: usize) -> bool {
+        match self.effective_pause_on_leave() {
+            PauseOnLeave::Never => false,
+            PauseOnLeave::Anyone => true,
+            PauseOnLeave::ControllersOnly => controller_left,
+            PauseOnLeave::BelowMinUsers => remaining_users < self.pause_on_leave_min_users,
+        }
+    }
+
+    /// Whether OSD messages of this category should be shown
+    pub fn osd_allows(&self, category: OsdCategory) -> bool {
+        self.osd_overrides
+            .get(category)
+            .unwrap_or(self.osd_verbosity >= category.min_verbosity())
+    }
+
+    /// Convert the boolean `show_*_osd` flags of older configs into a verbosity level
+    ///
+    /// Returns whether anything changed, so the caller knows to save.
+    pub fn migrate_legacy_osd_flags(&mut self) -> bool {
+        let legacy = [
+            (self.show_osd_warnings.take(), OsdCategory::Warning),
+            (self.show_slowdown_osd.take(), OsdCategory::Slowdown),
+            (self.show_same_room_osd.take(), OsdCategory::SameRoom),
+            (
+                self.show_different_room_osd.take(),
+                OsdCategory::DifferentRoom,
+            ),
+            (
+                self.show_non_controller_osd.take(),
+                OsdCategory::NonController,
+            ),
+        ];
+        let show_osd = self.show_osd.take();
+        if show_osd.is_none() && legacy.iter().all(|(flag, _)| flag.is_none()) {
+            return false;
+        }
+        self.osd_overrides = OsdOverrides::default();
+        if show_osd == Some(false) {
+            self.osd_verbosity = OsdVerbosity::Off;
+            return true;
+        }
+        self.osd_verbosity = OsdVerbosity::Important;
+        for (flag, category) in legacy {
+            let Some(enabled) = flag else { continue };
+            if enabled != self.osd_allows(category) {
+                *self.osd_overrides.get_mut(category) = Some(enabled);
+            }
+        }
+        true
+    }
+
+    pub fn is_user_ignored(&self, username: &str) -> bool {
+        self.ignored_users.iter().any(|name| name == username)
+    }
+
+    /// Whether the file differences of `username` playing `filename` were accepted
+    pub fn is_file_trusted(&self, username: &str, filename: &str) -> bool {
+        let hash = hash_filename(filename, true);
+        self.trusted_files
+            .get(username)
+            .is_some_and(|hashes| hashes.contains(&hash))
+    }
+
+    /// Accept the file differences of `username` while they play `filename`
+    pub fn trust_file(&mut self, username: &str, filename: &str) {
+        let hash = hash_filename(filename, true);
+        let hashes = self.trusted_files.entry(username.to_string()).or_default();
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+
+    pub fn operators_only_room(&self, room: &str) -> Option<&OperatorsOnlyRoom> {
+        self.operators_only_rooms
+            .iter()
+            .find(|entry| entry.room == room)
+    }
+
+    pub fn room_password(&self, room: &str) -> Option<String> {
+        self.room_passwords.get(room).cloned()
+    }
+
+    /// Operator passwords saved as `room:password` entries of the room list
+    pub fn saved_operator_passwords(&self) -> Vec<(String, String)> {
+        self.room_list
+            .iter()
+            .filter_map(|entry| match parse_controlled_room_input(entry) {
+                (room, Some(password)) => Some((room, password)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Drop the operator password saved for `room`, keeping the room itself listed
+    pub fn forget_operator_password(&mut self, room: &str) -> bool {
+        let mut changed = false;
+        let mut kept = Vec::with_capacity(self.room_list.len());
+        for entry in self.room_list.drain(..) {
+            let (entry_room, password) = parse_controlled_room_input(&entry);
+            if password.is_some() && entry_room == room {
+                changed = true;
+                if !kept.contains(&entry_room) {
+                    kept.push(entry_room);
+                }
+            } else if !kept.contains(&entry) {
+                kept.push(entry);
+            }
+        }
+        self.room_list = kept;
+        changed
+    }
+
+    /// Whether this exact MOTD was dismissed for the server
+    pub fn is_motd_dismissed(&self, server: &str, fingerprint: &str) -> bool {
+        self.dismissed_motds
+            .get(server)
+            .is_some_and(|dismissed| dismissed == fingerprint)
+    }
+}
+
 fn default_side_panel_layout() -> String {
     "rows".to_string()
 }
 
+fn default_fastforward_behind_threshold() -> f64 {
+    1.75
+}
+
+fn default_fastforward_extra_time() -> f64 {
+    0.25
+}
+
+fn default_fastforward_reset_threshold() -> f64 {
+    3.0
+}
+
+fn default_pause_on_leave_min_users() -> usize {
+    2
+}
+
+fn default_unpause_countdown_seconds() -> i32 {
+    3
+}
+
+fn default_auto_ready_stable_seconds() -> u64 {
+    5
+}
+
+fn default_osd_font_scale() -> f64 {
+    1.0
+}
+
+fn default_mention_flash_taskbar() -> bool {
+    true
+}
+
+fn default_segment_skip_prompt() -> bool {
+    true
+}
+
+fn default_auto_restart() -> bool {
+    true
+}
+
+fn default_wait_while_buffering() -> bool {
+    true
+}
+
+fn default_stall_detection() -> bool {
+    true
+}
+
+fn default_stall_timeout_secs() -> u64 {
+    5
+}
+
+fn default_restart_grace_seconds() -> u64 {
+    3
+}
+
+fn default_ui_event_interval_ms() -> u64 {
+    250
+}
+
+fn default_smooth_sync_threshold() -> f64 {
+    1.5
+}
+
+fn default_smooth_sync_rate() -> f64 {
+    0.03
+}
+
+fn default_slowdown_emulation() -> bool {
+    true
+}
+
+fn default_slowdown_micro_seek_step() -> f64 {
+    0.5
+}
+
+fn default_room_votes() -> bool {
+    true
+}
+
+fn default_vote_majority_percent() -> u8 {
+    50
+}
+
+fn default_vote_timeout_secs() -> u64 {
+    60
+}
+
+fn default_large_seek_threshold() -> f64 {
+    600.0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicServer {
     pub name: String,
     pub address: String,
 }
 
+/// How many unpinned servers are kept in the recent list
+pub const RECENT_SERVERS_LIMIT: usize = 10;
+
+/// How many files are kept in the recently played list
+pub const RECENT_FILES_LIMIT: usize = 20;
+
+/// A file played while connected to a room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFile {
+    /// Name as shown to the room
+    pub name: String,
+    /// Local path or URL it was opened from
+    #[serde(default)]
+    pub path: Option<String>,
+    pub room: String,
+    /// Unix time in seconds
+    pub played_at: u64,
+}
+
+/// A named server, room, username and player to connect with in one go
+///
+/// Passwords are not kept here; the ones saved for the server and room apply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionPreset {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub room: String,
+    /// Player to switch to before connecting; the current one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_path: Option<String>,
+}
+
 /// Complete Syncplay configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncplayConfig {
@@ -289,6 +1167,18 @@ pub struct SyncplayConfig {
     pub player: PlayerConfig,
     pub recent_servers: Vec<ServerConfig>,
     pub public_servers: Vec<PublicServer>,
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    #[serde(default)]
+    pub recent_files: Vec<RecentFile>,
+    #[serde(default)]
+    pub presets: Vec<ConnectionPreset>,
+    #[serde(default)]
+    pub remote: RemoteControlConfig,
+    #[serde(default)]
+    pub scripts: ScriptsConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 impl Default for SyncplayConfig {
@@ -302,7 +1192,7 @@ impl Default for SyncplayConfig {
                 ServerConfig {
                     host: "syncplay.pl".to_string(),
                     port: 8995,
-                    password: None,
+                    ..ServerConfig::default()
                 },
             ],
             public_servers: vec![
@@ -327,6 +1217,12 @@ impl Default for SyncplayConfig {
                     address: "syncplay.pl:8999".to_string(),
                 },
             ],
+            reconnect: ReconnectPolicy::default(),
+            recent_files: Vec::new(),
+            presets: Vec::new(),
+            remote: RemoteControlConfig::default(),
+            scripts: ScriptsConfig::default(),
+            metrics: MetricsConfig::default(),
         }
     }
 }
@@ -339,60 +1235,424 @@ impl SyncplayConfig {
 
     /// Validate the configuration
     pub fn validate(&self) -> Result<(), String> {
+        match self.validation_errors().into_iter().next() {
+            Some(error) => Err(error.message),
+            None => Ok(()),
+        }
+    }
+
+    /// Every range and cross-field rule the configuration breaks
+    pub fn validation_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
         if self.server.host.is_empty() {
-            return Err("Server host cannot be empty".to_string());
+            errors.push(FieldError::new(
+                "server.host",
+                "Server host cannot be empty",
+            ));
         }
 
         if self.server.port == 0 {
-            return Err("Server port must be greater than 0".to_string());
+            errors.push(FieldError::new(
+                "server.port",
+                "Server port must be greater than 0",
+            ));
+        }
+
+        if let Some(address) = self.server.bind_address.as_deref().map(str::trim) {
+            if !address.is_empty() && address.parse::<IpAddr>().is_err() {
+                errors.push(FieldError::new(
+                    "server.bind_address",
+                    format!("Bind address must be an IP address: {}", address),
+                ));
+            }
         }
 
         // Validate thresholds
         if self.user.seek_threshold_rewind <= 0.0 {
-            return Err("Seek threshold rewind must be positive".to_string());
+            errors.push(FieldError::new(
+                "user.seek_threshold_rewind",
+                "Seek threshold rewind must be positive",
+            ));
         }
 
         if self.user.seek_threshold_fastforward <= 0.0 {
-            return Err("Seek threshold fastforward must be positive".to_string());
+            errors.push(FieldError::new(
+                "user.seek_threshold_fastforward",
+                "Seek threshold fastforward must be positive",
+            ));
         }
 
         if self.user.slowdown_threshold <= 0.0 {
-            return Err("Slowdown threshold must be positive".to_string());
+            errors.push(FieldError::new(
+                "user.slowdown_threshold",
+                "Slowdown threshold must be positive",
+            ));
         }
 
         if self.user.slowdown_rate <= 0.0 || self.user.slowdown_rate >= 1.0 {
-            return Err("Slowdown rate must be between 0 and 1".to_string());
+            errors.push(FieldError::new(
+                "user.slowdown_rate",
+                "Slowdown rate must be between 0 and 1",
+            ));
+        }
+
+        if self.user.slowdown_reset_threshold < 0.0
+            || self.user.slowdown_reset_threshold >= self.user.slowdown_threshold
+        {
+            errors.push(FieldError::new(
+                "user.slowdown_reset_threshold",
+                "Slowdown reset threshold must be below the slowdown threshold".to_string(),
+            ));
+        }
+
+        if self.user.fastforward_behind_threshold <= 0.0
+            || self.user.fastforward_behind_threshold > self.user.seek_threshold_fastforward
+        {
+            errors.push(FieldError::new("user.fastforward_behind_threshold", "Fastforward behind threshold must be positive and not exceed the fastforward threshold"
+                    .to_string(),));
+        }
+
+        if !(0.0..=5.0).contains(&self.user.fastforward_extra_time) {
+            errors.push(FieldError::new(
+                "user.fastforward_extra_time",
+                "Fastforward extra time must be between 0 and 5 seconds",
+            ));
+        }
+
+        if !(0.0..=30.0).contains(&self.user.fastforward_reset_threshold) {
+            errors.push(FieldError::new(
+                "user.fastforward_reset_threshold",
+                "Fastforward reset threshold must be between 0 and 30 seconds",
+            ));
+        }
+
+        if self.user.smooth_sync_threshold <= self.user.slowdown_reset_threshold
+            || self.user.smooth_sync_threshold > self.user.seek_threshold_rewind
+        {
+            errors.push(FieldError::new("user.smooth_sync_threshold", "Smooth sync threshold must be above the slowdown reset threshold and not exceed the rewind threshold"
+                    .to_string(),));
+        }
+
+        if !(0.02..=0.05).contains(&self.user.smooth_sync_rate) {
+            errors.push(FieldError::new(
+                "user.smooth_sync_rate",
+                "Smooth sync rate must be between 0.02 and 0.05",
+            ));
+        }
+
+        if !(0.1..=2.0).contains(&self.user.slowdown_micro_seek_step) {
+            errors.push(FieldError::new(
+                "user.slowdown_micro_seek_step",
+                "Emulated slowdown step must be between 0.1 and 2 seconds",
+            ));
+        }
+
+        if self.user.confirm_large_seeks && self.user.large_seek_threshold < 10.0 {
+            errors.push(FieldError::new(
+                "user.large_seek_threshold",
+                "Large seek threshold must be at least 10 seconds",
+            ));
+        }
+
+        if self
+            .user
+            .operators_only_rooms
+            .iter()
+            .any(|entry| entry.room.trim().is_empty() || entry.operators.is_empty())
+        {
+            errors.push(FieldError::new(
+                "user.operators_only_rooms",
+                "Each operators-only room needs a name and at least one operator",
+            ));
+        }
+
+        if !(1..=99).contains(&self.user.vote_majority_percent) {
+            errors.push(FieldError::new(
+                "user.vote_majority_percent",
+                "Vote majority must be between 1 and 99 percent",
+            ));
+        }
+
+        if !(10..=600).contains(&self.user.vote_timeout_secs) {
+            errors.push(FieldError::new(
+                "user.vote_timeout_secs",
+                "Votes must stay open between 10 and 600 seconds",
+            ));
+        }
+
+        if self.player.mpv_profile.cache
+            && !(1..=3600).contains(&self.player.mpv_profile.cache_secs)
+        {
+            errors.push(FieldError::new(
+                "player.mpv_profile.cache_secs",
+                "mpv cache must be between 1 and 3600 seconds",
+            ));
+        }
+
+        if self.player.mpv_profile.cache
+            && !(0.0..=60.0).contains(&self.player.mpv_profile.cache_pause_wait)
+        {
+            errors.push(FieldError::new(
+                "player.mpv_profile.cache_pause_wait",
+                "Buffering before resuming must be between 0 and 60 seconds",
+            ));
+        }
+
+        if self.player.stall_detection && !(2..=120).contains(&self.player.stall_timeout_secs) {
+            errors.push(FieldError::new(
+                "player.stall_timeout_secs",
+                "Stall timeout must be between 2 and 120 seconds",
+            ));
+        }
+
+        if self
+            .user
+            .privacy_rules
+            .iter()
+            .any(|rule| rule.filename_mode.is_none() && rule.filesize_mode.is_none())
+        {
+            errors.push(FieldError::new(
+                "user.privacy_rules",
+                "Each privacy rule needs a filename or file size mode",
+            ));
+        }
+
+        let remote = &self.remote;
+        if remote.enabled && remote.port < 1024 {
+            errors.push(FieldError::new(
+                "remote.port",
+                "Remote control port must be between 1024 and 65535",
+            ));
+        }
+        if remote.enabled && remote.token.len() < 16 {
+            errors.push(FieldError::new(
+                "remote.token",
+                "Remote control token must be at least 16 characters",
+            ));
+        }
+
+        let metrics = &self.metrics;
+        if metrics.enabled && metrics.port < 1024 {
+            errors.push(FieldError::new(
+                "metrics.port",
+                "Metrics port must be between 1024 and 65535",
+            ));
+        } else if metrics.enabled && remote.enabled && metrics.port == remote.port {
+            errors.push(FieldError::new(
+                "metrics.port",
+                "Metrics port must differ from the remote control port",
+            ));
+        }
+
+        let watchdog = &self.player.watchdog;
+        if watchdog.enabled
+            && (watchdog.warn_after_secs == 0
+                || watchdog.poll_fallback_after_secs < watchdog.warn_after_secs
+                || watchdog.restart_after_secs <= watchdog.poll_fallback_after_secs)
+        {
+            errors.push(FieldError::new(
+                "player.watchdog",
+                "Watchdog steps must come in order: warning, polling, then restart",
+            ));
+        }
+
+        if self.reconnect.base_delay_seconds <= 0.0 {
+            errors.push(FieldError::new(
+                "reconnect.base_delay_seconds",
+                "Reconnect base delay must be positive",
+            ));
+        }
+
+        if self.reconnect.max_delay_seconds < self.reconnect.base_delay_seconds {
+            errors.push(FieldError::new(
+                "reconnect.max_delay_seconds",
+                "Reconnect max delay must not be below the base delay",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.reconnect.jitter) {
+            errors.push(FieldError::new(
+                "reconnect.jitter",
+                "Reconnect jitter must be between 0 and 1",
+            ));
         }
 
         if self.user.osd_duration == 0 {
-            return Err("OSD duration must be positive".to_string());
+            errors.push(FieldError::new(
+                "user.osd_duration",
+                "OSD duration must be positive",
+            ));
+        }
+
+        if let Some(color) = self.user.osd_color.as_deref() {
+            if !is_hex_color(color) {
+                errors.push(FieldError::new(
+                    "user.osd_color",
+                    "OSD color must be in #RRGGBB format",
+                ));
+            }
+        }
+
+        if !(0.5..=3.0).contains(&self.user.osd_font_scale) {
+            errors.push(FieldError::new(
+                "user.osd_font_scale",
+                "OSD font scale must be between 0.5 and 3.0",
+            ));
+        }
+
+        if self.user.ui_event_interval_ms > 2000 {
+            errors.push(FieldError::new(
+                "user.ui_event_interval_ms",
+                "UI event interval must be at most 2000 ms",
+            ));
         }
 
         if self.user.chat_max_lines == 0 {
-            return Err("Chat max lines must be positive".to_string());
+            errors.push(FieldError::new(
+                "user.chat_max_lines",
+                "Chat max lines must be positive",
+            ));
+        }
+
+        if !(1..=10).contains(&self.user.unpause_countdown_seconds) {
+            errors.push(FieldError::new(
+                "user.unpause_countdown_seconds",
+                "Unpause countdown must be between 1 and 10 seconds",
+            ));
+        }
+
+        if !(1..=600).contains(&self.user.auto_ready_stable_seconds) {
+            errors.push(FieldError::new(
+                "user.auto_ready_stable_seconds",
+                "Auto-ready stable time must be between 1 and 600 seconds",
+            ));
+        }
+
+        if self.player.restart_grace_seconds > 60 {
+            errors.push(FieldError::new(
+                "player.restart_grace_seconds",
+                "Player restart grace period must be at most 60 seconds",
+            ));
         }
 
         if self.user.autoplay_min_users < -1 {
-            return Err("Autoplay min users must be >= -1".to_string());
+            errors.push(FieldError::new(
+                "user.autoplay_min_users",
+                "Autoplay min users must be >= -1",
+            ));
         }
 
-        Ok(())
+        errors
+    }
+
+    /// Hash every saved server password unless plaintext storage was opted into
+    pub fn secure_passwords(&mut self) {
+        let keep_plaintext = self.user.remember_plaintext_passwords;
+        self.server.secure_password(keep_plaintext);
+        for server in &mut self.recent_servers {
+            server.secure_password(keep_plaintext);
+        }
+    }
+
+    /// Hello password saved for `host:port`
+    pub fn saved_hello_password(&self, host: &str, port: u16) -> Option<String> {
+        std::iter::once(&self.server)
+            .chain(&self.recent_servers)
+            .filter(|server| server.host == host && server.port == port)
+            .find_map(|server| server.hello_password())
     }
 
     /// Add a server to recent servers list
-    pub fn add_recent_server(&mut self, server: ServerConfig) {
-        // Remove duplicates
+    pub fn add_recent_server(&mut self, mut server: ServerConfig) {
+        // Remove duplicates, keeping what the older entry remembered
+        if let Some(index) = self.recent_server_index(&server.host, server.port) {
+            let existing = self.recent_servers.remove(index);
+            server.pinned |= existing.pinned;
+            server.username = server.username.or(existing.username);
+            server.room = server.room.or(existing.room);
+        }
+
+        // Add to front, behind the pinned servers
+        let position = if server.pinned {
+            0
+        } else {
+            self.pinned_server_count()
+        };
+        self.recent_servers.insert(position, server);
+
+        // Keep only last 10, never dropping a pinned server
         self.recent_servers
-            .retain(|s| s.host != server.host || s.port != server.port);
+            .truncate(RECENT_SERVERS_LIMIT.max(self.pinned_server_count()));
+    }
+
+    pub fn remove_recent_server(&mut self, host: &str, port: u16) -> bool {
+        let Some(index) = self.recent_server_index(host, port) else {
+            return false;
+        };
+        self.recent_servers.remove(index);
+        true
+    }
+
+    /// Pin or unpin a recent server, moving it to the end of the pinned group
+    pub fn pin_server(&mut self, host: &str, port: u16, pinned: bool) -> bool {
+        let Some(index) = self.recent_server_index(host, port) else {
+            return false;
+        };
+        let mut server = self.recent_servers.remove(index);
+        server.pinned = pinned;
+        let position = self.pinned_server_count();
+        self.recent_servers.insert(position, server);
+        true
+    }
+
+    fn recent_server_index(&self, host: &str, port: u16) -> Option<usize> {
+        self.recent_servers
+            .iter()
+            .position(|server| server.host == host && server.port == port)
+    }
+
+    fn pinned_server_count(&self) -> usize {
+        self.recent_servers
+            .iter()
+            .filter(|server| server.pinned)
+            .count()
+    }
+
+    /// Add `preset`, replacing any preset of the same name in place
+    pub fn save_preset(&mut self, preset: ConnectionPreset) {
+        match self
+            .presets
+            .iter_mut()
+            .find(|entry| entry.name == preset.name)
+        {
+            Some(entry) => *entry = preset,
+            None => self.presets.push(preset),
+        }
+    }
 
-        // Add to front
-        self.recent_servers.insert(0, server);
+    pub fn delete_preset(&mut self, name: &str) -> bool {
+        let before = self.presets.len();
+        self.presets.retain(|entry| entry.name != name);
+        self.presets.len() != before
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&ConnectionPreset> {
+        self.presets.iter().find(|entry| entry.name == name)
+    }
 
-        // Keep only last 10
-        self.recent_servers.truncate(10);
+    /// Move `file` to the front of the recently played list
+    pub fn add_recent_file(&mut self, file: RecentFile) {
+        self.recent_files.retain(|entry| entry.name != file.name);
+        self.recent_files.insert(0, file);
+        self.recent_files.truncate(RECENT_FILES_LIMIT);
     }
 }
 
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -419,6 +1679,201 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_forget_operator_password_keeps_room_listed() {
+        let mut prefs = UserPreferences {
+            room_list: vec![
+                "movies".to_string(),
+                "+anime:ABC123:AB-123-456".to_string(),
+                "+anime:ABC123".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            prefs.saved_operator_passwords(),
+            vec![("+anime:ABC123".to_string(), "AB-123-456".to_string())]
+        );
+        assert!(prefs.forget_operator_password("+anime:ABC123"));
+        assert_eq!(prefs.room_list, vec!["movies", "+anime:ABC123"]);
+        assert!(prefs.saved_operator_passwords().is_empty());
+        assert!(!prefs.forget_operator_password("+anime:ABC123"));
+    }
+
+    #[test]
+    fn test_trusted_files_are_per_user_and_file() {
+        let mut prefs = UserPreferences::default();
+        prefs.trust_file("bob", "Movie.2024.mkv");
+        prefs.trust_file("bob", "Movie.2024.mkv");
+        assert_eq!(prefs.trusted_files["bob"].len(), 1);
+        assert!(prefs.is_file_trusted("bob", "Movie.2024.mkv"));
+        assert!(!prefs.is_file_trusted("bob", "Other.mkv"));
+        assert!(!prefs.is_file_trusted("amy", "Movie.2024.mkv"));
+    }
+
+    #[test]
+    fn test_pause_on_leave_policies() {
+        let mut prefs = UserPreferences::default();
+        assert!(!prefs.pauses_on_leave(true, 1));
+
+        prefs.pause_on_leave = true;
+        assert_eq!(prefs.effective_pause_on_leave(), PauseOnLeave::Anyone);
+        assert!(prefs.pauses_on_leave(false, 5));
+
+        prefs.pause_on_leave_policy = PauseOnLeave::ControllersOnly;
+        assert!(!prefs.pauses_on_leave(false, 1));
+        assert!(prefs.pauses_on_leave(true, 5));
+
+        prefs.pause_on_leave_policy = PauseOnLeave::BelowMinUsers;
+        prefs.pause_on_leave_min_users = 3;
+        assert!(!prefs.pauses_on_leave(true, 3));
+        assert!(prefs.pauses_on_leave(false, 2));
+    }
+
+    #[test]
+    fn test_connects_on_launch_honours_gui_prompt() {
+        let mut prefs = UserPreferences {
+            auto_connect_on_launch: true,
+            ..Default::default()
+        };
+        assert!(!prefs.connects_on_launch());
+
+        prefs.force_gui_prompt = false;
+        assert!(prefs.connects_on_launch());
+
+        prefs.auto_connect_on_launch = false;
+        prefs.auto_connect = true;
+        assert!(prefs.connects_on_launch());
+    }
+
+    #[test]
+    fn test_effective_playlist_mode_uses_legacy_loop_flag() {
+        let mut prefs = UserPreferences::default();
+        assert_eq!(prefs.effective_playlist_mode(), PlaylistMode::Normal);
+
+        prefs.loop_at_end_of_playlist = true;
+        assert_eq!(prefs.effective_playlist_mode(), PlaylistMode::RepeatAll);
+
+        prefs.playlist_mode = PlaylistMode::RepeatOne;
+        assert_eq!(prefs.effective_playlist_mode(), PlaylistMode::RepeatOne);
+    }
+
+    #[test]
+    fn test_is_user_ignored() {
+        let mut prefs = UserPreferences::default();
+        assert!(!prefs.is_user_ignored("troll"));
+        prefs.ignored_users.push("troll".to_string());
+        assert!(prefs.is_user_ignored("troll"));
+        assert!(!prefs.is_user_ignored("Troll2"));
+    }
+
+    #[test]
+    fn test_osd_allows_verbosity_and_overrides() {
+        let mut prefs = UserPreferences::default();
+        assert!(prefs.osd_allows(OsdCategory::SameRoom));
+        assert!(!prefs.osd_allows(OsdCategory::DifferentRoom));
+
+        prefs.osd_verbosity = OsdVerbosity::Errors;
+        assert!(prefs.osd_allows(OsdCategory::Error));
+        assert!(!prefs.osd_allows(OsdCategory::Warning));
+
+        prefs.osd_overrides.warnings = Some(true);
+        assert!(prefs.osd_allows(OsdCategory::Warning));
+    }
+
+    #[test]
+    fn test_migrate_legacy_osd_flags() {
+        let mut value = serde_json::to_value(SyncplayConfig::default()).unwrap();
+        let user = value["user"].as_object_mut().unwrap();
+        user.remove("osd_verbosity");
+        user.insert("show_osd".into(), true.into());
+        user.insert("show_slowdown_osd".into(), false.into());
+        user.insert("show_different_room_osd".into(), true.into());
+        user.insert("show_same_room_osd".into(), true.into());
+        let mut config: SyncplayConfig = serde_json::from_value(value).unwrap();
+
+        assert!(config.user.migrate_legacy_osd_flags());
+        assert_eq!(config.user.osd_verbosity, OsdVerbosity::Important);
+        assert_eq!(config.user.osd_overrides.slowdown, Some(false));
+        assert_eq!(config.user.osd_overrides.different_room, Some(true));
+        assert_eq!(config.user.osd_overrides.same_room, None);
+        assert!(!config.user.migrate_legacy_osd_flags());
+
+        let serialized = serde_json::to_value(&config).unwrap();
+        assert!(serialized["user"].get("show_osd").is_none());
+
+        let mut prefs = UserPreferences {
+            show_osd: Some(false),
+            ..UserPreferences::default()
+        };
+        assert!(prefs.migrate_legacy_osd_flags());
+        assert_eq!(prefs.osd_verbosity, OsdVerbosity::Off);
+    }
+
+    #[test]
+    fn test_validate_fastforward_thresholds() {
+        let mut config = SyncplayConfig::default();
+        config.user.fastforward_behind_threshold = config.user.seek_threshold_fastforward + 1.0;
+        assert!(config.validate().is_err());
+
+        let mut config = SyncplayConfig::default();
+        config.user.fastforward_extra_time = -0.5;
+        assert!(config.validate().is_err());
+
+        let mut config = SyncplayConfig::default();
+        config.user.slowdown_reset_threshold = config.user.slowdown_threshold;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_reconnect_policy_delay() {
+        let policy = ReconnectPolicy::default();
+        assert!((policy.delay_for_attempt(0) - 0.1).abs() < 1e-9);
+        assert!((policy.delay_for_attempt(3) - 0.8).abs() < 1e-9);
+        assert!((policy.delay_for_attempt(50) - 3.2).abs() < 1e-9);
+
+        let mut config = SyncplayConfig::default();
+        config.reconnect.max_delay_seconds = 0.05;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_osd_style() {
+        let mut config = SyncplayConfig::default();
+        config.user.osd_color = Some("#00ff88".to_string());
+        assert!(config.validate().is_ok());
+
+        config.user.osd_color = Some("00ff88".to_string());
+        assert!(config.validate().is_err());
+
+        let mut config = SyncplayConfig::default();
+        config.user.osd_font_scale = 5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_smooth_sync_settings() {
+        let mut config = SyncplayConfig::default();
+        config.user.sync_mode = SyncMode::Smooth;
+        assert!(config.validate().is_ok());
+
+        config.user.smooth_sync_rate = 0.1;
+        assert!(config.validate().is_err());
+
+        let mut config = SyncplayConfig::default();
+        config.user.smooth_sync_threshold = config.user.seek_threshold_rewind + 1.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_watchdog_steps_in_order() {
+        let mut config = SyncplayConfig::default();
+        config.player.watchdog.restart_after_secs = config.player.watchdog.poll_fallback_after_secs;
+        assert!(config.validate().is_err());
+
+        config.player.watchdog.enabled = false;
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_default_public_servers() {
         let config = SyncplayConfig::default();
@@ -435,7 +1890,7 @@ mod tests {
         let server = ServerConfig {
             host: "example.com".to_string(),
             port: 8999,
-            password: None,
+            ..ServerConfig::default()
         };
 
         config.add_recent_server(server.clone());
@@ -443,6 +1898,33 @@ mod tests {
         assert_eq!(config.recent_servers[0].host, "example.com");
     }
 
+    #[test]
+    fn test_bind_address_validation() {
+        let mut config = SyncplayConfig::default();
+        config.server.bind_address = Some(" 10.8.0.2 ".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.server.bind_ip(), Some("10.8.0.2".parse().unwrap()));
+        config.server.bind_address = Some("eth0".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_secure_passwords_keeps_only_hash() {
+        let mut config = SyncplayConfig::default();
+        config.server.password = Some("password".to_string());
+        config.secure_passwords();
+        assert!(config.server.password.is_none());
+        assert_eq!(
+            config.saved_hello_password(&config.server.host, config.server.port),
+            Some("5f4dcc3b5aa765d61d8327deb882cf99".to_string())
+        );
+
+        config.user.remember_plaintext_passwords = true;
+        config.server.password = Some("secret".to_string());
+        config.secure_passwords();
+        assert_eq!(config.server.password.as_deref(), Some("secret"));
+    }
+
     #[test]
     fn test_recent_servers_limit() {
         let mut config = SyncplayConfig::default();
@@ -451,10 +1933,96 @@ mod tests {
             config.add_recent_server(ServerConfig {
                 host: format!("server{}.com", i),
                 port: 8999,
-                password: None,
+                ..ServerConfig::default()
             });
         }
 
         assert_eq!(config.recent_servers.len(), 10);
     }
+
+    #[test]
+    fn test_pinned_servers_stay_on_top_and_keep_remembered_details() {
+        let mut config = SyncplayConfig::default();
+        config.add_recent_server(ServerConfig {
+            host: "home.example".to_string(),
+            port: 8999,
+            username: Some("alice".to_string()),
+            room: Some("movies".to_string()),
+            ..ServerConfig::default()
+        });
+        assert!(config.pin_server("home.example", 8999, true));
+        for i in 0..15 {
+            config.add_recent_server(ServerConfig {
+                host: format!("server{}.com", i),
+                port: 8999,
+                ..ServerConfig::default()
+            });
+        }
+        assert_eq!(config.recent_servers.len(), 10);
+        assert_eq!(config.recent_servers[0].host, "home.example");
+        assert_eq!(config.recent_servers[1].host, "server14.com");
+
+        config.add_recent_server(ServerConfig {
+            host: "home.example".to_string(),
+            port: 8999,
+            ..ServerConfig::default()
+        });
+        let home = &config.recent_servers[0];
+        assert!(home.pinned);
+        assert_eq!(home.username.as_deref(), Some("alice"));
+        assert_eq!(home.room.as_deref(), Some("movies"));
+
+        assert!(config.remove_recent_server("home.example", 8999));
+        assert!(!config.pin_server("home.example", 8999, true));
+    }
+
+    #[test]
+    fn test_saving_a_preset_replaces_the_one_with_the_same_name() {
+        let mut config = SyncplayConfig::default();
+        let preset = |name: &str, room: &str| ConnectionPreset {
+            name: name.to_string(),
+            host: "syncplay.pl".to_string(),
+            port: 8999,
+            username: "alice".to_string(),
+            room: room.to_string(),
+            player_path: None,
+        };
+        config.save_preset(preset("Friday", "movies"));
+        config.save_preset(preset("Anime", "anime"));
+        config.save_preset(preset("Friday", "films"));
+        assert_eq!(config.presets.len(), 2);
+        assert_eq!(config.presets[0].name, "Friday");
+        assert_eq!(config.preset("Friday").unwrap().room, "films");
+        assert!(config.delete_preset("Friday"));
+        assert!(!config.delete_preset("Friday"));
+        assert!(config.preset("Friday").is_none());
+    }
+
+    #[test]
+    fn test_recent_files_move_to_front_and_are_capped() {
+        let mut config = SyncplayConfig::default();
+        let file = |name: String, room: &str| RecentFile {
+            name,
+            path: None,
+            room: room.to_string(),
+            played_at: 0,
+        };
+        for i in 0..25 {
+            config.add_recent_file(file(format!("episode{}.mkv", i), "movies"));
+        }
+        assert_eq!(config.recent_files.len(), RECENT_FILES_LIMIT);
+        assert_eq!(config.recent_files[0].name, "episode24.mkv");
+
+        config.add_recent_file(file("episode10.mkv".to_string(), "anime"));
+        assert_eq!(config.recent_files.len(), RECENT_FILES_LIMIT);
+        assert_eq!(config.recent_files[0].room, "anime");
+        assert_eq!(
+            config
+                .recent_files
+                .iter()
+                .filter(|entry| entry.name == "episode10.mkv")
+                .count(),
+            1
+        );
+    }
 }
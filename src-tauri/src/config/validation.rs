@@ -0,0 +1,98 @@
+// Config validation module
+// Field-level findings the settings UI shows next to the offending input
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::SyncplayConfig;
+
+/// A setting that cannot be applied as is
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Dotted path of the setting, e.g. `user.osd_duration`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Everything wrong with `config`, including paths that do not exist on this machine
+///
+/// Missing paths do not stop `update_config`, since a media directory on an unmounted
+/// drive is still worth keeping; they are only reported here.
+pub fn validate_config(config: &SyncplayConfig) -> Vec<FieldError> {
+    let mut errors = config.validation_errors();
+    errors.extend(path_errors(config));
+    errors
+}
+
+fn path_errors(config: &SyncplayConfig) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    // Bare names like `mpv` are looked up on PATH when the player starts
+    let player_path = config.player.player_path.trim();
+    if is_explicit_path(player_path) && !Path::new(player_path).is_file() {
+        errors.push(FieldError::new(
+            "player.player_path",
+            format!("Player not found: {}", player_path),
+        ));
+    }
+
+    for (index, directory) in config.player.media_directories.iter().enumerate() {
+        if !Path::new(directory).is_dir() {
+            errors.push(FieldError::new(
+                &format!("player.media_directories.{}", index),
+                format!("Media directory not found: {}", directory),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn is_explicit_path(value: &str) -> bool {
+    value.contains('/') || value.contains('\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_name_the_offending_fields() {
+        let mut config = SyncplayConfig::default();
+        assert!(validate_config(&config).is_empty());
+
+        config.server.port = 0;
+        config.user.osd_duration = 0;
+        config.player.player_path = "/nonexistent/bin/mpv".to_string();
+        config.player.media_directories = vec!["/nonexistent/media".to_string()];
+        let fields: Vec<String> = validate_config(&config)
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "server.port",
+                "user.osd_duration",
+                "player.player_path",
+                "player.media_directories.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bare_player_names_are_not_checked() {
+        let mut config = SyncplayConfig::default();
+        config.player.player_path = "vlc".to_string();
+        assert!(validate_config(&config).is_empty());
+    }
+}
@@ -0,0 +1,468 @@
+// Message catalog
+// Client-generated system and OSD messages with per-language templates
+
+use crate::config::Language;
+use std::fmt::Display;
+
+/// Translate `key` into `language`, filling `{}` placeholders in order
+///
+/// Keys missing from a catalog fall back to English, and unknown keys are
+/// returned verbatim so a typo is visible instead of silently dropped.
+pub fn tr(language: Language, key: &str, args: &[&dyn Display]) -> String {
+    let template = lookup(catalog(language), key)
+        .or_else(|| lookup(ENGLISH, key))
+        .unwrap_or(key);
+    fill(template, args)
+}
+
+fn catalog(language: Language) -> &'static [(&'static str, &'static str)] {
+    match language {
+        Language::English => ENGLISH,
+        Language::German => GERMAN,
+        Language::ChineseSimplified => CHINESE_SIMPLIFIED,
+    }
+}
+
+fn lookup(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(entry, _)| *entry == key)
+        .map(|(_, template)| *template)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(index) = rest.find("{}") {
+        output.push_str(&rest[..index]);
+        match args.next() {
+            Some(arg) => output.push_str(&arg.to_string()),
+            None => output.push_str("{}"),
+        }
+        rest = &rest[index + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("server-too-old", "Server {} is too old, these features are disabled: {}"),
+    ("feature-shared-playlists", "shared playlists"),
+    ("feature-chat", "chat"),
+    ("feature-readiness", "readiness"),
+    ("feature-managed-rooms", "managed rooms"),
+    ("feature-set-others-readiness", "setting others' readiness"),
+    ("shared-playlists-disabled", "Shared playlists are disabled by the server"),
+    ("reached-server-with-address", "Successfully reached {} ({} via {})"),
+    ("reached-server", "Successfully reached {}"),
+    ("attempting-tls", "Attempting secure connection"),
+    ("client-no-tls", "This client does not support TLS"),
+    ("server-no-tls", "This server does not support TLS"),
+    ("tls-established", "Secure connection established ({})"),
+    ("connecting", "Attempting to connect to {}:{}"),
+    ("connection-failed", "Connection with server failed"),
+    ("connection-lost-reconnecting", "Connection with server lost, attempting to reconnect"),
+    ("reconnect-cancelled", "Stopped reconnecting to server"),
+    ("hello", "Hello {},"),
+    ("connected", "Successfully connected to server"),
+    ("disconnected", "Disconnected from server"),
+    ("server-timed-out", "Server timed out"),
+    ("connection-stalled", "Connection stalled: the server stopped responding"),
+    ("username-changing", "Reconnecting to change username from {} to {}"),
+    ("room-password-rejected", "Room {} needs a different password"),
+    ("room-marked-persistent", "The playlist of room {} will be remembered"),
+    ("room-unmarked-persistent", "The playlist of room {} will no longer be remembered"),
+    ("unknown-user", "Unknown"),
+    ("user-seeked", "{} jumped from {} to {}"),
+    ("user-paused", "{} paused at {}"),
+    ("user-unpaused", "{} unpaused"),
+    ("user-unpausing-in", "{} is unpausing in {}s"),
+    ("rewinded", "Rewinded due to time difference with {}"),
+    ("fastforwarded", "Fast-forwarded due to time difference with {}"),
+    ("slowdown", "Slowing down due to time difference with {}"),
+    ("speed-reset", "Reverting speed back to normal"),
+    ("smooth-sync-adjusting", "Adjusting speed to {}% to stay in sync with {}"),
+    ("clock-skew-warning", "Your system clock differs from the server by {} seconds; sync may be inaccurate"),
+    ("alone-in-room", "You are currently by yourself in the room"),
+    ("file-differences", "File differences: {}"),
+    ("your-file-differs", "Your file differs in the following way(s): {}"),
+    ("file-difference-name", "name"),
+    ("file-difference-size", "size"),
+    ("file-difference-duration", "duration"),
+    ("all-users-ready", "Everyone is ready ({} users)"),
+    ("not-ready-users", "Not ready: {}"),
+    ("autoplay-countdown", "All users ready ({}) - autoplaying in {}s"),
+    ("unpause-countdown", "Unpausing in {}..."),
+    ("user-set-ready-by", "{} was set as ready by {}"),
+    ("ready-check-started", "{} asks: ready in {}s?"),
+    ("room-finished-file", "Everyone finished {}"),
+    ("player-restart-needed", "Restart the player to apply the new player settings"),
+    ("playlist-advance-waiting", "Waiting for the room to reach the end before playing the next file"),
+    ("launch-connect-failed", "Could not connect on launch: {}"),
+    ("player-interface-mismatch", "{} reported {} version {}, expected {}"),
+    ("operator-offer-sent", "Offered operator control to {}"),
+    ("operator-offer-received", "{} offers you operator control of this room"),
+    ("operator-handoff-sent", "Handed operator control to {}"),
+    ("operator-handoff-received", "Received operator control from {}"),
+    ("ready-check-result", "Ready check: {} of {} ready"),
+    ("session-scheduled", "{} scheduled the session to start at {}"),
+    ("session-starts-in", "Session starts in {}"),
+    ("bookmark-shared", "{} shared bookmark \"{}\" at {}"),
+    ("segment-intro", "Intro"),
+    ("segment-outro", "Outro"),
+    ("segment-preview", "Preview"),
+    ("segment-skip-prompt", "{} playing, skip to {}?"),
+    ("sync-detached", "Playback detached from the room until you resync"),
+    ("sync-resynced", "Playback resynced with the room"),
+    ("player-crashed", "The player stopped unexpectedly"),
+    ("player-restarting", "The player stopped unexpectedly, restarting in {}s"),
+    ("player-restarted", "Player restarted at {}"),
+    ("file-downloading", "{} is still being downloaded; seeking near its end will stall until it finishes"),
+    ("file-download-finished", "{} finished downloading"),
+    ("url-untrusted", "{} is not a trusted domain; confirm to load the URL"),
+    ("stream-buffering", "Stream is buffering"),
+    ("stream-buffering-waiting", "Stream is buffering, the room waits for you"),
+    ("stream-buffered", "Stream buffered"),
+    ("playback-stalled", "Playback stalled"),
+    ("playback-resumed", "Playback is moving again"),
+    ("seek-needs-confirmation", "Confirm the seek to {} before the room follows"),
+    ("operators-only-set", "Only {} can pause and seek in this room"),
+    ("operators-only-announced", "{} made pause and seek operators-only: {}"),
+    ("operators-only-lifted", "{} let everyone pause and seek again"),
+    ("operators-only-suggested", "Only operators can pause and seek here; suggested it to them"),
+    ("suggestion-pause", "{} wants to pause"),
+    ("suggestion-unpause", "{} wants to unpause"),
+    ("suggestion-seek", "{} wants to seek to {}"),
+    ("vote-counted-pause", "{} voted to pause ({}/{})"),
+    ("vote-counted-seek", "{} voted to seek to {} ({}/{})"),
+    ("vote-counted-skip", "{} voted to skip to the next item ({}/{})"),
+    ("vote-passed-pause", "The room voted to pause"),
+    ("vote-passed-seek", "The room voted to seek to {}"),
+    ("vote-passed-skip", "The room voted to skip to the next item"),
+    ("script-failed", "Script {} failed: {}"),
+    ("session-summary", "Session recap: watched {} of {}, {} files, {} rewinds, {} fast-forwards, {} messages"),
+    ("dnd-enabled", "Do not disturb: only errors are shown in the player"),
+    ("dnd-disabled", "Do not disturb ended"),
+    ("idle-room", "Room: {}"),
+    ("idle-server", "Server: {}"),
+    ("idle-waiting-for-file", "Waiting for a file"),
+    ("idle-not-connected", "Not connected"),
+    ("player-unresponsive", "{} has not responded for {} seconds"),
+    ("player-responsive", "{} is responding again"),
+    ("player-watchdog-restarting", "{} stopped responding, restarting it"),
+    ("player-watchdog-stopped", "{} stopped responding and was closed, please restart it"),
+    ("join-needs-username", "Set a username before joining from a link or file"),
+    ("opened-file-not-found", "Could not find {}"),
+    ("user-set-not-ready-by", "{} was set as not ready by {}"),
+    ("set-ready", "You are now set as ready"),
+    ("set-not-ready", "You are now set as not ready"),
+    ("set-ready-unpause-again", "You are now set as ready - unpause again to unpause"),
+    ("auto-ready-matching-file", "You have been set as ready because your file matches the room"),
+    ("paused-focus-lost", "Paused because the player lost focus"),
+    ("stopped-after-current", "Playback stopped after the current item"),
+    ("playlist-updated", "{} updated the playlist"),
+    ("playlist-selection-changed", "{} changed the playlist selection"),
+    ("user-left", "{} has left"),
+    ("user-joined-room", "{} has joined the room: '{}'"),
+    ("user-playing", "{} is playing '{}' ({})"),
+    ("user-playing-in-room", " in room: '{}'"),
+    ("identifying-as-operator", "Identifying as room operator with password '{}'..."),
+    ("operator-authenticated", "{} authenticated as a room operator"),
+    ("operator-auth-failed", "{} failed to identify as a room operator."),
+    ("managed-room-created", "Created managed room '{}' with password '{}'. Please save this information for future reference!\n\nIn managed rooms everyone is kept in sync with the room operator(s) who are the only ones who can pause, unpause, seek, and change the playlist.\n\nYou should ask regular viewers to join the room '{}' but the room operators can join the room '{}' to automatically authenticate themselves."),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    ("server-too-old", "Server {} ist zu alt, diese Funktionen sind deaktiviert: {}"),
+    ("feature-shared-playlists", "geteilte Playlists"),
+    ("feature-chat", "Chat"),
+    ("feature-readiness", "Bereitschaft"),
+    ("feature-managed-rooms", "verwaltete Räume"),
+    ("feature-set-others-readiness", "Bereitschaft anderer setzen"),
+    ("shared-playlists-disabled", "Geteilte Playlists sind auf dem Server deaktiviert"),
+    ("reached-server-with-address", "{} erfolgreich erreicht ({} über {})"),
+    ("reached-server", "{} erfolgreich erreicht"),
+    ("attempting-tls", "Versuche sichere Verbindung aufzubauen"),
+    ("client-no-tls", "Dieser Client unterstützt kein TLS"),
+    ("server-no-tls", "Dieser Server unterstützt kein TLS"),
+    ("tls-established", "Sichere Verbindung hergestellt ({})"),
+    ("connecting", "Verbinde mit {}:{}"),
+    ("connection-failed", "Verbindung zum Server fehlgeschlagen"),
+    ("connection-lost-reconnecting", "Verbindung zum Server verloren, versuche erneut zu verbinden"),
+    ("reconnect-cancelled", "Erneutes Verbinden zum Server abgebrochen"),
+    ("hello", "Hallo {},"),
+    ("connected", "Erfolgreich mit dem Server verbunden"),
+    ("disconnected", "Vom Server getrennt"),
+    ("server-timed-out", "Zeitüberschreitung beim Server"),
+    ("connection-stalled", "Verbindung hängt: Der Server antwortet nicht mehr"),
+    ("username-changing", "Neu verbinden, um den Benutzernamen von {} in {} zu ändern"),
+    ("room-password-rejected", "Raum {} benötigt ein anderes Passwort"),
+    ("room-marked-persistent", "Die Playlist von Raum {} wird gespeichert"),
+    ("room-unmarked-persistent", "Die Playlist von Raum {} wird nicht mehr gespeichert"),
+    ("unknown-user", "Unbekannt"),
+    ("user-seeked", "{} sprang von {} nach {}"),
+    ("user-paused", "{} pausierte bei {}"),
+    ("user-unpaused", "{} setzte die Wiedergabe fort"),
+    ("user-unpausing-in", "{} setzt die Wiedergabe in {}s fort"),
+    ("rewinded", "Zurückgespult wegen Zeitdifferenz zu {}"),
+    ("fastforwarded", "Vorgespult wegen Zeitdifferenz zu {}"),
+    ("slowdown", "Verlangsamt wegen Zeitdifferenz zu {}"),
+    ("speed-reset", "Geschwindigkeit wieder normal"),
+    ("smooth-sync-adjusting", "Geschwindigkeit auf {}% angepasst, um synchron zu {} zu bleiben"),
+    ("clock-skew-warning", "Deine Systemuhr weicht um {} Sekunden vom Server ab; die Synchronisation kann ungenau sein"),
+    ("alone-in-room", "Du bist alleine im Raum"),
+    ("file-differences", "Dateiunterschiede: {}"),
+    ("your-file-differs", "Deine Datei unterscheidet sich auf folgende Art: {}"),
+    ("file-difference-name", "Name"),
+    ("file-difference-size", "Größe"),
+    ("file-difference-duration", "Dauer"),
+    ("all-users-ready", "Alle sind bereit ({} Benutzer)"),
+    ("not-ready-users", "Nicht bereit: {}"),
+    ("autoplay-countdown", "Alle bereit ({}) - Wiedergabe startet in {}s"),
+    ("unpause-countdown", "Wiedergabe startet in {}..."),
+    ("user-set-ready-by", "{} wurde von {} als bereit markiert"),
+    ("ready-check-started", "{} fragt: bereit in {}s?"),
+    ("room-finished-file", "Alle sind mit {} fertig"),
+    ("player-restart-needed", "Starte den Player neu, um die neuen Player-Einstellungen zu übernehmen"),
+    ("playlist-advance-waiting", "Warte, bis alle das Ende erreicht haben, bevor die nächste Datei startet"),
+    ("launch-connect-failed", "Verbindung beim Start fehlgeschlagen: {}"),
+    ("player-interface-mismatch", "{} meldet {}-Version {}, erwartet wird {}"),
+    ("operator-offer-sent", "Operator-Rechte an {} angeboten"),
+    ("operator-offer-received", "{} bietet dir Operator-Rechte für diesen Raum an"),
+    ("operator-handoff-sent", "Operator-Rechte an {} übergeben"),
+    ("operator-handoff-received", "Operator-Rechte von {} erhalten"),
+    ("ready-check-result", "Bereitschaftsabfrage: {} von {} bereit"),
+    ("session-scheduled", "{} hat den Start der Sitzung auf {} gelegt"),
+    ("session-starts-in", "Sitzung beginnt in {}"),
+    ("bookmark-shared", "{} hat das Lesezeichen \"{}\" bei {} geteilt"),
+    ("segment-intro", "Intro"),
+    ("segment-outro", "Abspann"),
+    ("segment-preview", "Vorschau"),
+    ("segment-skip-prompt", "{} läuft, zu {} springen?"),
+    ("sync-detached", "Wiedergabe bis zur Neusynchronisierung vom Raum gelöst"),
+    ("sync-resynced", "Wiedergabe wieder mit dem Raum synchronisiert"),
+    ("player-crashed", "Der Player wurde unerwartet beendet"),
+    ("player-restarting", "Der Player wurde unerwartet beendet, Neustart in {}s"),
+    ("player-restarted", "Player bei {} neu gestartet"),
+    ("file-downloading", "{} wird noch heruntergeladen; Springen nahe ans Ende hängt, bis der Download fertig ist"),
+    ("file-download-finished", "{} ist fertig heruntergeladen"),
+    ("url-untrusted", "{} ist keine vertrauenswürdige Domain; zum Laden der URL bestätigen"),
+    ("stream-buffering", "Stream wird gepuffert"),
+    ("stream-buffering-waiting", "Stream wird gepuffert, der Raum wartet auf dich"),
+    ("stream-buffered", "Stream gepuffert"),
+    ("playback-stalled", "Wiedergabe hängt"),
+    ("playback-resumed", "Wiedergabe läuft wieder"),
+    ("seek-needs-confirmation", "Bestätige den Sprung zu {}, bevor der Raum folgt"),
+    ("operators-only-set", "Nur {} können in diesem Raum pausieren und springen"),
+    ("operators-only-announced", "{} hat Pausieren und Springen auf Operatoren beschränkt: {}"),
+    ("operators-only-lifted", "{} lässt wieder alle pausieren und springen"),
+    ("operators-only-suggested", "Nur Operatoren können hier pausieren und springen; als Vorschlag gesendet"),
+    ("suggestion-pause", "{} möchte pausieren"),
+    ("suggestion-unpause", "{} möchte fortsetzen"),
+    ("suggestion-seek", "{} möchte zu {} springen"),
+    ("vote-counted-pause", "{} stimmt für Pausieren ({}/{})"),
+    ("vote-counted-seek", "{} stimmt für einen Sprung zu {} ({}/{})"),
+    ("vote-counted-skip", "{} stimmt für den nächsten Eintrag ({}/{})"),
+    ("vote-passed-pause", "Der Raum hat für Pausieren gestimmt"),
+    ("vote-passed-seek", "Der Raum hat für einen Sprung zu {} gestimmt"),
+    ("vote-passed-skip", "Der Raum hat für den nächsten Eintrag gestimmt"),
+    ("script-failed", "Skript {} ist fehlgeschlagen: {}"),
+    ("session-summary", "Rückblick: {} von {} geschaut, {} Dateien, {} Rücksprünge, {} Vorsprünge, {} Nachrichten"),
+    ("dnd-enabled", "Bitte nicht stören: im Player erscheinen nur noch Fehler"),
+    ("dnd-disabled", "Bitte nicht stören beendet"),
+    ("idle-room", "Raum: {}"),
+    ("idle-server", "Server: {}"),
+    ("idle-waiting-for-file", "Warte auf eine Datei"),
+    ("idle-not-connected", "Nicht verbunden"),
+    ("player-unresponsive", "{} reagiert seit {} Sekunden nicht"),
+    ("player-responsive", "{} reagiert wieder"),
+    ("player-watchdog-restarting", "{} reagiert nicht mehr und wird neu gestartet"),
+    ("player-watchdog-stopped", "{} reagiert nicht mehr und wurde geschlossen, bitte neu starten"),
+    ("join-needs-username", "Lege einen Benutzernamen fest, bevor du über einen Link oder eine Datei beitrittst"),
+    ("opened-file-not-found", "{} wurde nicht gefunden"),
+    ("user-set-not-ready-by", "{} wurde von {} als nicht bereit markiert"),
+    ("set-ready", "Du bist jetzt bereit"),
+    ("set-not-ready", "Du bist jetzt nicht bereit"),
+    ("set-ready-unpause-again", "Du bist jetzt bereit - zum Fortsetzen erneut starten"),
+    ("auto-ready-matching-file", "Du wurdest als bereit markiert, weil deine Datei zum Raum passt"),
+    ("paused-focus-lost", "Pausiert, weil der Player den Fokus verloren hat"),
+    ("stopped-after-current", "Wiedergabe nach dem aktuellen Eintrag gestoppt"),
+    ("playlist-updated", "{} hat die Playlist aktualisiert"),
+    ("playlist-selection-changed", "{} hat die Auswahl in der Playlist geändert"),
+    ("user-left", "{} ist gegangen"),
+    ("user-joined-room", "{} hat den Raum '{}' betreten"),
+    ("user-playing", "{} spielt '{}' ({})"),
+    ("user-playing-in-room", " im Raum: '{}'"),
+    ("identifying-as-operator", "Identifiziere als Raumleiter mit Passwort '{}'..."),
+    ("operator-authenticated", "{} hat sich als Raumleiter authentifiziert"),
+    ("operator-auth-failed", "{} konnte sich nicht als Raumleiter identifizieren."),
+    ("managed-room-created", "Zentral gesteuerter Raum '{}' mit Passwort '{}' erstellt. Bitte diese Informationen für später speichern!\n\nIn zentral gesteuerten Räumen werden alle mit den Raumleitern synchronisiert, die als Einzige pausieren, fortsetzen, spulen und die Playlist ändern können.\n\nNormale Zuschauer sollten den Raum '{}' betreten, Raumleiter können den Raum '{}' betreten, um sich automatisch zu authentifizieren."),
+];
+
+const CHINESE_SIMPLIFIED: &[(&str, &str)] = &[
+    ("server-too-old", "服务器 {} 版本过旧，以下功能已禁用：{}"),
+    ("feature-shared-playlists", "共享播放列表"),
+    ("feature-chat", "聊天"),
+    ("feature-readiness", "准备状态"),
+    ("feature-managed-rooms", "管理房间"),
+    ("feature-set-others-readiness", "设置他人准备状态"),
+    ("shared-playlists-disabled", "服务器已禁用共享播放列表"),
+    ("reached-server-with-address", "已成功连接 {}（{}，{}）"),
+    ("reached-server", "已成功连接 {}"),
+    ("attempting-tls", "正在尝试建立安全连接"),
+    ("client-no-tls", "此客户端不支持 TLS"),
+    ("server-no-tls", "此服务器不支持 TLS"),
+    ("tls-established", "已建立安全连接（{}）"),
+    ("connecting", "正在连接 {}:{}"),
+    ("connection-failed", "与服务器的连接失败"),
+    ("connection-lost-reconnecting", "与服务器的连接已断开，正在尝试重新连接"),
+    ("reconnect-cancelled", "已停止重新连接服务器"),
+    ("hello", "{}，你好"),
+    ("connected", "已成功连接到服务器"),
+    ("disconnected", "已与服务器断开连接"),
+    ("server-timed-out", "服务器超时"),
+    ("connection-stalled", "连接停滞：服务器已停止响应"),
+    ("username-changing", "正在重新连接，将用户名从 {} 改为 {}"),
+    ("room-password-rejected", "房间 {} 需要其他密码"),
+    ("room-marked-persistent", "将记住房间 {} 的播放列表"),
+    ("room-unmarked-persistent", "不再记住房间 {} 的播放列表"),
+    ("unknown-user", "未知用户"),
+    ("user-seeked", "{} 从 {} 跳转到 {}"),
+    ("user-paused", "{} 暂停于 {}"),
+    ("user-unpaused", "{} 继续播放"),
+    ("user-unpausing-in", "{} 将在 {} 秒后继续播放"),
+    ("rewinded", "由于与 {} 的时间差而后退"),
+    ("fastforwarded", "由于与 {} 的时间差而快进"),
+    ("slowdown", "由于与 {} 的时间差而减速"),
+    ("speed-reset", "播放速度已恢复正常"),
+    ("smooth-sync-adjusting", "已将速度调整为 {}% 以与 {} 保持同步"),
+    ("clock-skew-warning", "你的系统时钟与服务器相差 {} 秒，同步可能不准确"),
+    ("alone-in-room", "房间里现在只有你一个人"),
+    ("file-differences", "文件差异：{}"),
+    ("your-file-differs", "你的文件在以下方面不同：{}"),
+    ("file-difference-name", "名称"),
+    ("file-difference-size", "大小"),
+    ("file-difference-duration", "时长"),
+    ("all-users-ready", "所有人都已准备好（{} 位用户）"),
+    ("not-ready-users", "未准备：{}"),
+    ("autoplay-countdown", "所有用户已准备好（{}）- {} 秒后自动播放"),
+    ("unpause-countdown", "{} 秒后继续播放..."),
+    ("user-set-ready-by", "{} 被 {} 设为已准备"),
+    ("ready-check-started", "{} 询问：{} 秒内准备好了吗？"),
+    ("room-finished-file", "所有人都已看完 {}"),
+    ("player-restart-needed", "重启播放器以应用新的播放器设置"),
+    ("playlist-advance-waiting", "等待房间内所有人播放到结尾后再播放下一个文件"),
+    ("launch-connect-failed", "启动时连接失败：{}"),
+    ("player-interface-mismatch", "{} 报告的{}版本为 {}，需要 {}"),
+    ("operator-offer-sent", "已向 {} 提供管理员权限"),
+    ("operator-offer-received", "{} 向你提供此房间的管理员权限"),
+    ("operator-handoff-sent", "已将管理员权限移交给 {}"),
+    ("operator-handoff-received", "已从 {} 获得管理员权限"),
+    ("ready-check-result", "准备确认：{} / {} 已准备"),
+    ("session-scheduled", "{} 将会话开始时间定为 {}"),
+    ("session-starts-in", "会话将在 {} 后开始"),
+    ("bookmark-shared", "{} 分享了书签“{}”，位置 {}"),
+    ("segment-intro", "片头"),
+    ("segment-outro", "片尾"),
+    ("segment-preview", "预告"),
+    ("segment-skip-prompt", "正在播放{}，跳到 {}？"),
+    ("sync-detached", "播放已脱离房间同步，重新同步前不会影响他人"),
+    ("sync-resynced", "播放已与房间重新同步"),
+    ("player-crashed", "播放器意外退出"),
+    ("player-restarting", "播放器意外退出，将在 {} 秒后重启"),
+    ("player-restarted", "播放器已重启，位置 {}"),
+    ("file-downloading", "{} 仍在下载中，在下载完成前跳转到接近结尾处会卡住"),
+    ("file-download-finished", "{} 已下载完成"),
+    ("url-untrusted", "{} 不是受信任的域名，确认后才会加载该链接"),
+    ("stream-buffering", "正在缓冲串流"),
+    ("stream-buffering-waiting", "正在缓冲串流，房间在等你"),
+    ("stream-buffered", "串流缓冲完成"),
+    ("playback-stalled", "播放卡住了"),
+    ("playback-resumed", "播放已恢复"),
+    ("seek-needs-confirmation", "确认跳转到 {} 后房间才会跟随"),
+    ("operators-only-set", "此房间仅 {} 可以暂停和跳转"),
+    ("operators-only-announced", "{} 将暂停和跳转限制为管理员：{}"),
+    ("operators-only-lifted", "{} 已允许所有人暂停和跳转"),
+    ("operators-only-suggested", "此房间仅管理员可以暂停和跳转，已向他们发送建议"),
+    ("suggestion-pause", "{} 想要暂停"),
+    ("suggestion-unpause", "{} 想要继续播放"),
+    ("suggestion-seek", "{} 想要跳转到 {}"),
+    ("vote-counted-pause", "{} 投票暂停（{}/{}）"),
+    ("vote-counted-seek", "{} 投票跳转到 {}（{}/{}）"),
+    ("vote-counted-skip", "{} 投票跳到下一项（{}/{}）"),
+    ("vote-passed-pause", "房间投票决定暂停"),
+    ("vote-passed-seek", "房间投票决定跳转到 {}"),
+    ("vote-passed-skip", "房间投票决定跳到下一项"),
+    ("script-failed", "脚本 {} 出错：{}"),
+    ("session-summary", "本次回顾：观看 {}（共 {}），{} 个文件，回退 {} 次，快进 {} 次，消息 {} 条"),
+    ("dnd-enabled", "勿扰模式：播放器中只显示错误"),
+    ("dnd-disabled", "勿扰模式已关闭"),
+    ("idle-room", "房间：{}"),
+    ("idle-server", "服务器：{}"),
+    ("idle-waiting-for-file", "等待文件"),
+    ("idle-not-connected", "未连接"),
+    ("player-unresponsive", "{} 已有 {} 秒没有响应"),
+    ("player-responsive", "{} 已恢复响应"),
+    ("player-watchdog-restarting", "{} 没有响应，正在重启"),
+    ("player-watchdog-stopped", "{} 没有响应，已被关闭，请重新启动"),
+    ("join-needs-username", "通过链接或文件加入前请先设置用户名"),
+    ("opened-file-not-found", "找不到 {}"),
+    ("user-set-not-ready-by", "{} 被 {} 设为未准备"),
+    ("set-ready", "你现在已准备"),
+    ("set-not-ready", "你现在未准备"),
+    ("set-ready-unpause-again", "你现在已准备 - 再次取消暂停以开始播放"),
+    ("auto-ready-matching-file", "你的文件与房间匹配，已自动设为已准备"),
+    ("paused-focus-lost", "播放器失去焦点，已暂停"),
+    ("stopped-after-current", "当前项目播放完毕后已停止"),
+    ("playlist-updated", "{} 更新了播放列表"),
+    ("playlist-selection-changed", "{} 更改了播放列表中的选择"),
+    ("user-left", "{} 已离开"),
+    ("user-joined-room", "{} 加入了房间：'{}'"),
+    ("user-playing", "{} 正在播放 '{}'（{}）"),
+    ("user-playing-in-room", "，房间：'{}'"),
+    ("identifying-as-operator", "正在使用密码 '{}' 验证房间管理员身份..."),
+    ("operator-authenticated", "{} 已验证为房间管理员"),
+    ("operator-auth-failed", "{} 验证房间管理员身份失败。"),
+    ("managed-room-created", "已创建受控房间 '{}'，密码为 '{}'。请保存此信息以备日后使用！\n\n在受控房间中，所有人都与房间管理员保持同步，只有管理员可以暂停、继续、跳转和修改播放列表。\n\n普通观众应加入房间 '{}'，房间管理员可以加入房间 '{}' 以自动验证身份。"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(template: &str) -> usize {
+        template.matches("{}").count()
+    }
+
+    #[test]
+    fn test_tr_fills_placeholders_in_order() {
+        let message = tr(
+            Language::English,
+            "user-seeked",
+            &[&"alice", &"00:10", &"01:00"],
+        );
+        assert_eq!(message, "alice jumped from 00:10 to 01:00");
+        assert_eq!(
+            tr(Language::German, "user-left", &[&"bob"]),
+            "bob ist gegangen"
+        );
+        assert_eq!(tr(Language::English, "missing-key", &[]), "missing-key");
+    }
+
+    #[test]
+    fn test_translations_match_english_keys() {
+        for catalog in [GERMAN, CHINESE_SIMPLIFIED] {
+            for (key, template) in catalog {
+                let english = lookup(ENGLISH, key)
+                    .unwrap_or_else(|| panic!("'{}' is missing from the English catalog", key));
+                assert_eq!(
+                    placeholders(english),
+                    placeholders(template),
+                    "placeholder count differs for '{}'",
+                    key
+                );
+            }
+        }
+    }
+}
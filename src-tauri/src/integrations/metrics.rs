@@ -0,0 +1,252 @@
+// Metrics module
+// Opt-in counters and gauges for graphing sync health, as a command and a Prometheus endpoint
+//
+// The endpoint answers `GET /metrics` on 127.0.0.1 in the Prometheus text format. Counters
+// run from client start, so they keep counting across reconnects.
+
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{timeout, Duration};
+
+use crate::app_state::AppState;
+use crate::client::sync::CorrectionTotals;
+use crate::config::MetricsConfig;
+use crate::network::connection::TrafficCounters;
+
+/// Longest request head read before answering; scrapers send far less
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Counters that are not kept anywhere else
+#[derive(Debug, Default)]
+pub struct ClientMetrics {
+    pub traffic: Arc<TrafficCounters>,
+    pub reconnect_attempts: AtomicU64,
+    pub reconnects: AtomicU64,
+}
+
+/// Every metric at one moment
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    pub connected: bool,
+    pub reconnect_attempts: u64,
+    pub reconnects: u64,
+    /// Latest round trip to the server, in seconds, while connected
+    pub rtt_seconds: Option<f64>,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub outbound_queue_depth: usize,
+    pub corrections: CorrectionTotals,
+}
+
+impl MetricsSnapshot {
+    pub fn collect(state: &AppState) -> Self {
+        let connected = state.is_connected();
+        let metrics = &state.metrics;
+        let connection = state.session.connection.lock().clone();
+        Self {
+            connected,
+            reconnect_attempts: metrics.reconnect_attempts.load(Ordering::Relaxed),
+            reconnects: metrics.reconnects.load(Ordering::Relaxed),
+            rtt_seconds: connected.then(|| state.session.ping_service.lock().get_rtt()),
+            messages_sent: metrics.traffic.sent.load(Ordering::Relaxed),
+            messages_received: metrics.traffic.received.load(Ordering::Relaxed),
+            outbound_queue_depth: connection
+                .map(|connection| connection.metrics().queue_depth)
+                .unwrap_or(0),
+            corrections: state.sync_engine.lock().correction_totals(),
+        }
+    }
+
+    /// The Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, samples: &[(&str, String)]| {
+            let _ = writeln!(out, "# HELP syncplay_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE syncplay_{} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "syncplay_{}{} {}", name, labels, value);
+            }
+        };
+        family(
+            "connected",
+            "gauge",
+            "Whether the client is connected to a server",
+            &[("", u8::from(self.connected).to_string())],
+        );
+        family(
+            "reconnect_attempts_total",
+            "counter",
+            "Attempts to reconnect after losing the server",
+            &[("", self.reconnect_attempts.to_string())],
+        );
+        family(
+            "reconnects_total",
+            "counter",
+            "Reconnects that succeeded",
+            &[("", self.reconnects.to_string())],
+        );
+        if let Some(rtt) = self.rtt_seconds {
+            family(
+                "rtt_seconds",
+                "gauge",
+                "Latest round trip time to the server",
+                &[("", rtt.to_string())],
+            );
+        }
+        family(
+            "messages_sent_total",
+            "counter",
+            "Protocol messages sent to servers",
+            &[("", self.messages_sent.to_string())],
+        );
+        family(
+            "messages_received_total",
+            "counter",
+            "Protocol messages received from servers",
+            &[("", self.messages_received.to_string())],
+        );
+        family(
+            "outbound_queue_depth",
+            "gauge",
+            "Messages waiting to be written to the server",
+            &[("", self.outbound_queue_depth.to_string())],
+        );
+        let corrections = &self.corrections;
+        family(
+            "desync_corrections_total",
+            "counter",
+            "Corrections applied to stay in sync with the room",
+            &[
+                ("{kind=\"rewind\"}", corrections.rewinds.to_string()),
+                (
+                    "{kind=\"fastforward\"}",
+                    corrections.fastforwards.to_string(),
+                ),
+                ("{kind=\"slowdown\"}", corrections.slowdowns.to_string()),
+                ("{kind=\"speedup\"}", corrections.speedups.to_string()),
+                (
+                    "{kind=\"speed_reset\"}",
+                    corrections.speed_resets.to_string(),
+                ),
+            ],
+        );
+        out
+    }
+}
+
+/// Start, restart or stop the metrics endpoint to match the config
+pub fn apply_config(state: &Arc<AppState>, config: &MetricsConfig) {
+    if let Some(server) = state.metrics_server.lock().take() {
+        server.abort();
+    }
+    if !config.enabled {
+        return;
+    }
+    let state_clone = state.clone();
+    let port = config.port;
+    let server = tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(state_clone, port).await {
+            tracing::warn!("Metrics endpoint stopped: {}", e);
+        }
+    });
+    *state.metrics_server.lock() = Some(server);
+}
+
+async fn serve(state: Arc<AppState>, port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    tracing::info!("Metrics endpoint listening on 127.0.0.1:{}", port);
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = answer(&state, stream).await {
+                        tracing::debug!("Metrics request failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => tracing::warn!("Failed to accept metrics request: {}", e),
+        }
+    }
+}
+
+async fn answer(state: &AppState, mut stream: TcpStream) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = timeout(REQUEST_TIMEOUT, stream.read(&mut buffer))
+            .await
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+        if read == 0 || request.len() + read > MAX_REQUEST_BYTES {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let (status, body) = match request_path(&request) {
+        Some("/metrics") => ("200 OK", MetricsSnapshot::collect(state).render()),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Path of a GET request, without its query
+fn request_path(request: &[u8]) -> Option<&str> {
+    let line = std::str::from_utf8(request).ok()?.lines().next()?;
+    let mut parts = line.split(' ');
+    if parts.next()? != "GET" {
+        return None;
+    }
+    parts.next()?.split('?').next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let snapshot = MetricsSnapshot {
+            connected: true,
+            reconnects: 2,
+            rtt_seconds: Some(0.05),
+            messages_sent: 10,
+            corrections: CorrectionTotals {
+                rewinds: 3,
+                ..CorrectionTotals::default()
+            },
+            ..MetricsSnapshot::default()
+        };
+        let text = snapshot.render();
+        assert!(text.contains("# TYPE syncplay_reconnects_total counter\n"));
+        assert!(text.contains("syncplay_connected 1\n"));
+        assert!(text.contains("syncplay_reconnects_total 2\n"));
+        assert!(text.contains("syncplay_rtt_seconds 0.05\n"));
+        assert!(text.contains("syncplay_desync_corrections_total{kind=\"rewind\"} 3\n"));
+        let text = MetricsSnapshot::default().render();
+        assert!(!text.contains("syncplay_rtt_seconds"));
+    }
+
+    #[test]
+    fn test_request_path() {
+        assert_eq!(
+            request_path(b"GET /metrics?x=1 HTTP/1.1\r\nHost: a\r\n\r\n"),
+            Some("/metrics")
+        );
+        assert_eq!(request_path(b"POST /metrics HTTP/1.1\r\n\r\n"), None);
+        assert_eq!(request_path(b"\xff\xfe"), None);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod metrics;
+pub mod remote;
+pub mod scripts;
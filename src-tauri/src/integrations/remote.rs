@@ -0,0 +1,284 @@
+// Remote control module
+// Localhost WebSocket API for stream decks, phones and scripts
+//
+// Clients connect to ws://127.0.0.1:<port>/ with the configured token, either as a
+// `?token=` query or an `Authorization: Bearer` header. They send JSON commands such as
+// `{"id": 1, "command": "seek", "position": 90.0}` and get a response with the same id;
+// every frontend event is pushed to them as `{"event": ..., "payload": ...}`.
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::app_state::AppState;
+use crate::commands::chat::send_chat_message_from_player;
+use crate::commands::connection::send_ready_state;
+use crate::commands::room::room_state_snapshot;
+use crate::config::RemoteControlConfig;
+
+/// Events buffered for each remote client before it starts missing some
+pub const REMOTE_EVENT_CAPACITY: usize = 256;
+
+/// A frontend event as pushed to remote clients
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteEvent {
+    pub event: String,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum RemoteCommand {
+    Pause,
+    Unpause,
+    /// Seek to the position, in seconds
+    Seek {
+        position: f64,
+    },
+    SetReady {
+        ready: bool,
+    },
+    Chat {
+        message: String,
+    },
+    GetState,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RemoteRequest {
+    /// Echoed back in the response
+    #[serde(default)]
+    pub id: Value,
+    #[serde(flatten)]
+    pub command: RemoteCommand,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteResponse {
+    pub id: Value,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RemoteResponse {
+    fn new(id: Value, result: Result<Value, String>) -> Self {
+        match result {
+            Ok(result) => Self {
+                id,
+                ok: true,
+                result: (!result.is_null()).then_some(result),
+                error: None,
+            },
+            Err(error) => Self {
+                id,
+                ok: false,
+                result: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// Whether the handshake carries `token`, as a query parameter or a bearer header
+pub fn is_authorized(query: Option<&str>, authorization: Option<&str>, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let from_query = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|pair| {
+            pair.strip_prefix("token=")
+                .is_some_and(|given| tokens_match(given, token))
+        });
+    let from_header = authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| tokens_match(given.trim(), token));
+    from_query || from_header
+}
+
+/// Compare without returning early, so timing says nothing about the token
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Start, restart or stop the remote control server to match the config
+pub fn apply_config(state: &Arc<AppState>, config: &RemoteControlConfig) {
+    if let Some(server) = state.remote_server.lock().take() {
+        server.abort();
+    }
+    if !config.enabled {
+        return;
+    }
+    let state_clone = state.clone();
+    let config = config.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(state_clone, config).await {
+            tracing::warn!("Remote control server stopped: {}", e);
+        }
+    });
+    *state.remote_server.lock() = Some(server);
+}
+
+async fn serve(state: Arc<AppState>, config: RemoteControlConfig) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", config.port)).await?;
+    tracing::info!("Remote control listening on 127.0.0.1:{}", config.port);
+    // Aborting the server drops the set, which ends every client with it
+    let mut clients = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    tracing::info!("Remote control client connected from {}", peer);
+                    clients.spawn(handle_client(state.clone(), stream, config.token.clone()));
+                }
+                Err(e) => tracing::warn!("Failed to accept remote control client: {}", e),
+            },
+            Some(_) = clients.join_next() => {}
+        }
+    }
+}
+
+async fn handle_client(state: Arc<AppState>, stream: TcpStream, token: String) {
+    // The handshake callback's signature is fixed by tungstenite
+    #[allow(clippy::result_large_err)]
+    let authorize = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let authorization = request
+            .headers()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        if is_authorized(request.uri().query(), authorization, &token) {
+            return Ok(response);
+        }
+        let mut error = ErrorResponse::new(Some("Missing or wrong token".to_string()));
+        *error.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(error)
+    };
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::warn!("Remote control handshake failed: {}", e);
+            return;
+        }
+    };
+    let (mut sink, mut source) = socket.split();
+    let mut events = state.remote_events.subscribe();
+    loop {
+        let outgoing = tokio::select! {
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Text(text))) => handle_request(&state, &text).await,
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    tracing::warn!("Remote control client error: {}", e);
+                    break;
+                }
+            },
+            event = events.recv() => match event {
+                Ok(event) => serde_json::to_string(&event).unwrap_or_default(),
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!("Remote control client missed {} events", missed);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if sink.send(Message::Text(outgoing)).await.is_err() {
+            break;
+        }
+    }
+    tracing::info!("Remote control client disconnected");
+}
+
+async fn handle_request(state: &Arc<AppState>, text: &str) -> String {
+    let response = match serde_json::from_str::<RemoteRequest>(text) {
+        Ok(request) => {
+            let result = run_command(state, request.command).await;
+            RemoteResponse::new(request.id, result)
+        }
+        Err(e) => RemoteResponse::new(Value::Null, Err(format!("Invalid command: {}", e))),
+    };
+    serde_json::to_string(&response).unwrap_or_default()
+}
+
+async fn run_command(state: &Arc<AppState>, command: RemoteCommand) -> Result<Value, String> {
+    match command {
+        RemoteCommand::Pause | RemoteCommand::Unpause | RemoteCommand::Seek { .. } => {
+            let player = state.player.lock().clone();
+            let Some(player) = player else {
+                return Err("No player connected".to_string());
+            };
+            // The state loop sends these on like any pause or seek of ours
+            match command {
+                RemoteCommand::Seek { position } => {
+                    if !position.is_finite() || position < 0.0 {
+                        return Err("Seek position must be a positive number".to_string());
+                    }
+                    *state.confirmed_seek.lock() = Some(position);
+                    player.set_position(position).await
+                }
+                RemoteCommand::Pause => player.set_paused(true).await,
+                _ => player.set_paused(false).await,
+            }
+            .map_err(|e| e.to_string())?;
+        }
+        RemoteCommand::SetReady { ready } => {
+            if !state.is_connected() {
+                return Err("Not connected to server".to_string());
+            }
+            send_ready_state(state, ready, true)?;
+        }
+        RemoteCommand::Chat { message } => send_chat_message_from_player(state, &message).await?,
+        RemoteCommand::GetState => {
+            return serde_json::to_value(room_state_snapshot(state)).map_err(|e| e.to_string());
+        }
+    }
+    Ok(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    #[test]
+    fn test_is_authorized_by_query_or_bearer() {
+        assert!(is_authorized(
+            Some("a=1&token=0123456789abcdef"),
+            None,
+            TOKEN
+        ));
+        assert!(is_authorized(None, Some("Bearer 0123456789abcdef"), TOKEN));
+        assert!(!is_authorized(Some("token=0123456789abcdeX"), None, TOKEN));
+        assert!(!is_authorized(Some("token="), Some("Basic abc"), TOKEN));
+        assert!(!is_authorized(Some("token="), None, ""));
+    }
+
+    #[test]
+    fn test_parse_remote_request() {
+        let request: RemoteRequest =
+            serde_json::from_str(r#"{"id": 7, "command": "seek", "position": 90.5}"#).unwrap();
+        assert_eq!(request.id, Value::from(7));
+        assert_eq!(request.command, RemoteCommand::Seek { position: 90.5 });
+        let request: RemoteRequest = serde_json::from_str(r#"{"command": "get_state"}"#).unwrap();
+        assert_eq!(request.id, Value::Null);
+        assert_eq!(request.command, RemoteCommand::GetState);
+        assert!(serde_json::from_str::<RemoteRequest>(r#"{"command": "eject"}"#).is_err());
+    }
+}
@@ -0,0 +1,487 @@
+// Scripts module
+// User Lua scripts that react to client events, for automation such as greetings
+//
+// Every `*.lua` file in the scripts directory runs in one sandboxed Lua state on its own
+// thread, without the io, os or package libraries. Scripts register handlers with
+// `syncplay.on(event, handler)` for "file_loaded", "user_joined" and "chat_message", and
+// act through `syncplay.send_chat`, `syncplay.set_ready`, `syncplay.show_osd` and
+// `syncplay.log`. Actions are queued while a handler runs and carried out afterwards.
+
+use mlua::{Function, HookTriggers, Lua, LuaOptions, StdLib, Table};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::sync::mpsc as async_mpsc;
+
+use crate::app_state::AppState;
+use crate::commands::chat::send_chat_message_from_player;
+use crate::commands::connection::{emit_error_message, maybe_show_osd, send_ready_state};
+use crate::config::{OsdCategory, ScriptsConfig};
+
+/// Events scripts can register handlers for
+pub const SCRIPT_EVENTS: [&str; 3] = ["file_loaded", "user_joined", "chat_message"];
+
+/// How long loading a script or handling one event may run before it is stopped
+const SCRIPT_TIME_LIMIT: Duration = Duration::from_millis(250);
+
+/// Memory all scripts together may allocate; the time limit cannot stop one huge allocation
+const SCRIPT_MEMORY_LIMIT: usize = 8 * 1024 * 1024;
+
+/// Actions one event may cause; more are dropped so scripts cannot flood the room
+const SCRIPT_ACTIONS_PER_EVENT: usize = 5;
+
+const HANDLERS_KEY: &str = "syncplay_handlers";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptEvent {
+    FileLoaded {
+        filename: String,
+        duration: Option<f64>,
+    },
+    /// Another user joined our room
+    UserJoined { username: String, room: String },
+    ChatMessage {
+        username: String,
+        message: String,
+        /// Sent by us, possibly by a script
+        own: bool,
+    },
+}
+
+impl ScriptEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FileLoaded { .. } => "file_loaded",
+            Self::UserJoined { .. } => "user_joined",
+            Self::ChatMessage { .. } => "chat_message",
+        }
+    }
+
+    fn to_table<'lua>(&self, lua: &'lua Lua) -> mlua::Result<Table<'lua>> {
+        let table = lua.create_table()?;
+        match self {
+            Self::FileLoaded { filename, duration } => {
+                table.set("filename", filename.as_str())?;
+                table.set("duration", *duration)?;
+            }
+            Self::UserJoined { username, room } => {
+                table.set("username", username.as_str())?;
+                table.set("room", room.as_str())?;
+            }
+            Self::ChatMessage {
+                username,
+                message,
+                own,
+            } => {
+                table.set("username", username.as_str())?;
+                table.set("message", message.as_str())?;
+                table.set("own", *own)?;
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Something a script asked the client to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptAction {
+    SendChat(String),
+    SetReady(bool),
+    ShowOsd(String),
+}
+
+/// A script file and why it failed to load, if it did
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptInfo {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+/// Scripts directory and the scripts loaded from it
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScriptsStatus {
+    pub directory: Option<String>,
+    pub scripts: Vec<ScriptInfo>,
+}
+
+/// The sandboxed Lua state every script shares
+pub struct ScriptEngine {
+    lua: Lua,
+    actions: Arc<Mutex<Vec<ScriptAction>>>,
+    deadline: Arc<Mutex<Instant>>,
+}
+
+impl ScriptEngine {
+    pub fn new() -> mlua::Result<Self> {
+        let lua = Lua::new_with(
+            StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8,
+            LuaOptions::default(),
+        )?;
+        lua.set_memory_limit(SCRIPT_MEMORY_LIMIT)?;
+        let actions = Arc::new(Mutex::new(Vec::new()));
+        let deadline = Arc::new(Mutex::new(Instant::now()));
+        let hook_deadline = deadline.clone();
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(1000),
+            move |_, _| {
+                if Instant::now() > *hook_deadline.lock() {
+                    return Err(mlua::Error::RuntimeError(
+                        "script ran for too long".to_string(),
+                    ));
+                }
+                Ok(())
+            },
+        );
+        // The base library can still read files
+        for name in ["dofile", "loadfile"] {
+            lua.globals().set(name, mlua::Nil)?;
+        }
+        lua.set_named_registry_value(HANDLERS_KEY, lua.create_table()?)?;
+        install_api(&lua, &actions)?;
+        Ok(Self {
+            lua,
+            actions,
+            deadline,
+        })
+    }
+
+    /// Run a script's top level, which registers its handlers
+    pub fn load(&self, name: &str, source: &str) -> Result<(), String> {
+        *self.deadline.lock() = Instant::now() + SCRIPT_TIME_LIMIT;
+        let result = self
+            .lua
+            .load(source)
+            .set_name(name)
+            .exec()
+            .map_err(|e| e.to_string());
+        // Actions belong to events, not to loading
+        self.actions.lock().clear();
+        result
+    }
+
+    /// Run every handler for the event and collect the actions they asked for
+    pub fn dispatch(&self, event: &ScriptEvent) -> Vec<ScriptAction> {
+        if let Err(e) = self.run_handlers(event) {
+            tracing::warn!("Script handlers for {} failed: {}", event.name(), e);
+        }
+        let mut actions = std::mem::take(&mut *self.actions.lock());
+        if actions.len() > SCRIPT_ACTIONS_PER_EVENT {
+            tracing::warn!(
+                "Scripts asked for {} actions on {}, keeping the first {}",
+                actions.len(),
+                event.name(),
+                SCRIPT_ACTIONS_PER_EVENT
+            );
+            actions.truncate(SCRIPT_ACTIONS_PER_EVENT);
+        }
+        actions
+    }
+
+    fn run_handlers(&self, event: &ScriptEvent) -> mlua::Result<()> {
+        let handlers: Table = self.lua.named_registry_value(HANDLERS_KEY)?;
+        let Some(list) = handlers.get::<_, Option<Table>>(event.name())? else {
+            return Ok(());
+        };
+        let payload = event.to_table(&self.lua)?;
+        *self.deadline.lock() = Instant::now() + SCRIPT_TIME_LIMIT;
+        for handler in list.sequence_values::<Function>() {
+            // One failing handler does not keep the others from running
+            if let Err(e) = handler?.call::<_, ()>(payload.clone()) {
+                tracing::warn!("Script handler for {} failed: {}", event.name(), e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn install_api(lua: &Lua, actions: &Arc<Mutex<Vec<ScriptAction>>>) -> mlua::Result<()> {
+    let api = lua.create_table()?;
+    api.set(
+        "on",
+        lua.create_function(|lua, (event, handler): (String, Function)| {
+            if !SCRIPT_EVENTS.contains(&event.as_str()) {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "unknown event \"{}\"",
+                    event
+                )));
+            }
+            let handlers: Table = lua.named_registry_value(HANDLERS_KEY)?;
+            let list = match handlers.get::<_, Option<Table>>(event.as_str())? {
+                Some(list) => list,
+                None => {
+                    let list = lua.create_table()?;
+                    handlers.set(event.as_str(), list.clone())?;
+                    list
+                }
+            };
+            list.raw_set(list.raw_len() + 1, handler)
+        })?,
+    )?;
+    let queue = actions.clone();
+    api.set(
+        "send_chat",
+        lua.create_function(move |_, message: String| {
+            queue.lock().push(ScriptAction::SendChat(message));
+            Ok(())
+        })?,
+    )?;
+    let queue = actions.clone();
+    api.set(
+        "set_ready",
+        lua.create_function(move |_, ready: bool| {
+            queue.lock().push(ScriptAction::SetReady(ready));
+            Ok(())
+        })?,
+    )?;
+    let queue = actions.clone();
+    api.set(
+        "show_osd",
+        lua.create_function(move |_, message: String| {
+            queue.lock().push(ScriptAction::ShowOsd(message));
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "log",
+        lua.create_function(|_, message: String| {
+            tracing::info!("Script: {}", message);
+            Ok(())
+        })?,
+    )?;
+    lua.globals().set("syncplay", api)
+}
+
+/// The running scripts thread and what it loaded
+pub struct ScriptHost {
+    events: mpsc::Sender<ScriptEvent>,
+    status: ScriptsStatus,
+}
+
+/// Hand an event to the scripts, if any are running
+pub fn dispatch(state: &AppState, event: ScriptEvent) {
+    if let Some(host) = state.scripts.lock().as_ref() {
+        let _ = host.events.send(event);
+    }
+}
+
+pub fn status(state: &AppState) -> ScriptsStatus {
+    state
+        .scripts
+        .lock()
+        .as_ref()
+        .map(|host| host.status.clone())
+        .unwrap_or_default()
+}
+
+/// Stop the running scripts and load them again when enabled
+pub fn start(state: &Arc<AppState>, config: &ScriptsConfig) -> ScriptsStatus {
+    // Dropping the host closes its channel, which ends the thread
+    state.scripts.lock().take();
+    if !config.enabled {
+        return ScriptsStatus::default();
+    }
+    let Some(directory) = scripts_directory(state, config) else {
+        tracing::warn!("No directory to load scripts from");
+        return ScriptsStatus::default();
+    };
+    let sources = read_scripts(&directory);
+    let (events_tx, events_rx) = mpsc::channel::<ScriptEvent>();
+    let (loaded_tx, loaded_rx) = mpsc::channel();
+    let (actions_tx, mut actions_rx) = async_mpsc::unbounded_channel();
+    let spawned = std::thread::Builder::new()
+        .name("scripts".to_string())
+        .spawn(move || {
+            let engine = match ScriptEngine::new() {
+                Ok(engine) => engine,
+                Err(e) => {
+                    let _ = loaded_tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let scripts = sources
+                .into_iter()
+                .map(|(name, source)| {
+                    let error = source.and_then(|source| engine.load(&name, &source)).err();
+                    ScriptInfo { name, error }
+                })
+                .collect::<Vec<_>>();
+            let _ = loaded_tx.send(Ok(scripts));
+            for event in events_rx {
+                for action in engine.dispatch(&event) {
+                    if actions_tx.send(action).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("Failed to start the scripts thread: {}", e);
+        return ScriptsStatus::default();
+    }
+    let loaded = loaded_rx
+        .recv()
+        .unwrap_or_else(|_| Err("the scripts thread exited".to_string()));
+    let scripts = match loaded {
+        Ok(scripts) => scripts,
+        Err(e) => {
+            tracing::warn!("Failed to set up scripting: {}", e);
+            return ScriptsStatus::default();
+        }
+    };
+    for script in &scripts {
+        if let Some(error) = &script.error {
+            emit_error_message(state, &state.tr("script-failed", &[&script.name, error]));
+        }
+    }
+    let state_clone = state.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(action) = actions_rx.recv().await {
+            if let Err(e) = run_action(&state_clone, action).await {
+                tracing::warn!("Script action failed: {}", e);
+            }
+        }
+    });
+    let status = ScriptsStatus {
+        directory: Some(directory.display().to_string()),
+        scripts,
+    };
+    tracing::info!("Loaded {} scripts", status.scripts.len());
+    *state.scripts.lock() = Some(ScriptHost {
+        events: events_tx,
+        status: status.clone(),
+    });
+    status
+}
+
+fn scripts_directory(state: &AppState, config: &ScriptsConfig) -> Option<PathBuf> {
+    if let Some(directory) = config
+        .directory
+        .as_deref()
+        .filter(|dir| !dir.trim().is_empty())
+    {
+        return Some(PathBuf::from(directory));
+    }
+    let handle = state.app_handle.lock().clone()?;
+    handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join("scripts"))
+}
+
+/// `*.lua` files of the directory by name, with their source or why it could not be read
+fn read_scripts(directory: &Path) -> Vec<(String, Result<String, String>)> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::info!("No scripts loaded from {}: {}", directory.display(), e);
+            return Vec::new();
+        }
+    };
+    let mut scripts: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lua"))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            let source = std::fs::read_to_string(&path).map_err(|e| e.to_string());
+            Some((name, source))
+        })
+        .collect();
+    scripts.sort_by(|a, b| a.0.cmp(&b.0));
+    scripts
+}
+
+async fn run_action(state: &Arc<AppState>, action: ScriptAction) -> Result<(), String> {
+    match action {
+        ScriptAction::SendChat(message) => send_chat_message_from_player(state, &message).await,
+        ScriptAction::SetReady(ready) => {
+            if !state.is_connected() {
+                return Err("Not connected to server".to_string());
+            }
+            send_ready_state(state, ready, true)
+        }
+        ScriptAction::ShowOsd(message) => {
+            let config = state.config.lock().clone();
+            maybe_show_osd(state, &config, &message, OsdCategory::Status);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(username: &str, message: &str) -> ScriptEvent {
+        ScriptEvent::ChatMessage {
+            username: username.to_string(),
+            message: message.to_string(),
+            own: false,
+        }
+    }
+
+    #[test]
+    fn test_handlers_queue_actions() {
+        let engine = ScriptEngine::new().unwrap();
+        engine
+            .load(
+                "greet.lua",
+                r#"
+                syncplay.on("user_joined", function(event)
+                    syncplay.send_chat("Hi " .. event.username)
+                end)
+                syncplay.on("chat_message", function(event)
+                    if not event.own and event.message == "!ready" then
+                        syncplay.set_ready(true)
+                    end
+                end)
+                "#,
+            )
+            .unwrap();
+        let joined = ScriptEvent::UserJoined {
+            username: "alice".to_string(),
+            room: "movies".to_string(),
+        };
+        assert_eq!(
+            engine.dispatch(&joined),
+            vec![ScriptAction::SendChat("Hi alice".to_string())]
+        );
+        assert_eq!(
+            engine.dispatch(&chat("bob", "!ready")),
+            vec![ScriptAction::SetReady(true)]
+        );
+        assert!(engine.dispatch(&chat("bob", "hello")).is_empty());
+    }
+
+    #[test]
+    fn test_scripts_are_sandboxed_and_limited() {
+        let engine = ScriptEngine::new().unwrap();
+        assert!(engine.load("io.lua", "io.open('/etc/passwd')").is_err());
+        assert!(engine.load("os.lua", "os.execute('true')").is_err());
+        assert!(engine.load("dofile.lua", "dofile('/etc/passwd')").is_err());
+        assert!(engine
+            .load("event.lua", "syncplay.on('tick', print)")
+            .is_err());
+        assert!(engine.load("loop.lua", "while true do end").is_err());
+        assert!(engine
+            .load("memory.lua", "local s = string.rep('x', 2^31)")
+            .is_err());
+        engine
+            .load(
+                "flood.lua",
+                r#"
+                syncplay.on("chat_message", function()
+                    for i = 1, 100 do syncplay.send_chat("spam") end
+                end)
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            engine.dispatch(&chat("bob", "hi")).len(),
+            SCRIPT_ACTIONS_PER_EVENT
+        );
+    }
+}
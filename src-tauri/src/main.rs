@@ -7,7 +7,10 @@ mod app_state;
 mod client;
 mod commands;
 mod config;
+mod i18n;
+mod integrations;
 mod network;
+mod notifications;
 mod player;
 mod utils;
 
@@ -15,6 +18,7 @@ use app_state::AppState;
 #[cfg(target_os = "macos")]
 use tauri::utils::TitleBarStyle;
 use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
 #[cfg(windows)]
 use tauri_plugin_frame::FramePluginBuilder;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -31,11 +35,13 @@ fn main() {
 
     // Create global app state
     let app_state = AppState::new();
+    let demo_mode = std::env::args().any(|arg| arg == "--demo");
 
     let builder = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_updater::Builder::new().build());
 
     let builder = with_frame_plugin(builder);
@@ -64,11 +70,51 @@ fn main() {
                     }
                 }
             }
-            let config = crate::config::load_config(app.handle()).unwrap_or_else(|e| {
+            let mut config = crate::config::load_config(app.handle()).unwrap_or_else(|e| {
                 tracing::error!("Failed to load config: {}", e);
                 crate::config::SyncplayConfig::default()
             });
+            let overrides = crate::config::ConfigOverrides::from_env_and_args(
+                |name| std::env::var(name).ok(),
+                std::env::args().skip(1),
+            );
+            if !overrides.is_empty() {
+                tracing::info!("Applying startup config overrides");
+                *app_state.config_overrides.lock() = Some(overrides.apply(&mut config));
+            }
             *app_state.config.lock() = config.clone();
+            match crate::config::load_playlist_snapshot(app.handle()) {
+                Ok(Some(snapshot)) if !snapshot.items.is_empty() => {
+                    let room = snapshot.room.clone();
+                    app_state.session.playlist.restore_snapshot(snapshot);
+                    *app_state.restored_playlist_room.lock() = room;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to load saved playlist: {}", e),
+            }
+            match crate::config::load_bookmarks(app.handle()) {
+                Ok(bookmarks) => app_state.bookmarks.restore(bookmarks),
+                Err(e) => tracing::warn!("Failed to load saved bookmarks: {}", e),
+            }
+            match crate::config::load_segments(app.handle()) {
+                Ok(segments) => app_state.segments.restore(segments),
+                Err(e) => tracing::warn!("Failed to load saved segments: {}", e),
+            }
+            match crate::config::load_delays(app.handle()) {
+                Ok(delays) => app_state.delays.restore(delays),
+                Err(e) => tracing::warn!("Failed to load saved delays: {}", e),
+            }
+            match crate::config::load_persistent_rooms(app.handle()) {
+                Ok(rooms) => app_state.persistent_rooms.restore(rooms),
+                Err(e) => tracing::warn!("Failed to load saved persistent rooms: {}", e),
+            }
+            match crate::config::load_player_detection(app.handle()) {
+                Ok(cache) => {
+                    *app_state.detected_players.lock() = cache.players;
+                    *app_state.detected_players_updated_at.lock() = cache.updated_at;
+                }
+                Err(e) => tracing::warn!("Failed to load saved player detection: {}", e),
+            }
             app_state
                 .sync_engine
                 .lock()
@@ -86,32 +132,133 @@ fn main() {
                     .clone()
                     .request_refresh(app_state.clone());
             }
+            crate::commands::autostart::schedule_launch_connect(app_state.clone());
+            crate::integrations::remote::apply_config(&app_state, &config.remote);
+            crate::integrations::scripts::start(&app_state, &config.scripts);
+            crate::integrations::metrics::apply_config(&app_state, &config.metrics);
             let state = app_state.clone();
             tauri::async_runtime::spawn(async move {
-                crate::player::controller::spawn_player_state_loop(state);
+                crate::player::controller::spawn_player_state_loop(state.clone());
+                crate::player::controller::spawn_player_watchdog(state);
             });
+            setup_join_links(app);
+            open_startup_files(app);
+            if demo_mode {
+                tauri::async_runtime::spawn(run_demo_server());
+            }
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(focused) = event {
+                let state = window.state::<std::sync::Arc<AppState>>();
+                *state.app_window_focused.lock() = *focused;
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::connection::connect_to_server,
+            commands::connection::retry_room_join,
+            commands::connection::change_username,
             commands::connection::disconnect_from_server,
+            commands::connection::cancel_reconnect,
+            commands::autostart::cancel_launch_connect,
             commands::connection::get_connection_status,
+            commands::connection::get_connection_metrics,
+            commands::connection::get_metrics,
+            commands::connection::get_server_features,
+            commands::connection::verify_server_password,
             commands::chat::send_chat_message,
+            commands::chat::ignore_user,
+            commands::chat::unignore_user,
+            commands::chat::dismiss_motd,
+            commands::chat::export_session_log,
             commands::room::change_room,
             commands::room::set_ready,
+            commands::room::get_room_state,
+            commands::room::get_room_timeline,
+            commands::room::request_ready_check,
+            commands::room::trust_user_file,
+            commands::room::untrust_user_file,
+            commands::room::list_room_operators,
+            commands::room::grant_operator,
+            commands::room::respond_operator_offer,
+            commands::room::set_operators_only,
+            commands::room_passwords::list_saved_room_passwords,
+            commands::room_passwords::reveal_saved_room_password,
+            commands::room_passwords::remove_saved_room_password,
+            commands::servers::get_recent_servers,
+            commands::servers::remove_recent_server,
+            commands::servers::pin_server,
+            commands::presets::save_preset,
+            commands::presets::delete_preset,
+            commands::presets::connect_with_preset,
+            commands::room::schedule_session,
+            commands::bookmarks::add_bookmark,
+            commands::bookmarks::get_bookmarks,
+            commands::bookmarks::remove_bookmark,
+            commands::bookmarks::jump_to_bookmark,
+            commands::segments::get_segments,
+            commands::segments::set_segments,
+            commands::segments::import_segments,
+            commands::segments::skip_segment,
+            commands::delays::get_delays,
+            commands::delays::set_audio_delay,
+            commands::delays::set_sub_delay,
+            commands::room::list_rooms,
+            commands::room::get_room_overview,
+            commands::room::join_room_at_position,
+            commands::room::list_persistent_rooms,
+            commands::room::set_room_persistent,
+            commands::room::join_room,
             commands::playlist::update_playlist,
+            commands::playlist::queue_next,
+            commands::playlist::set_playlist_mode,
+            commands::playlist::add_directory_to_playlist,
             commands::playlist::check_playlist_items,
+            commands::privacy::preview_privacy,
+            commands::recent_files::get_recent_files,
+            commands::recent_files::replay_recent_file,
+            commands::recent_files::remove_recent_file,
             commands::config::get_config,
             commands::config::update_config,
+            commands::config::validate_config,
             commands::config::get_config_path,
+            commands::scripts::get_scripts,
+            commands::scripts::reload_scripts,
             commands::config::refresh_media_index,
             commands::config::get_media_index_refreshing,
             commands::player::detect_available_players,
             commands::player::get_cached_players,
             commands::player::refresh_player_detection,
+            commands::player::clear_player_detection,
+            commands::sync::get_sync_report,
+            commands::sync::frame_step,
+            commands::sync::frame_back_step,
+            commands::sync::set_local_speed,
+            commands::sync::resync_playback,
+            commands::sync::confirm_seek,
+            commands::do_not_disturb::set_dnd,
+            commands::untrusted_urls::accept_untrusted_url,
+            commands::untrusted_urls::dismiss_untrusted_url,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::Exit => {
+                let state = app.state::<std::sync::Arc<AppState>>().inner().clone();
+                tauri::async_runtime::block_on(commands::connection::shutdown_session(&state));
+            }
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            tauri::RunEvent::Opened { urls } => {
+                for path in urls.iter().filter_map(|url| url.to_file_path().ok()) {
+                    let handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let path = path.to_string_lossy().to_string();
+                        commands::connection::open_media_file(handle, &path).await;
+                    });
+                }
+            }
+            _ => {}
+        });
 }
 
 #[cfg(windows)]
@@ -129,3 +276,73 @@ fn with_frame_plugin<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Bu
 fn with_frame_plugin<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
     builder
 }
+
+/// Follow `syncplay://` links the app was started with or is handed later
+fn setup_join_links(app: &mut tauri::App) {
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("Failed to register join link scheme: {}", e);
+    }
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                commands::connection::open_join_link(handle, url.as_str()).await;
+            });
+        }
+    });
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    commands::connection::open_join_link(handle, url.as_str()).await;
+                });
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to read startup join link: {}", e),
+    }
+}
+
+/// Open media files passed on the command line, as file associations do on Windows and Linux
+fn open_startup_files(app: &mut tauri::App) {
+    let link_prefix = format!("{}:", utils::JOIN_LINK_SCHEME);
+    let mut previous: Option<String> = None;
+    let files = std::env::args().skip(1).filter(|arg| {
+        // Values of `--room movies` style overrides are not files
+        let is_value = previous
+            .replace(arg.clone())
+            .is_some_and(|flag| crate::config::overrides::takes_value(&flag));
+        !is_value
+            && !arg.starts_with('-')
+            && !arg.starts_with(&link_prefix)
+            && (utils::is_media_file(arg) || std::path::Path::new(arg).is_file())
+    });
+    for file in files {
+        let handle = app.handle().clone();
+        tauri::async_runtime::spawn(async move {
+            commands::connection::open_media_file(handle, &file).await;
+        });
+    }
+}
+
+/// Serve the built-in mock server on localhost for `--demo` runs
+async fn run_demo_server() {
+    use crate::network::mock_server::{MockServer, MockServerConfig, DEMO_SERVER_PORT};
+
+    let config = MockServerConfig {
+        port: DEMO_SERVER_PORT,
+        motd: Some("Welcome to the Syncplay demo server".to_string()),
+        ..MockServerConfig::default()
+    };
+    match MockServer::start(config).await {
+        Ok(server) => {
+            tracing::info!("Demo server running on localhost:{}", server.port());
+            // Keep the server alive for the lifetime of the app
+            std::future::pending::<()>().await;
+        }
+        Err(e) => tracing::error!("Failed to start demo server: {}", e),
+    }
+}
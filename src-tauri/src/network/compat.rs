@@ -0,0 +1,130 @@
+// Compatibility module
+// Strips what older servers do not understand before it reaches the outbound queue
+
+use super::messages::{ProtocolMessage, SetMessage};
+
+/// What the connected server accepts; everything is allowed until its Hello says otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerCompat {
+    pub shared_playlists: bool,
+    pub readiness: bool,
+    pub chat: bool,
+    pub managed_rooms: bool,
+}
+
+impl Default for ServerCompat {
+    fn default() -> Self {
+        Self {
+            shared_playlists: true,
+            readiness: true,
+            chat: true,
+            managed_rooms: true,
+        }
+    }
+}
+
+impl ServerCompat {
+    /// The message as this server may receive it, or `None` when nothing of it is left
+    pub fn filter(&self, message: ProtocolMessage) -> Option<ProtocolMessage> {
+        match message {
+            ProtocolMessage::Chat { .. } if !self.chat => None,
+            ProtocolMessage::Set { Set } => {
+                let mut set = *Set;
+                if !self.shared_playlists {
+                    set.playlist_index = None;
+                    set.playlist_change = None;
+                }
+                if !self.readiness {
+                    set.ready = None;
+                }
+                if !self.managed_rooms {
+                    set.controller_auth = None;
+                    set.new_controlled_room = None;
+                }
+                if is_empty_set(&set) {
+                    return None;
+                }
+                Some(ProtocolMessage::Set { Set: Box::new(set) })
+            }
+            message => Some(message),
+        }
+    }
+}
+
+fn is_empty_set(set: &SetMessage) -> bool {
+    set.room.is_none()
+        && set.file.is_none()
+        && set.user.is_none()
+        && set.ready.is_none()
+        && set.playlist_index.is_none()
+        && set.playlist_change.is_none()
+        && set.controller_auth.is_none()
+        && set.new_controlled_room.is_none()
+        && set.features.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::messages::{ChatMessage, FileInfo, PlaylistChange, ReadyState};
+
+    fn set_message(with_file: bool) -> ProtocolMessage {
+        ProtocolMessage::Set {
+            Set: Box::new(SetMessage {
+                room: None,
+                file: with_file.then(|| FileInfo {
+                    name: Some("a.mkv".to_string()),
+                    size: None,
+                    duration: None,
+                    downloading: None,
+                }),
+                user: None,
+                ready: Some(ReadyState {
+                    username: None,
+                    is_ready: Some(true),
+                    manually_initiated: Some(true),
+                    set_by: None,
+                }),
+                playlist_index: None,
+                playlist_change: Some(PlaylistChange {
+                    user: None,
+                    files: vec!["a.mkv".to_string()],
+                }),
+                controller_auth: None,
+                new_controlled_room: None,
+                features: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_old_server_gets_stripped_set() {
+        let compat = ServerCompat {
+            shared_playlists: false,
+            readiness: false,
+            chat: false,
+            managed_rooms: false,
+        };
+        assert!(compat.filter(set_message(false)).is_none());
+        let Some(ProtocolMessage::Set { Set: set }) = compat.filter(set_message(true)) else {
+            panic!("file update should still be sent");
+        };
+        assert!(set.file.is_some());
+        assert!(set.ready.is_none());
+        assert!(set.playlist_change.is_none());
+        let chat = ProtocolMessage::Chat {
+            Chat: ChatMessage::Text("hi".to_string()),
+        };
+        assert!(compat.filter(chat).is_none());
+    }
+
+    #[test]
+    fn test_current_server_gets_everything() {
+        let compat = ServerCompat::default();
+        let Some(ProtocolMessage::Set { Set: set }) = compat.filter(set_message(false)) else {
+            panic!("set should pass through");
+        };
+        assert!(set.ready.is_some());
+        assert!(set.playlist_change.is_some());
+    }
+}
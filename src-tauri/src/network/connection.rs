@@ -1,17 +1,34 @@
 use anyhow::{Context, Result};
+use futures::stream::FuturesUnordered;
 use futures::{SinkExt, StreamExt};
 use parking_lot::Mutex;
-use tokio::net::TcpStream;
-use tokio::sync::{mpsc, oneshot};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::net::{TcpSocket, TcpStream};
+use tokio::sync::{mpsc, oneshot, Notify};
 use tokio::time::{Duration, Instant};
 use tokio_util::codec::Framed;
 use tracing::{debug, error, info, warn};
 
-use super::messages::ProtocolMessage;
+use super::compat::ServerCompat;
+use super::messages::{IgnoringInfo, ProtocolMessage, StateMessage};
 use super::protocol::SyncplayCodec;
 use super::tls::{upgrade_to_tls, TlsInfo};
 
 const CONNECT_TIMEOUT_SECONDS: u64 = 30;
+/// Head start each connection attempt gets before the next address is tried (RFC 8305)
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+/// Most bulk messages (chat, playlist changes, lists) waiting to be written
+const OUTBOUND_QUEUE_CAPACITY: usize = 64;
+/// How often the connection loop checks for a silent server
+const KEEPALIVE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Silence after which the server is asked for a List to prove it is still there
+const KEEPALIVE_PROBE_AFTER: Duration = Duration::from_secs(4);
+/// Silence after which the connection is treated as half-open and closed
+const KEEPALIVE_STALL_TIMEOUT: Duration = Duration::from_secs(8);
 
 /// Connection state
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,8 +39,14 @@ pub enum ConnectionState {
     Authenticated,
 }
 
+/// Why the connection loop closed on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The server stopped answering, even to keep-alive probes
+    Stalled,
+}
+
 enum ConnectionCommand {
-    Send(Box<ProtocolMessage>),
     UpgradeTls {
         domain: String,
         response: oneshot::Sender<Result<TlsInfo>>,
@@ -31,6 +54,136 @@ enum ConnectionCommand {
     Disconnect,
 }
 
+/// Outbound queue snapshot for diagnostics
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionMetrics {
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    pub dropped_state_updates: u64,
+}
+
+/// Messages moved over connections, shared so totals outlive any one connection
+#[derive(Debug, Default)]
+pub struct TrafficCounters {
+    pub sent: AtomicU64,
+    pub received: AtomicU64,
+}
+
+/// Messages waiting for the socket, split into lanes so a State or handshake never
+/// waits behind a large playlist change
+///
+/// States waiting together are merged: the newest playstate wins, as an older one would
+/// report a stale position, but a pending seek and the server's echoes are kept.
+struct OutboundQueue {
+    lanes: Mutex<OutboundLanes>,
+    notify: Notify,
+    dropped_states: AtomicU64,
+}
+
+#[derive(Default)]
+struct OutboundLanes {
+    handshake: VecDeque<ProtocolMessage>,
+    state: Option<StateMessage>,
+    bulk: VecDeque<ProtocolMessage>,
+}
+
+impl OutboundQueue {
+    fn new() -> Self {
+        Self {
+            lanes: Mutex::new(OutboundLanes::default()),
+            notify: Notify::new(),
+            dropped_states: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, message: ProtocolMessage) -> Result<()> {
+        {
+            let mut lanes = self.lanes.lock();
+            match message {
+                ProtocolMessage::State { State: newer } => {
+                    let merged = match lanes.state.take() {
+                        Some(older) => {
+                            self.dropped_states.fetch_add(1, Ordering::Relaxed);
+                            merge_states(older, newer)
+                        }
+                        None => newer,
+                    };
+                    lanes.state = Some(merged);
+                }
+                ProtocolMessage::Hello { .. } | ProtocolMessage::TLS { .. } => {
+                    lanes.handshake.push_back(message);
+                }
+                _ => {
+                    if lanes.bulk.len() >= OUTBOUND_QUEUE_CAPACITY {
+                        anyhow::bail!("Outbound queue is full");
+                    }
+                    lanes.bulk.push_back(message);
+                }
+            }
+        }
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    fn pop(&self) -> Option<ProtocolMessage> {
+        let mut lanes = self.lanes.lock();
+        lanes
+            .handshake
+            .pop_front()
+            .or_else(|| {
+                lanes
+                    .state
+                    .take()
+                    .map(|message| ProtocolMessage::State { State: message })
+            })
+            .or_else(|| lanes.bulk.pop_front())
+    }
+
+    fn metrics(&self) -> ConnectionMetrics {
+        let lanes = self.lanes.lock();
+        ConnectionMetrics {
+            queue_depth: lanes.handshake.len()
+                + lanes.bulk.len()
+                + usize::from(lanes.state.is_some()),
+            queue_capacity: OUTBOUND_QUEUE_CAPACITY,
+            dropped_state_updates: self.dropped_states.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Fold a State that was never sent into the one replacing it
+///
+/// The server only stops ignoring our playstates once it sees its ignoring echo, and a
+/// heartbeat without a playstate must not swallow a seek or pause still waiting to go out.
+fn merge_states(older: StateMessage, newer: StateMessage) -> StateMessage {
+    let seeked = older
+        .playstate
+        .as_ref()
+        .is_some_and(|playstate| playstate.do_seek == Some(true));
+    let playstate = match newer.playstate {
+        Some(mut playstate) => {
+            if seeked {
+                playstate.do_seek = Some(true);
+            }
+            Some(playstate)
+        }
+        None => older.playstate,
+    };
+    let ignoring_on_the_fly = match (older.ignoring_on_the_fly, newer.ignoring_on_the_fly) {
+        (Some(older), Some(newer)) => Some(IgnoringInfo {
+            server: newer.server.or(older.server),
+            client: newer.client.or(older.client),
+        }),
+        (older, newer) => newer.or(older),
+    };
+    StateMessage {
+        playstate,
+        ping: newer.ping.or(older.ping),
+        ignoring_on_the_fly,
+    }
+}
+
 enum Transport {
     Plain(Box<Framed<TcpStream, SyncplayCodec>>),
     Tls(Box<Framed<tokio_rustls::client::TlsStream<TcpStream>, SyncplayCodec>>),
@@ -79,15 +232,28 @@ pub struct Connection {
     host: Mutex<String>,
     port: Mutex<u16>,
     tx: Mutex<Option<mpsc::UnboundedSender<ConnectionCommand>>>,
+    outbound: Mutex<Option<Arc<OutboundQueue>>>,
+    close_reason: Arc<Mutex<Option<CloseReason>>>,
+    compat: Mutex<ServerCompat>,
+    traffic: Arc<TrafficCounters>,
 }
 
 impl Connection {
     pub fn new() -> Self {
+        Self::with_traffic(Arc::new(TrafficCounters::default()))
+    }
+
+    /// A connection that counts its messages into `traffic`
+    pub fn with_traffic(traffic: Arc<TrafficCounters>) -> Self {
         Self {
             state: Mutex::new(ConnectionState::Disconnected),
             host: Mutex::new(String::new()),
             port: Mutex::new(0),
             tx: Mutex::new(None),
+            outbound: Mutex::new(None),
+            close_reason: Arc::new(Mutex::new(None)),
+            compat: Mutex::new(ServerCompat::default()),
+            traffic,
         }
     }
 
@@ -95,28 +261,28 @@ impl Connection {
         self.state.lock().clone()
     }
 
-    /// Connect to a Syncplay server
+    /// Connect to a Syncplay server, from `bind_address` when one is given
     pub async fn connect(
         &self,
         host: String,
         port: u16,
-    ) -> Result<(mpsc::UnboundedReceiver<ProtocolMessage>, Option<String>)> {
+        bind_address: Option<IpAddr>,
+    ) -> Result<(mpsc::UnboundedReceiver<ProtocolMessage>, Option<SocketAddr>)> {
         info!("Connecting to {}:{}", host, port);
         *self.state.lock() = ConnectionState::Connecting;
         *self.host.lock() = host.clone();
         *self.port.lock() = port;
 
-        // Connect TCP stream
-        let address = format!("{}:{}", host, port);
+        // Connect TCP stream, racing every resolved address
         let stream = tokio::time::timeout(
             Duration::from_secs(CONNECT_TIMEOUT_SECONDS),
-            TcpStream::connect(&address),
+            connect_any(&host, port, bind_address),
         )
         .await
         .context("Connection attempt timed out")?
         .context("Failed to connect to server")?;
 
-        let peer_address = stream.peer_addr().ok().map(|addr| addr.ip().to_string());
+        let peer_address = stream.peer_addr().ok();
 
         info!("TCP connection established");
         *self.state.lock() = ConnectionState::Connected;
@@ -128,36 +294,51 @@ impl Connection {
         // Create channels for bidirectional communication
         let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel::<ConnectionCommand>();
         let (msg_tx, msg_rx) = mpsc::unbounded_channel::<ProtocolMessage>();
+        let outbound = Arc::new(OutboundQueue::new());
 
         *self.tx.lock() = Some(cmd_tx);
+        *self.outbound.lock() = Some(outbound.clone());
+        *self.close_reason.lock() = None;
+        let close_reason = self.close_reason.clone();
+        let traffic = self.traffic.clone();
 
         tokio::spawn(async move {
             info!("Connection loop started");
-            let mut idle_tick = tokio::time::interval(Duration::from_secs(10));
+            let mut keepalive_tick = tokio::time::interval(KEEPALIVE_CHECK_INTERVAL);
             let mut last_received = Instant::now();
+            // Nothing is expected before the server first speaks, and one probe per
+            // silence is enough
+            let mut heard_from_server = false;
+            let mut probe_sent = false;
             loop {
                 tokio::select! {
+                    biased;
                     Some(cmd) = cmd_rx.recv() => {
                         match cmd {
-                            ConnectionCommand::Send(msg) => {
-                                if let Err(e) = transport.send(*msg).await {
-                                    error!("Failed to send message: {}", e);
-                                    break;
-                                }
-                            }
                             ConnectionCommand::UpgradeTls { domain, response } => {
                                 let result = transport.upgrade_tls(&domain).await;
                                 let _ = response.send(result);
                             }
                             ConnectionCommand::Disconnect => {
+                                // Let goodbyes queued before the disconnect go out first
+                                let _ = flush_outbound(&mut transport, &outbound, &traffic).await;
                                 break;
                             }
                         }
                     }
+                    _ = outbound.notify.notified() => {
+                        if let Err(e) = flush_outbound(&mut transport, &outbound, &traffic).await {
+                            error!("Failed to send message: {}", e);
+                            break;
+                        }
+                    }
                     message = transport.next_message() => {
                         match message {
                             Some(Ok(msg)) => {
+                                traffic.received.fetch_add(1, Ordering::Relaxed);
                                 last_received = Instant::now();
+                                heard_from_server = true;
+                                probe_sent = false;
                                 if msg_tx.send(msg).is_err() {
                                     warn!("Failed to forward received message");
                                     break;
@@ -173,10 +354,24 @@ impl Connection {
                             }
                         }
                     }
-                    _ = idle_tick.tick() => {
-                        let idle = last_received.elapsed().as_secs();
-                        if idle >= 10 {
-                            debug!("No server messages received for {}s", idle);
+                    _ = keepalive_tick.tick() => {
+                        if !heard_from_server {
+                            continue;
+                        }
+                        let idle = last_received.elapsed();
+                        if idle >= KEEPALIVE_STALL_TIMEOUT {
+                            warn!("No server messages received for {}s, closing stalled connection", idle.as_secs());
+                            *close_reason.lock() = Some(CloseReason::Stalled);
+                            break;
+                        }
+                        if idle >= KEEPALIVE_PROBE_AFTER && !probe_sent {
+                            debug!("No server messages received for {}s, probing", idle.as_secs());
+                            probe_sent = true;
+                            if let Err(e) = transport.send(ProtocolMessage::List { List: None }).await {
+                                error!("Failed to send keep-alive probe: {}", e);
+                                break;
+                            }
+                            traffic.sent.fetch_add(1, Ordering::Relaxed);
                         }
                     }
                 }
@@ -188,13 +383,32 @@ impl Connection {
 
     /// Send a message to the server
     pub fn send(&self, message: ProtocolMessage) -> Result<()> {
-        if let Some(tx) = self.tx.lock().as_ref() {
-            tx.send(ConnectionCommand::Send(Box::new(message)))
-                .context("Failed to send message to connection")?;
-            Ok(())
-        } else {
+        if self.tx.lock().as_ref().is_none_or(|tx| tx.is_closed()) {
             anyhow::bail!("Not connected");
         }
+        let Some(message) = self.compat.lock().filter(message) else {
+            debug!("Dropped a message the server does not support");
+            return Ok(());
+        };
+        let outbound = self.outbound.lock().clone();
+        match outbound {
+            Some(outbound) => outbound.push(message),
+            None => anyhow::bail!("Not connected"),
+        }
+    }
+
+    /// Why the connection closed itself, if it did
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        *self.close_reason.lock()
+    }
+
+    /// Outbound queue depth and dropped State updates
+    pub fn metrics(&self) -> ConnectionMetrics {
+        self.outbound
+            .lock()
+            .as_ref()
+            .map(|outbound| outbound.metrics())
+            .unwrap_or_default()
     }
 
     /// Upgrade connection to TLS
@@ -222,6 +436,7 @@ impl Connection {
             let _ = tx.send(ConnectionCommand::Disconnect);
         }
         *self.tx.lock() = None;
+        *self.outbound.lock() = None;
         *self.state.lock() = ConnectionState::Disconnected;
     }
 
@@ -237,6 +452,11 @@ impl Connection {
     pub fn set_authenticated(&self) {
         *self.state.lock() = ConnectionState::Authenticated;
     }
+
+    /// Limit what `send` lets through to what the server understands
+    pub fn set_compat(&self, compat: ServerCompat) {
+        *self.compat.lock() = compat;
+    }
 }
 
 impl Default for Connection {
@@ -244,3 +464,281 @@ impl Default for Connection {
         Self::new()
     }
 }
+
+/// Resolve `host` and race its addresses, keeping the first to connect
+async fn connect_any(host: &str, port: u16, bind_address: Option<IpAddr>) -> Result<TcpStream> {
+    let resolved: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("Failed to resolve {}", host))?
+        // A socket bound to one family cannot reach the other
+        .filter(|address| bind_address.is_none_or(|bind| bind.is_ipv6() == address.is_ipv6()))
+        .collect();
+    if resolved.is_empty() {
+        match bind_address {
+            Some(bind) => anyhow::bail!("{} has no address reachable from {}", host, bind),
+            None => anyhow::bail!("{} has no addresses", host),
+        }
+    }
+    race_connect(resolved, bind_address).await
+}
+
+/// Try addresses happy-eyeballs style, alternating families, keeping the first to connect
+async fn race_connect(
+    addresses: Vec<SocketAddr>,
+    bind_address: Option<IpAddr>,
+) -> Result<TcpStream> {
+    let mut remaining = interleave_families(addresses);
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+    loop {
+        if attempts.is_empty() {
+            let Some(address) = remaining.pop_front() else {
+                break;
+            };
+            attempts.push(connect_attempt(address, bind_address));
+        }
+        tokio::select! {
+            Some((address, result)) = attempts.next() => match result {
+                Ok(stream) => {
+                    debug!("Connected to {}", address);
+                    return Ok(stream);
+                }
+                Err(e) => {
+                    debug!("Connection attempt to {} failed: {}", address, e);
+                    last_error = Some(e);
+                    if let Some(address) = remaining.pop_front() {
+                        attempts.push(connect_attempt(address, bind_address));
+                    }
+                }
+            },
+            _ = tokio::time::sleep(CONNECTION_ATTEMPT_DELAY), if !remaining.is_empty() => {
+                if let Some(address) = remaining.pop_front() {
+                    attempts.push(connect_attempt(address, bind_address));
+                }
+            }
+        }
+    }
+    Err(last_error
+        .map(anyhow::Error::from)
+        .unwrap_or_else(|| anyhow::anyhow!("No address connected")))
+}
+
+async fn connect_attempt(
+    address: SocketAddr,
+    bind_address: Option<IpAddr>,
+) -> (SocketAddr, std::io::Result<TcpStream>) {
+    let result = match bind_address {
+        Some(bind) => bound_connect(address, bind).await,
+        None => TcpStream::connect(address).await,
+    };
+    (address, result)
+}
+
+async fn bound_connect(address: SocketAddr, bind: IpAddr) -> std::io::Result<TcpStream> {
+    let socket = if address.is_ipv6() {
+        TcpSocket::new_v6()?
+    } else {
+        TcpSocket::new_v4()?
+    };
+    socket.bind(SocketAddr::new(bind, 0))?;
+    socket.connect(address).await
+}
+
+/// Alternate address families, starting with IPv6 when the host has it
+fn interleave_families(addresses: Vec<SocketAddr>) -> VecDeque<SocketAddr> {
+    let (mut v6, mut v4): (VecDeque<_>, VecDeque<_>) =
+        addresses.into_iter().partition(|address| address.is_ipv6());
+    let mut ordered = VecDeque::with_capacity(v6.len() + v4.len());
+    loop {
+        match (v6.pop_front(), v4.pop_front()) {
+            (None, None) => break,
+            (first, second) => ordered.extend(first.into_iter().chain(second)),
+        }
+    }
+    ordered
+}
+
+/// Write every queued message, most urgent lane first
+async fn flush_outbound(
+    transport: &mut Transport,
+    outbound: &OutboundQueue,
+    traffic: &TrafficCounters,
+) -> Result<()> {
+    while let Some(message) = outbound.pop() {
+        transport.send(message).await?;
+        traffic.sent.fetch_add(1, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::messages::{ChatMessage, PingInfo, PlayState};
+
+    fn chat(text: &str) -> ProtocolMessage {
+        ProtocolMessage::Chat {
+            Chat: ChatMessage::Text(text.to_string()),
+        }
+    }
+
+    fn state(position: f64) -> ProtocolMessage {
+        ProtocolMessage::State {
+            State: StateMessage {
+                playstate: Some(PlayState {
+                    position,
+                    paused: false,
+                    do_seek: None,
+                    set_by: None,
+                }),
+                ping: None,
+                ignoring_on_the_fly: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_outbound_queue_prioritizes_latest_state() {
+        let queue = OutboundQueue::new();
+        queue.push(chat("first")).unwrap();
+        queue.push(state(1.0)).unwrap();
+        queue.push(state(2.0)).unwrap();
+        let metrics = queue.metrics();
+        assert_eq!(metrics.queue_depth, 2);
+        assert_eq!(metrics.dropped_state_updates, 1);
+        let Some(ProtocolMessage::State { State: message }) = queue.pop() else {
+            panic!("State should jump the queue");
+        };
+        assert_eq!(
+            message.playstate.map(|playstate| playstate.position),
+            Some(2.0)
+        );
+        assert!(matches!(queue.pop(), Some(ProtocolMessage::Chat { .. })));
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_outbound_queue_heartbeat_keeps_pending_seek_and_echoes() {
+        let queue = OutboundQueue::new();
+        queue
+            .push(ProtocolMessage::State {
+                State: StateMessage {
+                    playstate: Some(PlayState {
+                        position: 600.0,
+                        paused: true,
+                        do_seek: Some(true),
+                        set_by: None,
+                    }),
+                    ping: None,
+                    ignoring_on_the_fly: Some(IgnoringInfo {
+                        server: Some(3),
+                        client: None,
+                    }),
+                },
+            })
+            .unwrap();
+        // A heartbeat sent while a client ignore is active carries no playstate
+        queue
+            .push(ProtocolMessage::State {
+                State: StateMessage {
+                    playstate: None,
+                    ping: Some(PingInfo {
+                        latency_calculation: Some(12.5),
+                        client_latency_calculation: None,
+                        client_rtt: None,
+                        server_rtt: None,
+                    }),
+                    ignoring_on_the_fly: Some(IgnoringInfo {
+                        server: None,
+                        client: Some(1),
+                    }),
+                },
+            })
+            .unwrap();
+        let Some(ProtocolMessage::State { State: message }) = queue.pop() else {
+            panic!("the merged State should be queued");
+        };
+        let playstate = message.playstate.expect("the seek should survive");
+        assert_eq!(playstate.position, 600.0);
+        assert!(playstate.paused);
+        assert_eq!(playstate.do_seek, Some(true));
+        let ignoring = message.ignoring_on_the_fly.unwrap();
+        assert_eq!(ignoring.server, Some(3));
+        assert_eq!(ignoring.client, Some(1));
+        assert_eq!(message.ping.unwrap().latency_calculation, Some(12.5));
+        assert!(queue.pop().is_none());
+
+        // A newer position still goes out as the seek that never did
+        let seek = |position| ProtocolMessage::State {
+            State: StateMessage {
+                playstate: Some(PlayState {
+                    position,
+                    paused: false,
+                    do_seek: Some(true),
+                    set_by: None,
+                }),
+                ping: None,
+                ignoring_on_the_fly: None,
+            },
+        };
+        queue.push(seek(300.0)).unwrap();
+        queue.push(state(300.5)).unwrap();
+        let Some(ProtocolMessage::State { State: message }) = queue.pop() else {
+            panic!("the merged State should be queued");
+        };
+        let playstate = message.playstate.unwrap();
+        assert_eq!(playstate.position, 300.5);
+        assert_eq!(playstate.do_seek, Some(true));
+    }
+
+    #[test]
+    fn test_interleave_families_prefers_ipv6() {
+        let addresses: Vec<SocketAddr> = ["192.0.2.1:8999", "192.0.2.2:8999", "[2001:db8::1]:8999"]
+            .iter()
+            .map(|address| address.parse().unwrap())
+            .collect();
+        let ordered: Vec<String> = interleave_families(addresses)
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        assert_eq!(
+            ordered,
+            ["[2001:db8::1]:8999", "192.0.2.1:8999", "192.0.2.2:8999"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_race_connect_falls_back_across_families() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // IPv6 is tried first and nothing listens there, or the host has no IPv6 at all
+        let addresses = vec![
+            SocketAddr::from(([127, 0, 0, 1], port)),
+            SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, port)),
+        ];
+        let stream = race_connect(addresses, None).await.unwrap();
+        assert_eq!(
+            stream.peer_addr().unwrap(),
+            SocketAddr::from(([127, 0, 0, 1], port))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_any_binds_local_address() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let bind: IpAddr = "127.0.0.1".parse().unwrap();
+        let stream = connect_any("127.0.0.1", port, Some(bind)).await.unwrap();
+        assert_eq!(stream.local_addr().unwrap().ip(), bind);
+    }
+
+    #[test]
+    fn test_outbound_queue_is_bounded() {
+        let queue = OutboundQueue::new();
+        for _ in 0..OUTBOUND_QUEUE_CAPACITY {
+            queue.push(chat("spam")).unwrap();
+        }
+        assert!(queue.push(chat("one too many")).is_err());
+        assert!(queue.push(state(0.0)).is_ok());
+    }
+}
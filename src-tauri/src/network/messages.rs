@@ -157,6 +157,9 @@ pub struct ClientFeatures {
     pub set_others_readiness: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ui_mode: Option<String>,
+    /// Minutes east of UTC; servers pass it on to other clients untouched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utc_offset: Option<i32>,
 }
 
 /// Set message - update settings
@@ -183,7 +186,7 @@ pub struct SetMessage {
     pub features: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -191,9 +194,12 @@ pub struct FileInfo {
     pub size: Option<FileSizeInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<f64>,
+    /// Set while the file is still growing on disk, as during a download
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloading: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum FileSizeInfo {
     Number(u64),
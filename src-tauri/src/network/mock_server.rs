@@ -0,0 +1,694 @@
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Duration, Instant};
+use tokio_util::codec::Framed;
+use tracing::{debug, info, warn};
+
+use super::messages::{
+    ChatMessage, HelloMessage, IgnoringInfo, ListResponse, PingInfo, PlayState, ProtocolMessage,
+    ReadyState, RoomInfo, SetMessage, StateMessage, TLSMessage, UserEvent, UserInfo, UserUpdate,
+};
+use super::protocol::SyncplayCodec;
+
+pub const DEMO_SERVER_PORT: u16 = 8999;
+const MOCK_SERVER_VERSION: &str = "1.2.255";
+const MOCK_SERVER_REALVERSION: &str = "1.7.3";
+const STATE_INTERVAL_MS: u64 = 1000;
+
+/// Behaviour switches for the mock server
+#[derive(Debug, Clone)]
+pub struct MockServerConfig {
+    pub port: u16,
+    pub motd: Option<String>,
+    pub state_interval: Duration,
+}
+
+impl Default for MockServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            motd: None,
+            state_interval: Duration::from_millis(STATE_INTERVAL_MS),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RoomPlayState {
+    position: f64,
+    paused: bool,
+    set_by: Option<String>,
+    updated_at: Instant,
+}
+
+impl RoomPlayState {
+    fn current_position(&self) -> f64 {
+        if self.paused {
+            self.position
+        } else {
+            self.position + self.updated_at.elapsed().as_secs_f64()
+        }
+    }
+}
+
+impl Default for RoomPlayState {
+    fn default() -> Self {
+        Self {
+            position: 0.0,
+            paused: true,
+            set_by: None,
+            updated_at: Instant::now(),
+        }
+    }
+}
+
+struct MockClient {
+    username: String,
+    room: String,
+    file: Option<super::messages::FileInfo>,
+    is_ready: Option<bool>,
+    tx: mpsc::UnboundedSender<ProtocolMessage>,
+    /// client ignoring-on-the-fly counter to echo back
+    client_ignore: Option<u32>,
+    last_client_latency: Option<f64>,
+}
+
+#[derive(Default)]
+struct MockServerState {
+    next_id: u64,
+    clients: HashMap<u64, MockClient>,
+    rooms: HashMap<String, RoomPlayState>,
+}
+
+impl MockServerState {
+    fn room_of(&self, id: u64) -> Option<String> {
+        self.clients.get(&id).map(|client| client.room.clone())
+    }
+
+    fn broadcast(&self, room: &str, message: &ProtocolMessage, except: Option<u64>) {
+        for (id, client) in &self.clients {
+            if client.room == room && Some(*id) != except {
+                let _ = client.tx.send(message.clone());
+            }
+        }
+    }
+
+    fn user_list(&self) -> ListResponse {
+        let mut rooms: ListResponse = HashMap::new();
+        for client in self.clients.values() {
+            rooms.entry(client.room.clone()).or_default().insert(
+                client.username.clone(),
+                UserInfo {
+                    file: client.file.clone(),
+                    controller: Some(false),
+                    is_ready: client.is_ready,
+                    features: None,
+                },
+            );
+        }
+        rooms
+    }
+}
+
+/// Minimal in-process Syncplay server speaking the JSON-line protocol
+pub struct MockServer {
+    addr: SocketAddr,
+    shutdown: watch::Sender<bool>,
+}
+
+impl MockServer {
+    /// Bind on localhost and start accepting clients
+    pub async fn start(config: MockServerConfig) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", config.port))
+            .await
+            .context("Failed to bind mock server")?;
+        let addr = listener.local_addr()?;
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let state = Arc::new(Mutex::new(MockServerState::default()));
+        info!("Mock Syncplay server listening on {}", addr);
+
+        let config = Arc::new(config);
+        let mut accept_shutdown = shutdown_rx.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => {
+                        let Ok((stream, peer)) = accepted else { continue };
+                        debug!("Mock server accepted {}", peer);
+                        tokio::spawn(handle_client(
+                            stream,
+                            state.clone(),
+                            config.clone(),
+                            shutdown_rx.clone(),
+                        ));
+                    }
+                    _ = accept_shutdown.changed() => break,
+                }
+            }
+        });
+
+        Ok(Self { addr, shutdown })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Drop every client and stop accepting new ones
+    pub fn shutdown(&self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    state: Arc<Mutex<MockServerState>>,
+    config: Arc<MockServerConfig>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut framed = Framed::new(stream, SyncplayCodec::new());
+    let (tx, mut rx) = mpsc::unbounded_channel::<ProtocolMessage>();
+    let mut client_id: Option<u64> = None;
+    let mut state_tick = tokio::time::interval(config.state_interval);
+
+    loop {
+        tokio::select! {
+            incoming = framed.next() => {
+                let message = match incoming {
+                    Some(Ok(message)) => message,
+                    Some(Err(e)) => {
+                        warn!("Mock server failed to decode message: {}", e);
+                        break;
+                    }
+                    None => break,
+                };
+                if let Some(reply) = handle_message(&state, &config, &tx, &mut client_id, message) {
+                    if framed.send(reply).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            Some(outgoing) = rx.recv() => {
+                if framed.send(outgoing).await.is_err() {
+                    break;
+                }
+            }
+            _ = state_tick.tick() => {
+                let Some(id) = client_id else { continue };
+                let Some(message) = build_state_message(&state, id) else { continue };
+                if framed.send(message).await.is_err() {
+                    break;
+                }
+            }
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    if let Some(id) = client_id {
+        disconnect_client(&state, id);
+    }
+}
+
+fn handle_message(
+    state: &Arc<Mutex<MockServerState>>,
+    config: &MockServerConfig,
+    tx: &mpsc::UnboundedSender<ProtocolMessage>,
+    client_id: &mut Option<u64>,
+    message: ProtocolMessage,
+) -> Option<ProtocolMessage> {
+    match message {
+        // TLS is never offered, so clients exercise their plain-TCP fallback
+        ProtocolMessage::TLS { .. } => Some(ProtocolMessage::TLS {
+            TLS: TLSMessage {
+                start_tls: Some("false".to_string()),
+            },
+        }),
+        ProtocolMessage::Hello { Hello } => {
+            let room = Hello
+                .room
+                .as_ref()
+                .map(|room| room.name.clone())
+                .unwrap_or_else(|| "default".to_string());
+            let mut guard = state.lock();
+            guard.next_id += 1;
+            let id = guard.next_id;
+            *client_id = Some(id);
+            guard.clients.insert(
+                id,
+                MockClient {
+                    username: Hello.username.clone(),
+                    room: room.clone(),
+                    file: None,
+                    is_ready: None,
+                    tx: tx.clone(),
+                    client_ignore: None,
+                    last_client_latency: None,
+                },
+            );
+            guard.rooms.entry(room.clone()).or_default();
+            guard.broadcast(
+                &room,
+                &user_set(&Hello.username, &room, None, Some(joined_event())),
+                Some(id),
+            );
+            Some(ProtocolMessage::Hello {
+                Hello: HelloMessage {
+                    username: Hello.username,
+                    password: None,
+                    room: Some(RoomInfo {
+                        name: room,
+                        password: None,
+                    }),
+                    version: MOCK_SERVER_VERSION.to_string(),
+                    realversion: MOCK_SERVER_REALVERSION.to_string(),
+                    features: Some(json!({
+                        "isolateRooms": false,
+                        "readiness": true,
+                        "managedRooms": false,
+                        "persistentRooms": false,
+                        "chat": true,
+                        "sharedPlaylists": true,
+                        "featureList": true,
+                        "maxChatMessageLength": 150,
+                        "maxUsernameLength": 150,
+                        "maxRoomNameLength": 35,
+                        "maxFilenameLength": 250,
+                    })),
+                    motd: config.motd.clone(),
+                },
+            })
+        }
+        ProtocolMessage::List { .. } => Some(ProtocolMessage::List {
+            List: Some(state.lock().user_list()),
+        }),
+        ProtocolMessage::Chat { Chat } => {
+            let id = (*client_id)?;
+            let message = match Chat {
+                ChatMessage::Text(message) => message,
+                ChatMessage::Entry { message, .. } => message,
+            };
+            let guard = state.lock();
+            let client = guard.clients.get(&id)?;
+            let chat = ProtocolMessage::Chat {
+                Chat: ChatMessage::Entry {
+                    username: client.username.clone(),
+                    message,
+                },
+            };
+            guard.broadcast(&client.room, &chat, None);
+            None
+        }
+        ProtocolMessage::State { State } => {
+            let id = (*client_id)?;
+            handle_client_state(state, id, State);
+            None
+        }
+        ProtocolMessage::Set { Set } => {
+            let id = (*client_id)?;
+            handle_client_set(state, id, *Set);
+            None
+        }
+        ProtocolMessage::Error { .. } => None,
+    }
+}
+
+fn handle_client_state(state: &Arc<Mutex<MockServerState>>, id: u64, message: StateMessage) {
+    let mut guard = state.lock();
+    let Some(client) = guard.clients.get_mut(&id) else {
+        return;
+    };
+    if let Some(ping) = message.ping.as_ref() {
+        client.last_client_latency = ping.client_latency_calculation;
+    }
+    let client_ignore = message
+        .ignoring_on_the_fly
+        .as_ref()
+        .and_then(|ignoring| ignoring.client);
+    if client_ignore.is_some() {
+        client.client_ignore = client_ignore;
+    }
+    let username = client.username.clone();
+    let room = client.room.clone();
+    let Some(playstate) = message.playstate else {
+        return;
+    };
+    // Only explicit changes move the room; plain position reports are
+    // answered by the next periodic State
+    if client_ignore.is_none() {
+        return;
+    }
+    let room_state = guard.rooms.entry(room.clone()).or_default();
+    *room_state = RoomPlayState {
+        position: playstate.position,
+        paused: playstate.paused,
+        set_by: Some(username),
+        updated_at: Instant::now(),
+    };
+    let do_seek = playstate.do_seek;
+    let ids: Vec<u64> = guard
+        .clients
+        .iter()
+        .filter(|(other, client)| **other != id && client.room == room)
+        .map(|(other, _)| *other)
+        .collect();
+    for other in ids {
+        if let Some(mut message) = build_state_message_locked(&guard, other) {
+            if let ProtocolMessage::State { State } = &mut message {
+                if let Some(playstate) = State.playstate.as_mut() {
+                    playstate.do_seek = do_seek;
+                }
+            }
+            if let Some(client) = guard.clients.get(&other) {
+                let _ = client.tx.send(message);
+            }
+        }
+    }
+}
+
+fn handle_client_set(state: &Arc<Mutex<MockServerState>>, id: u64, set: SetMessage) {
+    let mut guard = state.lock();
+    let Some(username) = guard.clients.get(&id).map(|c| c.username.clone()) else {
+        return;
+    };
+    if let Some(room) = set.room {
+        let old_room = guard.room_of(id).unwrap_or_default();
+        if let Some(client) = guard.clients.get_mut(&id) {
+            client.room = room.name.clone();
+        }
+        guard.rooms.entry(room.name.clone()).or_default();
+        let update = user_set(&username, &room.name, None, None);
+        guard.broadcast(&old_room, &update, None);
+        guard.broadcast(&room.name, &update, Some(id));
+    }
+    let Some(room) = guard.room_of(id) else {
+        return;
+    };
+    if let Some(file) = set.file {
+        if let Some(client) = guard.clients.get_mut(&id) {
+            client.file = Some(file.clone());
+        }
+        guard.broadcast(&room, &user_set(&username, &room, Some(file), None), None);
+    }
+    if let Some(ready) = set.ready {
+        let target = ready.username.clone().unwrap_or_else(|| username.clone());
+        let is_ready = ready.is_ready;
+        if let Some(client) = guard.clients.values_mut().find(|c| c.username == target) {
+            client.is_ready = is_ready;
+        }
+        let message = set_message(SetMessage {
+            ready: Some(ReadyState {
+                username: Some(target.clone()),
+                is_ready,
+                manually_initiated: ready.manually_initiated,
+                set_by: (target != username).then(|| username.clone()),
+            }),
+            ..empty_set()
+        });
+        guard.broadcast(&room, &message, None);
+    }
+    if let Some(mut change) = set.playlist_change {
+        change.user = Some(username.clone());
+        let message = set_message(SetMessage {
+            playlist_change: Some(change),
+            ..empty_set()
+        });
+        guard.broadcast(&room, &message, None);
+    }
+    if let Some(mut index) = set.playlist_index {
+        index.user = Some(username.clone());
+        let message = set_message(SetMessage {
+            playlist_index: Some(index),
+            ..empty_set()
+        });
+        guard.broadcast(&room, &message, None);
+    }
+}
+
+fn disconnect_client(state: &Arc<Mutex<MockServerState>>, id: u64) {
+    let mut guard = state.lock();
+    if let Some(client) = guard.clients.remove(&id) {
+        let left = UserEvent {
+            joined: None,
+            left: Some(true),
+            extra: HashMap::new(),
+        };
+        guard.broadcast(
+            &client.room,
+            &user_set(&client.username, &client.room, None, Some(left)),
+            None,
+        );
+    }
+}
+
+fn build_state_message(state: &Arc<Mutex<MockServerState>>, id: u64) -> Option<ProtocolMessage> {
+    let mut guard = state.lock();
+    let message = build_state_message_locked(&guard, id);
+    // Each acknowledgement is only echoed once
+    if let Some(client) = guard.clients.get_mut(&id) {
+        client.client_ignore = None;
+    }
+    message
+}
+
+fn build_state_message_locked(guard: &MockServerState, id: u64) -> Option<ProtocolMessage> {
+    let client = guard.clients.get(&id)?;
+    let room_state = guard.rooms.get(&client.room).cloned().unwrap_or_default();
+    Some(ProtocolMessage::State {
+        State: StateMessage {
+            playstate: Some(PlayState {
+                position: room_state.current_position(),
+                paused: room_state.paused,
+                do_seek: None,
+                set_by: room_state.set_by,
+            }),
+            ping: Some(PingInfo {
+                latency_calculation: Some(crate::network::ping::PingService::new_timestamp()),
+                client_latency_calculation: client.last_client_latency,
+                client_rtt: None,
+                server_rtt: Some(0.0),
+            }),
+            ignoring_on_the_fly: client.client_ignore.map(|client| IgnoringInfo {
+                server: None,
+                client: Some(client),
+            }),
+        },
+    })
+}
+
+fn joined_event() -> UserEvent {
+    UserEvent {
+        joined: Some(true),
+        left: None,
+        extra: HashMap::new(),
+    }
+}
+
+fn empty_set() -> SetMessage {
+    SetMessage {
+        room: None,
+        file: None,
+        user: None,
+        ready: None,
+        playlist_index: None,
+        playlist_change: None,
+        controller_auth: None,
+        new_controlled_room: None,
+        features: None,
+    }
+}
+
+fn set_message(set: SetMessage) -> ProtocolMessage {
+    ProtocolMessage::Set { Set: Box::new(set) }
+}
+
+fn user_set(
+    username: &str,
+    room: &str,
+    file: Option<super::messages::FileInfo>,
+    event: Option<UserEvent>,
+) -> ProtocolMessage {
+    let update = UserUpdate {
+        room: Some(RoomInfo {
+            name: room.to_string(),
+            password: None,
+        }),
+        file,
+        event,
+        controller: None,
+        is_ready: None,
+        features: None,
+    };
+    set_message(SetMessage {
+        user: Some(HashMap::from([(username.to_string(), update)])),
+        ..empty_set()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::connection::Connection;
+
+    async fn next_message(rx: &mut mpsc::UnboundedReceiver<ProtocolMessage>) -> ProtocolMessage {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for message")
+            .expect("connection closed")
+    }
+
+    /// Skip periodic State messages until something else arrives
+    async fn next_non_state(rx: &mut mpsc::UnboundedReceiver<ProtocolMessage>) -> ProtocolMessage {
+        loop {
+            let message = next_message(rx).await;
+            if !matches!(message, ProtocolMessage::State { .. }) {
+                return message;
+            }
+        }
+    }
+
+    fn hello(username: &str) -> ProtocolMessage {
+        ProtocolMessage::Hello {
+            Hello: HelloMessage {
+                username: username.to_string(),
+                password: None,
+                room: Some(RoomInfo {
+                    name: "lobby".to_string(),
+                    password: None,
+                }),
+                version: "1.2.255".to_string(),
+                realversion: "1.7.3".to_string(),
+                features: None,
+                motd: None,
+            },
+        }
+    }
+
+    async fn join(
+        server: &MockServer,
+        username: &str,
+    ) -> (Connection, mpsc::UnboundedReceiver<ProtocolMessage>) {
+        let connection = Connection::new();
+        let (mut rx, _) = connection
+            .connect("127.0.0.1".to_string(), server.port(), None)
+            .await
+            .expect("connect to mock server");
+        connection.send(hello(username)).unwrap();
+        let reply = next_non_state(&mut rx).await;
+        assert!(matches!(reply, ProtocolMessage::Hello { .. }));
+        (connection, rx)
+    }
+
+    #[tokio::test]
+    async fn test_tls_request_falls_back() {
+        let server = MockServer::start(MockServerConfig::default())
+            .await
+            .unwrap();
+        let connection = Connection::new();
+        let (mut rx, _) = connection
+            .connect("127.0.0.1".to_string(), server.port(), None)
+            .await
+            .unwrap();
+        connection
+            .send(ProtocolMessage::TLS {
+                TLS: TLSMessage {
+                    start_tls: Some("send".to_string()),
+                },
+            })
+            .unwrap();
+        let ProtocolMessage::TLS { TLS } = next_message(&mut rx).await else {
+            panic!("expected TLS reply");
+        };
+        assert_eq!(TLS.start_tls.as_deref(), Some("false"));
+    }
+
+    #[tokio::test]
+    async fn test_join_list_and_chat() {
+        let server = MockServer::start(MockServerConfig {
+            state_interval: Duration::from_secs(60),
+            ..MockServerConfig::default()
+        })
+        .await
+        .unwrap();
+        let (alice, mut alice_rx) = join(&server, "alice").await;
+        let (bob, mut bob_rx) = join(&server, "bob").await;
+
+        let ProtocolMessage::Set { Set } = next_non_state(&mut alice_rx).await else {
+            panic!("expected join notification");
+        };
+        assert!(Set.user.is_some_and(|users| users.contains_key("bob")));
+
+        bob.send(ProtocolMessage::List { List: None }).unwrap();
+        let ProtocolMessage::List { List: Some(rooms) } = next_non_state(&mut bob_rx).await else {
+            panic!("expected user list");
+        };
+        assert_eq!(rooms["lobby"].len(), 2);
+
+        alice
+            .send(ProtocolMessage::Chat {
+                Chat: ChatMessage::Text("hello".to_string()),
+            })
+            .unwrap();
+        let ProtocolMessage::Chat {
+            Chat: ChatMessage::Entry { username, message },
+        } = next_non_state(&mut bob_rx).await
+        else {
+            panic!("expected chat entry");
+        };
+        assert_eq!(username, "alice");
+        assert_eq!(message, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_and_leave_are_broadcast() {
+        let server = MockServer::start(MockServerConfig {
+            state_interval: Duration::from_secs(60),
+            ..MockServerConfig::default()
+        })
+        .await
+        .unwrap();
+        let (alice, mut alice_rx) = join(&server, "alice").await;
+        let (bob, _bob_rx) = join(&server, "bob").await;
+        let _ = next_non_state(&mut alice_rx).await;
+
+        bob.send(set_message(SetMessage {
+            ready: Some(ReadyState {
+                username: None,
+                is_ready: Some(true),
+                manually_initiated: Some(true),
+                set_by: None,
+            }),
+            ..empty_set()
+        }))
+        .unwrap();
+        let ProtocolMessage::Set { Set } = next_non_state(&mut alice_rx).await else {
+            panic!("expected ready update");
+        };
+        let ready = Set.ready.expect("ready state");
+        assert_eq!(ready.username.as_deref(), Some("bob"));
+        assert_eq!(ready.is_ready, Some(true));
+
+        bob.disconnect();
+        let ProtocolMessage::Set { Set } = next_non_state(&mut alice_rx).await else {
+            panic!("expected leave notification");
+        };
+        let users = Set.user.expect("user update");
+        assert_eq!(
+            users["bob"].event.as_ref().and_then(|event| event.left),
+            Some(true)
+        );
+        drop(alice);
+    }
+}
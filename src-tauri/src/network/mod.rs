@@ -1,5 +1,7 @@
+pub mod compat;
 pub mod connection;
 pub mod messages;
+pub mod mock_server;
 pub mod ping;
 pub mod protocol;
 pub mod tls;
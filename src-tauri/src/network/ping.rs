@@ -1,8 +1,23 @@
+use std::collections::VecDeque;
+
+/// Number of recent RTT samples kept for outlier detection
+const PING_WINDOW_SIZE: usize = 10;
+/// Samples needed before spikes are rejected
+const PING_MIN_SAMPLES_FOR_REJECTION: usize = 3;
+/// A sample this many times above the median is treated as a spike
+const PING_OUTLIER_FACTOR: f64 = 2.5;
+/// Spikes smaller than this (seconds) above the median are always accepted
+const PING_OUTLIER_MIN_SECONDS: f64 = 0.05;
+const PING_MOVING_AVERAGE_WEIGHT: f64 = 0.85;
+
 #[derive(Debug, Clone)]
 pub struct PingService {
     rtt: f64,
     fd: f64,
+    raw_fd: f64,
     avr_rtt: f64,
+    window: VecDeque<f64>,
+    clock_skew: Option<f64>,
 }
 
 impl Default for PingService {
@@ -10,7 +25,10 @@ impl Default for PingService {
         Self {
             rtt: 0.0,
             fd: 0.0,
+            raw_fd: 0.0,
             avr_rtt: 0.0,
+            window: VecDeque::with_capacity(PING_WINDOW_SIZE),
+            clock_skew: None,
         }
     }
 }
@@ -28,26 +46,148 @@ impl PingService {
             return;
         }
         let now = Self::new_timestamp();
-        self.rtt = now - timestamp;
+        self.record_rtt(now - timestamp, sender_rtt);
+    }
+
+    fn record_rtt(&mut self, rtt: f64, sender_rtt: f64) {
+        self.rtt = rtt;
         if self.rtt < 0.0 || sender_rtt < 0.0 {
             return;
         }
+        let outlier = self.is_outlier(rtt);
+        if self.window.len() == PING_WINDOW_SIZE {
+            self.window.pop_front();
+        }
+        self.window.push_back(rtt);
+
+        self.raw_fd = forward_delay(rtt, rtt, sender_rtt);
+        if outlier {
+            tracing::debug!("Ignoring RTT spike of {:.3}s", rtt);
+            return;
+        }
         if self.avr_rtt == 0.0 {
-            self.avr_rtt = self.rtt;
+            self.avr_rtt = rtt;
+        }
+        self.avr_rtt =
+            self.avr_rtt * PING_MOVING_AVERAGE_WEIGHT + rtt * (1.0 - PING_MOVING_AVERAGE_WEIGHT);
+        self.fd = forward_delay(self.avr_rtt, rtt, sender_rtt);
+    }
+
+    /// Estimate the local clock offset from a server timestamp
+    ///
+    /// Positive values mean the local clock is ahead of the server.
+    pub fn update_clock_skew(&mut self, server_timestamp: f64) -> Option<f64> {
+        self.record_clock_skew(Self::new_timestamp(), server_timestamp)
+    }
+
+    fn record_clock_skew(&mut self, local_now: f64, server_timestamp: f64) -> Option<f64> {
+        if server_timestamp <= 0.0 {
+            return None;
+        }
+        let skew = local_now - (server_timestamp + self.avr_rtt / 2.0);
+        self.clock_skew = Some(skew);
+        self.clock_skew
+    }
+
+    pub fn get_clock_skew(&self) -> Option<f64> {
+        self.clock_skew
+    }
+
+    /// Local unix time matching a server timestamp
+    pub fn server_to_local_time(&self, server_timestamp: f64) -> f64 {
+        server_timestamp + self.clock_skew.unwrap_or(0.0)
+    }
+
+    /// Server unix time matching a local timestamp
+    pub fn local_to_server_time(&self, local_timestamp: f64) -> f64 {
+        local_timestamp - self.clock_skew.unwrap_or(0.0)
+    }
+
+    fn is_outlier(&self, rtt: f64) -> bool {
+        if self.window.len() < PING_MIN_SAMPLES_FOR_REJECTION {
+            return false;
         }
-        self.avr_rtt = self.avr_rtt * 0.85 + self.rtt * (1.0 - 0.85);
-        if sender_rtt < self.rtt {
-            self.fd = self.avr_rtt / 2.0 + (self.rtt - sender_rtt);
-        } else {
-            self.fd = self.avr_rtt / 2.0;
+        let median = self.median_rtt();
+        rtt > median * PING_OUTLIER_FACTOR && rtt - median > PING_OUTLIER_MIN_SECONDS
+    }
+
+    /// Median of the recent RTT window
+    pub fn median_rtt(&self) -> f64 {
+        if self.window.is_empty() {
+            return 0.0;
         }
+        let mut sorted: Vec<f64> = self.window.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let len = sorted.len();
+        (sorted[(len - 1) / 2] + sorted[len / 2]) / 2.0
     }
 
+    /// Forward delay based on the filtered RTT
     pub fn get_last_forward_delay(&self) -> f64 {
         self.fd
     }
 
+    /// Forward delay based on the last RTT sample only
+    pub fn get_raw_forward_delay(&self) -> f64 {
+        self.raw_fd
+    }
+
     pub fn get_rtt(&self) -> f64 {
         self.rtt
     }
+
+    pub fn get_filtered_rtt(&self) -> f64 {
+        self.avr_rtt
+    }
+}
+
+fn forward_delay(average_rtt: f64, rtt: f64, sender_rtt: f64) -> f64 {
+    if sender_rtt < rtt {
+        average_rtt / 2.0 + (rtt - sender_rtt)
+    } else {
+        average_rtt / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_rejects_spikes() {
+        let mut ping = PingService::default();
+        for _ in 0..5 {
+            ping.record_rtt(0.1, 0.1);
+        }
+        let filtered = ping.get_filtered_rtt();
+        ping.record_rtt(1.5, 0.1);
+        assert_eq!(ping.get_rtt(), 1.5);
+        assert_eq!(ping.get_filtered_rtt(), filtered);
+        assert!(ping.get_raw_forward_delay() > ping.get_last_forward_delay());
+    }
+
+    #[test]
+    fn test_ping_clock_skew_accounts_for_delay() {
+        let mut ping = PingService::default();
+        ping.record_rtt(0.2, 0.2);
+        let skew = ping.record_clock_skew(1030.1, 1000.0).unwrap();
+        assert!((skew - 30.0).abs() < 1e-9);
+        assert_eq!(ping.record_clock_skew(1000.0, 0.0), None);
+        assert_eq!(ping.get_clock_skew(), Some(skew));
+        assert!((ping.server_to_local_time(2000.0) - 2030.0).abs() < 1e-9);
+        assert!((ping.local_to_server_time(2030.0) - 2000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ping_adopts_sustained_latency_change() {
+        let mut ping = PingService::default();
+        for _ in 0..4 {
+            ping.record_rtt(0.05, 0.05);
+        }
+        for _ in 0..PING_WINDOW_SIZE {
+            ping.record_rtt(0.4, 0.4);
+        }
+        assert!((ping.median_rtt() - 0.4).abs() < f64::EPSILON);
+        assert!(ping.get_filtered_rtt() > 0.2);
+    }
 }
@@ -82,6 +82,7 @@ mod tests {
             feature_list: Some(true),
             set_others_readiness: Some(true),
             ui_mode: Some("GUI".to_string()),
+            utc_offset: None,
         };
         let hello = ProtocolMessage::Hello {
             Hello: HelloMessage {
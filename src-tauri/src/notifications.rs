@@ -0,0 +1,42 @@
+// Notifications module
+// Draws the user's attention to chat events while the window is in the background
+
+use tauri::{Manager, UserAttentionType};
+
+use crate::app_state::AppState;
+
+/// Play the mention sound and flash the taskbar, as configured
+pub fn notify_mention(state: &AppState) {
+    if *state.do_not_disturb.lock() {
+        return;
+    }
+    let (sound, flash) = {
+        let config = state.config.lock();
+        (config.user.mention_sound, config.user.mention_flash_taskbar)
+    };
+    if sound {
+        // Playback happens in the frontend, which already owns an audio context
+        state.emit_event(
+            "play-notification-sound",
+            serde_json::json!({ "kind": "mention" }),
+        );
+    }
+    if flash {
+        request_attention(state);
+    }
+}
+
+fn request_attention(state: &AppState) {
+    let Some(handle) = state.app_handle.lock().clone() else {
+        return;
+    };
+    let Some(window) = handle.get_webview_window("main") else {
+        return;
+    };
+    if window.is_focused().unwrap_or(false) {
+        return;
+    }
+    if let Err(e) = window.request_user_attention(Some(UserAttentionType::Informational)) {
+        tracing::warn!("Failed to request user attention: {}", e);
+    }
+}
@@ -1,5 +1,10 @@
+use super::idle::IdleScreenText;
 use super::properties::PlayerState;
+use crate::config::{OsdPosition, UserPreferences};
 use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Notify;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerKind {
@@ -67,22 +72,252 @@ pub fn default_player_path_for_kind(kind: PlayerKind) -> &'static str {
     }
 }
 
+/// What a player backend can do, so callers skip what it cannot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BackendCapabilities {
+    pub speed_control: bool,
+    pub osd: bool,
+    /// Chat messages get their own overlay instead of the plain OSD
+    pub chat_display: bool,
+    pub chapters: bool,
+    pub track_switching: bool,
+    /// A second subtitle track can be shown next to the first
+    pub multiple_subtitles: bool,
+}
+
+impl BackendCapabilities {
+    /// Capabilities of the built-in backend for `kind`
+    pub fn for_kind(kind: PlayerKind) -> Self {
+        match kind {
+            PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina => Self {
+                speed_control: true,
+                osd: true,
+                chat_display: true,
+                chapters: true,
+                track_switching: true,
+                multiple_subtitles: true,
+            },
+            PlayerKind::Vlc | PlayerKind::Mplayer => Self {
+                speed_control: true,
+                osd: true,
+                ..Self::default()
+            },
+            // MPC's slave API has no usable rate control
+            PlayerKind::MpcHc | PlayerKind::MpcBe => Self {
+                osd: true,
+                ..Self::default()
+            },
+            PlayerKind::Unknown => Self::default(),
+        }
+    }
+}
+
+/// Presentation of a single OSD message
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsdStyle {
+    pub position: OsdPosition,
+    /// Text color as `#RRGGBB`; `None` keeps the player default
+    pub color: Option<String>,
+    pub duration_ms: u64,
+    pub font_scale: f64,
+}
+
+impl OsdStyle {
+    pub fn from_config(user: &UserPreferences) -> Self {
+        Self {
+            position: user.osd_position,
+            color: user.osd_color.clone(),
+            duration_ms: user.osd_duration,
+            font_scale: user.osd_font_scale,
+        }
+    }
+
+    pub fn with_duration(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+
+    /// Color as an ASS `&HBBGGRR&` value
+    pub fn ass_color(&self) -> Option<String> {
+        let color = self.color.as_deref()?.strip_prefix('#')?;
+        if color.len() != 6 || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(format!(
+            "&H{}{}{}&",
+            &color[4..6],
+            &color[2..4],
+            &color[0..2]
+        ))
+    }
+
+    /// ASS override block applying position, color and scale
+    pub fn ass_tags(&self) -> String {
+        let scale = (self.font_scale * 100.0).round();
+        let mut tags = format!(
+            "{{\\an{}\\fscx{}\\fscy{}",
+            ass_alignment(self.position),
+            scale,
+            scale
+        );
+        if let Some(color) = self.ass_color() {
+            tags.push_str("\\1c");
+            tags.push_str(&color);
+        }
+        tags.push('}');
+        tags
+    }
+}
+
+impl Default for OsdStyle {
+    fn default() -> Self {
+        Self {
+            position: OsdPosition::TopRight,
+            color: None,
+            duration_ms: 3000,
+            font_scale: 1.0,
+        }
+    }
+}
+
+/// ASS numpad alignment (`\an`) for an OSD position
+pub fn ass_alignment(position: OsdPosition) -> u8 {
+    match position {
+        OsdPosition::BottomLeft => 1,
+        OsdPosition::Bottom => 2,
+        OsdPosition::BottomRight => 3,
+        OsdPosition::Left => 4,
+        OsdPosition::Center => 5,
+        OsdPosition::Right => 6,
+        OsdPosition::TopLeft => 7,
+        OsdPosition::Top => 8,
+        OsdPosition::TopRight => 9,
+    }
+}
+
+/// Chapter marker of the loaded file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start: f64,
+}
+
+/// Chat line as shown on the player OSD, e.g. `<alice> hello`
+pub fn format_chat_osd(username: Option<&str>, message: &str) -> String {
+    match username {
+        Some(name) => format!("<{}> {}", name, message),
+        None => message.to_string(),
+    }
+}
+
 #[async_trait]
 pub trait PlayerBackend: Send + Sync {
     fn kind(&self) -> PlayerKind;
     fn name(&self) -> &'static str;
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::for_kind(self.kind())
+    }
     fn get_state(&self) -> PlayerState;
     async fn poll_state(&self) -> anyhow::Result<()>;
     async fn set_position(&self, position: f64) -> anyhow::Result<()>;
     async fn set_paused(&self, paused: bool) -> anyhow::Result<()>;
     async fn set_speed(&self, speed: f64) -> anyhow::Result<()>;
     async fn load_file(&self, path: &str) -> anyhow::Result<()>;
+    /// Shift audio against video; positive values play the audio later
+    async fn set_audio_delay(&self, _ms: i64) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support audio delay", self.name())
+    }
+    /// Shift subtitles against video; positive values show them later
+    async fn set_sub_delay(&self, _ms: i64) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support subtitle delay", self.name())
+    }
+    /// Step one frame forward or back, leaving the player paused
+    async fn frame_step(&self, _backwards: bool) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support frame stepping", self.name())
+    }
+    /// Chapters of the loaded file, empty when the player cannot report them
+    async fn get_chapters(&self) -> anyhow::Result<Vec<Chapter>> {
+        Ok(Vec::new())
+    }
     fn mark_reset(&self, _is_stream: bool) {}
-    fn show_osd(&self, text: &str, duration_ms: Option<u64>) -> anyhow::Result<()>;
-    fn show_chat_message(&self, _username: Option<&str>, _message: &str) -> anyhow::Result<()> {
+    /// False once the connection to the player is gone for good
+    fn is_alive(&self) -> bool {
+        true
+    }
+    /// Round trip to the player for the health watchdog; polling answers it by default
+    async fn ping(&self) -> anyhow::Result<()> {
+        self.poll_state().await
+    }
+    /// Signal fired when the player pushes a state change; `None` means the
+    /// backend has to be polled
+    fn state_notifier(&self) -> Option<Arc<Notify>> {
+        None
+    }
+    fn show_osd(&self, text: &str, style: &OsdStyle) -> anyhow::Result<()>;
+    /// Show an incoming chat message; players without a chat overlay use the OSD
+    fn show_chat_message(
+        &self,
+        username: Option<&str>,
+        message: &str,
+        style: &OsdStyle,
+    ) -> anyhow::Result<()> {
+        self.show_osd(&format_chat_osd(username, message), style)
+    }
+    /// Show the generated idle view over `IDLE_SOURCE`, or hide it with `None`
+    ///
+    /// Only players that can open the generated source need it; the rest fall back
+    /// to the placeholder image.
+    fn show_idle_screen(&self, _text: Option<&IdleScreenText>) -> anyhow::Result<()> {
         Ok(())
     }
+    /// Whether the player can open `IDLE_SOURCE`
+    fn supports_idle_source(&self) -> bool {
+        false
+    }
+    /// Apply changed settings to the running player
+    ///
+    /// `arguments` holds the launch arguments when they changed. Returns false when
+    /// some settings only take effect once the player restarts; OSD styling is read
+    /// for every message, so players without more to push need nothing else.
+    async fn reload_settings(&self, arguments: Option<&[String]>) -> anyhow::Result<bool> {
+        Ok(arguments.is_none())
+    }
     async fn shutdown(&self) -> anyhow::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_for_kind() {
+        let mpv = BackendCapabilities::for_kind(PlayerKind::Iina);
+        assert!(mpv.speed_control && mpv.chat_display && mpv.chapters);
+        let mpc = BackendCapabilities::for_kind(PlayerKind::MpcBe);
+        assert!(!mpc.speed_control);
+        assert!(mpc.osd);
+        assert_eq!(
+            BackendCapabilities::for_kind(PlayerKind::Unknown),
+            BackendCapabilities::default()
+        );
+    }
+
+    #[test]
+    fn test_format_chat_osd() {
+        assert_eq!(format_chat_osd(Some("alice"), "hi"), "<alice> hi");
+        assert_eq!(format_chat_osd(None, "server notice"), "server notice");
+    }
+
+    #[test]
+    fn test_osd_style_ass_tags() {
+        let style = OsdStyle {
+            color: Some("#112233".to_string()),
+            font_scale: 1.5,
+            ..OsdStyle::default()
+        };
+        assert_eq!(style.ass_color().as_deref(), Some("&H332211&"));
+        assert_eq!(style.ass_tags(), "{\\an9\\fscx150\\fscy150\\1c&H332211&}");
+    }
+}
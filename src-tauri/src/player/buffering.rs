@@ -0,0 +1,126 @@
+// Buffering module
+// Notices when a streamed file stalls to rebuffer and when it recovers
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::properties::PlayerState;
+
+/// How long playback must stall before it counts as rebuffering; seeks stall briefly too
+pub const BUFFERING_GRACE: Duration = Duration::from_secs(1);
+
+/// Payload of the `buffering` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferingEvent {
+    pub buffering: bool,
+    /// Progress towards resuming, in percent
+    pub percent: Option<f64>,
+    /// Seconds buffered ahead of the playback position
+    pub cache_duration: Option<f64>,
+}
+
+impl BufferingEvent {
+    pub fn from_state(buffering: bool, player_state: &PlayerState) -> Self {
+        Self {
+            buffering,
+            percent: player_state.cache_buffering,
+            cache_duration: player_state.cache_duration,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferingChange {
+    Started,
+    Progressed,
+    Finished,
+}
+
+#[derive(Debug, Default)]
+pub struct BufferingTracker {
+    stalled_since: Option<Instant>,
+    buffering: bool,
+    last_percent: Option<f64>,
+}
+
+impl BufferingTracker {
+    /// Feed whether playback is stalled on the cache, and the rebuffering progress
+    pub fn update(
+        &mut self,
+        stalled: bool,
+        percent: Option<f64>,
+        now: Instant,
+    ) -> Option<BufferingChange> {
+        if !stalled {
+            self.stalled_since = None;
+            self.last_percent = None;
+            return std::mem::take(&mut self.buffering).then_some(BufferingChange::Finished);
+        }
+        let since = *self.stalled_since.get_or_insert(now);
+        if !self.buffering {
+            if now.duration_since(since) < BUFFERING_GRACE {
+                return None;
+            }
+            self.buffering = true;
+            self.last_percent = percent;
+            return Some(BufferingChange::Started);
+        }
+        if percent != self.last_percent {
+            self.last_percent = percent;
+            return Some(BufferingChange::Progressed);
+        }
+        None
+    }
+
+    pub fn is_buffering(&self) -> bool {
+        self.buffering
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_stalls_are_not_buffering() {
+        let mut tracker = BufferingTracker::default();
+        let start = Instant::now();
+        assert_eq!(tracker.update(true, Some(0.0), start), None);
+        assert_eq!(
+            tracker.update(true, Some(10.0), start + Duration::from_millis(500)),
+            None
+        );
+        assert_eq!(
+            tracker.update(false, None, start + Duration::from_millis(800)),
+            None
+        );
+        assert!(!tracker.is_buffering());
+    }
+
+    #[test]
+    fn test_reports_start_progress_and_finish() {
+        let mut tracker = BufferingTracker::default();
+        let start = Instant::now();
+        tracker.update(true, Some(0.0), start);
+        let later = start + BUFFERING_GRACE;
+        assert_eq!(
+            tracker.update(true, Some(20.0), later),
+            Some(BufferingChange::Started)
+        );
+        assert_eq!(tracker.update(true, Some(20.0), later), None);
+        assert_eq!(
+            tracker.update(true, Some(60.0), later),
+            Some(BufferingChange::Progressed)
+        );
+        assert_eq!(
+            tracker.update(false, Some(100.0), later),
+            Some(BufferingChange::Finished)
+        );
+        assert_eq!(tracker.update(false, None, later), None);
+    }
+}
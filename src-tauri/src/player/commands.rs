@@ -134,6 +134,24 @@ impl MpvCommand {
         }
     }
 
+    /// Create an osd-overlay command; `None` removes the overlay
+    pub fn osd_overlay(id: u64, ass_events: Option<&str>) -> Self {
+        let format = if ass_events.is_some() {
+            "ass-events"
+        } else {
+            "none"
+        };
+        Self {
+            command: vec![
+                Value::String("osd-overlay".to_string()),
+                Value::Number(id.into()),
+                Value::String(format.to_string()),
+                Value::String(ass_events.unwrap_or_default().to_string()),
+            ],
+            request_id: None,
+        }
+    }
+
     /// Create a cycle command (for pause/unpause)
     pub fn cycle(property: &str, request_id: u64) -> Self {
         Self {
@@ -145,6 +163,19 @@ impl MpvCommand {
         }
     }
 
+    /// Create a frame-step or frame-back-step command
+    pub fn frame_step(backwards: bool, request_id: u64) -> Self {
+        let name = if backwards {
+            "frame-back-step"
+        } else {
+            "frame-step"
+        };
+        Self {
+            command: vec![Value::String(name.to_string())],
+            request_id: Some(request_id),
+        }
+    }
+
     /// Create a quit command
     pub fn quit() -> Self {
         Self {
@@ -1,20 +1,35 @@
-use crate::app_state::{AppState, PlayerStateEvent};
+use crate::app_state::{
+    AppState, PendingUrlLoad, PlayerStateEvent, SeekConfirmationRequest, UntrustedUrlRequest,
+};
+use crate::client::endgame::MemberProgress;
+use crate::client::file_update::FileUpdateAction;
+use crate::client::media_resolver::file_size;
+use crate::client::protocol::SUGGESTION_CHAT_PREFIX;
+use crate::client::room_control::Suggestion;
+use crate::client::session_actor::SessionCommand;
+use crate::client::state::User;
 use crate::commands::playlist::{
     apply_playlist_index_from_server, change_playlist_from_filename, send_playlist_index,
     shared_playlists_enabled,
 };
-use crate::config::{SyncplayConfig, UnpauseAction};
+use crate::config::{
+    IdleScreen, PlaylistAdvanceWait, PlaylistMode, SyncplayConfig, UnpauseAction, WatchdogPolicy,
+};
 use crate::network::messages::{FileInfo, PlayState, ProtocolMessage, ReadyState, SetMessage};
 use crate::player::backend::{player_kind_from_path_or_default, PlayerBackend, PlayerKind};
-use crate::player::mpc_api::MpcApiBackend;
-use crate::player::mplayer_slave::MplayerBackend;
-use crate::player::mpv_backend::MpvBackend;
+use crate::player::buffering::{BufferingChange, BufferingEvent, BufferingTracker};
+use crate::player::events::MpvPlayerEvent;
+use crate::player::idle::{is_idle_source, IdleScreenText, IDLE_SOURCE};
 use crate::player::mpv_ipc::MpvIpc;
+use crate::player::mpv_profile::profile_args;
 use crate::player::properties::PlayerState;
-use crate::player::vlc_syncplay::VlcSyncplayBackend;
+use crate::player::registry::PlayerLaunch;
+use crate::player::sandbox::player_command;
+use crate::player::stall::{StallChange, StallTracker};
+use crate::player::watchdog::{PlayerHealthEvent, PlayerHealthStatus, PlayerWatchdog};
 use crate::utils::{
-    apply_privacy, is_music_file, is_trustable_and_trusted, is_url, same_filename, truncate_text,
-    PRIVACY_HIDDEN_FILENAME,
+    apply_privacy, format_time, is_music_file, is_trustable_and_trusted, is_url, resolve_privacy,
+    same_filename, truncate_text, PrivacyContext, ResolvedPrivacy, PRIVACY_HIDDEN_FILENAME,
 };
 use regex::Regex;
 use std::path::{Path, PathBuf};
@@ -24,8 +39,8 @@ use std::time::Instant;
 use tauri::Manager;
 #[cfg(unix)]
 use tempfile::Builder;
-use tokio::process::Command;
-use tokio::time::{sleep, Duration};
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration, Interval, MissedTickBehavior};
 use tracing::info;
 use url::Url;
 
@@ -35,11 +50,31 @@ const RECENT_ADVANCE_GRACE_SECONDS: f64 = 8.0;
 const LAST_PAUSED_DIFF_THRESHOLD_SECONDS: f64 = 2.0;
 const PLAYLIST_LOAD_NEXT_FILE_MINIMUM_LENGTH: f64 = 10.0;
 const PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD: f64 = 5.0;
+const AUTO_READY_CHECK_INTERVAL_SECONDS: f64 = 1.0;
 const DOUBLE_CHECK_REWIND: bool = true;
 const DOUBLE_CHECK_REWIND_POSITION_THRESHOLD: f64 = 5.0;
 const DOUBLE_CHECK_REWIND_DELAYS: [f64; 3] = [0.5, 1.0, 1.5];
 const RECENT_REWIND_FILE_UPDATE_SHIFT_SECONDS: f64 = 4.5;
 const FILE_UPDATE_AFTER_LOAD_DELAY_MS: u64 = 200;
+const FILE_GROWTH_CHECK_DELAY: Duration = Duration::from_millis(500);
+const DOWNLOAD_RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+const PLAYER_POLL_INTERVAL_MS: u64 = 100;
+const EVENT_DRIVEN_FALLBACK_INTERVAL_MS: u64 = 500;
+const PLAYER_POLL_TIMEOUT: Duration = Duration::from_secs(1);
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(2);
+const WATCHDOG_PING_TIMEOUT: Duration = Duration::from_secs(2);
+const PLAYER_EXIT_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+const RESTORE_FILE_LOAD_TIMEOUT: Duration = Duration::from_secs(10);
+const RESTORE_FILE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const MPV_IPC_CONNECT_ATTEMPTS: u32 = 10;
+pub(crate) const IINA_SOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a player we were talking to went away
+enum PlayerExit {
+    /// Closed on purpose, or a player we never launched
+    Clean,
+    Crashed(String),
+}
 
 struct PlayerConnectingGuard<'a> {
     flag: &'a parking_lot::Mutex<bool>,
@@ -74,8 +109,11 @@ pub async fn ensure_player_connected(state: &Arc<AppState>) -> Result<(), String
     let player_path = resolve_player_path(&config);
     let kind = player_kind_from_path_or_default(&player_path);
     let args = build_player_arguments(&config, &player_path);
-    let socket_path = ensure_mpv_socket_path(state)?;
-    let syncplayintf_path = resolve_syncplayintf_path(state);
+    let factory = state
+        .player_registry
+        .lock()
+        .find(&player_path)
+        .ok_or_else(|| format!("Unsupported player path: {}", player_path))?;
     {
         let mut process_guard = state.player_process.lock();
         if let Some(child) = process_guard.as_mut() {
@@ -85,155 +123,27 @@ pub async fn ensure_player_connected(state: &Arc<AppState>) -> Result<(), String
         }
     }
 
-    let should_spawn = should_spawn_player(state, kind);
-    if should_spawn {
-        *state.last_player_spawn.lock() = Some(Instant::now());
-        *state.last_player_kind.lock() = Some(kind);
-    }
-    let (backend, child) = match kind {
-        PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina => {
-            let mut child = None;
-            if should_spawn {
-                if kind == PlayerKind::Iina {
-                    let mut last_error = None;
-                    for _ in 0..3 {
-                        let spawned = start_mpv_process_if_needed(
-                            state,
-                            &player_path,
-                            kind,
-                            &args,
-                            &socket_path,
-                            syncplayintf_path.as_ref(),
-                        )?;
-                        match spawned {
-                            Some(mut spawned_child) => {
-                                match wait_for_ipc_socket(
-                                    &mut spawned_child,
-                                    &socket_path,
-                                    Duration::from_secs(10),
-                                )
-                                .await
-                                {
-                                    Ok(()) => {
-                                        child = Some(spawned_child);
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        last_error = Some(e);
-                                        let _ = spawned_child.kill().await;
-                                        let _ = spawned_child.wait().await;
-                                    }
-                                }
-                            }
-                            None => {
-                                child = None;
-                                break;
-                            }
-                        }
-                        sleep(Duration::from_millis(200)).await;
-                    }
-                    if child.is_none() {
-                        if let Some(error) = last_error {
-                            return Err(error);
-                        }
-                    }
-                } else {
-                    child = start_mpv_process_if_needed(
-                        state,
-                        &player_path,
-                        kind,
-                        &args,
-                        &socket_path,
-                        syncplayintf_path.as_ref(),
-                    )?;
-                }
+    let launch = PlayerLaunch {
+        state,
+        player_path: &player_path,
+        kind,
+        args: &args,
+    };
+    let mut child = factory.spawn(&launch).await?;
+    let backend = match factory.connect(&launch, &mut child).await {
+        Ok(backend) => backend,
+        Err(e) => {
+            if let Some(spawned) = child.as_mut() {
+                let _ = spawned.kill().await;
             }
-            let mut mpv = MpvIpc::new(socket_path.clone());
-            let mut attempts = 0;
-            let max_attempts = if kind == PlayerKind::Iina { 50 } else { 10 };
-            let event_rx = loop {
-                match mpv.connect().await {
-                    Ok(rx) => break rx,
-                    Err(e) => {
-                        attempts += 1;
-                        if attempts >= max_attempts {
-                            return Err(format!("Failed to connect to mpv IPC: {}", e));
-                        }
-                        sleep(Duration::from_millis(200)).await;
-                    }
-                }
-            };
-            let stdout = child.as_mut().and_then(|process| process.stdout.take());
-            let osc_compatible = match kind {
-                PlayerKind::Iina => true,
-                _ => check_mpv_version(&player_path)?.osc_visibility_change_compatible,
-            };
-            let backend = Arc::new(MpvBackend::new(
-                kind,
-                mpv,
-                Arc::downgrade(state),
-                osc_compatible,
-                stdout,
-            ));
-            backend.spawn_event_loop(event_rx);
-            let backend_dyn: Arc<dyn PlayerBackend> = backend.clone();
-            (backend_dyn, child)
-        }
-        PlayerKind::Vlc => {
-            let (backend, child) = if should_spawn {
-                let lua_path = resolve_syncplay_lua_path(state)
-                    .ok_or_else(|| "Syncplay VLC interface not found".to_string())?;
-                VlcSyncplayBackend::start(&player_path, &args, None, lua_path)
-                    .await
-                    .map_err(|e| e.to_string())?
-            } else {
-                return Err("Player not running".to_string());
-            };
-            (Arc::new(backend) as Arc<dyn PlayerBackend>, Some(child))
-        }
-        PlayerKind::Mplayer => {
-            let (backend, child) = if should_spawn {
-                MplayerBackend::start(&player_path, &args, None)
-                    .await
-                    .map_err(|e| e.to_string())?
-            } else {
-                return Err("Player not running".to_string());
-            };
-            (Arc::new(backend) as Arc<dyn PlayerBackend>, Some(child))
-        }
-        PlayerKind::MpcHc | PlayerKind::MpcBe => {
-            let (backend, child) = if should_spawn {
-                let mut mpc_args = args.clone();
-                if !mpc_args.iter().any(|arg| arg.eq_ignore_ascii_case("/open")) {
-                    mpc_args.push("/open".to_string());
-                }
-                if !mpc_args.iter().any(|arg| arg.eq_ignore_ascii_case("/new")) {
-                    mpc_args.push("/new".to_string());
-                }
-                MpcApiBackend::start(kind, &player_path, &mpc_args, None)
-                    .await
-                    .map_err(|e| e.to_string())?
-            } else {
-                return Err("Player not running".to_string());
-            };
-            (Arc::new(backend) as Arc<dyn PlayerBackend>, child)
-        }
-        PlayerKind::Unknown => {
-            return Err(format!("Unsupported player path: {}", player_path));
+            return Err(e);
         }
     };
 
     *state.player.lock() = Some(backend);
-    if !should_spawn && child.is_some() {
-        *state.last_player_spawn.lock() = Some(Instant::now());
-        *state.last_player_kind.lock() = Some(kind);
-    }
     if let Some(child) = child {
         *state.player_process.lock() = Some(child);
-    } else if !matches!(
-        kind,
-        PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina
-    ) {
+    } else if !factory.reuses_process() {
         *state.player_process.lock() = None;
     }
     Ok(())
@@ -247,8 +157,6 @@ pub async fn restart_player(state: &Arc<AppState>) -> Result<(), String> {
 pub async fn stop_player(state: &Arc<AppState>) -> Result<(), String> {
     let player = state.player.lock().clone();
     *state.player.lock() = None;
-    *state.last_player_spawn.lock() = None;
-    *state.last_player_kind.lock() = None;
     if let Some(player) = player {
         if let Err(e) = player.shutdown().await {
             tracing::warn!("Failed to shutdown player: {}", e);
@@ -267,20 +175,80 @@ pub async fn stop_player(state: &Arc<AppState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Wait until the player state is worth reading again
+///
+/// Players that push property changes wake the loop directly; the slower fallback tick
+/// keeps position and EOF handling moving. Returns whether a pushed change woke it.
+pub(crate) async fn wait_for_player_state(
+    notifier: Option<Arc<Notify>>,
+    poll_interval: &mut Interval,
+    fallback_interval: &mut Interval,
+) -> bool {
+    match notifier {
+        Some(notifier) => tokio::select! {
+            _ = notifier.notified() => true,
+            _ = fallback_interval.tick() => false,
+        },
+        None => {
+            poll_interval.tick().await;
+            false
+        }
+    }
+}
+
 pub fn spawn_player_state_loop(state: Arc<AppState>) {
     tokio::spawn(async move {
         let mut last_observed: Option<PlayerStateSnapshot> = None;
         let mut eof_sent = false;
-        let mut interval = tokio::time::interval(Duration::from_millis(100));
+        let mut had_focus = true;
+        let mut buffering = BufferingTracker::default();
+        let mut buffering_hold = None;
+        let mut stall = StallTracker::default();
+        let mut stall_unreadied = false;
+        let mut poll_interval =
+            tokio::time::interval(Duration::from_millis(PLAYER_POLL_INTERVAL_MS));
+        poll_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut fallback_interval =
+            tokio::time::interval(Duration::from_millis(EVENT_DRIVEN_FALLBACK_INTERVAL_MS));
+        fallback_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
-            interval.tick().await;
             let player = state.player.lock().clone();
-            let Some(player) = player else { continue };
-            if let Err(e) = player.poll_state().await {
-                tracing::warn!("Failed to poll player state: {}", e);
+            let Some(player) = player else {
+                poll_interval.tick().await;
+                continue;
+            };
+            let notifier = player
+                .state_notifier()
+                .filter(|_| !*state.player_poll_fallback.lock());
+            let woken_by_event =
+                wait_for_player_state(notifier, &mut poll_interval, &mut fallback_interval).await;
+            // A hung player must not stall the loop; the watchdog deals with it
+            if !woken_by_event {
+                match tokio::time::timeout(PLAYER_POLL_TIMEOUT, player.poll_state()).await {
+                    Ok(Err(e)) => tracing::warn!("Failed to poll player state: {}", e),
+                    Err(_) => tracing::debug!("Player state poll timed out"),
+                    Ok(Ok(())) => {}
+                }
+            }
+            if let Some(exit) = detect_player_exit(&state, player.as_ref()).await {
+                let snapshot = player.get_state();
+                handle_player_exit(&state, exit, snapshot).await;
+                last_observed = None;
+                buffering.reset();
+                buffering_hold = None;
+                stall.reset();
+                continue;
             }
             let player_state = player.get_state();
             emit_player_state(&state, &player_state);
+            track_buffering(
+                &state,
+                &player,
+                &player_state,
+                &mut buffering,
+                &mut buffering_hold,
+            )
+            .await;
 
             if state.is_connected() && check_protocol_timeout(&state) {
                 continue;
@@ -291,7 +259,30 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                 continue;
             }
 
+            if let Some(player_focused) = player_state.focused {
+                let has_focus = player_focused || *state.app_window_focused.lock();
+                if had_focus && !has_focus {
+                    handle_focus_lost(&state, &player_state).await;
+                }
+                had_focus = has_focus;
+            }
+
             let is_placeholder = is_placeholder_file(&state, &player_state);
+            // Rebuffering in hold has already told the room to wait
+            let watching = !is_placeholder && buffering_hold.is_none();
+            track_stall(
+                &state,
+                &player_state,
+                watching,
+                &mut stall,
+                &mut stall_unreadied,
+            );
+            if let Some(stats) = state.session_stats.lock().as_mut() {
+                stats.set_playing(
+                    !is_placeholder && player_state.paused == Some(false),
+                    Instant::now(),
+                );
+            }
 
             if !is_placeholder && file_info_changed(&player_state, last_observed.as_ref()) {
                 eof_sent = false;
@@ -301,6 +292,20 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                 } else {
                     send_file_update(&state, &player_state);
                 }
+                if let Some(filename) = player_state.filename.clone() {
+                    if let Some(stats) = state.session_stats.lock().as_mut() {
+                        stats.file_loaded(&filename);
+                    }
+                    crate::integrations::scripts::dispatch(
+                        &state,
+                        crate::integrations::scripts::ScriptEvent::FileLoaded {
+                            filename,
+                            duration: player_state.duration,
+                        },
+                    );
+                }
+                crate::commands::segments::refresh_chapter_segments(state.clone(), player.clone());
+                crate::commands::delays::apply_saved_delays(state.clone(), player.clone());
                 if matches!(player.kind(), PlayerKind::MpcHc | PlayerKind::MpcBe) {
                     sync_mpc_after_file_change(state.clone(), player.clone());
                 } else if matches!(player.kind(), PlayerKind::Vlc | PlayerKind::Mplayer) {
@@ -311,8 +316,9 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
             if let (Some(position), Some(paused_value)) =
                 (player_state.position, player_state.paused)
             {
-                let global = state.client_state.get_global_state();
-                let (mut local_pause_change, local_seeked) = {
+                crate::commands::segments::maybe_prompt_segment(&state, position);
+                let global = state.session.client_state.get_global_state();
+                let (mut local_pause_change, mut local_seeked) = {
                     let mut local_state = state.local_playback_state.lock();
                     let (pause_change, seeked) = local_state.update_from_player(
                         position,
@@ -322,13 +328,32 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                     );
                     (pause_change, seeked)
                 };
+                let detached = *state.sync_detached.lock();
+                if (local_pause_change || local_seeked)
+                    && !detached
+                    && hold_for_operators(&state, &player, position, paused_value, local_seeked)
+                        .await
+                {
+                    local_pause_change = false;
+                    local_seeked = false;
+                }
+                if local_seeked && !detached && hold_large_seek(&state, &player, position).await {
+                    local_seeked = false;
+                }
                 if local_seeked {
-                    *state.last_seek_from_position.lock() = Some(global.position);
+                    state
+                        .session
+                        .actor
+                        .send(SessionCommand::SetSeekFromPosition(Some(global.position)));
                 }
                 let mut paused = paused_value;
                 let mut skip_ready_toggle = false;
-                if local_pause_change && paused {
-                    let current_length = state.client_state.get_file_duration().unwrap_or(0.0);
+                if local_pause_change && paused && !detached {
+                    let current_length = state
+                        .session
+                        .client_state
+                        .get_file_duration()
+                        .unwrap_or(0.0);
                     let near_end = current_length > PLAYLIST_LOAD_NEXT_FILE_MINIMUM_LENGTH
                         && (position - current_length).abs()
                             < PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD;
@@ -339,6 +364,7 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                 }
                 if local_pause_change
                     && !local_seeked
+                    && !detached
                     && is_readiness_supported(&state, false)
                     && !skip_ready_toggle
                 {
@@ -349,12 +375,13 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                 }
 
                 if !is_placeholder
-                    && state.last_global_update.lock().is_some()
+                    && !detached
+                    && state.session.last_global_update.lock().is_some()
                     && (local_pause_change || local_seeked)
                 {
-                    let latency_calculation = *state.last_latency_calculation.lock();
+                    let latency_calculation = *state.session.last_latency_calculation.lock();
                     let play_state = if recently_rewound(&state) || recently_advanced(&state) {
-                        let global_state = state.client_state.get_global_state();
+                        let global_state = state.session.client_state.get_global_state();
                         PlayState {
                             position: global_state.position,
                             paused,
@@ -374,7 +401,9 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
                         Some(play_state),
                         latency_calculation,
                         local_pause_change || local_seeked,
-                    ) {
+                    )
+                    .await
+                    {
                         tracing::warn!("Failed to send state update: {}", e);
                     }
                 }
@@ -382,6 +411,10 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
 
             last_observed = Some(PlayerStateSnapshot::from(&player_state));
 
+            if !is_placeholder {
+                maybe_auto_ready(&state, &player_state);
+            }
+
             if !eof_sent {
                 if let (Some(duration), Some(position)) =
                     (player_state.duration, player_state.position)
@@ -403,6 +436,173 @@ pub fn spawn_player_state_loop(state: Arc<AppState>) {
     });
 }
 
+/// Ping the connected player and escalate per `player.watchdog` while it stays silent
+pub fn spawn_player_watchdog(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut watchdog = PlayerWatchdog::new();
+        let mut watched: Option<Arc<dyn PlayerBackend>> = None;
+        let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let policy = state.config.lock().player.watchdog.clone();
+            let player = state.player.lock().clone();
+            let Some(player) = player.filter(|_| policy.enabled) else {
+                if watched.take().is_some() {
+                    *state.player_poll_fallback.lock() = false;
+                    state.emit_event("player-health", serde_json::Value::Null);
+                }
+                continue;
+            };
+            // Every new player starts out healthy
+            if !watched
+                .as_ref()
+                .is_some_and(|watched| Arc::ptr_eq(watched, &player))
+            {
+                watchdog = PlayerWatchdog::new();
+                *state.player_poll_fallback.lock() = false;
+                watched = Some(player.clone());
+            }
+            let started = Instant::now();
+            let latency = match tokio::time::timeout(WATCHDOG_PING_TIMEOUT, player.ping()).await {
+                Ok(Ok(())) => Some(started.elapsed()),
+                Ok(Err(e)) => {
+                    tracing::debug!("Player ping failed: {}", e);
+                    None
+                }
+                Err(_) => None,
+            };
+            let now = Instant::now();
+            let previous = watchdog.status();
+            let change = watchdog.observe(latency, now, &policy);
+            let event = watchdog.event(now);
+            state.emit_event("player-health", event.clone());
+            if let Some(status) = change {
+                handle_player_health_change(&state, &player, previous, status, &event, &policy)
+                    .await;
+            }
+        }
+    });
+}
+
+async fn handle_player_health_change(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+    previous: PlayerHealthStatus,
+    status: PlayerHealthStatus,
+    event: &PlayerHealthEvent,
+    policy: &WatchdogPolicy,
+) {
+    let name = player.name();
+    match status {
+        PlayerHealthStatus::Healthy => {
+            *state.player_poll_fallback.lock() = false;
+            if previous >= PlayerHealthStatus::Warning {
+                let message = state.tr("player-responsive", &[&name]);
+                crate::commands::connection::emit_system_message(state, &message);
+            }
+        }
+        PlayerHealthStatus::Warning => {
+            let message = state.tr("player-unresponsive", &[&name, &event.unresponsive_secs]);
+            crate::commands::connection::emit_error_message(state, &message);
+        }
+        PlayerHealthStatus::Polling => {
+            tracing::warn!(
+                "{} is not responding, polling it instead of waiting for events",
+                name
+            );
+            *state.player_poll_fallback.lock() = true;
+        }
+        PlayerHealthStatus::Restarting if policy.restart => {
+            tracing::warn!("{} is not responding, restarting it", name);
+            let snapshot = player.get_state();
+            let message = state.tr("player-watchdog-restarting", &[&name]);
+            crate::commands::connection::emit_system_message(state, &message);
+            if let Err(e) = restart_player(state).await {
+                tracing::warn!("Failed to restart player: {}", e);
+                crate::commands::connection::emit_error_message(state, &e);
+                return;
+            }
+            restore_player_session(state, &snapshot).await;
+        }
+        PlayerHealthStatus::Restarting => {
+            tracing::warn!("{} is not responding, stopping it", name);
+            let message = state.tr("player-watchdog-stopped", &[&name]);
+            crate::commands::connection::emit_error_message(state, &message);
+            if let Err(e) = stop_player(state).await {
+                tracing::warn!("Failed to stop unresponsive player: {}", e);
+            }
+        }
+    }
+}
+
+/// Hold a URL load back and ask the user whether to trust its domain
+fn request_url_trust(
+    state: &Arc<AppState>,
+    url: &str,
+    reset_position: bool,
+    suppress_update: bool,
+) {
+    let Some(domain) = Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+    else {
+        return;
+    };
+    *state.pending_untrusted_url.lock() = Some(PendingUrlLoad {
+        url: url.to_string(),
+        domain: domain.clone(),
+        reset_position,
+        suppress_update,
+    });
+    crate::commands::connection::emit_system_message(state, &state.tr("url-untrusted", &[&domain]));
+    state.emit_event(
+        "untrusted-url",
+        UntrustedUrlRequest {
+            url: url.to_string(),
+            domain,
+        },
+    );
+}
+
+/// Load a URL that is already known to be trusted
+pub async fn load_url(
+    state: &Arc<AppState>,
+    url: &str,
+    reset_position: bool,
+    suppress_update: bool,
+) -> Result<(), String> {
+    ensure_player_connected(state).await?;
+    let player = state
+        .player
+        .lock()
+        .clone()
+        .ok_or_else(|| "Player not connected".to_string())?;
+    if reset_position {
+        player.mark_reset(true);
+    }
+    player
+        .load_file(url)
+        .await
+        .map_err(|e| format!("Failed to load URL: {}", e))?;
+    state.session.client_state.set_file(Some(url.to_string()));
+    state
+        .session
+        .actor
+        .send(SessionCommand::MarkFileUpdated(Instant::now()));
+    state.session.playlist.opened_file();
+    if reset_position {
+        rewind_player(state).await?;
+        crate::commands::connection::evaluate_autoplay(state);
+    }
+    if suppress_update {
+        *state.suppress_next_file_update.lock() = true;
+    } else {
+        schedule_file_update_after_load(state.clone());
+    }
+    Ok(())
+}
+
 pub async fn load_media_by_name(
     state: &Arc<AppState>,
     filename: &str,
@@ -416,41 +616,19 @@ pub async fn load_media_by_name(
             &config.user.trusted_domains,
             config.user.only_switch_to_trusted_domains,
         );
+        if trustable && !trusted {
+            request_url_trust(state, filename, reset_position, suppress_update);
+        }
         if !trustable || !trusted {
             return Err("URL is not trusted".to_string());
         }
-        ensure_player_connected(state).await?;
-        let player = state
-            .player
-            .lock()
-            .clone()
-            .ok_or_else(|| "Player not connected".to_string())?;
-        if reset_position {
-            player.mark_reset(true);
-        }
-        player
-            .load_file(filename)
-            .await
-            .map_err(|e| format!("Failed to load URL: {}", e))?;
-        state.client_state.set_file(Some(filename.to_string()));
-        *state.last_updated_file_time.lock() = Some(std::time::Instant::now());
-        state.playlist.opened_file();
-        if reset_position {
-            rewind_player(state).await?;
-            crate::commands::connection::evaluate_autoplay(state);
-        }
-        if suppress_update {
-            *state.suppress_next_file_update.lock() = true;
-        } else {
-            schedule_file_update_after_load(state.clone());
-        }
-        return Ok(());
+        return load_url(state, filename, reset_position, suppress_update).await;
     }
 
     let media_path = state
-        .media_index
-        .resolve_path(filename)
-        .or_else(|| resolve_media_path(&config.player.media_directories, filename))
+        .media_resolver()
+        .resolve(filename)
+        .await
         .ok_or_else(|| format!("File not found in media directories: {}", filename))?;
 
     ensure_player_connected(state).await?;
@@ -468,9 +646,15 @@ pub async fn load_media_by_name(
         .await
         .map_err(|e| format!("Failed to load file: {}", e))?;
 
-    state.client_state.set_file(Some(filename.to_string()));
-    *state.last_updated_file_time.lock() = Some(std::time::Instant::now());
-    state.playlist.opened_file();
+    state
+        .session
+        .client_state
+        .set_file(Some(filename.to_string()));
+    state
+        .session
+        .actor
+        .send(SessionCommand::MarkFileUpdated(Instant::now()));
+    state.session.playlist.opened_file();
     if reset_position {
         rewind_player(state).await?;
         crate::commands::connection::evaluate_autoplay(state);
@@ -505,7 +689,7 @@ fn schedule_file_update_after_load(state: Arc<AppState>) {
 
 fn sync_mpc_after_file_change(state: Arc<AppState>, player: Arc<dyn PlayerBackend>) {
     tokio::spawn(async move {
-        let global = state.client_state.get_global_state();
+        let global = state.session.client_state.get_global_state();
         for _ in 0..3 {
             let _ = player.set_paused(true).await;
             sleep(Duration::from_millis(10)).await;
@@ -518,59 +702,168 @@ fn sync_mpc_after_file_change(state: Arc<AppState>, player: Arc<dyn PlayerBacken
 
 fn sync_generic_after_file_change(state: Arc<AppState>, player: Arc<dyn PlayerBackend>) {
     tokio::spawn(async move {
-        let global = state.client_state.get_global_state();
+        let global = state.session.client_state.get_global_state();
         let _ = player.set_paused(global.paused).await;
         let _ = player.set_position(global.position).await;
     });
 }
 
-pub fn resolve_media_path(media_directories: &[String], filename: &str) -> Option<PathBuf> {
-    if filename == PRIVACY_HIDDEN_FILENAME {
-        return None;
-    }
-    let target = Path::new(filename)
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or(filename);
-    for directory in media_directories {
-        let directory = directory.trim();
-        if directory.is_empty() {
-            continue;
+/// Notice a player that quit or crashed since the last poll
+///
+/// mpv-based players are watched through their IPC connection because IINA's
+/// launcher process exits right after startup; other players through their
+/// spawned process.
+async fn detect_player_exit(
+    state: &Arc<AppState>,
+    player: &dyn PlayerBackend,
+) -> Option<PlayerExit> {
+    let ipc_watched = matches!(
+        player.kind(),
+        PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina
+    );
+    if ipc_watched {
+        if player.is_alive() {
+            return None;
         }
-        let candidate = Path::new(directory).join(target);
-        if candidate.exists() {
-            return Some(candidate);
+        let child = state.player_process.lock().take();
+        // A player we did not launch ourselves is left alone
+        let Some(mut child) = child else {
+            return Some(PlayerExit::Clean);
+        };
+        return match tokio::time::timeout(PLAYER_EXIT_STATUS_TIMEOUT, child.wait()).await {
+            Ok(Ok(status)) if status.success() => Some(PlayerExit::Clean),
+            Ok(Ok(status)) => Some(PlayerExit::Crashed(status.to_string())),
+            _ => {
+                let _ = child.kill().await;
+                Some(PlayerExit::Crashed("player stopped responding".to_string()))
+            }
+        };
+    }
+    let status = {
+        let mut guard = state.player_process.lock();
+        match guard.as_mut().map(|child| child.try_wait()) {
+            Some(Ok(Some(status))) => {
+                *guard = None;
+                status
+            }
+            _ => return None,
         }
+    };
+    if status.success() {
+        Some(PlayerExit::Clean)
+    } else {
+        Some(PlayerExit::Crashed(status.to_string()))
     }
+}
 
-    for directory in media_directories {
-        let directory = directory.trim();
-        if directory.is_empty() {
-            continue;
-        }
-        let dir_path = Path::new(directory);
-        let entries = match std::fs::read_dir(dir_path) {
-            Ok(entries) => entries,
-            Err(_) => continue,
-        };
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if !path.is_file() {
-                continue;
-            }
-            let candidate_name = path.file_name()?.to_string_lossy();
-            if same_filename(Some(target), Some(candidate_name.as_ref())) {
-                return Some(path);
+async fn handle_player_exit(state: &Arc<AppState>, exit: PlayerExit, snapshot: PlayerState) {
+    let reason = match exit {
+        PlayerExit::Clean => {
+            info!("Player was closed");
+            if let Err(e) = stop_player(state).await {
+                tracing::warn!("Failed to clean up closed player: {}", e);
             }
+            return;
         }
+        PlayerExit::Crashed(reason) => reason,
+    };
+    let config = state.config.lock().clone();
+    let restart = config.player.auto_restart;
+    let grace_seconds = config.player.restart_grace_seconds;
+    tracing::warn!("Player exited unexpectedly: {}", reason);
+    state.emit_event(
+        "player-crashed",
+        serde_json::json!({
+            "reason": reason,
+            "restarting": restart,
+            "graceSeconds": grace_seconds,
+        }),
+    );
+    let message = if restart {
+        state.tr("player-restarting", &[&grace_seconds])
+    } else {
+        state.tr("player-crashed", &[])
+    };
+    crate::commands::connection::emit_system_message(state, &message);
+    if let Err(e) = stop_player(state).await {
+        tracing::warn!("Failed to clean up crashed player: {}", e);
+    }
+    if !restart {
+        return;
+    }
+    sleep(Duration::from_secs(grace_seconds)).await;
+    if state.is_player_connected() {
+        return;
+    }
+    if let Err(e) = restart_player(state).await {
+        tracing::warn!("Failed to restart player: {}", e);
+        crate::commands::connection::emit_error_message(state, &e);
+        return;
     }
+    restore_player_session(state, &snapshot).await;
+}
 
-    None
+/// Reopen the file a crashed player had open, at the room position when there is one
+async fn restore_player_session(state: &Arc<AppState>, snapshot: &PlayerState) {
+    let player = state.player.lock().clone();
+    let Some(player) = player else { return };
+    let Some(path) = snapshot.path.clone() else {
+        return;
+    };
+    if is_placeholder_file(state, snapshot) {
+        return;
+    }
+    if let Err(e) = player.load_file(&path).await {
+        tracing::warn!("Failed to reopen {} after restart: {}", path, e);
+        return;
+    }
+    let started = Instant::now();
+    while started.elapsed() < RESTORE_FILE_LOAD_TIMEOUT {
+        if let Err(e) = player.poll_state().await {
+            tracing::debug!("Failed to poll restarted player: {}", e);
+        }
+        let current = player.get_state();
+        if current.path.as_deref() == Some(path.as_str()) && current.duration.is_some() {
+            break;
+        }
+        sleep(RESTORE_FILE_POLL_INTERVAL).await;
+    }
+    let global = state.session.client_state.get_global_state();
+    let follow_room = state.is_connected()
+        && state.session.last_global_update.lock().is_some()
+        && !*state.sync_detached.lock();
+    let (position, paused) = if follow_room {
+        (global.position_at(Instant::now()), global.paused)
+    } else {
+        (
+            snapshot.position.unwrap_or(0.0),
+            snapshot.paused.unwrap_or(true),
+        )
+    };
+    if let Err(e) = player.set_position(position).await {
+        tracing::warn!("Failed to restore position after restart: {}", e);
+    }
+    if let Err(e) = player.set_paused(paused).await {
+        tracing::warn!("Failed to restore pause state after restart: {}", e);
+    }
+    if let Err(e) = player.poll_state().await {
+        tracing::debug!("Failed to poll restarted player: {}", e);
+    }
+    // Take the restored spot as our own so it is not reported as a seek
+    let refreshed = player.get_state();
+    if let (Some(position), Some(paused)) = (refreshed.position, refreshed.paused) {
+        state.local_playback_state.lock().update_from_player(
+            position,
+            paused,
+            global.position,
+            global.paused,
+        );
+    }
+    let message = state.tr("player-restarted", &[&format_time(position, false)]);
+    crate::commands::connection::emit_system_message(state, &message);
 }
 
 pub async fn load_placeholder_if_empty(state: &Arc<AppState>) -> Result<(), String> {
-    let placeholder =
-        resolve_placeholder_path(state).ok_or_else(|| "Placeholder asset not found".to_string())?;
     let player = state
         .player
         .lock()
@@ -578,16 +871,69 @@ pub async fn load_placeholder_if_empty(state: &Arc<AppState>) -> Result<(), Stri
         .ok_or_else(|| "Player not connected".to_string())?;
     let player_state = player.get_state();
     if player_state.filename.is_some() {
+        // An idle view already up only needs its text to follow the session
+        if is_placeholder_file(state, &player_state) {
+            show_idle_screen(state, player.as_ref());
+        }
         return Ok(());
     }
+    let generated = state.config.lock().player.idle_screen == IdleScreen::Generated
+        && player.supports_idle_source();
+    let source = if generated {
+        IDLE_SOURCE.to_string()
+    } else {
+        resolve_placeholder_path(state)
+            .ok_or_else(|| "Placeholder asset not found".to_string())?
+            .to_string_lossy()
+            .to_string()
+    };
     *state.suppress_next_file_update.lock() = true;
     player
-        .load_file(placeholder.to_string_lossy().as_ref())
+        .load_file(&source)
         .await
         .map_err(|e| format!("Failed to load placeholder: {}", e))?;
+    if generated {
+        show_idle_screen(state, player.as_ref());
+    }
     Ok(())
 }
 
+/// Redraw the idle view after the room or connection changed
+pub fn refresh_idle_screen(state: &Arc<AppState>) {
+    let player = state.player.lock().clone();
+    let Some(player) = player else { return };
+    if is_placeholder_file(state, &player.get_state()) {
+        show_idle_screen(state, player.as_ref());
+    }
+}
+
+fn show_idle_screen(state: &Arc<AppState>, player: &dyn PlayerBackend) {
+    let text = if state.is_connected() {
+        let room = state.session.client_state.get_room();
+        let server = state
+            .reconnect_snapshot
+            .lock()
+            .as_ref()
+            .map(|snapshot| format!("{}:{}", snapshot.host, snapshot.port))
+            .unwrap_or_default();
+        IdleScreenText {
+            title: state.tr("idle-room", &[&room]),
+            details: vec![
+                state.tr("idle-server", &[&server]),
+                state.tr("idle-waiting-for-file", &[]),
+            ],
+        }
+    } else {
+        IdleScreenText {
+            title: state.tr("idle-not-connected", &[]),
+            details: Vec::new(),
+        }
+    };
+    if let Err(e) = player.show_idle_screen(Some(&text)) {
+        tracing::debug!("Failed to show the idle screen: {}", e);
+    }
+}
+
 fn resolve_player_path(config: &SyncplayConfig) -> String {
     let trimmed = config.player.player_path.trim();
     if trimmed.is_empty() || trimmed == "custom" {
@@ -597,6 +943,39 @@ fn resolve_player_path(config: &SyncplayConfig) -> String {
     }
 }
 
+/// Push changed settings to the running player instead of restarting it
+pub(crate) async fn reload_player_settings(
+    state: &Arc<AppState>,
+    previous: &SyncplayConfig,
+    config: &SyncplayConfig,
+) {
+    let Some(player) = state.player.lock().clone() else {
+        return;
+    };
+    let player_path = resolve_player_path(config);
+    if player_path != resolve_player_path(previous) {
+        emit_restart_needed(state);
+        return;
+    }
+    let arguments = build_player_arguments(config, &player_path);
+    let changed = arguments != build_player_arguments(previous, &player_path);
+    match player
+        .reload_settings(changed.then_some(arguments.as_slice()))
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => emit_restart_needed(state),
+        Err(e) => tracing::warn!("Failed to reload player settings: {}", e),
+    }
+}
+
+fn emit_restart_needed(state: &Arc<AppState>) {
+    crate::commands::connection::emit_system_message(
+        state,
+        &state.tr("player-restart-needed", &[]),
+    );
+}
+
 fn build_player_arguments(config: &SyncplayConfig, player_path: &str) -> Vec<String> {
     let mut args = config.player.player_arguments.clone();
     if let Some(extra_args) = config.player.per_player_arguments.get(player_path) {
@@ -605,7 +984,7 @@ fn build_player_arguments(config: &SyncplayConfig, player_path: &str) -> Vec<Str
     args
 }
 
-fn ensure_mpv_socket_path(state: &Arc<AppState>) -> Result<String, String> {
+pub(crate) fn ensure_mpv_socket_path(state: &Arc<AppState>) -> Result<String, String> {
     if let Some(path) = state.mpv_socket_path.lock().clone() {
         return Ok(path);
     }
@@ -684,7 +1063,7 @@ fn resolve_placeholder_path(state: &AppState) -> Option<PathBuf> {
     None
 }
 
-fn resolve_syncplay_lua_path(state: &AppState) -> Option<PathBuf> {
+pub(crate) fn resolve_syncplay_lua_path(state: &AppState) -> Option<PathBuf> {
     let candidates = [
         "resources/syncplay.lua",
         "syncplay.lua",
@@ -712,7 +1091,7 @@ fn resolve_syncplay_lua_path(state: &AppState) -> Option<PathBuf> {
     None
 }
 
-fn resolve_syncplayintf_path(state: &AppState) -> Option<PathBuf> {
+pub(crate) fn resolve_syncplayintf_path(state: &AppState) -> Option<PathBuf> {
     let candidates = [
         "resources/syncplayintf.lua",
         "syncplayintf.lua",
@@ -740,11 +1119,11 @@ fn resolve_syncplayintf_path(state: &AppState) -> Option<PathBuf> {
     None
 }
 
-struct MpvVersionFlags {
-    osc_visibility_change_compatible: bool,
+pub(crate) struct MpvVersionFlags {
+    pub osc_visibility_change_compatible: bool,
 }
 
-fn check_mpv_version(player_path: &str) -> Result<MpvVersionFlags, String> {
+pub(crate) fn check_mpv_version(player_path: &str) -> Result<MpvVersionFlags, String> {
     let output = std::process::Command::new(player_path)
         .arg("--version")
         .output()
@@ -776,20 +1155,7 @@ fn check_mpv_version(player_path: &str) -> Result<MpvVersionFlags, String> {
     })
 }
 
-fn should_spawn_player(state: &AppState, kind: PlayerKind) -> bool {
-    if kind != PlayerKind::Iina {
-        return true;
-    }
-    let now = Instant::now();
-    let last_spawn = *state.last_player_spawn.lock();
-    let last_kind = *state.last_player_kind.lock();
-    let recent = last_spawn
-        .map(|instant| now.duration_since(instant) < Duration::from_secs(15))
-        .unwrap_or(false);
-    !(recent && last_kind == Some(PlayerKind::Iina))
-}
-
-fn start_mpv_process_if_needed(
+pub(crate) fn start_mpv_process_if_needed(
     state: &Arc<AppState>,
     player_path: &str,
     kind: PlayerKind,
@@ -813,10 +1179,16 @@ fn start_mpv_process_if_needed(
         return Ok(None);
     }
 
-    let mut cmd = Command::new(player_path);
+    let mut cmd = if kind == PlayerKind::Iina {
+        tokio::process::Command::new(iina_cli_path(player_path))
+    } else {
+        player_command(player_path, &state.config.lock().player)
+    };
     cmd.env_remove("TERM");
     let launch_args = args.to_vec();
     let mut full_args = Vec::new();
+    let mpv_profile = state.config.lock().player.mpv_profile.clone();
+    let profile_dir = mpv_profile_dir(socket_path);
     let term_playing_msg = "<SyncplayUpdateFile>\nANS_filename=${filename}\nANS_length=${=duration:${=length:0}}\nANS_path=${path}\n</SyncplayUpdateFile>";
     match kind {
         PlayerKind::Iina => {
@@ -827,16 +1199,16 @@ fn start_mpv_process_if_needed(
                 .iter()
                 .any(|arg| arg.starts_with("--mpv-sid") || arg.starts_with("--sid"));
             full_args.push("--no-stdin".to_string());
+            // Stay alive as long as IINA so the process tells us when the player closes
+            full_args.push("--keep-running".to_string());
             if let Some(placeholder) = resolve_placeholder_path(state) {
                 full_args.push(placeholder.to_string_lossy().to_string());
             } else {
                 tracing::warn!("Placeholder asset not found for player startup");
             }
-            full_args.push("--mpv-keep-open=always".to_string());
-            full_args.push("--mpv-keep-open-pause=yes".to_string());
+            full_args.extend(profile_args(&mpv_profile, &profile_dir, "--mpv-"));
             full_args.push("--mpv-idle=yes".to_string());
             full_args.push("--mpv-input-terminal=no".to_string());
-            full_args.push("--mpv-hr-seek=always".to_string());
             full_args.push("--mpv-force-window=yes".to_string());
             full_args.push(format!("--mpv-input-ipc-server={}", socket_path));
             full_args.push(format!("--mpv-term-playing-msg={}", term_playing_msg));
@@ -853,9 +1225,7 @@ fn start_mpv_process_if_needed(
         _ => {
             full_args.push("--force-window=yes".to_string());
             full_args.push("--idle=yes".to_string());
-            full_args.push("--keep-open=always".to_string());
-            full_args.push("--keep-open-pause=yes".to_string());
-            full_args.push("--hr-seek=always".to_string());
+            full_args.extend(profile_args(&mpv_profile, &profile_dir, "--"));
             full_args.push("--input-terminal=no".to_string());
             full_args.push(format!("--input-ipc-server={}", socket_path));
             full_args.push(format!("--term-playing-msg={}", term_playing_msg));
@@ -883,33 +1253,348 @@ fn start_mpv_process_if_needed(
     Ok(Some(child))
 }
 
-async fn wait_for_ipc_socket(
-    child: &mut tokio::process::Child,
-    socket_path: &str,
-    timeout: Duration,
-) -> Result<(), String> {
-    let start = Instant::now();
-    while start.elapsed() < timeout {
-        if Path::new(socket_path).exists() {
-            return Ok(());
-        }
-        if let Ok(Some(status)) = child.try_wait() {
-            if !status.success() {
-                return Err(format!("Player exited with status {}", status));
+/// Connect to the mpv IPC socket, giving a freshly started player time to open it
+pub(crate) async fn connect_mpv_ipc(
+    mpv: &mut MpvIpc,
+) -> Result<tokio::sync::mpsc::UnboundedReceiver<MpvPlayerEvent>, String> {
+    let mut attempts = 0;
+    loop {
+        match mpv.connect().await {
+            Ok(rx) => return Ok(rx),
+            Err(e) => {
+                attempts += 1;
+                if attempts >= MPV_IPC_CONNECT_ATTEMPTS {
+                    return Err(format!("Failed to connect to mpv IPC: {}", e));
+                }
+                sleep(Duration::from_millis(200)).await;
             }
         }
-        sleep(Duration::from_millis(100)).await;
     }
-    Err("Timed out waiting for MPV IPC socket".to_string())
 }
 
-fn emit_player_state(state: &Arc<AppState>, player_state: &PlayerState) {
-    state.emit_event(
-        "player-state-changed",
-        PlayerStateEvent {
-            filename: player_state.filename.clone(),
-            position: player_state.position,
-            duration: player_state.duration,
+/// Socket IINA is started with; a fixed path lets a later launch find a running IINA
+pub(crate) fn iina_socket_path() -> String {
+    std::env::temp_dir()
+        .join("syncplay-iina-mpv-socket")
+        .to_string_lossy()
+        .to_string()
+}
+
+pub(crate) fn iina_is_running() -> bool {
+    std::process::Command::new("pgrep")
+        .args(["-x", "IINA"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// IINA's command line tool, found next to the app binary or inside the app bundle
+fn iina_cli_path(player_path: &str) -> PathBuf {
+    let path = Path::new(player_path);
+    let cli = if path.extension().is_some_and(|ext| ext == "app") {
+        path.join("Contents/MacOS/iina-cli")
+    } else {
+        path.with_file_name("iina-cli")
+    };
+    if cli.exists() {
+        cli
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Where the generated mpv profile goes: next to the IPC socket, which the player can reach
+#[allow(unused_variables)]
+fn mpv_profile_dir(socket_path: &str) -> PathBuf {
+    #[cfg(windows)]
+    {
+        std::env::temp_dir()
+    }
+    #[cfg(not(windows))]
+    {
+        Path::new(socket_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(std::env::temp_dir)
+    }
+}
+
+pub(crate) async fn wait_for_ipc_socket(
+    child: &mut tokio::process::Child,
+    socket_path: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if Path::new(socket_path).exists() {
+            return Ok(());
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            if !status.success() {
+                return Err(format!("Player exited with status {}", status));
+            }
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    Err("Timed out waiting for MPV IPC socket".to_string())
+}
+
+/// Take back a pause or seek of ours in an operators-only room and suggest it instead
+///
+/// Controlled rooms are left to `apply_ready_toggle`; the server enforces those itself.
+async fn hold_for_operators(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+    position: f64,
+    paused: bool,
+    seeked: bool,
+) -> bool {
+    let Some(rule) = crate::commands::room::operators_only_rule(state) else {
+        return false;
+    };
+    if rule.allows(&state.session.client_state.get_username()) {
+        return false;
+    }
+    let global = state.session.client_state.get_global_state();
+    if paused != global.paused {
+        if let Err(e) = player.set_paused(global.paused).await {
+            tracing::warn!("Failed to take back pause for the operators: {}", e);
+            return false;
+        }
+    }
+    if seeked {
+        if let Err(e) = player
+            .set_position(global.position_at(Instant::now()))
+            .await
+        {
+            tracing::warn!("Failed to take back seek for the operators: {}", e);
+            return false;
+        }
+    }
+    let suggestion = match (seeked, paused) {
+        (true, _) => Suggestion::Seek(position),
+        (false, true) => Suggestion::Pause,
+        (false, false) => Suggestion::Unpause,
+    };
+    if state.session.server_features.lock().chat {
+        let text = format!("{}{}", SUGGESTION_CHAT_PREFIX, suggestion.to_chat());
+        if let Err(e) = crate::commands::room::send_room_control_chat(state, text) {
+            tracing::warn!("Failed to send suggestion: {}", e);
+        }
+    }
+    let config = state.config.lock().clone();
+    let message = state.tr("operators-only-suggested", &[]);
+    crate::commands::connection::emit_system_message(state, &message);
+    crate::commands::connection::maybe_show_osd(
+        state,
+        &config,
+        &message,
+        crate::config::OsdCategory::Status,
+    );
+    true
+}
+
+/// How close a seek must land to a confirmed target to count as that seek
+const CONFIRMED_SEEK_TOLERANCE: f64 = 2.0;
+
+/// Take back a local seek past the confirmation threshold and ask the user about it
+async fn hold_large_seek(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+    position: f64,
+) -> bool {
+    let config = state.config.lock().clone();
+    let room_position = state
+        .session
+        .client_state
+        .get_global_state()
+        .position_at(Instant::now());
+    if !config.user.confirm_large_seeks
+        || (position - room_position).abs() < config.user.large_seek_threshold
+    {
+        return false;
+    }
+    if let Some(target) = state.confirmed_seek.lock().take() {
+        if (position - target).abs() < CONFIRMED_SEEK_TOLERANCE {
+            return false;
+        }
+    }
+    if let Err(e) = player.set_position(room_position).await {
+        tracing::warn!("Failed to take back large seek: {}", e);
+        return false;
+    }
+    let request = SeekConfirmationRequest {
+        from: room_position,
+        to: position,
+    };
+    *state.pending_seek.lock() = Some(request);
+    crate::commands::connection::emit_system_message(
+        state,
+        &state.tr("seek-needs-confirmation", &[&format_time(position, false)]),
+    );
+    state.emit_event("seek-confirmation", request);
+    true
+}
+
+/// Mark not ready while unpaused playback stands still, and ready again once it moves
+fn track_stall(
+    state: &Arc<AppState>,
+    player_state: &PlayerState,
+    watching: bool,
+    tracker: &mut StallTracker,
+    unreadied: &mut bool,
+) {
+    let config = state.config.lock().clone();
+    let position = player_state
+        .position
+        .filter(|_| watching && config.player.stall_detection);
+    let timeout = Duration::from_secs(config.player.stall_timeout_secs);
+    match tracker.update(position, player_state.paused, timeout, Instant::now()) {
+        Some(StallChange::Stalled) => {
+            tracing::warn!("Playback stalled at {:?}", player_state.position);
+            if state.session.client_state.is_ready() && is_readiness_supported(state, false) {
+                *unreadied = send_ready_state(state, false, false).is_ok();
+            }
+            let message = state.tr("playback-stalled", &[]);
+            crate::commands::connection::emit_system_message(state, &message);
+            crate::commands::connection::maybe_show_osd(
+                state,
+                &config,
+                &message,
+                crate::config::OsdCategory::Status,
+            );
+        }
+        Some(StallChange::Resumed) => {
+            // Pausing ends a stall too, and leaves readiness to the pause
+            if std::mem::take(unreadied)
+                && player_state.paused == Some(false)
+                && !state.session.client_state.is_ready()
+            {
+                if let Err(e) = send_ready_state(state, true, false) {
+                    tracing::warn!("Failed to mark ready after stall: {}", e);
+                }
+            }
+            crate::commands::connection::emit_system_message(
+                state,
+                &state.tr("playback-resumed", &[]),
+            );
+        }
+        None => {}
+    }
+}
+
+/// What we did to make the room wait while our stream rebuffers
+#[derive(Debug, Clone, Copy)]
+struct BufferingHold {
+    /// We paused the player, and with it the room
+    paused: bool,
+    was_ready: bool,
+}
+
+/// Report rebuffering of streamed files and hold the room while it lasts
+async fn track_buffering(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+    player_state: &PlayerState,
+    tracker: &mut BufferingTracker,
+    hold: &mut Option<BufferingHold>,
+) {
+    let streaming = player_state.path.as_deref().is_some_and(is_url);
+    let stalled = streaming && player_state.is_buffering();
+    let Some(change) = tracker.update(stalled, player_state.cache_buffering, Instant::now()) else {
+        return;
+    };
+    state.emit_event(
+        "buffering",
+        BufferingEvent::from_state(tracker.is_buffering(), player_state),
+    );
+    match change {
+        BufferingChange::Progressed => {}
+        BufferingChange::Started => {
+            info!("Stream is rebuffering");
+            *hold = hold_room_for_buffering(state, player).await;
+        }
+        BufferingChange::Finished => {
+            info!("Stream finished rebuffering");
+            crate::commands::connection::emit_system_message(
+                state,
+                &state.tr("stream-buffered", &[]),
+            );
+            if let Some(hold) = hold.take() {
+                release_room_after_buffering(state, player, hold).await;
+            }
+        }
+    }
+}
+
+async fn hold_room_for_buffering(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+) -> Option<BufferingHold> {
+    let config = state.config.lock().clone();
+    if !config.player.wait_while_buffering || !state.is_connected() || *state.sync_detached.lock() {
+        crate::commands::connection::emit_system_message(state, &state.tr("stream-buffering", &[]));
+        return None;
+    }
+    let was_ready = state.session.client_state.is_ready();
+    let global_paused = state.session.client_state.get_global_state().paused;
+    // The pause reaches the room the usual way, which also marks us not ready;
+    // without control of the room only readiness can ask it to wait
+    let paused = !global_paused && current_user_can_control(state);
+    if paused {
+        if let Err(e) = player.set_paused(true).await {
+            tracing::warn!("Failed to pause for buffering: {}", e);
+            return None;
+        }
+    } else if was_ready && is_readiness_supported(state, false) {
+        if let Err(e) = send_ready_state(state, false, false) {
+            tracing::warn!("Failed to mark not ready for buffering: {}", e);
+        }
+    }
+    let message = state.tr("stream-buffering-waiting", &[]);
+    crate::commands::connection::emit_system_message(state, &message);
+    crate::commands::connection::maybe_show_osd(
+        state,
+        &config,
+        &message,
+        crate::config::OsdCategory::Status,
+    );
+    Some(BufferingHold { paused, was_ready })
+}
+
+async fn release_room_after_buffering(
+    state: &Arc<AppState>,
+    player: &Arc<dyn PlayerBackend>,
+    hold: BufferingHold,
+) {
+    if !state.is_connected() {
+        return;
+    }
+    if hold.paused {
+        // Only resume a room that is still waiting for us; the unpause goes through the usual checks
+        let global_paused = state.session.client_state.get_global_state().paused;
+        if global_paused && player.get_state().paused == Some(true) {
+            if let Err(e) = player.set_paused(false).await {
+                tracing::warn!("Failed to resume after buffering: {}", e);
+            }
+        }
+    } else if hold.was_ready
+        && !state.session.client_state.is_ready()
+        && is_readiness_supported(state, false)
+    {
+        if let Err(e) = send_ready_state(state, true, false) {
+            tracing::warn!("Failed to mark ready after buffering: {}", e);
+        }
+    }
+}
+
+fn emit_player_state(state: &Arc<AppState>, player_state: &PlayerState) {
+    state.emit_event(
+        "player-state-changed",
+        PlayerStateEvent {
+            filename: player_state.filename.clone(),
+            position: player_state.position,
+            duration: player_state.duration,
             paused: player_state.paused,
             speed: player_state.speed,
         },
@@ -952,48 +1637,281 @@ pub(crate) fn send_file_update(state: &Arc<AppState>, player_state: &PlayerState
             return;
         }
     };
-    let raw_size = if let Some(local_path) = local_path.as_ref() {
-        match std::fs::metadata(local_path) {
-            Ok(metadata) => Some(metadata.len()),
-            Err(_) => Some(0),
-        }
-    } else if raw_path.as_deref().map(is_url).unwrap_or(false) {
-        Some(0)
-    } else {
-        raw_name.as_deref().filter(|name| is_url(name)).map(|_| 0)
-    };
     let raw_duration = player_state.duration;
 
+    state.session.client_state.set_file(raw_name.clone());
+    state.session.client_state.set_file_duration(raw_duration);
+    if state.session.connection.lock().is_some() {
+        if let Some(name) = raw_name.as_deref() {
+            let path = local_path
+                .as_deref()
+                .and_then(|path| path.to_str())
+                .or(raw_path.as_deref());
+            crate::commands::recent_files::remember_recent_file(state, name, path);
+        }
+    }
+    state
+        .session
+        .actor
+        .send(SessionCommand::MarkFileUpdated(Instant::now()));
+
+    // The size lookup can be slow on network shares, so it runs off the player loop;
+    // the ticket drops its result if a newer file arrived in the meantime
+    let ticket = state.file_update_throttle.lock().begin();
+    let is_remote = raw_path.as_deref().map(is_url).unwrap_or(false)
+        || raw_name.as_deref().map(is_url).unwrap_or(false);
+    let task_state = state.clone();
+    let outbound_name = raw_name.clone();
+    tokio::spawn(async move {
+        let state = task_state;
+        let mut raw_size = match local_path.as_deref() {
+            Some(local_path) => Some(file_size(local_path).await.unwrap_or(0)),
+            None => is_remote.then_some(0),
+        };
+        // A file that is still growing is being downloaded
+        let mut downloading = false;
+        if let (Some(local_path), Some(size)) = (local_path.as_deref(), raw_size) {
+            if size > 0 {
+                sleep(FILE_GROWTH_CHECK_DELAY).await;
+                if let Some(grown) = file_size(local_path).await.filter(|grown| *grown > size) {
+                    raw_size = Some(grown);
+                    downloading = true;
+                }
+            }
+        }
+        let info = outbound_file_info(
+            &state,
+            &config,
+            outbound_name.clone(),
+            raw_size,
+            raw_duration,
+            downloading,
+        );
+        let action = {
+            let mut throttle = state.file_update_throttle.lock();
+            if !throttle.is_current(ticket) {
+                return;
+            }
+            state.session.client_state.set_file_size(info.size.clone());
+            if state.session.connection.lock().is_none() {
+                return;
+            }
+            throttle.submit(info.clone(), Instant::now())
+        };
+        if downloading {
+            let name = outbound_name.unwrap_or_default();
+            let previous = state.downloading_file.lock().replace(name.clone());
+            if previous.as_deref() != Some(name.as_str()) {
+                crate::commands::connection::emit_error_message(
+                    &state,
+                    &state.tr("file-downloading", &[&name]),
+                );
+            }
+            if let (Some(local_path), Some(size)) = (local_path.clone(), raw_size) {
+                tokio::spawn(watch_downloading_file(
+                    state.clone(),
+                    ticket,
+                    local_path,
+                    name,
+                    raw_duration,
+                    size,
+                ));
+            }
+        } else {
+            *state.downloading_file.lock() = None;
+        }
+        dispatch_file_update(&state, action, info).await;
+    });
+
+    if let Some(raw_name) = raw_name {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = change_playlist_from_filename(&state_clone, &raw_name).await {
+                tracing::warn!("Failed to sync playlist from filename: {}", e);
+            }
+        });
+    }
+}
+
+/// Privacy modes for `filename` in the current room, after the privacy rules
+pub(crate) fn file_privacy(
+    state: &Arc<AppState>,
+    config: &SyncplayConfig,
+    filename: &str,
+) -> ResolvedPrivacy {
+    let room = state.session.client_state.get_room();
+    let server = state
+        .reconnect_snapshot
+        .lock()
+        .as_ref()
+        .map(|snapshot| format!("{}:{}", snapshot.host, snapshot.port));
+    let public_server = server.is_some_and(|server| {
+        config
+            .public_servers
+            .iter()
+            .any(|public| public.address.eq_ignore_ascii_case(&server))
+    });
+    resolve_privacy(
+        &config.user.privacy_rules,
+        PrivacyContext {
+            room: &room,
+            filename,
+            public_server,
+        },
+        &config.user.filename_privacy_mode,
+        &config.user.filesize_privacy_mode,
+    )
+}
+
+/// File information as the server may see it: truncated and with privacy applied
+pub(crate) fn outbound_file_info(
+    state: &Arc<AppState>,
+    config: &SyncplayConfig,
+    raw_name: Option<String>,
+    raw_size: Option<u64>,
+    duration: Option<f64>,
+    downloading: bool,
+) -> FileInfo {
     let max_len = state
+        .session
         .server_features
         .lock()
         .max_filename_length
         .unwrap_or(250);
-    let outbound_name = raw_name.clone().map(|name| truncate_text(&name, max_len));
+    let privacy = file_privacy(state, config, raw_name.as_deref().unwrap_or_default());
+    let raw_name = raw_name.map(|name| truncate_text(&name, max_len));
     let (name, size) = apply_privacy(
-        outbound_name,
+        raw_name,
         raw_size,
-        &config.user.filename_privacy_mode,
-        &config.user.filesize_privacy_mode,
+        &privacy.filename_mode,
+        &privacy.filesize_mode,
     );
+    FileInfo {
+        name,
+        size,
+        duration,
+        downloading: downloading.then_some(true),
+    }
+}
 
-    state.client_state.set_file(raw_name.clone());
-    state.client_state.set_file_size(size.clone());
-    state.client_state.set_file_duration(raw_duration);
-    *state.last_updated_file_time.lock() = Some(std::time::Instant::now());
-
-    let Some(connection) = state.connection.lock().clone() else {
+/// Announce our file again after a room change, when privacy rules may treat it differently
+pub(crate) fn refresh_file_privacy(state: &Arc<AppState>) {
+    let config = state.config.lock().clone();
+    if config.user.privacy_rules.is_empty() {
         return;
+    }
+    let Some(raw_name) = state.session.client_state.get_file() else {
+        return;
+    };
+    let player = state.player.lock().clone();
+    let local_path = player
+        .and_then(|player| player.get_state().path)
+        .as_deref()
+        .and_then(normalize_local_path);
+    let duration = state.session.client_state.get_file_duration();
+    let ticket = state.file_update_throttle.lock().begin();
+    let state = state.clone();
+    tokio::spawn(async move {
+        let raw_size = match local_path.as_deref() {
+            Some(local_path) => Some(file_size(local_path).await.unwrap_or(0)),
+            None => is_url(&raw_name).then_some(0),
+        };
+        let info = outbound_file_info(&state, &config, Some(raw_name), raw_size, duration, false);
+        let action = {
+            let mut throttle = state.file_update_throttle.lock();
+            if !throttle.is_current(ticket) {
+                return;
+            }
+            state.session.client_state.set_file_size(info.size.clone());
+            if state.session.connection.lock().is_none() {
+                return;
+            }
+            throttle.submit(info.clone(), Instant::now())
+        };
+        dispatch_file_update(&state, action, info).await;
+    });
+}
+
+/// Carry out what the throttle decided for a file update
+async fn dispatch_file_update(state: &Arc<AppState>, action: FileUpdateAction, info: FileInfo) {
+    match action {
+        FileUpdateAction::Send => send_file_info(state, info),
+        FileUpdateAction::Defer(delay) => {
+            sleep(delay).await;
+            let pending = state
+                .file_update_throttle
+                .lock()
+                .take_pending(Instant::now());
+            if let Some(info) = pending {
+                send_file_info(state, info);
+            }
+        }
+        FileUpdateAction::Merged | FileUpdateAction::Unchanged => {}
+    }
+}
+
+/// Re-check a downloading file until its size settles, then send the final size
+///
+/// Stops early once another file update takes over.
+async fn watch_downloading_file(
+    state: Arc<AppState>,
+    ticket: u64,
+    path: PathBuf,
+    name: String,
+    duration: Option<f64>,
+    mut size: u64,
+) {
+    loop {
+        sleep(DOWNLOAD_RECHECK_INTERVAL).await;
+        if !state.file_update_throttle.lock().is_current(ticket) {
+            return;
+        }
+        let Some(current) = file_size(&path).await else {
+            return;
+        };
+        if current > size {
+            size = current;
+            continue;
+        }
+        break;
+    }
+    let config = state.config.lock().clone();
+    let info = outbound_file_info(
+        &state,
+        &config,
+        Some(name.clone()),
+        Some(size),
+        duration,
+        false,
+    );
+    let action = {
+        let mut throttle = state.file_update_throttle.lock();
+        if !throttle.is_current(ticket) {
+            return;
+        }
+        state.session.client_state.set_file_size(info.size.clone());
+        if state.session.connection.lock().is_none() {
+            return;
+        }
+        throttle.submit(info.clone(), Instant::now())
     };
+    *state.downloading_file.lock() = None;
+    crate::commands::connection::emit_system_message(
+        &state,
+        &state.tr("file-download-finished", &[&name]),
+    );
+    dispatch_file_update(&state, action, info).await;
+}
 
+/// Tell the server about our file, then refresh the user list once for it
+fn send_file_info(state: &Arc<AppState>, info: FileInfo) {
+    let Some(connection) = state.session.connection.lock().clone() else {
+        return;
+    };
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
-            file: Some(FileInfo {
-                name,
-                size,
-                duration: raw_duration,
-            }),
+            file: Some(info),
             user: None,
             ready: None,
             playlist_index: None,
@@ -1010,15 +1928,6 @@ pub(crate) fn send_file_update(state: &Arc<AppState>, player_state: &PlayerState
     if let Err(e) = connection.send(ProtocolMessage::List { List: None }) {
         tracing::warn!("Failed to request user list after file update: {}", e);
     }
-
-    if let Some(raw_name) = raw_name {
-        let state_clone = state.clone();
-        tokio::spawn(async move {
-            if let Err(e) = change_playlist_from_filename(&state_clone, &raw_name).await {
-                tracing::warn!("Failed to sync playlist from filename: {}", e);
-            }
-        });
-    }
 }
 
 fn normalize_local_path(raw_path: &str) -> Option<PathBuf> {
@@ -1082,7 +1991,10 @@ pub(crate) async fn rewind_player(state: &Arc<AppState>) -> Result<(), String> {
     if let Err(e) = player.set_position(0.0).await {
         tracing::warn!("Failed to rewind player: {}", e);
     }
-    *state.last_rewind_time.lock() = Some(Instant::now());
+    state
+        .session
+        .actor
+        .send(SessionCommand::MarkRewind(Instant::now()));
     schedule_double_check_rewind(player);
     Ok(())
 }
@@ -1120,10 +2032,11 @@ fn file_info_changed(player_state: &PlayerState, last_sent: Option<&PlayerStateS
 }
 
 pub(crate) fn is_placeholder_file(state: &Arc<AppState>, player_state: &PlayerState) -> bool {
-    if let Some(name) = player_state.filename.as_deref() {
-        if name == "placeholder.png" {
-            return true;
-        }
+    if is_idle_source(
+        player_state.path.as_deref(),
+        player_state.filename.as_deref(),
+    ) {
+        return true;
     }
     if let (Some(path), Some(placeholder_path)) = (
         player_state.path.as_deref(),
@@ -1161,13 +2074,18 @@ async fn advance_playlist_check(state: &Arc<AppState>, position: f64) -> bool {
         return false;
     }
     if state
+        .session
         .playlist
         .not_just_changed(PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD)
-        && state.client_state.get_file().is_some()
+        && state.session.client_state.get_file().is_some()
     {
-        state.client_state.set_file_duration(Some(position));
+        state.session.client_state.set_file_duration(Some(position));
     }
-    let current_length = state.client_state.get_file_duration().unwrap_or(0.0);
+    let current_length = state
+        .session
+        .client_state
+        .get_file_duration()
+        .unwrap_or(0.0);
     if current_length <= PLAYLIST_LOAD_NEXT_FILE_MINIMUM_LENGTH {
         return false;
     }
@@ -1175,6 +2093,7 @@ async fn advance_playlist_check(state: &Arc<AppState>, position: f64) -> bool {
         return false;
     }
     if !state
+        .session
         .playlist
         .not_just_changed(PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD)
     {
@@ -1192,14 +2111,24 @@ async fn load_next_file_in_playlist(state: &Arc<AppState>, config: &SyncplayConf
         return;
     }
 
-    let items = state.playlist.get_item_filenames();
+    let items = state.session.playlist.get_item_filenames();
     if items.is_empty() {
         return;
     }
 
+    if state.session.playlist.take_stop_after_current() {
+        tracing::info!("Stopping after current playlist item");
+        crate::commands::connection::emit_system_message(
+            state,
+            &state.tr("stopped-after-current", &[]),
+        );
+        return;
+    }
+
+    let mode = config.user.effective_playlist_mode();
     let loop_single = config.user.loop_single_files || is_playing_music(state);
-    if items.len() == 1 && loop_single {
-        state.playlist.opened_file();
+    if mode == PlaylistMode::RepeatOne || (items.len() == 1 && loop_single) {
+        state.session.playlist.opened_file();
         let _ = rewind_player(state).await;
         let player = state.player.lock().clone();
         if let Some(player) = player {
@@ -1215,12 +2144,18 @@ async fn load_next_file_in_playlist(state: &Arc<AppState>, config: &SyncplayConf
         return;
     }
 
-    let loop_at_end = config.user.loop_at_end_of_playlist || is_playing_music(state);
-    let current_index = match state.playlist.get_current_index() {
+    if waits_for_room_to_finish(state, config) {
+        return;
+    }
+
+    let loop_at_end = mode == PlaylistMode::RepeatAll || is_playing_music(state);
+    let current_index = match state.session.playlist.get_current_index() {
         Some(index) => index,
         None => return,
     };
-    let next_index = if current_index + 1 < items.len() {
+    let next_index = if let Some(queued) = state.session.playlist.take_queued_next_index() {
+        queued
+    } else if current_index + 1 < items.len() {
         current_index + 1
     } else if loop_at_end {
         0
@@ -1229,12 +2164,15 @@ async fn load_next_file_in_playlist(state: &Arc<AppState>, config: &SyncplayConf
     };
 
     if let Some(filename) = items.get(next_index) {
-        if !playlist_item_available(state, filename) {
+        if !playlist_item_available(state, filename).await {
             return;
         }
     }
 
-    *state.last_advance_time.lock() = Some(Instant::now());
+    state
+        .session
+        .actor
+        .send(SessionCommand::MarkAdvance(Instant::now()));
     if let Err(e) = send_playlist_index(state, next_index, true) {
         tracing::warn!("Failed to send playlist index advance: {}", e);
     }
@@ -1243,28 +2181,55 @@ async fn load_next_file_in_playlist(state: &Arc<AppState>, config: &SyncplayConf
     }
 }
 
+/// Try the advance again that waited for the room to reach the end of the file
+pub(crate) async fn resume_playlist_advance(state: &Arc<AppState>) {
+    let config = state.config.lock().clone();
+    load_next_file_in_playlist(state, &config).await;
+}
+
+/// Whether the advance has to wait for the users `playlist_advance_wait` names
+fn waits_for_room_to_finish(state: &Arc<AppState>, config: &SyncplayConfig) -> bool {
+    let include: fn(&User) -> bool = match config.user.playlist_advance_wait {
+        PlaylistAdvanceWait::Off => return false,
+        PlaylistAdvanceWait::ReadyUsers => |user| user.is_ready.unwrap_or(false),
+        PlaylistAdvanceWait::Controllers => |user| user.is_controller,
+    };
+    let members = crate::commands::connection::room_member_progress(state, include);
+    if members.iter().all(MemberProgress::is_finished) {
+        state.session.endgame.lock().finish_waiting();
+        return false;
+    }
+    let file = state.session.client_state.get_file().unwrap_or_default();
+    if state.session.endgame.lock().wait_to_advance(&file) {
+        crate::commands::connection::emit_system_message(
+            state,
+            &state.tr("playlist-advance-waiting", &[]),
+        );
+    }
+    true
+}
+
 fn is_playing_current_index(state: &Arc<AppState>) -> bool {
-    let Some(index) = state.playlist.get_current_index() else {
+    let Some(index) = state.session.playlist.get_current_index() else {
         return false;
     };
-    let items = state.playlist.get_item_filenames();
+    let items = state.session.playlist.get_item_filenames();
     let Some(filename) = items.get(index) else {
         return false;
     };
-    let current_file = state.client_state.get_file();
+    let current_file = state.session.client_state.get_file();
     same_filename(current_file.as_deref(), Some(filename))
 }
 
-pub fn playlist_item_available(state: &Arc<AppState>, filename: &str) -> bool {
+pub async fn playlist_item_available(state: &Arc<AppState>, filename: &str) -> bool {
     if filename == PRIVACY_HIDDEN_FILENAME {
         return false;
     }
-    let (trusted_domains, only_trusted, media_directories) = {
+    let (trusted_domains, only_trusted) = {
         let config = state.config.lock();
         (
             config.user.trusted_domains.clone(),
             config.user.only_switch_to_trusted_domains,
-            config.player.media_directories.clone(),
         )
     };
     if is_url(filename) {
@@ -1272,22 +2237,20 @@ pub fn playlist_item_available(state: &Arc<AppState>, filename: &str) -> bool {
             is_trustable_and_trusted(filename, &trusted_domains, only_trusted);
         return trustable && trusted;
     }
-    if state.media_index.is_available(filename) {
-        return true;
-    }
-    resolve_media_path(&media_directories, filename).is_some()
+    state.media_resolver().is_available(filename).await
 }
 
 pub(crate) async fn handle_end_of_file(state: &Arc<AppState>) {
     if state
+        .session
         .playlist
         .not_just_changed(PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD)
-        && state.client_state.get_file().is_some()
+        && state.session.client_state.get_file().is_some()
     {
         let player = state.player.lock().clone();
         if let Some(player) = player {
             if let Some(position) = player.get_state().position {
-                state.client_state.set_file_duration(Some(position));
+                state.session.client_state.set_file_duration(Some(position));
             }
         }
     }
@@ -1297,6 +2260,7 @@ pub(crate) async fn handle_end_of_file(state: &Arc<AppState>) {
         return;
     }
     if !state
+        .session
         .playlist
         .not_just_changed(PLAYLIST_LOAD_NEXT_FILE_TIME_FROM_END_THRESHOLD)
     {
@@ -1306,12 +2270,13 @@ pub(crate) async fn handle_end_of_file(state: &Arc<AppState>) {
 }
 
 fn current_user_can_control(state: &Arc<AppState>) -> bool {
-    let room = state.client_state.get_room();
+    let room = state.session.client_state.get_room();
     if !crate::utils::is_controlled_room(&room) {
         return true;
     }
-    let username = state.client_state.get_username();
+    let username = state.session.client_state.get_username();
     state
+        .session
         .client_state
         .get_user(&username)
         .map(|user| user.is_controller)
@@ -1320,6 +2285,7 @@ fn current_user_can_control(state: &Arc<AppState>) -> bool {
 
 fn is_playing_music(state: &Arc<AppState>) -> bool {
     state
+        .session
         .client_state
         .get_file()
         .as_deref()
@@ -1332,15 +2298,16 @@ fn seamless_music_override(state: &Arc<AppState>) -> bool {
 }
 
 fn is_readiness_supported(state: &Arc<AppState>, requires_other_users: bool) -> bool {
-    if !state.server_features.lock().readiness {
+    if !state.session.server_features.lock().readiness {
         return false;
     }
     if !requires_other_users {
         return true;
     }
-    let room = state.client_state.get_room();
-    let username = state.client_state.get_username();
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
     state
+        .session
         .client_state
         .get_users_in_room(&room)
         .iter()
@@ -1348,10 +2315,11 @@ fn is_readiness_supported(state: &Arc<AppState>, requires_other_users: bool) ->
 }
 
 fn recently_rewound(state: &Arc<AppState>) -> bool {
-    let Some(mut last_rewind) = *state.last_rewind_time.lock() else {
+    let session = state.session.actor.snapshot();
+    let Some(mut last_rewind) = session.last_rewind_time else {
         return false;
     };
-    if let Some(last_updated) = *state.last_updated_file_time.lock() {
+    if let Some(last_updated) = session.last_updated_file_time {
         if last_updated > last_rewind {
             if let Some(adjusted) = last_rewind.checked_sub(Duration::from_secs_f64(
                 RECENT_REWIND_FILE_UPDATE_SHIFT_SECONDS,
@@ -1364,24 +2332,23 @@ fn recently_rewound(state: &Arc<AppState>) -> bool {
 }
 
 fn recently_advanced(state: &Arc<AppState>) -> bool {
-    let guard = state.last_advance_time.lock();
-    let Some(last_advance) = guard.as_ref() else {
+    let Some(last_advance) = state.session.actor.snapshot().last_advance_time else {
         return false;
     };
     last_advance.elapsed().as_secs_f64() < RECENT_ADVANCE_GRACE_SECONDS
 }
 
 fn check_protocol_timeout(state: &Arc<AppState>) -> bool {
-    let guard = state.last_global_update.lock();
-    let Some(last_global) = guard.as_ref() else {
+    let last_global = *state.session.last_global_update.lock();
+    let Some(last_global) = last_global else {
         return false;
     };
     if last_global.elapsed().as_secs_f64() <= PROTOCOL_TIMEOUT_SECONDS {
         return false;
     }
-    *state.last_global_update.lock() = None;
-    crate::commands::connection::emit_error_message(state, "Server timed out");
-    if let Some(connection) = state.connection.lock().clone() {
+    *state.session.last_global_update.lock() = None;
+    crate::commands::connection::emit_error_message(state, &state.tr("server-timed-out", &[]));
+    if let Some(connection) = state.session.connection.lock().clone() {
         connection.disconnect();
     }
     let state_clone = state.clone();
@@ -1401,7 +2368,7 @@ async fn apply_ready_toggle(
     let mut paused_value = paused;
 
     if !current_user_can_control(state) {
-        let new_ready = !state.client_state.is_ready();
+        let new_ready = !state.session.client_state.is_ready();
         if let Err(e) = player.set_paused(global_paused).await {
             tracing::warn!("Failed to enforce pause state: {}", e);
         }
@@ -1409,12 +2376,17 @@ async fn apply_ready_toggle(
         if !(recently_rewound(state) || (global_paused && !recently_advanced(state))) {
             let _ = send_ready_state(state, new_ready, true);
             let message = if new_ready {
-                "You are now set as ready"
+                state.tr("set-ready", &[])
             } else {
-                "You are now set as not ready"
+                state.tr("set-not-ready", &[])
             };
-            crate::commands::connection::emit_system_message(state, message);
-            crate::commands::connection::maybe_show_osd(state, &config, message, true);
+            crate::commands::connection::emit_system_message(state, &message);
+            crate::commands::connection::maybe_show_osd(
+                state,
+                &config,
+                &message,
+                crate::config::OsdCategory::Status,
+            );
         }
         return (false, paused_value);
     }
@@ -1437,26 +2409,53 @@ async fn apply_ready_toggle(
         return (false, paused_value);
     }
 
-    if !paused_value && !instaplay_conditions_met(state, &config) {
+    let scheduled_start =
+        !paused_value && std::mem::take(&mut state.autoplay.lock().scheduled_start_due);
+    if !paused_value && !scheduled_start && !instaplay_conditions_met(state, &config) {
         if let Err(e) = player.set_paused(true).await {
             tracing::warn!("Failed to block unpause: {}", e);
         }
         paused_value = true;
         let _ = send_ready_state(state, true, true);
-        let message = "You are now set as ready - unpause again to unpause";
-        crate::commands::connection::emit_system_message(state, message);
-        crate::commands::connection::maybe_show_osd(state, &config, message, true);
+        let message = state.tr("set-ready-unpause-again", &[]);
+        crate::commands::connection::emit_system_message(state, &message);
+        crate::commands::connection::maybe_show_osd(
+            state,
+            &config,
+            &message,
+            crate::config::OsdCategory::Status,
+        );
         return (false, paused_value);
     }
 
-    if let Some(last_paused) = state.last_paused_on_leave_time.lock().take() {
+    // Only whoever may unpause the room counts it down
+    if !paused_value
+        && !scheduled_start
+        && config.user.unpause_countdown_enabled
+        && current_user_can_control(state)
+    {
+        let bypass = std::mem::take(&mut state.autoplay.lock().unpause_countdown_bypass);
+        if !bypass {
+            if let Err(e) = player.set_paused(true).await {
+                tracing::warn!("Failed to hold unpause for countdown: {}", e);
+            }
+            crate::commands::connection::start_unpause_countdown(
+                state.clone(),
+                config.user.unpause_countdown_seconds,
+                true,
+            );
+            return (false, true);
+        }
+    }
+
+    if let Some(last_paused) = state.session.actor.take_paused_on_leave().await {
         if last_paused.elapsed().as_secs_f64() < LAST_PAUSED_DIFF_THRESHOLD_SECONDS {
             return (true, paused_value);
         }
     }
 
     let desired_ready = !paused_value;
-    if desired_ready != state.client_state.is_ready() {
+    if desired_ready != state.session.client_state.is_ready() {
         let _ = send_ready_state(state, desired_ready, false);
     }
 
@@ -1472,17 +2471,17 @@ fn instaplay_conditions_met(state: &Arc<AppState>, config: &SyncplayConfig) -> b
     }
     match config.user.unpause_action {
         UnpauseAction::Always => true,
-        UnpauseAction::IfAlreadyReady => state.client_state.is_ready(),
+        UnpauseAction::IfAlreadyReady => state.session.client_state.is_ready(),
         UnpauseAction::IfOthersReady => {
-            all_other_users_ready(state, &state.client_state.get_room())
+            all_other_users_ready(state, &state.session.client_state.get_room())
         }
         UnpauseAction::IfMinUsersReady => {
-            if !all_other_users_ready(state, &state.client_state.get_room()) {
+            if !all_other_users_ready(state, &state.session.client_state.get_room()) {
                 return false;
             }
             let min_users = config.user.autoplay_min_users;
             if min_users > 0 {
-                let count = users_in_room_count(state, &state.client_state.get_room());
+                let count = users_in_room_count(state, &state.session.client_state.get_room());
                 return count >= min_users as usize;
             }
             true
@@ -1491,8 +2490,8 @@ fn instaplay_conditions_met(state: &Arc<AppState>, config: &SyncplayConfig) -> b
 }
 
 fn all_other_users_ready(state: &Arc<AppState>, room: &str) -> bool {
-    let username = state.client_state.get_username();
-    for user in state.client_state.get_users_in_room(room) {
+    let username = state.session.client_state.get_username();
+    for user in state.session.client_state.get_users_in_room(room) {
         if user.username != username && user.is_ready_with_file() == Some(false) {
             return false;
         }
@@ -1502,8 +2501,8 @@ fn all_other_users_ready(state: &Arc<AppState>, room: &str) -> bool {
 
 fn users_in_room_count(state: &Arc<AppState>, room: &str) -> usize {
     let mut count = 1;
-    let username = state.client_state.get_username();
-    for user in state.client_state.get_users_in_room(room) {
+    let username = state.session.client_state.get_username();
+    for user in state.session.client_state.get_users_in_room(room) {
         if user.username == username {
             continue;
         }
@@ -1514,16 +2513,122 @@ fn users_in_room_count(state: &Arc<AppState>, room: &str) -> usize {
     count
 }
 
+/// Pause and un-ready when neither the player nor the app window has focus
+///
+/// Screen locks are covered as they take focus away from every window.
+async fn handle_focus_lost(state: &Arc<AppState>, player_state: &PlayerState) {
+    let config = state.config.lock().clone();
+    if !config.user.pause_on_focus_loss || player_state.paused != Some(false) {
+        return;
+    }
+    crate::commands::connection::pause_local_player(state).await;
+    if is_readiness_supported(state, false) && state.session.client_state.is_ready() {
+        if let Err(e) = send_ready_state(state, false, false) {
+            tracing::warn!("Failed to clear ready state after focus loss: {}", e);
+        }
+    }
+    let message = state.tr("paused-focus-lost", &[]);
+    crate::commands::connection::emit_system_message(state, &message);
+    crate::commands::connection::maybe_show_osd(
+        state,
+        &config,
+        &message,
+        crate::config::OsdCategory::Status,
+    );
+}
+
+/// Mark ready once the loaded file matches the room and, optionally, stopped growing
+fn maybe_auto_ready(state: &Arc<AppState>, player_state: &PlayerState) {
+    let config = state.config.lock().clone();
+    if !config.user.auto_ready_on_matching_file || !is_readiness_supported(state, false) {
+        return;
+    }
+    let Some(filename) = player_state.filename.clone() else {
+        return;
+    };
+    let now = Instant::now();
+    {
+        let mut auto_ready = state.auto_ready.lock();
+        if auto_ready.filename.as_deref() != Some(filename.as_str()) {
+            *auto_ready = crate::app_state::AutoReadyState {
+                filename: Some(filename.clone()),
+                ..Default::default()
+            };
+        }
+        if auto_ready.applied {
+            return;
+        }
+        if let Some(last_check) = auto_ready.last_check {
+            if now.duration_since(last_check).as_secs_f64() < AUTO_READY_CHECK_INTERVAL_SECONDS {
+                return;
+            }
+        }
+        auto_ready.last_check = Some(now);
+    }
+    if state.session.client_state.is_ready() || !file_matches_room(state, &filename) {
+        return;
+    }
+
+    if config.user.auto_ready_require_stable_size {
+        let size = player_state
+            .path
+            .as_deref()
+            .filter(|path| !is_url(path))
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len());
+        let mut auto_ready = state.auto_ready.lock();
+        if let Some(size) = size {
+            if auto_ready.last_size != Some(size) {
+                auto_ready.last_size = Some(size);
+                auto_ready.stable_since = Some(now);
+                return;
+            }
+        }
+        let stable_for = auto_ready
+            .stable_since
+            .map(|since| now.duration_since(since).as_secs())
+            .unwrap_or(0);
+        if size.is_some() && stable_for < config.user.auto_ready_stable_seconds {
+            return;
+        }
+    }
+
+    state.auto_ready.lock().applied = true;
+    if let Err(e) = send_ready_state(state, true, false) {
+        tracing::warn!("Failed to set automatic ready state: {}", e);
+        return;
+    }
+    crate::commands::connection::emit_system_message(
+        state,
+        &state.tr("auto-ready-matching-file", &[]),
+    );
+}
+
+fn file_matches_room(state: &Arc<AppState>, filename: &str) -> bool {
+    if let Some(current) = state.session.playlist.get_current_filename() {
+        return same_filename(Some(filename), Some(&current));
+    }
+    let room = state.session.client_state.get_room();
+    let username = state.session.client_state.get_username();
+    state
+        .session
+        .client_state
+        .get_users_in_room(&room)
+        .iter()
+        .filter(|user| user.username != username)
+        .any(|user| same_filename(Some(filename), user.file.as_deref()))
+}
+
 fn send_ready_state(
     state: &Arc<AppState>,
     is_ready: bool,
     manually_initiated: bool,
 ) -> Result<(), String> {
-    if !state.server_features.lock().readiness {
+    if !state.session.server_features.lock().readiness {
         return Ok(());
     }
-    state.client_state.set_ready(is_ready);
-    let username = state.client_state.get_username();
+    state.session.client_state.set_ready(is_ready);
+    let username = state.session.client_state.get_username();
     let message = ProtocolMessage::Set {
         Set: Box::new(SetMessage {
             room: None,
@@ -1543,39 +2648,10 @@ fn send_ready_state(
         }),
     };
 
-    let Some(connection) = state.connection.lock().clone() else {
+    let Some(connection) = state.session.connection.lock().clone() else {
         return Err("Not connected to server".to_string());
     };
     connection
         .send(message)
         .map_err(|e| format!("Failed to send ready state: {}", e))
 }
-
-#[cfg(test)]
-mod tests {
-    use super::resolve_media_path;
-    use std::fs;
-    use tempfile::TempDir;
-
-    #[test]
-    fn test_resolve_media_path_multiple_directories() {
-        let dir1 = TempDir::new().unwrap();
-        let dir2 = TempDir::new().unwrap();
-        let filename = "movie.mp4";
-        let file_path = dir2.path().join(filename);
-        fs::write(&file_path, b"test").unwrap();
-
-        let directories = vec![
-            dir1.path().to_string_lossy().to_string(),
-            dir2.path().to_string_lossy().to_string(),
-        ];
-        let resolved = resolve_media_path(&directories, filename).unwrap();
-        assert_eq!(resolved, file_path);
-    }
-
-    #[test]
-    fn test_resolve_media_path_empty() {
-        let directories: Vec<String> = Vec::new();
-        assert!(resolve_media_path(&directories, "file.mp4").is_none());
-    }
-}
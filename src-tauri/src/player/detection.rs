@@ -1,12 +1,101 @@
+use super::backend::{player_kind_from_path, BackendCapabilities, PlayerKind};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
 
+/// Where a detected player was installed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    #[default]
+    System,
+    Flatpak,
+    Snap,
+}
+
+impl InstallSource {
+    pub fn from_path(path: &str) -> Self {
+        let lower = path.to_ascii_lowercase();
+        if lower.contains("/flatpak/") {
+            Self::Flatpak
+        } else if lower.starts_with("/snap/") {
+            Self::Snap
+        } else {
+            Self::System
+        }
+    }
+}
+
+/// How the client talks to a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcKind {
+    /// mpv JSON IPC socket, also used by mpv.net and IINA
+    MpvJson,
+    /// Syncplay Lua interface over TCP
+    VlcLua,
+    /// MPlayer slave mode over stdin
+    MplayerSlave,
+    /// MPC-HC/MPC-BE API window messages
+    MpcApi,
+}
+
+/// What the client can do with a detected player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PlayerCapabilities {
+    pub speed_control: bool,
+    /// Chat messages get their own overlay instead of the plain OSD
+    pub chat_osd: bool,
+    pub ipc: Option<IpcKind>,
+}
+
+impl PlayerCapabilities {
+    pub fn for_kind(kind: PlayerKind) -> Self {
+        let ipc = match kind {
+            PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina => IpcKind::MpvJson,
+            PlayerKind::Vlc => IpcKind::VlcLua,
+            PlayerKind::Mplayer => IpcKind::MplayerSlave,
+            PlayerKind::MpcHc | PlayerKind::MpcBe => IpcKind::MpcApi,
+            PlayerKind::Unknown => return Self::default(),
+        };
+        let backend = BackendCapabilities::for_kind(kind);
+        Self {
+            speed_control: backend.speed_control,
+            chat_osd: backend.chat_display,
+            ipc: Some(ipc),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedPlayer {
     pub name: String,
     pub path: String,
     pub version: Option<String>,
+    #[serde(default)]
+    pub capabilities: PlayerCapabilities,
+    #[serde(default)]
+    pub source: InstallSource,
+}
+
+impl DetectedPlayer {
+    fn new(name: &str, path: &std::path::Path, version: Option<String>) -> Self {
+        let path = path.to_string_lossy().to_string();
+        Self {
+            name: name.to_string(),
+            capabilities: PlayerCapabilities::for_kind(player_kind_from_path(&path)),
+            source: InstallSource::from_path(&path),
+            path,
+            version,
+        }
+    }
+}
+
+/// Detected players as cached between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerDetectionCache {
+    pub players: Vec<DetectedPlayer>,
+    pub updated_at: Option<i64>,
 }
 
 /// Detect available media players on the system
@@ -63,11 +152,7 @@ fn detect_mpv() -> Option<DetectedPlayer> {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 let version = parse_mpv_version(&version_str);
 
-                return Some(DetectedPlayer {
-                    name: "MPV".to_string(),
-                    path: path.to_string_lossy().to_string(),
-                    version,
-                });
+                return Some(DetectedPlayer::new("MPV", &path, version));
             }
         }
     }
@@ -84,11 +169,7 @@ fn detect_vlc() -> Option<DetectedPlayer> {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 let version = parse_vlc_version(&version_str);
 
-                return Some(DetectedPlayer {
-                    name: "VLC".to_string(),
-                    path: path.to_string_lossy().to_string(),
-                    version,
-                });
+                return Some(DetectedPlayer::new("VLC", &path, version));
             }
         }
     }
@@ -115,11 +196,7 @@ fn detect_mpvnet() -> Option<DetectedPlayer> {
                     }
                 });
 
-            return Some(DetectedPlayer {
-                name: "mpv.net".to_string(),
-                path: path.to_string_lossy().to_string(),
-                version,
-            });
+            return Some(DetectedPlayer::new("mpv.net", &path, version));
         }
     }
 
@@ -134,11 +211,7 @@ fn detect_mplayer() -> Option<DetectedPlayer> {
             if output.status.success() {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 let version = parse_mplayer_version(&version_str);
-                return Some(DetectedPlayer {
-                    name: "MPlayer".to_string(),
-                    path: path.to_string_lossy().to_string(),
-                    version,
-                });
+                return Some(DetectedPlayer::new("MPlayer", &path, version));
             }
         }
     }
@@ -151,11 +224,11 @@ fn detect_mpc_hc() -> Option<DetectedPlayer> {
     let paths = get_mpc_hc_paths();
     for path in paths {
         if path.exists() {
-            return Some(DetectedPlayer {
-                name: "MPC-HC".to_string(),
-                path: path.to_string_lossy().to_string(),
-                version: None,
-            });
+            let version = mpc_registry_version(&[
+                "HKLM\\SOFTWARE\\MPC-HC\\MPC-HC",
+                "HKCU\\Software\\MPC-HC\\MPC-HC",
+            ]);
+            return Some(DetectedPlayer::new("MPC-HC", &path, version));
         }
     }
     None
@@ -166,11 +239,9 @@ fn detect_mpc_be() -> Option<DetectedPlayer> {
     let paths = get_mpc_be_paths();
     for path in paths {
         if path.exists() {
-            return Some(DetectedPlayer {
-                name: "MPC-BE".to_string(),
-                path: path.to_string_lossy().to_string(),
-                version: None,
-            });
+            let version =
+                mpc_registry_version(&["HKLM\\SOFTWARE\\MPC-BE", "HKCU\\Software\\MPC-BE"]);
+            return Some(DetectedPlayer::new("MPC-BE", &path, version));
         }
     }
     None
@@ -179,23 +250,48 @@ fn detect_mpc_be() -> Option<DetectedPlayer> {
 #[cfg(target_os = "macos")]
 fn detect_iina() -> Option<DetectedPlayer> {
     let paths = vec![
-        PathBuf::from("/Applications/IINA.app/Contents/MacOS/IINA"),
         PathBuf::from("/Applications/IINA.app/Contents/MacOS/iina-cli"),
+        PathBuf::from("/Applications/IINA.app/Contents/MacOS/IINA"),
     ];
 
     for path in paths {
         if path.exists() {
-            return Some(DetectedPlayer {
-                name: "IINA".to_string(),
-                path: path.to_string_lossy().to_string(),
-                version: None,
-            });
+            return Some(DetectedPlayer::new("IINA", &path, None));
         }
     }
 
     None
 }
 
+/// Launchers Flatpak exports for `app_id`, system-wide and per user
+#[cfg(target_os = "linux")]
+fn flatpak_export_paths(app_id: &str) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("/var/lib/flatpak/exports/bin").join(app_id)];
+    if let Some(home) = std::env::var_os("HOME") {
+        paths.push(
+            PathBuf::from(home)
+                .join(".local/share/flatpak/exports/bin")
+                .join(app_id),
+        );
+    }
+    paths
+}
+
+/// Version the MPC installer recorded under one of `keys`
+#[cfg(target_os = "windows")]
+fn mpc_registry_version(keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", "Version"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_registry_value(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
 fn get_mpv_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
@@ -212,6 +308,8 @@ fn get_mpv_paths() -> Vec<PathBuf> {
         paths.push(PathBuf::from("/usr/bin/mpv"));
         paths.push(PathBuf::from("/usr/local/bin/mpv"));
         paths.push(PathBuf::from("/opt/mpv/mpv"));
+        paths.push(PathBuf::from("/snap/bin/mpv"));
+        paths.extend(flatpak_export_paths("io.mpv.Mpv"));
     }
 
     #[cfg(target_os = "windows")]
@@ -282,6 +380,7 @@ fn get_vlc_paths() -> Vec<PathBuf> {
         paths.push(PathBuf::from("/usr/local/bin/vlc"));
         paths.push(PathBuf::from("/usr/local/bin/vlc-wrapper"));
         paths.push(PathBuf::from("/snap/bin/vlc"));
+        paths.extend(flatpak_export_paths("org.videolan.VLC"));
     }
 
     #[cfg(target_os = "windows")]
@@ -395,3 +494,51 @@ fn parse_mplayer_version(output: &str) -> Option<String> {
         .find(|line| line.to_ascii_lowercase().contains("mplayer"))
         .and_then(|line| line.split_whitespace().nth(1).map(|v| v.to_string()))
 }
+
+/// Value of a `reg query` line like "    Version    REG_SZ    1.9.24"
+fn parse_registry_value(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, value) = line.split_once("REG_SZ")?;
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_source_from_path() {
+        assert_eq!(
+            InstallSource::from_path("/var/lib/flatpak/exports/bin/io.mpv.Mpv"),
+            InstallSource::Flatpak
+        );
+        assert_eq!(
+            InstallSource::from_path("/snap/bin/vlc"),
+            InstallSource::Snap
+        );
+        assert_eq!(
+            InstallSource::from_path("/usr/bin/mpv"),
+            InstallSource::System
+        );
+    }
+
+    #[test]
+    fn test_detected_player_capabilities() {
+        let mpv = DetectedPlayer::new("MPV", std::path::Path::new("/usr/bin/mpv"), None);
+        assert_eq!(mpv.capabilities.ipc, Some(IpcKind::MpvJson));
+        assert!(mpv.capabilities.chat_osd);
+        let vlc = DetectedPlayer::new("VLC", std::path::Path::new("/snap/bin/vlc"), None);
+        assert_eq!(vlc.capabilities.ipc, Some(IpcKind::VlcLua));
+        assert!(vlc.capabilities.speed_control && !vlc.capabilities.chat_osd);
+        assert_eq!(vlc.source, InstallSource::Snap);
+    }
+
+    #[test]
+    fn test_parse_registry_value() {
+        let output = "HKEY_LOCAL_MACHINE\\SOFTWARE\\MPC-HC\r\n    Version    REG_SZ    1.9.24\r\n";
+        assert_eq!(parse_registry_value(output), Some("1.9.24".to_string()));
+        assert_eq!(parse_registry_value("ERROR: not found"), None);
+    }
+}
@@ -0,0 +1,80 @@
+// Idle screen module
+// Generated view the player shows while no file is open
+
+/// Plain background generated by mpv's lavfi input; the text goes on an OSD overlay
+pub const IDLE_SOURCE: &str = "av://lavfi:color=c=0x16181d:s=1280x720:r=1";
+/// Name of the bundled placeholder image, for players without a generated source
+pub const PLACEHOLDER_IMAGE: &str = "placeholder.png";
+
+/// Whether the player has our idle view open
+///
+/// mpv reports the part of a URL after its last `/` as the filename, so either the
+/// path or the filename is enough to recognise the generated source.
+pub fn is_idle_source(path: Option<&str>, filename: Option<&str>) -> bool {
+    if path == Some(IDLE_SOURCE) {
+        return true;
+    }
+    let source_name = IDLE_SOURCE.rsplit('/').next().unwrap_or(IDLE_SOURCE);
+    match filename {
+        Some(name) => name == source_name || name == IDLE_SOURCE || name == PLACEHOLDER_IMAGE,
+        None => false,
+    }
+}
+
+/// Lines of the idle view, largest first
+pub struct IdleScreenText {
+    pub title: String,
+    pub details: Vec<String>,
+}
+
+impl IdleScreenText {
+    /// ASS events for mpv's `osd-overlay`, centred on a 720 line canvas
+    pub fn to_ass(&self) -> String {
+        let mut ass = format!("{{\\an5\\fs56\\b1}}{}{{\\b0}}", escape_ass(&self.title));
+        for line in &self.details {
+            ass.push_str("\\N{\\fs32}");
+            ass.push_str(&escape_ass(line));
+        }
+        ass
+    }
+}
+
+/// Backslashes would start ASS tags, so they are swapped for a lookalike
+fn escape_ass(text: &str) -> String {
+    text.replace('\\', "＼")
+        .replace('{', "\\{")
+        .replace('}', "\\}")
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_idle_source() {
+        assert!(is_idle_source(Some(IDLE_SOURCE), None));
+        assert!(is_idle_source(
+            None,
+            Some("lavfi:color=c=0x16181d:s=1280x720:r=1")
+        ));
+        assert!(is_idle_source(
+            Some("/opt/syncplay/resources/placeholder.png"),
+            Some("placeholder.png")
+        ));
+        assert!(!is_idle_source(Some("/media/a.mkv"), Some("a.mkv")));
+        assert!(!is_idle_source(None, None));
+    }
+
+    #[test]
+    fn test_idle_text_escapes_ass() {
+        let text = IdleScreenText {
+            title: "Room {a}".to_string(),
+            details: vec!["syncplay.pl:8999".to_string()],
+        };
+        assert_eq!(
+            text.to_ass(),
+            "{\\an5\\fs56\\b1}Room \\{a\\}{\\b0}\\N{\\fs32}syncplay.pl:8999"
+        );
+    }
+}
@@ -1,13 +1,20 @@
 pub mod backend;
+pub mod buffering;
 pub mod commands;
 pub mod controller;
 pub mod detection;
 pub mod events;
+pub mod idle;
 pub mod mpc_api;
 pub mod mpc_web;
 pub mod mplayer_slave;
 pub mod mpv_backend;
 pub mod mpv_ipc;
+pub mod mpv_profile;
 pub mod properties;
+pub mod registry;
+pub mod sandbox;
+pub mod stall;
 pub mod vlc_rc;
 pub mod vlc_syncplay;
+pub mod watchdog;
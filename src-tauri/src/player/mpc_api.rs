@@ -1,5 +1,6 @@
-use super::backend::{PlayerBackend, PlayerKind};
+use super::backend::{OsdStyle, PlayerBackend, PlayerKind};
 use super::properties::PlayerState;
+use crate::config::OsdPosition;
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
@@ -15,7 +16,8 @@ const MPC_LOCK_WAIT_TIME: Duration = Duration::from_millis(200);
 const MPC_RETRY_WAIT_TIME: Duration = Duration::from_millis(10);
 const MPC_MAX_RETRIES: usize = 30;
 const MPC_PAUSE_TOGGLE_DELAY: Duration = Duration::from_millis(50);
-const MPC_OSD_POSITION: i32 = 1;
+const MPC_OSD_POSITION_TOP_LEFT: i32 = 1;
+const MPC_OSD_POSITION_TOP_RIGHT: i32 = 2;
 const MPC_MIN_VER: &str = "1.6.4";
 const MPC_BE_MIN_VER: &str = "1.5.2.3123";
 
@@ -535,13 +537,13 @@ impl MpcApiBackend {
         self.file_ready.load(Ordering::SeqCst)
     }
 
-    fn send_osd(&self, message: &str, duration_ms: i32) -> anyhow::Result<()> {
+    fn send_osd(&self, message: &str, duration_ms: i32, position: i32) -> anyhow::Result<()> {
         self.listener.send_command(
             CMD_OSDSHOWMESSAGE,
             Some(CommandPayload::Osd {
                 message: message.to_string(),
                 duration_ms,
-                position: MPC_OSD_POSITION,
+                position,
             }),
         )
     }
@@ -626,9 +628,9 @@ impl PlayerBackend for MpcApiBackend {
         Ok(())
     }
 
-    fn show_osd(&self, text: &str, duration_ms: Option<u64>) -> anyhow::Result<()> {
-        let duration = duration_ms.unwrap_or(3000) as i32;
-        self.send_osd(text, duration)
+    fn show_osd(&self, text: &str, style: &OsdStyle) -> anyhow::Result<()> {
+        let duration = style.duration_ms.min(i32::MAX as u64) as i32;
+        self.send_osd(text, duration, mpc_osd_position(style.position))
     }
 
     async fn shutdown(&self) -> anyhow::Result<()> {
@@ -738,6 +740,16 @@ fn split_mpc_fields(input: &str) -> Vec<String> {
     parts
 }
 
+/// MPC only anchors OSD messages to the top-left or top-right corner
+fn mpc_osd_position(position: OsdPosition) -> i32 {
+    match position {
+        OsdPosition::TopRight | OsdPosition::Right | OsdPosition::BottomRight => {
+            MPC_OSD_POSITION_TOP_RIGHT
+        }
+        _ => MPC_OSD_POSITION_TOP_LEFT,
+    }
+}
+
 fn mpc_filename_from_path(path: &str) -> Option<String> {
     path.trim_end_matches(['\\', '/'])
         .rsplit(['\\', '/'])
@@ -747,7 +759,11 @@ fn mpc_filename_from_path(path: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{mpc_filename_from_path, split_mpc_fields};
+    use super::{
+        mpc_filename_from_path, mpc_osd_position, split_mpc_fields, MPC_OSD_POSITION_TOP_LEFT,
+        MPC_OSD_POSITION_TOP_RIGHT,
+    };
+    use crate::config::OsdPosition;
 
     #[test]
     fn split_mpc_fields_keeps_windows_path_separators() {
@@ -810,6 +826,18 @@ mod tests {
             Some("Clip.mkv")
         );
     }
+
+    #[test]
+    fn mpc_osd_position_maps_to_nearest_corner() {
+        assert_eq!(
+            mpc_osd_position(OsdPosition::BottomRight),
+            MPC_OSD_POSITION_TOP_RIGHT
+        );
+        assert_eq!(
+            mpc_osd_position(OsdPosition::Center),
+            MPC_OSD_POSITION_TOP_LEFT
+        );
+    }
 }
 
 fn meets_min_version(version: &str, min: &str) -> bool {
@@ -903,7 +931,7 @@ impl PlayerBackend for MpcApiBackend {
         Err(anyhow::anyhow!("MPC backend is only supported on Windows"))
     }
 
-    fn show_osd(&self, _text: &str, _duration_ms: Option<u64>) -> anyhow::Result<()> {
+    fn show_osd(&self, _text: &str, _style: &OsdStyle) -> anyhow::Result<()> {
         Err(anyhow::anyhow!("MPC backend is only supported on Windows"))
     }
 }
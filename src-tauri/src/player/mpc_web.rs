@@ -7,7 +7,7 @@ use reqwest::Client;
 use tokio::process::{Child, Command};
 use tracing::{debug, info, warn};
 
-use super::backend::PlayerBackend;
+use super::backend::{OsdStyle, PlayerBackend};
 use super::properties::PlayerState;
 
 const DEFAULT_MPC_PORT: u16 = 13579;
@@ -154,7 +154,7 @@ impl PlayerBackend for MpcWebBackend {
         self.send_command(0xA0000000, Some(path)).await
     }
 
-    fn show_osd(&self, text: &str, _duration_ms: Option<u64>) -> anyhow::Result<()> {
+    fn show_osd(&self, text: &str, _style: &OsdStyle) -> anyhow::Result<()> {
         let message = text.replace('"', "'");
         let client = self.client.clone();
         let url = format!(
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use anyhow::Context;
 use async_trait::async_trait;
@@ -8,15 +8,20 @@ use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::Mutex as TokioMutex;
 use tracing::{debug, info, warn};
 
-use super::backend::{PlayerBackend, PlayerKind};
+use super::backend::{format_chat_osd, OsdStyle, PlayerBackend, PlayerKind};
 use super::properties::PlayerState;
+use crate::app_state::AppState;
+use crate::player::controller::handle_end_of_file;
 
 const MPLAYER_ARGS: &[&str] = &[
     "-slave",
+    // Stay open at the end of the file so the playlist can load the next one
+    "-idle",
     "--hr-seek=always",
     "-nomsgcolor",
     "-msglevel",
-    "all=1:global=4:cplayer=4",
+    // global=6 prints the EOF code, identify=4 the ID_ lines for pauses and new files
+    "all=1:global=6:cplayer=4:identify=4",
     "-af-add",
     "scaletempo",
 ];
@@ -31,6 +36,13 @@ enum ResponseKey {
     Speed,
 }
 
+/// Lines MPlayer prints on its own, without being asked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MplayerEvent {
+    Paused,
+    EndOfFile,
+}
+
 pub struct MplayerBackend {
     kind: PlayerKind,
     stdin: Arc<TokioMutex<ChildStdin>>,
@@ -42,6 +54,7 @@ impl MplayerBackend {
         player_path: &str,
         args: &[String],
         initial_file: Option<&str>,
+        app_state: Weak<AppState>,
     ) -> anyhow::Result<(Self, Child)> {
         info!(
             "Starting player: kind=Mplayer, path={}, args={:?}, initial_file={:?}",
@@ -89,6 +102,13 @@ impl MplayerBackend {
                 if line.is_empty() {
                     continue;
                 }
+                if parse_event(&line) == Some(MplayerEvent::EndOfFile) {
+                    debug!("mplayer >> {}", line);
+                    if let Some(app_state) = app_state.upgrade() {
+                        handle_end_of_file(&app_state).await;
+                    }
+                    continue;
+                }
                 handle_line(&state_clone, &line);
             }
         });
@@ -115,10 +135,36 @@ impl MplayerBackend {
         guard.flush().await.context("Failed to flush MPlayer")?;
         Ok(())
     }
+
+    /// Send an OSD command without waiting for it to be written
+    fn send_osd_text(&self, text: &str, duration_ms: u64) {
+        let cmd = format!(
+            "osd_show_text \"{}\" {}",
+            sanitize_osd_text(text),
+            duration_ms
+        );
+        let stdin = self.stdin.clone();
+        tokio::spawn(async move {
+            let mut guard = stdin.lock().await;
+            let _ = guard.write_all(format!("{}\n", cmd).as_bytes()).await;
+            let _ = guard.flush().await;
+        });
+    }
+}
+
+/// MPlayer's slave parser ends the text at a quote and reads `\` as an escape
+fn sanitize_osd_text(text: &str) -> String {
+    text.replace('\\', "/")
+        .replace('"', "'")
+        .replace(['\n', '\r'], " ")
 }
 
 fn handle_line(state: &Arc<Mutex<PlayerState>>, line: &str) {
     debug!("mplayer >> {}", line);
+    if parse_event(line) == Some(MplayerEvent::Paused) {
+        state.lock().paused = Some(true);
+        return;
+    }
     if let Some((key, value)) = parse_response(line) {
         let mut state_guard = state.lock();
         match key {
@@ -146,9 +192,15 @@ fn parse_response(line: &str) -> Option<(ResponseKey, String)> {
     if let Some(value) = line.strip_prefix("ANS_TIME_POSITION=") {
         return Some((ResponseKey::Position, value.to_string()));
     }
-    if let Some(value) = line.strip_prefix("ANS_LENGTH=") {
+    if let Some(value) = line
+        .strip_prefix("ANS_LENGTH=")
+        .or_else(|| line.strip_prefix("ID_LENGTH="))
+    {
         return Some((ResponseKey::Duration, value.to_string()));
     }
+    if let Some(value) = line.strip_prefix("ID_FILENAME=") {
+        return Some((ResponseKey::Path, value.trim_matches('"').to_string()));
+    }
     if let Some(value) = line.strip_prefix("ANS_FILENAME=") {
         let value = value.trim_matches('"').to_string();
         return Some((ResponseKey::Path, value.clone()));
@@ -170,6 +222,22 @@ fn parse_response(line: &str) -> Option<(ResponseKey, String)> {
     None
 }
 
+fn parse_event(line: &str) -> Option<MplayerEvent> {
+    let line = line.trim();
+    if line == "ID_PAUSED" {
+        return Some(MplayerEvent::Paused);
+    }
+    // `EOF code: 1` is the end of the file, also when cut short by -endpos; other codes
+    // are quits and playlist skips
+    if line
+        .strip_prefix("EOF code:")
+        .is_some_and(|code| code.trim() == "1")
+    {
+        return Some(MplayerEvent::EndOfFile);
+    }
+    None
+}
+
 #[async_trait]
 impl PlayerBackend for MplayerBackend {
     fn kind(&self) -> PlayerKind {
@@ -224,18 +292,42 @@ impl PlayerBackend for MplayerBackend {
             .await
     }
 
+    async fn frame_step(&self, backwards: bool) -> anyhow::Result<()> {
+        if backwards {
+            anyhow::bail!("{} cannot step frames backwards", self.name());
+        }
+        self.send_command("frame_step").await
+    }
+
+    async fn set_audio_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.send_command(&format!(
+            "set_property audio_delay {:.3}",
+            ms as f64 / 1000.0
+        ))
+        .await
+    }
+
+    async fn set_sub_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.send_command(&format!("set_property sub_delay {:.3}", ms as f64 / 1000.0))
+            .await
+    }
+
     async fn load_file(&self, path: &str) -> anyhow::Result<()> {
         self.send_command(&format!("loadfile \"{}\" 0", path)).await
     }
 
-    fn show_osd(&self, text: &str, _duration_ms: Option<u64>) -> anyhow::Result<()> {
-        let cmd = format!("osd_show_text \"{}\"", text.replace('"', "'"));
-        let stdin = self.stdin.clone();
-        tokio::spawn(async move {
-            let mut guard = stdin.lock().await;
-            let _ = guard.write_all(format!("{}\n", cmd).as_bytes()).await;
-            let _ = guard.flush().await;
-        });
+    fn show_osd(&self, text: &str, style: &OsdStyle) -> anyhow::Result<()> {
+        self.send_osd_text(text, style.duration_ms);
+        Ok(())
+    }
+
+    fn show_chat_message(
+        &self,
+        username: Option<&str>,
+        message: &str,
+        style: &OsdStyle,
+    ) -> anyhow::Result<()> {
+        self.send_osd_text(&format_chat_osd(username, message), style.duration_ms);
         Ok(())
     }
 
@@ -243,3 +335,36 @@ impl PlayerBackend for MplayerBackend {
         self.send_command("quit").await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event() {
+        assert_eq!(parse_event("ID_PAUSED"), Some(MplayerEvent::Paused));
+        assert_eq!(parse_event("EOF code: 1  "), Some(MplayerEvent::EndOfFile));
+        assert_eq!(parse_event("EOF code: 4"), None);
+        assert_eq!(parse_event("ANS_pause=yes"), None);
+    }
+
+    #[test]
+    fn test_identify_lines_update_file_info() {
+        let state = Arc::new(Mutex::new(PlayerState::default()));
+        handle_line(&state, "ID_FILENAME=/videos/a.mkv");
+        handle_line(&state, "ID_LENGTH=1420.50");
+        handle_line(&state, "ID_PAUSED");
+        let state = state.lock();
+        assert_eq!(state.path.as_deref(), Some("/videos/a.mkv"));
+        assert_eq!(state.duration, Some(1420.5));
+        assert_eq!(state.paused, Some(true));
+    }
+
+    #[test]
+    fn test_sanitize_osd_text() {
+        assert_eq!(
+            sanitize_osd_text("say \"hi\"\nC:\\videos"),
+            "say 'hi' C:/videos"
+        );
+    }
+}
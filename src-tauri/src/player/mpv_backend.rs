@@ -7,13 +7,16 @@ use parking_lot::Mutex;
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::ChildStdout;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
 use tokio::time::timeout;
 use tracing::{debug, warn};
 
-use super::backend::{PlayerBackend, PlayerKind};
+use super::backend::{
+    ass_alignment, format_chat_osd, Chapter, OsdStyle, PlayerBackend, PlayerKind,
+};
 use super::commands::MpvCommand;
 use super::events::{EndFileReason, MpvPlayerEvent};
+use super::idle::IdleScreenText;
 use super::mpv_ipc::MpvIpc;
 use super::properties::PlayerState;
 use crate::app_state::AppState;
@@ -22,6 +25,7 @@ use crate::commands::connection::emit_error_message;
 use crate::player::controller::handle_end_of_file;
 use crate::player::controller::is_placeholder_file;
 use crate::player::controller::stop_player;
+use crate::utils::sanitize_display_text;
 
 pub struct MpvBackend {
     kind: PlayerKind,
@@ -36,7 +40,8 @@ pub struct MpvBackend {
 const MPV_NEWFILE_IGNORE_TIME: Duration = Duration::from_secs(1);
 const STREAM_ADDITIONAL_IGNORE_TIME: Duration = Duration::from_secs(10);
 const PLAYER_ASK_DELAY: Duration = Duration::from_millis(100);
-const MPV_UNRESPONSIVE_THRESHOLD: Duration = Duration::from_secs(60);
+/// Overlay ids are private to each IPC client, so this cannot clash with scripts
+const IDLE_OVERLAY_ID: u64 = 1;
 const MPV_SCRIPT_MESSAGE_TIMEOUT: Duration = Duration::from_millis(250);
 const DO_NOT_RESET_POSITION_THRESHOLD: f64 = 1.0;
 const MPV_INPUT_BACKSLASH_SUBSTITUTE: &str = "＼";
@@ -161,6 +166,9 @@ impl MpvBackend {
         if next_loaded {
             *self.last_loaded.lock() = Some(Instant::now());
             self.ipc.set_ready(true);
+            if let Err(e) = self.ipc.set_osd_overlay(IDLE_OVERLAY_ID, None) {
+                debug!("Failed to hide the idle screen: {}", e);
+            }
         } else {
             *self.last_loaded.lock() = None;
             self.ipc.set_ready(false);
@@ -191,7 +199,7 @@ impl PlayerBackend for MpvBackend {
         let is_loaded = self.file_loaded.load(Ordering::SeqCst);
         if let Some(app_state) = self.state.upgrade() {
             if !is_loaded || self.recently_reset() {
-                let global = app_state.client_state.get_global_state();
+                let global = app_state.session.client_state.get_global_state();
                 state.position = Some(global.position);
                 state.paused = Some(global.paused);
                 return state;
@@ -207,19 +215,6 @@ impl PlayerBackend for MpvBackend {
                         state.position = Some(position + diff.as_secs_f64());
                     }
                 }
-                if diff > MPV_UNRESPONSIVE_THRESHOLD {
-                    if let Some(app_state) = self.state.upgrade() {
-                        let message = format!(
-                            "mpv has not responded for {} seconds so appears to have malfunctioned. Please restart Syncplay.",
-                            diff.as_secs()
-                        );
-                        emit_error_message(&app_state, &message);
-                        let app_state_clone = app_state.clone();
-                        tokio::spawn(async move {
-                            let _ = stop_player(&app_state_clone).await;
-                        });
-                    }
-                }
             }
         }
 
@@ -237,6 +232,13 @@ impl PlayerBackend for MpvBackend {
         Ok(())
     }
 
+    async fn ping(&self) -> anyhow::Result<()> {
+        self.ipc
+            .send_command_async(MpvCommand::get_property("pause", 0))
+            .await?;
+        Ok(())
+    }
+
     async fn set_position(&self, position: f64) -> anyhow::Result<()> {
         if position < DO_NOT_RESET_POSITION_THRESHOLD && self.recently_reset() {
             return Ok(());
@@ -256,6 +258,22 @@ impl PlayerBackend for MpvBackend {
         self.ipc.load_file(path).await
     }
 
+    async fn frame_step(&self, backwards: bool) -> anyhow::Result<()> {
+        self.ipc.frame_step(backwards).await
+    }
+
+    async fn set_audio_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.ipc.set_delay("audio-delay", ms).await
+    }
+
+    async fn set_sub_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.ipc.set_delay("sub-delay", ms).await
+    }
+
+    async fn get_chapters(&self) -> anyhow::Result<Vec<Chapter>> {
+        self.ipc.get_chapters().await
+    }
+
     fn mark_reset(&self, is_stream: bool) {
         let mut until = Instant::now() + MPV_NEWFILE_IGNORE_TIME;
         if is_stream {
@@ -264,7 +282,15 @@ impl PlayerBackend for MpvBackend {
         *self.reset_ignore_until.lock() = Some(until);
     }
 
-    fn show_osd(&self, text: &str, duration_ms: Option<u64>) -> anyhow::Result<()> {
+    fn is_alive(&self) -> bool {
+        !self.ipc.is_closed()
+    }
+
+    fn state_notifier(&self) -> Option<Arc<Notify>> {
+        Some(self.ipc.state_notifier())
+    }
+
+    fn show_osd(&self, text: &str, style: &OsdStyle) -> anyhow::Result<()> {
         if let Some(state) = self.state.upgrade() {
             let config = state.config.lock().clone();
             if config.user.chat_output_enabled {
@@ -281,18 +307,50 @@ impl PlayerBackend for MpvBackend {
                 return Ok(());
             }
         }
-        self.ipc.show_osd(text, duration_ms)
+        self.ipc.show_osd(text, style)
     }
 
-    fn show_chat_message(&self, username: Option<&str>, message: &str) -> anyhow::Result<()> {
-        let mut output = String::new();
-        if let Some(name) = username {
-            output.push('<');
-            output.push_str(&sanitize_mpv_text(name));
-            output.push('>');
-            output.push(' ');
+    async fn reload_settings(&self, arguments: Option<&[String]>) -> anyhow::Result<bool> {
+        let Some(state) = self.state.upgrade() else {
+            return Ok(true);
+        };
+        send_syncplayintf_options(&self.ipc, &state, self.osc_visibility_change_compatible).await;
+        apply_osd_position(&self.ipc, &state).await;
+        let mut applied = true;
+        for argument in arguments.unwrap_or_default() {
+            let Some((name, value)) = property_from_argument(argument) else {
+                applied = false;
+                continue;
+            };
+            let response = self
+                .ipc
+                .send_command_async(MpvCommand::set_property(name, Value::String(value), 0))
+                .await;
+            if !response.is_ok_and(|response| response.error == "success") {
+                debug!("mpv cannot change {} while running", name);
+                applied = false;
+            }
         }
-        output.push_str(&sanitize_mpv_text(message));
+        Ok(applied)
+    }
+
+    fn show_idle_screen(&self, text: Option<&IdleScreenText>) -> anyhow::Result<()> {
+        let ass = text.map(IdleScreenText::to_ass);
+        self.ipc.set_osd_overlay(IDLE_OVERLAY_ID, ass.as_deref())
+    }
+
+    fn supports_idle_source(&self) -> bool {
+        true
+    }
+
+    fn show_chat_message(
+        &self,
+        username: Option<&str>,
+        message: &str,
+        _style: &OsdStyle,
+    ) -> anyhow::Result<()> {
+        let username = username.map(sanitize_mpv_text);
+        let output = format_chat_osd(username.as_deref(), &sanitize_mpv_text(message));
         let ipc = self.ipc.clone();
         tokio::spawn(async move {
             let cmd =
@@ -361,13 +419,7 @@ async fn handle_syncplayintf_line(
     }
     if line.contains("<get_syncplayintf_options>") {
         if let Some(state) = state.upgrade() {
-            let options = build_syncplayintf_options(&state, osc_visibility_change_compatible);
-            let cmd = MpvCommand::script_message_to(
-                "syncplayintf",
-                "set_syncplayintf_options",
-                vec![Value::String(options)],
-            );
-            let _ = ipc.send_command_async(cmd).await;
+            send_syncplayintf_options(ipc, &state, osc_visibility_change_compatible).await;
             let socket = ipc.socket_path().to_string();
             let _ = ipc
                 .send_command_async(MpvCommand::set_property(
@@ -402,7 +454,7 @@ async fn handle_syncplayintf_line(
                 {
                     return;
                 }
-                let global = app_state.client_state.get_global_state();
+                let global = app_state.session.client_state.get_global_state();
                 let _ = ipc.set_position(global.position).await;
                 let _ = ipc.set_paused(global.paused).await;
             });
@@ -492,12 +544,38 @@ fn parse_pause_position(line: &str) -> Option<(Option<bool>, Option<f64>)> {
     Some((paused, position))
 }
 
+async fn send_syncplayintf_options(
+    ipc: &Arc<MpvIpc>,
+    state: &Arc<AppState>,
+    osc_visibility_change_compatible: bool,
+) {
+    let options = build_syncplayintf_options(state, osc_visibility_change_compatible);
+    let cmd = MpvCommand::script_message_to(
+        "syncplayintf",
+        "set_syncplayintf_options",
+        vec![Value::String(options)],
+    );
+    let _ = ipc.send_command_async(cmd).await;
+}
+
+/// Property an `--name=value` launch option sets, so it can be changed at runtime
+fn property_from_argument(argument: &str) -> Option<(&str, String)> {
+    let option = argument.strip_prefix("--")?;
+    if let Some((name, value)) = option.split_once('=') {
+        return (!name.is_empty()).then(|| (name, value.to_string()));
+    }
+    if let Some(name) = option.strip_prefix("no-") {
+        return (!name.is_empty()).then(|| (name, "no".to_string()));
+    }
+    (!option.is_empty()).then(|| (option, "yes".to_string()))
+}
+
 fn build_syncplayintf_options(
     state: &Arc<AppState>,
     osc_visibility_change_compatible: bool,
 ) -> String {
     let config = state.config.lock().clone();
-    let server_features = state.server_features.lock().clone();
+    let server_features = state.session.server_features.lock().clone();
     let mut options = Vec::new();
 
     let bool_value = |value: bool| if value { "True" } else { "False" };
@@ -572,6 +650,17 @@ fn build_syncplayintf_options(
         "notificationTimeout={}",
         config.user.notification_timeout
     ));
+    options.push(format!(
+        "notificationPosition={}",
+        ass_alignment(config.user.osd_position)
+    ));
+    options.push(format!(
+        "notificationFontScale={}",
+        (config.user.osd_font_scale * 100.0).round()
+    ));
+    if let Some(color) = config.user.osd_color.as_deref() {
+        options.push(format!("notificationColor={}", color));
+    }
     options.push(format!("alertTimeout={}", config.user.alert_timeout));
     options.push(format!("chatTimeout={}", config.user.chat_timeout));
     options.push(format!(
@@ -638,8 +727,31 @@ async fn apply_osd_position(ipc: &Arc<MpvIpc>, state: &Arc<AppState>) {
 }
 
 fn sanitize_mpv_text(input: &str) -> String {
-    let mut text = input.replace("\r", "").replace("\n", "\\n");
+    let mut text = sanitize_display_text(input).replace("\n", "\\n");
     text = text.replace('\\', MPV_INPUT_BACKSLASH_SUBSTITUTE);
     text = text.replace('"', "'");
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_property_from_argument() {
+        assert_eq!(
+            property_from_argument("--sub-font-size=40"),
+            Some(("sub-font-size", "40".to_string()))
+        );
+        assert_eq!(
+            property_from_argument("--no-osc"),
+            Some(("osc", "no".to_string()))
+        );
+        assert_eq!(
+            property_from_argument("--fullscreen"),
+            Some(("fullscreen", "yes".to_string()))
+        );
+        assert_eq!(property_from_argument("movie.mkv"), None);
+        assert_eq!(property_from_argument("--"), None);
+    }
+}
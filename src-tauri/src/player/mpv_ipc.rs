@@ -3,10 +3,11 @@ use futures::{SinkExt, StreamExt};
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
 use tracing::{debug, error, info, warn};
 
 #[cfg(windows)]
@@ -14,13 +15,32 @@ use tokio::net::windows::named_pipe::ClientOptions;
 #[cfg(unix)]
 use tokio::net::UnixStream;
 
+use super::backend::{Chapter, OsdStyle};
 use super::commands::{MpvCommand, MpvMessage, MpvResponse};
 use super::events::MpvPlayerEvent;
-use super::properties::{PlayerState, PropertyId};
+use super::properties::{parse_chapter_list, PlayerState, PropertyId};
 
 const MPV_SENDMESSAGE_COOLDOWN_TIME: Duration = Duration::from_millis(50);
 const MPV_MAX_NEWFILE_COOLDOWN_TIME: Duration = Duration::from_secs(3);
 
+/// Record a property mpv pushed, waking the state loop for anything but position ticks
+fn apply_property_change(
+    state: &Mutex<PlayerState>,
+    last_position_update: &Mutex<Option<Instant>>,
+    state_changed: &Notify,
+    prop_id: PropertyId,
+    value: &serde_json::Value,
+) {
+    if prop_id == PropertyId::TimePos && !value.is_null() {
+        *last_position_update.lock() = Some(Instant::now());
+    }
+    state.lock().update_property(prop_id, value);
+    // Playback position ticks continuously; seeks arrive as their own event
+    if prop_id != PropertyId::TimePos {
+        state_changed.notify_one();
+    }
+}
+
 enum QueueMessage {
     Command(MpvCommand),
     SetReady(bool),
@@ -41,6 +61,8 @@ pub struct MpvIpc {
     next_request_id: Arc<Mutex<u64>>,
     pending_requests: Arc<Mutex<HashMap<u64, tokio::sync::oneshot::Sender<MpvResponse>>>>,
     last_position_update: Arc<Mutex<Option<Instant>>>,
+    state_changed: Arc<Notify>,
+    closed: Arc<AtomicBool>,
 }
 
 impl MpvIpc {
@@ -52,6 +74,8 @@ impl MpvIpc {
             next_request_id: Arc::new(Mutex::new(1)),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
             last_position_update: Arc::new(Mutex::new(None)),
+            state_changed: Arc::new(Notify::new()),
+            closed: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -85,6 +109,9 @@ impl MpvIpc {
         let state = Arc::clone(&self.state);
         let pending_requests = Arc::clone(&self.pending_requests);
         let last_position_update = Arc::clone(&self.last_position_update);
+        let state_changed = Arc::clone(&self.state_changed);
+        let closed = Arc::clone(&self.closed);
+        closed.store(false, Ordering::SeqCst);
 
         // Spawn write task
         tokio::spawn(async move {
@@ -183,10 +210,13 @@ impl MpvIpc {
                             if let Some(id) = event.id {
                                 if let Some(prop_id) = PropertyId::from_u64(id) {
                                     let value = event.data.unwrap_or(serde_json::Value::Null);
-                                    if prop_id == PropertyId::TimePos && !value.is_null() {
-                                        *last_position_update.lock() = Some(Instant::now());
-                                    }
-                                    state.lock().update_property(prop_id, &value);
+                                    apply_property_change(
+                                        &state,
+                                        &last_position_update,
+                                        &state_changed,
+                                        prop_id,
+                                        &value,
+                                    );
                                 }
                             }
                         } else if event.event == "log-message" {
@@ -201,6 +231,7 @@ impl MpvIpc {
                                 &event.event,
                                 event.reason.as_deref(),
                             );
+                            state_changed.notify_one();
                             if event_tx.send(player_event).is_err() {
                                 warn!("Failed to send player event");
                                 break;
@@ -210,6 +241,8 @@ impl MpvIpc {
                 }
             }
             debug!("MPV read task terminated");
+            closed.store(true, Ordering::SeqCst);
+            state_changed.notify_one();
         });
 
         // Observe properties
@@ -230,6 +263,10 @@ impl MpvIpc {
             PropertyId::Duration,
             PropertyId::Path,
             PropertyId::Speed,
+            PropertyId::Focused,
+            PropertyId::PausedForCache,
+            PropertyId::CacheBufferingState,
+            PropertyId::DemuxerCacheDuration,
         ];
 
         for prop in properties {
@@ -303,6 +340,11 @@ impl MpvIpc {
         Ok(())
     }
 
+    /// Whether mpv closed the IPC connection
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
     /// Send a command without waiting for response
     fn send_command(&self, cmd: MpvCommand) -> Result<()> {
         if let Some(tx) = &self.queue_tx {
@@ -371,6 +413,26 @@ impl MpvIpc {
         Ok(())
     }
 
+    /// Set a delay property such as `audio-delay`, given in milliseconds
+    pub async fn set_delay(&self, property: &str, ms: i64) -> Result<()> {
+        let seconds = ms as f64 / 1000.0;
+        let cmd = MpvCommand::set_property(
+            property,
+            serde_json::Value::Number(serde_json::Number::from_f64(seconds).unwrap()),
+            0,
+        );
+        self.send_command_async(cmd).await?;
+        Ok(())
+    }
+
+    /// Step a single frame, which also pauses playback
+    pub async fn frame_step(&self, backwards: bool) -> Result<()> {
+        let cmd = MpvCommand::frame_step(backwards, 0);
+        self.send_command_async(cmd).await?;
+        self.state.lock().paused = Some(true);
+        Ok(())
+    }
+
     /// Load a file
     pub async fn load_file(&self, path: &str) -> Result<()> {
         let cmd = MpvCommand::loadfile(path, "replace", 0);
@@ -378,12 +440,33 @@ impl MpvIpc {
         Ok(())
     }
 
+    /// Read the chapter list of the loaded file
+    pub async fn get_chapters(&self) -> Result<Vec<Chapter>> {
+        let cmd = MpvCommand::get_property("chapter-list", 0);
+        let response = self.send_command_async(cmd).await?;
+        Ok(response
+            .data
+            .as_ref()
+            .map(parse_chapter_list)
+            .unwrap_or_default())
+    }
+
     /// Show OSD message
-    pub fn show_osd(&self, text: &str, duration_ms: Option<u64>) -> Result<()> {
-        let cmd = MpvCommand::show_text(text, duration_ms);
+    pub fn show_osd(&self, text: &str, style: &OsdStyle) -> Result<()> {
+        let text = format!(
+            "${{osd-ass-cc/0}}{}{}",
+            style.ass_tags(),
+            text.replace('{', "\\{").replace('}', "\\}")
+        );
+        let cmd = MpvCommand::show_text(&text, Some(style.duration_ms));
         self.send_command(cmd)
     }
 
+    /// Show or remove an ASS overlay that stays up until replaced
+    pub fn set_osd_overlay(&self, id: u64, ass_events: Option<&str>) -> Result<()> {
+        self.send_command(MpvCommand::osd_overlay(id, ass_events))
+    }
+
     /// Quit MPV/IINA
     pub fn quit(&self) -> Result<()> {
         let cmd = MpvCommand::quit();
@@ -409,6 +492,9 @@ impl MpvIpc {
     pub fn update_pause_and_position(&self, paused: Option<bool>, position: Option<f64>) {
         let mut state = self.state.lock();
         if let Some(paused) = paused {
+            if state.paused != Some(paused) {
+                self.state_changed.notify_one();
+            }
             state.paused = Some(paused);
         }
         if let Some(position) = position {
@@ -417,6 +503,11 @@ impl MpvIpc {
         }
     }
 
+    /// Notified whenever mpv reports a state change other than a position tick
+    pub fn state_notifier(&self) -> Arc<Notify> {
+        Arc::clone(&self.state_changed)
+    }
+
     pub fn last_position_update(&self) -> Option<Instant> {
         *self.last_position_update.lock()
     }
@@ -505,3 +596,52 @@ async fn send_with_throttle(
         *last_send = Some(Instant::now());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::controller::wait_for_player_state;
+
+    #[tokio::test]
+    async fn test_pause_change_wakes_state_loop_before_fallback() {
+        let ipc = MpvIpc::new("unused");
+        let fallback = Duration::from_millis(500);
+        let mut poll_interval = tokio::time::interval(Duration::from_millis(100));
+        let mut fallback_interval = tokio::time::interval(fallback);
+        // The first tick of an interval completes at once
+        fallback_interval.tick().await;
+
+        let started = tokio::time::Instant::now();
+        let notifier = ipc.state_notifier();
+        let waiter = tokio::spawn(async move {
+            wait_for_player_state(Some(notifier), &mut poll_interval, &mut fallback_interval).await
+        });
+        apply_property_change(
+            &ipc.state,
+            &ipc.last_position_update,
+            &ipc.state_changed,
+            PropertyId::Pause,
+            &serde_json::Value::Bool(true),
+        );
+        assert!(waiter.await.unwrap(), "the pause should wake the loop");
+        assert!(started.elapsed() < fallback);
+        assert_eq!(ipc.get_state().paused, Some(true));
+
+        // Position ticks do not wake it; only the fallback does
+        apply_property_change(
+            &ipc.state,
+            &ipc.last_position_update,
+            &ipc.state_changed,
+            PropertyId::TimePos,
+            &serde_json::json!(12.0),
+        );
+        let mut poll_interval = tokio::time::interval(Duration::from_millis(100));
+        let mut fallback_interval = tokio::time::interval(Duration::from_millis(50));
+        fallback_interval.tick().await;
+        let notifier = ipc.state_notifier();
+        assert!(
+            !wait_for_player_state(Some(notifier), &mut poll_interval, &mut fallback_interval)
+                .await
+        );
+    }
+}
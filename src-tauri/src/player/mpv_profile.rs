@@ -0,0 +1,115 @@
+// mpv profile module
+// Writes the sync-friendly defaults to a profile file mpv includes at startup
+
+use crate::config::MpvProfile;
+use std::path::{Path, PathBuf};
+
+/// Name of the profile section in the generated file
+pub const MPV_PROFILE_NAME: &str = "syncplay";
+const MPV_PROFILE_FILE: &str = "syncplay-mpv.conf";
+
+/// Options the profile sets, in the order they are written
+pub fn profile_options(profile: &MpvProfile) -> Vec<(&'static str, String)> {
+    let mut options = Vec::new();
+    if profile.hr_seek {
+        options.push(("hr-seek", "always".to_string()));
+    }
+    if profile.keep_open {
+        options.push(("keep-open", "always".to_string()));
+        options.push(("keep-open-pause", "yes".to_string()));
+    }
+    if profile.cache {
+        options.push(("cache", "yes".to_string()));
+        options.push(("cache-secs", profile.cache_secs.to_string()));
+        options.push(("cache-pause-wait", profile.cache_pause_wait.to_string()));
+    }
+    options
+}
+
+/// Contents of the include file: one profile section holding `options`
+pub fn profile_text(options: &[(&str, String)]) -> String {
+    let mut text = format!(
+        "[{}]\nprofile-desc=Syncplay sync-friendly defaults\n",
+        MPV_PROFILE_NAME
+    );
+    for (name, value) in options {
+        text.push_str(&format!("{}={}\n", name, value));
+    }
+    text
+}
+
+/// Command line options applying `profile`; `prefix` is `--` for mpv and `--mpv-` for IINA
+///
+/// The profile is written to `dir`. Should that fail the same options are passed as flags.
+pub fn profile_args(profile: &MpvProfile, dir: &Path, prefix: &str) -> Vec<String> {
+    if !profile.enabled {
+        return Vec::new();
+    }
+    let options = profile_options(profile);
+    if options.is_empty() {
+        return Vec::new();
+    }
+    match write_profile(dir, &options) {
+        Ok(path) => vec![
+            format!("{}include={}", prefix, path.to_string_lossy()),
+            format!("{}profile={}", prefix, MPV_PROFILE_NAME),
+        ],
+        Err(e) => {
+            tracing::warn!(
+                "Failed to write mpv profile, passing its options as flags: {}",
+                e
+            );
+            options
+                .into_iter()
+                .map(|(name, value)| format!("{}{}={}", prefix, name, value))
+                .collect()
+        }
+    }
+}
+
+fn write_profile(dir: &Path, options: &[(&str, String)]) -> std::io::Result<PathBuf> {
+    let path = dir.join(MPV_PROFILE_FILE);
+    std::fs::write(&path, profile_text(options))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_follows_toggles() {
+        let mut profile = MpvProfile::default();
+        let names: Vec<&str> = profile_options(&profile)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["hr-seek", "keep-open", "keep-open-pause"]);
+
+        profile.keep_open = false;
+        profile.cache = true;
+        profile.cache_secs = 60;
+        let text = profile_text(&profile_options(&profile));
+        assert!(text.starts_with("[syncplay]\n"));
+        assert!(text.contains("hr-seek=always\n"));
+        assert!(text.contains("cache-secs=60\n"));
+        assert!(text.contains("cache-pause-wait=1\n"));
+        assert!(!text.contains("keep-open"));
+    }
+
+    #[test]
+    fn test_profile_args_include_written_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = profile_args(&MpvProfile::default(), dir.path(), "--mpv-");
+        assert_eq!(args.len(), 2);
+        assert!(args[0].starts_with("--mpv-include="));
+        assert_eq!(args[1], "--mpv-profile=syncplay");
+        assert!(dir.path().join(MPV_PROFILE_FILE).is_file());
+
+        let disabled = MpvProfile {
+            enabled: false,
+            ..MpvProfile::default()
+        };
+        assert!(profile_args(&disabled, dir.path(), "--").is_empty());
+    }
+}
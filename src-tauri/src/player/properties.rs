@@ -1,5 +1,7 @@
 use serde_json::Value;
 
+use super::backend::Chapter;
+
 /// MPV property IDs for observation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PropertyId {
@@ -9,6 +11,10 @@ pub enum PropertyId {
     Duration = 4,
     Path = 5,
     Speed = 6,
+    Focused = 7,
+    PausedForCache = 8,
+    CacheBufferingState = 9,
+    DemuxerCacheDuration = 10,
 }
 
 impl PropertyId {
@@ -24,6 +30,10 @@ impl PropertyId {
             4 => Some(Self::Duration),
             5 => Some(Self::Path),
             6 => Some(Self::Speed),
+            7 => Some(Self::Focused),
+            8 => Some(Self::PausedForCache),
+            9 => Some(Self::CacheBufferingState),
+            10 => Some(Self::DemuxerCacheDuration),
             _ => None,
         }
     }
@@ -36,6 +46,10 @@ impl PropertyId {
             Self::Duration => "duration",
             Self::Path => "path",
             Self::Speed => "speed",
+            Self::Focused => "focused",
+            Self::PausedForCache => "paused-for-cache",
+            Self::CacheBufferingState => "cache-buffering-state",
+            Self::DemuxerCacheDuration => "demuxer-cache-duration",
         }
     }
 }
@@ -49,6 +63,14 @@ pub struct PlayerState {
     pub duration: Option<f64>,
     pub path: Option<String>,
     pub speed: Option<f64>,
+    /// Whether the player window has focus, if the player reports it
+    pub focused: Option<bool>,
+    /// Whether playback is stalled waiting for the stream to buffer
+    pub paused_for_cache: Option<bool>,
+    /// How far the player is through rebuffering, in percent
+    pub cache_buffering: Option<f64>,
+    /// Seconds buffered ahead of the playback position
+    pub cache_duration: Option<f64>,
 }
 
 impl Default for PlayerState {
@@ -60,6 +82,10 @@ impl Default for PlayerState {
             duration: None,
             path: None,
             speed: Some(1.0),
+            focused: None,
+            paused_for_cache: None,
+            cache_buffering: None,
+            cache_duration: None,
         }
     }
 }
@@ -85,6 +111,63 @@ impl PlayerState {
             PropertyId::Speed => {
                 self.speed = value.as_f64();
             }
+            PropertyId::Focused => {
+                self.focused = value.as_bool();
+            }
+            PropertyId::PausedForCache => {
+                self.paused_for_cache = value.as_bool();
+            }
+            PropertyId::CacheBufferingState => {
+                self.cache_buffering = value.as_f64();
+            }
+            PropertyId::DemuxerCacheDuration => {
+                self.cache_duration = value.as_f64();
+            }
         }
     }
+
+    pub fn is_buffering(&self) -> bool {
+        self.paused_for_cache == Some(true)
+    }
+}
+
+/// Parse mpv's `chapter-list` property, `[{"title": "OP", "time": 0.0}, ...]`
+pub fn parse_chapter_list(value: &Value) -> Vec<Chapter> {
+    value
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let start = entry.get("time")?.as_f64()?;
+                    let title = entry
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    Some(Chapter { title, start })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chapter_list() {
+        let value = serde_json::json!([
+            {"title": "OP", "time": 0.0},
+            {"time": 90.5},
+            {"title": "broken"}
+        ]);
+        let chapters = parse_chapter_list(&value);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "OP");
+        assert_eq!(chapters[1].title, "");
+        assert_eq!(chapters[1].start, 90.5);
+        assert!(parse_chapter_list(&Value::Null).is_empty());
+    }
 }
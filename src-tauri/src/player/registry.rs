@@ -0,0 +1,361 @@
+// Player registry module
+// Factories that launch and connect each kind of player backend
+
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::process::Child;
+use tracing::info;
+
+use super::backend::{player_kind_from_path_or_default, PlayerBackend, PlayerKind};
+use super::controller::{
+    check_mpv_version, connect_mpv_ipc, ensure_mpv_socket_path, iina_is_running, iina_socket_path,
+    resolve_syncplay_lua_path, resolve_syncplayintf_path, start_mpv_process_if_needed,
+    wait_for_ipc_socket, IINA_SOCKET_TIMEOUT,
+};
+use super::mpc_api::MpcApiBackend;
+use super::mplayer_slave::MplayerBackend;
+use super::mpv_backend::MpvBackend;
+use super::mpv_ipc::MpvIpc;
+use super::sandbox::PlayerSandbox;
+use super::vlc_syncplay::VlcSyncplayBackend;
+use crate::app_state::AppState;
+
+/// The player a factory is asked to launch
+pub struct PlayerLaunch<'a> {
+    pub state: &'a Arc<AppState>,
+    pub player_path: &'a str,
+    pub kind: PlayerKind,
+    pub args: &'a [String],
+}
+
+/// Launches one family of players and connects a backend to them
+#[async_trait]
+pub trait PlayerBackendFactory: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Whether this factory handles the player at `player_path`
+    fn detect(&self, player_path: &str, kind: PlayerKind) -> bool;
+    /// Start the player process
+    ///
+    /// `None` means there is no process to start: the player is already running, or
+    /// `connect` starts it.
+    async fn spawn(&self, _launch: &PlayerLaunch<'_>) -> Result<Option<Child>, String> {
+        Ok(None)
+    }
+    /// Connect a backend to the player, starting it first when `spawn` did not
+    async fn connect(
+        &self,
+        launch: &PlayerLaunch<'_>,
+        child: &mut Option<Child>,
+    ) -> Result<Arc<dyn PlayerBackend>, String>;
+    /// Whether a running process of ours is reused instead of being replaced
+    fn reuses_process(&self) -> bool {
+        false
+    }
+}
+
+/// Factories in lookup order; the first one that detects a player launches it
+pub struct PlayerRegistry {
+    factories: Vec<Arc<dyn PlayerBackendFactory>>,
+}
+
+impl PlayerRegistry {
+    pub fn empty() -> Self {
+        Self {
+            factories: Vec::new(),
+        }
+    }
+
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(MpvFactory));
+        registry.register(Arc::new(VlcFactory));
+        registry.register(Arc::new(MplayerFactory));
+        registry.register(Arc::new(MpcFactory));
+        registry
+    }
+
+    /// Add a factory; it is tried after the ones already registered
+    pub fn register(&mut self, factory: Arc<dyn PlayerBackendFactory>) {
+        self.factories.push(factory);
+    }
+
+    /// Add a factory that takes precedence over the ones already registered
+    pub fn register_first(&mut self, factory: Arc<dyn PlayerBackendFactory>) {
+        self.factories.insert(0, factory);
+    }
+
+    pub fn find(&self, player_path: &str) -> Option<Arc<dyn PlayerBackendFactory>> {
+        let kind = player_kind_from_path_or_default(player_path);
+        self.factories
+            .iter()
+            .find(|factory| factory.detect(player_path, kind))
+            .cloned()
+    }
+}
+
+impl Default for PlayerRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+/// mpv, mpv.net and IINA, all driven over mpv's JSON IPC
+struct MpvFactory;
+
+impl MpvFactory {
+    fn socket_path(launch: &PlayerLaunch<'_>) -> Result<String, String> {
+        if launch.kind == PlayerKind::Iina {
+            return Ok(iina_socket_path());
+        }
+        match PlayerSandbox::from_player_path(launch.player_path) {
+            Some(sandbox) => sandbox
+                .socket_path()
+                .map_err(|e| format!("Failed to prepare the {} sandbox: {}", sandbox.name(), e)),
+            None => ensure_mpv_socket_path(launch.state),
+        }
+    }
+}
+
+#[async_trait]
+impl PlayerBackendFactory for MpvFactory {
+    fn name(&self) -> &'static str {
+        "mpv"
+    }
+
+    fn detect(&self, _player_path: &str, kind: PlayerKind) -> bool {
+        matches!(
+            kind,
+            PlayerKind::Mpv | PlayerKind::MpvNet | PlayerKind::Iina
+        )
+    }
+
+    async fn spawn(&self, launch: &PlayerLaunch<'_>) -> Result<Option<Child>, String> {
+        let socket_path = Self::socket_path(launch)?;
+        // An IINA we launched earlier keeps its socket, so reuse its window
+        if launch.kind == PlayerKind::Iina {
+            if iina_is_running() && Path::new(&socket_path).exists() {
+                return Ok(None);
+            }
+            let _ = std::fs::remove_file(&socket_path);
+        }
+        let sandbox = PlayerSandbox::from_player_path(launch.player_path);
+        let syncplayintf_path =
+            resolve_syncplayintf_path(launch.state).map(|path| match &sandbox {
+                // The sandbox cannot read our bundled resources in place
+                Some(sandbox) => sandbox.stage_file(&path).unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to stage {} for {}: {}",
+                        path.display(),
+                        sandbox.name(),
+                        e
+                    );
+                    path
+                }),
+                None => path,
+            });
+        let mut child = start_mpv_process_if_needed(
+            launch.state,
+            launch.player_path,
+            launch.kind,
+            launch.args,
+            &socket_path,
+            syncplayintf_path.as_ref(),
+        )?;
+        if launch.kind == PlayerKind::Iina {
+            if let Some(spawned) = child.as_mut() {
+                if let Err(e) =
+                    wait_for_ipc_socket(spawned, &socket_path, IINA_SOCKET_TIMEOUT).await
+                {
+                    let _ = spawned.kill().await;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(child)
+    }
+
+    async fn connect(
+        &self,
+        launch: &PlayerLaunch<'_>,
+        child: &mut Option<Child>,
+    ) -> Result<Arc<dyn PlayerBackend>, String> {
+        let socket_path = Self::socket_path(launch)?;
+        let mut mpv = MpvIpc::new(socket_path.clone());
+        if launch.kind == PlayerKind::Iina && child.is_none() {
+            info!("Attaching to running IINA at {}", socket_path);
+        }
+        let event_rx = connect_mpv_ipc(&mut mpv).await?;
+        let stdout = child.as_mut().and_then(|process| process.stdout.take());
+        let osc_compatible = match launch.kind {
+            PlayerKind::Iina => true,
+            _ => check_mpv_version(launch.player_path)?.osc_visibility_change_compatible,
+        };
+        let backend = Arc::new(MpvBackend::new(
+            launch.kind,
+            mpv,
+            Arc::downgrade(launch.state),
+            osc_compatible,
+            stdout,
+        ));
+        backend.spawn_event_loop(event_rx);
+        Ok(backend)
+    }
+
+    fn reuses_process(&self) -> bool {
+        true
+    }
+}
+
+/// VLC through the bundled syncplay.lua interface
+struct VlcFactory;
+
+#[async_trait]
+impl PlayerBackendFactory for VlcFactory {
+    fn name(&self) -> &'static str {
+        "VLC"
+    }
+
+    fn detect(&self, _player_path: &str, kind: PlayerKind) -> bool {
+        kind == PlayerKind::Vlc
+    }
+
+    async fn connect(
+        &self,
+        launch: &PlayerLaunch<'_>,
+        child: &mut Option<Child>,
+    ) -> Result<Arc<dyn PlayerBackend>, String> {
+        let lua_path = resolve_syncplay_lua_path(launch.state)
+            .ok_or_else(|| "Syncplay VLC interface not found".to_string())?;
+        let (backend, process) = VlcSyncplayBackend::start(
+            launch.player_path,
+            launch.args,
+            None,
+            lua_path,
+            Arc::downgrade(launch.state),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        *child = Some(process);
+        Ok(Arc::new(backend))
+    }
+}
+
+/// MPlayer in slave mode
+struct MplayerFactory;
+
+#[async_trait]
+impl PlayerBackendFactory for MplayerFactory {
+    fn name(&self) -> &'static str {
+        "MPlayer"
+    }
+
+    fn detect(&self, _player_path: &str, kind: PlayerKind) -> bool {
+        kind == PlayerKind::Mplayer
+    }
+
+    async fn connect(
+        &self,
+        launch: &PlayerLaunch<'_>,
+        child: &mut Option<Child>,
+    ) -> Result<Arc<dyn PlayerBackend>, String> {
+        let (backend, process) = MplayerBackend::start(
+            launch.player_path,
+            launch.args,
+            None,
+            Arc::downgrade(launch.state),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        *child = Some(process);
+        Ok(Arc::new(backend))
+    }
+}
+
+/// MPC-HC and MPC-BE through their slave API
+struct MpcFactory;
+
+#[async_trait]
+impl PlayerBackendFactory for MpcFactory {
+    fn name(&self) -> &'static str {
+        "MPC"
+    }
+
+    fn detect(&self, _player_path: &str, kind: PlayerKind) -> bool {
+        matches!(kind, PlayerKind::MpcHc | PlayerKind::MpcBe)
+    }
+
+    async fn connect(
+        &self,
+        launch: &PlayerLaunch<'_>,
+        child: &mut Option<Child>,
+    ) -> Result<Arc<dyn PlayerBackend>, String> {
+        let mut mpc_args = launch.args.to_vec();
+        if !mpc_args.iter().any(|arg| arg.eq_ignore_ascii_case("/open")) {
+            mpc_args.push("/open".to_string());
+        }
+        if !mpc_args.iter().any(|arg| arg.eq_ignore_ascii_case("/new")) {
+            mpc_args.push("/new".to_string());
+        }
+        let (backend, process) =
+            MpcApiBackend::start(launch.kind, launch.player_path, &mpc_args, None)
+                .await
+                .map_err(|e| e.to_string())?;
+        *child = process;
+        Ok(Arc::new(backend))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry_finds_each_player() {
+        let registry = PlayerRegistry::with_builtin();
+        let name = |path: &str| registry.find(path).map(|factory| factory.name());
+        assert_eq!(name(""), Some("mpv"));
+        assert_eq!(name("/Applications/IINA.app"), Some("mpv"));
+        assert_eq!(name("/usr/bin/vlc"), Some("VLC"));
+        assert_eq!(name("mplayer"), Some("MPlayer"));
+        assert_eq!(name("C:\\Program Files\\MPC-HC\\mpc-hc64.exe"), Some("MPC"));
+        assert_eq!(name("/usr/bin/totem"), None);
+    }
+
+    #[test]
+    fn test_registered_first_takes_precedence() {
+        struct Custom;
+
+        #[async_trait]
+        impl PlayerBackendFactory for Custom {
+            fn name(&self) -> &'static str {
+                "custom"
+            }
+
+            fn detect(&self, player_path: &str, _kind: PlayerKind) -> bool {
+                player_path.ends_with("custom-mpv")
+            }
+
+            async fn connect(
+                &self,
+                _launch: &PlayerLaunch<'_>,
+                _child: &mut Option<Child>,
+            ) -> Result<Arc<dyn PlayerBackend>, String> {
+                Err("not connectable".to_string())
+            }
+        }
+
+        let mut registry = PlayerRegistry::with_builtin();
+        registry.register_first(Arc::new(Custom));
+        assert_eq!(
+            registry
+                .find("/opt/custom-mpv")
+                .map(|factory| factory.name()),
+            Some("custom")
+        );
+        assert_eq!(
+            registry.find("/usr/bin/mpv").map(|factory| factory.name()),
+            Some("mpv")
+        );
+    }
+}
@@ -0,0 +1,172 @@
+// Sandbox module
+// Launches players installed through Flatpak or Snap so they can reach our IPC socket and scripts
+
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::config::PlayerConfig;
+
+/// Sandbox a player path runs in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerSandbox {
+    /// Flatpak app launched with `flatpak run <app_id>`
+    Flatpak { app_id: String },
+    /// Strictly confined snap, launched through `/snap/bin`
+    Snap { name: String },
+}
+
+impl PlayerSandbox {
+    /// Sandbox of an exported Flatpak launcher or a `/snap/bin` command
+    pub fn from_player_path(player_path: &str) -> Option<Self> {
+        let path = Path::new(player_path);
+        let file_name = path.file_name()?.to_str()?;
+        if player_path.contains("/flatpak/exports/bin/") {
+            return Some(Self::Flatpak {
+                app_id: file_name.to_string(),
+            });
+        }
+        if player_path.starts_with("/snap/bin/") {
+            // `/snap/bin/vlc.cvlc` runs an app of the `vlc` snap
+            let name = file_name.split('.').next().unwrap_or(file_name);
+            return Some(Self::Snap {
+                name: name.to_string(),
+            });
+        }
+        None
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Flatpak { app_id } => app_id,
+            Self::Snap { name } => name,
+        }
+    }
+
+    /// Directory under `XDG_RUNTIME_DIR` that both we and the sandboxed player can use
+    pub fn runtime_dir_in(&self, xdg_runtime_dir: &Path) -> PathBuf {
+        match self {
+            Self::Flatpak { app_id } => xdg_runtime_dir.join("app").join(app_id),
+            Self::Snap { name } => xdg_runtime_dir.join(format!("snap.{}", name)),
+        }
+    }
+
+    /// Shared runtime directory, created if missing
+    pub fn runtime_dir(&self) -> std::io::Result<PathBuf> {
+        let xdg_runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .filter(|dir| !dir.is_empty())
+            .ok_or_else(|| std::io::Error::other("XDG_RUNTIME_DIR is not set"))?;
+        let dir = self.runtime_dir_in(Path::new(&xdg_runtime_dir));
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// IPC socket path the sandboxed player can create
+    pub fn socket_path(&self) -> std::io::Result<String> {
+        Ok(self
+            .runtime_dir()?
+            .join("syncplay-mpv-socket")
+            .to_string_lossy()
+            .to_string())
+    }
+
+    /// Copy a bundled file into the shared runtime directory, where the sandbox can read it
+    pub fn stage_file(&self, path: &Path) -> std::io::Result<PathBuf> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| std::io::Error::other("path has no file name"))?;
+        let staged = self.runtime_dir()?.join(file_name);
+        std::fs::copy(path, &staged)?;
+        Ok(staged)
+    }
+}
+
+/// Command that starts the player at `player_path`, inside its sandbox if it has one
+pub fn player_command(player_path: &str, config: &PlayerConfig) -> Command {
+    match PlayerSandbox::from_player_path(player_path) {
+        Some(PlayerSandbox::Flatpak { app_id }) => {
+            let mut cmd = Command::new("flatpak");
+            cmd.args(flatpak_run_args(&app_id, config));
+            cmd
+        }
+        _ => Command::new(player_path),
+    }
+}
+
+/// `flatpak run` arguments, granting read access to the media directories only
+///
+/// Files are loaded over IPC after launch, so access cannot be granted per file; the whole
+/// host filesystem is shared only when the user opted into it.
+fn flatpak_run_args(app_id: &str, config: &PlayerConfig) -> Vec<String> {
+    let mut args = vec!["run".to_string()];
+    if config.flatpak_host_filesystem {
+        args.push("--filesystem=host:ro".to_string());
+    } else {
+        args.extend(
+            config
+                .media_directories
+                .iter()
+                .filter(|dir| !dir.trim().is_empty())
+                .map(|dir| format!("--filesystem={}:ro", dir)),
+        );
+    }
+    args.push(app_id.to_string());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_from_player_path() {
+        assert_eq!(
+            PlayerSandbox::from_player_path("/var/lib/flatpak/exports/bin/io.mpv.Mpv"),
+            Some(PlayerSandbox::Flatpak {
+                app_id: "io.mpv.Mpv".to_string()
+            })
+        );
+        assert_eq!(
+            PlayerSandbox::from_player_path("/snap/bin/vlc.cvlc"),
+            Some(PlayerSandbox::Snap {
+                name: "vlc".to_string()
+            })
+        );
+        assert_eq!(PlayerSandbox::from_player_path("/usr/bin/mpv"), None);
+    }
+
+    #[test]
+    fn test_flatpak_shares_only_media_directories_by_default() {
+        let mut config = PlayerConfig {
+            media_directories: vec!["/home/me/Videos".to_string(), " ".to_string()],
+            ..PlayerConfig::default()
+        };
+        assert_eq!(
+            flatpak_run_args("io.mpv.Mpv", &config),
+            ["run", "--filesystem=/home/me/Videos:ro", "io.mpv.Mpv"]
+        );
+        config.flatpak_host_filesystem = true;
+        assert_eq!(
+            flatpak_run_args("io.mpv.Mpv", &config),
+            ["run", "--filesystem=host:ro", "io.mpv.Mpv"]
+        );
+    }
+
+    #[test]
+    fn test_runtime_dir_is_shared_with_the_sandbox() {
+        let runtime = Path::new("/run/user/1000");
+        let flatpak = PlayerSandbox::Flatpak {
+            app_id: "io.mpv.Mpv".to_string(),
+        };
+        assert_eq!(
+            flatpak.runtime_dir_in(runtime),
+            PathBuf::from("/run/user/1000/app/io.mpv.Mpv")
+        );
+        let snap = PlayerSandbox::Snap {
+            name: "mpv".to_string(),
+        };
+        assert_eq!(
+            snap.runtime_dir_in(runtime),
+            PathBuf::from("/run/user/1000/snap.mpv")
+        );
+    }
+}
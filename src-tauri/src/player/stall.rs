@@ -0,0 +1,116 @@
+// Stall module
+// Notices playback that is unpaused but no longer moving
+
+use std::time::{Duration, Instant};
+
+/// Smallest position change that counts as playback moving
+const STALL_POSITION_EPSILON: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallChange {
+    Stalled,
+    Resumed,
+}
+
+#[derive(Debug, Default)]
+pub struct StallTracker {
+    /// Position last seen moving, and since when it has stood still
+    last_position: Option<(f64, Instant)>,
+    stalled: bool,
+}
+
+impl StallTracker {
+    /// Feed the player's position and pause state; reports entering and leaving a stall
+    ///
+    /// Paused playback, or no file, never counts as stalled.
+    pub fn update(
+        &mut self,
+        position: Option<f64>,
+        paused: Option<bool>,
+        timeout: Duration,
+        now: Instant,
+    ) -> Option<StallChange> {
+        let (Some(position), Some(false)) = (position, paused) else {
+            self.last_position = None;
+            return self.resume();
+        };
+        match self.last_position {
+            Some((last, _)) if (position - last).abs() < STALL_POSITION_EPSILON => {}
+            _ => {
+                self.last_position = Some((position, now));
+                return self.resume();
+            }
+        }
+        let since = self.last_position.map(|(_, since)| since).unwrap_or(now);
+        if !self.stalled && now.duration_since(since) >= timeout {
+            self.stalled = true;
+            return Some(StallChange::Stalled);
+        }
+        None
+    }
+
+    fn resume(&mut self) -> Option<StallChange> {
+        std::mem::take(&mut self.stalled).then_some(StallChange::Resumed)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_stalls_when_position_stands_still_while_playing() {
+        let mut tracker = StallTracker::default();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        assert_eq!(
+            tracker.update(Some(10.0), Some(false), TIMEOUT, at(0)),
+            None
+        );
+        assert_eq!(
+            tracker.update(Some(10.0), Some(false), TIMEOUT, at(4)),
+            None
+        );
+        assert_eq!(
+            tracker.update(Some(10.0), Some(false), TIMEOUT, at(5)),
+            Some(StallChange::Stalled)
+        );
+        assert_eq!(
+            tracker.update(Some(10.0), Some(false), TIMEOUT, at(9)),
+            None
+        );
+        assert_eq!(
+            tracker.update(Some(10.5), Some(false), TIMEOUT, at(10)),
+            Some(StallChange::Resumed)
+        );
+        assert_eq!(
+            tracker.update(Some(11.5), Some(false), TIMEOUT, at(11)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_paused_playback_is_not_stalled() {
+        let mut tracker = StallTracker::default();
+        let start = Instant::now();
+        tracker.update(Some(10.0), Some(false), TIMEOUT, start);
+        assert_eq!(
+            tracker.update(Some(10.0), Some(false), TIMEOUT, start + TIMEOUT),
+            Some(StallChange::Stalled)
+        );
+        assert_eq!(
+            tracker.update(Some(10.0), Some(true), TIMEOUT, start + TIMEOUT * 2),
+            Some(StallChange::Resumed)
+        );
+        assert_eq!(
+            tracker.update(Some(10.0), Some(true), TIMEOUT, start + TIMEOUT * 4),
+            None
+        );
+    }
+}
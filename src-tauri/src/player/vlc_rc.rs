@@ -8,7 +8,7 @@ use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::Mutex as TokioMutex;
 use tracing::{debug, info, warn};
 
-use super::backend::PlayerBackend;
+use super::backend::{OsdStyle, PlayerBackend};
 use super::properties::PlayerState;
 
 const VLC_ARGS: &[&str] = &["--extraintf", "rc", "--rc-fake-tty", "--quiet"];
@@ -176,12 +176,19 @@ impl PlayerBackend for VlcBackend {
         self.send_command(&format!("rate {}", speed)).await
     }
 
+    async fn frame_step(&self, backwards: bool) -> anyhow::Result<()> {
+        if backwards {
+            anyhow::bail!("{} cannot step frames backwards", self.name());
+        }
+        self.send_command("frame").await
+    }
+
     async fn load_file(&self, path: &str) -> anyhow::Result<()> {
         *self.last_loaded.lock() = Some(path.to_string());
         self.send_command(&format!("add {}", path)).await
     }
 
-    fn show_osd(&self, text: &str, _duration_ms: Option<u64>) -> anyhow::Result<()> {
+    fn show_osd(&self, text: &str, _style: &OsdStyle) -> anyhow::Result<()> {
         let message = text.replace('"', "'");
         let stdin = self.stdin.clone();
         tokio::spawn(async move {
@@ -1,25 +1,33 @@
-use super::backend::PlayerBackend;
+use super::backend::{format_chat_osd, OsdStyle, PlayerBackend};
 use super::properties::PlayerState;
+use super::sandbox::player_command;
+use crate::app_state::AppState;
+use crate::config::OsdPosition;
 use async_trait::async_trait;
 use futures::StreamExt;
 use parking_lot::Mutex;
 use rand::Rng;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncWriteExt, BufReader};
 use tokio::net::{tcp::OwnedReadHalf, tcp::OwnedWriteHalf, TcpStream};
-use tokio::process::{Child, Command};
-use tokio::sync::Mutex as TokioMutex;
+use tokio::process::Child;
+use tokio::sync::{Mutex as TokioMutex, Notify};
 use tokio_util::codec::{FramedRead, LinesCodec};
 use tracing::{debug, info, warn};
 
 const VLC_MIN_VERSION: &str = "2.2.1";
-const VLC_INTERFACE_VERSION: &str = "0.3.7";
+const VLC_INTERFACE_VERSION: &str = "0.3.8";
 const VLC_OPEN_MAX_WAIT_TIME: Duration = Duration::from_secs(20);
 const VLC_MIN_PORT: u16 = 10000;
 const VLC_MAX_PORT: u16 = 55000;
 const VLC_LATENCY_ERROR_THRESHOLD: f64 = 2.0;
+/// VLC drops its input briefly between playlist items and on stream hiccups; file
+/// info is only cleared once the input stays gone this long
+const VLC_NO_INPUT_GRACE: Duration = Duration::from_secs(2);
 
 const VLC_ARGS: &[&str] = &[
     "--extraintf=luaintf",
@@ -44,12 +52,42 @@ impl Connection {
     }
 }
 
+/// Payload of the `player-interface-mismatch` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerInterfaceMismatch {
+    pub player: String,
+    /// `interface` for the syncplay.lua connector, `player` for VLC itself
+    pub component: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// State shared between the backend and its reader task
+#[derive(Clone)]
+struct ReaderContext {
+    connection: Connection,
+    state: Arc<Mutex<PlayerState>>,
+    last_position_update: Arc<Mutex<Option<Instant>>>,
+    last_duration: Arc<Mutex<Option<f64>>>,
+    no_input_since: Arc<Mutex<Option<Instant>>>,
+    push_events: Arc<AtomicBool>,
+    state_changed: Arc<Notify>,
+    /// Woken by every line VLC sends
+    replied: Arc<Notify>,
+    app_state: Weak<AppState>,
+}
+
 pub struct VlcSyncplayBackend {
     state: Arc<Mutex<PlayerState>>,
     connection: Connection,
     last_position_update: Arc<Mutex<Option<Instant>>>,
     last_duration: Arc<Mutex<Option<f64>>>,
     last_loaded: Arc<Mutex<Option<String>>>,
+    no_input_since: Arc<Mutex<Option<Instant>>>,
+    push_events: Arc<AtomicBool>,
+    state_changed: Arc<Notify>,
+    replied: Arc<Notify>,
 }
 
 impl VlcSyncplayBackend {
@@ -58,6 +96,7 @@ impl VlcSyncplayBackend {
         args: &[String],
         initial_file: Option<&str>,
         syncplay_lua_path: PathBuf,
+        app_state: Weak<AppState>,
     ) -> anyhow::Result<(Self, Child)> {
         info!(
             "Starting VLC: path={}, args={:?}, initial_file={:?}",
@@ -69,7 +108,11 @@ impl VlcSyncplayBackend {
         install_syncplay_lua(&user_path, &syncplay_lua_path)?;
 
         let module_path = format!("{}/modules/?.luac", intf_path.replace('\\', "/"));
-        let mut cmd = Command::new(player_path);
+        let player_config = app_state
+            .upgrade()
+            .map(|state| state.config.lock().player.clone())
+            .unwrap_or_default();
+        let mut cmd = player_command(player_path, &player_config);
         cmd.args(VLC_ARGS);
         cmd.arg(format!(
             "--lua-config=syncplay={{modulepath=\"{}\",port=\"{}\"}}",
@@ -103,14 +146,24 @@ impl VlcSyncplayBackend {
         let last_position_update = Arc::new(Mutex::new(None));
         let last_duration = Arc::new(Mutex::new(None));
         let last_loaded = Arc::new(Mutex::new(initial_file.map(|s| s.to_string())));
+        let no_input_since = Arc::new(Mutex::new(None));
+        let push_events = Arc::new(AtomicBool::new(false));
+        let state_changed = Arc::new(Notify::new());
+        let replied = Arc::new(Notify::new());
 
         spawn_reader(
-            connection.clone(),
+            ReaderContext {
+                connection: connection.clone(),
+                state: state.clone(),
+                last_position_update: last_position_update.clone(),
+                last_duration: last_duration.clone(),
+                no_input_since: no_input_since.clone(),
+                push_events: push_events.clone(),
+                state_changed: state_changed.clone(),
+                replied: replied.clone(),
+                app_state,
+            },
             read_half,
-            state.clone(),
-            last_position_update.clone(),
-            last_duration.clone(),
-            last_loaded.clone(),
         );
 
         let backend = Self {
@@ -119,9 +172,16 @@ impl VlcSyncplayBackend {
             last_position_update,
             last_duration,
             last_loaded,
+            no_input_since,
+            push_events,
+            state_changed,
+            replied,
         };
 
         let _ = backend.connection.send_line("get-vlc-version").await;
+        let _ = backend.connection.send_line("get-interface-version").await;
+        // Interfaces older than 0.3.8 answer with an unknown-command error and stay polled
+        let _ = backend.connection.send_line("set-push-events: on").await;
         backend.request_file_info().await?;
         Ok((backend, child))
     }
@@ -150,6 +210,11 @@ impl PlayerBackend for VlcSyncplayBackend {
 
     fn get_state(&self) -> PlayerState {
         let mut snapshot = self.state.lock().clone();
+        if input_lost(*self.no_input_since.lock()) {
+            snapshot.path = None;
+            snapshot.filename = None;
+            snapshot.duration = None;
+        }
         let base_position = snapshot.position;
         let last_update = *self.last_position_update.lock();
         if snapshot.paused == Some(false) {
@@ -185,6 +250,19 @@ impl PlayerBackend for VlcSyncplayBackend {
         Ok(())
     }
 
+    async fn ping(&self) -> anyhow::Result<()> {
+        let reply = self.replied.notified();
+        self.request_status().await?;
+        reply.await;
+        Ok(())
+    }
+
+    fn state_notifier(&self) -> Option<Arc<Notify>> {
+        self.push_events
+            .load(Ordering::SeqCst)
+            .then(|| self.state_changed.clone())
+    }
+
     async fn set_position(&self, position: f64) -> anyhow::Result<()> {
         *self.last_position_update.lock() = Some(Instant::now());
         self.connection
@@ -209,6 +287,18 @@ impl PlayerBackend for VlcSyncplayBackend {
             .await
     }
 
+    async fn set_audio_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.connection
+            .send_line(&format!("set-audio-delay: {:.3}", ms as f64 / 1000.0))
+            .await
+    }
+
+    async fn set_sub_delay(&self, ms: i64) -> anyhow::Result<()> {
+        self.connection
+            .send_line(&format!("set-subtitle-delay: {:.3}", ms as f64 / 1000.0))
+            .await
+    }
+
     async fn load_file(&self, path: &str) -> anyhow::Result<()> {
         let arg = if is_ascii_path(path) && !is_url(path) {
             path.to_string()
@@ -221,10 +311,37 @@ impl PlayerBackend for VlcSyncplayBackend {
             .await
     }
 
-    fn show_osd(&self, text: &str, duration_ms: Option<u64>) -> anyhow::Result<()> {
-        let duration = duration_ms.unwrap_or(3000) as f64 / 1000.0;
+    fn show_osd(&self, text: &str, style: &OsdStyle) -> anyhow::Result<()> {
+        let duration = style.duration_ms as f64 / 1000.0;
         let message = text.replace('"', "'");
-        let command = format!("display-osd: top-right, {}, {}", duration, message);
+        let command = format!(
+            "display-osd: {}, {}, {}",
+            vlc_osd_position(style.position),
+            duration,
+            message
+        );
+        let connection = self.connection.clone();
+        tokio::spawn(async move {
+            let _ = connection.send_line(&command).await;
+        });
+        Ok(())
+    }
+
+    /// Chat goes to the secondary OSD channel so it does not replace sync notices
+    fn show_chat_message(
+        &self,
+        username: Option<&str>,
+        message: &str,
+        style: &OsdStyle,
+    ) -> anyhow::Result<()> {
+        let duration = style.duration_ms as f64 / 1000.0;
+        let message = format_chat_osd(username, message).replace('"', "'");
+        let command = format!(
+            "display-secondary-osd: {}, {}, {}",
+            vlc_osd_position(style.position),
+            duration,
+            message
+        );
         let connection = self.connection.clone();
         tokio::spawn(async move {
             let _ = connection.send_line(&command).await;
@@ -237,14 +354,21 @@ impl PlayerBackend for VlcSyncplayBackend {
     }
 }
 
-fn spawn_reader(
-    connection: Connection,
-    read_half: OwnedReadHalf,
-    state: Arc<Mutex<PlayerState>>,
-    last_position_update: Arc<Mutex<Option<Instant>>>,
-    last_duration: Arc<Mutex<Option<f64>>>,
-    _last_loaded: Arc<Mutex<Option<String>>>,
-) {
+fn vlc_osd_position(position: OsdPosition) -> &'static str {
+    match position {
+        OsdPosition::TopLeft => "top-left",
+        OsdPosition::Top => "top",
+        OsdPosition::TopRight => "top-right",
+        OsdPosition::Left => "left",
+        OsdPosition::Center => "center",
+        OsdPosition::Right => "right",
+        OsdPosition::BottomLeft => "bottom-left",
+        OsdPosition::Bottom => "bottom",
+        OsdPosition::BottomRight => "bottom-right",
+    }
+}
+
+fn spawn_reader(context: ReaderContext, read_half: OwnedReadHalf) {
     tokio::spawn(async move {
         let reader = BufReader::new(read_half);
         let mut lines = FramedRead::new(reader, LinesCodec::new());
@@ -252,46 +376,38 @@ fn spawn_reader(
             if line.trim().is_empty() {
                 continue;
             }
-            handle_line(
-                &connection,
-                &state,
-                &last_position_update,
-                &last_duration,
-                &line,
-            )
-            .await;
+            context.replied.notify_waiters();
+            handle_line(&context, &line).await;
         }
     });
 }
 
-async fn handle_line(
-    connection: &Connection,
-    state: &Arc<Mutex<PlayerState>>,
-    last_position_update: &Arc<Mutex<Option<Instant>>>,
-    last_duration: &Arc<Mutex<Option<f64>>>,
-    line: &str,
-) {
+async fn handle_line(context: &ReaderContext, line: &str) {
     debug!("vlc >> {}", line);
+    let state = &context.state;
     if line == "filepath-change-notification" {
+        let connection = &context.connection;
         let _ = connection.send_line("get-duration").await;
         let _ = connection.send_line("get-filepath").await;
         let _ = connection.send_line("get-filename").await;
         return;
     }
+    if line == "state-change-notification" {
+        context.state_changed.notify_one();
+        return;
+    }
 
     let (command, argument) = parse_line(line);
     match command.as_str() {
-        "playstate" => {
-            if !argument.is_empty() {
-                let paused = argument != "playing";
-                state.lock().paused = Some(paused);
-            }
+        "playstate" if !argument.is_empty() && argument != "no-input" => {
+            let paused = argument != "playing";
+            state.lock().paused = Some(paused);
         }
         "position" => {
             if argument != "no-input" {
                 if let Ok(pos) = argument.replace(',', ".").parse::<f64>() {
                     state.lock().position = Some(pos);
-                    *last_position_update.lock() = Some(Instant::now());
+                    *context.last_position_update.lock() = Some(Instant::now());
                 }
             } else {
                 state.lock().position = None;
@@ -299,62 +415,94 @@ async fn handle_line(
         }
         "duration" | "duration-change" => {
             if argument == "no-input" {
-                state.lock().duration = None;
+                // Keep the duration through input gaps; get_state drops it if the gap lasts
             } else if argument == "invalid-32-bit-value" {
                 warn!("VLC reported invalid duration value");
                 state.lock().duration = None;
             } else if let Ok(value) = argument.replace(',', ".").parse::<f64>() {
                 state.lock().duration = Some(value);
-                *last_duration.lock() = Some(value);
+                *context.last_duration.lock() = Some(value);
             }
         }
-        "filepath" => {
-            if argument == "no-input" {
-                state.lock().path = None;
-            } else {
-                let mut value = argument.clone();
-                if value.starts_with("file://") {
-                    value = value.trim_start_matches("file://").to_string();
-                    if !Path::new(&value).exists() {
-                        value = value.trim_start_matches('/').to_string();
-                    }
-                } else if is_url(&value) {
-                    value = urlencoding::decode(&value)
-                        .unwrap_or_else(|_| value.clone().into())
-                        .to_string();
-                }
-                state.lock().path = Some(value.clone());
-                state.lock().filename = Path::new(&value)
-                    .file_name()
-                    .map(|name| name.to_string_lossy().to_string());
+        "cache" => {
+            if let Ok(fraction) = argument.replace(',', ".").parse::<f64>() {
+                let mut guard = state.lock();
+                guard.paused_for_cache = Some(fraction < 1.0);
+                guard.cache_buffering = Some((fraction * 100.0).clamp(0.0, 100.0));
             }
         }
-        "filename" => {
-            if argument != "no-input" {
-                state.lock().filename = Some(argument.clone());
+        "filepath" if argument != "no-input" => {
+            let mut value = argument.clone();
+            if value.starts_with("file://") {
+                value = value.trim_start_matches("file://").to_string();
+                if !Path::new(&value).exists() {
+                    value = value.trim_start_matches('/').to_string();
+                }
+            } else if is_url(&value) {
+                value = urlencoding::decode(&value)
+                    .unwrap_or_else(|_| value.clone().into())
+                    .to_string();
             }
+            let mut guard = state.lock();
+            guard.filename = Path::new(&value)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string());
+            guard.path = Some(value);
+        }
+        "filename" if argument != "no-input" => {
+            state.lock().filename = Some(argument.clone());
         }
         "inputstate-change" => {
+            let mut no_input_since = context.no_input_since.lock();
             if argument == "no-input" {
-                let mut guard = state.lock();
-                guard.path = None;
-                guard.filename = None;
-                guard.duration = None;
-                guard.position = None;
+                state.lock().position = None;
+                no_input_since.get_or_insert_with(Instant::now);
+            } else {
+                *no_input_since = None;
             }
         }
-        "vlc-version" => {
-            if !meets_min_version(&argument, VLC_MIN_VERSION) {
-                warn!(
-                    "VLC version {} is below minimum {}",
-                    argument, VLC_MIN_VERSION
-                );
-            }
+        "push-events" => {
+            let enabled = argument == "on";
+            debug!("VLC push events: {}", argument);
+            context.push_events.store(enabled, Ordering::SeqCst);
+        }
+        "interface-version" if argument != VLC_INTERFACE_VERSION => {
+            report_mismatch(context, "interface", VLC_INTERFACE_VERSION, &argument);
+        }
+        "vlc-version" if !meets_min_version(&argument, VLC_MIN_VERSION) => {
+            report_mismatch(context, "player", VLC_MIN_VERSION, &argument);
         }
         _ => {}
     }
 }
 
+/// Whether VLC has been without input past the grace period
+fn input_lost(no_input_since: Option<Instant>) -> bool {
+    no_input_since.is_some_and(|since| since.elapsed() >= VLC_NO_INPUT_GRACE)
+}
+
+fn report_mismatch(context: &ReaderContext, component: &str, expected: &str, actual: &str) {
+    warn!(
+        "VLC {} version {} does not match {}",
+        component, actual, expected
+    );
+    let Some(app_state) = context.app_state.upgrade() else {
+        return;
+    };
+    let mismatch = PlayerInterfaceMismatch {
+        player: "VLC".to_string(),
+        component: component.to_string(),
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+    };
+    let message = app_state.tr(
+        "player-interface-mismatch",
+        &[&mismatch.player, &component, &actual, &expected],
+    );
+    app_state.emit_event("player-interface-mismatch", mismatch);
+    crate::commands::connection::emit_error_message(&app_state, &message);
+}
+
 fn parse_line(line: &str) -> (String, String) {
     if let Some((cmd, arg)) = line.split_once(": ") {
         (cmd.trim().to_string(), arg.trim().to_string())
@@ -412,7 +560,18 @@ fn resolve_vlc_paths(player_path: &str) -> anyhow::Result<(String, String)> {
     let player_path = Path::new(player_path);
     #[cfg(target_os = "linux")]
     {
-        if player_path_str.contains("snap") {
+        if let Some(super::sandbox::PlayerSandbox::Flatpak { app_id }) =
+            super::sandbox::PlayerSandbox::from_player_path(player_path_str)
+        {
+            // VLC reads its interface from the app and user scripts from the app's data dir
+            let intf = "/app/lib/vlc/lua/intf/".to_string();
+            let user = format!(
+                "{}/.var/app/{}/data/vlc/lua/intf/",
+                std::env::var("HOME").unwrap_or_default(),
+                app_id
+            );
+            Ok((intf, user))
+        } else if player_path_str.contains("snap") {
             let intf = "/snap/vlc/current/usr/lib/vlc/lua/intf/".to_string();
             let user = format!(
                 "{}/snap/vlc/current/.local/share/vlc/lua/intf/",
@@ -547,3 +706,23 @@ fn meets_min_version(version: &str, min: &str) -> bool {
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_is_lost_after_grace_period() {
+        assert!(!input_lost(None));
+        assert!(!input_lost(Some(Instant::now())));
+        let long_ago = Instant::now() - VLC_NO_INPUT_GRACE - Duration::from_millis(10);
+        assert!(input_lost(Some(long_ago)));
+    }
+
+    #[test]
+    fn test_meets_min_version() {
+        assert!(meets_min_version("3.0.20 Vetinari", VLC_MIN_VERSION));
+        assert!(meets_min_version("2.2.1", VLC_MIN_VERSION));
+        assert!(!meets_min_version("2.2.0", VLC_MIN_VERSION));
+    }
+}
@@ -0,0 +1,173 @@
+// Watchdog module
+// Measures how quickly the player answers and escalates when it stops answering
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::config::WatchdogPolicy;
+
+/// Pings kept for the average latency
+const LATENCY_SAMPLES: usize = 30;
+
+/// How far the watchdog has escalated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerHealthStatus {
+    Healthy,
+    /// The user was told the player is not responding
+    Warning,
+    /// Pushed events are ignored and the player is polled instead
+    Polling,
+    /// The player is being restarted, or stopped when restarts are off
+    Restarting,
+}
+
+/// Payload of the `player-health` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerHealthEvent {
+    pub status: PlayerHealthStatus,
+    /// Round trip of the last answered ping
+    pub latency_ms: Option<u64>,
+    pub average_latency_ms: Option<u64>,
+    pub max_latency_ms: Option<u64>,
+    /// Seconds since the player last answered, zero while it answers
+    pub unresponsive_secs: u64,
+}
+
+/// Health of the connected player, fed with one ping result at a time
+#[derive(Debug)]
+pub struct PlayerWatchdog {
+    status: PlayerHealthStatus,
+    latencies: VecDeque<Duration>,
+    last_latency: Option<Duration>,
+    /// First unanswered ping since the player last answered
+    unresponsive_since: Option<Instant>,
+}
+
+impl PlayerWatchdog {
+    pub fn new() -> Self {
+        Self {
+            status: PlayerHealthStatus::Healthy,
+            latencies: VecDeque::new(),
+            last_latency: None,
+            unresponsive_since: None,
+        }
+    }
+
+    pub fn status(&self) -> PlayerHealthStatus {
+        self.status
+    }
+
+    /// Record a ping; `None` when the player did not answer in time
+    ///
+    /// Returns the new status when it changed. Each step is only reached once per outage.
+    pub fn observe(
+        &mut self,
+        latency: Option<Duration>,
+        now: Instant,
+        policy: &WatchdogPolicy,
+    ) -> Option<PlayerHealthStatus> {
+        let next = match latency {
+            Some(latency) => {
+                self.last_latency = Some(latency);
+                self.latencies.push_back(latency);
+                if self.latencies.len() > LATENCY_SAMPLES {
+                    self.latencies.pop_front();
+                }
+                self.unresponsive_since = None;
+                PlayerHealthStatus::Healthy
+            }
+            None => {
+                let since = *self.unresponsive_since.get_or_insert(now);
+                let silent = now.saturating_duration_since(since).as_secs();
+                let step = if silent >= policy.restart_after_secs {
+                    PlayerHealthStatus::Restarting
+                } else if silent >= policy.poll_fallback_after_secs {
+                    PlayerHealthStatus::Polling
+                } else if silent >= policy.warn_after_secs {
+                    PlayerHealthStatus::Warning
+                } else {
+                    PlayerHealthStatus::Healthy
+                };
+                step.max(self.status)
+            }
+        };
+        if next == self.status {
+            return None;
+        }
+        self.status = next;
+        Some(next)
+    }
+
+    pub fn event(&self, now: Instant) -> PlayerHealthEvent {
+        let as_ms = |duration: Duration| duration.as_millis() as u64;
+        let average = (!self.latencies.is_empty())
+            .then(|| self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32);
+        PlayerHealthEvent {
+            status: self.status,
+            latency_ms: self.last_latency.map(as_ms),
+            average_latency_ms: average.map(as_ms),
+            max_latency_ms: self.latencies.iter().max().copied().map(as_ms),
+            unresponsive_secs: self
+                .unresponsive_since
+                .map(|since| now.saturating_duration_since(since).as_secs())
+                .unwrap_or(0),
+        }
+    }
+}
+
+impl Default for PlayerWatchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escalates_through_each_step_once() {
+        let policy = WatchdogPolicy::default();
+        let mut watchdog = PlayerWatchdog::new();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        assert_eq!(watchdog.observe(None, at(0), &policy), None);
+        assert_eq!(
+            watchdog.observe(None, at(5), &policy),
+            Some(PlayerHealthStatus::Warning)
+        );
+        assert_eq!(watchdog.observe(None, at(6), &policy), None);
+        assert_eq!(
+            watchdog.observe(None, at(15), &policy),
+            Some(PlayerHealthStatus::Polling)
+        );
+        assert_eq!(
+            watchdog.observe(None, at(60), &policy),
+            Some(PlayerHealthStatus::Restarting)
+        );
+        assert_eq!(watchdog.event(at(62)).unresponsive_secs, 62);
+        assert_eq!(
+            watchdog.observe(Some(Duration::from_millis(20)), at(63), &policy),
+            Some(PlayerHealthStatus::Healthy)
+        );
+        assert_eq!(watchdog.event(at(63)).unresponsive_secs, 0);
+    }
+
+    #[test]
+    fn test_latency_metrics() {
+        let policy = WatchdogPolicy::default();
+        let mut watchdog = PlayerWatchdog::new();
+        let now = Instant::now();
+        for ms in [10, 30, 20] {
+            watchdog.observe(Some(Duration::from_millis(ms)), now, &policy);
+        }
+        let event = watchdog.event(now);
+        assert_eq!(event.status, PlayerHealthStatus::Healthy);
+        assert_eq!(event.latency_ms, Some(20));
+        assert_eq!(event.average_latency_ms, Some(20));
+        assert_eq!(event.max_latency_ms, Some(30));
+    }
+}
@@ -1,16 +1,59 @@
+use md5::Md5;
 use regex::Regex;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
 use url::Url;
 
-use crate::config::PrivacyMode;
+use crate::config::{PrivacyMode, PrivacyRule};
 use crate::network::messages::FileSizeInfo;
 
 pub const PRIVACY_HIDDEN_FILENAME: &str = "**Hidden filename**";
+pub const JOIN_LINK_SCHEME: &str = "syncplay";
+const JOIN_LINK_DEFAULT_PORT: u16 = 8999;
 pub const MUSIC_FORMATS: [&str; 8] = [
     ".mp3", ".m4a", ".m4p", ".wav", ".aiff", ".r", ".ogg", ".flac",
 ];
 
+pub const VIDEO_FORMATS: [&str; 14] = [
+    ".mkv", ".mp4", ".m4v", ".avi", ".mov", ".wmv", ".flv", ".webm", ".mpg", ".mpeg", ".ts",
+    ".m2ts", ".ogv", ".3gp",
+];
+
+/// Format a playback position as `MM:SS`, or `HH:MM:SS` from one hour on
+///
+/// `always_show_hours` keeps the hour field for positions under an hour.
+pub fn format_time(time_seconds: f64, always_show_hours: bool) -> String {
+    let mut seconds = time_seconds.round() as i64;
+    let sign = if seconds < 0 {
+        seconds = -seconds;
+        "-"
+    } else {
+        ""
+    };
+
+    let weeks = seconds / 604_800;
+    let days = (seconds % 604_800) / 86_400;
+    let hours = (seconds % 86_400) / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    let secs = seconds % 60;
+
+    if weeks > 0 {
+        format!(
+            "{}{}w, {}d, {:02}:{:02}:{:02}",
+            sign, weeks, days, hours, minutes, secs
+        )
+    } else if days > 0 {
+        format!("{}{}d, {:02}:{:02}:{:02}", sign, days, hours, minutes, secs)
+    } else if hours > 0 || always_show_hours {
+        format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, secs)
+    } else {
+        format!("{}{:02}:{:02}", sign, minutes, secs)
+    }
+}
+
 pub fn truncate_text(value: &str, max_len: usize) -> String {
     if max_len == 0 {
         return String::new();
@@ -22,11 +65,78 @@ pub fn truncate_text(value: &str, max_len: usize) -> String {
     String::from_utf8_lossy(&bytes[..max_len]).to_string()
 }
 
+/// Normalize text for display, dropping invisible and direction control characters
+///
+/// Unusual spaces become plain spaces so OSD line widths stay predictable.
+/// Zero-width joiners and variation selectors are kept for emoji sequences.
+pub fn sanitize_display_text(value: &str) -> String {
+    value
+        .nfc()
+        .filter_map(|c| match c {
+            '\n' => Some(c),
+            '\t' | '\u{00a0}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}' => {
+                Some(' ')
+            }
+            '\u{200b}' | '\u{200e}' | '\u{200f}' | '\u{061c}' | '\u{2060}' | '\u{feff}' => None,
+            '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => None,
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
 pub fn is_music_file(filename: &str) -> bool {
     let lower = filename.to_ascii_lowercase();
     MUSIC_FORMATS.iter().any(|ext| lower.ends_with(ext))
 }
 
+pub fn is_media_file(filename: &str) -> bool {
+    let lower = filename.to_ascii_lowercase();
+    is_music_file(&lower) || VIDEO_FORMATS.iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Compare filenames so that embedded numbers sort numerically (E2 before E10)
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut a_num = String::new();
+                while let Some(c) = a_chars.peek().copied().filter(char::is_ascii_digit) {
+                    a_num.push(c);
+                    a_chars.next();
+                }
+                let mut b_num = String::new();
+                while let Some(c) = b_chars.peek().copied().filter(char::is_ascii_digit) {
+                    b_num.push(c);
+                    b_chars.next();
+                }
+                let a_trimmed = a_num.trim_start_matches('0');
+                let b_trimmed = b_num.trim_start_matches('0');
+                let ordering = a_trimmed
+                    .len()
+                    .cmp(&b_trimmed.len())
+                    .then_with(|| a_trimmed.cmp(b_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
 pub fn playlist_filename_from_path(path: &str) -> Option<String> {
     if is_url(path) {
         return Some(path.to_string());
@@ -110,6 +220,9 @@ pub fn is_trustable_and_trusted(
     (true, false)
 }
 
+/// Filename reduced to the form upstream Syncplay compares and hashes
+///
+/// Like upstream, percent-escapes are decoded first so a URL and the file it points to agree.
 pub fn strip_filename(filename: &str, strip_url: bool) -> String {
     let mut base = filename.to_string();
     if strip_url || is_url(filename) {
@@ -122,6 +235,9 @@ pub fn strip_filename(filename: &str, strip_url: bool) -> String {
             }
         }
     }
+    if let Ok(decoded) = urlencoding::decode(&base) {
+        base = decoded.into_owned();
+    }
     let regex = Regex::new(r"[-~_\.\[\](): ]").expect("invalid filename regex");
     regex.replace_all(&base, "").to_string()
 }
@@ -135,6 +251,19 @@ pub fn hash_filename(filename: &str, strip_url: bool) -> String {
     hex.chars().take(12).collect()
 }
 
+/// Whether a peer's filename looks like what `hash_filename` sends in hashed privacy mode
+pub fn is_filename_hash(value: &str) -> bool {
+    value.len() == 12
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Server passwords travel as an MD5 hex digest, as upstream Syncplay sends them
+pub fn hash_server_password(password: &str) -> String {
+    format!("{:x}", Md5::digest(password.as_bytes()))
+}
+
 pub fn hash_filesize(size: u64) -> String {
     let mut hasher = Sha256::new();
     hasher.update(size.to_string().as_bytes());
@@ -166,6 +295,82 @@ pub fn apply_privacy(
     (name, size)
 }
 
+/// Where a file is about to be announced, for matching privacy rules
+#[derive(Debug, Clone, Copy)]
+pub struct PrivacyContext<'a> {
+    pub room: &'a str,
+    pub filename: &'a str,
+    pub public_server: bool,
+}
+
+/// Privacy modes a file gets, and the rules they came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedPrivacy {
+    pub filename_mode: PrivacyMode,
+    pub filesize_mode: PrivacyMode,
+    /// Index of the rule that set the filename mode; `None` for the global setting
+    pub filename_rule: Option<usize>,
+    pub filesize_rule: Option<usize>,
+}
+
+/// Apply the first matching rule for each mode on top of the global modes
+pub fn resolve_privacy(
+    rules: &[PrivacyRule],
+    context: PrivacyContext<'_>,
+    filename_mode: &PrivacyMode,
+    filesize_mode: &PrivacyMode,
+) -> ResolvedPrivacy {
+    let mut resolved = ResolvedPrivacy {
+        filename_mode: filename_mode.clone(),
+        filesize_mode: filesize_mode.clone(),
+        filename_rule: None,
+        filesize_rule: None,
+    };
+    let matching = rules.iter().enumerate().filter(|(_, rule)| {
+        (!rule.public_servers_only || context.public_server)
+            && wildcard_match(&rule.room, context.room)
+            && wildcard_match(&rule.file, context.filename)
+    });
+    for (index, rule) in matching {
+        if let (None, Some(mode)) = (resolved.filename_rule, &rule.filename_mode) {
+            resolved.filename_mode = mode.clone();
+            resolved.filename_rule = Some(index);
+        }
+        if let (None, Some(mode)) = (resolved.filesize_rule, &rule.filesize_mode) {
+            resolved.filesize_mode = mode.clone();
+            resolved.filesize_rule = Some(index);
+        }
+    }
+    resolved
+}
+
+/// Case-insensitive match where `*` stands for any run of characters
+///
+/// An empty pattern matches everything.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    if pattern.is_empty() {
+        return true;
+    }
+    let text = text.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, rest) = parts.split_first().unwrap_or((&"", &[]));
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return remaining.is_empty();
+    };
+    for part in middle {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
 pub fn same_filename(a: Option<&str>, b: Option<&str>) -> bool {
     let a = match a {
         Some(value) => value,
@@ -184,6 +389,13 @@ pub fn same_filename(a: Option<&str>, b: Option<&str>) -> bool {
         return true;
     }
 
+    // A peer in hashed privacy mode only sends the hash, so compare ours against it
+    match (is_filename_hash(a), is_filename_hash(b)) {
+        (true, false) => return hash_filename(b, is_url(b)) == a,
+        (false, true) => return hash_filename(a, is_url(a)) == b,
+        _ => {}
+    }
+
     let a_stripped = strip_filename(a, is_url(a) ^ is_url(b));
     let b_stripped = strip_filename(b, is_url(a) ^ is_url(b));
     if a_stripped == b_stripped {
@@ -270,6 +482,11 @@ pub fn parse_controlled_room_input(room: &str) -> (String, Option<String>) {
     (normalized_room, password)
 }
 
+/// Servers list blank names for rooms nobody is in anymore
+pub fn is_placeholder_username(username: &str) -> bool {
+    username.trim().is_empty()
+}
+
 pub fn is_controlled_room(room: &str) -> bool {
     if !room.starts_with('+') {
         return false;
@@ -314,16 +531,129 @@ pub fn version_meets_min(version: &str, min_version: &str) -> bool {
     true
 }
 
+/// Server, room and file named by a `syncplay://host:port/room?file=...` link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinLink {
+    pub host: String,
+    pub port: u16,
+    pub room: Option<String>,
+    pub file: Option<String>,
+}
+
+pub fn parse_join_link(link: &str) -> Result<JoinLink, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("Invalid join link: {}", e))?;
+    if url.scheme() != JOIN_LINK_SCHEME {
+        return Err(format!(
+            "Join links must start with {}://",
+            JOIN_LINK_SCHEME
+        ));
+    }
+    let host = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| "Join link has no server".to_string())?
+        .to_string();
+    let path = url.path().trim_matches('/');
+    let room = urlencoding::decode(path)
+        .map_err(|e| format!("Invalid room in join link: {}", e))?
+        .trim()
+        .to_string();
+    let file = url
+        .query_pairs()
+        .find(|(key, _)| key == "file")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|file| !file.is_empty());
+    Ok(JoinLink {
+        host,
+        port: url.port().unwrap_or(JOIN_LINK_DEFAULT_PORT),
+        room: (!room.is_empty()).then_some(room),
+        file,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(65.4, false), "01:05");
+        assert_eq!(format_time(65.4, true), "00:01:05");
+        assert_eq!(format_time(3_723.0, false), "01:02:03");
+        assert_eq!(format_time(-5.0, false), "-00:05");
+        assert_eq!(format_time(90_061.0, false), "1d, 01:01:01");
+    }
+
+    #[test]
+    fn test_hash_server_password() {
+        assert_eq!(
+            hash_server_password("password"),
+            "5f4dcc3b5aa765d61d8327deb882cf99"
+        );
+    }
+
     #[test]
     fn test_hash_filename() {
         let hashed = hash_filename("Movie File.mp4", true);
         assert_eq!(hashed.len(), 12);
     }
 
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("", "anything"));
+        assert!(wildcard_match("friends", "Friends"));
+        assert!(wildcard_match("*.mkv", "Episode 1.MKV"));
+        assert!(wildcard_match("show*e0*", "Show S01E03.mkv"));
+        assert!(!wildcard_match("friends", "friends2"));
+        assert!(!wildcard_match("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn test_resolve_privacy_first_matching_rule_wins() {
+        let rules = vec![
+            PrivacyRule {
+                room: "friends".to_string(),
+                filename_mode: Some(PrivacyMode::SendRaw),
+                ..PrivacyRule::default()
+            },
+            PrivacyRule {
+                public_servers_only: true,
+                filename_mode: Some(PrivacyMode::SendHashed),
+                filesize_mode: Some(PrivacyMode::SendHashed),
+                ..PrivacyRule::default()
+            },
+        ];
+        let context = PrivacyContext {
+            room: "friends",
+            filename: "a.mkv",
+            public_server: true,
+        };
+        let resolved = resolve_privacy(
+            &rules,
+            context,
+            &PrivacyMode::DoNotSend,
+            &PrivacyMode::SendRaw,
+        );
+        assert_eq!(resolved.filename_mode, PrivacyMode::SendRaw);
+        assert_eq!(resolved.filename_rule, Some(0));
+        assert_eq!(resolved.filesize_mode, PrivacyMode::SendHashed);
+        assert_eq!(resolved.filesize_rule, Some(1));
+
+        let private = PrivacyContext {
+            room: "lobby",
+            public_server: false,
+            ..context
+        };
+        let resolved = resolve_privacy(
+            &rules,
+            private,
+            &PrivacyMode::DoNotSend,
+            &PrivacyMode::SendRaw,
+        );
+        assert_eq!(resolved.filename_mode, PrivacyMode::DoNotSend);
+        assert_eq!(resolved.filename_rule, None);
+    }
+
     #[test]
     fn test_same_filename_hidden() {
         assert!(same_filename(Some(PRIVACY_HIDDEN_FILENAME), Some("foo")));
@@ -334,6 +664,18 @@ mod tests {
         let name = "Movie File.mp4";
         let hashed = hash_filename(name, true);
         assert!(same_filename(Some(name), Some(&hashed)));
+        assert!(same_filename(Some(&hashed), Some(name)));
+        assert!(!same_filename(Some("Other File.mp4"), Some(&hashed)));
+    }
+
+    #[test]
+    fn test_hashed_url_matches_local_file() {
+        let hashed = hash_filename("https://example.com/media/Movie%20File.mp4", false);
+        assert!(is_filename_hash(&hashed));
+        assert_eq!(hashed, hash_filename("Movie File.mp4", false));
+        assert!(same_filename(Some("Movie File.mp4"), Some(&hashed)));
+        assert!(!is_filename_hash("Movie.mp4"));
+        assert!(!is_filename_hash("ABCDEF123456"));
     }
 
     #[test]
@@ -342,9 +684,65 @@ mod tests {
         assert_eq!(args, vec!["--foo", "bar", "--baz=1"]);
     }
 
+    #[test]
+    fn test_natural_cmp_orders_episodes() {
+        let mut names = vec![
+            "Show S01E10.mkv".to_string(),
+            "Show S01E2.mkv".to_string(),
+            "Show S01E1.mkv".to_string(),
+            "show s01e03.mkv".to_string(),
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec![
+                "Show S01E1.mkv",
+                "Show S01E2.mkv",
+                "show s01e03.mkv",
+                "Show S01E10.mkv",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_media_file() {
+        assert!(is_media_file("Episode.MKV"));
+        assert!(is_media_file("track.flac"));
+        assert!(!is_media_file("subtitles.srt"));
+    }
+
     #[test]
     fn test_truncate_text() {
         let text = truncate_text("hello", 3);
         assert_eq!(text, "hel");
     }
+
+    #[test]
+    fn test_sanitize_display_text() {
+        assert_eq!(
+            sanitize_display_text("e\u{301}vil\u{202e}txt.exe\u{200b}"),
+            "\u{e9}viltxt.exe"
+        );
+        assert_eq!(sanitize_display_text("a\u{3000}b\tc\u{7}"), "a b c");
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        assert_eq!(sanitize_display_text(family), family);
+    }
+
+    #[test]
+    fn test_parse_join_link() {
+        let link =
+            parse_join_link("syncplay://syncplay.pl:8997/movie%20night?file=Episode%2001.mkv")
+                .unwrap();
+        assert_eq!(link.host, "syncplay.pl");
+        assert_eq!(link.port, 8997);
+        assert_eq!(link.room.as_deref(), Some("movie night"));
+        assert_eq!(link.file.as_deref(), Some("Episode 01.mkv"));
+
+        let link = parse_join_link("syncplay://example.com").unwrap();
+        assert_eq!(link.port, 8999);
+        assert!(link.room.is_none() && link.file.is_none());
+
+        assert!(parse_join_link("https://example.com/room").is_err());
+        assert!(parse_join_link("syncplay:///room").is_err());
+    }
 }
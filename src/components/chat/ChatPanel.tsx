@@ -1,6 +1,8 @@
 import { useState, useRef, useEffect } from "react";
 import { useSyncplayStore } from "../../store";
 import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
+import { MotdBanner } from "./MotdBanner";
 
 export function ChatPanel() {
   const messages = useSyncplayStore((state) => state.messages);
@@ -9,6 +11,7 @@ export function ChatPanel() {
   const [inputValue, setInputValue] = useState("");
   const messagesEndRef = useRef<HTMLDivElement>(null);
   const chatInputEnabled = config?.user.chat_input_enabled ?? true;
+  const hour12 = config?.user.chat_timestamp_format === "hours12";
 
   // Auto-scroll to bottom when new messages arrive
   useEffect(() => {
@@ -26,6 +29,23 @@ export function ChatPanel() {
     }
   };
 
+  const handleExportLog = async () => {
+    try {
+      const path = await save({
+        defaultPath: "syncplay-session.txt",
+        filters: [
+          { name: "Text transcript", extensions: ["txt"] },
+          { name: "JSON", extensions: ["json"] },
+        ],
+      });
+      if (!path) return;
+      const format = path.toLowerCase().endsWith(".json") ? "json" : "text";
+      await invoke("export_session_log", { path, format });
+    } catch (error) {
+      console.error("Failed to export session log:", error);
+    }
+  };
+
   const handleKeyPress = (e: React.KeyboardEvent) => {
     if (e.key === "Enter" && !e.shiftKey) {
       e.preventDefault();
@@ -39,7 +59,7 @@ export function ChatPanel() {
       hour: "2-digit",
       minute: "2-digit",
       second: "2-digit",
-      hour12: false,
+      hour12,
     });
   };
 
@@ -56,6 +76,7 @@ export function ChatPanel() {
 
   return (
     <div className="flex flex-col h-full">
+      <MotdBanner />
       {/* Messages area */}
       <div className="flex-1 p-5 pt-7 overflow-auto space-y-0.5">
         {messages.length === 0 ? (
@@ -70,7 +91,10 @@ export function ChatPanel() {
             const displayMessage = msg.message.trim();
 
             return (
-              <div key={index} className="text-sm app-message">
+              <div
+                key={index}
+                className={`text-sm app-message ${msg.mentioned ? "app-message-mention" : ""}`}
+              >
                 <span className="app-text-muted text-xs">{formatTimestamp(msg.timestamp)}</span>
                 {msg.username && (
                   <span className="app-text-accent font-medium ml-2">{msg.username}:</span>
@@ -84,7 +108,7 @@ export function ChatPanel() {
       </div>
 
       {/* Input area */}
-      <div className="border-t app-divider p-4 app-surface">
+      <div className="border-t app-divider p-4 app-surface flex gap-2">
         <input
           type="text"
           value={inputValue}
@@ -100,6 +124,13 @@ export function ChatPanel() {
           className="w-full app-input px-4 py-2 rounded-md focus:outline-none focus:border-blue-500"
           disabled={!connection.connected || !chatInputEnabled}
         />
+        <button
+          onClick={handleExportLog}
+          className="btn-neutral px-3 py-2 rounded-md text-sm shrink-0"
+          title="Export chat and room activity"
+        >
+          Export
+        </button>
       </div>
     </div>
   );
@@ -0,0 +1,58 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+
+export function MotdBanner() {
+  const motd = useSyncplayStore((state) => state.motd);
+  const setMotd = useSyncplayStore((state) => state.setMotd);
+
+  if (!motd) return null;
+
+  const handleDontShowAgain = async () => {
+    try {
+      await invoke("dismiss_motd");
+      setMotd(null);
+    } catch (error) {
+      console.error("Failed to dismiss MOTD:", error);
+    }
+  };
+
+  return (
+    <div className="border-b app-divider app-surface px-5 py-3 text-sm">
+      <div className="flex items-start justify-between gap-3">
+        <div className="min-w-0">
+          <p className="app-text-accent font-medium">
+            {motd.kind === "motd" ? "Message of the day" : "Server announcement"}
+          </p>
+          <p className="whitespace-pre-wrap break-words">{motd.message}</p>
+          {motd.links.length > 0 && (
+            <ul className="mt-1 space-y-0.5">
+              {motd.links.map((link) => (
+                <li key={link} className="truncate">
+                  <a href={link} target="_blank" rel="noreferrer" className="app-text-accent">
+                    {link}
+                  </a>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+        <div className="flex shrink-0 gap-2">
+          {motd.kind === "motd" && (
+            <button
+              onClick={handleDontShowAgain}
+              className="btn-neutral px-3 py-2 rounded-md text-sm"
+            >
+              Don't show again
+            </button>
+          )}
+          <button
+            onClick={() => setMotd(null)}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+          >
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -3,19 +3,54 @@ import { LuSettings } from "react-icons/lu";
 import { useSyncplayStore } from "../../store";
 import { useNotificationStore } from "../../store/notifications";
 import { invoke } from "@tauri-apps/api/core";
-import { PublicServer, SyncplayConfig } from "../../types/config";
+import {
+  ConnectionPreset,
+  PublicServer,
+  ServerConfig,
+  SyncplayConfig,
+} from "../../types/config";
 
 interface ConnectionDialogProps {
   isOpen: boolean;
   onClose: () => void;
 }
 
+type PlayerIpcKind = "mpv_json" | "vlc_lua" | "mplayer_slave" | "mpc_api";
+
+interface PlayerCapabilities {
+  speed_control: boolean;
+  chat_osd: boolean;
+  ipc: PlayerIpcKind | null;
+}
+
 interface DetectedPlayer {
   name: string;
   path: string;
   version: string | null;
+  capabilities: PlayerCapabilities;
+  source: "system" | "flatpak" | "snap";
 }
 
+const IPC_LABELS: Record<PlayerIpcKind, string> = {
+  mpv_json: "mpv IPC",
+  vlc_lua: "Syncplay Lua interface",
+  mplayer_slave: "slave mode",
+  mpc_api: "MPC API",
+};
+
+const describePlayer = (player: DetectedPlayer) => {
+  const parts = [player.capabilities.ipc ? IPC_LABELS[player.capabilities.ipc] : "no control"];
+  if (player.capabilities.speed_control) parts.push("speed control");
+  if (player.capabilities.chat_osd) parts.push("chat overlay");
+  if (player.source !== "system") parts.push(`installed via ${player.source}`);
+  return parts.join(" · ");
+};
+
+type PasswordCheck =
+  | { status: "accepted" }
+  | { status: "authFailed"; message: string }
+  | { status: "networkError"; message: string };
+
 interface PlayerDetectionCache {
   players: DetectedPlayer[];
   updated_at: number | null;
@@ -159,16 +194,22 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
   const [isConnecting, setIsConnecting] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [showOptions, setShowOptions] = useState(false);
+  const [checkingPassword, setCheckingPassword] = useState(false);
+  const [passwordCheck, setPasswordCheck] = useState<PasswordCheck | null>(null);
   const [detectedPlayers, setDetectedPlayers] = useState<DetectedPlayer[]>([]);
   const [detectingPlayers, setDetectingPlayers] = useState(false);
   const [playersUpdatedAt, setPlayersUpdatedAt] = useState<number | null>(null);
   const [playersError, setPlayersError] = useState<string | null>(null);
   const [playerArgsInput, setPlayerArgsInput] = useState("");
+  const [presetName, setPresetName] = useState("");
 
   const serverOptions = buildServerOptions(config?.recent_servers ?? [], config?.public_servers);
+  const recentServers = config?.recent_servers ?? [];
+  const presets = config?.presets ?? [];
   const roomOptions = config?.user.room_list ?? [];
   const playerPath = config?.player.player_path?.trim() ?? "";
   const playerSelectionMissing = !playerPath || playerPath === "custom";
+  const selectedPlayer = detectedPlayers.find((player) => player.path === playerPath);
 
   useEffect(() => {
     if (!isOpen) return;
@@ -207,8 +248,9 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
   useEffect(() => {
     if (!isOpen || activeTab !== "player") return;
     void (async () => {
-      await loadPlayerCache();
-      await refreshPlayers();
+      if (!(await loadPlayerCache())) {
+        await refreshPlayers();
+      }
     })();
   }, [isOpen, activeTab]);
 
@@ -227,10 +269,91 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
     setFormData((prev) => ({
       ...prev,
       address: value,
+      username: entry?.username || prev.username,
+      room: entry?.room || prev.room,
       password: entry?.password || prev.password,
     }));
   };
 
+  const reloadConfig = async () => {
+    try {
+      setConfig(await invoke<SyncplayConfig>("get_config"));
+    } catch (err) {
+      setError("Failed to load saved config");
+    }
+  };
+
+  const handlePinServer = async (server: ServerConfig) => {
+    try {
+      await invoke("pin_server", { host: server.host, port: server.port, pinned: !server.pinned });
+      await reloadConfig();
+    } catch (err) {
+      setError(err as string);
+    }
+  };
+
+  const handleSavePreset = async () => {
+    const address = parseAddress(formData.address);
+    if (!address) {
+      setError("Address must be in host:port format");
+      return;
+    }
+    const preset: ConnectionPreset = {
+      name: presetName.trim(),
+      host: address.host,
+      port: address.port,
+      username: formData.username,
+      room: formData.room,
+      player_path: playerSelectionMissing ? null : playerPath,
+    };
+    try {
+      await invoke("save_preset", { preset });
+      setPresetName("");
+      await reloadConfig();
+    } catch (err) {
+      setError(err as string);
+    }
+  };
+
+  const handleDeletePreset = async (name: string) => {
+    try {
+      await invoke("delete_preset", { name });
+      await reloadConfig();
+    } catch (err) {
+      setError(err as string);
+    }
+  };
+
+  const handleConnectWithPreset = async (name: string) => {
+    setIsConnecting(true);
+    setError(null);
+    try {
+      await invoke("connect_with_preset", { name });
+      addNotification({
+        type: "success",
+        message: `Connected with ${name}`,
+      });
+      onClose();
+    } catch (err) {
+      setError(err as string);
+      addNotification({
+        type: "error",
+        message: `Connection failed: ${err}`,
+      });
+    } finally {
+      setIsConnecting(false);
+    }
+  };
+
+  const handleRemoveServer = async (server: ServerConfig) => {
+    try {
+      await invoke("remove_recent_server", { host: server.host, port: server.port });
+      await reloadConfig();
+    } catch (err) {
+      setError(err as string);
+    }
+  };
+
   const parseAddress = (address: string): { host: string; port: number } | null => {
     const trimmed = address.trim();
     if (!trimmed) {
@@ -311,9 +434,23 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
       setDetectedPlayers(cache.players);
       setPlayersUpdatedAt(cache.updated_at);
       setPlayersError(null);
+      return cache.updated_at !== null;
     } catch (err) {
       console.error("Failed to load cached players:", err);
       setPlayersError("Failed to load cached players.");
+      return false;
+    }
+  };
+
+  const clearPlayerCache = async () => {
+    try {
+      await invoke("clear_player_detection");
+      setDetectedPlayers([]);
+      setPlayersUpdatedAt(null);
+      setPlayersError(null);
+    } catch (err) {
+      console.error("Failed to clear player cache:", err);
+      setPlayersError("Failed to clear the player cache.");
     }
   };
 
@@ -349,9 +486,20 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
     port: number,
     password: string | null
   ) => {
-    const next = servers.filter((entry) => entry.host !== host || entry.port !== port);
-    next.unshift({ host, port, password });
-    return next.slice(0, 10);
+    const existing = servers.find((entry) => entry.host === host && entry.port === port);
+    const next = servers.filter((entry) => entry !== existing);
+    const server = {
+      ...existing,
+      host,
+      port,
+      password,
+      username: formData.username,
+      room: formData.room,
+    };
+    // Pinned servers stay on top and don't count towards the limit
+    const pinnedCount = next.filter((entry) => entry.pinned).length;
+    next.splice(server.pinned ? 0 : pinnedCount, 0, server);
+    return next.slice(0, Math.max(10, pinnedCount + (server.pinned ? 1 : 0)));
   };
 
   function buildServerOptions(
@@ -370,6 +518,30 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
     return [...publicOptions, ...recentOptions];
   }
 
+  const handleCheckPassword = async () => {
+    const address = parseAddress(formData.address);
+    if (!address) {
+      setError("Address must be in host:port format");
+      return;
+    }
+    setCheckingPassword(true);
+    setPasswordCheck(null);
+    try {
+      const result = await invoke<PasswordCheck>("verify_server_password", {
+        host: address.host,
+        port: address.port,
+        username: formData.username,
+        room: formData.room,
+        password: formData.password || null,
+      });
+      setPasswordCheck(result);
+    } catch (err) {
+      setPasswordCheck({ status: "networkError", message: String(err) });
+    } finally {
+      setCheckingPassword(false);
+    }
+  };
+
   const handleConnect = async (saveConfig: boolean) => {
     if (!formData.username.trim()) {
       setError("Username is required");
@@ -506,6 +678,99 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
           >
             {activeTab === "connection" ? (
               <>
+                <div>
+                  <label className="block text-sm font-medium mb-1">Presets</label>
+                  {presets.length > 0 && (
+                    <div className="flex flex-wrap gap-2 mb-2">
+                      {presets.map((preset) => (
+                        <div
+                          key={preset.name}
+                          className="flex items-center app-panel-muted rounded"
+                        >
+                          <button
+                            type="button"
+                            onClick={() => handleConnectWithPreset(preset.name)}
+                            disabled={isConnecting}
+                            title={`${preset.username} in ${preset.room} on ${preset.host}:${preset.port}`}
+                            className="px-3 py-1 text-sm disabled:opacity-60"
+                          >
+                            {preset.name}
+                          </button>
+                          <button
+                            type="button"
+                            onClick={() => handleDeletePreset(preset.name)}
+                            className="px-2 py-1 text-xs app-text-danger hover:opacity-80"
+                            aria-label={`Delete ${preset.name}`}
+                          >
+                            ×
+                          </button>
+                        </div>
+                      ))}
+                    </div>
+                  )}
+                  <div className="flex gap-2">
+                    <input
+                      type="text"
+                      value={presetName}
+                      onChange={(e) => setPresetName(e.target.value)}
+                      className="w-full app-input px-3 py-2 rounded-md focus:outline-none focus:border-blue-500"
+                      placeholder="Preset name"
+                    />
+                    <button
+                      type="button"
+                      onClick={handleSavePreset}
+                      disabled={!presetName.trim() || !formData.username.trim()}
+                      className="btn-neutral px-3 py-2 rounded-md text-sm whitespace-nowrap"
+                    >
+                      Save current
+                    </button>
+                  </div>
+                </div>
+
+                {recentServers.length > 0 && (
+                  <div>
+                    <label className="block text-sm font-medium mb-1">Saved servers</label>
+                    <div className="space-y-1 max-h-40 overflow-auto">
+                      {recentServers.map((server) => {
+                        const address = `${server.host}:${server.port}`;
+                        return (
+                          <div
+                            key={address}
+                            className={`flex items-center justify-between gap-2 px-3 py-1.5 rounded ${formData.address === address ? "app-panel-muted" : ""}`}
+                          >
+                            <button
+                              type="button"
+                              onClick={() => handleAddressSelect(address)}
+                              className="flex-1 min-w-0 text-left"
+                            >
+                              <div className="text-sm truncate">{address}</div>
+                              {(server.username || server.room) && (
+                                <div className="text-xs app-text-muted truncate">
+                                  {[server.username, server.room].filter(Boolean).join(" · ")}
+                                </div>
+                              )}
+                            </button>
+                            <button
+                              type="button"
+                              onClick={() => handlePinServer(server)}
+                              className="text-xs app-text-muted hover:opacity-80"
+                            >
+                              {server.pinned ? "Unpin" : "Pin"}
+                            </button>
+                            <button
+                              type="button"
+                              onClick={() => handleRemoveServer(server)}
+                              className="text-xs app-text-danger hover:opacity-80"
+                            >
+                              Remove
+                            </button>
+                          </div>
+                        );
+                      })}
+                    </div>
+                  </div>
+                )}
+
                 <ComboBox
                   label="Address (host:port)"
                   value={formData.address}
@@ -537,13 +802,37 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
 
                 <div>
                   <label className="block text-sm font-medium mb-1">Password (optional)</label>
-                  <input
-                    type="password"
-                    value={formData.password}
-                    onChange={(e) => setFormData({ ...formData, password: e.target.value })}
-                    className="w-full app-input px-3 py-2 rounded-md focus:outline-none focus:border-blue-500"
-                    placeholder="Server password"
-                  />
+                  <div className="flex gap-2">
+                    <input
+                      type="password"
+                      value={formData.password}
+                      onChange={(e) => {
+                        setFormData({ ...formData, password: e.target.value });
+                        setPasswordCheck(null);
+                      }}
+                      className="w-full app-input px-3 py-2 rounded-md focus:outline-none focus:border-blue-500"
+                      placeholder="Server password"
+                    />
+                    <button
+                      type="button"
+                      onClick={handleCheckPassword}
+                      disabled={checkingPassword || !formData.username.trim()}
+                      className="btn-neutral px-3 py-2 rounded-md text-sm"
+                    >
+                      {checkingPassword ? "Checking..." : "Check"}
+                    </button>
+                  </div>
+                  {passwordCheck && (
+                    <p
+                      className={`text-xs mt-1 ${passwordCheck.status === "accepted" ? "app-text-muted" : "app-text-danger"}`}
+                    >
+                      {passwordCheck.status === "accepted"
+                        ? "Password accepted"
+                        : passwordCheck.status === "authFailed"
+                          ? `Server rejected the login: ${passwordCheck.message}`
+                          : `Could not reach the server: ${passwordCheck.message}`}
+                    </p>
+                  )}
                 </div>
 
                 <div className="flex items-center justify-between">
@@ -574,11 +863,27 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
                     <label className="flex items-center gap-2 text-sm">
                       <input
                         type="checkbox"
-                        checked={config.user.auto_connect}
-                        onChange={(e) => updateUserConfig({ auto_connect: e.target.checked })}
+                        checked={config.user.remember_plaintext_passwords ?? false}
+                        onChange={(e) =>
+                          updateUserConfig({ remember_plaintext_passwords: e.target.checked })
+                        }
+                        className="w-4 h-4"
+                      />
+                      Save server passwords in plain text
+                    </label>
+                    <label className="flex items-center gap-2 text-sm">
+                      <input
+                        type="checkbox"
+                        checked={config.user.auto_connect_on_launch || config.user.auto_connect}
+                        onChange={(e) =>
+                          updateUserConfig({
+                            auto_connect_on_launch: e.target.checked,
+                            auto_connect: false,
+                          })
+                        }
                         className="w-4 h-4"
                       />
-                      Auto-connect on startup
+                      Connect to the saved server and room on launch
                     </label>
                     <label className="flex items-center gap-2 text-sm">
                       <input
@@ -614,6 +919,14 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
                     >
                       {detectingPlayers ? "Refreshing..." : "Refresh Players"}
                     </button>
+                    <button
+                      type="button"
+                      onClick={clearPlayerCache}
+                      className="btn-neutral px-2 py-1 rounded-md text-xs"
+                      disabled={detectingPlayers || playersUpdatedAt === null}
+                    >
+                      Clear Cache
+                    </button>
                   </div>
                 </div>
 
@@ -651,6 +964,11 @@ export function ConnectionDialog({ isOpen, onClose }: ConnectionDialogProps) {
                           No players detected. Enter path manually.
                         </p>
                       )}
+                      {selectedPlayer && (
+                        <p className="text-xs app-text-muted mt-1">
+                          {describePlayer(selectedPlayer)}
+                        </p>
+                      )}
                     </div>
 
                     {(config.player.player_path === "custom" ||
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+// Counts down to the connect-on-launch and lets the user abort it
+export function LaunchConnectDialog() {
+  const countdown = useSyncplayStore((state) => state.launchConnect);
+  const setCountdown = useSyncplayStore((state) => state.setLaunchConnect);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+
+  if (!countdown) return null;
+
+  const cancel = async () => {
+    try {
+      await invoke("cancel_launch_connect");
+      setCountdown(null);
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to cancel connecting: ${error}`,
+      });
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-sm shadow-xl">
+        <h2 className="text-xl font-bold mb-1">Connecting in {countdown.remaining}s</h2>
+        <p className="text-sm mb-2">
+          Joining {countdown.room} on {countdown.host}:{countdown.port} as {countdown.username}.
+        </p>
+        <p className="text-xs app-text-muted mb-4">
+          Turn this off under Connection Options in the connection dialog.
+        </p>
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={() => void cancel()}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+          >
+            Cancel
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
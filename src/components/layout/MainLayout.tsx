@@ -2,7 +2,12 @@ import { useCallback, useEffect, useRef, useState } from "react";
 import { UserList } from "../users/UserList";
 import { ChatPanel } from "../chat/ChatPanel";
 import { PlayerStatus } from "../player/PlayerStatus";
+import { BookmarksDialog } from "../player/BookmarksDialog";
+import { DelaysDialog } from "../player/DelaysDialog";
+import { SegmentsDialog } from "../player/SegmentsDialog";
 import {
+  LuAudioLines,
+  LuBookmark,
   LuColumns2,
   LuContrast,
   LuLock,
@@ -13,6 +18,7 @@ import {
   LuListMusic,
   LuMoon,
   LuRows2,
+  LuScissors,
   LuSettings,
   LuSun,
   LuZap,
@@ -21,9 +27,14 @@ import { getVersion } from "@tauri-apps/api/app";
 import { PhysicalSize } from "@tauri-apps/api/dpi";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useWindowDrag } from "../../hooks/useWindowDrag";
+import { SeekConfirmDialog } from "../player/SeekConfirmDialog";
 import { PlaylistPanel } from "../playlist/PlaylistPanel";
+import { UntrustedUrlDialog } from "../playlist/UntrustedUrlDialog";
 import { ConnectionDialog } from "../connection/ConnectionDialog";
+import { LaunchConnectDialog } from "../connection/LaunchConnectDialog";
 import { SettingsDialog } from "../settings/SettingsDialog";
+import { OperatorOfferDialog } from "../users/OperatorOfferDialog";
+import { RoomPasswordDialog } from "../users/RoomPasswordDialog";
 import { NotificationContainer } from "../notifications/NotificationContainer";
 import { useSyncplayStore } from "../../store";
 import { useNotificationStore } from "../../store/notifications";
@@ -52,6 +63,9 @@ export function MainLayout() {
   const sidePanelsRef = useRef<HTMLDivElement | null>(null);
   const [showConnectionDialog, setShowConnectionDialog] = useState(false);
   const [showSettingsDialog, setShowSettingsDialog] = useState(false);
+  const [showBookmarksDialog, setShowBookmarksDialog] = useState(false);
+  const [showSegmentsDialog, setShowSegmentsDialog] = useState(false);
+  const [showDelaysDialog, setShowDelaysDialog] = useState(false);
   const [showPlaylist, setShowPlaylist] = useState(true);
   const [sideLayout, setSideLayout] = useState<"columns" | "rows">("rows");
   const [theme, setTheme] = useState<ThemePreference>("dark");
@@ -151,25 +165,6 @@ export function MainLayout() {
 
         if (config.user.force_gui_prompt) {
           setShowConnectionDialog(true);
-        } else if (
-          config.user.auto_connect &&
-          !connection.connected &&
-          config.user.username.trim()
-        ) {
-          try {
-            await invoke("connect_to_server", {
-              host: config.server.host,
-              port: config.server.port,
-              username: config.user.username,
-              room: config.user.default_room,
-              password: config.server.password || null,
-            });
-          } catch (error) {
-            addNotification({
-              type: "error",
-              message: "Auto-connect failed",
-            });
-          }
         }
       } catch (error) {
         addNotification({
@@ -180,7 +175,7 @@ export function MainLayout() {
     };
 
     initFromConfig();
-  }, [connection.connected, addNotification, setConfig]);
+  }, [addNotification, setConfig]);
 
   const flushUiLayoutPatch = useCallback(async () => {
     if (uiLayoutPersistingRef.current) {
@@ -740,6 +735,30 @@ export function MainLayout() {
                       <LuDroplets className="app-icon" />
                     )}
                   </button>
+                  <button
+                    onClick={() => setShowBookmarksDialog(true)}
+                    className="btn-neutral app-icon-button"
+                    data-tauri-drag-region="false"
+                    aria-label="Bookmarks"
+                  >
+                    <LuBookmark className="app-icon" />
+                  </button>
+                  <button
+                    onClick={() => setShowSegmentsDialog(true)}
+                    className="btn-neutral app-icon-button"
+                    data-tauri-drag-region="false"
+                    aria-label="Intro and outro"
+                  >
+                    <LuScissors className="app-icon" />
+                  </button>
+                  <button
+                    onClick={() => setShowDelaysDialog(true)}
+                    className="btn-neutral app-icon-button"
+                    data-tauri-drag-region="false"
+                    aria-label="Audio and subtitle delay"
+                  >
+                    <LuAudioLines className="app-icon" />
+                  </button>
                 </div>
                 <div className="flex items-center gap-2 ml-auto">
                   {connection.connected && rttLabel && (
@@ -833,6 +852,15 @@ export function MainLayout() {
         onClose={() => setShowConnectionDialog(false)}
       />
 
+      <BookmarksDialog isOpen={showBookmarksDialog} onClose={() => setShowBookmarksDialog(false)} />
+      <SegmentsDialog isOpen={showSegmentsDialog} onClose={() => setShowSegmentsDialog(false)} />
+      <DelaysDialog isOpen={showDelaysDialog} onClose={() => setShowDelaysDialog(false)} />
+      <RoomPasswordDialog />
+      <UntrustedUrlDialog />
+      <SeekConfirmDialog />
+      <OperatorOfferDialog />
+      <LaunchConnectDialog />
+
       {/* Settings dialog */}
       <SettingsDialog
         isOpen={showSettingsDialog}
@@ -0,0 +1,161 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+interface BookmarksDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+const formatPosition = (position: number) => {
+  const totalSeconds = Math.floor(position);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+  const time = `${minutes.toString().padStart(2, "0")}:${seconds.toString().padStart(2, "0")}`;
+  return hours > 0 ? `${hours}:${time}` : time;
+};
+
+export function BookmarksDialog({ isOpen, onClose }: BookmarksDialogProps) {
+  const bookmarks = useSyncplayStore((state) => state.bookmarks);
+  const loadBookmarks = useSyncplayStore((state) => state.loadBookmarks);
+  const filename = useSyncplayStore((state) => state.player.filename);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [label, setLabel] = useState("");
+  const [share, setShare] = useState(true);
+
+  useEffect(() => {
+    if (isOpen) {
+      void loadBookmarks();
+    } else {
+      setLabel("");
+    }
+  }, [isOpen, loadBookmarks]);
+
+  const reportError = (action: string, error: unknown) => {
+    const message =
+      typeof error === "string"
+        ? error
+        : (error as { message?: string })?.message || "Unknown error";
+    addNotification({
+      type: "error",
+      message: `Failed to ${action}: ${message}`,
+    });
+  };
+
+  const addBookmark = async () => {
+    const trimmed = label.trim();
+    if (!trimmed) return;
+    try {
+      await invoke("add_bookmark", { label: trimmed, share });
+      setLabel("");
+    } catch (error) {
+      reportError("add bookmark", error);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  const sorted = [...bookmarks].sort(
+    (a, b) => a.file.localeCompare(b.file) || a.position - b.position
+  );
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-2xl max-h-[80vh] overflow-auto shadow-xl">
+        <div className="flex flex-wrap items-center justify-between gap-4 mb-4">
+          <div>
+            <h2 className="text-xl font-bold">Bookmarks</h2>
+            <p className="text-xs app-text-muted">Mark positions and jump back to them together.</p>
+          </div>
+          <button onClick={onClose} className="btn-neutral px-3 py-2 rounded-md text-sm">
+            Close
+          </button>
+        </div>
+
+        <div className="space-y-4">
+          <div>
+            <label className="block text-sm font-medium mb-1">Bookmark current position</label>
+            <div className="flex gap-2">
+              <input
+                type="text"
+                value={label}
+                onChange={(e) => setLabel(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") {
+                    e.preventDefault();
+                    void addBookmark();
+                  }
+                }}
+                className="flex-1 app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                placeholder="OP end"
+                disabled={!filename}
+              />
+              <button
+                type="button"
+                onClick={() => void addBookmark()}
+                className="btn-primary px-3 py-2 rounded text-sm"
+                disabled={!filename}
+              >
+                Add
+              </button>
+            </div>
+            <label className="flex items-center gap-2 mt-2 text-sm">
+              <input type="checkbox" checked={share} onChange={(e) => setShare(e.target.checked)} />
+              Share with the room
+            </label>
+          </div>
+
+          {sorted.length === 0 ? (
+            <p className="text-xs app-text-muted">No bookmarks yet.</p>
+          ) : (
+            <div className="space-y-2">
+              {sorted.map((bookmark) => (
+                <div
+                  key={bookmark.id}
+                  className="flex items-center justify-between app-panel-muted px-3 py-2 rounded"
+                >
+                  <div className="min-w-0">
+                    <p className="text-sm truncate">
+                      <span className="font-mono mr-2">{formatPosition(bookmark.position)}</span>
+                      {bookmark.label}
+                    </p>
+                    <p className="text-xs app-text-muted truncate">
+                      {bookmark.file}
+                      {bookmark.sharedBy && ` · from ${bookmark.sharedBy}`}
+                    </p>
+                  </div>
+                  <div className="flex items-center gap-3 shrink-0">
+                    <button
+                      type="button"
+                      onClick={() =>
+                        void invoke("jump_to_bookmark", { id: bookmark.id }).catch((error) =>
+                          reportError("jump to bookmark", error)
+                        )
+                      }
+                      className="text-xs app-text-accent hover:opacity-80"
+                    >
+                      Jump
+                    </button>
+                    <button
+                      type="button"
+                      onClick={() =>
+                        void invoke("remove_bookmark", { id: bookmark.id }).catch((error) =>
+                          reportError("remove bookmark", error)
+                        )
+                      }
+                      className="text-xs app-text-danger hover:opacity-80"
+                    >
+                      Remove
+                    </button>
+                  </div>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,111 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { MediaDelays, useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+interface DelaysDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+const DELAY_STEP_MS = 50;
+
+export function DelaysDialog({ isOpen, onClose }: DelaysDialogProps) {
+  const filename = useSyncplayStore((state) => state.player.filename);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [audioMs, setAudioMs] = useState(0);
+  const [subMs, setSubMs] = useState(0);
+
+  useEffect(() => {
+    if (!isOpen || !filename) {
+      setAudioMs(0);
+      setSubMs(0);
+      return;
+    }
+    invoke<MediaDelays>("get_delays")
+      .then((delays) => {
+        setAudioMs(delays.audioMs);
+        setSubMs(delays.subMs);
+      })
+      .catch((error) => console.error("Failed to load delays:", error));
+  }, [isOpen, filename]);
+
+  const applyDelay = async (command: "set_audio_delay" | "set_sub_delay", ms: number) => {
+    try {
+      const delays = await invoke<MediaDelays>(command, { ms: Math.round(ms) });
+      setAudioMs(delays.audioMs);
+      setSubMs(delays.subMs);
+    } catch (error) {
+      const message =
+        typeof error === "string"
+          ? error
+          : (error as { message?: string })?.message || "Unknown error";
+      addNotification({
+        type: "error",
+        message: `Failed to set delay: ${message}`,
+      });
+    }
+  };
+
+  if (!isOpen) return null;
+
+  const rows = [
+    { label: "Audio delay", value: audioMs, setValue: setAudioMs, command: "set_audio_delay" },
+    { label: "Subtitle delay", value: subMs, setValue: setSubMs, command: "set_sub_delay" },
+  ] as const;
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-md shadow-xl">
+        <div className="flex flex-wrap items-center justify-between gap-4 mb-4">
+          <div>
+            <h2 className="text-xl font-bold">Delays</h2>
+            <p className="text-xs app-text-muted truncate">{filename ?? "No file open"}</p>
+          </div>
+          <button onClick={onClose} className="btn-neutral px-3 py-2 rounded-md text-sm">
+            Close
+          </button>
+        </div>
+
+        {filename ? (
+          <div className="space-y-3">
+            {rows.map((row) => (
+              <div key={row.command} className="flex items-center gap-2">
+                <span className="text-sm w-32">{row.label}</span>
+                <button
+                  type="button"
+                  onClick={() => void applyDelay(row.command, row.value - DELAY_STEP_MS)}
+                  className="btn-neutral px-2 py-1 rounded text-sm"
+                >
+                  -
+                </button>
+                <input
+                  type="number"
+                  step={DELAY_STEP_MS}
+                  value={row.value}
+                  onChange={(e) => row.setValue(Number(e.target.value))}
+                  onBlur={() => void applyDelay(row.command, row.value)}
+                  className="w-24 app-input px-2 py-1 rounded text-sm"
+                  aria-label={`${row.label} (ms)`}
+                />
+                <button
+                  type="button"
+                  onClick={() => void applyDelay(row.command, row.value + DELAY_STEP_MS)}
+                  className="btn-neutral px-2 py-1 rounded text-sm"
+                >
+                  +
+                </button>
+                <span className="text-xs app-text-muted">ms</span>
+              </div>
+            ))}
+            <p className="text-xs app-text-muted">
+              Positive values play audio or show subtitles later. Offsets are remembered per file.
+            </p>
+          </div>
+        ) : (
+          <p className="text-sm app-text-muted">Open a file to adjust its delays.</p>
+        )}
+      </div>
+    </div>
+  );
+}
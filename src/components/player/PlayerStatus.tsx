@@ -1,7 +1,31 @@
-import { useSyncplayStore } from "../../store";
+import { invoke } from "@tauri-apps/api/core";
+import { LuBell, LuBellOff, LuChevronLeft, LuChevronRight } from "react-icons/lu";
+import { PlayerHealthStatus, SegmentKind, useSyncplayStore } from "../../store";
+
+const SKIP_LABELS: Record<SegmentKind, string> = {
+  intro: "Skip intro",
+  outro: "Skip outro",
+  preview: "Skip preview",
+};
+
+const HEALTH_LABELS: Record<PlayerHealthStatus, string> = {
+  healthy: "Player",
+  warning: "Player not responding",
+  polling: "Player not responding",
+  restarting: "Restarting player",
+};
 
 export function PlayerStatus() {
   const connection = useSyncplayStore((state) => state.connection);
+  const activeSegment = useSyncplayStore((state) => state.activeSegment);
+  const syncDetached = useSyncplayStore((state) => state.syncDetached);
+  const doNotDisturb = useSyncplayStore((state) => state.doNotDisturb);
+  const playerHealth = useSyncplayStore((state) => state.playerHealth);
+  const buffering = useSyncplayStore((state) => state.buffering);
+
+  const runCommand = (command: string) => {
+    void invoke(command).catch((error) => console.error(`Failed to run ${command}:`, error));
+  };
 
   if (!connection.connected) {
     return (
@@ -13,6 +37,90 @@ export function PlayerStatus() {
 
   return (
     <div className="flex items-center gap-4 text-sm">
+      {activeSegment && (
+        <button
+          onClick={() =>
+            void invoke("skip_segment", { kind: activeSegment.kind }).catch((error) =>
+              console.error("Failed to skip segment:", error)
+            )
+          }
+          className="btn-primary px-3 py-1 rounded-md text-xs"
+        >
+          {SKIP_LABELS[activeSegment.kind]}
+        </button>
+      )}
+      <div className="flex items-center gap-1">
+        <button
+          onClick={() => runCommand("frame_back_step")}
+          className="btn-neutral app-icon-button"
+          aria-label="Previous frame"
+        >
+          <LuChevronLeft className="app-icon" />
+        </button>
+        <button
+          onClick={() => runCommand("frame_step")}
+          className="btn-neutral app-icon-button"
+          aria-label="Next frame"
+        >
+          <LuChevronRight className="app-icon" />
+        </button>
+        <button
+          onClick={() =>
+            void invoke("set_dnd", { enabled: !doNotDisturb }).catch((error) =>
+              console.error("Failed to toggle do not disturb:", error)
+            )
+          }
+          className="btn-neutral app-icon-button"
+          aria-label={doNotDisturb ? "Turn off do not disturb" : "Do not disturb"}
+          title={
+            doNotDisturb
+              ? "Do not disturb is on: only errors show in the player"
+              : "Hide chat and notices in the player; sync keeps working"
+          }
+        >
+          {doNotDisturb ? <LuBellOff className="app-icon" /> : <LuBell className="app-icon" />}
+        </button>
+      </div>
+      {syncDetached && (
+        <button
+          onClick={() => runCommand("resync_playback")}
+          className="btn-primary px-3 py-1 rounded-md text-xs"
+        >
+          Resync with room
+        </button>
+      )}
+      {playerHealth && (
+        <span
+          className={
+            playerHealth.status === "healthy"
+              ? "app-text-muted text-xs"
+              : "app-text-warning text-xs"
+          }
+          title={
+            playerHealth.averageLatencyMs !== null
+              ? `Average ${playerHealth.averageLatencyMs} ms, max ${playerHealth.maxLatencyMs} ms`
+              : undefined
+          }
+        >
+          {HEALTH_LABELS[playerHealth.status]}
+          {playerHealth.status === "healthy"
+            ? playerHealth.latencyMs !== null && ` ${playerHealth.latencyMs} ms`
+            : ` (${playerHealth.unresponsiveSecs}s)`}
+        </span>
+      )}
+      {buffering && (
+        <span
+          className="app-text-warning text-xs"
+          title={
+            buffering.cacheDuration !== null
+              ? `${buffering.cacheDuration.toFixed(1)}s buffered ahead`
+              : undefined
+          }
+        >
+          Buffering
+          {buffering.percent !== null && ` ${Math.round(buffering.percent)}%`}
+        </span>
+      )}
       {/* Server info */}
       {connection.server && (
         <div className="flex items-center gap-2 ml-auto">
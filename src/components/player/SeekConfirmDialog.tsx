@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+const formatPosition = (position: number) => {
+  const totalSeconds = Math.floor(position);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+  const time = `${minutes.toString().padStart(2, "0")}:${seconds.toString().padStart(2, "0")}`;
+  return hours > 0 ? `${hours}:${time}` : time;
+};
+
+// Asks before a large local seek moves the whole room
+export function SeekConfirmDialog() {
+  const request = useSyncplayStore((state) => state.seekConfirmation);
+  const setRequest = useSyncplayStore((state) => state.setSeekConfirmation);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+
+  if (!request) return null;
+
+  const answer = async (confirm: boolean) => {
+    setRequest(null);
+    try {
+      await invoke("confirm_seek", { confirm });
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to seek: ${error}`,
+      });
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-sm shadow-xl">
+        <h2 className="text-xl font-bold mb-1">Seek the room?</h2>
+        <p className="text-xs app-text-muted mb-4">
+          Jump from {formatPosition(request.from)} to {formatPosition(request.to)} for everyone in
+          the room.
+        </p>
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={() => void answer(false)}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+          >
+            Stay
+          </button>
+          <button
+            onClick={() => void answer(true)}
+            className="btn-primary px-3 py-2 rounded-md text-sm"
+          >
+            Seek
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,169 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Segment, SegmentKind, useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+interface SegmentsDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+export function SegmentsDialog({ isOpen, onClose }: SegmentsDialogProps) {
+  const filename = useSyncplayStore((state) => state.player.filename);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [segments, setSegments] = useState<Segment[]>([]);
+
+  const reportError = (action: string, error: unknown) => {
+    const message =
+      typeof error === "string"
+        ? error
+        : (error as { message?: string })?.message || "Unknown error";
+    addNotification({
+      type: "error",
+      message: `Failed to ${action}: ${message}`,
+    });
+  };
+
+  useEffect(() => {
+    if (!isOpen || !filename) {
+      setSegments([]);
+      return;
+    }
+    invoke<Segment[]>("get_segments")
+      .then(setSegments)
+      .catch((error) => console.error("Failed to load segments:", error));
+  }, [isOpen, filename]);
+
+  const updateSegment = (index: number, update: Partial<Segment>) => {
+    setSegments((current) =>
+      current.map((segment, i) => (i === index ? { ...segment, ...update } : segment))
+    );
+  };
+
+  const saveSegments = async () => {
+    try {
+      const saved = await invoke<Segment[]>("set_segments", { segments });
+      setSegments(saved);
+      addNotification({
+        type: "success",
+        message: "Segments saved",
+      });
+    } catch (error) {
+      reportError("save segments", error);
+    }
+  };
+
+  const importSegments = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Segments", extensions: ["json", "edl", "txt"] }],
+    });
+    if (typeof selected !== "string") return;
+    try {
+      const count = await invoke<number>("import_segments", { path: selected });
+      setSegments(await invoke<Segment[]>("get_segments"));
+      addNotification({
+        type: "success",
+        message: `Imported ${count} segments`,
+      });
+    } catch (error) {
+      reportError("import segments", error);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-2xl max-h-[80vh] overflow-auto shadow-xl">
+        <div className="flex flex-wrap items-center justify-between gap-4 mb-4">
+          <div>
+            <h2 className="text-xl font-bold">Intro & Outro</h2>
+            <p className="text-xs app-text-muted truncate">{filename ?? "No file open"}</p>
+          </div>
+          <button onClick={onClose} className="btn-neutral px-3 py-2 rounded-md text-sm">
+            Close
+          </button>
+        </div>
+
+        {filename ? (
+          <div className="space-y-3">
+            {segments.length === 0 && (
+              <p className="text-xs app-text-muted">No segments for this file.</p>
+            )}
+            {segments.map((segment, index) => (
+              <div
+                key={index}
+                className="flex items-center gap-2 app-panel-muted px-3 py-2 rounded"
+              >
+                <select
+                  value={segment.kind}
+                  onChange={(e) => updateSegment(index, { kind: e.target.value as SegmentKind })}
+                  className="app-input px-2 py-1 rounded text-sm"
+                >
+                  <option value="intro">Intro</option>
+                  <option value="outro">Outro</option>
+                  <option value="preview">Preview</option>
+                </select>
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={segment.start}
+                  onChange={(e) => updateSegment(index, { start: Number(e.target.value) })}
+                  className="w-24 app-input px-2 py-1 rounded text-sm"
+                  aria-label="Start (seconds)"
+                />
+                <span className="text-xs app-text-muted">to</span>
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={segment.end}
+                  onChange={(e) => updateSegment(index, { end: Number(e.target.value) })}
+                  className="w-24 app-input px-2 py-1 rounded text-sm"
+                  aria-label="End (seconds)"
+                />
+                <button
+                  type="button"
+                  onClick={() => setSegments((current) => current.filter((_, i) => i !== index))}
+                  className="ml-auto text-xs app-text-danger hover:opacity-80"
+                >
+                  Remove
+                </button>
+              </div>
+            ))}
+            <div className="flex flex-wrap gap-2">
+              <button
+                type="button"
+                onClick={() =>
+                  setSegments((current) => [...current, { kind: "intro", start: 0, end: 90 }])
+                }
+                className="btn-neutral px-3 py-2 rounded text-sm"
+              >
+                Add segment
+              </button>
+              <button
+                type="button"
+                onClick={() => void importSegments()}
+                className="btn-neutral px-3 py-2 rounded text-sm"
+              >
+                Import JSON/EDL
+              </button>
+              <button
+                type="button"
+                onClick={() => void saveSegments()}
+                className="btn-primary px-3 py-2 rounded text-sm ml-auto"
+              >
+                Save
+              </button>
+            </div>
+          </div>
+        ) : (
+          <p className="text-sm app-text-muted">Open a file to edit its segments.</p>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -3,6 +3,7 @@ import {
   LuChevronLeft,
   LuChevronRight,
   LuFolder,
+  LuHistory,
   LuListMusic,
   LuPlay,
   LuPlus,
@@ -21,6 +22,7 @@ import { SyncplayConfig } from "../../types/config";
 import { useCallback, useEffect, useRef, useState, type DragEvent } from "react";
 import { createPortal } from "react-dom";
 import { MediaDirectoriesDialog } from "./MediaDirectoriesDialog";
+import { RecentFilesDialog } from "./RecentFilesDialog";
 import { TrustedDomainsDialog } from "./TrustedDomainsDialog";
 
 interface PlaylistItemStatus {
@@ -40,6 +42,7 @@ export function PlaylistPanel() {
   const addNotification = useNotificationStore((state) => state.addNotification);
   const [showMediaDirectories, setShowMediaDirectories] = useState(false);
   const [showTrustedDomains, setShowTrustedDomains] = useState(false);
+  const [showRecentFiles, setShowRecentFiles] = useState(false);
   const [availability, setAvailability] = useState<PlaylistItemStatus[]>([]);
   const availabilityRef = useRef<PlaylistItemStatus[]>([]);
   const playlistContainerRef = useRef<HTMLDivElement | null>(null);
@@ -241,9 +244,13 @@ export function PlaylistPanel() {
 
   const formatTime = (seconds: number | null) => {
     if (seconds === null) return "--:--";
-    const mins = Math.floor(seconds / 60);
-    const secs = Math.floor(seconds % 60);
-    return `${mins}:${secs.toString().padStart(2, "0")}`;
+    const hours = Math.floor(seconds / 3600);
+    const mins = Math.floor((seconds % 3600) / 60);
+    const secs = Math.floor(seconds % 60).toString().padStart(2, "0");
+    if (hours > 0 || config?.user.always_show_hours) {
+      return `${hours}:${mins.toString().padStart(2, "0")}:${secs}`;
+    }
+    return `${mins}:${secs}`;
   };
 
   const formatSpeed = (speed: number | null) => {
@@ -669,6 +676,13 @@ export function PlaylistPanel() {
               </button>
             </div>
             <div className="flex items-center gap-2">
+              <button
+                onClick={() => setShowRecentFiles(true)}
+                className="btn-neutral app-icon-button"
+                aria-label="Recently played"
+              >
+                <LuHistory className="app-icon" />
+              </button>
               <button
                 onClick={() => setShowTrustedDomains(true)}
                 className="btn-neutral app-icon-button"
@@ -928,6 +942,7 @@ export function PlaylistPanel() {
         isOpen={showTrustedDomains}
         onClose={() => setShowTrustedDomains(false)}
       />
+      <RecentFilesDialog isOpen={showRecentFiles} onClose={() => setShowRecentFiles(false)} />
     </div>
   );
 }
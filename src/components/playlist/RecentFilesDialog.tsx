@@ -0,0 +1,97 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+interface RecentFilesDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+const formatPlayedAt = (seconds: number) =>
+  seconds > 0 ? new Date(seconds * 1000).toLocaleString() : "Unknown";
+
+export function RecentFilesDialog({ isOpen, onClose }: RecentFilesDialogProps) {
+  const config = useSyncplayStore((state) => state.config);
+  const connection = useSyncplayStore((state) => state.connection);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+
+  if (!isOpen) return null;
+
+  const recentFiles = config?.recent_files ?? [];
+
+  const replay = async (name: string) => {
+    try {
+      await invoke("replay_recent_file", { name });
+      onClose();
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to replay ${name}: ${error}`,
+      });
+    }
+  };
+
+  const remove = async (name: string) => {
+    try {
+      await invoke("remove_recent_file", { name });
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: "Failed to update recently played files",
+      });
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-2xl max-h-[80vh] overflow-auto shadow-xl">
+        <div className="flex flex-wrap items-center justify-between gap-4 mb-4">
+          <div>
+            <h2 className="text-xl font-bold">Recently Played</h2>
+            <p className="text-xs app-text-muted">Files played in synced sessions.</p>
+          </div>
+          <button onClick={onClose} className="btn-neutral px-3 py-2 rounded-md text-sm">
+            Close
+          </button>
+        </div>
+
+        {recentFiles.length === 0 ? (
+          <p className="text-xs app-text-muted">No files played yet.</p>
+        ) : (
+          <div className="space-y-2">
+            {recentFiles.map((file) => (
+              <div
+                key={file.name}
+                className="flex items-center justify-between gap-3 app-panel-muted px-3 py-2 rounded"
+              >
+                <div className="min-w-0">
+                  <div className="text-sm truncate">{file.name}</div>
+                  <div className="text-xs app-text-muted truncate">
+                    {file.room} · {formatPlayedAt(file.played_at)}
+                  </div>
+                </div>
+                <div className="flex items-center gap-3 shrink-0">
+                  <button
+                    type="button"
+                    onClick={() => replay(file.name)}
+                    disabled={!connection.connected}
+                    className="btn-primary px-3 py-1 rounded text-xs disabled:opacity-60 disabled:cursor-not-allowed"
+                  >
+                    Replay with room
+                  </button>
+                  <button
+                    type="button"
+                    onClick={() => remove(file.name)}
+                    className="text-xs app-text-danger hover:opacity-80"
+                  >
+                    Remove
+                  </button>
+                </div>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
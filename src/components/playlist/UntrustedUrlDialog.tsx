@@ -0,0 +1,72 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+// Asks whether to load a URL from a domain that is not trusted yet
+export function UntrustedUrlDialog() {
+  const request = useSyncplayStore((state) => state.untrustedUrl);
+  const setRequest = useSyncplayStore((state) => state.setUntrustedUrl);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [loading, setLoading] = useState(false);
+
+  if (!request) return null;
+
+  const accept = async (mode: "once" | "always") => {
+    setLoading(true);
+    try {
+      await invoke("accept_untrusted_url", { url: request.url, mode });
+      setRequest(null);
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to load ${request.url}: ${error}`,
+      });
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const dismiss = async () => {
+    setRequest(null);
+    try {
+      await invoke("dismiss_untrusted_url");
+    } catch (error) {
+      console.error("Failed to dismiss untrusted URL:", error);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-md shadow-xl">
+        <h2 className="text-xl font-bold mb-1">Untrusted URL</h2>
+        <p className="text-xs app-text-muted mb-3">
+          {request.domain} is not one of your trusted domains.
+        </p>
+        <p className="text-sm break-all app-panel-muted px-3 py-2 rounded">{request.url}</p>
+        <div className="flex justify-end gap-2 mt-4">
+          <button
+            onClick={() => void dismiss()}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={() => void accept("once")}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+            disabled={loading}
+          >
+            Load once
+          </button>
+          <button
+            onClick={() => void accept("always")}
+            className="btn-primary px-3 py-2 rounded-md text-sm"
+            disabled={loading}
+          >
+            Always trust {request.domain}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
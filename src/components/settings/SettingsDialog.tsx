@@ -10,9 +10,23 @@ import {
 import {
   ChatInputPosition,
   ChatOutputMode,
+  FieldError,
+  IdleScreen,
+  MetricsConfig,
+  MpvProfile,
+  OsdOverrides,
+  OsdVerbosity,
+  PauseOnLeave,
+  PlaylistAdvanceWait,
   PrivacyMode,
+  PrivacyPreview,
+  PrivacyRule,
+  RemoteControlConfig,
+  ScriptsConfig,
+  ScriptsStatus,
   SyncplayConfig,
   UnpauseAction,
+  WatchdogPolicy,
 } from "../../types/config";
 
 interface SettingsDialogProps {
@@ -23,6 +37,70 @@ interface SettingsDialogProps {
   onUpdateAvailable?: (version: string | null) => void;
 }
 
+// Per-category exceptions to the OSD verbosity level
+const OSD_CATEGORIES: { key: keyof OsdOverrides; label: string }[] = [
+  { key: "errors", label: "Errors" },
+  { key: "warnings", label: "Room warnings" },
+  { key: "status", label: "Your own status" },
+  { key: "same_room", label: "Same room events" },
+  { key: "slowdown", label: "Slowdown notices" },
+  { key: "different_room", label: "Other room events" },
+  { key: "non_controller", label: "Non-operator events" },
+];
+
+const DEFAULT_MPV_PROFILE: MpvProfile = {
+  enabled: true,
+  hr_seek: true,
+  keep_open: true,
+  cache: false,
+  cache_secs: 30,
+  cache_pause_wait: 1,
+};
+
+const DEFAULT_WATCHDOG_POLICY: WatchdogPolicy = {
+  enabled: true,
+  warn_after_secs: 5,
+  poll_fallback_after_secs: 15,
+  restart: true,
+  restart_after_secs: 60,
+};
+
+const DEFAULT_REMOTE_CONTROL: RemoteControlConfig = {
+  enabled: false,
+  port: 8996,
+  token: "",
+};
+
+const DEFAULT_METRICS: MetricsConfig = {
+  enabled: false,
+  port: 9464,
+};
+
+const DEFAULT_SCRIPTS: ScriptsConfig = {
+  enabled: false,
+  directory: null,
+};
+
+const generateRemoteToken = () =>
+  Array.from(crypto.getRandomValues(new Uint8Array(16)), (byte) =>
+    byte.toString(16).padStart(2, "0"),
+  ).join("");
+
+const WATCHDOG_STEPS: {
+  key: "warn_after_secs" | "poll_fallback_after_secs" | "restart_after_secs";
+  label: string;
+}[] = [
+  { key: "warn_after_secs", label: "Warn after (seconds)" },
+  { key: "poll_fallback_after_secs", label: "Poll after (seconds)" },
+  { key: "restart_after_secs", label: "Restart after (seconds)" },
+];
+
+const MPV_PROFILE_TOGGLES: { key: "hr_seek" | "keep_open" | "cache"; label: string }[] = [
+  { key: "hr_seek", label: "Precise seeking (hr-seek)" },
+  { key: "keep_open", label: "Stay on the last frame at the end (keep-open)" },
+  { key: "cache", label: "Read ahead of playback (cache)" },
+];
+
 type SettingsTab = "sync" | "ready" | "privacy" | "chat" | "osd" | "misc";
 type UpdateStatus =
   | "idle"
@@ -58,12 +136,85 @@ const unpauseOptions: Array<{ label: string; value: UnpauseAction }> = [
   { label: "Always", value: "always" },
 ];
 
+const pauseOnLeaveOptions: Array<{ label: string; value: PauseOnLeave }> = [
+  { label: "Never", value: "never" },
+  { label: "When anyone leaves", value: "anyone" },
+  { label: "When an operator leaves", value: "controllers_only" },
+  { label: "When the room gets smaller than", value: "below_min_users" },
+];
+
+const playlistAdvanceWaitOptions: Array<{ label: string; value: PlaylistAdvanceWait }> = [
+  { label: "Don't wait", value: "off" },
+  { label: "Until ready users reach the end", value: "ready_users" },
+  { label: "Until operators reach the end", value: "controllers" },
+];
+
+const EMPTY_PRIVACY_RULE: PrivacyRule = {
+  room: "",
+  file: "",
+  public_servers_only: false,
+  filename_mode: null,
+  filesize_mode: null,
+};
+
+const describePrivacy = (mode: PrivacyMode, rule: number | null) => {
+  const label = privacyOptions.find((option) => option.value === mode)?.label ?? mode;
+  return rule === null ? label : `${label}, rule ${rule + 1}`;
+};
+
+const idleScreenOptions: Array<{ label: string; value: IdleScreen }> = [
+  { label: "Room and server info (mpv only)", value: "generated" },
+  { label: "Syncplay image", value: "image" },
+];
+
+// Older configs only have the on/off flag
+const effectivePauseOnLeave = (config: SyncplayConfig): PauseOnLeave => {
+  const policy = config.user.pause_on_leave_policy ?? "never";
+  return policy === "never" && config.user.pause_on_leave ? "anyone" : policy;
+};
+
 const chatInputPositions: Array<{ label: string; value: ChatInputPosition }> = [
   { label: "Top", value: "top" },
   { label: "Middle", value: "middle" },
   { label: "Bottom", value: "bottom" },
 ];
 
+// Fields whose validation errors show next to their input
+const inlineErrorFields = new Set([
+  "user.seek_threshold_rewind",
+  "user.seek_threshold_fastforward",
+  "user.slowdown_threshold",
+  "user.slowdown_reset_threshold",
+  "user.slowdown_rate",
+  "user.slowdown_micro_seek_step",
+  "user.large_seek_threshold",
+  "user.vote_majority_percent",
+  "user.vote_timeout_secs",
+  "user.privacy_rules",
+  "user.autoplay_min_users",
+  "user.chat_max_lines",
+  "user.osd_duration",
+  "player.restart_grace_seconds",
+  "player.stall_timeout_secs",
+  "player.mpv_profile.cache_secs",
+  "player.mpv_profile.cache_pause_wait",
+  "player.watchdog",
+  "server.bind_address",
+  "remote.port",
+  "remote.token",
+  "metrics.port",
+]);
+
+function FieldErrorText({ errors, field }: { errors: FieldError[]; field: string }) {
+  const messages = errors.filter((error) => error.field === field);
+  if (messages.length === 0) return null;
+  return (
+    <p className="text-xs app-text-danger mt-1">
+      {messages.map((error) => error.message).join(" ")}
+    </p>
+  );
+}
+
 const chatOutputModes: Array<{ label: string; value: ChatOutputMode }> = [
   { label: "Chatroom", value: "chatroom" },
   { label: "Scrolling", value: "scrolling" },
@@ -79,6 +230,7 @@ export function SettingsDialog({
   const [config, setConfig] = useState<SyncplayConfig | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [fieldErrors, setFieldErrors] = useState<FieldError[]>([]);
   const [activeTab, setActiveTab] = useState<SettingsTab>(initialTab ?? "sync");
   const saveTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const skipAutoSaveRef = useRef(true);
@@ -92,6 +244,80 @@ export function SettingsDialog({
   const updateProgressRef = useRef<{ downloaded: number; total?: number }>({
     downloaded: 0,
   });
+  const [privacyPreviewInput, setPrivacyPreviewInput] = useState("");
+  const [privacyPreview, setPrivacyPreview] = useState<PrivacyPreview | null>(null);
+  const [scriptsStatus, setScriptsStatus] = useState<ScriptsStatus | null>(null);
+  const [scriptsError, setScriptsError] = useState<string | null>(null);
+  const mpvProfile = config?.player.mpv_profile ?? DEFAULT_MPV_PROFILE;
+
+  const setMpvProfile = (patch: Partial<MpvProfile>) => {
+    if (!config) return;
+    setConfig({
+      ...config,
+      player: { ...config.player, mpv_profile: { ...mpvProfile, ...patch } },
+    });
+  };
+
+  const remote = config?.remote ?? DEFAULT_REMOTE_CONTROL;
+
+  const setRemote = (patch: Partial<RemoteControlConfig>) => {
+    if (!config) return;
+    setConfig({ ...config, remote: { ...remote, ...patch } });
+  };
+
+  const metrics = config?.metrics ?? DEFAULT_METRICS;
+
+  const setMetrics = (patch: Partial<MetricsConfig>) => {
+    if (!config) return;
+    setConfig({ ...config, metrics: { ...metrics, ...patch } });
+  };
+
+  const scripts = config?.scripts ?? DEFAULT_SCRIPTS;
+
+  const setScripts = (patch: Partial<ScriptsConfig>) => {
+    if (!config) return;
+    setConfig({ ...config, scripts: { ...scripts, ...patch } });
+  };
+
+  const reloadScripts = async () => {
+    setScriptsError(null);
+    try {
+      setScriptsStatus(await invoke<ScriptsStatus>("reload_scripts"));
+    } catch (error) {
+      setScriptsError(error as string);
+    }
+  };
+
+  const privacyRules = config?.user.privacy_rules ?? [];
+
+  const setPrivacyRules = (rules: PrivacyRule[]) => {
+    if (!config) return;
+    setConfig({ ...config, user: { ...config.user, privacy_rules: rules } });
+  };
+
+  const updatePrivacyRule = (index: number, patch: Partial<PrivacyRule>) => {
+    setPrivacyRules(privacyRules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)));
+  };
+
+  const previewPrivacy = async () => {
+    try {
+      setPrivacyPreview(
+        await invoke<PrivacyPreview>("preview_privacy", { filename: privacyPreviewInput })
+      );
+    } catch (error) {
+      console.error("Failed to preview privacy:", error);
+    }
+  };
+
+  const watchdog = config?.player.watchdog ?? DEFAULT_WATCHDOG_POLICY;
+
+  const setWatchdog = (patch: Partial<WatchdogPolicy>) => {
+    if (!config) return;
+    setConfig({
+      ...config,
+      player: { ...config.player, watchdog: { ...watchdog, ...patch } },
+    });
+  };
 
   useEffect(() => {
     if (!isOpen) {
@@ -111,6 +337,13 @@ export function SettingsDialog({
     setActiveTab(initialTab ?? "sync");
   }, [isOpen, initialTab]);
 
+  useEffect(() => {
+    if (!isOpen || activeTab !== "misc") return;
+    invoke<ScriptsStatus>("get_scripts")
+      .then(setScriptsStatus)
+      .catch((error) => console.error("Failed to load scripts:", error));
+  }, [isOpen, activeTab]);
+
   const loadConfig = async () => {
     setLoading(true);
     setError(null);
@@ -236,13 +469,17 @@ export function SettingsDialog({
     }
 
     saveTimeoutRef.current = setTimeout(async () => {
+      const candidate = {
+        ...config,
+        user: { ...config.user, theme: getAppliedTheme() },
+      };
       try {
-        await invoke("update_config", {
-          config: {
-            ...config,
-            user: { ...config.user, theme: getAppliedTheme() },
-          },
-        });
+        setFieldErrors(await invoke<FieldError[]>("validate_config", { candidate }));
+      } catch (err) {
+        console.warn("Failed to validate settings", err);
+      }
+      try {
+        await invoke("update_config", { config: candidate });
         setError(null);
       } catch (err) {
         setError(err as string);
@@ -326,6 +563,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.seek_threshold_rewind" />
                 </div>
 
                 <div>
@@ -347,6 +585,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.seek_threshold_fastforward" />
                 </div>
 
                 <div>
@@ -368,6 +607,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.slowdown_threshold" />
                 </div>
 
                 <div>
@@ -389,6 +629,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.slowdown_reset_threshold" />
                 </div>
 
                 <div>
@@ -410,6 +651,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.slowdown_rate" />
                 </div>
 
                 <div className="flex flex-col gap-2">
@@ -469,7 +711,159 @@ export function SettingsDialog({
                     />
                     Do not slow down with me
                   </label>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.slowdown_emulation ?? true}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, slowdown_emulation: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Emulate slowdown with small seeks on MPC
+                  </label>
+                </div>
+
+                {(config.user.slowdown_emulation ?? true) && (
+                  <div>
+                    <label className="block text-sm font-medium mb-1">
+                      Emulated Slowdown Step (seconds)
+                    </label>
+                    <input
+                      type="number"
+                      step="0.1"
+                      min="0.1"
+                      max="2"
+                      value={config.user.slowdown_micro_seek_step ?? 0.5}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: {
+                            ...config.user,
+                            slowdown_micro_seek_step: parseFloat(e.target.value),
+                          },
+                        })
+                      }
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <p className="text-xs app-text-muted mt-1">
+                      MPC cannot change its speed, so it steps back by up to this much per update
+                      instead.
+                    </p>
+                    <FieldErrorText errors={fieldErrors} field="user.slowdown_micro_seek_step" />
+                  </div>
+                )}
+
+                <div>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.confirm_large_seeks ?? false}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, confirm_large_seeks: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Confirm large seeks before the room follows
+                  </label>
+                </div>
+
+                {(config.user.confirm_large_seeks ?? false) && (
+                  <div>
+                    <label className="block text-sm font-medium mb-1">
+                      Large Seek Threshold (seconds)
+                    </label>
+                    <input
+                      type="number"
+                      step="1"
+                      min="10"
+                      value={config.user.large_seek_threshold ?? 600}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: {
+                            ...config.user,
+                            large_seek_threshold: parseFloat(e.target.value),
+                          },
+                        })
+                      }
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText errors={fieldErrors} field="user.large_seek_threshold" />
+                  </div>
+                )}
+
+                <div>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.room_votes ?? true}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, room_votes: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Count room votes (!vote pause, !vote seek 12:30, !vote skip)
+                  </label>
                 </div>
+
+                {(config.user.room_votes ?? true) && (
+                  <div className="space-y-4">
+                    <div>
+                      <label className="block text-sm font-medium mb-1">Majority (%)</label>
+                      <input
+                        type="number"
+                        step="1"
+                        min="1"
+                        max="99"
+                        value={config.user.vote_majority_percent ?? 50}
+                        onChange={(e) =>
+                          setConfig({
+                            ...config,
+                            user: {
+                              ...config.user,
+                              vote_majority_percent: parseInt(e.target.value, 10),
+                            },
+                          })
+                        }
+                        className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                      />
+                      <FieldErrorText errors={fieldErrors} field="user.vote_majority_percent" />
+                    </div>
+                    <div>
+                      <label className="block text-sm font-medium mb-1">
+                        Votes Stay Open (seconds)
+                      </label>
+                      <input
+                        type="number"
+                        step="1"
+                        min="10"
+                        max="600"
+                        value={config.user.vote_timeout_secs ?? 60}
+                        onChange={(e) =>
+                          setConfig({
+                            ...config,
+                            user: {
+                              ...config.user,
+                              vote_timeout_secs: parseInt(e.target.value, 10),
+                            },
+                          })
+                        }
+                        className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                      />
+                      <FieldErrorText errors={fieldErrors} field="user.vote_timeout_secs" />
+                    </div>
+                  </div>
+                )}
               </div>
             )}
 
@@ -493,19 +887,87 @@ export function SettingsDialog({
                   <label className="flex items-center gap-2 text-sm">
                     <input
                       type="checkbox"
-                      checked={config.user.pause_on_leave}
+                      checked={config.user.segment_skip_prompt ?? true}
                       onChange={(e) =>
                         setConfig({
                           ...config,
-                          user: { ...config.user, pause_on_leave: e.target.checked },
+                          user: { ...config.user, segment_skip_prompt: e.target.checked },
                         })
                       }
                       className="w-4 h-4"
                     />
-                    Pause when someone leaves the room
+                    Offer to skip known intros and outros
                   </label>
                 </div>
 
+                <div>
+                  <label className="block text-sm font-medium mb-1">Pause when leaving</label>
+                  <select
+                    value={effectivePauseOnLeave(config)}
+                    onChange={(e) => {
+                      const policy = e.target.value as PauseOnLeave;
+                      setConfig({
+                        ...config,
+                        user: {
+                          ...config.user,
+                          pause_on_leave: policy !== "never",
+                          pause_on_leave_policy: policy,
+                        },
+                      });
+                    }}
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  >
+                    {pauseOnLeaveOptions.map((option) => (
+                      <option key={option.value} value={option.value}>
+                        {option.label}
+                      </option>
+                    ))}
+                  </select>
+                  {effectivePauseOnLeave(config) === "below_min_users" && (
+                    <input
+                      type="number"
+                      min={1}
+                      value={config.user.pause_on_leave_min_users ?? 2}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: {
+                            ...config.user,
+                            pause_on_leave_min_users: Math.max(
+                              1,
+                              parseInt(e.target.value, 10) || 1,
+                            ),
+                          },
+                        })
+                      }
+                      className="w-full app-input px-3 py-2 rounded mt-2 focus:outline-none focus:border-blue-500"
+                    />
+                  )}
+                </div>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">Play next playlist file</label>
+                  <select
+                    value={config.user.playlist_advance_wait ?? "off"}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        user: {
+                          ...config.user,
+                          playlist_advance_wait: e.target.value as PlaylistAdvanceWait,
+                        },
+                      })
+                    }
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  >
+                    {playlistAdvanceWaitOptions.map((option) => (
+                      <option key={option.value} value={option.value}>
+                        {option.label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+
                 <div>
                   <label className="block text-sm font-medium mb-1">Unpause behavior</label>
                   <select
@@ -576,6 +1038,7 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.autoplay_min_users" />
                   <p className="text-xs app-text-muted mt-1">Use -1 to disable minimum.</p>
                 </div>
               </div>
@@ -628,6 +1091,182 @@ export function SettingsDialog({
                     ))}
                   </select>
                 </div>
+
+                <div className="space-y-2">
+                  <div className="flex items-center justify-between gap-2">
+                    <label className="block text-sm font-medium">Privacy exceptions</label>
+                    <button
+                      type="button"
+                      onClick={() => setPrivacyRules([...privacyRules, EMPTY_PRIVACY_RULE])}
+                      className="btn-neutral px-3 py-1 rounded text-xs"
+                    >
+                      Add rule
+                    </button>
+                  </div>
+                  <p className="text-xs app-text-muted">
+                    The first rule matching the room and file decides each mode. Use * as a
+                    wildcard; leave a pattern empty to match everything.
+                  </p>
+                  {privacyRules.map((rule, index) => (
+                    <div key={index} className="app-panel-muted rounded p-3 space-y-2">
+                      <div className="grid grid-cols-1 md:grid-cols-2 gap-2">
+                        <input
+                          type="text"
+                          value={rule.room}
+                          placeholder="Room"
+                          onChange={(e) => updatePrivacyRule(index, { room: e.target.value })}
+                          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                        />
+                        <input
+                          type="text"
+                          value={rule.file}
+                          placeholder="File, e.g. *.mkv"
+                          onChange={(e) => updatePrivacyRule(index, { file: e.target.value })}
+                          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                        />
+                        <select
+                          value={rule.filename_mode ?? ""}
+                          onChange={(e) =>
+                            updatePrivacyRule(index, {
+                              filename_mode: (e.target.value || null) as PrivacyMode | null,
+                            })
+                          }
+                          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                        >
+                          <option value="">Filename: keep default</option>
+                          {privacyOptions.map((option) => (
+                            <option key={option.value} value={option.value}>
+                              Filename: {option.label}
+                            </option>
+                          ))}
+                        </select>
+                        <select
+                          value={rule.filesize_mode ?? ""}
+                          onChange={(e) =>
+                            updatePrivacyRule(index, {
+                              filesize_mode: (e.target.value || null) as PrivacyMode | null,
+                            })
+                          }
+                          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                        >
+                          <option value="">Filesize: keep default</option>
+                          {privacyOptions.map((option) => (
+                            <option key={option.value} value={option.value}>
+                              Filesize: {option.label}
+                            </option>
+                          ))}
+                        </select>
+                      </div>
+                      <div className="flex items-center justify-between gap-2">
+                        <label className="flex items-center gap-2 text-sm">
+                          <input
+                            type="checkbox"
+                            checked={rule.public_servers_only}
+                            onChange={(e) =>
+                              updatePrivacyRule(index, { public_servers_only: e.target.checked })
+                            }
+                            className="w-4 h-4"
+                          />
+                          Only on public servers
+                        </label>
+                        <button
+                          type="button"
+                          onClick={() =>
+                            setPrivacyRules(privacyRules.filter((_, i) => i !== index))
+                          }
+                          className="text-xs app-text-danger hover:opacity-80"
+                        >
+                          Remove
+                        </button>
+                      </div>
+                    </div>
+                  ))}
+                  <FieldErrorText errors={fieldErrors} field="user.privacy_rules" />
+                </div>
+
+                <div className="space-y-2">
+                  <label className="block text-sm font-medium">Preview what is sent</label>
+                  <div className="flex gap-2">
+                    <input
+                      type="text"
+                      value={privacyPreviewInput}
+                      placeholder="File name or path"
+                      onChange={(e) => setPrivacyPreviewInput(e.target.value)}
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <button
+                      type="button"
+                      onClick={previewPrivacy}
+                      disabled={!privacyPreviewInput.trim()}
+                      className="btn-neutral px-3 py-2 rounded text-sm disabled:opacity-60"
+                    >
+                      Preview
+                    </button>
+                  </div>
+                  {privacyPreview && (
+                    <div className="text-xs app-text-muted space-y-1">
+                      <div>
+                        Name: {privacyPreview.name ?? "nothing"} (
+                        {describePrivacy(
+                          privacyPreview.privacy.filenameMode,
+                          privacyPreview.privacy.filenameRule
+                        )}
+                        )
+                      </div>
+                      <div>
+                        Size: {privacyPreview.size ?? "unknown"} (
+                        {describePrivacy(
+                          privacyPreview.privacy.filesizeMode,
+                          privacyPreview.privacy.filesizeRule
+                        )}
+                        )
+                      </div>
+                    </div>
+                  )}
+                </div>
+
+                <div>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.session_history ?? false}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, session_history: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Keep a session history
+                  </label>
+                  <p className="text-xs app-text-muted mt-1">
+                    Adds a recap of each session to session-history.jsonl in the app data
+                    directory: watch time, file count, corrections, average ping and chat count,
+                    without names.
+                  </p>
+                </div>
+
+                <div>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.share_utc_offset ?? false}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, share_utc_offset: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Share my local time with the room
+                  </label>
+                  <p className="text-xs app-text-muted mt-1">
+                    Sends your UTC offset when connecting, so others in the user list see your local
+                    time. Only clients that support it show it. Takes effect on the next connection.
+                  </p>
+                </div>
               </div>
             )}
 
@@ -782,11 +1421,39 @@ export function SettingsDialog({
                     />
                     Enable chat output
                   </label>
-                </div>
-
-                <div>
-                  <label className="block text-sm font-medium mb-1">Chat output mode</label>
-                  <select
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.mention_flash_taskbar ?? true}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, mention_flash_taskbar: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Flash the taskbar when mentioned
+                  </label>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.user.mention_sound ?? false}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          user: { ...config.user, mention_sound: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Play a sound when mentioned
+                  </label>
+                </div>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">Chat output mode</label>
+                  <select
                     value={config.user.chat_output_mode}
                     onChange={(e) =>
                       setConfig({
@@ -869,6 +1536,7 @@ export function SettingsDialog({
                       }
                       className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                     />
+                    <FieldErrorText errors={fieldErrors} field="user.chat_max_lines" />
                   </div>
                 </div>
 
@@ -1032,108 +1700,74 @@ export function SettingsDialog({
                     }
                     className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
                   />
+                  <FieldErrorText errors={fieldErrors} field="user.osd_duration" />
+                </div>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">OSD messages</label>
+                  <select
+                    value={config.user.osd_verbosity ?? "important"}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        user: { ...config.user, osd_verbosity: e.target.value as OsdVerbosity },
+                      })
+                    }
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  >
+                    <option value="off">Off</option>
+                    <option value="errors">Errors only</option>
+                    <option value="important">Important</option>
+                    <option value="everything">Everything</option>
+                  </select>
                 </div>
 
                 <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_osd}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_osd: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show OSD
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_osd_warnings}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_osd_warnings: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show OSD warnings
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_slowdown_osd}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_slowdown_osd: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show slowdown OSD
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_same_room_osd}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_same_room_osd: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show same room OSD
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_different_room_osd}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_different_room_osd: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show different room OSD
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_non_controller_osd}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_non_controller_osd: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show non-controller OSD
-                  </label>
-                  <label className="flex items-center gap-2 text-sm">
-                    <input
-                      type="checkbox"
-                      checked={config.user.show_duration_notification}
-                      onChange={(e) =>
-                        setConfig({
-                          ...config,
-                          user: { ...config.user, show_duration_notification: e.target.checked },
-                        })
-                      }
-                      className="w-4 h-4"
-                    />
-                    Show duration notification
-                  </label>
+                  {OSD_CATEGORIES.map(({ key, label }) => {
+                    const override = config.user.osd_overrides?.[key];
+                    return (
+                      <div key={key} className="flex items-center justify-between gap-2 text-sm">
+                        <span>{label}</span>
+                        <select
+                          value={override == null ? "default" : override ? "show" : "hide"}
+                          onChange={(e) =>
+                            setConfig({
+                              ...config,
+                              user: {
+                                ...config.user,
+                                osd_overrides: {
+                                  ...config.user.osd_overrides,
+                                  [key]:
+                                    e.target.value === "default" ? null : e.target.value === "show",
+                                },
+                              },
+                            })
+                          }
+                          className="app-input px-2 py-1 rounded focus:outline-none focus:border-blue-500"
+                        >
+                          <option value="default">Default</option>
+                          <option value="show">Show</option>
+                          <option value="hide">Hide</option>
+                        </select>
+                      </div>
+                    );
+                  })}
                 </div>
+
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={config.user.show_duration_notification}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        user: { ...config.user, show_duration_notification: e.target.checked },
+                      })
+                    }
+                    className="w-4 h-4"
+                  />
+                  Show duration notification
+                </label>
               </div>
             )}
 
@@ -1237,6 +1871,427 @@ export function SettingsDialog({
                   />
                   Enable debug logging
                 </label>
+
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={config.player.auto_restart ?? true}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        player: { ...config.player, auto_restart: e.target.checked },
+                      })
+                    }
+                    className="w-4 h-4"
+                  />
+                  Restart the player if it crashes
+                </label>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">
+                    Player restart delay (seconds)
+                  </label>
+                  <input
+                    type="number"
+                    min={0}
+                    max={60}
+                    value={config.player.restart_grace_seconds ?? 3}
+                    disabled={!(config.player.auto_restart ?? true)}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        player: {
+                          ...config.player,
+                          restart_grace_seconds: parseInt(e.target.value, 10) || 0,
+                        },
+                      })
+                    }
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  />
+                  <FieldErrorText errors={fieldErrors} field="player.restart_grace_seconds" />
+                </div>
+
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={config.player.leave_player_running ?? false}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        player: { ...config.player, leave_player_running: e.target.checked },
+                      })
+                    }
+                    className="w-4 h-4"
+                  />
+                  Leave the player running when quitting the app
+                </label>
+
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={config.player.wait_while_buffering ?? true}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        player: { ...config.player, wait_while_buffering: e.target.checked },
+                      })
+                    }
+                    className="w-4 h-4"
+                  />
+                  Pause the room while a stream rebuffers
+                </label>
+
+                <div>
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.player.flatpak_host_filesystem ?? false}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          player: { ...config.player, flatpak_host_filesystem: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Let Flatpak players read all files
+                  </label>
+                  <p className="text-xs app-text-muted mt-1">
+                    Flatpak players can only read your media directories. Turn this on to give them
+                    read access to the whole filesystem, e.g. for files opened from elsewhere.
+                  </p>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={config.player.stall_detection ?? true}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          player: { ...config.player, stall_detection: e.target.checked },
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Mark me not ready when playback freezes
+                  </label>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Frozen for (seconds)</label>
+                    <input
+                      type="number"
+                      min={2}
+                      max={120}
+                      value={config.player.stall_timeout_secs ?? 5}
+                      disabled={!(config.player.stall_detection ?? true)}
+                      onChange={(e) =>
+                        setConfig({
+                          ...config,
+                          player: {
+                            ...config.player,
+                            stall_timeout_secs: parseInt(e.target.value, 10) || 0,
+                          },
+                        })
+                      }
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText errors={fieldErrors} field="player.stall_timeout_secs" />
+                  </div>
+                </div>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">Idle screen</label>
+                  <select
+                    value={config.player.idle_screen ?? "generated"}
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        player: { ...config.player, idle_screen: e.target.value as IdleScreen },
+                      })
+                    }
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  >
+                    {idleScreenOptions.map((option) => (
+                      <option key={option.value} value={option.value}>
+                        {option.label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={watchdog.enabled}
+                      onChange={(e) => setWatchdog({ enabled: e.target.checked })}
+                      className="w-4 h-4"
+                    />
+                    Watch for a player that stops responding
+                  </label>
+                  <label className="flex items-center gap-2 text-sm pl-6">
+                    <input
+                      type="checkbox"
+                      checked={watchdog.restart}
+                      disabled={!watchdog.enabled}
+                      onChange={(e) => setWatchdog({ restart: e.target.checked })}
+                      className="w-4 h-4"
+                    />
+                    Restart it instead of closing it
+                  </label>
+                  <div className="grid grid-cols-1 md:grid-cols-3 gap-4 pl-6">
+                    {WATCHDOG_STEPS.map(({ key, label }) => (
+                      <div key={key}>
+                        <label className="block text-sm font-medium mb-1">{label}</label>
+                        <input
+                          type="number"
+                          min={1}
+                          value={watchdog[key]}
+                          disabled={!watchdog.enabled}
+                          onChange={(e) =>
+                            setWatchdog({ [key]: parseInt(e.target.value, 10) || 0 })
+                          }
+                          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                        />
+                      </div>
+                    ))}
+                  </div>
+                  <div className="pl-6">
+                    <FieldErrorText errors={fieldErrors} field="player.watchdog" />
+                  </div>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={mpvProfile.enabled}
+                      onChange={(e) => setMpvProfile({ enabled: e.target.checked })}
+                      className="w-4 h-4"
+                    />
+                    Start mpv with sync-friendly defaults
+                  </label>
+                  <p className="text-xs app-text-muted">
+                    Turn off any option your own mpv.conf sets differently. Changes apply the next
+                    time the player starts.
+                  </p>
+                  {MPV_PROFILE_TOGGLES.map(({ key, label }) => (
+                    <label key={key} className="flex items-center gap-2 text-sm pl-6">
+                      <input
+                        type="checkbox"
+                        checked={mpvProfile[key]}
+                        disabled={!mpvProfile.enabled}
+                        onChange={(e) => setMpvProfile({ [key]: e.target.checked })}
+                        className="w-4 h-4"
+                      />
+                      {label}
+                    </label>
+                  ))}
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Cache length (seconds)</label>
+                    <input
+                      type="number"
+                      min={1}
+                      max={3600}
+                      value={mpvProfile.cache_secs}
+                      disabled={!mpvProfile.enabled || !mpvProfile.cache}
+                      onChange={(e) =>
+                        setMpvProfile({ cache_secs: parseInt(e.target.value, 10) || 0 })
+                      }
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText errors={fieldErrors} field="player.mpv_profile.cache_secs" />
+                  </div>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">
+                      Buffer before resuming a stalled stream (seconds)
+                    </label>
+                    <input
+                      type="number"
+                      min={0}
+                      max={60}
+                      step={0.5}
+                      value={mpvProfile.cache_pause_wait}
+                      disabled={!mpvProfile.enabled || !mpvProfile.cache}
+                      onChange={(e) =>
+                        setMpvProfile({ cache_pause_wait: parseFloat(e.target.value) || 0 })
+                      }
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText
+                      errors={fieldErrors}
+                      field="player.mpv_profile.cache_pause_wait"
+                    />
+                  </div>
+                </div>
+
+                <div>
+                  <label className="block text-sm font-medium mb-1">Bind address</label>
+                  <input
+                    type="text"
+                    value={config.server.bind_address ?? ""}
+                    placeholder="Automatic"
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        server: { ...config.server, bind_address: e.target.value.trim() || null },
+                      })
+                    }
+                    className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                  />
+                  <FieldErrorText errors={fieldErrors} field="server.bind_address" />
+                  <p className="text-xs app-text-muted mt-1">
+                    Local IP address to connect from, e.g. to keep Syncplay on or off a VPN.
+                  </p>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={remote.enabled}
+                      onChange={(e) =>
+                        setRemote({
+                          enabled: e.target.checked,
+                          token: remote.token || generateRemoteToken(),
+                        })
+                      }
+                      className="w-4 h-4"
+                    />
+                    Enable remote control
+                  </label>
+                  <p className="text-xs app-text-muted">
+                    Lets stream decks, phones and scripts on this computer pause, seek, set ready
+                    and chat over a WebSocket at ws://127.0.0.1:{remote.port}/?token=…
+                  </p>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Port</label>
+                    <input
+                      type="number"
+                      min={1024}
+                      max={65535}
+                      value={remote.port}
+                      disabled={!remote.enabled}
+                      onChange={(e) => setRemote({ port: parseInt(e.target.value, 10) || 0 })}
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText errors={fieldErrors} field="remote.port" />
+                  </div>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Token</label>
+                    <div className="flex gap-2">
+                      <input
+                        type="text"
+                        value={remote.token}
+                        disabled={!remote.enabled}
+                        onChange={(e) => setRemote({ token: e.target.value.trim() })}
+                        className="flex-1 app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500 font-mono"
+                      />
+                      <button
+                        type="button"
+                        disabled={!remote.enabled}
+                        onClick={() => setRemote({ token: generateRemoteToken() })}
+                        className="btn-neutral px-3 py-1 rounded text-xs"
+                      >
+                        Generate
+                      </button>
+                    </div>
+                    <FieldErrorText errors={fieldErrors} field="remote.token" />
+                  </div>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={metrics.enabled}
+                      onChange={(e) => setMetrics({ enabled: e.target.checked })}
+                      className="w-4 h-4"
+                    />
+                    Expose metrics
+                  </label>
+                  <p className="text-xs app-text-muted">
+                    Reconnects, round trip time, desync corrections and message counts for
+                    Prometheus at http://127.0.0.1:{metrics.port}/metrics
+                  </p>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Port</label>
+                    <input
+                      type="number"
+                      min={1024}
+                      max={65535}
+                      value={metrics.port}
+                      disabled={!metrics.enabled}
+                      onChange={(e) => setMetrics({ port: parseInt(e.target.value, 10) || 0 })}
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                    <FieldErrorText errors={fieldErrors} field="metrics.port" />
+                  </div>
+                </div>
+
+                <div className="space-y-2">
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={scripts.enabled}
+                      onChange={(e) => setScripts({ enabled: e.target.checked })}
+                      className="w-4 h-4"
+                    />
+                    Run user scripts
+                  </label>
+                  <p className="text-xs app-text-muted">
+                    Lua files that react to loaded files, joining users and chat, and can send chat,
+                    set ready and show OSD messages.
+                  </p>
+                  <div className="pl-6">
+                    <label className="block text-sm font-medium mb-1">Scripts directory</label>
+                    <input
+                      type="text"
+                      value={scripts.directory ?? ""}
+                      placeholder={scriptsStatus?.directory ?? "scripts in the config directory"}
+                      disabled={!scripts.enabled}
+                      onChange={(e) => setScripts({ directory: e.target.value.trim() || null })}
+                      className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    />
+                  </div>
+                  {scripts.enabled && (
+                    <div className="pl-6 space-y-1">
+                      <div className="flex items-center justify-between">
+                        <span className="text-sm font-medium">Loaded scripts</span>
+                        <button
+                          type="button"
+                          onClick={() => void reloadScripts()}
+                          className="btn-neutral px-3 py-1 rounded text-xs"
+                        >
+                          Reload
+                        </button>
+                      </div>
+                      {scriptsStatus && scriptsStatus.scripts.length === 0 && (
+                        <p className="text-xs app-text-muted">No scripts found.</p>
+                      )}
+                      {scriptsStatus?.scripts.map((script) => (
+                        <p key={script.name} className="text-xs">
+                          <span className="font-mono">{script.name}</span>
+                          {script.error && (
+                            <span className="app-text-danger"> — {script.error}</span>
+                          )}
+                        </p>
+                      ))}
+                      {scriptsError && <p className="text-xs app-text-danger">{scriptsError}</p>}
+                    </div>
+                  )}
+                </div>
+              </div>
+            )}
+
+            {fieldErrors.some((fieldError) => !inlineErrorFields.has(fieldError.field)) && (
+              <div className="mt-4 app-alert app-alert-danger px-4 py-2 text-sm space-y-1">
+                {fieldErrors
+                  .filter((fieldError) => !inlineErrorFields.has(fieldError.field))
+                  .map((fieldError) => (
+                    <p key={fieldError.field}>{fieldError.message}</p>
+                  ))}
               </div>
             )}
 
@@ -0,0 +1,60 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+// Asks whether to take over operator control offered by another operator
+export function OperatorOfferDialog() {
+  const offer = useSyncplayStore((state) => state.operatorOffer);
+  const setOffer = useSyncplayStore((state) => state.setOperatorOffer);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [responding, setResponding] = useState(false);
+
+  if (!offer) return null;
+
+  const respond = async (accept: boolean) => {
+    setResponding(true);
+    try {
+      await invoke("respond_operator_offer", { accept });
+      setOffer(null);
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to answer ${offer.operator}: ${error}`,
+      });
+      setOffer(null);
+    } finally {
+      setResponding(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-sm shadow-xl">
+        <h2 className="text-xl font-bold mb-1">Operator control</h2>
+        <p className="text-sm mb-2">
+          {offer.operator} offers you operator control of {offer.room}.
+        </p>
+        <p className="text-xs app-text-muted mb-4">
+          The room password is sent encrypted for this client only.
+        </p>
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={() => void respond(false)}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+            disabled={responding}
+          >
+            Decline
+          </button>
+          <button
+            onClick={() => void respond(true)}
+            className="btn-primary px-3 py-2 rounded-md text-sm"
+            disabled={responding}
+          >
+            Accept
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { SyncplayConfig } from "../../types/config";
+import { PersistentRoomInfo, SavedRoomPassword, useSyncplayStore } from "../../store";
 import { useNotificationStore } from "../../store/notifications";
 
 interface RoomManagerDialogProps {
@@ -14,6 +15,12 @@ export function RoomManagerDialog({ isOpen, onClose }: RoomManagerDialogProps) {
   const [connecting, setConnecting] = useState(false);
   const [roomNameInput, setRoomNameInput] = useState("");
   const [roomListInput, setRoomListInput] = useState("");
+  const [persistentRooms, setPersistentRooms] = useState<PersistentRoomInfo[] | null>(null);
+  const [savedPasswords, setSavedPasswords] = useState<SavedRoomPassword[]>([]);
+  const [revealed, setRevealed] = useState<Record<string, string>>({});
+  const [operatorsInput, setOperatorsInput] = useState("");
+  const connected = useSyncplayStore((state) => state.connection.connected);
+  const currentRoom = useSyncplayStore((state) => state.rooms.find((room) => room.isCurrent));
   const addNotification = useNotificationStore((state) => state.addNotification);
 
   useEffect(() => {
@@ -21,6 +28,10 @@ export function RoomManagerDialog({ isOpen, onClose }: RoomManagerDialogProps) {
       setConfig(null);
       setRoomNameInput("");
       setRoomListInput("");
+      setPersistentRooms(null);
+      setSavedPasswords([]);
+      setRevealed({});
+      setOperatorsInput("");
       return;
     }
 
@@ -41,8 +52,79 @@ export function RoomManagerDialog({ isOpen, onClose }: RoomManagerDialogProps) {
     };
 
     loadConfig();
+    invoke<SavedRoomPassword[]>("list_saved_room_passwords")
+      .then(setSavedPasswords)
+      .catch(() => setSavedPasswords([]));
+    // Fails when not connected or when the server has no persistent rooms
+    invoke<PersistentRoomInfo[]>("list_persistent_rooms")
+      .then(setPersistentRooms)
+      .catch(() => setPersistentRooms(null));
   }, [isOpen, addNotification]);
 
+  const setCurrentRoomPersistent = async (persistent: boolean) => {
+    try {
+      await invoke("set_room_persistent", { persistent });
+      setPersistentRooms(await invoke<PersistentRoomInfo[]>("list_persistent_rooms"));
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: "Failed to update persistent room",
+      });
+    }
+  };
+
+  const setOperatorsOnly = async (operators: string[]) => {
+    try {
+      await invoke("set_operators_only", { operators });
+      setConfig(await invoke<SyncplayConfig>("get_config"));
+      setOperatorsInput("");
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to update operators: ${error}`,
+      });
+    }
+  };
+
+  const passwordKey = (entry: SavedRoomPassword) => `${entry.kind}:${entry.room}`;
+
+  const revealPassword = async (entry: SavedRoomPassword) => {
+    const key = passwordKey(entry);
+    if (revealed[key] !== undefined) {
+      setRevealed((current) => {
+        const next = { ...current };
+        delete next[key];
+        return next;
+      });
+      return;
+    }
+    try {
+      const password = await invoke<string>("reveal_saved_room_password", {
+        room: entry.room,
+        kind: entry.kind,
+      });
+      setRevealed((current) => ({ ...current, [key]: password }));
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to reveal password: ${error}`,
+      });
+    }
+  };
+
+  const removePassword = async (entry: SavedRoomPassword) => {
+    try {
+      await invoke("remove_saved_room_password", { room: entry.room, kind: entry.kind });
+      setSavedPasswords(await invoke<SavedRoomPassword[]>("list_saved_room_passwords"));
+      setConfig(await invoke<SyncplayConfig>("get_config"));
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to remove password: ${error}`,
+      });
+    }
+  };
+
   const saveConfig = async (nextConfig: SyncplayConfig) => {
     try {
       await invoke("update_config", { config: nextConfig });
@@ -233,6 +315,151 @@ export function RoomManagerDialog({ isOpen, onClose }: RoomManagerDialogProps) {
                 </div>
               )}
             </div>
+
+            {connected && currentRoom && !currentRoom.name.startsWith("+") && (
+              <div>
+                <label className="block text-sm font-medium mb-1">Operators Only</label>
+                <p className="text-xs app-text-muted mb-2">
+                  Only the listed users can pause and seek in this room; everyone else suggests it
+                  in chat instead.
+                </p>
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    value={operatorsInput}
+                    onChange={(e) => setOperatorsInput(e.target.value)}
+                    className="flex-1 app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+                    placeholder="Other operators, separated by spaces"
+                  />
+                  <button
+                    type="button"
+                    onClick={() => void setOperatorsOnly(operatorsInput.split(/\s+/))}
+                    className="btn-primary px-3 py-2 rounded text-sm"
+                  >
+                    Apply
+                  </button>
+                </div>
+                {(config.user.operators_only_rooms ?? []).length > 0 && (
+                  <div className="mt-2 space-y-2">
+                    {(config.user.operators_only_rooms ?? []).map((entry) => (
+                      <div
+                        key={entry.room}
+                        className="flex items-center justify-between app-panel-muted px-3 py-2 rounded"
+                      >
+                        <div className="flex items-center gap-2 min-w-0">
+                          <span className="text-sm truncate">{entry.room}</span>
+                          <span className="text-xs app-text-muted truncate">
+                            {entry.operators.join(", ")}
+                          </span>
+                        </div>
+                        {entry.room === currentRoom.name && (
+                          <button
+                            type="button"
+                            onClick={() => void setOperatorsOnly([])}
+                            className="text-xs app-text-danger hover:opacity-80 shrink-0"
+                          >
+                            Lift
+                          </button>
+                        )}
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </div>
+            )}
+
+            {savedPasswords.length > 0 && (
+              <div>
+                <label className="block text-sm font-medium mb-1">Saved Passwords</label>
+                <div className="space-y-2">
+                  {savedPasswords.map((entry) => {
+                    const key = passwordKey(entry);
+                    return (
+                      <div
+                        key={key}
+                        className="flex items-center justify-between app-panel-muted px-3 py-2 rounded"
+                      >
+                        <div className="flex items-center gap-2 min-w-0">
+                          <span className="text-sm truncate">{entry.room}</span>
+                          <span className="text-xs app-tag-muted px-2 py-0.5 rounded">
+                            {entry.kind === "operator" ? "Operator" : "Join"}
+                          </span>
+                          {!entry.saved && (
+                            <span className="text-xs app-text-muted">This session only</span>
+                          )}
+                          {revealed[key] !== undefined && (
+                            <code className="text-xs truncate">{revealed[key]}</code>
+                          )}
+                        </div>
+                        <div className="flex items-center gap-3 shrink-0">
+                          <button
+                            type="button"
+                            onClick={() => void revealPassword(entry)}
+                            className="text-xs app-text-muted hover:opacity-80"
+                          >
+                            {revealed[key] !== undefined ? "Hide" : "Reveal"}
+                          </button>
+                          <button
+                            type="button"
+                            onClick={() => void removePassword(entry)}
+                            className="text-xs app-text-danger hover:opacity-80"
+                          >
+                            Forget
+                          </button>
+                        </div>
+                      </div>
+                    );
+                  })}
+                </div>
+              </div>
+            )}
+
+            {persistentRooms && (
+              <div>
+                <label className="block text-sm font-medium mb-1">Persistent Rooms</label>
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={persistentRooms.some((room) => room.isCurrent && room.marked)}
+                    onChange={(e) => void setCurrentRoomPersistent(e.target.checked)}
+                  />
+                  Remember the playlist of the current room
+                </label>
+                {persistentRooms.length === 0 ? (
+                  <p className="text-xs app-text-muted mt-2">No persistent rooms on this server.</p>
+                ) : (
+                  <div className="mt-2 space-y-2">
+                    {persistentRooms.map((room) => (
+                      <div
+                        key={room.name}
+                        className="flex items-center justify-between app-panel-muted px-3 py-2 rounded"
+                      >
+                        <div className="flex items-center gap-2 min-w-0">
+                          <span className="text-sm truncate">{room.name}</span>
+                          {room.marked && (
+                            <span className="text-xs app-tag-accent px-2 py-0.5 rounded">
+                              {room.playlistLength} remembered
+                            </span>
+                          )}
+                          <span className="text-xs app-text-muted">
+                            {room.userCount} {room.userCount === 1 ? "user" : "users"}
+                          </span>
+                        </div>
+                        {!room.isCurrent && (
+                          <button
+                            type="button"
+                            onClick={() => void invoke("join_room", { name: room.name })}
+                            className="text-xs app-text-muted hover:opacity-80 shrink-0"
+                          >
+                            Join
+                          </button>
+                        )}
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </div>
+            )}
           </div>
         ) : null}
       </div>
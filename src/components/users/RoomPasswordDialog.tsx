@@ -0,0 +1,73 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useSyncplayStore } from "../../store";
+import { useNotificationStore } from "../../store/notifications";
+
+// Asks for another password after the server turned a room join down
+export function RoomPasswordDialog() {
+  const request = useSyncplayStore((state) => state.roomPasswordRequest);
+  const setRequest = useSyncplayStore((state) => state.setRoomPasswordRequest);
+  const addNotification = useNotificationStore((state) => state.addNotification);
+  const [password, setPassword] = useState("");
+  const [joining, setJoining] = useState(false);
+
+  useEffect(() => {
+    setPassword("");
+  }, [request]);
+
+  if (!request) return null;
+
+  const retry = async () => {
+    if (!password) return;
+    setJoining(true);
+    try {
+      await invoke("retry_room_join", { password });
+      setRequest(null);
+    } catch (error) {
+      addNotification({
+        type: "error",
+        message: `Failed to join ${request.room}: ${error}`,
+      });
+    } finally {
+      setJoining(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 app-overlay flex items-center justify-center z-50">
+      <div className="app-panel app-panel-glass rounded-xl p-6 w-full max-w-sm shadow-xl">
+        <h2 className="text-xl font-bold mb-1">Room password</h2>
+        <p className="text-xs app-text-muted mb-4">{request.message}</p>
+        <input
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") {
+              e.preventDefault();
+              void retry();
+            }
+          }}
+          className="w-full app-input px-3 py-2 rounded focus:outline-none focus:border-blue-500"
+          placeholder={`Password for ${request.room}`}
+          autoFocus
+        />
+        <div className="flex justify-end gap-2 mt-4">
+          <button
+            onClick={() => setRequest(null)}
+            className="btn-neutral px-3 py-2 rounded-md text-sm"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={() => void retry()}
+            className="btn-primary px-3 py-2 rounded-md text-sm"
+            disabled={joining || !password}
+          >
+            {joining ? "Joining..." : "Join"}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,22 +1,45 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { LuCheck, LuCircle, LuPencilLine, LuUsers } from "react-icons/lu";
+import {
+  LuAlarmClock,
+  LuCheck,
+  LuCircle,
+  LuListChecks,
+  LuMoon,
+  LuPencilLine,
+  LuSun,
+  LuUsers,
+} from "react-icons/lu";
 import { useSyncplayStore } from "../../store";
 import { useNotificationStore } from "../../store/notifications";
 import { RoomManagerDialog } from "./RoomManagerDialog";
 
 export function UserList() {
   const users = useSyncplayStore((state) => state.users);
+  const fileDifferences = useSyncplayStore((state) => state.fileDifferences);
+  const roomFinished = useSyncplayStore((state) => state.roomFinished);
+  const setRoomFinished = useSyncplayStore((state) => state.setRoomFinished);
+  const rooms = useSyncplayStore((state) => state.rooms);
   const connection = useSyncplayStore((state) => state.connection);
   const config = useSyncplayStore((state) => state.config);
   const addNotification = useNotificationStore((state) => state.addNotification);
   const [showRoomManager, setShowRoomManager] = useState(false);
+  const [showSchedule, setShowSchedule] = useState(false);
+  const [scheduleTime, setScheduleTime] = useState("");
+  const [now, setNow] = useState(() => Date.now());
+
+  // Keeps the local times of users who share their UTC offset current
+  useEffect(() => {
+    const timer = window.setInterval(() => setNow(Date.now()), 30_000);
+    return () => window.clearInterval(timer);
+  }, []);
 
   const currentUsername = config?.user.username ?? null;
   const currentUser = users.find((user) => user.username === currentUsername);
   const isReady = currentUser?.isReady ?? false;
 
   const currentRoom = currentUser?.room ?? config?.user.default_room ?? "Room";
+  const currentGroup = rooms.find((room) => room.isCurrent);
 
   const handleToggleReady = () => {
     if (!connection.connected) {
@@ -38,6 +61,132 @@ export function UserList() {
     });
   };
 
+  const handleReadyCheck = () => {
+    void invoke("request_ready_check", {}).catch((error) => {
+      const message =
+        typeof error === "string"
+          ? error
+          : (error as { message?: string })?.message || "Unknown error";
+      addNotification({
+        type: "error",
+        message: `Failed to start ready check: ${message}`,
+      });
+    });
+  };
+
+  const handleJoinAtPosition = (room: string) => {
+    void invoke("join_room_at_position", { room }).catch((error) => {
+      const message =
+        typeof error === "string"
+          ? error
+          : (error as { message?: string })?.message || "Unknown error";
+      addNotification({
+        type: "error",
+        message: `Failed to join ${room}: ${message}`,
+      });
+    });
+  };
+
+  // "their size vs ours" details for the warnings shown under a user's file
+  const describeDifference = (username: string) => {
+    const difference = fileDifferences.find((entry) => entry.username === username);
+    if (!difference) return null;
+    const parts: string[] = [];
+    if (difference.name) {
+      parts.push(`name: ${difference.name.theirsNormalized} vs ${difference.name.mineNormalized}`);
+    }
+    if (difference.size) {
+      parts.push(
+        `size: ${formatFileSize(difference.size.theirs)} vs ${formatFileSize(difference.size.mine)}`,
+      );
+    }
+    if (difference.duration) {
+      const delta = difference.duration.deltaSeconds;
+      parts.push(
+        delta === null
+          ? "duration unknown"
+          : `duration ${delta > 0 ? "+" : ""}${Math.round(delta)}s`,
+      );
+    }
+    return parts.join(" · ");
+  };
+
+  const fileDiffers = (user: (typeof users)[number]) =>
+    !hasSameFileName(user.file, currentUserFile) ||
+    !hasSameFileSize(user.fileSize, currentUserSize) ||
+    !hasSameDuration(user.fileDuration ?? null, currentUserDuration ?? null);
+
+  const handleTrustFile = (username: string, forSession: boolean) => {
+    void invoke("trust_user_file", { username, forSession }).catch((error) => {
+      addNotification({
+        type: "error",
+        message: `Failed to trust ${username}'s file: ${error}`,
+      });
+    });
+  };
+
+  const handleUntrustFile = (username: string) => {
+    void invoke("untrust_user_file", { username }).catch((error) => {
+      addNotification({
+        type: "error",
+        message: `Failed to update ${username}: ${error}`,
+      });
+    });
+  };
+
+  const handleAdvanceForEveryone = () => {
+    setRoomFinished(null);
+    void invoke("update_playlist", { action: "next" }).catch((error) => {
+      addNotification({
+        type: "error",
+        message: `Failed to advance the playlist: ${error}`,
+      });
+    });
+  };
+
+  const handleGrantOperator = (username: string) => {
+    void invoke("grant_operator", { username })
+      .then(() => {
+        addNotification({
+          type: "info",
+          message: `Offered operator control to ${username}`,
+        });
+      })
+      .catch((error) => {
+        const message =
+          typeof error === "string"
+            ? error
+            : (error as { message?: string })?.message || "Unknown error";
+        addNotification({
+          type: "error",
+          message: `Failed to hand over control: ${message}`,
+        });
+      });
+  };
+
+  const handleScheduleSession = () => {
+    const start = new Date(scheduleTime);
+    if (Number.isNaN(start.getTime())) {
+      addNotification({
+        type: "warning",
+        message: "Pick a start time first",
+      });
+      return;
+    }
+    void invoke("schedule_session", { startTime: start.toISOString() })
+      .then(() => setShowSchedule(false))
+      .catch((error) => {
+        const message =
+          typeof error === "string"
+            ? error
+            : (error as { message?: string })?.message || "Unknown error";
+        addNotification({
+          type: "error",
+          message: `Failed to schedule session: ${message}`,
+        });
+      });
+  };
+
   if (!connection.connected) {
     return (
       <div className="flex flex-col h-full min-h-0 min-w-0 gap-2">
@@ -91,6 +240,17 @@ export function UserList() {
     return hours > 0 ? `${hours}:${time}` : time;
   };
 
+  // A user's wall clock, and whether it is a time they are likely awake
+  const localTimeOf = (utcOffset: number) => {
+    const clock = new Date(now + utcOffset * 60_000);
+    const hours = clock.getUTCHours();
+    const minutes = clock.getUTCMinutes();
+    return {
+      time: `${hours.toString().padStart(2, "0")}:${minutes.toString().padStart(2, "0")}`,
+      awake: hours >= 7 && hours < 23,
+    };
+  };
+
   const hasSameFileName = (a?: string | null, b?: string | null) =>
     a && b ? a.toLowerCase() === b.toLowerCase() : false;
 
@@ -112,12 +272,9 @@ export function UserList() {
   const currentUserFile = currentUser?.file ?? null;
   const currentUserSize = currentUser?.fileSize;
   const currentUserDuration = currentUser?.fileDuration;
-  const sortedUsers = currentUsername
-    ? [
-        ...users.filter((user) => user.username === currentUsername),
-        ...users.filter((user) => user.username !== currentUsername),
-      ]
-    : users;
+  const userCountLabel = currentGroup
+    ? `${currentGroup.readyCount}/${currentGroup.users.length} ready`
+    : `${users.length}`;
 
   return (
     <div className="flex flex-col h-full min-h-0 min-w-0 gap-2">
@@ -125,9 +282,31 @@ export function UserList() {
         <div className="flex items-center gap-2 min-w-0">
           <LuUsers className="app-icon app-text-muted" />
           <span className="text-sm font-semibold truncate">{currentRoom}</span>
-          <span className="text-xs app-text-muted shrink-0">({users.length})</span>
+          <span className="text-xs app-text-muted shrink-0">
+            ({userCountLabel})
+          </span>
         </div>
         <div className="flex items-center gap-2">
+          {currentUser?.isController && (
+            <button
+              onClick={handleReadyCheck}
+              className="btn-neutral app-icon-button"
+              aria-label="Ready check"
+              title="Ask the room to get ready"
+            >
+              <LuListChecks className="app-icon" />
+            </button>
+          )}
+          {currentUser?.isController && (
+            <button
+              onClick={() => setShowSchedule((value) => !value)}
+              className={`app-icon-button ${showSchedule ? "btn-primary" : "btn-neutral"}`}
+              aria-label="Schedule start"
+              title="Schedule the session start"
+            >
+              <LuAlarmClock className="app-icon" />
+            </button>
+          )}
           <button
             onClick={handleToggleReady}
             className={`app-icon-button ${isReady ? "btn-primary" : "btn-neutral"}`}
@@ -145,14 +324,66 @@ export function UserList() {
         </div>
       </div>
 
+      {showSchedule && (
+        <div className="flex items-center gap-2">
+          <input
+            type="datetime-local"
+            value={scheduleTime}
+            onChange={(event) => setScheduleTime(event.target.value)}
+            className="flex-1 min-w-0 app-input px-2 py-1 rounded text-sm focus:outline-none focus:border-blue-500"
+          />
+          <button
+            onClick={handleScheduleSession}
+            className="btn-primary px-3 py-1 rounded-md text-sm"
+          >
+            Schedule
+          </button>
+        </div>
+      )}
+
+      {roomFinished && roomFinished.room === currentRoom && (
+        <div className="flex items-center justify-between gap-2 app-panel-muted rounded-md px-3 py-2 text-xs">
+          <span className="truncate">Everyone finished {roomFinished.file}</span>
+          <div className="flex items-center gap-2 shrink-0">
+            {(currentUser?.isController || !currentRoom.startsWith("+")) && (
+              <button
+                onClick={handleAdvanceForEveryone}
+                className="app-text-accent hover:opacity-80"
+              >
+                Next for everyone
+              </button>
+            )}
+            <button
+              onClick={() => setRoomFinished(null)}
+              className="app-text-muted hover:opacity-80"
+            >
+              Dismiss
+            </button>
+          </div>
+        </div>
+      )}
+
       {users.length === 0 ? (
         <div className="flex-1 min-h-0 overflow-auto">
           <p className="app-text-muted text-sm">No users in room</p>
         </div>
       ) : (
         <div className="space-y-2 flex-1 min-h-0 overflow-auto pr-1">
-          {sortedUsers.map((user) => (
+          {/* Users arrive grouped by room, with the current room and the local user first */}
+          {users.map((user, index) => (
             <div key={user.username} className="app-panel-muted rounded-md p-3 text-sm">
+              {user.room !== currentRoom && users[index - 1]?.room !== user.room && (
+                <div className="flex items-center justify-between gap-2 mb-1 text-[10px]">
+                  <span className="app-text-muted truncate">Room: {user.room}</span>
+                  <button
+                    onClick={() => handleJoinAtPosition(user.room)}
+                    className="app-text-accent hover:opacity-80 shrink-0"
+                    title="Join this room at its file and position"
+                  >
+                    Join them
+                  </button>
+                </div>
+              )}
               <div className="flex items-center justify-between">
                 <div className="flex items-center gap-2 min-w-0">
                   <span className="font-medium truncate flex-1 min-w-0">{user.username}</span>
@@ -166,17 +397,49 @@ export function UserList() {
                   {currentUsername === user.username && (
                     <span className="text-[10px] app-tag-accent px-2 py-0 rounded-full">You</span>
                   )}
+                  {user.utcOffset !== null &&
+                    user.utcOffset !== undefined &&
+                    (() => {
+                      const local = localTimeOf(user.utcOffset);
+                      return (
+                        <span
+                          className="flex items-center gap-1 text-[10px] app-text-muted shrink-0"
+                          title={
+                            local.awake
+                              ? `Local time for ${user.username}`
+                              : `Local time for ${user.username}, who may be asleep`
+                          }
+                        >
+                          {local.awake ? <LuSun /> : <LuMoon />}
+                          {local.time}
+                        </span>
+                      );
+                    })()}
                 </div>
                 {user.isController && (
                   <span className="text-xs app-tag-accent px-2 py-0.5 rounded">Controller</span>
                 )}
+                {!user.isController &&
+                  currentUser?.isController &&
+                  user.room === currentRoom &&
+                  user.username !== currentUsername && (
+                    <button
+                      onClick={() => handleGrantOperator(user.username)}
+                      className="text-xs app-text-accent hover:opacity-80 shrink-0"
+                      title="Hand over operator control without sharing the password in chat"
+                    >
+                      Make operator
+                    </button>
+                  )}
               </div>
 
               {user.file && (
                 <div className="mt-1 space-y-1">
                   <div
                     className={`text-xs truncate ${
-                      user.room === currentRoom && !hasSameFileName(user.file, currentUserFile)
+                      user.room === currentRoom &&
+                      !user.fileTrusted &&
+                      !hasSameFileName(user.file, currentUserFile)
                         ? "app-text-warning"
                         : "app-text-muted"
                     }`}
@@ -187,6 +450,7 @@ export function UserList() {
                     <span
                       className={`${
                         user.room === currentRoom &&
+                        !user.fileTrusted &&
                         !hasSameFileSize(user.fileSize, currentUserSize)
                           ? "app-text-warning"
                           : "app-text-muted"
@@ -198,6 +462,7 @@ export function UserList() {
                     <span
                       className={`${
                         user.room === currentRoom &&
+                        !user.fileTrusted &&
                         !hasSameDuration(user.fileDuration ?? null, currentUserDuration ?? null)
                           ? "app-text-warning"
                           : "app-text-muted"
@@ -206,6 +471,41 @@ export function UserList() {
                       Duration: {formatDuration(user.fileDuration ?? null)}
                     </span>
                   </div>
+                  {describeDifference(user.username) && (
+                    <div className="text-[10px] app-text-warning truncate">
+                      Theirs vs yours: {describeDifference(user.username)}
+                    </div>
+                  )}
+                  {user.room === currentRoom &&
+                    user.username !== currentUsername &&
+                    (user.fileTrusted ? (
+                      <button
+                        onClick={() => handleUntrustFile(user.username)}
+                        className="text-[10px] app-text-muted hover:opacity-80"
+                        title="Warn about differences with this user's file again"
+                      >
+                        File trusted · undo
+                      </button>
+                    ) : (
+                      fileDiffers(user) && (
+                        <div className="flex items-center gap-2 text-[10px]">
+                          <button
+                            onClick={() => handleTrustFile(user.username, false)}
+                            className="app-text-accent hover:opacity-80"
+                            title="Stop warning about this file from this user"
+                          >
+                            Trust this file
+                          </button>
+                          <button
+                            onClick={() => handleTrustFile(user.username, true)}
+                            className="app-text-muted hover:opacity-80"
+                            title="Stop warning about this user's files until the app closes"
+                          >
+                            Trust for this session
+                          </button>
+                        </div>
+                      )
+                    ))}
                 </div>
               )}
             </div>
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { RoomOverview } from "../store";
 
 export interface ConnectionParams {
   host: string;
@@ -6,6 +7,7 @@ export interface ConnectionParams {
   username: string;
   room: string;
   password?: string;
+  roomPassword?: string;
 }
 
 export const tauriApi = {
@@ -22,14 +24,38 @@ export const tauriApi = {
     return invoke("get_connection_status");
   },
 
+  async changeUsername(newName: string): Promise<void> {
+    return invoke("change_username", { newName });
+  },
+
   // Chat commands
   async sendChatMessage(message: string): Promise<void> {
     return invoke("send_chat_message", { message });
   },
 
   // Room commands
-  async changeRoom(room: string): Promise<void> {
-    return invoke("change_room", { room });
+  async changeRoom(room: string, roomPassword?: string): Promise<void> {
+    return invoke("change_room", { room, roomPassword });
+  },
+
+  async getRoomOverview(room: string): Promise<RoomOverview> {
+    return invoke("get_room_overview", { room });
+  },
+
+  async joinRoomAtPosition(room: string): Promise<void> {
+    return invoke("join_room_at_position", { room });
+  },
+
+  async listRoomOperators(): Promise<string[]> {
+    return invoke("list_room_operators");
+  },
+
+  async grantOperator(username: string): Promise<void> {
+    return invoke("grant_operator", { username });
+  },
+
+  async respondOperatorOffer(accept: boolean): Promise<void> {
+    return invoke("respond_operator_offer", { accept });
   },
 
   async setReady(isReady: boolean): Promise<void> {
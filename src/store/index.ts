@@ -19,6 +19,29 @@ interface User {
   fileDuration?: number | null;
   isReady: boolean;
   isController: boolean;
+  position?: number | null;
+  paused?: boolean | null;
+  syncDelta?: number | null;
+  fileTrusted?: boolean;
+  // Minutes east of UTC, for users who share it
+  utcOffset?: number | null;
+}
+
+export interface FileDifference {
+  username: string;
+  file: string;
+  name: { mine: string; theirs: string; mineNormalized: string; theirsNormalized: string } | null;
+  size: { mine: number | string | null; theirs: number | string | null } | null;
+  duration: { mine: number | null; theirs: number | null; deltaSeconds: number | null } | null;
+}
+
+export interface RoomGroup {
+  name: string;
+  isCurrent: boolean;
+  users: string[];
+  readyCount: number;
+  sameFileCount: number;
+  controllers: string[];
 }
 
 interface ChatMessage {
@@ -26,6 +49,140 @@ interface ChatMessage {
   username: string | null;
   message: string;
   messageType: string;
+  mentioned?: boolean;
+}
+
+export interface ServerMotd {
+  server: string;
+  kind: "motd" | "announcement";
+  message: string;
+  links: string[];
+  markdown: boolean;
+  fingerprint: string;
+  timestamp: string;
+}
+
+export type RoomEventKind =
+  | { type: "joined" }
+  | { type: "left" }
+  | { type: "file_changed"; file: string; duration: number | null }
+  | { type: "seeked"; from: number; to: number }
+  | { type: "paused"; position: number }
+  | { type: "unpaused" }
+  | { type: "ready"; ready: boolean; setBy: string | null };
+
+export type RoomEvent = RoomEventKind & {
+  timestamp: string;
+  username: string;
+  room: string;
+};
+
+export interface Bookmark {
+  id: number;
+  file: string;
+  position: number;
+  label: string;
+  sharedBy: string | null;
+}
+
+export interface MediaDelays {
+  file: string;
+  audioMs: number;
+  subMs: number;
+}
+
+export interface ServerFeatures {
+  feature_list: boolean;
+  shared_playlists: boolean;
+  chat: boolean;
+  readiness: boolean;
+  managed_rooms: boolean;
+  persistent_rooms: boolean;
+  set_others_readiness: boolean;
+  max_chat_message_length: number | null;
+  max_username_length: number | null;
+  max_room_name_length: number | null;
+  max_filename_length: number | null;
+}
+
+export interface FeatureDowngrade {
+  feature: string;
+  reason: "server_too_old" | "disabled_by_server" | "disabled_in_settings";
+  min_version: string | null;
+}
+
+export interface ServerFeatureReport {
+  server_version: string | null;
+  features: ServerFeatures;
+  raw_features: Record<string, unknown> | null;
+  downgrades: FeatureDowngrade[];
+}
+
+export interface RoomOverview {
+  name: string;
+  isCurrent: boolean;
+  users: { username: string; file: string | null; isReady: boolean; isController: boolean }[];
+  files: string[];
+  position: number | null;
+  paused: boolean | null;
+}
+
+export interface RoomPasswordRequest {
+  room: string;
+  message: string;
+}
+
+export interface UntrustedUrlRequest {
+  url: string;
+  domain: string;
+}
+
+export interface SeekConfirmation {
+  from: number;
+  to: number;
+}
+
+export interface SavedRoomPassword {
+  room: string;
+  kind: "operator" | "join";
+  saved: boolean;
+  active: boolean;
+}
+
+export interface RoomFinished {
+  room: string;
+  file: string;
+  users: string[];
+}
+
+export interface OperatorOffer {
+  operator: string;
+  room: string;
+}
+
+export interface LaunchConnectCountdown {
+  host: string;
+  port: number;
+  username: string;
+  room: string;
+  remaining: number;
+}
+
+export interface PersistentRoomInfo {
+  name: string;
+  userCount: number;
+  onServer: boolean;
+  marked: boolean;
+  playlistLength: number;
+  isCurrent: boolean;
+}
+
+export type SegmentKind = "intro" | "outro" | "preview";
+
+export interface Segment {
+  kind: SegmentKind;
+  start: number;
+  end: number;
 }
 
 interface PlaylistState {
@@ -41,14 +198,46 @@ interface PlayerState {
   speed: number | null;
 }
 
+export type PlayerHealthStatus = "healthy" | "warning" | "polling" | "restarting";
+
+export interface PlayerHealth {
+  status: PlayerHealthStatus;
+  latencyMs: number | null;
+  averageLatencyMs: number | null;
+  maxLatencyMs: number | null;
+  unresponsiveSecs: number;
+}
+
+export interface BufferingState {
+  buffering: boolean;
+  percent: number | null;
+  cacheDuration: number | null;
+}
+
 interface SyncplayStore {
   // State
   connection: ConnectionState;
   tlsStatus: TlsStatus;
   users: User[];
+  rooms: RoomGroup[];
+  fileDifferences: FileDifference[];
   messages: ChatMessage[];
+  motd: ServerMotd | null;
+  roomPasswordRequest: RoomPasswordRequest | null;
+  untrustedUrl: UntrustedUrlRequest | null;
+  seekConfirmation: SeekConfirmation | null;
+  operatorOffer: OperatorOffer | null;
+  launchConnect: LaunchConnectCountdown | null;
+  roomFinished: RoomFinished | null;
+  roomEvents: RoomEvent[];
+  bookmarks: Bookmark[];
+  activeSegment: Segment | null;
+  syncDetached: boolean;
+  doNotDisturb: boolean;
   playlist: PlaylistState;
   player: PlayerState;
+  playerHealth: PlayerHealth | null;
+  buffering: BufferingState | null;
   rttMs: number | null;
   config: SyncplayConfig | null;
   mediaIndexVersion: number;
@@ -59,6 +248,15 @@ interface SyncplayStore {
   setTlsStatus: (status: TlsStatus) => void;
   setUsers: (users: User[]) => void;
   addMessage: (message: ChatMessage) => void;
+  setMotd: (motd: ServerMotd | null) => void;
+  setRoomPasswordRequest: (request: RoomPasswordRequest | null) => void;
+  setUntrustedUrl: (request: UntrustedUrlRequest | null) => void;
+  setSeekConfirmation: (request: SeekConfirmation | null) => void;
+  setOperatorOffer: (offer: OperatorOffer | null) => void;
+  setLaunchConnect: (countdown: LaunchConnectCountdown | null) => void;
+  setRoomFinished: (finished: RoomFinished | null) => void;
+  loadRoomTimeline: () => Promise<void>;
+  loadBookmarks: () => Promise<void>;
   setPlaylist: (playlist: PlaylistState) => void;
   setPlayerState: (state: PlayerState) => void;
   setRttMs: (rttMs: number | null) => void;
@@ -71,6 +269,31 @@ interface SyncplayStore {
 }
 
 let listenersInitialized = false;
+let notificationAudio: AudioContext | null = null;
+
+// Short two-tone chime, generated so no audio asset has to be bundled
+const playNotificationSound = () => {
+  try {
+    notificationAudio ??= new AudioContext();
+    const context = notificationAudio;
+    [880, 1320].forEach((frequency, index) => {
+      const start = context.currentTime + index * 0.12;
+      const oscillator = context.createOscillator();
+      const gain = context.createGain();
+      oscillator.frequency.value = frequency;
+      gain.gain.setValueAtTime(0.15, start);
+      gain.gain.exponentialRampToValueAtTime(0.001, start + 0.25);
+      oscillator.connect(gain).connect(context.destination);
+      oscillator.start(start);
+      oscillator.stop(start + 0.25);
+    });
+  } catch (error) {
+    console.error("Failed to play notification sound:", error);
+  }
+};
+
+// Matches the backend room timeline limit
+const MAX_ROOM_EVENTS = 500;
 
 export const useSyncplayStore = create<SyncplayStore>((set) => ({
   // Initial state
@@ -80,7 +303,21 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
   },
   tlsStatus: "unknown",
   users: [],
+  rooms: [],
+  fileDifferences: [],
   messages: [],
+  motd: null,
+  roomPasswordRequest: null,
+  untrustedUrl: null,
+  seekConfirmation: null,
+  operatorOffer: null,
+  launchConnect: null,
+  roomFinished: null,
+  roomEvents: [],
+  bookmarks: [],
+  activeSegment: null,
+  syncDetached: false,
+  doNotDisturb: false,
   playlist: {
     items: [],
     currentIndex: null,
@@ -92,6 +329,8 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
     paused: true,
     speed: 1.0,
   },
+  playerHealth: null,
+  buffering: null,
   rttMs: null,
   config: null,
   mediaIndexVersion: 0,
@@ -118,6 +357,63 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
       messages: [...state.messages, message],
     })),
 
+  setMotd: (motd) =>
+    set(() => ({
+      motd,
+    })),
+
+  setRoomPasswordRequest: (request) =>
+    set(() => ({
+      roomPasswordRequest: request,
+    })),
+
+  setUntrustedUrl: (request) =>
+    set(() => ({
+      untrustedUrl: request,
+    })),
+
+  setSeekConfirmation: (request) =>
+    set(() => ({
+      seekConfirmation: request,
+    })),
+
+  setOperatorOffer: (offer) =>
+    set(() => ({
+      operatorOffer: offer,
+    })),
+
+  setLaunchConnect: (countdown) =>
+    set(() => ({
+      launchConnect: countdown,
+    })),
+
+  setRoomFinished: (finished) =>
+    set(() => ({
+      roomFinished: finished,
+    })),
+
+  loadRoomTimeline: async () => {
+    try {
+      const roomEvents = await invoke<RoomEvent[]>("get_room_timeline");
+      set(() => ({
+        roomEvents,
+      }));
+    } catch (error) {
+      console.error("Failed to load room timeline:", error);
+    }
+  },
+
+  loadBookmarks: async () => {
+    try {
+      const bookmarks = await invoke<Bookmark[]>("get_bookmarks");
+      set(() => ({
+        bookmarks,
+      }));
+    } catch (error) {
+      console.error("Failed to load bookmarks:", error);
+    }
+  },
+
   setPlaylist: (playlist) =>
     set(() => ({
       playlist,
@@ -176,9 +472,17 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
     });
 
     // User list updates
-    listenSafe<{ users: User[] }>("user-list-updated", (event) => {
+    listenSafe<{ users: User[]; rooms: RoomGroup[] }>("user-list-updated", (event) => {
       set(() => ({
         users: event.payload.users,
+        rooms: event.payload.rooms,
+      }));
+    });
+
+    // What differs between our file and those of the room
+    listenSafe<{ room: string; differences: FileDifference[] }>("file-differences", (event) => {
+      set(() => ({
+        fileDifferences: event.payload.differences,
       }));
     });
 
@@ -189,6 +493,99 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
       }));
     });
 
+    listenSafe<{ kind: string }>("play-notification-sound", () => {
+      playNotificationSound();
+    });
+
+    // Server MOTD and announcements, shown outside the chat stream
+    listenSafe<ServerMotd>("server-motd", (event) => {
+      set(() => ({
+        motd: event.payload,
+      }));
+    });
+
+    // The server turned down a room join over its password
+    listenSafe<RoomPasswordRequest>("room-password-required", (event) => {
+      set(() => ({
+        roomPasswordRequest: event.payload,
+      }));
+    });
+
+    // A URL load waits until its domain is trusted
+    listenSafe<UntrustedUrlRequest>("untrusted-url", (event) => {
+      set(() => ({
+        untrustedUrl: event.payload,
+      }));
+    });
+
+    // A large seek of ours waits for confirmation before the room follows
+    listenSafe<SeekConfirmation>("seek-confirmation", (event) => {
+      set(() => ({
+        seekConfirmation: event.payload,
+      }));
+    });
+
+    // Everyone in the room reached the end of the file
+    listenSafe<RoomFinished>("room-finished", (event) => {
+      set(() => ({
+        roomFinished: event.payload,
+      }));
+    });
+
+    // An operator offered us control of the room
+    listenSafe<OperatorOffer>("operator-offer", (event) => {
+      set(() => ({
+        operatorOffer: event.payload,
+      }));
+    });
+
+    // Connect-on-launch countdown; a remaining of 0 means the connect has started
+    listenSafe<LaunchConnectCountdown>("launch-connect-countdown", (event) => {
+      set(() => ({
+        launchConnect: event.payload.remaining > 0 ? event.payload : null,
+      }));
+    });
+
+    listenSafe("launch-connect-cancelled", () => {
+      set(() => ({
+        launchConnect: null,
+      }));
+    });
+
+    // Room activity feed
+    listenSafe<RoomEvent>("room-event", (event) => {
+      set((state) => ({
+        roomEvents: [...state.roomEvents.slice(-(MAX_ROOM_EVENTS - 1)), event.payload],
+      }));
+    });
+
+    listenSafe<Bookmark[]>("bookmarks-updated", (event) => {
+      set(() => ({
+        bookmarks: event.payload,
+      }));
+    });
+
+    // Known intro/outro the playback is currently in
+    listenSafe<Segment | null>("segment-changed", (event) => {
+      set(() => ({
+        activeSegment: event.payload,
+      }));
+    });
+
+    // Local player running apart from the room, e.g. while frame stepping
+    listenSafe<boolean>("sync-detached-changed", (event) => {
+      set(() => ({
+        syncDetached: event.payload,
+      }));
+    });
+
+    // Only errors reach the OSD, and mentions stay quiet
+    listenSafe<boolean>("dnd-changed", (event) => {
+      set(() => ({
+        doNotDisturb: event.payload,
+      }));
+    });
+
     // Playlist updates
     listenSafe<PlaylistState>("playlist-updated", (event) => {
       set(() => ({
@@ -203,6 +600,19 @@ export const useSyncplayStore = create<SyncplayStore>((set) => ({
       }));
     });
 
+    listenSafe<PlayerHealth | null>("player-health", (event) => {
+      set(() => ({
+        playerHealth: event.payload,
+      }));
+    });
+
+    // A streamed file stalled to rebuffer, or recovered
+    listenSafe<BufferingState>("buffering", (event) => {
+      set(() => ({
+        buffering: event.payload.buffering ? event.payload : null,
+      }));
+    });
+
     listenSafe<{ rttMs: number }>("ping-updated", (event) => {
       set(() => ({
         rttMs: event.payload.rttMs,
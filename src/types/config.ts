@@ -7,11 +7,74 @@ export type UnpauseAction =
 export type ChatInputPosition = "top" | "middle" | "bottom";
 export type ChatOutputMode = "chatroom" | "scrolling";
 export type TransparencyMode = "off" | "low" | "high";
+export type PlaylistMode = "normal" | "repeat_one" | "repeat_all";
+export type PlaylistAdvanceWait = "off" | "ready_users" | "controllers";
+export type SyncMode = "seek" | "smooth";
+export type IdleScreen = "generated" | "image";
+export type PauseOnLeave = "never" | "anyone" | "controllers_only" | "below_min_users";
+export type Language = "en" | "de" | "zh_CN";
+export type TimestampFormat = "hours24" | "hours12";
+export type OsdPosition =
+  | "top_left"
+  | "top"
+  | "top_right"
+  | "left"
+  | "center"
+  | "right"
+  | "bottom_left"
+  | "bottom"
+  | "bottom_right";
+
+export type OsdVerbosity = "off" | "errors" | "important" | "everything";
+
+export interface OsdOverrides {
+  errors?: boolean | null;
+  warnings?: boolean | null;
+  status?: boolean | null;
+  same_room?: boolean | null;
+  slowdown?: boolean | null;
+  different_room?: boolean | null;
+  non_controller?: boolean | null;
+}
 
 export interface ServerConfig {
   host: string;
   port: number;
   password: string | null;
+  password_hash?: string | null;
+  bind_address?: string | null;
+  username?: string | null;
+  room?: string | null;
+  pinned?: boolean;
+}
+
+// Localhost WebSocket API for stream decks, phones and scripts
+export interface RemoteControlConfig {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
+
+// Opt-in Prometheus endpoint on localhost
+export interface MetricsConfig {
+  enabled: boolean;
+  port: number;
+}
+
+// Lua scripts that react to client events
+export interface ScriptsConfig {
+  enabled: boolean;
+  directory: string | null;
+}
+
+export interface ScriptInfo {
+  name: string;
+  error: string | null;
+}
+
+export interface ScriptsStatus {
+  directory: string | null;
+  scripts: ScriptInfo[];
 }
 
 export interface PublicServer {
@@ -19,10 +82,35 @@ export interface PublicServer {
   address: string;
 }
 
+export interface PrivacyRule {
+  room: string;
+  file: string;
+  public_servers_only: boolean;
+  filename_mode: PrivacyMode | null;
+  filesize_mode: PrivacyMode | null;
+}
+
+export interface OperatorsOnlyRoom {
+  room: string;
+  operators: string[];
+}
+
+export interface PrivacyPreview {
+  name: string | null;
+  size: number | string | null;
+  privacy: {
+    filenameMode: PrivacyMode;
+    filesizeMode: PrivacyMode;
+    filenameRule: number | null;
+    filesizeRule: number | null;
+  };
+}
+
 export interface UserPreferences {
   username: string;
   default_room: string;
   room_list: string[];
+  room_passwords?: Record<string, string>;
   theme: string;
   transparency_mode: TransparencyMode;
 
@@ -31,6 +119,20 @@ export interface UserPreferences {
   slowdown_threshold: number;
   slowdown_reset_threshold: number;
   slowdown_rate: number;
+  fastforward_behind_threshold: number;
+  fastforward_extra_time: number;
+  fastforward_reset_threshold: number;
+  sync_mode?: SyncMode;
+  smooth_sync_threshold?: number;
+  smooth_sync_rate?: number;
+  slowdown_emulation?: boolean;
+  slowdown_micro_seek_step?: number;
+  confirm_large_seeks?: boolean;
+  large_seek_threshold?: number;
+  operators_only_rooms?: OperatorsOnlyRoom[];
+  room_votes?: boolean;
+  vote_majority_percent?: number;
+  vote_timeout_secs?: number;
   slow_on_desync: boolean;
   rewind_on_desync: boolean;
   fastforward_on_desync: boolean;
@@ -38,24 +140,37 @@ export interface UserPreferences {
 
   ready_at_start: boolean;
   pause_on_leave: boolean;
+  pause_on_leave_policy?: PauseOnLeave;
+  pause_on_leave_min_users?: number;
+  pause_on_focus_loss?: boolean;
   unpause_action: UnpauseAction;
   autoplay_enabled: boolean;
   autoplay_min_users: number;
   autoplay_require_same_filenames: boolean;
+  unpause_countdown_enabled?: boolean;
+  unpause_countdown_seconds?: number;
+  auto_ready_on_matching_file?: boolean;
+  auto_ready_require_stable_size?: boolean;
+  auto_ready_stable_seconds?: number;
+  segment_skip_prompt?: boolean;
 
   filename_privacy_mode: PrivacyMode;
   filesize_privacy_mode: PrivacyMode;
+  privacy_rules?: PrivacyRule[];
+  session_history?: boolean;
+  share_utc_offset?: boolean;
 
   only_switch_to_trusted_domains: boolean;
   trusted_domains: string[];
+  ignored_users?: string[];
+  dismissed_motds?: Record<string, string>;
 
-  show_osd: boolean;
+  osd_verbosity?: OsdVerbosity;
+  osd_overrides?: OsdOverrides;
   osd_duration: number;
-  show_osd_warnings: boolean;
-  show_slowdown_osd: boolean;
-  show_different_room_osd: boolean;
-  show_same_room_osd: boolean;
-  show_non_controller_osd: boolean;
+  osd_position?: OsdPosition;
+  osd_color?: string | null;
+  osd_font_scale?: number;
   show_duration_notification: boolean;
 
   chat_input_enabled: boolean;
@@ -81,11 +196,20 @@ export interface UserPreferences {
   notification_timeout: number;
   alert_timeout: number;
   chat_timeout: number;
+  mention_flash_taskbar?: boolean;
+  mention_sound?: boolean;
 
+  language?: Language;
+  chat_timestamp_format?: TimestampFormat;
+  always_show_hours?: boolean;
+  ui_event_interval_ms?: number;
   autosave_joins_to_list: boolean;
+  remember_plaintext_passwords?: boolean;
   shared_playlist_enabled: boolean;
   loop_at_end_of_playlist: boolean;
   loop_single_files: boolean;
+  playlist_mode?: PlaylistMode;
+  playlist_advance_wait?: PlaylistAdvanceWait;
   show_playlist: boolean;
   side_panel_layout: "rows" | "columns";
   side_column_width?: number;
@@ -93,6 +217,7 @@ export interface UserPreferences {
   window_width?: number;
   window_height?: number;
   auto_connect: boolean;
+  auto_connect_on_launch?: boolean;
   force_gui_prompt: boolean;
   check_for_updates_automatically: boolean | null;
   debug: boolean;
@@ -103,6 +228,59 @@ export interface PlayerConfig {
   media_directories: string[];
   player_arguments: string[];
   per_player_arguments: Record<string, string[]>;
+  auto_restart?: boolean;
+  restart_grace_seconds?: number;
+  leave_player_running?: boolean;
+  mpv_profile?: MpvProfile;
+  watchdog?: WatchdogPolicy;
+  idle_screen?: IdleScreen;
+  wait_while_buffering?: boolean;
+  stall_detection?: boolean;
+  stall_timeout_secs?: number;
+  flatpak_host_filesystem?: boolean;
+}
+
+export interface MpvProfile {
+  enabled: boolean;
+  hr_seek: boolean;
+  keep_open: boolean;
+  cache: boolean;
+  cache_secs: number;
+  cache_pause_wait: number;
+}
+
+export interface WatchdogPolicy {
+  enabled: boolean;
+  warn_after_secs: number;
+  poll_fallback_after_secs: number;
+  restart: boolean;
+  restart_after_secs: number;
+}
+
+export type ReconnectGiveUpAction = "notify" | "pause_player" | "stop_player";
+
+export interface ReconnectPolicy {
+  max_retries: number;
+  base_delay_seconds: number;
+  max_delay_seconds: number;
+  jitter: number;
+  give_up_action: ReconnectGiveUpAction;
+}
+
+export interface RecentFile {
+  name: string;
+  path: string | null;
+  room: string;
+  played_at: number;
+}
+
+export interface ConnectionPreset {
+  name: string;
+  host: string;
+  port: number;
+  username: string;
+  room: string;
+  player_path?: string | null;
 }
 
 export interface SyncplayConfig {
@@ -111,4 +289,16 @@ export interface SyncplayConfig {
   player: PlayerConfig;
   recent_servers: ServerConfig[];
   public_servers: PublicServer[];
+  reconnect?: ReconnectPolicy;
+  recent_files?: RecentFile[];
+  presets?: ConnectionPreset[];
+  remote?: RemoteControlConfig;
+  scripts?: ScriptsConfig;
+  metrics?: MetricsConfig;
+}
+
+// A setting validate_config rejects, with the dotted path of the field
+export interface FieldError {
+  field: string;
+  message: string;
 }